@@ -31,6 +31,9 @@ fn main() {
         nvcc.flag("-O5");
         nvcc.flag("-arch=sm_75");
         nvcc.flag("-maxrregcount=255");
+        if env::var_os("PLONKY2_GPU_DEBUG").is_some() {
+            nvcc.define("PLONKY2_GPU_DEBUG", None);
+        }
         // nvcc.flag("-Xcompiler").flag("-Wno-unused-function");
         // nvcc.flag("-Xcompiler").flag("-Wno-subobject-linkage");
         nvcc.file("plonky2_gpu.cu").compile("plonky2_cuda");
@@ -38,6 +41,7 @@ fn main() {
         println!("cargo:rustc-cfg=feature=\"cuda\"");
         println!("cargo:rerun-if-changed=cuda");
         println!("cargo:rerun-if-env-changed=CXXFLAGS");
+        println!("cargo:rerun-if-env-changed=PLONKY2_GPU_DEBUG");
     } else {
         println!("nvcc must be in the path. Consider adding /usr/local/cuda/bin.");
         // panic!();
@@ -57,6 +57,115 @@ struct Gate {
 #include "U32RangeCheckGate.cuh"
 #include "U32SubtractionGate.cuh"
 
+// Must match `GpuGateKind` in plonky2/src/backend/gates.rs.
+enum GateKind {
+    GATE_NOOP = 0,
+    GATE_CONSTANT = 1,
+    GATE_PUBLIC_INPUT = 2,
+    GATE_BASE_SUM_2 = 3,
+    GATE_BASE_SUM_4 = 4,
+    GATE_ARITHMETIC = 5,
+    GATE_COMPARISON = 6,
+    GATE_U32_ADD_MANY = 7,
+    GATE_U32_ARITHMETIC = 8,
+    GATE_U32_RANGE_CHECK = 9,
+    GATE_U32_SUBTRACTION = 10,
+    GATE_RANDOM_ACCESS = 11,
+    GATE_POSEIDON = 12,
+};
+
+constexpr int MAX_GPU_GATES = 32;
+
+// A gate of the circuit: its kind, the fields of its struct, and where it sits in the selectors.
+struct GateDescriptor {
+    int kind;
+    int params[3];
+    int row;
+    int selector_index;
+    int group_start;
+    int group_end;
+    int num_constraints;
+};
+
+struct GateSet {
+    int num_gates;
+    int num_selectors;
+    GateDescriptor gates[MAX_GPU_GATES];
+};
+
+__device__ inline
+void eval_gate_unfiltered(const GateDescriptor& desc, EvaluationVarsBasePacked vars, StridedConstraintConsumer yield_constr) {
+    switch (desc.kind) {
+        case GATE_NOOP: {
+            NoopGate gate;
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_CONSTANT: {
+            ConstantGate gate{ .num_consts = (usize)desc.params[0] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_PUBLIC_INPUT: {
+            PublicInputGate gate;
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_BASE_SUM_2: {
+            BaseSumGate<2> gate{ .num_limbs = (usize)desc.params[0] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_BASE_SUM_4: {
+            BaseSumGate<4> gate{ .num_limbs = (usize)desc.params[0] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_ARITHMETIC: {
+            ArithmeticGate gate{ .num_ops = desc.params[0] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_COMPARISON: {
+            ComparisonGate gate{ .num_bits = (usize)desc.params[0], .num_chunks = (usize)desc.params[1] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_U32_ADD_MANY: {
+            U32AddManyGate gate{ .num_addends = (usize)desc.params[0], .num_ops = (usize)desc.params[1] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_U32_ARITHMETIC: {
+            U32ArithmeticGate gate{ .num_ops = (usize)desc.params[0] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_U32_RANGE_CHECK: {
+            U32RangeCheckGate gate{ .num_input_limbs = (usize)desc.params[0] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_U32_SUBTRACTION: {
+            U32SubtractionGate gate{ .num_ops = (usize)desc.params[0] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_RANDOM_ACCESS: {
+            RandomAccessGate gate{ .bits = (usize)desc.params[0], .num_copies = (usize)desc.params[1], .num_extra_constants = (usize)desc.params[2] };
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        case GATE_POSEIDON: {
+            PoseidonGate gate;
+            gate.eval_unfiltered_base_packed(vars, yield_constr);
+            break;
+        }
+        default:
+            assert(false);
+    }
+}
+
 
 
 #endif
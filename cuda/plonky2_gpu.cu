@@ -30,10 +30,18 @@ struct RustError { /* to be returned exclusively by value */
 #endif
 };
 
+// The timings and sizes of the kernels are printed only when built with `PLONKY2_GPU_DEBUG` set,
+// errors are returned to Rust.
+#ifdef PLONKY2_GPU_DEBUG
+#define DEBUG_PRINTF(...) printf(__VA_ARGS__)
+#else
+#define DEBUG_PRINTF(...) do {} while (0)
+#endif
+
 #define CUDA_ASSERT(expr) \
 do {\
     if (auto code = expr; code != cudaSuccess) {\
-        printf("%s@%d failed: %s\n", #expr, __LINE__, cudaGetErrorString(code));\
+        DEBUG_PRINTF("%s@%d failed: %s\n", #expr, __LINE__, cudaGetErrorString(code));\
         return RustError{code};\
     }\
 } while(0)
@@ -80,7 +88,7 @@ extern "C" {
         clock_t start = clock();
         ifft_kernel<<<poly_num, 32*8, 0, stream>>>(d_values_flatten, poly_num, values_num_per_poly, log_len, d_root_table, n_inv);
         cudaStreamSynchronize(stream);
-        printf("ifft_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("ifft_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
 
         return RustError{cudaSuccess};
     }
@@ -108,7 +116,7 @@ extern "C" {
         lde_kernel<<<(thcnt + nthreads - 1) / nthreads, nthreads, 0, stream>>>(d_values_flatten, d_ext_values_flatten,
                                                                                poly_num, values_num_per_poly, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("lde_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("lde_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
 
         start = clock();
         thcnt = values_num_per_poly * poly_num;
@@ -116,7 +124,7 @@ extern "C" {
         init_lde_kernel<<<(thcnt + nthreads - 1) / nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, poly_num,
                                                                                     values_num_per_poly, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("init_lde_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("init_lde_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
 
         start = clock();
         thcnt = values_num_per_poly * poly_num;
@@ -125,13 +133,13 @@ extern "C" {
                                                                                      values_num_per_poly, rate_bits,
                                                                                      d_shift_powers);
         cudaStreamSynchronize(stream);
-        printf("mul_shift_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("mul_shift_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
 
         start = clock();
         fft_kernel<<<poly_num, 32 * 8, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_poly * (1 << rate_bits),
                                                     log_len + rate_bits, d_root_table2, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("fft_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("fft_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
     }
 
 
@@ -163,7 +171,7 @@ extern "C" {
                                                                                               values_num_per_extpoly,
                                                                                               log_len + rate_bits);
         cudaStreamSynchronize(stream);
-        printf("reverse_index_bits_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("reverse_index_bits_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
 
         int log2_leaves_len = log_len + rate_bits;
         assert(cap_height <= log2_leaves_len);
@@ -176,7 +184,7 @@ extern "C" {
         hash_leaves_kernel<<<(thcnt + nthreads - 1) / nthreads, nthreads, 0, stream>>>(
                 d_ext_values_flatten, poly_num + salt_size, values_num_per_extpoly, d_digest_buf, len_cap, num_digests);
         cudaStreamSynchronize(stream);
-        printf("hash_leaves_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("hash_leaves_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
 
         start = clock();
         nthreads = 32 * 8;
@@ -185,7 +193,7 @@ extern "C" {
                                                                                           d_digest_buf, len_cap,
                                                                                           num_digests);
         cudaStreamSynchronize(stream);
-        printf("reduce_digests_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("reduce_digests_kernel elapsed: %.2lf\n", (double) (clock() - start) / CLOCKS_PER_SEC * 1000);
     }
 
 
@@ -210,7 +218,7 @@ extern "C" {
         nthreads = 32;
         transpose_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, d_ext_values_flatten - pad_extvalues_len, ext_poly_num, values_num_per_extpoly);
         cudaStreamSynchronize(stream);
-        printf("transpose_kernel elapsed: %.2lf\n",  (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("transpose_kernel elapsed: %.2lf\n",  (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
 
         return RustError{cudaSuccess};
     }
@@ -226,9 +234,9 @@ extern "C" {
            CudaInvContext* ctx
     ){
         assert(0);
-        printf("start merkle_tree_from_values: poly_num:%d, values_num_per_poly:%d, log_len:%d, n_inv:%lu\n",
+        DEBUG_PRINTF("start merkle_tree_from_values: poly_num:%d, values_num_per_poly:%d, log_len:%d, n_inv:%lu\n",
                poly_num, values_num_per_poly, log_len, p_inv->data);
-        printf("d_values_flatten: %p, d_ext_values_flatten: %p\n", d_values_flatten, d_ext_values_flatten);
+        DEBUG_PRINTF("d_values_flatten: %p, d_ext_values_flatten: %p\n", d_values_flatten, d_ext_values_flatten);
 
         int values_num_per_extpoly = values_num_per_poly*(1<<rate_bits);
         GoldilocksField n_inv = *p_inv;
@@ -269,7 +277,8 @@ extern "C" {
         ifft_kernel<<<poly_num, 32*8, 0, stream>>>(d_values_flatten, poly_num, values_num_per_poly, log_len, d_root_table, n_inv);
         cudaStreamSynchronize(stream);
 //        cudaStreamSynchronize(ctx->stream2);
-        printf("ifft_kernel elapsed: %.2lf\n", ifft_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        ifft_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("ifft_kernel elapsed: %.2lf\n", ifft_kernel_use);
 
 //        if (poly_num == 20) {
 //            std::vector<GoldilocksField> values_flatten(values_num_per_poly*poly_num);
@@ -292,21 +301,23 @@ extern "C" {
         nthreads = 32;
         lde_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_values_flatten, d_ext_values_flatten, poly_num, values_num_per_poly, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("lde_kernel elapsed: %.2lf\n", lde_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        lde_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("lde_kernel elapsed: %.2lf\n", lde_kernel_use);
 
         start = clock();
         thcnt = values_num_per_poly*poly_num;
         nthreads = 32;
         init_lde_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_poly, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("init_lde_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("init_lde_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
 
         start = clock();
         thcnt = values_num_per_poly*poly_num;
         nthreads = 32;
         mul_shift_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_poly, rate_bits, d_shift_powers);
         cudaStreamSynchronize(stream);
-        printf("mul_shift_kernel elapsed: %.2lf\n", mul_shift_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        mul_shift_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("mul_shift_kernel elapsed: %.2lf\n", mul_shift_kernel_use);
 
 //        if (poly_num == 20)
 //        {
@@ -327,7 +338,8 @@ extern "C" {
         start = clock();
         fft_kernel<<<poly_num, 32*8, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_poly*(1<<rate_bits), log_len+rate_bits, d_root_table2, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("fft_kernel elapsed: %.2lf\n", fft_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        fft_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("fft_kernel elapsed: %.2lf\n", fft_kernel_use);
 
 
 //        if (poly_num == 20)
@@ -353,7 +365,8 @@ extern "C" {
         nthreads = 32;
         reverse_index_bits_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_extpoly, log_len+rate_bits);
         cudaStreamSynchronize(stream);
-        printf("reverse_index_bits_kernel elapsed: %.2lf\n", reverse_index_bits_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        reverse_index_bits_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("reverse_index_bits_kernel elapsed: %.2lf\n", reverse_index_bits_kernel_use);
 
         int log2_leaves_len = log_len + rate_bits;
         assert(cap_height <= log2_leaves_len);
@@ -366,14 +379,16 @@ extern "C" {
         hash_leaves_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(
                 d_ext_values_flatten, poly_num+salt_size, values_num_per_extpoly, d_digest_buf, len_cap, num_digests);
         cudaStreamSynchronize(stream);
-        printf("hash_leaves_kernel elapsed: %.2lf\n", hash_leaves_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        hash_leaves_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("hash_leaves_kernel elapsed: %.2lf\n", hash_leaves_kernel_use);
 
         start = clock();
         nthreads = 32*8;
         thcnt = len_cap * nthreads;
         reduce_digests_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(values_num_per_extpoly, d_digest_buf, len_cap, num_digests);
         cudaStreamSynchronize(stream);
-        printf("reduce_digests_kernel elapsed: %.2lf\n", reduce_digests_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        reduce_digests_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("reduce_digests_kernel elapsed: %.2lf\n", reduce_digests_kernel_use);
 
 //    printf("error: %s\n", cudaGetErrorString(cudaGetLastError()));
 
@@ -397,7 +412,8 @@ extern "C" {
         nthreads = 32;
         transpose_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, d_ext_values_flatten - pad_extvalues_len, ext_poly_num, values_num_per_extpoly);
         cudaStreamSynchronize(stream);
-        printf("transpose_kernel elapsed: %.2lf\n", transpose_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        transpose_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("transpose_kernel elapsed: %.2lf\n", transpose_kernel_use);
 
         double total_use =
                 ifft_kernel_use+
@@ -428,7 +444,7 @@ extern "C" {
 //
 //        }
 //
-        printf("total use:%.2lf\n", total_use);
+        DEBUG_PRINTF("total use:%.2lf\n", total_use);
         return RustError{cudaSuccess};
     }
 
@@ -442,9 +458,9 @@ extern "C" {
             int pad_extvalues_len,
             CudaInvContext* ctx
     ){
-        printf("start merkle_tree_from_coeffs: poly_num:%d, values_num_per_poly:%d, log_len:%d\n",
+        DEBUG_PRINTF("start merkle_tree_from_coeffs: poly_num:%d, values_num_per_poly:%d, log_len:%d\n",
                poly_num, values_num_per_poly, log_len);
-        printf("d_values_flatten: %p, d_ext_values_flatten: %p\n", d_values_flatten, d_ext_values_flatten);
+        DEBUG_PRINTF("d_values_flatten: %p, d_ext_values_flatten: %p\n", d_values_flatten, d_ext_values_flatten);
 
         int values_num_per_extpoly = values_num_per_poly*(1<<rate_bits);
         auto stream = ctx->stream;
@@ -483,21 +499,23 @@ extern "C" {
         nthreads = 32;
         lde_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_values_flatten, d_ext_values_flatten, poly_num, values_num_per_poly, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("lde_kernel elapsed: %.2lf\n", lde_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        lde_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("lde_kernel elapsed: %.2lf\n", lde_kernel_use);
 
         start = clock();
         thcnt = values_num_per_poly*poly_num;
         nthreads = 32;
         init_lde_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_poly, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("init_lde_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("init_lde_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
 
         start = clock();
         thcnt = values_num_per_poly*poly_num;
         nthreads = 32;
         mul_shift_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_poly, rate_bits, d_shift_powers);
         cudaStreamSynchronize(stream);
-        printf("mul_shift_kernel elapsed: %.2lf\n", mul_shift_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        mul_shift_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("mul_shift_kernel elapsed: %.2lf\n", mul_shift_kernel_use);
 
     //        if (poly_num == 20)
     //        {
@@ -518,7 +536,8 @@ extern "C" {
         start = clock();
         fft_kernel<<<poly_num, 32*8, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_poly*(1<<rate_bits), log_len+rate_bits, d_root_table2, rate_bits);
         cudaStreamSynchronize(stream);
-        printf("fft_kernel elapsed: %.2lf\n", fft_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        fft_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("fft_kernel elapsed: %.2lf\n", fft_kernel_use);
 
 
     //        if (poly_num == 20)
@@ -544,7 +563,8 @@ extern "C" {
         nthreads = 32;
         reverse_index_bits_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, poly_num, values_num_per_extpoly, log_len+rate_bits);
         cudaStreamSynchronize(stream);
-        printf("reverse_index_bits_kernel elapsed: %.2lf\n", reverse_index_bits_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        reverse_index_bits_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("reverse_index_bits_kernel elapsed: %.2lf\n", reverse_index_bits_kernel_use);
 
         int log2_leaves_len = log_len + rate_bits;
         assert(cap_height <= log2_leaves_len);
@@ -557,14 +577,16 @@ extern "C" {
         hash_leaves_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(
                 d_ext_values_flatten, poly_num+salt_size, values_num_per_extpoly, d_digest_buf, len_cap, num_digests);
         cudaStreamSynchronize(stream);
-        printf("hash_leaves_kernel elapsed: %.2lf\n", hash_leaves_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        hash_leaves_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("hash_leaves_kernel elapsed: %.2lf\n", hash_leaves_kernel_use);
 
         start = clock();
         nthreads = 32*8;
         thcnt = len_cap * nthreads;
         reduce_digests_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(values_num_per_extpoly, d_digest_buf, len_cap, num_digests);
         cudaStreamSynchronize(stream);
-        printf("reduce_digests_kernel elapsed: %.2lf\n", reduce_digests_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        reduce_digests_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("reduce_digests_kernel elapsed: %.2lf\n", reduce_digests_kernel_use);
 
     //    printf("error: %s\n", cudaGetErrorString(cudaGetLastError()));
 
@@ -590,7 +612,8 @@ extern "C" {
         nthreads = 32;
         transpose_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_ext_values_flatten, d_ext_values_flatten - pad_extvalues_len, ext_poly_num, values_num_per_extpoly);
         cudaStreamSynchronize(stream);
-        printf("transpose_kernel elapsed: %.2lf\n", transpose_kernel_use=(double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        transpose_kernel_use = (double )(clock()-start) / CLOCKS_PER_SEC * 1000;
+        DEBUG_PRINTF("transpose_kernel elapsed: %.2lf\n", transpose_kernel_use);
 
         double total_use =
                 lde_kernel_use+
@@ -601,7 +624,7 @@ extern "C" {
                 reduce_digests_kernel_use+
                 transpose_kernel_use;
 
-        printf("total use:%.2lf\n", total_use);
+        DEBUG_PRINTF("total use:%.2lf\n", total_use);
         return RustError{cudaSuccess};
     }
 
@@ -626,6 +649,12 @@ extern "C" {
             DataSlice<GoldilocksField>* betas,
             DataSlice<GoldilocksField>* gammas,
 
+            const QuotientShape* shape,
+            const GateSet* gate_set,
+            const GoldilocksField* d_fallback_outs,
+            const GoldilocksField* public_inputs_hash,
+            const GoldilocksField* n_inv_ext,
+
             CudaInvContext* ctx
     ) {
 
@@ -662,46 +691,39 @@ extern "C" {
 //        size_t total_dev_use = start_p-(uint8_t*)d_ext_values_flatten;
 //        printf("total_dev_use: %fG\n", (double )total_dev_use/1024/1024/1024);
 
-        int num_challenges = 2;
-        int num_gate_constraints = 231;
-        int num_constants = 8;
-        int num_routed_wires = 80;
-        int quotient_degree_factor = 8;
-        int num_partial_products = 9;
-        int constants_sigmas_commitment_leaf_len = 88;
-        int zs_partial_products_commitment_leaf_len = 20;
-        int wires_commitment_leaf_len = 234;
-
-//    printf("%d, %d\n", constants_sigmas_commitment_leaves->len, values_num_per_extpoly*constants_sigmas_commitment_leaf_len);
-        assert(constants_sigmas_commitment_leaves->len    == values_num_per_extpoly*constants_sigmas_commitment_leaf_len);
-        assert(zs_partial_products_commitment_leaves->len == values_num_per_extpoly*zs_partial_products_commitment_leaf_len);
-        assert(points->len == values_num_per_extpoly);
-        assert(alphas->len == num_challenges);
-        assert(betas->len == num_challenges);
-        assert(gammas->len == num_challenges);
+        int num_challenges = shape->num_challenges;
+
+        if (shape->num_challenges > MAX_NUM_CHALLENGES ||
+            shape->num_gate_constraints > MAX_NUM_GATE_CONSTRAINTS ||
+            shape->num_routed_wires / shape->quotient_degree_factor > MAX_PARTIAL_PRODUCT_ROUNDS ||
+            shape->quotient_degree_factor != (1<<rate_bits) ||
+            gate_set->num_gates > MAX_GPU_GATES ||
+            constants_sigmas_commitment_leaves->len    != values_num_per_extpoly*shape->constants_sigmas_commitment_leaf_len ||
+            zs_partial_products_commitment_leaves->len != values_num_per_extpoly*shape->zs_partial_products_commitment_leaf_len ||
+            points->len != values_num_per_extpoly ||
+            alphas->len != num_challenges ||
+            betas->len != num_challenges ||
+            gammas->len != num_challenges) {
+            return RustError{cudaErrorInvalidValue, "unsupported quotient kernel shape"};
+        }
 
         start = clock();
         thcnt = 300000;
         nthreads = 32;
-        PoseidonHasher::HashOut public_inputs_hash = {
-                GoldilocksField{0x672c5e6c12ad3476}, GoldilocksField{0xca5c2e49acfad27e},
-                GoldilocksField{0x296be18388d15f70}, GoldilocksField{0x66b42e146a70d96d}
+        PoseidonHasher::HashOut h_public_inputs_hash = {
+                public_inputs_hash[0], public_inputs_hash[1], public_inputs_hash[2], public_inputs_hash[3]
         };
         compute_quotient_values_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(
                 log_len, rate_bits,
                 points->ptr,
                 d_outs,
-                public_inputs_hash,
+                h_public_inputs_hash,
+                *shape,
+                *gate_set,
 
-                constants_sigmas_commitment_leaves->ptr,     constants_sigmas_commitment_leaf_len,
-                zs_partial_products_commitment_leaves->ptr,  zs_partial_products_commitment_leaf_len,
-                d_ext_values_flatten,                wires_commitment_leaf_len,
-                num_constants, num_routed_wires,
-                num_challenges,
-                num_gate_constraints,
-
-                quotient_degree_factor,
-                num_partial_products,
+                constants_sigmas_commitment_leaves->ptr,
+                zs_partial_products_commitment_leaves->ptr,
+                d_ext_values_flatten,
 
                 z_h_on_coset_evals->ptr,
                 z_h_on_coset_inverses->ptr,
@@ -709,14 +731,14 @@ extern "C" {
                 k_is->ptr,
                 alphas->ptr,
                 betas->ptr,
-                gammas->ptr
+                gammas->ptr,
+
+                d_fallback_outs
         );
-        if (auto code = cudaGetLastError(); code != cudaSuccess) {
-            printf("compute quotient error: %s\n", cudaGetErrorString(code));
-        }
+        CUDA_ASSERT(cudaGetLastError());
 
         cudaStreamSynchronize(stream);
-        printf("compute_quotient_values_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("compute_quotient_values_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
 
 //        {
 //            std::vector<GoldilocksField> outs(num_challenges*values_num_per_extpoly);
@@ -740,13 +762,12 @@ extern "C" {
         nthreads = 32;
         transpose_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_outs, d_quotient_polys, values_num_per_extpoly, num_challenges);
         cudaStreamSynchronize(stream);
-        printf("transpose_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("transpose_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
 
         start = clock();
-        GoldilocksField n_inv_ext = {.data = 0xfffff7ff00000801ULL};
-        ifft_kernel<<<num_challenges, 32*8, 0, stream>>>(d_quotient_polys, num_challenges, values_num_per_extpoly, log_len+rate_bits, d_root_table2, n_inv_ext);
+        ifft_kernel<<<num_challenges, 32*8, 0, stream>>>(d_quotient_polys, num_challenges, values_num_per_extpoly, log_len+rate_bits, d_root_table2, *n_inv_ext);
         cudaStreamSynchronize(stream);
-        printf("ifft_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("ifft_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
 
 //        {
 //            std::vector<GoldilocksField> outs(num_challenges*values_num_per_extpoly);
@@ -761,7 +782,7 @@ extern "C" {
         nthreads = 32;
         mul_kernel<<<(thcnt+nthreads-1)/nthreads, nthreads, 0, stream>>>(d_quotient_polys, num_challenges, values_num_per_extpoly, d_shift_inv_powers);
         cudaStreamSynchronize(stream);
-        printf("mul_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
+        DEBUG_PRINTF("mul_kernel elapsed: %.2lf\n", (double )(clock()-start) / CLOCKS_PER_SEC * 1000);
 
 //        {
 //            std::vector<GoldilocksField> outs(num_challenges*values_num_per_extpoly);
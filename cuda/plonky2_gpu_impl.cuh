@@ -481,22 +481,34 @@ void transpose_kernel(GoldilocksField* src_values_flatten, GoldilocksField* dst_
 
 #include "gates-def.cuh"
 
+// Limits of the per-thread arrays of compute_quotient_values_kernel, checked on the Rust side by
+// `QuotientKernelShape::new`.
+constexpr int MAX_NUM_CHALLENGES = 4;
+constexpr int MAX_NUM_GATE_CONSTRAINTS = 256;
+constexpr int MAX_PARTIAL_PRODUCT_ROUNDS = 32;
+
+struct QuotientShape {
+    int num_challenges;
+    int num_gate_constraints;
+    int num_constants;
+    int num_routed_wires;
+    int quotient_degree_factor;
+    int num_partial_products;
+    int constants_sigmas_commitment_leaf_len;
+    int zs_partial_products_commitment_leaf_len;
+    int wires_commitment_leaf_len;
+};
 
 __global__
 void compute_quotient_values_kernel(
         int degree_log, int rate_bits, GoldilocksField* points, GoldilocksField* outs,
         PoseidonHasher::HashOut public_inputs_hash,
+        QuotientShape shape,
+        GateSet gate_set,
 
-
-        GoldilocksField* constants_sigmas_commitment_leaves,     int constants_sigmas_commitment_leaf_len,
-        GoldilocksField* zs_partial_products_commitment_leaves,  int zs_partial_products_commitment_leaf_len,
-        GoldilocksField* wires_commitment_leaves,                int wires_commitment_leaf_len,
-        int num_constants, int _num_routed_wires,
-        int _num_challenges,
-        int _num_gate_constraints,
-
-        int _quotient_degree_factor,
-        int num_partial_products,
+        GoldilocksField* constants_sigmas_commitment_leaves,
+        GoldilocksField* zs_partial_products_commitment_leaves,
+        GoldilocksField* wires_commitment_leaves,
 
         GoldilocksField* z_h_on_coset_evals,
         GoldilocksField* z_h_on_coset_inverses,
@@ -504,14 +516,18 @@ void compute_quotient_values_kernel(
         GoldilocksField* k_is,
         GoldilocksField* alphas,
         GoldilocksField* betas,
-        GoldilocksField* gammas
+        GoldilocksField* gammas,
 
+        // The contribution of the gates evaluated on the CPU, if any.
+        const GoldilocksField* fallback_outs
 )
 {
-    constexpr int num_challenges = 2;
-    constexpr int num_gate_constraints = 231;
-    assert(num_gate_constraints == _num_gate_constraints);
-    assert(num_challenges == _num_challenges);
+    const int num_challenges = shape.num_challenges;
+    const int num_gate_constraints = shape.num_gate_constraints;
+    const int num_constants = shape.num_constants;
+    const int constants_sigmas_commitment_leaf_len = shape.constants_sigmas_commitment_leaf_len;
+    const int zs_partial_products_commitment_leaf_len = shape.zs_partial_products_commitment_leaf_len;
+    const int wires_commitment_leaf_len = shape.wires_commitment_leaf_len;
 
     int thCnt = get_global_thcnt();
     int gid = get_global_id();
@@ -522,16 +538,17 @@ void compute_quotient_values_kernel(
 ////        assert(res == GoldilocksField::from_canonical_u64(1));
 //    }
 
+    // The quotient degree factor is the blowup factor, so the vanishing polynomial is evaluated on
+    // every point of the LDE.
     int step = 1;
-    int next_step = 8;
+    int next_step = shape.quotient_degree_factor;
     int values_num_per_extpoly = (1<<(rate_bits+degree_log));
 //    int values_num_per_extpoly = 1;
     int lde_size  = values_num_per_extpoly;
 
-    constexpr int quotient_degree_factor = 8;
-    constexpr int num_routed_wires = 80;
-    constexpr int max_degree = quotient_degree_factor;
-    int num_prods = num_partial_products;
+    const int num_routed_wires = shape.num_routed_wires;
+    const int max_degree = shape.quotient_degree_factor;
+    int num_prods = shape.num_partial_products;
 
 //    if (gid == 0) {
 //        GoldilocksFieldView{alphas, num_challenges}.print_hex("alphas");
@@ -581,7 +598,7 @@ void compute_quotient_values_kernel(
 
 //        let constraint_terms = PackedStridedView::new(&constraint_terms_batch, n, k);
 
-        GoldilocksField res[num_challenges] = {0};
+        GoldilocksField res[MAX_NUM_CHALLENGES] = {0};
 
         auto reduce_with_powers = [&res, &alphas, num_challenges](GoldilocksField term) {
             for (int i = 0; i < num_challenges; ++i) {
@@ -589,173 +606,54 @@ void compute_quotient_values_kernel(
             }
         };
 
-        GoldilocksField constraint_terms_batch[num_gate_constraints] = {0};
-        auto evaluate_gate_constraints_base_batch = [&]()
-        {
-            struct SelectorsInfo {
-                int *selector_indices;
-                Range<int>* groups;
-            };
+        GoldilocksField constraint_terms_batch[MAX_NUM_GATE_CONSTRAINTS] = {0};
+        GoldilocksField terms[MAX_NUM_GATE_CONSTRAINTS];
+        for (int g = 0; g < gate_set.num_gates; ++g) {
+            const GateDescriptor& gate = gate_set.gates[g];
+
+            auto compute_filter = [](int row, Range<int> group_range, GoldilocksField s,
+                                     bool many_selector) -> GoldilocksField {
+                assert(group_range.contains(row));
+                GoldilocksField res = {1};
+                for (int i = group_range.first; i < group_range.second; ++i) {
+                    if (i == row)
+                        continue;
+                    res *= GoldilocksField::from_canonical_u64(i) - s;
+                }
 
-            int selector_indices[25] = {
-                    0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 4, 4, 4, 5
-            };
+                const uint32_t UNUSED_SELECTOR = UINT32_MAX;
 
-            constexpr  int num_selectors = 6;
-            Range<int> groups[num_selectors] = {
-                    Range<int>{0,6},
-                    Range<int>{6,11},
-                    Range<int>{11,16},
-                    Range<int>{16,21},
-                    Range<int>{21,24},
-                    Range<int>{24,25}
-            };
-            SelectorsInfo selectors_info = {
-                    .selector_indices = selector_indices,
-                    .groups = groups
+                if (many_selector) {
+                    res *= GoldilocksField::from_canonical_u64(UNUSED_SELECTOR) - s;
+                }
+                return res;
             };
 
-            struct BaseGate {};
-            using FUNC = void (BaseGate::*)(EvaluationVarsBasePacked, StridedConstraintConsumer yield_constr);
-
-            struct GateFUNC {
-                BaseGate* gate;
-                FUNC func;
-                int num_constraints;
+            auto filter = compute_filter(
+                    gate.row,
+                    Range<int>{gate.group_start, gate.group_end},
+                    local_constants[gate.selector_index],
+                    gate_set.num_selectors > 1
+            );
+
+            EvaluationVarsBasePacked vars = {
+                    .local_constants = local_constants.view(gate_set.num_selectors, local_constants.len),
+                    .local_wires = local_wires,
+                    .public_inputs_hash = public_inputs_hash,
+                    .index = index
             };
-            constexpr const int num_gates = 25;
-            GateFUNC gate_objs[num_gates];
-
-#define DECL_GATE_NAME(TYPE, NAME, INDEX) \
-        gate_objs[INDEX] = GateFUNC{.gate = (BaseGate*)&NAME, .func = (FUNC)&TYPE::eval_unfiltered_base_packed, .num_constraints = NAME.num_constraints()};
-
-            NoopGate NoopGate_ins;
-            DECL_GATE_NAME(NoopGate,NoopGate_ins, 0);
-            ConstantGate ConstantGate_ins{ .num_consts = 2 };
-            DECL_GATE_NAME(ConstantGate, ConstantGate_ins, 1);
-
-            PublicInputGate PublicInputGate_ins;
-            DECL_GATE_NAME(PublicInputGate,PublicInputGate_ins, 2);
-
-            BaseSumGate<2> BaseSumGate_ins{ .num_limbs = 32 };
-            DECL_GATE_NAME(BaseSumGate<2>,BaseSumGate_ins, 3);
-            BaseSumGate<2> BaseSumGate_ins2{ .num_limbs = 63 };
-            DECL_GATE_NAME(BaseSumGate<2>,BaseSumGate_ins2, 4);
-            ArithmeticGate ArithmeticGate_ins{ .num_ops = 20 };
-            DECL_GATE_NAME(ArithmeticGate,ArithmeticGate_ins, 5);
-            BaseSumGate<4> BaseSumGate_ins3{ .num_limbs = 16 };
-            DECL_GATE_NAME(BaseSumGate<4>,BaseSumGate_ins3, 6);
-
-            ComparisonGate ComparisonGate_ins{ .num_bits = 32, .num_chunks = 16};
-            DECL_GATE_NAME(ComparisonGate,ComparisonGate_ins, 7);
-
-            U32AddManyGate U32AddManyGate_ins{ .num_addends = 0, .num_ops = 11};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins, 8);
-            U32AddManyGate U32AddManyGate_ins2{ .num_addends = 11, .num_ops = 5};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins2, 9);
-            U32AddManyGate U32AddManyGate_ins3{ .num_addends = 13, .num_ops = 5};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins3, 10);
-            U32AddManyGate U32AddManyGate_ins4{ .num_addends = 15, .num_ops = 4};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins4, 11);
-            U32AddManyGate U32AddManyGate_ins5{ .num_addends = 16, .num_ops = 4};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins5, 12);
-            U32AddManyGate U32AddManyGate_ins6{ .num_addends = 2, .num_ops = 10};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins6, 13);
-            U32AddManyGate U32AddManyGate_ins7{ .num_addends = 3, .num_ops = 9};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins7, 14);
-            U32AddManyGate U32AddManyGate_ins8{ .num_addends = 5, .num_ops = 9};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins8, 15);
-            U32AddManyGate U32AddManyGate_ins9{ .num_addends = 7, .num_ops = 8};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins9, 16);
-            U32AddManyGate U32AddManyGate_ins10{ .num_addends = 9, .num_ops = 6};
-            DECL_GATE_NAME(U32AddManyGate,U32AddManyGate_ins10, 17);
-            U32ArithmeticGate U32ArithmeticGate_ins{ .num_ops = 6};
-            DECL_GATE_NAME(U32ArithmeticGate,U32ArithmeticGate_ins, 18);
-            U32RangeCheckGate U32RangeCheckGate_ins2{ .num_input_limbs = 0};
-            DECL_GATE_NAME(U32RangeCheckGate,U32RangeCheckGate_ins2, 19);
-            U32RangeCheckGate U32RangeCheckGate_ins3{ .num_input_limbs = 1};
-            DECL_GATE_NAME(U32RangeCheckGate,U32RangeCheckGate_ins3, 20);
-            U32RangeCheckGate U32RangeCheckGate_ins4{ .num_input_limbs = 8};
-            DECL_GATE_NAME(U32RangeCheckGate,U32RangeCheckGate_ins4, 21);
-            U32SubtractionGate U32SubtractionGate_ins{ .num_ops = 11};
-            DECL_GATE_NAME(U32SubtractionGate,U32SubtractionGate_ins, 22);
-            RandomAccessGate RandomAccessGate_ins{ .bits = 4, .num_copies = 4, .num_extra_constants = 2};
-            DECL_GATE_NAME(RandomAccessGate,RandomAccessGate_ins, 23);
-            PoseidonGate PoseidonGate_ins;
-            DECL_GATE_NAME(PoseidonGate,PoseidonGate_ins, 24);
-
-//            if (index == 1048576) {
-//                printf("i: %d, local_constants: ", index);
-//                local_constants.print_hex();
-//                printf("i: %d, local_wires: ", index);
-//                local_wires.print_hex();
-//            }
-
-            GoldilocksField terms[num_gate_constraints];
-            auto evaluate_gate_constraints_base_batch = [index, public_inputs_hash, &constraint_terms_batch, &terms, gate_objs, selectors_info, local_constants, local_wires]() {
-                for (int row = 0; row < num_gates; ++row) {
-                    int selector_index = selectors_info.selector_indices[row];
-                    auto gate = gate_objs[row];
-
-                    auto compute_filter = [](int row, Range<int> group_range, GoldilocksField s,
-                                             bool many_selector) -> GoldilocksField {
-                        assert(group_range.contains(row));
-                        GoldilocksField res = {1};
-                        for (int i = group_range.first; i < group_range.second; ++i) {
-                            if (i == row)
-                                continue;
-                            res *= GoldilocksField::from_canonical_u64(i) - s;
-                        }
-
-                        const uint32_t UNUSED_SELECTOR = UINT32_MAX;
-
-                        if (many_selector) {
-                            res *= GoldilocksField::from_canonical_u64(UNUSED_SELECTOR) - s;
-                        }
-                        return res;
-                    };
-
-                    auto filter = compute_filter(
-                            row,
-                            selectors_info.groups[selector_index],
-                            local_constants[selector_index],
-                            num_selectors > 1
-                    );
-
-                    EvaluationVarsBasePacked vars = {
-                            .local_constants = local_constants.view(num_selectors, local_constants.len),
-                            .local_wires = local_wires,
-                            .public_inputs_hash = public_inputs_hash,
-                            .index = index
-                    };
-
-//                    if (index == 1048576) {
-//                        printf("i: %d, row: %d, filter: ", index, row);
-//                        filter.print_hex(nullptr, GoldilocksField::newline);
-//                    }
 
-                    for (int i = 0; i < gate.num_constraints; ++i) {
-                        terms[i] = GoldilocksField{0};
-                    }
-
-                    auto fn = gate.func;
-                    auto yield_constr =  StridedConstraintConsumer{terms, &terms[gate.num_constraints]};
-                    ((gate.gate)->*fn)(vars, yield_constr);
-//                    if (index == 1048576) {
-//                        printf("i: %d, row: %d, terms: ", index, row);
-//                        GoldilocksFieldView{terms, gate.num_constraints}.print_hex();
-//                    }
-
-                    for (int i = 0; i < gate.num_constraints; ++i) {
-                        constraint_terms_batch[i] += terms[i] * filter;
-                    }
+            for (int i = 0; i < gate.num_constraints; ++i) {
+                terms[i] = GoldilocksField{0};
+            }
 
-                }
-            };
+            auto yield_constr =  StridedConstraintConsumer{terms, &terms[gate.num_constraints]};
+            eval_gate_unfiltered(gate, vars, yield_constr);
 
-            evaluate_gate_constraints_base_batch();
-        };
-        evaluate_gate_constraints_base_batch();
+            for (int i = 0; i < gate.num_constraints; ++i) {
+                constraint_terms_batch[i] += terms[i] * filter;
+            }
+        }
 //        if (index == 1048576) {
 //            printf("i: %d, constraint_terms: ", index);
 //            GoldilocksFieldView{constraint_terms_batch, num_gate_constraints}.print_hex();
@@ -764,8 +662,8 @@ void compute_quotient_values_kernel(
             reduce_with_powers(constraint_terms_batch[i]);
         }
 
-        constexpr int vanishing_partial_products_terms_len = num_challenges * num_routed_wires/max_degree;
-        GoldilocksField vanishing_partial_products_terms[vanishing_partial_products_terms_len];
+        const int vanishing_partial_products_terms_len = num_challenges * num_routed_wires/max_degree;
+        GoldilocksField vanishing_partial_products_terms[MAX_NUM_CHALLENGES * MAX_PARTIAL_PRODUCT_ROUNDS];
         for (int i = 0; i < num_challenges; ++i) {
             auto z_x = local_zs[i];
             auto z_gx = next_zs[i];
@@ -783,7 +681,7 @@ void compute_quotient_values_kernel(
 //            );
 
             GoldilocksField prev_acc, next_acc;
-            constexpr int partial_product_rounds = num_routed_wires/max_degree;
+            const int partial_product_rounds = num_routed_wires/max_degree;
             assert(current_partial_products.len == partial_product_rounds-1);
             for (int k = 0; k < partial_product_rounds; ++k) {
                 GoldilocksField num_chunk_product = GoldilocksField::from_canonical_u64(1);
@@ -871,8 +769,11 @@ void compute_quotient_values_kernel(
 //            GoldilocksFieldView{res, num_challenges}.print_hex();
 //        }
 
-        outs[index*2]   = res[0];
-        outs[index*2+1] = res[1];
+        for (int i = 0; i < num_challenges; ++i) {
+            outs[index*num_challenges + i] = fallback_outs == nullptr
+                    ? res[i]
+                    : res[i] + fallback_outs[index*num_challenges + i];
+        }
     }
 
 }
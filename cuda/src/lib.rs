@@ -34,6 +34,17 @@ mod cuda {
         }
     }
 
+    impl Error {
+        /// `Ok` if the call returning `self` succeeded, otherwise the message of the error.
+        pub fn into_result(self) -> Result<(), String> {
+            if self.code == 0 {
+                Ok(())
+            } else {
+                Err(self.into())
+            }
+        }
+    }
+
     impl From<Error> for String {
         fn from(status: Error) -> Self {
             let c_str = if let Some(ptr) = status.str {
@@ -49,12 +60,50 @@ mod cuda {
     }
 }
 
+pub use cuda::Error;
+
 #[repr(C)]
 pub struct DataSlice {
     pub ptr: *const c_void,
     pub len: i32,
 }
 
+/// The circuit shape read by `compute_quotient_values_kernel`.
+#[repr(C)]
+pub struct QuotientShape {
+    pub num_challenges: i32,
+    pub num_gate_constraints: i32,
+    pub num_constants: i32,
+    pub num_routed_wires: i32,
+    pub quotient_degree_factor: i32,
+    pub num_partial_products: i32,
+    pub constants_sigmas_commitment_leaf_len: i32,
+    pub zs_partial_products_commitment_leaf_len: i32,
+    pub wires_commitment_leaf_len: i32,
+}
+
+pub const MAX_GPU_GATES: usize = 32;
+
+/// A gate evaluated by `compute_quotient_values_kernel`, see `GateDescriptor` in `gates-def.cuh`.
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct GateDescriptor {
+    pub kind: i32,
+    pub params: [i32; 3],
+    pub row: i32,
+    pub selector_index: i32,
+    pub group_start: i32,
+    pub group_end: i32,
+    pub num_constraints: i32,
+}
+
+#[repr(C)]
+pub struct GateSet {
+    pub num_gates: i32,
+    pub num_selectors: i32,
+    pub gates: [GateDescriptor; MAX_GPU_GATES],
+}
+
 extern "C" {
     pub fn init();
 
@@ -139,6 +188,12 @@ extern "C" {
         betas: *const DataSlice,
         gammas: *const DataSlice,
 
+        shape: *const QuotientShape,
+        gate_set: *const GateSet,
+        fallback_outs: *const c_void,
+        public_inputs_hash: *const u64,
+        n_inv_ext: *const u64,
+
         ctx: *mut c_void,
     ) -> cuda::Error;
 
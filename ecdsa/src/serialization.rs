@@ -0,0 +1,101 @@
+//! Registration of this crate's generators, for serializing circuits which use them.
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::secp256k1_base::Secp256K1Base;
+use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
+use plonky2::field::types::PrimeField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::util::serialization::generator_serialization::WitnessGeneratorSerializer;
+
+use crate::gadgets::biguint::BigUintDivRemGenerator;
+use crate::gadgets::glv::GLVDecompositionGenerator;
+use crate::gadgets::nonnative::{
+    nonnative_generator_id, NonNativeAdditionGenerator, NonNativeInverseGenerator,
+    NonNativeMultipleAddsGenerator, NonNativeMultiplicationGenerator,
+    NonNativeSubtractionGenerator,
+};
+
+/// Registers the generators of this crate, along with those of `plonky2_u32` which its gadgets
+/// use. The gates come from `plonky2_u32::serialization::register_gates`.
+pub fn register_generators<F: RichField + Extendable<D>, const D: usize>(
+    serializer: &mut WitnessGeneratorSerializer<F, D>,
+) {
+    plonky2_u32::serialization::register_generators(serializer);
+    serializer.register_simple::<BigUintDivRemGenerator<F, D>>("BigUintDivRemGenerator");
+    serializer.register_simple::<GLVDecompositionGenerator<F, D>>("GLVDecompositionGenerator");
+    register_nonnative_generators::<F, D, Secp256K1Base>(serializer);
+    register_nonnative_generators::<F, D, Secp256K1Scalar>(serializer);
+}
+
+fn register_nonnative_generators<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>(
+    serializer: &mut WitnessGeneratorSerializer<F, D>,
+) {
+    let id = nonnative_generator_id::<FF>;
+    serializer
+        .register_simple::<NonNativeAdditionGenerator<F, D, FF>>(&id("NonNativeAdditionGenerator"));
+    serializer.register_simple::<NonNativeMultipleAddsGenerator<F, D, FF>>(&id(
+        "NonNativeMultipleAddsGenerator",
+    ));
+    serializer.register_simple::<NonNativeSubtractionGenerator<F, D, FF>>(&id(
+        "NonNativeSubtractionGenerator",
+    ));
+    serializer.register_simple::<NonNativeMultiplicationGenerator<F, D, FF>>(&id(
+        "NonNativeMultiplicationGenerator",
+    ));
+    serializer
+        .register_simple::<NonNativeInverseGenerator<F, D, FF>>(&id("NonNativeInverseGenerator"));
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::BigUint;
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::serialization::gate_serialization::GateSerializer;
+
+    use super::*;
+    use crate::gadgets::biguint::CircuitBuilderBiguint;
+    use crate::gadgets::glv::CircuitBuilderGlv;
+    use crate::gadgets::nonnative::CircuitBuilderNonNative;
+
+    #[test]
+    fn test_generator_serialization() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.constant_nonnative(Secp256K1Base::rand());
+        let y = builder.constant_nonnative(Secp256K1Base::rand());
+        let sum = builder.add_nonnative(&x, &y);
+        let sum = builder.add_many_nonnative(&[sum, x.clone(), y.clone()]);
+        let diff = builder.sub_nonnative(&sum, &y);
+        let product = builder.mul_nonnative(&diff, &x);
+        builder.inv_nonnative(&product);
+        let k = builder.constant_nonnative(Secp256K1Scalar::rand());
+        builder.decompose_secp256k1_scalar(&k);
+        let a = builder.constant_biguint(&BigUint::from(u64::MAX));
+        let b = builder.constant_biguint(&BigUint::from(12345u32));
+        builder.div_rem_biguint(&a, &b);
+        let data = builder.build::<C>();
+
+        let mut gate_serializer = GateSerializer::plonky2();
+        plonky2_u32::serialization::register_gates(&mut gate_serializer);
+        let mut generator_serializer = WitnessGeneratorSerializer::plonky2();
+        register_generators(&mut generator_serializer);
+        let bytes = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(bytes, &gate_serializer, &generator_serializer)
+                .map_err(anyhow::Error::msg)?;
+
+        let proof = data_from_bytes.prove(PartialWitness::new())?;
+        data.verify(proof)
+    }
+}
@@ -13,7 +13,7 @@ use plonky2_util::log2_strict;
 use serde::{Deserialize, Serialize};
 
 use crate::extension::{Extendable, FieldExtension};
-use crate::fft::{fft, fft_with_options, ifft, FftRootTable};
+use crate::fft::{fft, fft_with_options, ifft, ifft_with_options, FftRootTable};
 use crate::types::Field;
 
 /// A polynomial in point-value form.
@@ -60,10 +60,23 @@ impl<F: Field> PolynomialValues<F> {
         ifft(self)
     }
 
+    pub fn ifft_with_options(self, root_table: Option<&FftRootTable<F>>) -> PolynomialCoeffs<F> {
+        ifft_with_options(self, None, root_table)
+    }
+
     /// Returns the polynomial whose evaluation on the coset `shift*H` is `self`.
     pub fn coset_ifft(self, shift: F) -> PolynomialCoeffs<F> {
+        self.coset_ifft_with_options(shift, None)
+    }
+
+    /// Returns the polynomial whose evaluation on the coset `shift*H` is `self`.
+    pub fn coset_ifft_with_options(
+        self,
+        shift: F,
+        root_table: Option<&FftRootTable<F>>,
+    ) -> PolynomialCoeffs<F> {
         // info!("before ifft: {:?}", self.values[2086137]);
-        let mut shifted_coeffs = self.ifft();
+        let mut shifted_coeffs = self.ifft_with_options(root_table);
         // info!("after  ifft: {:?}", shifted_coeffs.coeffs[2086137]);
 
         shifted_coeffs
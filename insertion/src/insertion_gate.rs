@@ -5,6 +5,7 @@ use alloc::{format, vec};
 use core::marker::PhantomData;
 use core::ops::Range;
 
+use anyhow::Result;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
 use plonky2::gates::gate::Gate;
@@ -17,6 +18,7 @@ use plonky2::iop::wire::Wire;
 use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES};
 
 /// A gate for inserting a value into a list at a non-deterministic location.
 #[derive(Clone, Debug)]
@@ -80,10 +82,19 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for InsertionGate<
         format!("{self:?}<D={D}>")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.vec_size)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let vec_size = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self::new(vec_size))
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         todo!()
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         todo!()
     }
 
@@ -250,7 +261,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for InsertionGate<
 }
 
 #[derive(Debug)]
-struct InsertionGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct InsertionGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     gate: InsertionGate<F, D>,
 }
@@ -324,6 +335,21 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F> for Insert
             out_buffer.set_wire(insert_here_wire, insert_here_vals[i]);
         }
     }
+
+    fn id(&self) -> String {
+        "InsertionGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        self.gate.serialize(dst)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let gate = InsertionGate::<F, D>::deserialize(src)?;
+        Ok(Self { row, gate })
+    }
 }
 
 #[cfg(test)]
@@ -10,3 +10,4 @@ extern crate alloc;
 
 pub mod insert_gadget;
 pub mod insertion_gate;
+pub mod serialization;
@@ -0,0 +1,20 @@
+//! Registration of this crate's gates and generators, for serializing circuits which use them.
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::util::serialization::gate_serialization::GateSerializer;
+use plonky2::util::serialization::generator_serialization::WitnessGeneratorSerializer;
+
+use crate::insertion_gate::{InsertionGate, InsertionGenerator};
+
+pub fn register_gates<F: RichField + Extendable<D>, const D: usize>(
+    serializer: &mut GateSerializer<F, D>,
+) {
+    serializer.register::<InsertionGate<F, D>>("InsertionGate");
+}
+
+pub fn register_generators<F: RichField + Extendable<D>, const D: usize>(
+    serializer: &mut WitnessGeneratorSerializer<F, D>,
+) {
+    serializer.register_simple::<InsertionGenerator<F, D>>("InsertionGenerator");
+}
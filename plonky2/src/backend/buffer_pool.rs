@@ -0,0 +1,313 @@
+//! Reusable host buffers for the transfers between the host and a backend.
+//!
+//! Copies to and from a GPU are much faster from page-locked ("pinned") memory, which is however
+//! expensive to allocate. A [`BufferPool`] keeps the buffers it hands out once they are dropped,
+//! sorted by size class, so that the next proof of the same circuit gets them back instead of
+//! allocating again. [`BufferPool::host`] pools ordinary host memory, which behaves the same and
+//! is what the [`CpuBackend`](crate::backend::cpu::CpuBackend) uses.
+
+use alloc::alloc::Global;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::alloc::{AllocError, Allocator, Layout};
+use core::fmt::{Debug, Formatter};
+use core::mem::size_of;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use std::sync::Mutex;
+
+use crate::field::types::Field;
+
+/// The allocator of a pool, either `Global` or the pinned memory allocator.
+#[derive(Clone)]
+pub struct PoolAllocator(Arc<dyn Allocator + Send + Sync>);
+
+impl PoolAllocator {
+    pub fn host() -> Self {
+        Self(Arc::new(Global))
+    }
+
+    #[cfg(feature = "cuda")]
+    pub fn pinned() -> Self {
+        Self(Arc::new(crate::fri::oracle::MyAllocator {}))
+    }
+}
+
+unsafe impl Allocator for PoolAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.deallocate(ptr, layout)
+    }
+}
+
+/// Allocation statistics of a [`BufferPool`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PoolStats {
+    /// The number of buffers allocated.
+    pub allocations: usize,
+    /// The number of buffers handed out again instead of being allocated.
+    pub reuses: usize,
+    /// The bytes held by the pool, whether handed out or not.
+    pub bytes_allocated: usize,
+    /// The bytes currently handed out.
+    pub bytes_in_use: usize,
+    /// The maximum of `bytes_in_use` so far.
+    pub peak_bytes_in_use: usize,
+}
+
+struct PoolState<T> {
+    /// The buffers which aren't handed out, keyed by capacity.
+    free: BTreeMap<usize, Vec<Vec<T, PoolAllocator>>>,
+    stats: PoolStats,
+}
+
+struct Shared<T> {
+    allocator: PoolAllocator,
+    state: Mutex<PoolState<T>>,
+}
+
+/// A pool of buffers of `T`s, of power-of-two capacities. Clones share the same buffers.
+pub struct BufferPool<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for BufferPool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: Copy + Default> BufferPool<T> {
+    pub fn new(allocator: PoolAllocator) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                allocator,
+                state: Mutex::new(PoolState {
+                    free: BTreeMap::new(),
+                    stats: PoolStats::default(),
+                }),
+            }),
+        }
+    }
+
+    /// A pool of ordinary host memory.
+    pub fn host() -> Self {
+        Self::new(PoolAllocator::host())
+    }
+
+    /// A pool of page-locked memory.
+    #[cfg(feature = "cuda")]
+    pub fn pinned() -> Self {
+        Self::new(PoolAllocator::pinned())
+    }
+
+    /// A buffer of `len` default values, which returns to the pool when dropped.
+    pub fn take(&self, len: usize) -> PooledBuffer<T> {
+        let capacity = len.next_power_of_two();
+        let bytes = capacity * size_of::<T>();
+        let mut state = self.shared.state.lock().unwrap();
+        let reused = state
+            .free
+            .get_mut(&capacity)
+            .and_then(|buffers| buffers.pop());
+        let mut buffer = match reused {
+            Some(buffer) => {
+                state.stats.reuses += 1;
+                buffer
+            }
+            None => {
+                state.stats.allocations += 1;
+                state.stats.bytes_allocated += bytes;
+                Vec::with_capacity_in(capacity, self.shared.allocator.clone())
+            }
+        };
+        state.stats.bytes_in_use += bytes;
+        state.stats.peak_bytes_in_use = state.stats.peak_bytes_in_use.max(state.stats.bytes_in_use);
+        drop(state);
+
+        buffer.clear();
+        buffer.resize(len, T::default());
+        PooledBuffer {
+            buffer,
+            pool: Some(self.clone()),
+        }
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        self.shared.state.lock().unwrap().stats
+    }
+
+    /// Frees the buffers which aren't handed out.
+    pub fn clear(&self) {
+        let mut state = self.shared.state.lock().unwrap();
+        let freed = state
+            .free
+            .values()
+            .flatten()
+            .map(|b| b.capacity() * size_of::<T>())
+            .sum::<usize>();
+        state.free.clear();
+        state.stats.bytes_allocated -= freed;
+    }
+
+    fn give_back(&self, buffer: Vec<T, PoolAllocator>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.stats.bytes_in_use -= buffer.capacity() * size_of::<T>();
+        state
+            .free
+            .entry(buffer.capacity())
+            .or_default()
+            .push(buffer);
+    }
+}
+
+/// A buffer handed out by a [`BufferPool`], or a standalone one if it has no pool.
+pub struct PooledBuffer<T: Copy + Default> {
+    buffer: Vec<T, PoolAllocator>,
+    pool: Option<BufferPool<T>>,
+}
+
+impl<T: Copy + Default> PooledBuffer<T> {
+    /// A buffer of `len` default values in ordinary host memory, outside of any pool.
+    pub fn detached(len: usize) -> Self {
+        let mut buffer = Vec::with_capacity_in(len, PoolAllocator::host());
+        buffer.resize(len, T::default());
+        Self { buffer, pool: None }
+    }
+
+    /// A buffer of `len` default values, taken from `pool` if any.
+    pub fn take_from(pool: Option<&BufferPool<T>>, len: usize) -> Self {
+        match pool {
+            Some(pool) => pool.take(len),
+            None => Self::detached(len),
+        }
+    }
+}
+
+impl<T: Copy + Default> Default for PooledBuffer<T> {
+    fn default() -> Self {
+        Self::detached(0)
+    }
+}
+
+impl<T: Copy + Default> Drop for PooledBuffer<T> {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.take() {
+            let buffer = core::mem::replace(&mut self.buffer, Vec::new_in(PoolAllocator::host()));
+            pool.give_back(buffer);
+        }
+    }
+}
+
+impl<T: Copy + Default> Deref for PooledBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.buffer
+    }
+}
+
+impl<T: Copy + Default> DerefMut for PooledBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.buffer
+    }
+}
+
+impl<T: Copy + Default> Clone for PooledBuffer<T> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::take_from(self.pool.as_ref(), self.len());
+        clone.copy_from_slice(self);
+        clone
+    }
+}
+
+impl<T: Copy + Default + Debug> Debug for PooledBuffer<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+/// The buffers a backend keeps across proofs: words for the copies to and from its memory, and
+/// field elements for the values `my_prove` computes on the host.
+#[derive(Clone)]
+pub struct HostBuffers<F: Field> {
+    pub words: BufferPool<u64>,
+    pub values: BufferPool<F>,
+}
+
+impl<F: Field> HostBuffers<F> {
+    pub fn host() -> Self {
+        Self {
+            words: BufferPool::host(),
+            values: BufferPool::host(),
+        }
+    }
+
+    #[cfg(feature = "cuda")]
+    pub fn pinned() -> Self {
+        Self {
+            words: BufferPool::pinned(),
+            values: BufferPool::pinned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_reuse() {
+        let pool = BufferPool::<u64>::host();
+        let a = pool.take(100);
+        assert_eq!(a.len(), 100);
+        assert!(a.iter().all(|&x| x == 0));
+        let b = pool.take(20);
+        drop(a);
+
+        // Same size class as `a`, which is reused, and cleared.
+        let mut c = pool.take(128);
+        assert!(c.iter().all(|&x| x == 0));
+        c[0] = 1;
+        drop(c);
+        let c = pool.take(65);
+        assert_eq!(c[0], 0);
+        // No buffer of that size class is free.
+        let d = pool.take(100);
+
+        let stats = pool.stats();
+        assert_eq!(stats.allocations, 3);
+        assert_eq!(stats.reuses, 2);
+        assert_eq!(stats.bytes_allocated, (128 + 32 + 128) * 8);
+        assert_eq!(stats.bytes_in_use, (128 + 32 + 128) * 8);
+
+        drop((b, c, d));
+        let stats = pool.stats();
+        assert_eq!(stats.bytes_in_use, 0);
+        assert_eq!(stats.peak_bytes_in_use, (128 + 32 + 128) * 8);
+
+        pool.clear();
+        assert_eq!(pool.stats().bytes_allocated, 0);
+    }
+
+    #[test]
+    fn test_detached_and_cloned_buffers() {
+        let pool = BufferPool::<u64>::host();
+        let mut a = PooledBuffer::take_from(Some(&pool), 3);
+        a.copy_from_slice(&[1, 2, 3]);
+        let b = a.clone();
+        assert_eq!(&*b, &[1, 2, 3]);
+        assert_eq!(pool.stats().allocations, 2);
+
+        let c = PooledBuffer::<u64>::take_from(None, 3);
+        drop((a, b, c));
+        assert_eq!(pool.stats().bytes_in_use, 0);
+        assert_eq!(pool.stats().bytes_allocated, 2 * 4 * 8);
+    }
+}
@@ -0,0 +1,487 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use anyhow::Result;
+use maybe_rayon::*;
+
+use crate::backend::buffer_pool::HostBuffers;
+use crate::backend::gates::GateRegistry;
+use crate::backend::{
+    hashes_to_words, lde_values_fn, CommitmentLayout, ProverBackend, ProverLayout,
+    QuotientPolysArgs,
+};
+use crate::field::extension::Extendable;
+use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
+use crate::fri::oracle::{PolynomialBatch, SaltSource};
+use crate::hash::hash_types::RichField;
+use crate::hash::merkle_tree::MerkleTree;
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::GenericConfig;
+use crate::plonk::prover::{compute_quotient_polys_from_ldes, QuotientDomain};
+use crate::util::timing::TimingTree;
+use crate::util::{log2_strict, reverse_index_bits_in_place, transpose};
+
+/// A pure-Rust backend keeping its memory in a `Vec<u64>`, with the same layout as the GPU's.
+/// It is meant as a reference for the CUDA kernels, and to run `my_prove` without a GPU.
+pub struct CpuBackend<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    common_data: CommonCircuitData<F, D>,
+    /// The leaves of the constants and sigmas commitment, one after the other.
+    constants_sigmas_leaves: Vec<F>,
+    memory: Vec<u64>,
+    second_stage_offset: usize,
+    gate_registry: GateRegistry,
+    salt_source: SaltSource<F>,
+    host_buffers: HostBuffers<F>,
+    _phantom: PhantomData<C>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> CpuBackend<F, C, D> {
+    pub fn new(
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Self {
+        let second_stage_offset = ProverLayout::min_second_stage_offset(common_data);
+        let memory_len = ProverLayout::new(common_data, second_stage_offset).end();
+        let constants_sigmas_leaves = (0..common_data.lde_size())
+            .flat_map(|i| {
+                prover_data
+                    .constants_sigmas_commitment
+                    .merkle_tree
+                    .get(i)
+                    .to_vec()
+            })
+            .collect();
+
+        Self {
+            common_data: common_data.clone(),
+            constants_sigmas_leaves,
+            memory: vec![0; memory_len],
+            second_stage_offset,
+            gate_registry: GateRegistry::gpu(),
+            salt_source: SaltSource::Random,
+            host_buffers: HostBuffers::host(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Only evaluates the gates of `gate_registry`, instead of those supported by the GPU.
+    pub fn with_gate_registry(mut self, gate_registry: GateRegistry) -> Self {
+        self.gate_registry = gate_registry;
+        self
+    }
+
+    /// Salts the leaves of hiding commitments with `salt_source`, e.g. to commit to the same
+    /// leaves as a `Prover` using the same source.
+    pub fn with_salt_source(mut self, salt_source: SaltSource<F>) -> Self {
+        self.salt_source = salt_source;
+        self
+    }
+
+    fn values(&self, offset: usize, len: usize) -> Vec<F> {
+        self.memory[offset..offset + len]
+            .iter()
+            .map(|&w| F::from_noncanonical_u64(w))
+            .collect()
+    }
+
+    fn set_values(&mut self, offset: usize, values: &[F]) {
+        self.memory[offset..offset + values.len()]
+            .iter_mut()
+            .zip(values)
+            .for_each(|(w, v)| *w = v.to_canonical_u64());
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProverBackend<F, C, D> for CpuBackend<F, C, D>
+{
+    fn memory_len(&self) -> usize {
+        self.memory.len()
+    }
+
+    fn second_stage_offset(&self) -> usize {
+        self.second_stage_offset
+    }
+
+    fn write_words(&mut self, offset: usize, words: &[u64]) {
+        self.memory[offset..offset + words.len()].copy_from_slice(words);
+    }
+
+    fn read_words(&mut self, offset: usize, words: &mut [u64]) {
+        words.copy_from_slice(&self.memory[offset..offset + words.len()]);
+    }
+
+    fn ifft(&mut self, offset: usize, poly_num: usize, values_num_per_poly: usize) -> Result<()> {
+        self.memory[offset..offset + poly_num * values_num_per_poly]
+            .par_chunks_mut(values_num_per_poly)
+            .for_each(|chunk| {
+                let values = chunk.iter().map(|&w| F::from_noncanonical_u64(w)).collect();
+                let coeffs = PolynomialValues::new(values).ifft();
+                chunk
+                    .iter_mut()
+                    .zip(coeffs.coeffs)
+                    .for_each(|(w, c)| *w = c.to_canonical_u64());
+            });
+        Ok(())
+    }
+
+    fn merkle_tree_from_coeffs(&mut self, layout: &CommitmentLayout) -> Result<()> {
+        let coeffs = self
+            .values(layout.offset, layout.values_len())
+            .chunks(layout.values_num_per_poly)
+            .map(|chunk| PolynomialCoeffs::new(chunk.to_vec()))
+            .collect::<Vec<_>>();
+        let lde_values = PolynomialBatch::<F, C, D>::lde_values(&coeffs, layout.rate_bits, None);
+        self.set_values(
+            layout.offset + layout.ext_values_len(),
+            &lde_values.concat(),
+        );
+
+        let mut leaves = transpose(&lde_values);
+        reverse_index_bits_in_place(&mut leaves);
+        // The salts are already in leaf order.
+        let lde_size = layout.lde_size();
+        let salts = self.values(layout.salts_offset(), layout.salt_size * lde_size);
+        for (i, leaf) in leaves.iter_mut().enumerate() {
+            leaf.extend(salts.iter().skip(i).step_by(lde_size));
+        }
+        self.set_values(layout.offset, &leaves.concat());
+
+        let tree = MerkleTree::<F, C::Hasher>::new(leaves, layout.cap_height);
+        let words = hashes_to_words::<F, C::Hasher>(&[tree.digests, tree.cap.0].concat());
+        self.write_words(layout.digests_offset(), &words);
+        Ok(())
+    }
+
+    fn gate_registry(&self) -> GateRegistry {
+        self.gate_registry.clone()
+    }
+
+    fn salt_source(&self) -> SaltSource<F> {
+        self.salt_source
+    }
+
+    fn host_buffers(&self) -> Option<&HostBuffers<F>> {
+        Some(&self.host_buffers)
+    }
+
+    fn compute_quotient_polys(&mut self, args: &QuotientPolysArgs<F>) -> Result<()> {
+        let common_data = &self.common_data;
+        let lde_bits = log2_strict(args.wires.lde_size());
+
+        let wires_leaves = self.values(args.wires.offset, args.wires.ext_values_len());
+        let zs_partial_products_leaves = self.values(
+            args.zs_partial_products.offset,
+            args.zs_partial_products.ext_values_len(),
+        );
+        let num_preprocessed_polys = common_data.num_preprocessed_polys();
+        let gate_rows = args.gates.iter().map(|g| g.row).collect::<Vec<_>>();
+
+        let quotient_polys = compute_quotient_polys_from_ldes::<F, C, D>(
+            common_data,
+            &QuotientDomain::new(common_data),
+            args.public_inputs_hash,
+            &lde_values_fn(
+                &self.constants_sigmas_leaves,
+                num_preprocessed_polys,
+                num_preprocessed_polys,
+                lde_bits,
+            ),
+            &lde_values_fn(
+                &wires_leaves,
+                args.wires.leaf_len(),
+                args.wires.poly_num,
+                lde_bits,
+            ),
+            &lde_values_fn(
+                &zs_partial_products_leaves,
+                args.zs_partial_products.leaf_len(),
+                args.zs_partial_products.poly_num,
+                lde_bits,
+            ),
+            args.betas,
+            args.gammas,
+            // `my_prove` rejects circuits with lookups.
+            &[],
+            args.alphas,
+            &gate_rows,
+            args.fallback_quotient_values,
+            &mut TimingTree::default(),
+            &mut None,
+        );
+
+        let coeffs = quotient_polys
+            .into_iter()
+            .flat_map(|p| p.coeffs)
+            .collect::<Vec<_>>();
+        self.set_values(args.quotient_polys_offset, &coeffs);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::hash::hash_types::HashOut;
+    use crate::iop::generator::generate_partial_witness;
+    use crate::iop::witness::{PartialWitness, PartitionWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+    use crate::plonk::prover::{
+        my_prove, my_prove_from_witness, my_prove_from_witness_with_trace, prove_from_witness,
+        prove_from_witness_with_trace,
+    };
+    use crate::plonk::prover_rounds::Prover;
+    use crate::plonk::prover_trace::{TraceBundle, TraceStage};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn check_my_prove(
+        data: &CircuitData<F, C, D>,
+        witness: &PartitionWitness<F>,
+        backend: &mut CpuBackend<F, C, D>,
+    ) -> Result<()> {
+        // Without zero-knowledge, proving a given witness is deterministic, so the proof must match
+        // `prove`'s.
+        let expected = prove_from_witness(
+            &data.prover_only,
+            &data.common,
+            witness.clone(),
+            &mut TimingTree::default(),
+        )?;
+        let proof = my_prove_from_witness(
+            &data.prover_only,
+            &data.common,
+            witness.clone(),
+            &mut TimingTree::default(),
+            backend,
+        )?;
+        assert_eq!(proof, expected);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_my_prove_with_cpu_backend() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let xy = builder.mul(x, y);
+        let z = builder.add(xy, x);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        let witness = generate_partial_witness(pw, &data.prover_only, &data.common)?;
+
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        check_my_prove(&data, &witness, &mut backend)?;
+
+        // Evaluating every gate through the fallback path must give the same proof.
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common)
+            .with_gate_registry(GateRegistry::new());
+        check_my_prove(&data, &witness, &mut backend)
+    }
+
+    #[test]
+    fn test_my_prove_with_unsupported_gates() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let e = builder.add_virtual_target();
+        let y = builder.exp(x, e, 8);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let support = GateRegistry::gpu().support(&data.common);
+        assert!(!support.is_fully_accelerated());
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(e, F::from_canonical_u64(123));
+        let witness = generate_partial_witness(pw, &data.prover_only, &data.common)?;
+
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        check_my_prove(&data, &witness, &mut backend)
+    }
+
+    #[test]
+    fn test_my_prove_trace_matches_prove() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let xy = builder.mul(x, y);
+        builder.register_public_input(xy);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        // The unused public input wires are random, so both provers must share the witness.
+        let witness = generate_partial_witness(pw, &data.prover_only, &data.common)?;
+
+        let mut expected = TraceBundle::new();
+        prove_from_witness_with_trace(
+            &data.prover_only,
+            &data.common,
+            witness.clone(),
+            &mut TimingTree::default(),
+            &mut Some(&mut expected),
+        )?;
+        let mut actual = TraceBundle::new();
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        let proof = my_prove_from_witness_with_trace(
+            &data.prover_only,
+            &data.common,
+            witness,
+            &mut TimingTree::default(),
+            &mut backend,
+            &mut Some(&mut actual),
+        )?;
+
+        assert!(expected.get(TraceStage::QuotientValues).is_some());
+        assert!(actual.get(TraceStage::QuotientValues).is_none());
+        assert_eq!(actual.first_divergence(&expected), None);
+        assert_eq!(
+            actual.get(TraceStage::PowWitness).unwrap().values,
+            vec![proof.proof.opening_proof.pow_witness]
+        );
+
+        let actual = TraceBundle::from_bytes(actual.to_bytes()).expect("Invalid trace bundle");
+        assert_eq!(actual.first_divergence(&expected), None);
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_my_prove_other_rate() {
+        let mut config = CircuitConfig::standard_recursion_config();
+        config.fri_config.rate_bits = 4;
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let xy = builder.mul(x, y);
+        builder.register_public_input(xy);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        // The quotient degree factor stays 8, so the quotient doesn't fill the LDE.
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        let err = my_prove(
+            &data.prover_only,
+            &data.common,
+            pw,
+            &mut TimingTree::default(),
+            &mut backend,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("quotient degree factor"));
+    }
+
+    #[test]
+    fn test_my_prove_zero_knowledge() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_zk_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let xy = builder.mul(x, y);
+        builder.register_public_input(xy);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        // Fix the blinding wires and the salts, so that both provers use the same randomness.
+        let witness = data.record_witness(pw)?;
+        let salt_source = SaltSource::Seeded(HashOut::rand());
+
+        let expected = Prover::new(
+            &data.prover_only,
+            &data.common,
+            witness
+                .clone()
+                .into_partition_witness(&data.prover_only, &data.common)?,
+            &mut TimingTree::default(),
+            &mut None,
+        )
+        .with_salt_source(salt_source)
+        .finish(&mut TimingTree::default(), &mut None)?;
+        let mut backend =
+            CpuBackend::new(&data.prover_only, &data.common).with_salt_source(salt_source);
+        let proof = my_prove_from_witness(
+            &data.prover_only,
+            &data.common,
+            witness
+                .clone()
+                .into_partition_witness(&data.prover_only, &data.common)?,
+            &mut TimingTree::default(),
+            &mut backend,
+        )?;
+        assert_eq!(proof, expected);
+        data.verify(proof)?;
+
+        // With random salts, the commitments differ.
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        let proof = my_prove_from_witness(
+            &data.prover_only,
+            &data.common,
+            witness.into_partition_witness(&data.prover_only, &data.common)?,
+            &mut TimingTree::default(),
+            &mut backend,
+        )?;
+        assert_ne!(proof.proof.wires_cap, expected.proof.wires_cap);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_my_prove_reuses_host_buffers() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let xy = builder.mul(x, y);
+        builder.register_public_input(xy);
+        let data = builder.build::<C>();
+
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        let mut stats = vec![];
+        for _ in 0..2 {
+            let mut pw = PartialWitness::new();
+            pw.set_target(x, F::rand());
+            pw.set_target(y, F::rand());
+            let proof = my_prove(
+                &data.prover_only,
+                &data.common,
+                pw,
+                &mut TimingTree::default(),
+                &mut backend,
+            )?;
+            data.verify(proof)?;
+
+            let buffers = backend.host_buffers().unwrap();
+            stats.push((buffers.words.stats(), buffers.values.stats()));
+        }
+
+        let ((words_1, values_1), (words_2, values_2)) = (stats[0], stats[1]);
+        assert_eq!(words_1.bytes_in_use, 0);
+        assert_eq!(values_1.bytes_in_use, 0);
+        // The second proof doesn't allocate anything new.
+        assert_eq!(words_2.allocations, words_1.allocations);
+        assert_eq!(values_2.allocations, values_1.allocations);
+        assert!(words_2.reuses > words_1.reuses);
+        assert!(values_2.reuses > values_1.reuses);
+        Ok(())
+    }
+}
@@ -0,0 +1,324 @@
+use core::ffi::c_void;
+use std::mem::transmute;
+
+use anyhow::{anyhow, ensure, Result};
+use plonky2_cuda::{DataSlice, GateDescriptor, GateSet, QuotientShape, MAX_GPU_GATES};
+use rustacuda::context::CurrentContext;
+use rustacuda::memory::{AsyncCopyDestination, DeviceCopy, DeviceSlice};
+use rustacuda::prelude::{Context, ContextFlags, Device, DeviceBuffer, Stream, StreamFlags};
+
+use crate::backend::buffer_pool::HostBuffers;
+use crate::backend::gates::GpuGate;
+use crate::backend::shape::QuotientKernelShape;
+use crate::backend::{CommitmentLayout, ProverBackend, ProverLayout, QuotientPolysArgs};
+use crate::field::extension::Extendable;
+use crate::field::fft::fft_root_table;
+use crate::fri::oracle::{CudaDeviceTables, CudaInnerContext, CudaInvContext};
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::GenericConfig;
+use crate::plonk::prover::QuotientDomain;
+use crate::util::log2_strict;
+
+/// Turns the status returned by the `plonky2_cuda` function `call` into an error.
+fn check(status: plonky2_cuda::Error, call: &str) -> Result<()> {
+    status
+        .into_result()
+        .map_err(|e| anyhow!("plonky2_cuda::{call} failed: {e}"))
+}
+
+fn data_slice<F>(slice: &DeviceSlice<F>) -> DataSlice {
+    DataSlice {
+        ptr: slice.as_ptr() as *const c_void,
+        len: slice.len() as i32,
+    }
+}
+
+fn quotient_shape(shape: &QuotientKernelShape) -> QuotientShape {
+    QuotientShape {
+        num_challenges: shape.num_challenges as i32,
+        num_gate_constraints: shape.num_gate_constraints as i32,
+        num_constants: shape.num_constants as i32,
+        num_routed_wires: shape.num_routed_wires as i32,
+        quotient_degree_factor: shape.quotient_degree_factor as i32,
+        num_partial_products: shape.num_partial_products as i32,
+        constants_sigmas_commitment_leaf_len: shape.constants_sigmas_leaf_len as i32,
+        zs_partial_products_commitment_leaf_len: shape.zs_partial_products_leaf_len as i32,
+        wires_commitment_leaf_len: shape.wires_leaf_len as i32,
+    }
+}
+
+fn gate_set(gates: &[GpuGate], num_selectors: usize) -> GateSet {
+    assert!(gates.len() <= MAX_GPU_GATES);
+    let mut descriptors = [GateDescriptor::default(); MAX_GPU_GATES];
+    for (descriptor, gate) in descriptors.iter_mut().zip(gates) {
+        *descriptor = GateDescriptor {
+            kind: gate.kind as i32,
+            params: gate.params.map(|p| p as i32),
+            row: gate.row as i32,
+            selector_index: gate.selector_index as i32,
+            group_start: gate.group_range.start as i32,
+            group_end: gate.group_range.end as i32,
+            num_constraints: gate.num_constraints as i32,
+        };
+    }
+    GateSet {
+        num_gates: gates.len() as i32,
+        num_selectors: num_selectors as i32,
+        gates: descriptors,
+    }
+}
+
+impl<F: RichField + Extendable<D> + DeviceCopy, C: GenericConfig<D, F = F>, const D: usize>
+    CudaInvContext<F, C, D>
+{
+    /// Creates a context on `device` proving the given circuit: the memory of `my_prove` and the
+    /// tables of the circuit are allocated on the device, and the context is made current on the
+    /// calling thread.
+    pub fn for_circuit(
+        device: Device,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<Self> {
+        let ctx =
+            Context::create_and_push(ContextFlags::MAP_HOST | ContextFlags::SCHED_AUTO, device)?;
+        let inner = CudaInnerContext {
+            stream: Stream::new(StreamFlags::NON_BLOCKING, None)?,
+            stream2: Stream::new(StreamFlags::NON_BLOCKING, None)?,
+        };
+        let second_stage_offset = ProverLayout::min_second_stage_offset(common_data);
+        let memory_len = ProverLayout::new(common_data, second_stage_offset).end();
+        let cache_mem_device = unsafe { DeviceBuffer::uninitialized(memory_len)? };
+
+        let degree = common_data.degree();
+        let lde_size = common_data.lde_size();
+        let constants_sigmas_leaves = (0..lde_size)
+            .flat_map(|i| {
+                prover_data
+                    .constants_sigmas_commitment
+                    .merkle_tree
+                    .get(i)
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        let shift_powers = F::coset_shift().powers().take(degree).collect::<Vec<_>>();
+        let shift_inv_powers = F::coset_shift()
+            .inverse()
+            .powers()
+            .take(lde_size)
+            .collect::<Vec<_>>();
+        let domain = QuotientDomain::new(common_data);
+        let tables = CudaDeviceTables {
+            root_table_device: DeviceBuffer::from_slice(&prover_data.fft_root_table_deg)?,
+            root_table_device2: DeviceBuffer::from_slice(&fft_root_table(lde_size).concat())?,
+            constants_sigmas_commitment_leaves_device: DeviceBuffer::from_slice(
+                &constants_sigmas_leaves,
+            )?,
+            shift_powers_device: DeviceBuffer::from_slice(&shift_powers)?,
+            shift_inv_powers_device: DeviceBuffer::from_slice(&shift_inv_powers)?,
+            points_device: DeviceBuffer::from_slice(&domain.shifted_points)?,
+            z_h_on_coset_evals_device: DeviceBuffer::from_slice(&domain.z_h_on_coset.evals)?,
+            z_h_on_coset_inverses_device: DeviceBuffer::from_slice(&domain.z_h_on_coset.inverses)?,
+            k_is_device: DeviceBuffer::from_slice(&common_data.k_is)?,
+        };
+
+        let context = Self::new(
+            common_data,
+            inner,
+            ctx,
+            cache_mem_device,
+            second_stage_offset,
+            tables,
+            HostBuffers::pinned(),
+        )?;
+        Ok(context)
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProverBackend<F, C, D> for CudaInvContext<F, C, D>
+{
+    fn memory_len(&self) -> usize {
+        self.cache_mem_device.len()
+    }
+
+    fn second_stage_offset(&self) -> usize {
+        self.second_stage_offset
+    }
+
+    fn write_words(&mut self, offset: usize, words: &[u64]) {
+        let dst = &mut self.cache_mem_device[offset..offset + words.len()];
+        unsafe {
+            transmute::<&mut DeviceSlice<F>, &mut DeviceSlice<u64>>(dst)
+                .async_copy_from(words, &self.inner.stream)
+                .unwrap();
+        }
+        self.inner.stream.synchronize().unwrap();
+    }
+
+    fn read_words(&mut self, offset: usize, words: &mut [u64]) {
+        let src = &self.cache_mem_device[offset..offset + words.len()];
+        unsafe {
+            transmute::<&DeviceSlice<F>, &DeviceSlice<u64>>(src)
+                .async_copy_to(words, &self.inner.stream)
+                .unwrap();
+        }
+        self.inner.stream.synchronize().unwrap();
+    }
+
+    fn ifft(&mut self, offset: usize, poly_num: usize, values_num_per_poly: usize) -> Result<()> {
+        let lg_n = log2_strict(values_num_per_poly);
+        let n_inv = F::inverse_2exp(lg_n);
+        let values_device = &mut self.cache_mem_device[offset..];
+        let ctx_ptr: *mut CudaInnerContext = &mut self.inner;
+        let status = unsafe {
+            plonky2_cuda::ifft(
+                values_device.as_mut_ptr() as *mut u64,
+                poly_num as i32,
+                values_num_per_poly as i32,
+                lg_n as i32,
+                self.root_table_device.as_ptr() as *const u64,
+                &n_inv as *const F as *const u64,
+                ctx_ptr as *mut c_void,
+            )
+        };
+        check(status, "ifft")
+    }
+
+    fn merkle_tree_from_coeffs(&mut self, layout: &CommitmentLayout) -> Result<()> {
+        let values_device = &mut self.cache_mem_device[layout.offset..];
+        let ctx_ptr: *mut CudaInnerContext = &mut self.inner;
+        let status = unsafe {
+            plonky2_cuda::merkle_tree_from_coeffs(
+                values_device.as_mut_ptr() as *mut u64,
+                values_device.as_mut_ptr() as *mut u64,
+                layout.poly_num as i32,
+                layout.values_num_per_poly as i32,
+                log2_strict(layout.values_num_per_poly) as i32,
+                self.root_table_device.as_ptr() as *const u64,
+                self.root_table_device2.as_ptr() as *const u64,
+                self.shift_powers_device.as_ptr() as *const u64,
+                layout.rate_bits as i32,
+                layout.salt_size as i32,
+                layout.cap_height as i32,
+                layout.ext_values_len() as i32,
+                ctx_ptr as *mut c_void,
+            )
+        };
+        check(status, "merkle_tree_from_coeffs")?;
+        self.inner.stream.synchronize()?;
+        Ok(())
+    }
+
+    fn compute_quotient_polys(&mut self, args: &QuotientPolysArgs<F>) -> Result<()> {
+        let lde_size = args.wires.lde_size();
+        let num_challenges = args.alphas.len();
+        ensure!(
+            num_challenges == self.quotient_kernel_shape.num_challenges,
+            "{num_challenges} challenges, but the context was built for {}",
+            self.quotient_kernel_shape.num_challenges
+        );
+
+        // The kernel uses the words following the quotient polynomials as scratch space, followed
+        // by the challenges.
+        let quotient_polys_len = num_challenges * lde_size;
+        let outs_offset = args.quotient_polys_offset + quotient_polys_len;
+        let challenges_offset = outs_offset + quotient_polys_len;
+        for (i, challenges) in [args.alphas, args.betas, args.gammas].iter().enumerate() {
+            let words = challenges
+                .iter()
+                .map(|c| c.to_canonical_u64())
+                .collect::<Vec<_>>();
+            ProverBackend::<F, C, D>::write_words(
+                self,
+                challenges_offset + i * num_challenges,
+                &words,
+            );
+        }
+
+        // The contribution of the gates evaluated on the CPU goes after the challenges.
+        let fallback_offset = challenges_offset + 3 * num_challenges;
+        if let Some(values) = args.fallback_quotient_values {
+            let words = values
+                .iter()
+                .map(|v| v.to_canonical_u64())
+                .collect::<Vec<_>>();
+            ProverBackend::<F, C, D>::write_words(self, fallback_offset, &words);
+        }
+        let gate_set = gate_set(args.gates, self.quotient_kernel_shape.num_selectors);
+
+        let mem = &self.cache_mem_device;
+        let fallback_outs = match args.fallback_quotient_values {
+            Some(_) => mem[fallback_offset..].as_ptr() as *const c_void,
+            None => core::ptr::null(),
+        };
+        let zs_partial_products_leaves_device = data_slice(
+            &mem[args.zs_partial_products.offset
+                ..args.zs_partial_products.offset + args.zs_partial_products.ext_values_len()],
+        );
+        let alphas_device = data_slice(&mem[challenges_offset..][..num_challenges]);
+        let betas_device = data_slice(&mem[challenges_offset + num_challenges..][..num_challenges]);
+        let gammas_device =
+            data_slice(&mem[challenges_offset + 2 * num_challenges..][..num_challenges]);
+        let wires_device = mem[args.wires.offset..].as_ptr();
+        let d_quotient_polys = mem[args.quotient_polys_offset..].as_ptr();
+        let d_outs = mem[outs_offset..].as_ptr();
+
+        let constants_sigmas_commitment_leaves_device =
+            data_slice(&self.constants_sigmas_commitment_leaves_device);
+        let points_device = data_slice(&self.points_device);
+        let z_h_on_coset_evals_device = data_slice(&self.z_h_on_coset_evals_device);
+        let z_h_on_coset_inverses_device = data_slice(&self.z_h_on_coset_inverses_device);
+        let k_is_device = data_slice(&self.k_is_device);
+
+        let shape = quotient_shape(&self.quotient_kernel_shape);
+        let public_inputs_hash = args
+            .public_inputs_hash
+            .elements
+            .map(|x| x.to_canonical_u64());
+        let n_inv_ext = F::inverse_2exp(log2_strict(lde_size));
+
+        let ctx_ptr: *mut CudaInnerContext = &mut self.inner;
+        let status = unsafe {
+            plonky2_cuda::compute_quotient_polys(
+                wires_device as *const u64,
+                args.wires.poly_num as i32,
+                args.wires.values_num_per_poly as i32,
+                log2_strict(args.wires.values_num_per_poly) as i32,
+                self.root_table_device2.as_ptr() as *const u64,
+                self.shift_inv_powers_device.as_ptr() as *const u64,
+                args.wires.rate_bits as i32,
+                args.wires.salt_size as i32,
+                &zs_partial_products_leaves_device,
+                &constants_sigmas_commitment_leaves_device,
+                d_outs as *mut c_void,
+                d_quotient_polys as *mut c_void,
+                &points_device,
+                &z_h_on_coset_evals_device,
+                &z_h_on_coset_inverses_device,
+                &k_is_device,
+                &alphas_device,
+                &betas_device,
+                &gammas_device,
+                &shape,
+                &gate_set,
+                fallback_outs,
+                public_inputs_hash.as_ptr(),
+                &n_inv_ext as *const F as *const u64,
+                ctx_ptr as *mut c_void,
+            )
+        };
+        check(status, "compute_quotient_polys")?;
+        self.inner.stream.synchronize()?;
+        Ok(())
+    }
+
+    fn host_buffers(&self) -> Option<&HostBuffers<F>> {
+        Some(&self.host_buffers)
+    }
+
+    fn bind_to_current_thread(&mut self) -> Result<()> {
+        CurrentContext::set_current(&self.ctx)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+//! Which gates a backend evaluates itself.
+//!
+//! The quotient kernel only implements the gates of `cuda/gates-def.cuh`. A [`GateRegistry`] maps
+//! the name of each of these gates, as found at the start of its [`Gate::id`], to a parser turning
+//! the id into the gate's parameters. Gates of the circuit missing from the registry have their
+//! constraints evaluated on the CPU, see [`GateSupport`].
+//!
+//! [`Gate::id`]: crate::gates::gate::Gate::id
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::gates::gate::gate_name;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_data::CommonCircuitData;
+
+/// Largest number of gates the quotient kernel evaluates. Further gates are evaluated on the CPU.
+pub const MAX_GPU_GATES: usize = 32;
+
+/// The gates implemented by the quotient kernel. Must match `GateKind` in `cuda/gates-def.cuh`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[repr(i32)]
+pub enum GpuGateKind {
+    Noop = 0,
+    Constant = 1,
+    PublicInput = 2,
+    BaseSum2 = 3,
+    BaseSum4 = 4,
+    Arithmetic = 5,
+    Comparison = 6,
+    U32AddMany = 7,
+    U32Arithmetic = 8,
+    U32RangeCheck = 9,
+    U32Subtraction = 10,
+    RandomAccess = 11,
+    Poseidon = 12,
+}
+
+/// A gate of the circuit, as described to the quotient kernel.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GpuGate {
+    pub kind: GpuGateKind,
+    /// The parameters of the gate, in the order of the fields of its CUDA struct.
+    pub params: [usize; 3],
+    /// Index of the gate in `CommonCircuitData::gates`.
+    pub row: usize,
+    pub selector_index: usize,
+    pub group_range: Range<usize>,
+    pub num_constraints: usize,
+}
+
+/// Extracts the kind and parameters of a gate from its id.
+pub type GateIdParser = fn(&str) -> Option<(GpuGateKind, [usize; 3])>;
+
+/// Gates known to a backend, keyed by the name at the start of their id.
+#[derive(Clone, Debug, Default)]
+pub struct GateRegistry {
+    parsers: HashMap<String, GateIdParser>,
+}
+
+impl GateRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The gates of `cuda/gates-def.cuh`.
+    pub fn gpu() -> Self {
+        let mut registry = Self::new();
+        registry.register("NoopGate", |_| Some((GpuGateKind::Noop, [0; 3])));
+        registry.register("ConstantGate", |id| {
+            Some((GpuGateKind::Constant, [id_field(id, "num_consts")?, 0, 0]))
+        });
+        registry.register("PublicInputGate", |_| {
+            Some((GpuGateKind::PublicInput, [0; 3]))
+        });
+        registry.register("BaseSumGate", |id| {
+            let kind = match id_field(id, "Base")? {
+                2 => GpuGateKind::BaseSum2,
+                4 => GpuGateKind::BaseSum4,
+                _ => return None,
+            };
+            Some((kind, [id_field(id, "num_limbs")?, 0, 0]))
+        });
+        registry.register("ArithmeticGate", |id| {
+            Some((GpuGateKind::Arithmetic, [id_field(id, "num_ops")?, 0, 0]))
+        });
+        registry.register("ComparisonGate", |id| {
+            Some((
+                GpuGateKind::Comparison,
+                [id_field(id, "num_bits")?, id_field(id, "num_chunks")?, 0],
+            ))
+        });
+        registry.register("U32AddManyGate", |id| {
+            Some((
+                GpuGateKind::U32AddMany,
+                [id_field(id, "num_addends")?, id_field(id, "num_ops")?, 0],
+            ))
+        });
+        registry.register("U32ArithmeticGate", |id| {
+            Some((GpuGateKind::U32Arithmetic, [id_field(id, "num_ops")?, 0, 0]))
+        });
+        registry.register("U32RangeCheckGate", |id| {
+            Some((
+                GpuGateKind::U32RangeCheck,
+                [id_field(id, "num_input_limbs")?, 0, 0],
+            ))
+        });
+        registry.register("U32SubtractionGate", |id| {
+            Some((
+                GpuGateKind::U32Subtraction,
+                [id_field(id, "num_ops")?, 0, 0],
+            ))
+        });
+        registry.register("RandomAccessGate", |id| {
+            Some((
+                GpuGateKind::RandomAccess,
+                [
+                    id_field(id, "bits")?,
+                    id_field(id, "num_copies")?,
+                    id_field(id, "num_extra_constants")?,
+                ],
+            ))
+        });
+        registry.register("PoseidonGate", |_| Some((GpuGateKind::Poseidon, [0; 3])));
+        registry
+    }
+
+    pub fn register(&mut self, name: &str, parser: GateIdParser) {
+        self.parsers.insert(name.to_string(), parser);
+    }
+
+    /// Returns the kind and parameters of the gate with the given id, if it is supported.
+    pub fn parse(&self, id: &str) -> Option<(GpuGateKind, [usize; 3])> {
+        self.parsers.get(gate_name(id)).and_then(|parse| parse(id))
+    }
+
+    /// Splits the gates of the circuit between the backend and the CPU.
+    pub fn support<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> GateSupport {
+        let selectors_info = &common_data.selectors_info;
+        let mut accelerated = Vec::new();
+        let mut fallback = Vec::new();
+        for (row, gate) in common_data.gates.iter().enumerate() {
+            match self.parse(&gate.0.id()) {
+                Some((kind, params)) if accelerated.len() < MAX_GPU_GATES => {
+                    let selector_index = selectors_info.selector_indices[row];
+                    accelerated.push(GpuGate {
+                        kind,
+                        params,
+                        row,
+                        selector_index,
+                        group_range: selectors_info.groups[selector_index].clone(),
+                        num_constraints: gate.0.num_constraints(),
+                    })
+                }
+                _ => fallback.push(row),
+            }
+        }
+        GateSupport {
+            accelerated,
+            fallback,
+        }
+    }
+}
+
+/// The gates of a circuit, split between the backend and the CPU.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GateSupport {
+    /// The gates whose constraints are evaluated by the backend.
+    pub accelerated: Vec<GpuGate>,
+    /// Indices in `CommonCircuitData::gates` of the gates whose constraints are evaluated on the
+    /// CPU.
+    pub fallback: Vec<usize>,
+}
+
+impl GateSupport {
+    pub fn is_fully_accelerated(&self) -> bool {
+        self.fallback.is_empty()
+    }
+
+    pub fn accelerated_rows(&self) -> Vec<usize> {
+        self.accelerated.iter().map(|g| g.row).collect()
+    }
+}
+
+/// Parses the value of `field` in an id such as `U32AddManyGate { num_addends: 2, num_ops: 10 }`
+/// or `BaseSumGate { num_limbs: 63 } + Base: 2`.
+fn id_field(id: &str, field: &str) -> Option<usize> {
+    let pattern = [" ", field, ": "].concat();
+    let start = id.find(&pattern)? + pattern.len();
+    let digits = &id[start..];
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::arithmetic_base::ArithmeticGate;
+    use crate::gates::base_sum::BaseSumGate;
+    use crate::gates::exponentiation::ExponentiationGate;
+    use crate::gates::gate::Gate;
+    use crate::gates::poseidon::PoseidonGate;
+    use crate::gates::random_access::RandomAccessGate;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_parse_ids() {
+        let registry = GateRegistry::gpu();
+        let id = <ArithmeticGate as Gate<F, D>>::id(&ArithmeticGate { num_ops: 20 });
+        assert_eq!(
+            registry.parse(&id),
+            Some((GpuGateKind::Arithmetic, [20, 0, 0]))
+        );
+        let id = <BaseSumGate<4> as Gate<F, D>>::id(&BaseSumGate::<4>::new(16));
+        assert_eq!(
+            registry.parse(&id),
+            Some((GpuGateKind::BaseSum4, [16, 0, 0]))
+        );
+        let id = <BaseSumGate<3> as Gate<F, D>>::id(&BaseSumGate::<3>::new(16));
+        assert_eq!(registry.parse(&id), None);
+        let config = CircuitConfig::standard_recursion_config();
+        let id = RandomAccessGate::<F, D>::new_from_config(&config, 4).id();
+        assert_eq!(
+            registry.parse(&id),
+            Some((GpuGateKind::RandomAccess, [4, 4, 2]))
+        );
+        let id = PoseidonGate::<F, D>::new().id();
+        assert_eq!(registry.parse(&id), Some((GpuGateKind::Poseidon, [0; 3])));
+        let id = ExponentiationGate::<F, D>::new(8).id();
+        assert_eq!(registry.parse(&id), None);
+    }
+
+    #[test]
+    fn test_support() {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        let w = builder.exp_u64(z, 12345);
+        let e = builder.add_virtual_target();
+        let v = builder.exp(w, e, 8);
+        builder.register_public_input(v);
+        let common_data = builder.build::<C>().common;
+
+        let support = GateRegistry::gpu().support(&common_data);
+        assert!(!support.is_fully_accelerated());
+        assert_eq!(
+            support.accelerated.len() + support.fallback.len(),
+            common_data.gates.len()
+        );
+        for &row in &support.fallback {
+            assert!(common_data.gates[row]
+                .0
+                .id()
+                .starts_with("ExponentiationGate"));
+        }
+        for gate in &support.accelerated {
+            let selector_index = common_data.selectors_info.selector_indices[gate.row];
+            assert_eq!(gate.selector_index, selector_index);
+            assert_eq!(
+                gate.num_constraints,
+                common_data.gates[gate.row].0.num_constraints()
+            );
+        }
+
+        assert_eq!(
+            GateRegistry::new().support(&common_data).fallback,
+            (0..common_data.gates.len()).collect::<Vec<_>>()
+        );
+    }
+}
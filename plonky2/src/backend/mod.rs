@@ -0,0 +1,374 @@
+//! Prover backends.
+//!
+//! A backend owns a flat buffer of 64-bit words and implements the heavy stages of `my_prove`
+//! (IFFTs, LDEs + Merkle trees, quotient polynomials) on data living in that buffer. The layout of
+//! the buffer is the one used by the CUDA kernels in `plonky2_cuda`, so that the same prover code
+//! can drive either the GPU or the pure-Rust [`cpu::CpuBackend`].
+
+use alloc::vec::Vec;
+
+use anyhow::Result;
+
+use crate::backend::buffer_pool::{HostBuffers, PooledBuffer};
+use crate::backend::gates::{GateRegistry, GpuGate};
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::fri::oracle::SaltSource;
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher};
+use crate::plonk::plonk_common::{salt_size, PlonkOracle};
+use crate::util::reverse_bits;
+
+pub mod buffer_pool;
+pub mod cpu;
+#[cfg(feature = "cuda")]
+pub mod cuda;
+pub mod gates;
+#[cfg(feature = "std")]
+pub mod pool;
+pub mod shape;
+
+/// Number of words used to store a single digest in backend memory.
+pub const HASH_WORDS: usize = 4;
+
+/// Position and shape of a single polynomial commitment inside backend memory.
+///
+/// Starting at `offset`, the commitment uses the following regions:
+/// - `[offset, offset + values_len)`: the polynomials, one after the other (values or
+///   coefficients), which get overwritten by the Merkle leaves, leaf after leaf, in bit-reversed
+///   order;
+/// - `[offset + ext_values_len, offset + 2 * ext_values_len)`: the LDEs, one polynomial after
+///   the other, followed by the `salt_size` salts of the leaves, which the host writes in leaf
+///   order (see `salts_offset`);
+/// - `[digests_offset, end)`: the digests of the tree, in the order of `MerkleTree::digests`,
+///   followed by the cap.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CommitmentLayout {
+    pub offset: usize,
+    pub poly_num: usize,
+    pub values_num_per_poly: usize,
+    pub rate_bits: usize,
+    pub salt_size: usize,
+    pub cap_height: usize,
+}
+
+impl CommitmentLayout {
+    /// Layout of the constants and sigmas commitment of the given circuit.
+    pub fn constants_sigmas<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+        offset: usize,
+    ) -> Self {
+        Self::for_oracle(
+            common_data,
+            offset,
+            common_data.num_preprocessed_polys(),
+            PlonkOracle::CONSTANTS_SIGMAS,
+        )
+    }
+
+    /// Layout of the wires commitment of the given circuit.
+    pub fn wires<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+        offset: usize,
+    ) -> Self {
+        Self::for_oracle(
+            common_data,
+            offset,
+            common_data.config.num_wires,
+            PlonkOracle::WIRES,
+        )
+    }
+
+    /// Layout of the `Z`s and partial products commitment of the given circuit.
+    pub fn zs_partial_products<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+        offset: usize,
+    ) -> Self {
+        Self::for_oracle(
+            common_data,
+            offset,
+            common_data.num_zs_partial_products_polys(),
+            PlonkOracle::ZS_PARTIAL_PRODUCTS,
+        )
+    }
+
+    /// Layout of the quotient polynomials commitment of the given circuit.
+    pub fn quotient_polys<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+        offset: usize,
+    ) -> Self {
+        Self::for_oracle(
+            common_data,
+            offset,
+            common_data.num_quotient_polys(),
+            PlonkOracle::QUOTIENT,
+        )
+    }
+
+    fn for_oracle<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+        offset: usize,
+        poly_num: usize,
+        oracle: PlonkOracle,
+    ) -> Self {
+        let config = &common_data.config;
+        Self {
+            offset,
+            poly_num,
+            values_num_per_poly: common_data.degree(),
+            rate_bits: config.fri_config.rate_bits,
+            salt_size: salt_size(config.zero_knowledge && oracle.blinding),
+            cap_height: config.fri_config.cap_height,
+        }
+    }
+
+    pub fn values_len(&self) -> usize {
+        self.poly_num * self.values_num_per_poly
+    }
+
+    pub fn lde_size(&self) -> usize {
+        self.values_num_per_poly << self.rate_bits
+    }
+
+    pub fn leaf_len(&self) -> usize {
+        self.poly_num + self.salt_size
+    }
+
+    pub fn ext_values_len(&self) -> usize {
+        self.leaf_len() * self.lde_size()
+    }
+
+    /// Where the salts of the leaves start: `lde_size` words per salt, the `i`th word of each
+    /// going into the `i`th leaf.
+    pub fn salts_offset(&self) -> usize {
+        self.offset + self.ext_values_len() + self.poly_num * self.lde_size()
+    }
+
+    pub fn len_cap(&self) -> usize {
+        1 << self.cap_height
+    }
+
+    pub fn num_digests(&self) -> usize {
+        2 * (self.lde_size() - self.len_cap())
+    }
+
+    pub fn digests_offset(&self) -> usize {
+        self.offset + 2 * self.ext_values_len()
+    }
+
+    /// One past the last word used by this commitment.
+    pub fn end(&self) -> usize {
+        self.digests_offset() + (self.num_digests() + self.len_cap()) * HASH_WORDS
+    }
+}
+
+/// Layouts of the commitments computed by `my_prove`. The wires are committed at the start of the
+/// memory, the `Z`s and partial products at the second stage offset, and the quotient
+/// polynomials right after the leaves of the latter, overwriting their (no longer needed) LDEs.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ProverLayout {
+    pub wires: CommitmentLayout,
+    pub zs_partial_products: CommitmentLayout,
+    pub quotient_polys: CommitmentLayout,
+}
+
+impl ProverLayout {
+    pub fn new<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+        second_stage_offset: usize,
+    ) -> Self {
+        let wires = CommitmentLayout::wires(common_data, 0);
+        assert!(
+            second_stage_offset >= wires.ext_values_len(),
+            "The second stage would overwrite the wires leaves"
+        );
+        let zs_partial_products =
+            CommitmentLayout::zs_partial_products(common_data, second_stage_offset);
+        let quotient_polys = CommitmentLayout::quotient_polys(
+            common_data,
+            second_stage_offset + zs_partial_products.ext_values_len(),
+        );
+        Self {
+            wires,
+            zs_partial_products,
+            quotient_polys,
+        }
+    }
+
+    /// The smallest second stage offset which keeps the whole wires commitment intact.
+    pub fn min_second_stage_offset<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+    ) -> usize {
+        CommitmentLayout::wires(common_data, 0).end()
+    }
+
+    /// One past the last word used by `my_prove`.
+    pub fn end(&self) -> usize {
+        self.zs_partial_products
+            .end()
+            .max(self.quotient_polys.end())
+    }
+}
+
+/// Inputs of the quotient polynomials computation. All commitments must already be built.
+pub struct QuotientPolysArgs<'a, F: Field> {
+    pub wires: CommitmentLayout,
+    pub zs_partial_products: CommitmentLayout,
+    /// Where to write the coefficients of the quotient polynomials, one polynomial of
+    /// `lde_size` coefficients per challenge.
+    pub quotient_polys_offset: usize,
+    pub public_inputs_hash: &'a HashOut<F>,
+    pub alphas: &'a [F],
+    pub betas: &'a [F],
+    pub gammas: &'a [F],
+    /// The gates whose constraints the backend must evaluate.
+    pub gates: &'a [GpuGate],
+    /// The contribution of the other gates of the circuit to the quotient polynomials, evaluated
+    /// on the CPU: the `num_challenges` values of each point of the LDE, one point after the other.
+    pub fallback_quotient_values: Option<&'a [F]>,
+}
+
+/// The operations `my_prove` needs from a device. Buffers are addressed by word offset into the
+/// backend's memory, see [`CommitmentLayout`] for how commitments are laid out.
+pub trait ProverBackend<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    /// Size of the backend memory, in words.
+    fn memory_len(&self) -> usize;
+
+    /// Offset of the second stage (`Z`s and partial products) commitment.
+    fn second_stage_offset(&self) -> usize;
+
+    fn write_words(&mut self, offset: usize, words: &[u64]);
+
+    fn read_words(&mut self, offset: usize, words: &mut [u64]);
+
+    /// Interpolates, in place, `poly_num` polynomials of `values_num_per_poly` values each.
+    fn ifft(&mut self, offset: usize, poly_num: usize, values_num_per_poly: usize) -> Result<()>;
+
+    /// Computes the LDEs of the polynomials whose coefficients are stored at `layout.offset`, and
+    /// builds their Merkle tree. The leaves end with the salts found at `layout.salts_offset()`.
+    fn merkle_tree_from_coeffs(&mut self, layout: &CommitmentLayout) -> Result<()>;
+
+    /// Like `merkle_tree_from_coeffs`, but starting from the values of the polynomials. The
+    /// coefficients are left at `layout.offset`, before being overwritten by the leaves.
+    fn merkle_tree_from_values(&mut self, layout: &CommitmentLayout) -> Result<()> {
+        self.ifft(layout.offset, layout.poly_num, layout.values_num_per_poly)?;
+        self.merkle_tree_from_coeffs(layout)
+    }
+
+    /// The gates `compute_quotient_polys` can evaluate. The constraints of the other gates of the
+    /// circuit are evaluated on the CPU. Defaults to the gates of the CUDA kernel.
+    fn gate_registry(&self) -> GateRegistry {
+        GateRegistry::gpu()
+    }
+
+    /// Computes the coefficients of the quotient polynomials. Fails if the device rejects the
+    /// circuit, e.g. if the quotient kernel doesn't support its shape.
+    fn compute_quotient_polys(&mut self, args: &QuotientPolysArgs<F>) -> Result<()>;
+
+    /// Where `my_prove` takes the salts of hiding commitments from.
+    fn salt_source(&self) -> SaltSource<F> {
+        SaltSource::Random
+    }
+
+    /// The buffers the host side of `my_prove` borrows, kept from one proof to the next. Without
+    /// them, every buffer is allocated afresh.
+    fn host_buffers(&self) -> Option<&HostBuffers<F>> {
+        None
+    }
+
+    /// Makes the backend usable from the calling thread, e.g. by making its device context
+    /// current. It is called before proving on a thread other than the one which created it.
+    fn bind_to_current_thread(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'b, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    dyn ProverBackend<F, C, D> + 'b
+{
+    /// A buffer of `len` words to stage a copy to or from the backend memory.
+    pub fn staging_words(&self, len: usize) -> PooledBuffer<u64> {
+        PooledBuffer::take_from(self.host_buffers().map(|b| &b.words), len)
+    }
+
+    /// A buffer of `len` field elements for values computed on the host.
+    pub fn host_values(&self, len: usize) -> PooledBuffer<F> {
+        PooledBuffer::take_from(self.host_buffers().map(|b| &b.values), len)
+    }
+
+    pub fn write_values(&mut self, offset: usize, values: &[F]) {
+        let mut words = self.staging_words(values.len());
+        for (w, v) in words.iter_mut().zip(values) {
+            *w = v.to_canonical_u64();
+        }
+        self.write_words(offset, &words);
+    }
+
+    pub fn read_values(&mut self, offset: usize, len: usize) -> Vec<F> {
+        let mut words = self.staging_words(len);
+        self.read_words(offset, &mut words);
+        words.iter().map(|&w| F::from_noncanonical_u64(w)).collect()
+    }
+
+    /// Reads the digests and the cap of the Merkle tree described by `layout`.
+    #[allow(clippy::type_complexity)]
+    pub fn read_digests_and_cap(
+        &mut self,
+        layout: &CommitmentLayout,
+    ) -> (
+        Vec<<C::Hasher as Hasher<F>>::Hash>,
+        Vec<<C::Hasher as Hasher<F>>::Hash>,
+    ) {
+        let num_digests = layout.num_digests();
+        let mut words = self.staging_words((num_digests + layout.len_cap()) * HASH_WORDS);
+        self.read_words(layout.digests_offset(), &mut words);
+        let mut hashes = words_to_hashes::<F, C::Hasher>(&words);
+        let cap = hashes.split_off(num_digests);
+        (hashes, cap)
+    }
+}
+
+/// Returns a function fetching the first `num_polys` values of the leaf holding the
+/// `index * step`th LDE point, given the leaves of a commitment as laid out in backend memory.
+pub(crate) fn lde_values_fn<'a, F: Field>(
+    leaves: &'a [F],
+    leaf_len: usize,
+    num_polys: usize,
+    lde_bits: usize,
+) -> impl Fn(usize, usize) -> &'a [F] + Sync + 'a {
+    move |index, step| {
+        let index = reverse_bits(index * step, lde_bits);
+        &leaves[index * leaf_len..index * leaf_len + num_polys]
+    }
+}
+
+/// Encodes digests as words, `HASH_WORDS` words per digest.
+pub fn hashes_to_words<F: RichField, H: Hasher<F>>(hashes: &[H::Hash]) -> Vec<u64> {
+    debug_assert!(H::HASH_SIZE <= HASH_WORDS * 8);
+    hashes
+        .iter()
+        .flat_map(|h| {
+            let mut bytes = h.to_bytes();
+            bytes.resize(HASH_WORDS * 8, 0);
+            bytes
+                .chunks(8)
+                .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Inverse of `hashes_to_words`.
+pub fn words_to_hashes<F: RichField, H: Hasher<F>>(words: &[u64]) -> Vec<H::Hash> {
+    words
+        .chunks(HASH_WORDS)
+        .map(|chunk| {
+            let bytes = chunk
+                .iter()
+                .flat_map(|w| w.to_le_bytes())
+                .collect::<Vec<_>>();
+            H::Hash::from_bytes(&bytes[..H::HASH_SIZE])
+        })
+        .collect()
+}
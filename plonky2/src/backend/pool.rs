@@ -0,0 +1,509 @@
+//! Scheduling of many proofs of the same circuit on several devices.
+//!
+//! Within a proof, each commitment depends on the caps of the previous ones through the
+//! challenges, so a proof runs on a single backend from start to end; it is the unit of work
+//! which the pool spreads across devices and their streams. Each (device, stream) slot owns a
+//! backend, created the first time the slot is used and reused, along with its memory, for all the
+//! following proofs of the same circuit. The slots take the proofs from a shared queue, so that
+//! faster devices prove more of them, and a slot whose backend can't be created leaves its proofs
+//! to the other slots.
+//!
+//! The pool doesn't shard the commitments of one proof across devices: only whole proofs are
+//! pipelined, which speeds up batches of proofs but not a single one. Sharding the polynomials of
+//! a batch would need the LDEs of all its columns on one device to hash the Merkle leaves, which
+//! are rows, so it is left out of the pool, for a backend spanning several devices.
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::vec::Vec;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+#[cfg(feature = "cuda")]
+use rustacuda::memory::DeviceCopy;
+
+use crate::backend::cpu::CpuBackend;
+use crate::backend::ProverBackend;
+use crate::field::extension::Extendable;
+#[cfg(feature = "cuda")]
+use crate::fri::oracle::CudaInvContext;
+use crate::hash::hash_types::RichField;
+use crate::iop::witness::PartialWitness;
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::proof::ProofWithPublicInputs;
+use crate::plonk::prover::my_prove;
+use crate::util::timing::TimingTree;
+
+type CircuitDigest<F, C, const D: usize> = <<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash;
+
+/// A backend of a slot, which its thread can be moved to.
+pub type SlotBackend<F, C, const D: usize> = Box<dyn ProverBackend<F, C, D> + Send>;
+
+/// The witnesses left to prove, along with the indices of their proofs.
+type ProofQueue<F> = Mutex<VecDeque<(usize, PartialWitness<F>)>>;
+
+/// Proofs along with their indices.
+type IndexedProofs<F, C, const D: usize> = Vec<(usize, Result<ProofWithPublicInputs<F, C, D>>)>;
+
+/// A device able to run several proofs concurrently, one per stream.
+pub trait Device<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>:
+    Send + Sync
+{
+    fn num_streams(&self) -> usize;
+
+    /// Creates the backend proving on `stream`. It is called on the thread which then uses the
+    /// backend, so that devices can bind their context to it.
+    fn create_backend(
+        &self,
+        stream: usize,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<SlotBackend<F, C, D>>;
+}
+
+/// A device running its streams on the CPU, with a [`CpuBackend`] each.
+#[derive(Copy, Clone, Debug)]
+pub struct CpuDevice {
+    pub num_streams: usize,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F> + 'static, const D: usize>
+    Device<F, C, D> for CpuDevice
+{
+    fn num_streams(&self) -> usize {
+        self.num_streams
+    }
+
+    fn create_backend(
+        &self,
+        _stream: usize,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<SlotBackend<F, C, D>> {
+        Ok(Box::new(CpuBackend::new(prover_data, common_data)))
+    }
+}
+
+/// A CUDA device, each stream of which proves with a [`CudaInvContext`] of its own.
+#[cfg(feature = "cuda")]
+#[derive(Copy, Clone, Debug)]
+pub struct CudaDevice {
+    device: rustacuda::device::Device,
+    num_streams: usize,
+}
+
+#[cfg(feature = "cuda")]
+impl CudaDevice {
+    /// The CUDA device of the given ordinal, initializing the driver.
+    pub fn new(ordinal: u32, num_streams: usize) -> Result<Self> {
+        rustacuda::init(rustacuda::CudaFlags::empty())?;
+        let device = rustacuda::device::Device::get_device(ordinal)?;
+        Ok(Self {
+            device,
+            num_streams,
+        })
+    }
+}
+
+#[cfg(feature = "cuda")]
+impl<
+        F: RichField + Extendable<D> + DeviceCopy,
+        C: GenericConfig<D, F = F> + 'static,
+        const D: usize,
+    > Device<F, C, D> for CudaDevice
+{
+    fn num_streams(&self) -> usize {
+        self.num_streams
+    }
+
+    fn create_backend(
+        &self,
+        _stream: usize,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<SlotBackend<F, C, D>> {
+        let context = CudaInvContext::for_circuit(self.device, prover_data, common_data)?;
+        Ok(Box::new(context))
+    }
+}
+
+/// A (device, stream) pair, and the backend it last used.
+struct Slot<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    device: usize,
+    stream: usize,
+    backend: Option<(CircuitDigest<F, C, D>, SlotBackend<F, C, D>)>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> Slot<F, C, D> {
+    /// The backend of this slot for the given circuit, created if the slot hasn't proven it yet,
+    /// and bound to the calling thread.
+    fn backend(
+        &mut self,
+        device: &dyn Device<F, C, D>,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<&mut dyn ProverBackend<F, C, D>> {
+        let digest = prover_data.circuit_digest;
+        if !matches!(&self.backend, Some((d, _)) if *d == digest) {
+            // Drop the previous backend first, to free its device memory.
+            self.backend = None;
+            let backend = device.create_backend(self.stream, prover_data, common_data)?;
+            self.backend = Some((digest, backend));
+        }
+        let backend = self.backend.as_mut().unwrap().1.as_mut();
+        backend.bind_to_current_thread()?;
+        Ok(backend)
+    }
+
+    /// Proves the proofs of `queue` until it is empty, returning them along with their indices.
+    /// If the backend of the slot can't be created or bound, the proof taken from the queue is
+    /// put back, and the error is returned along with the proofs already proven.
+    fn prove_queued(
+        &mut self,
+        device: &dyn Device<F, C, D>,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+        queue: &ProofQueue<F>,
+    ) -> (IndexedProofs<F, C, D>, Option<anyhow::Error>) {
+        let mut proofs = Vec::new();
+        loop {
+            // The queue is unlocked before proving.
+            let Some((i, inputs)) = queue.lock().unwrap().pop_front() else {
+                break;
+            };
+            let backend = match self.backend(device, prover_data, common_data) {
+                Ok(backend) => backend,
+                Err(e) => {
+                    queue.lock().unwrap().push_front((i, inputs));
+                    let msg = format!("device {}, stream {}", self.device, self.stream);
+                    return (proofs, Some(e.context(msg)));
+                }
+            };
+            let mut timing = TimingTree::default();
+            let proof = my_prove(prover_data, common_data, inputs, &mut timing, backend);
+            proofs.push((i, proof));
+        }
+        (proofs, None)
+    }
+}
+
+/// Devices proving batches of proofs, one proof per stream at a time.
+pub struct DevicePool<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    devices: Vec<Box<dyn Device<F, C, D>>>,
+    /// The slots, interleaving the devices: the first stream of each device, then the second
+    /// stream of each device, etc.
+    slots: Vec<Slot<F, C, D>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> DevicePool<F, C, D> {
+    pub fn new(devices: Vec<Box<dyn Device<F, C, D>>>) -> Self {
+        let max_streams = devices.iter().map(|d| d.num_streams()).max().unwrap_or(0);
+        let slots = (0..max_streams)
+            .flat_map(|stream| {
+                devices
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, d)| stream < d.num_streams())
+                    .map(move |(device, _)| Slot {
+                        device,
+                        stream,
+                        backend: None,
+                    })
+            })
+            .collect::<Vec<_>>();
+        assert!(!slots.is_empty(), "The pool has no stream to prove on");
+        Self { devices, slots }
+    }
+
+    /// The (device, stream) pairs of the pool.
+    pub fn slots(&self) -> Vec<(usize, usize)> {
+        self.slots.iter().map(|s| (s.device, s.stream)).collect()
+    }
+
+    /// Proves a witness of the given circuit for each of `inputs`, running one proof per slot
+    /// concurrently. The results are in the order of `inputs`.
+    ///
+    /// The slots take the proofs from a shared queue, in order. A slot whose backend can't be
+    /// created or bound to its thread puts its proof back in the queue and stops, so that the
+    /// other slots prove it; the proofs are only failed with the errors of the slots if every
+    /// slot failed. Errors of a proof itself, e.g. an unsatisfied witness, are not retried.
+    pub fn prove_all(
+        &mut self,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+        inputs: Vec<PartialWitness<F>>,
+    ) -> Vec<Result<ProofWithPublicInputs<F, C, D>>> {
+        let num_proofs = inputs.len();
+        let queue = Mutex::new(inputs.into_iter().enumerate().collect::<VecDeque<_>>());
+        let mut results = (0..num_proofs).map(|_| None).collect::<Vec<_>>();
+        let mut slot_errors = (0..self.slots.len()).map(|_| None).collect::<Vec<_>>();
+
+        // A slot may fail after the others have emptied the queue and stopped, so the healthy
+        // slots are run again until the queue is empty.
+        while !queue.lock().unwrap().is_empty() && slot_errors.iter().any(Option::is_none) {
+            let devices = &self.devices;
+            let queue = &queue;
+            std::thread::scope(|scope| {
+                let handles = self
+                    .slots
+                    .iter_mut()
+                    .zip(&slot_errors)
+                    .enumerate()
+                    .filter(|(_, (_, error))| error.is_none())
+                    .map(|(slot_index, (slot, _))| {
+                        let device = devices[slot.device].as_ref();
+                        scope.spawn(move || {
+                            let (proofs, error) =
+                                slot.prove_queued(device, prover_data, common_data, queue);
+                            (slot_index, proofs, error)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                for handle in handles {
+                    let (slot_index, proofs, error) =
+                        handle.join().expect("A prover thread panicked");
+                    for (i, proof) in proofs {
+                        results[i] = Some(proof);
+                    }
+                    slot_errors[slot_index] = error;
+                }
+            });
+        }
+
+        // Every slot failed: the remaining proofs fail with the errors of all the slots.
+        let slot_errors = slot_errors
+            .into_iter()
+            .flatten()
+            .map(|e| format!("{e:#}"))
+            .collect::<Vec<_>>();
+        for (i, _) in queue.into_inner().unwrap() {
+            results[i] = Some(Err(anyhow!(
+                "No slot of the pool could prove it: {}",
+                slot_errors.join("; ")
+            )));
+        }
+        results.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::backend::{CommitmentLayout, QuotientPolysArgs};
+    use crate::field::types::Sample;
+    use crate::iop::witness::WitnessWrite;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// The (device, stream) of each backend creation and quotient computation.
+    #[derive(Default)]
+    struct Log {
+        created: Vec<(usize, usize)>,
+        quotients: Vec<(usize, usize)>,
+    }
+
+    struct MockDevice {
+        id: usize,
+        num_streams: usize,
+        log: Arc<Mutex<Log>>,
+    }
+
+    struct MockBackend {
+        slot: (usize, usize),
+        inner: CpuBackend<F, C, D>,
+        log: Arc<Mutex<Log>>,
+    }
+
+    impl Device<F, C, D> for MockDevice {
+        fn num_streams(&self) -> usize {
+            self.num_streams
+        }
+
+        fn create_backend(
+            &self,
+            stream: usize,
+            prover_data: &ProverOnlyCircuitData<F, C, D>,
+            common_data: &CommonCircuitData<F, D>,
+        ) -> Result<SlotBackend<F, C, D>> {
+            self.log.lock().unwrap().created.push((self.id, stream));
+            Ok(Box::new(MockBackend {
+                slot: (self.id, stream),
+                inner: CpuBackend::new(prover_data, common_data),
+                log: self.log.clone(),
+            }))
+        }
+    }
+
+    impl ProverBackend<F, C, D> for MockBackend {
+        fn memory_len(&self) -> usize {
+            self.inner.memory_len()
+        }
+
+        fn second_stage_offset(&self) -> usize {
+            self.inner.second_stage_offset()
+        }
+
+        fn write_words(&mut self, offset: usize, words: &[u64]) {
+            self.inner.write_words(offset, words)
+        }
+
+        fn read_words(&mut self, offset: usize, words: &mut [u64]) {
+            self.inner.read_words(offset, words)
+        }
+
+        fn ifft(
+            &mut self,
+            offset: usize,
+            poly_num: usize,
+            values_num_per_poly: usize,
+        ) -> Result<()> {
+            self.inner.ifft(offset, poly_num, values_num_per_poly)
+        }
+
+        fn merkle_tree_from_coeffs(&mut self, layout: &CommitmentLayout) -> Result<()> {
+            self.inner.merkle_tree_from_coeffs(layout)
+        }
+
+        fn compute_quotient_polys(&mut self, args: &QuotientPolysArgs<F>) -> Result<()> {
+            self.log.lock().unwrap().quotients.push(self.slot);
+            self.inner.compute_quotient_polys(args)
+        }
+    }
+
+    #[test]
+    fn test_slots() {
+        let log = Arc::new(Mutex::new(Log::default()));
+        let device = |id, num_streams| -> Box<dyn Device<F, C, D>> {
+            Box::new(MockDevice {
+                id,
+                num_streams,
+                log: log.clone(),
+            })
+        };
+        let pool = DevicePool::new(vec![device(0, 2), device(1, 1)]);
+        assert_eq!(pool.slots(), vec![(0, 0), (1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_prove_all_reuses_backends() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let xy = builder.mul(x, y);
+        builder.register_public_input(xy);
+        let data = builder.build::<C>();
+
+        let log = Arc::new(Mutex::new(Log::default()));
+        let device = |id, num_streams| -> Box<dyn Device<F, C, D>> {
+            Box::new(MockDevice {
+                id,
+                num_streams,
+                log: log.clone(),
+            })
+        };
+        let mut pool = DevicePool::new(vec![device(0, 2), device(1, 1)]);
+
+        let witnesses = |n| {
+            (0..n)
+                .map(|_| {
+                    let (x_value, y_value) = (F::rand(), F::rand());
+                    let mut pw = PartialWitness::new();
+                    pw.set_target(x, x_value);
+                    pw.set_target(y, y_value);
+                    (pw, x_value * y_value)
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>()
+        };
+        for _ in 0..2 {
+            let (inputs, products) = witnesses(4);
+            let proofs = pool.prove_all(&data.prover_only, &data.common, inputs);
+            // The unused public input wires are random, so the proofs can't be compared with
+            // `prove`'s; they are checked to be valid proofs of the right witnesses instead.
+            for (proof, product) in proofs.into_iter().zip(products) {
+                let proof = proof?;
+                assert_eq!(proof.public_inputs, vec![product]);
+                data.verify(proof)?;
+            }
+        }
+
+        let log = log.lock().unwrap();
+        // At most one backend per slot, kept across batches.
+        let mut created = log.created.clone();
+        created.sort();
+        created.dedup();
+        assert_eq!(created.len(), log.created.len());
+        // Each proof ran on a slot which created its backend.
+        assert_eq!(log.quotients.len(), 8);
+        assert!(log.quotients.iter().all(|slot| created.contains(slot)));
+        Ok(())
+    }
+
+    /// A device whose backends cannot be created, e.g. for lack of memory.
+    struct FailingDevice;
+
+    impl Device<F, C, D> for FailingDevice {
+        fn num_streams(&self) -> usize {
+            1
+        }
+
+        fn create_backend(
+            &self,
+            _stream: usize,
+            _prover_data: &ProverOnlyCircuitData<F, C, D>,
+            _common_data: &CommonCircuitData<F, D>,
+        ) -> Result<SlotBackend<F, C, D>> {
+            Err(anyhow!("out of memory"))
+        }
+    }
+
+    #[test]
+    fn test_prove_all_requeues_failed_slots() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let x2 = builder.mul(x, x);
+        builder.register_public_input(x2);
+        let data = builder.build::<C>();
+        let inputs = || {
+            (0..4)
+                .map(|_| {
+                    let mut pw = PartialWitness::new();
+                    pw.set_target(x, F::rand());
+                    pw
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The proofs taken by the failing device are proven by the other one.
+        let devices: Vec<Box<dyn Device<F, C, D>>> = vec![
+            Box::new(FailingDevice),
+            Box::new(CpuDevice { num_streams: 1 }),
+            Box::new(FailingDevice),
+        ];
+        let mut pool = DevicePool::new(devices);
+        for proof in pool.prove_all(&data.prover_only, &data.common, inputs()) {
+            data.verify(proof?)?;
+        }
+
+        // Without any working slot, every proof fails with the errors of the slots.
+        let devices: Vec<Box<dyn Device<F, C, D>>> =
+            vec![Box::new(FailingDevice), Box::new(FailingDevice)];
+        let mut pool = DevicePool::new(devices);
+        for proof in pool.prove_all(&data.prover_only, &data.common, inputs()) {
+            let err = format!("{:#}", proof.unwrap_err());
+            assert!(err.contains("device 0, stream 0: out of memory"));
+            assert!(err.contains("device 1, stream 0: out of memory"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+//! The circuit shapes supported by the CUDA quotient kernel.
+//!
+//! `compute_quotient_values_kernel` keeps its per-point accumulators in fixed-size arrays, and
+//! indexes the commitments with 32-bit integers. [`QuotientKernelShape::new`] checks a circuit
+//! against these limits on the host, so that an unsupported circuit is rejected with an error
+//! instead of tripping a device-side `assert`.
+
+use core::fmt::{Display, Formatter};
+
+use crate::backend::CommitmentLayout;
+use crate::field::extension::Extendable;
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::Field64;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_data::CommonCircuitData;
+
+/// Largest `num_challenges` supported by the kernel.
+pub const MAX_NUM_CHALLENGES: usize = 4;
+
+/// Largest `num_gate_constraints` supported by the kernel.
+pub const MAX_NUM_GATE_CONSTRAINTS: usize = 256;
+
+/// Largest number of partial product checks per challenge, i.e.
+/// `num_routed_wires / quotient_degree_factor`, supported by the kernel.
+pub const MAX_PARTIAL_PRODUCT_ROUNDS: usize = 32;
+
+/// Why a circuit cannot be handled by the quotient kernel.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UnsupportedShapeError {
+    /// The kernel only implements Goldilocks arithmetic.
+    NotGoldilocks,
+    TooManyChallenges {
+        num_challenges: usize,
+    },
+    TooManyGateConstraints {
+        num_gate_constraints: usize,
+    },
+    /// The kernel evaluates the vanishing polynomial on the whole LDE, so the quotient degree
+    /// factor must be the blowup factor.
+    QuotientDegreeFactor {
+        quotient_degree_factor: usize,
+        rate_bits: usize,
+    },
+    /// The kernel expects every partial product to cover `quotient_degree_factor` routed wires.
+    RoutedWires {
+        num_routed_wires: usize,
+        quotient_degree_factor: usize,
+    },
+    TooManyPartialProducts {
+        num_partial_products: usize,
+    },
+    /// A commitment has too many values to be indexed with an `i32`.
+    TooLarge {
+        num_values: usize,
+    },
+}
+
+impl Display for UnsupportedShapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::NotGoldilocks => write!(f, "the quotient kernel only supports Goldilocks"),
+            Self::TooManyChallenges { num_challenges } => write!(
+                f,
+                "{num_challenges} challenges, the quotient kernel supports at most {MAX_NUM_CHALLENGES}"
+            ),
+            Self::TooManyGateConstraints {
+                num_gate_constraints,
+            } => write!(
+                f,
+                "{num_gate_constraints} gate constraints, the quotient kernel supports at most \
+                 {MAX_NUM_GATE_CONSTRAINTS}"
+            ),
+            Self::QuotientDegreeFactor {
+                quotient_degree_factor,
+                rate_bits,
+            } => write!(
+                f,
+                "quotient degree factor {quotient_degree_factor} differs from the blowup factor \
+                 2^{rate_bits}"
+            ),
+            Self::RoutedWires {
+                num_routed_wires,
+                quotient_degree_factor,
+            } => write!(
+                f,
+                "{num_routed_wires} routed wires is not a multiple of the quotient degree factor \
+                 {quotient_degree_factor}"
+            ),
+            Self::TooManyPartialProducts {
+                num_partial_products,
+            } => write!(
+                f,
+                "{num_partial_products} partial products, the quotient kernel supports at most {}",
+                MAX_PARTIAL_PRODUCT_ROUNDS - 1
+            ),
+            Self::TooLarge { num_values } => {
+                write!(f, "a commitment of {num_values} values is too large for the GPU")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsupportedShapeError {}
+
+/// The shape of a circuit, as passed to the quotient kernel. It can only be built by
+/// [`QuotientKernelShape::new`], which checks that the kernel supports it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct QuotientKernelShape {
+    pub(crate) num_challenges: usize,
+    pub(crate) num_gate_constraints: usize,
+    pub(crate) num_constants: usize,
+    pub(crate) num_selectors: usize,
+    pub(crate) num_routed_wires: usize,
+    pub(crate) quotient_degree_factor: usize,
+    pub(crate) num_partial_products: usize,
+    pub(crate) constants_sigmas_leaf_len: usize,
+    pub(crate) zs_partial_products_leaf_len: usize,
+    pub(crate) wires_leaf_len: usize,
+}
+
+impl QuotientKernelShape {
+    pub fn new<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<Self, UnsupportedShapeError> {
+        let config = &common_data.config;
+        let rate_bits = config.fri_config.rate_bits;
+        let quotient_degree_factor = common_data.quotient_degree_factor;
+
+        if F::ORDER != GoldilocksField::ORDER {
+            return Err(UnsupportedShapeError::NotGoldilocks);
+        }
+        if config.num_challenges > MAX_NUM_CHALLENGES {
+            return Err(UnsupportedShapeError::TooManyChallenges {
+                num_challenges: config.num_challenges,
+            });
+        }
+        if common_data.num_gate_constraints > MAX_NUM_GATE_CONSTRAINTS {
+            return Err(UnsupportedShapeError::TooManyGateConstraints {
+                num_gate_constraints: common_data.num_gate_constraints,
+            });
+        }
+        if quotient_degree_factor != 1 << rate_bits {
+            return Err(UnsupportedShapeError::QuotientDegreeFactor {
+                quotient_degree_factor,
+                rate_bits,
+            });
+        }
+        if !config.num_routed_wires.is_multiple_of(quotient_degree_factor) {
+            return Err(UnsupportedShapeError::RoutedWires {
+                num_routed_wires: config.num_routed_wires,
+                quotient_degree_factor,
+            });
+        }
+        if common_data.num_partial_products >= MAX_PARTIAL_PRODUCT_ROUNDS {
+            return Err(UnsupportedShapeError::TooManyPartialProducts {
+                num_partial_products: common_data.num_partial_products,
+            });
+        }
+
+        let constants_sigmas = CommitmentLayout::constants_sigmas(common_data, 0);
+        let zs_partial_products = CommitmentLayout::zs_partial_products(common_data, 0);
+        let wires = CommitmentLayout::wires(common_data, 0);
+        for layout in [constants_sigmas, zs_partial_products, wires] {
+            let num_values = layout.ext_values_len();
+            if num_values > i32::MAX as usize {
+                return Err(UnsupportedShapeError::TooLarge { num_values });
+            }
+        }
+
+        Ok(Self {
+            num_challenges: config.num_challenges,
+            num_gate_constraints: common_data.num_gate_constraints,
+            num_constants: common_data.num_constants,
+            num_selectors: common_data.selectors_info.num_selectors(),
+            num_routed_wires: config.num_routed_wires,
+            quotient_degree_factor,
+            num_partial_products: common_data.num_partial_products,
+            constants_sigmas_leaf_len: constants_sigmas.leaf_len(),
+            zs_partial_products_leaf_len: zs_partial_products.leaf_len(),
+            wires_leaf_len: wires.leaf_len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn common_data(config: CircuitConfig) -> CommonCircuitData<F, D> {
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        builder.build::<C>().common
+    }
+
+    #[test]
+    fn test_standard_configs_are_supported() {
+        for config in [
+            CircuitConfig::standard_recursion_config(),
+            CircuitConfig::standard_recursion_zk_config(),
+            CircuitConfig::standard_ecc_config(),
+            CircuitConfig::wide_ecc_config(),
+        ] {
+            let common_data = common_data(config.clone());
+            let shape = QuotientKernelShape::new(&common_data).unwrap();
+            assert_eq!(shape.num_challenges, config.num_challenges);
+            assert_eq!(shape.num_routed_wires, config.num_routed_wires);
+            assert_eq!(
+                shape.wires_leaf_len,
+                CommitmentLayout::wires(&common_data, 0).leaf_len()
+            );
+            assert_eq!(
+                shape.constants_sigmas_leaf_len,
+                common_data.num_preprocessed_polys()
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_many_challenges() {
+        let config = CircuitConfig {
+            num_challenges: MAX_NUM_CHALLENGES + 1,
+            ..CircuitConfig::standard_recursion_config()
+        };
+        assert_eq!(
+            QuotientKernelShape::new(&common_data(config)),
+            Err(UnsupportedShapeError::TooManyChallenges {
+                num_challenges: MAX_NUM_CHALLENGES + 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_quotient_degree_factor_must_match_rate() {
+        let mut common_data = common_data(CircuitConfig::standard_recursion_config());
+        common_data.quotient_degree_factor = 4;
+        assert_eq!(
+            QuotientKernelShape::new(&common_data),
+            Err(UnsupportedShapeError::QuotientDegreeFactor {
+                quotient_degree_factor: 4,
+                rate_bits: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_routed_wires_must_fill_partial_products() {
+        let config = CircuitConfig {
+            num_routed_wires: 84,
+            ..CircuitConfig::standard_recursion_config()
+        };
+        assert_eq!(
+            QuotientKernelShape::new(&common_data(config)),
+            Err(UnsupportedShapeError::RoutedWires {
+                num_routed_wires: 84,
+                quotient_degree_factor: 8
+            })
+        );
+    }
+
+    #[test]
+    fn test_too_many_gate_constraints() {
+        let mut common_data = common_data(CircuitConfig::standard_recursion_config());
+        common_data.num_gate_constraints = MAX_NUM_GATE_CONSTRAINTS + 1;
+        assert_eq!(
+            QuotientKernelShape::new(&common_data),
+            Err(UnsupportedShapeError::TooManyGateConstraints {
+                num_gate_constraints: MAX_NUM_GATE_CONSTRAINTS + 1
+            })
+        );
+    }
+}
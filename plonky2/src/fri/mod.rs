@@ -3,6 +3,7 @@ use alloc::vec::Vec;
 use crate::fri::reduction_strategies::FriReductionStrategy;
 
 mod challenges;
+pub mod optimizer;
 pub mod oracle;
 pub mod proof;
 pub mod prover;
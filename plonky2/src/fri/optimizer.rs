@@ -0,0 +1,288 @@
+//! Searches for the `FriConfig` of a circuit which reaches a target security at the smallest cost,
+//! for a given notion of cost: proof size, prover time or recursive verification.
+//!
+//! The costs are estimates, good for comparing configurations rather than predicting the actual
+//! proof size or prover time. The security is the conjectured FRI security, as checked by
+//! `CircuitBuilder::new`: `num_query_rounds * rate_bits + proof_of_work_bits`.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::fri::reduction_strategies::{
+    min_cost_arity_bits, relative_proof_size, FriReductionStrategy,
+};
+use crate::fri::FriConfig;
+use crate::hash::hashing::SPONGE_RATE;
+use crate::util::ceil_div_usize;
+
+/// The number of field multiplications which cost about as much as a Poseidon permutation, used to
+/// compare FFTs with hashing.
+const MULS_PER_PERMUTATION: usize = 200;
+
+/// The number of extension field operations in an `ArithmeticExtensionGate`.
+const OPS_PER_ARITHMETIC_GATE: usize = 10;
+
+/// What a `FriConfig` is optimized for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FriObjective {
+    /// The size of the proof, in field elements.
+    ProofSize,
+    /// The time spent by the prover on LDEs, Merkle trees, FRI folding and the proof of work, in
+    /// Poseidon permutations.
+    ProverTime,
+    /// The number of gates of a circuit verifying the proof recursively.
+    RecursiveVerifierGates,
+}
+
+/// The parameters of the search, and the shape of the proven circuit.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FriOptimizer {
+    pub degree_bits: usize,
+    pub security_bits: usize,
+    pub objective: FriObjective,
+    /// The number of Merkle trees committed before FRI starts, which are opened at each query.
+    pub num_initial_trees: usize,
+    /// The total number of polynomials in those trees, i.e. the number of field elements opened
+    /// in the initial trees at each query.
+    pub num_initial_polys: usize,
+    pub extension_degree: usize,
+    /// The smallest `rate_bits` to search. It must be at least `log2(quotient_degree_factor)`. A
+    /// rate of `0` gives no security, so the search starts at `1` at least.
+    pub min_rate_bits: usize,
+    pub max_rate_bits: usize,
+    pub max_cap_height: usize,
+    pub max_proof_of_work_bits: u32,
+    pub max_arity_bits: usize,
+}
+
+impl FriOptimizer {
+    /// A search for circuits shaped like the ones of `CircuitConfig::standard_recursion_config`.
+    pub fn new(degree_bits: usize, security_bits: usize, objective: FriObjective) -> Self {
+        Self {
+            degree_bits,
+            security_bits,
+            objective,
+            // The constants and sigmas, the wires, the `Z`s and partial products, the quotient.
+            num_initial_trees: 4,
+            num_initial_polys: 2 + 80 + 135 + 2 * 10 + 2 * 8,
+            extension_degree: 2,
+            min_rate_bits: 3,
+            max_rate_bits: 5,
+            max_cap_height: 5,
+            max_proof_of_work_bits: 20,
+            max_arity_bits: 4,
+        }
+    }
+
+    /// The number of queries needed to reach `security_bits` with the given rate and proof of work.
+    /// `rate_bits` must be positive.
+    pub fn num_query_rounds(&self, rate_bits: usize, proof_of_work_bits: u32) -> usize {
+        assert!(rate_bits > 0, "A rate of 0 gives no security");
+        let query_bits = self
+            .security_bits
+            .saturating_sub(proof_of_work_bits as usize);
+        ceil_div_usize(query_bits, rate_bits)
+    }
+
+    /// Returns the config with the smallest cost among the ones reaching `security_bits`. Its
+    /// reduction strategy is the fixed sequence of arities found for `degree_bits`.
+    pub fn optimize(&self) -> FriConfig {
+        let mut best: Option<(usize, FriConfig)> = None;
+        for rate_bits in self.min_rate_bits.max(1)..=self.max_rate_bits {
+            for proof_of_work_bits in 0..=self.max_proof_of_work_bits {
+                let num_query_rounds = self.num_query_rounds(rate_bits, proof_of_work_bits);
+                let max_cap_height = self.max_cap_height.min(self.degree_bits + rate_bits);
+                for cap_height in 0..=max_cap_height {
+                    let config = FriConfig {
+                        rate_bits,
+                        cap_height,
+                        proof_of_work_bits,
+                        reduction_strategy: FriReductionStrategy::Fixed(vec![]),
+                        num_query_rounds,
+                    };
+                    let (arity_bits, cost) = min_cost_arity_bits(
+                        self.degree_bits,
+                        rate_bits,
+                        self.max_arity_bits,
+                        vec![],
+                        &|arity_bits| self.cost_with_arities(&config, arity_bits),
+                    );
+                    let is_better = match &best {
+                        Some((best_cost, _)) => cost < *best_cost,
+                        None => true,
+                    };
+                    if is_better {
+                        let config = FriConfig {
+                            reduction_strategy: FriReductionStrategy::Fixed(arity_bits),
+                            ..config
+                        };
+                        best = Some((cost, config));
+                    }
+                }
+            }
+        }
+        best.expect("There is at least one rate to search").1
+    }
+
+    /// The estimated cost of `config` for the objective.
+    pub fn cost(&self, config: &FriConfig) -> usize {
+        let arity_bits = config.reduction_strategy.reduction_arity_bits(
+            self.degree_bits,
+            config.rate_bits,
+            config.cap_height,
+            config.num_query_rounds,
+        );
+        self.cost_with_arities(config, &arity_bits)
+    }
+
+    /// The estimated cost of `config` with the given reduction arities, or `usize::MAX` if a FRI
+    /// tree would be shorter than its cap.
+    fn cost_with_arities(&self, config: &FriConfig, arity_bits: &[usize]) -> usize {
+        let lde_bits = self.degree_bits + config.rate_bits;
+        let layers_bits = arity_bits
+            .iter()
+            .scan(lde_bits, |layer_bits, &arity_bits| {
+                let bits = *layer_bits;
+                *layer_bits -= arity_bits;
+                Some((bits, arity_bits))
+            })
+            .collect::<Vec<_>>();
+        if layers_bits
+            .iter()
+            .any(|&(bits, arity_bits)| bits - arity_bits < config.cap_height)
+        {
+            return usize::MAX;
+        }
+
+        match self.objective {
+            FriObjective::ProofSize => self.proof_size(config, lde_bits, arity_bits),
+            FriObjective::ProverTime => self.prover_time(config, lde_bits, &layers_bits),
+            FriObjective::RecursiveVerifierGates => {
+                self.recursive_verifier_gates(config, lde_bits, &layers_bits)
+            }
+        }
+    }
+
+    fn proof_size(&self, config: &FriConfig, lde_bits: usize, arity_bits: &[usize]) -> usize {
+        let num_queries = config.num_query_rounds;
+        let num_trees = self.num_initial_trees + arity_bits.len();
+        let caps = (num_trees * 4) << config.cap_height;
+        let initial_openings = num_queries
+            * (self.num_initial_polys
+                + self.num_initial_trees * 4 * (lde_bits - config.cap_height));
+        // The cap replaces the top of the Merkle paths of the FRI trees.
+        let fri_openings =
+            relative_proof_size(self.degree_bits, config.rate_bits, num_queries, arity_bits)
+                - num_queries * arity_bits.len() * 4 * config.cap_height;
+        caps + initial_openings + fri_openings + 1
+    }
+
+    fn prover_time(
+        &self,
+        config: &FriConfig,
+        lde_bits: usize,
+        layers_bits: &[(usize, usize)],
+    ) -> usize {
+        let lde_size = 1 << lde_bits;
+        let d = self.extension_degree;
+        // The coset FFTs and the Merkle trees of the initial polynomials, and the FFT of their
+        // combination in the extension field.
+        let initial_ffts = (self.num_initial_polys + d) * lde_size * lde_bits;
+        let initial_hashes = lde_size
+            * (ceil_div_usize(self.num_initial_polys, SPONGE_RATE) + self.num_initial_trees);
+        // Each FRI layer hashes its cosets, then the folded polynomial is evaluated on the next
+        // layer.
+        let fri_hashes = layers_bits
+            .iter()
+            .map(|&(bits, arity_bits)| {
+                (1 << (bits - arity_bits)) * (ceil_div_usize(d << arity_bits, SPONGE_RATE) + 1)
+            })
+            .sum::<usize>();
+        let fri_ffts = layers_bits
+            .iter()
+            .map(|&(bits, arity_bits)| (d * (bits - arity_bits)) << (bits - arity_bits))
+            .sum::<usize>();
+        let proof_of_work = 1 << config.proof_of_work_bits;
+        (initial_ffts + fri_ffts) / MULS_PER_PERMUTATION
+            + initial_hashes
+            + fri_hashes
+            + proof_of_work
+    }
+
+    fn recursive_verifier_gates(
+        &self,
+        config: &FriConfig,
+        lde_bits: usize,
+        layers_bits: &[(usize, usize)],
+    ) -> usize {
+        let d = self.extension_degree;
+        // A Poseidon gate per leaf chunk and per sibling.
+        let initial_per_query = ceil_div_usize(self.num_initial_polys, SPONGE_RATE)
+            + self.num_initial_trees * (lde_bits - config.cap_height)
+            // Combining the openings into the FRI polynomial.
+            + ceil_div_usize(self.num_initial_polys * 2, OPS_PER_ARITHMETIC_GATE);
+        let fri_per_query = layers_bits
+            .iter()
+            .map(|&(bits, arity_bits)| {
+                let arity = 1 << arity_bits;
+                ceil_div_usize(d * arity, SPONGE_RATE)
+                    + (bits - arity_bits - config.cap_height)
+                    // Interpolating the coset at the folding challenge.
+                    + ceil_div_usize(2 * arity, OPS_PER_ARITHMETIC_GATE)
+            })
+            .sum::<usize>();
+        let total_arity_bits = layers_bits.iter().map(|l| l.1).sum::<usize>();
+        let final_poly_len = 1 << (self.degree_bits - total_arity_bits);
+        let final_poly_per_query = ceil_div_usize(final_poly_len, OPS_PER_ARITHMETIC_GATE);
+        // Observing the caps.
+        let num_trees = self.num_initial_trees + layers_bits.len();
+        let caps = ceil_div_usize((num_trees * 4) << config.cap_height, SPONGE_RATE);
+        config.num_query_rounds * (initial_per_query + fri_per_query + final_poly_per_query)
+            + caps
+            + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fri::optimizer::{FriObjective, FriOptimizer};
+    use crate::fri::reduction_strategies::FriReductionStrategy;
+    use crate::plonk::circuit_data::CircuitConfig;
+
+    #[test]
+    fn optimized_configs_beat_the_standard_one() {
+        let standard = CircuitConfig::standard_recursion_config().fri_config;
+        for objective in [
+            FriObjective::ProofSize,
+            FriObjective::ProverTime,
+            FriObjective::RecursiveVerifierGates,
+        ] {
+            let optimizer = FriOptimizer::new(14, 100, objective);
+            let config = optimizer.optimize();
+            assert!(
+                config.num_query_rounds * config.rate_bits + config.proof_of_work_bits as usize
+                    >= 100
+            );
+            assert!(matches!(
+                config.reduction_strategy,
+                FriReductionStrategy::Fixed(_)
+            ));
+            assert!(optimizer.cost(&config) <= optimizer.cost(&standard));
+        }
+    }
+
+    #[test]
+    fn prover_time_prefers_the_smallest_rate() {
+        let config = FriOptimizer::new(14, 100, FriObjective::ProverTime).optimize();
+        assert_eq!(config.rate_bits, 3);
+    }
+
+    #[test]
+    fn zero_rate_is_skipped() {
+        let mut optimizer = FriOptimizer::new(14, 100, FriObjective::ProverTime);
+        optimizer.min_rate_bits = 0;
+        optimizer.max_rate_bits = 2;
+        let config = optimizer.optimize();
+        assert!(config.rate_bits >= 1);
+    }
+}
@@ -1,14 +1,13 @@
 use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
 use itertools::Itertools;
+use log::debug;
 
 use maybe_rayon::*;
 
-#[cfg(feature = "cuda")]
-use std::intrinsics::transmute;
-
-#[cfg(feature = "cuda")]
-use rustacuda::memory::{AsyncCopyDestination, DeviceSlice};
-
+use crate::backend::ProverBackend;
 use crate::field::extension::{flatten, unflatten, Extendable};
 use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::fri::proof::{FriInitialTreeProof, FriProof, FriQueryRound, FriQueryStep};
@@ -24,7 +23,7 @@ use crate::timed;
 use crate::util::reverse_index_bits_in_place;
 use crate::util::timing::TimingTree;
 
-/// Builds a FRI proof.
+/// Builds a FRI proof, along with the statistics of its proof-of-work search.
 pub fn fri_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
     initial_merkle_trees: &[&MerkleTree<F, C::Hasher>],
     // Coefficients of the polynomial on which the LDT is performed. Only the first `1/rate` coefficients are non-zero.
@@ -34,8 +33,8 @@ pub fn fri_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const
     challenger: &mut Challenger<F, C::Hasher>,
     fri_params: &FriParams,
     timing: &mut TimingTree,
-    ctx: &mut Option<&mut crate::fri::oracle::CudaInvContext<F, C, D>>,
-) -> FriProof<F, C::Hasher, D> {
+    backend: &mut Option<&mut dyn ProverBackend<F, C, D>>,
+) -> (FriProof<F, C::Hasher, D>, PowStatistics) {
     let n = lde_polynomial_values.len();
     assert_eq!(lde_polynomial_coeffs.len(), n);
 
@@ -52,25 +51,37 @@ pub fn fri_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const
     );
 
     // PoW phase
-    let pow_witness = timed!(
+    let (pow_witness, pow_statistics) = timed!(
         timing,
         "find proof-of-work witness",
         fri_proof_of_work::<F, C, D>(challenger, &fri_params.config)
     );
+    debug!(
+        "Found the proof-of-work witness after trying {} nonces",
+        pow_statistics.nonces_tried
+    );
 
     // Query phase
     let query_round_proofs = timed!(
         timing,
         "fri_prover_query_rounds",
-        fri_prover_query_rounds::<F, C, D>(initial_merkle_trees, &trees, challenger, n, fri_params, ctx)
+        fri_prover_query_rounds::<F, C, D>(
+            initial_merkle_trees,
+            &trees,
+            challenger,
+            n,
+            fri_params,
+            backend,
+        )
     );
 
-    FriProof {
+    let fri_proof = FriProof {
         commit_phase_merkle_caps: trees.iter().map(|t| t.cap.clone()).collect(),
         query_round_proofs,
         final_poly: final_coeffs,
         pow_witness,
-    }
+    };
+    (fri_proof, pow_statistics)
 }
 
 type FriCommitedTrees<F, C, const D: usize> = (
@@ -123,11 +134,35 @@ fn fri_committed_trees<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>,
     (trees, coeffs)
 }
 
-/// Performs the proof-of-work (a.k.a. grinding) step of the FRI protocol. Returns the PoW witness.
-fn fri_proof_of_work<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+/// The number of consecutive nonces which each thread tries in a block of the proof-of-work search.
+const POW_NONCES_PER_TASK: u64 = 1 << 10;
+
+/// Statistics of a proof-of-work search.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PowStatistics {
+    /// The number of PoW responses computed, including the ones computed by other workers after
+    /// the witness was found.
+    pub nonces_tried: u64,
+    /// The time spent searching, measured with the `std` feature only.
+    pub elapsed: Option<Duration>,
+}
+
+/// Performs the proof-of-work (a.k.a. grinding) step of the FRI protocol. Returns the PoW witness,
+/// which is the smallest valid nonce, so the proof is the same for any number of threads.
+///
+/// The nonces are searched in ascending blocks of `POW_NONCES_PER_TASK` nonces per thread, each
+/// thread searching its range of the block, and the search stops after the first block containing
+/// a witness.
+pub fn fri_proof_of_work<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
     challenger: &mut Challenger<F, C::Hasher>,
     config: &FriConfig,
-) -> F {
+) -> (F, PowStatistics) {
+    #[cfg(feature = "std")]
+    let start = std::time::Instant::now();
     let min_leading_zeros = config.proof_of_work_bits + (64 - F::order().bits()) as u32;
 
     // The easiest implementation would be repeatedly clone our Challenger. With each clone, we'd
@@ -152,26 +187,64 @@ fn fri_proof_of_work<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, c
         duplex_intermediate_state[i] = *input;
     }
 
-    let pow_witness = (0..=F::NEG_ONE.to_canonical_u64())
-        .into_par_iter()
-        .find_any(|&candidate| {
-            let mut duplex_state = duplex_intermediate_state;
-            duplex_state[witness_input_pos] = F::from_canonical_u64(candidate);
-            duplex_state =
-                <<C as GenericConfig<D>>::Hasher as Hasher<F>>::Permutation::permute(duplex_state);
-            let pow_response = duplex_state[SPONGE_RATE - 1];
-            let leading_zeros = pow_response.to_canonical_u64().leading_zeros();
-            leading_zeros >= min_leading_zeros
-        })
-        .map(F::from_canonical_u64)
-        .expect("Proof of work failed. This is highly unlikely!");
+    let is_witness = |candidate: u64| {
+        let mut duplex_state = duplex_intermediate_state;
+        duplex_state[witness_input_pos] = F::from_canonical_u64(candidate);
+        duplex_state =
+            <<C as GenericConfig<D>>::Hasher as Hasher<F>>::Permutation::permute_vectorized(
+                duplex_state,
+            );
+        let pow_response = duplex_state[SPONGE_RATE - 1];
+        pow_response.to_canonical_u64().leading_zeros() >= min_leading_zeros
+    };
+
+    let max_nonce = F::NEG_ONE.to_canonical_u64();
+    let num_tasks = current_num_threads() as u64;
+    let nonces_tried = AtomicU64::new(0);
+    let mut block_start = 0;
+    let pow_witness = loop {
+        assert!(
+            block_start <= max_nonce,
+            "Proof of work failed. This is highly unlikely!"
+        );
+        let witness = (0..num_tasks)
+            .into_par_iter()
+            .filter_map(|task| {
+                let first = block_start + task * POW_NONCES_PER_TASK;
+                if first > max_nonce {
+                    return None;
+                }
+                let last = max_nonce.min(first + (POW_NONCES_PER_TASK - 1));
+                let mut tried = 0;
+                let witness = (first..=last).find(|&candidate| {
+                    tried += 1;
+                    is_witness(candidate)
+                });
+                nonces_tried.fetch_add(tried, Ordering::Relaxed);
+                witness
+            })
+            .min();
+        if let Some(witness) = witness {
+            break F::from_canonical_u64(witness);
+        }
+        block_start += num_tasks * POW_NONCES_PER_TASK;
+    };
 
     // Recompute pow_response using our normal Challenger code, and make sure it matches.
     challenger.observe_element(pow_witness);
     let pow_response = challenger.get_challenge();
     let leading_zeros = pow_response.to_canonical_u64().leading_zeros();
     assert!(leading_zeros >= min_leading_zeros);
-    pow_witness
+
+    #[cfg(feature = "std")]
+    let elapsed = Some(start.elapsed());
+    #[cfg(not(feature = "std"))]
+    let elapsed = None;
+    let statistics = PowStatistics {
+        nonces_tried: nonces_tried.into_inner(),
+        elapsed,
+    };
+    (pow_witness, statistics)
 }
 
 fn fri_prover_query_rounds<
@@ -184,46 +257,36 @@ fn fri_prover_query_rounds<
     challenger: &mut Challenger<F, C::Hasher>,
     n: usize,
     fri_params: &FriParams,
-    ctx: &mut Option<&mut crate::fri::oracle::CudaInvContext<F, C, D>>,
+    backend: &mut Option<&mut dyn ProverBackend<F, C, D>>,
 ) -> Vec<FriQueryRound<F, C::Hasher, D>> {
     let challs = challenger.get_n_challenges(fri_params.config.num_query_rounds);
 
-    let proofs_vec = challs.iter()
+    let proofs_vec = challs
+        .iter()
         .map(|rand| {
             let x_index = rand.to_canonical_u64() as usize % n;
 
             let initial_proof = initial_merkle_trees
                 .iter()
                 .map(|t| {
-                    #[cfg(feature = "cuda")]
-                    if t.my_leaves_dev_offset >= 0 && ctx.is_some() {
-                        let ctx = ctx.as_mut().unwrap();
-                        let data = &mut (*ctx).cache_mem_device[t.my_leaves_dev_offset as usize..];
-                        let data = &mut data[x_index * t.my_leaf_len.. (x_index+1) * t.my_leaf_len];
-
-                        let mut values = Vec::<F>::with_capacity(t.my_leaf_len);
-                        unsafe {
-                            values.set_len(data.len());
-                            transmute::<&DeviceSlice<F>, &DeviceSlice<u64>>(data).async_copy_to(
-                                transmute::<&mut Vec<F>, &mut Vec<u64>>(&mut values),
-                                &ctx.inner.stream).unwrap();
-                            ctx.inner.stream.synchronize().unwrap();
-                        }
-                        ctx.inner.stream.synchronize().unwrap();
-                        (values, t.prove(x_index))
-                    } else {
-                        (t.get(x_index).to_vec(), t.prove(x_index))
-                    }
-
-                    #[cfg(not(feature = "cuda"))]
-                    (t.get(x_index).to_vec(), t.prove(x_index))
+                    // Trees committed by a backend keep their leaves in the backend memory.
+                    let leaf = match backend.as_mut() {
+                        Some(backend) if t.my_leaves_dev_offset >= 0 => backend.read_values(
+                            t.my_leaves_dev_offset as usize + x_index * t.my_leaf_len,
+                            t.my_leaf_len,
+                        ),
+                        _ => t.get(x_index).to_vec(),
+                    };
+                    (leaf, t.prove(x_index))
                 })
                 .collect::<Vec<_>>();
             initial_proof
         })
         .collect_vec();
 
-    challs.into_par_iter().zip(proofs_vec)
+    challs
+        .into_par_iter()
+        .zip(proofs_vec)
         .map(|(rand, initial_proof)| {
             let x_index = rand.to_canonical_u64() as usize % n;
             fri_prover_query_round::<F, C, D>(initial_proof, trees, x_index, fri_params)
@@ -266,3 +329,47 @@ fn fri_prover_query_round<
         steps: query_steps,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::field::types::{Field, PrimeField64};
+    use crate::fri::prover::fri_proof_of_work;
+    use crate::fri::reduction_strategies::FriReductionStrategy;
+    use crate::fri::FriConfig;
+    use crate::iop::challenger::Challenger;
+    use crate::plonk::config::{GenericConfig, Hasher, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn proof_of_work_finds_smallest_witness() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+
+        let config = FriConfig {
+            rate_bits: 3,
+            cap_height: 4,
+            proof_of_work_bits: 12,
+            reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
+            num_query_rounds: 28,
+        };
+        let min_leading_zeros = config.proof_of_work_bits + (64 - F::order().bits()) as u32;
+        let mut challenger = Challenger::<F, H>::new();
+        challenger.observe_hash::<H>(H::hash_no_pad(&[F::ONE]));
+        let is_witness = |nonce: u64| {
+            let mut challenger = challenger.clone();
+            challenger.observe_element(F::from_canonical_u64(nonce));
+            challenger
+                .get_challenge()
+                .to_canonical_u64()
+                .leading_zeros()
+                >= min_leading_zeros
+        };
+
+        let (witness, statistics) = fri_proof_of_work::<F, C, D>(&mut challenger.clone(), &config);
+        let witness = witness.to_canonical_u64();
+        assert!(is_witness(witness));
+        assert!((0..witness).all(|nonce| !is_witness(nonce)));
+        assert!(statistics.nonces_tried > witness);
+    }
+}
@@ -66,8 +66,13 @@ fn min_size_arity_bits(
 
     #[cfg(feature = "timing")]
     let start = Instant::now();
-    let (mut arity_bits, fri_proof_size) =
-        min_size_arity_bits_helper(degree_bits, rate_bits, num_queries, max_arity_bits, vec![]);
+    let (mut arity_bits, fri_proof_size) = min_cost_arity_bits(
+        degree_bits,
+        rate_bits,
+        max_arity_bits,
+        vec![],
+        &|arity_bits| relative_proof_size(degree_bits, rate_bits, num_queries, arity_bits),
+    );
     arity_bits.shrink_to_fit();
 
     #[cfg(feature = "timing")]
@@ -83,20 +88,21 @@ fn min_size_arity_bits(
     arity_bits
 }
 
-/// Return `(arity_bits, fri_proof_size)`.
-fn min_size_arity_bits_helper(
+/// Searches the sequences of reduction arities starting with `prefix` for the one with the smallest
+/// `cost`. Return `(arity_bits, cost)`.
+pub(crate) fn min_cost_arity_bits(
     degree_bits: usize,
     rate_bits: usize,
-    num_queries: usize,
     global_max_arity_bits: usize,
     prefix: Vec<usize>,
+    cost: &dyn Fn(&[usize]) -> usize,
 ) -> (Vec<usize>, usize) {
     let sum_of_arities: usize = prefix.iter().sum();
     let current_layer_bits = degree_bits + rate_bits - sum_of_arities;
     assert!(current_layer_bits >= rate_bits);
 
     let mut best_arity_bits = prefix.clone();
-    let mut best_size = relative_proof_size(degree_bits, rate_bits, num_queries, &prefix);
+    let mut best_cost = cost(&prefix);
 
     // The largest next_arity_bits to search. Note that any optimal arity sequence will be
     // monotonically non-increasing, as a larger arity will shrink more Merkle proofs if it occurs
@@ -111,26 +117,26 @@ fn min_size_arity_bits_helper(
         let mut extended_prefix = prefix.clone();
         extended_prefix.push(next_arity_bits);
 
-        let (arity_bits, size) = min_size_arity_bits_helper(
+        let (arity_bits, extended_cost) = min_cost_arity_bits(
             degree_bits,
             rate_bits,
-            num_queries,
             max_arity_bits,
             extended_prefix,
+            cost,
         );
-        if size < best_size {
+        if extended_cost < best_cost {
             best_arity_bits = arity_bits;
-            best_size = size;
+            best_cost = extended_cost;
         }
     }
 
-    (best_arity_bits, best_size)
+    (best_arity_bits, best_cost)
 }
 
 /// Compute the approximate size of a FRI proof with the given reduction arities. Note that this
 /// ignores initial evaluations, which aren't affected by arities, and some other minor
 /// contributions. The result is measured in field elements.
-fn relative_proof_size(
+pub(crate) fn relative_proof_size(
     degree_bits: usize,
     rate_bits: usize,
     num_queries: usize,
@@ -0,0 +1,341 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::gates::lookup::LookupGate;
+use crate::gates::lookup_table::LookupTable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Registers a lookup table, and returns its index for `add_lookup`. The inputs of the table
+    /// should be distinct: an input is always mapped to the output of its first entry.
+    ///
+    /// Lookups are not supported with zero knowledge, as the polynomials of the lookup argument
+    /// aren't blinded: [`CircuitBuilder::try_build`] fails if `config.zero_knowledge` is set.
+    pub fn add_lookup_table(&mut self, table: LookupTable) -> usize {
+        assert!(!table.is_empty(), "Lookup tables cannot be empty");
+        self.lookup_tables.push(table);
+        self.lookups.push(Vec::new());
+        self.lookup_tables.len() - 1
+    }
+
+    /// Returns the output of the table at `table_index` for `input`. Proving fails if `input` is
+    /// not one of the inputs of the table, with a `WitnessGenerationError` reporting the
+    /// `LookupGenerator` of the lookup as stalled.
+    pub fn add_lookup(&mut self, table_index: usize, input: Target) -> Target {
+        let kappa = F::from_canonical_usize(table_index);
+        let (row, slot) = self.find_slot(
+            LookupGate::new_from_config(&self.config),
+            &[kappa],
+            &[kappa],
+        );
+        self.connect(input, Target::wire(row, LookupGate::wire_input(slot)));
+        self.lookups[table_index].push(input);
+        Target::wire(row, LookupGate::wire_output(slot))
+    }
+}
+
+/// A lookup table of a circuit, as the prover uses it to generate the witness.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProverLookupTable {
+    pub table: LookupTable,
+    /// The output of each input, i.e. of the first entry with that input.
+    outputs: HashMap<u16, u16>,
+}
+
+impl ProverLookupTable {
+    pub fn new(table: LookupTable) -> Self {
+        let mut outputs = HashMap::with_capacity(table.len());
+        for &(input, output) in table.iter() {
+            outputs.entry(input).or_insert(output);
+        }
+        Self { table, outputs }
+    }
+
+    /// The output of `input`, if it is an input of the table.
+    pub fn output(&self, input: u16) -> Option<u16> {
+        self.outputs.get(&input).copied()
+    }
+}
+
+/// Sets the outputs of the slots of a `LookupGate` from their inputs. The inputs of a row may
+/// depend on the outputs of the same row, so each output is set as soon as its input is known.
+/// The output of an input which isn't in the table is left unset, so that the generator never
+/// finishes and witness generation fails.
+#[derive(Debug)]
+pub(crate) struct LookupGenerator {
+    pub(crate) row: usize,
+    pub(crate) num_slots: usize,
+    pub(crate) table_index: usize,
+}
+
+impl<F: RichField> WitnessGenerator<F> for LookupGenerator {
+    fn watch_list(&self) -> Vec<Target> {
+        (0..self.num_slots)
+            .map(|slot| Target::wire(self.row, LookupGate::wire_input(slot)))
+            .collect()
+    }
+
+    fn run(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) -> bool {
+        let table = &witness.lookup_tables[self.table_index];
+        let mut finished = true;
+        for slot in 0..self.num_slots {
+            let output_target = Target::wire(self.row, LookupGate::wire_output(slot));
+            if witness.contains(output_target) {
+                continue;
+            }
+            let input_target = Target::wire(self.row, LookupGate::wire_input(slot));
+            let input = match witness.try_get_target(input_target) {
+                Some(input) => input,
+                None => {
+                    finished = false;
+                    continue;
+                }
+            };
+            let Some(output) = u16::try_from(input.to_canonical_u64())
+                .ok()
+                .and_then(|i| table.output(i))
+            else {
+                finished = false;
+                continue;
+            };
+            out_buffer.set_target(output_target, F::from_canonical_u16(output));
+        }
+        finished
+    }
+
+    fn id(&self) -> String {
+        "LookupGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.num_slots)?;
+        dst.write_usize(self.table_index)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let num_slots = src.read_usize()?;
+        let table_index = src.read_usize()?;
+        Ok(Self {
+            row,
+            num_slots,
+            table_index,
+        })
+    }
+}
+
+/// Sets the multiplicities of the entries of a table, i.e. the number of times each of them is
+/// looked up. `multiplicities` may be longer than the table, as its last `LookupTableGate` is
+/// padded.
+#[derive(Debug)]
+pub(crate) struct LookupTableGenerator {
+    pub(crate) table_index: usize,
+    pub(crate) lookups: Vec<Target>,
+    pub(crate) multiplicities: Vec<Target>,
+}
+
+impl<F: RichField> SimpleGenerator<F> for LookupTableGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        self.lookups.clone()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let table = &witness.lookup_tables[self.table_index].table;
+        let mut counts = HashMap::<u64, usize>::new();
+        for &lookup in &self.lookups {
+            *counts
+                .entry(witness.get_target(lookup).to_canonical_u64())
+                .or_default() += 1;
+        }
+        // Each input is counted for its first entry only, as `LookupGenerator` uses that one.
+        for (i, &multiplicity) in self.multiplicities.iter().enumerate() {
+            let count = table
+                .get(i)
+                .and_then(|&(input, _)| counts.remove(&(input as u64)))
+                .unwrap_or(0);
+            out_buffer.set_target(multiplicity, F::from_canonical_usize(count));
+        }
+    }
+
+    fn id(&self) -> String {
+        "LookupTableGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.table_index)?;
+        dst.write_target_vec(&self.lookups)?;
+        dst.write_target_vec(&self.multiplicities)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let table_index = src.read_usize()?;
+        let lookups = src.read_target_vec()?;
+        let multiplicities = src.read_target_vec()?;
+        Ok(Self {
+            table_index,
+            lookups,
+            multiplicities,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use anyhow::Result;
+
+    use crate::backend::cpu::CpuBackend;
+    use crate::field::types::Field;
+    use crate::iop::generator::generate_partial_witness;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData, ProverOnlyCircuitData};
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::proof::ProofWithPublicInputs;
+    use crate::plonk::prover::{my_prove, prove};
+    use crate::util::serialization::gate_serialization::GateSerializer;
+    use crate::util::serialization::generator_serialization::WitnessGeneratorSerializer;
+    use crate::util::timing::TimingTree;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Proves `x -> (3x + 1) mod 256` chained a few times over a byte table, with a second table
+    /// which is looked up once.
+    #[test]
+    fn test_lookups() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let byte_table = Arc::new(
+            (0..256u16)
+                .map(|x| (x, (3 * x + 1) % 256))
+                .collect::<Vec<_>>(),
+        );
+        let square_table = Arc::new((0..16u16).map(|x| (x, x * x)).collect::<Vec<_>>());
+        let bytes = builder.add_lookup_table(byte_table);
+        let squares = builder.add_lookup_table(square_table);
+
+        let x = builder.add_virtual_target();
+        let mut y = x;
+        for _ in 0..50 {
+            y = builder.add_lookup(bytes, y);
+        }
+        let z = builder.add_virtual_target();
+        let z_squared = builder.add_lookup(squares, z);
+        builder.register_public_input(y);
+        builder.register_public_input(z_squared);
+        let data = builder.build::<C>();
+        assert!(data.common.has_lookups());
+
+        // The generators find the tables in the prover data, which serializes each of them once.
+        let generator_serializer = WitnessGeneratorSerializer::plonky2();
+        let prover_only_bytes = data
+            .prover_only
+            .to_bytes(&generator_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let prover_only =
+            ProverOnlyCircuitData::<F, C, D>::from_bytes(prover_only_bytes, &generator_serializer)
+                .map_err(anyhow::Error::msg)?;
+        assert_eq!(prover_only.lookup_tables, data.prover_only.lookup_tables);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(7));
+        pw.set_target(z, F::from_canonical_u64(12));
+        let proof = prove(&prover_only, &data.common, pw, &mut TimingTree::default())?;
+        let expected = (0..50).fold(7, |y, _| (3 * y + 1) % 256);
+        assert_eq!(
+            proof.public_inputs,
+            [expected, 144].map(F::from_canonical_u64)
+        );
+
+        let proof_bytes = proof.to_bytes();
+        let proof_from_bytes = ProofWithPublicInputs::from_bytes(proof_bytes, &data.common)?;
+        assert_eq!(proof_from_bytes, proof);
+
+        let gate_serializer = GateSerializer::plonky2();
+        let common_bytes = data
+            .common
+            .to_bytes(&gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let common_from_bytes = CommonCircuitData::from_bytes(common_bytes, &gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        assert_eq!(common_from_bytes, data.common);
+
+        data.verify(proof.clone())?;
+
+        // The proof also verifies recursively.
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let proof_target = builder.add_virtual_proof_with_pis::<C>(&data.common);
+        let verifier_data = builder.constant_verifier_data(&data.verifier_only);
+        builder.verify_proof::<C>(&proof_target, &verifier_data, &data.common);
+        let recursive_data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_proof_with_pis_target(&proof_target, &proof);
+        let recursive_proof = recursive_data.prove(pw)?;
+        recursive_data.verify(recursive_proof)
+    }
+
+    #[test]
+    fn test_backends_reject_lookups() {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let table = builder.add_lookup_table(Arc::new(vec![(0, 1), (1, 0)]));
+        let x = builder.add_virtual_target();
+        builder.add_lookup(table, x);
+        let data = builder.build::<C>();
+
+        // The generators would fail on this input, had they run.
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO);
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        let result = my_prove(
+            &data.prover_only,
+            &data.common,
+            pw,
+            &mut TimingTree::default(),
+            &mut backend,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let table = builder.add_lookup_table(Arc::new(vec![(0, 1), (1, 0)]));
+        let x = builder.add_virtual_target();
+        builder.add_lookup(table, x);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO);
+        let err = generate_partial_witness(pw.clone(), &data.prover_only, &data.common)
+            .err()
+            .unwrap();
+        assert!(err
+            .stalled_generators
+            .iter()
+            .any(|stalled| stalled.generator.starts_with("LookupGenerator")));
+        assert!(data.prove(pw).is_err());
+    }
+
+    #[test]
+    fn test_zero_knowledge_is_rejected() {
+        let mut builder =
+            CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_zk_config());
+        let table = builder.add_lookup_table(Arc::new(vec![(0, 1), (1, 0)]));
+        let x = builder.add_virtual_target();
+        builder.add_lookup(table, x);
+        let err = builder.try_build::<C>().err().unwrap();
+        assert!(err.to_string().contains("zero knowledge"));
+    }
+}
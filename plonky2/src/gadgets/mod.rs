@@ -1,6 +1,7 @@
 pub mod arithmetic;
 pub mod arithmetic_extension;
 pub mod hash;
+pub mod lookup;
 pub mod polynomial;
 pub mod random_access;
 pub mod range_check;
@@ -3,6 +3,8 @@ use alloc::format;
 use alloc::string::String;
 use alloc::vec::Vec;
 
+use anyhow::Result;
+
 use crate::field::extension::Extendable;
 use crate::field::packed::PackedField;
 use crate::gates::gate::Gate;
@@ -19,6 +21,7 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES};
 
 /// A gate which can perform a weighted multiply-add, i.e. `result = c0 x y + c1 z`. If the config
 /// supports enough routed wires, it can support several such operations in one gate.
@@ -60,7 +63,16 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticGate
         format!("{self:?}")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.num_ops)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let num_ops = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self { num_ops })
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "template Arithmetic$NUM_OPS() {{
   signal input constants[NUM_OPENINGS_CONSTANTS()][2];
@@ -82,9 +94,9 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticGate
 }}"
         ).to_string();
         template_str = template_str.replace("$NUM_OPS", &*self.num_ops.to_string());
-        template_str
+        Ok(template_str)
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "library Arithmetic$NUM_OPSLib {{
     using GoldilocksExtLib for uint64[2];
@@ -102,7 +114,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticGate
         )
             .to_string();
         template_str = template_str.replace("$NUM_OPS", &*self.num_ops.to_string());
-        template_str
+        Ok(template_str)
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -217,7 +229,7 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for
 }
 
 #[derive(Clone, Debug)]
-struct ArithmeticBaseGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct ArithmeticBaseGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     const_0: F,
     const_1: F,
@@ -252,6 +264,30 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
 
         out_buffer.set_target(output_target, computed_output)
     }
+
+    fn id(&self) -> String {
+        "ArithmeticBaseGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_field(self.const_0)?;
+        dst.write_field(self.const_1)?;
+        dst.write_usize(self.i)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let const_0 = src.read_field()?;
+        let const_1 = src.read_field()?;
+        let i = src.read_usize()?;
+        Ok(Self {
+            row,
+            const_0,
+            const_1,
+            i,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -4,6 +4,8 @@ use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::ops::Range;
 
+use anyhow::Result;
+
 use crate::field::extension::Extendable;
 use crate::field::packed::PackedField;
 use crate::field::types::{Field, Field64};
@@ -23,6 +25,7 @@ use crate::plonk::vars::{
     EvaluationVarsBasePacked,
 };
 use crate::util::log_floor;
+use crate::util::serialization::{Buffer, IoError, IoResult, Read, Write, MAX_GATE_WIRES};
 
 /// A gate which can decompose a number into base B little-endian limbs.
 #[derive(Copy, Clone, Debug)]
@@ -55,7 +58,21 @@ impl<F: RichField + Extendable<D>, const D: usize, const B: usize> Gate<F, D> fo
         format!("{self:?} + Base: {B}")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    /// `BaseSumGate`s of all bases share a name, so the base is written first.
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(B)?;
+        dst.write_usize(self.num_limbs)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        if src.read_usize()? != B {
+            return Err(IoError);
+        }
+        let num_limbs = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self { num_limbs })
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "template BaseSum$NUM_LIMBS() {{
   signal input constants[NUM_OPENINGS_CONSTANTS()][2];
@@ -93,9 +110,9 @@ impl<F: RichField + Extendable<D>, const D: usize, const B: usize> Gate<F, D> fo
         template_str = template_str.replace("$NUM_LIMBS", &*self.num_limbs.to_string());
         template_str = template_str.replace("$B", &*B.to_string());
 
-        template_str
+        Ok(template_str)
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         let mut template_str = format!("library BaseSum$NUM_LIMBSLib {{
     using GoldilocksExtLib for uint64[2];
     function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {{
@@ -122,7 +139,7 @@ impl<F: RichField + Extendable<D>, const D: usize, const B: usize> Gate<F, D> fo
         template_str = template_str.replace("$NUM_LIMBS", &*self.num_limbs.to_string());
         template_str = template_str.replace("$B", &*B.to_string());
 
-        template_str
+        Ok(template_str)
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -265,6 +282,21 @@ impl<F: RichField, const B: usize> SimpleGenerator<F> for BaseSplitGenerator<B>
             out_buffer.set_target(b, b_value);
         }
     }
+
+    fn id(&self) -> String {
+        format!("BaseSplitGenerator + Base: {B}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.num_limbs)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let num_limbs = src.read_usize()?;
+        Ok(Self { row, num_limbs })
+    }
 }
 
 #[cfg(test)]
@@ -3,6 +3,8 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use alloc::{format, vec};
 
+use anyhow::Result;
+
 use crate::field::extension::Extendable;
 use crate::field::packed::PackedField;
 use crate::gates::gate::Gate;
@@ -16,6 +18,7 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES};
 
 /// A gate which takes a single constant parameter and outputs that value.
 #[derive(Copy, Clone, Debug)]
@@ -40,7 +43,16 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ConstantGate {
         format!("{self:?}")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.num_consts)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let num_consts = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self { num_consts })
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "template Constant$NUM_CONSTANTS() {{
   signal input constants[NUM_OPENINGS_CONSTANTS()][2];
@@ -61,9 +73,9 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ConstantGate {
 }}"
         ).to_string();
         template_str = template_str.replace("$NUM_CONSTANTS", &*self.num_consts.to_string());
-        template_str
+        Ok(template_str)
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "library Constant$NUM_CONSTANTSLib {{
     function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {{
@@ -81,7 +93,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ConstantGate {
 
         template_str = template_str.replace("$NUM_CONSTANTS", &*self.num_consts.to_string());
 
-        template_str
+        Ok(template_str)
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
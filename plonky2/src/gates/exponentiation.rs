@@ -4,6 +4,8 @@ use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::marker::PhantomData;
 
+use anyhow::Result;
+
 use crate::field::extension::Extendable;
 use crate::field::ops::Square;
 use crate::field::packed::PackedField;
@@ -23,6 +25,7 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES};
 
 /// A gate for raising a value to a power.
 #[derive(Clone, Debug)]
@@ -76,7 +79,16 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Exponentiation
         format!("{self:?}<D={D}>")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.num_power_bits)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let num_power_bits = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self::new(num_power_bits))
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
         "template Exponentiation$NUM_POWER_BITS() {{
   signal input constants[NUM_OPENINGS_CONSTANTS()][2];
@@ -113,9 +125,9 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Exponentiation
 }}"
         ).to_string();
         template_str = template_str.replace("$NUM_POWER_BITS", &*self.num_power_bits.to_string());
-        template_str
+        Ok(template_str)
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "library Exponentiation$NUM_POWER_BITSLib {{
     using GoldilocksExtLib for uint64[2];
@@ -144,7 +156,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Exponentiation
         )
             .to_string();
         template_str = template_str.replace("$NUM_POWER_BITS", &*self.num_power_bits.to_string());
-        template_str
+        Ok(template_str)
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -300,7 +312,7 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
 }
 
 #[derive(Debug)]
-struct ExponentiationGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct ExponentiationGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     gate: ExponentiationGate<F, D>,
 }
@@ -352,6 +364,21 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
         let output_wire = local_wire(self.gate.wire_output());
         out_buffer.set_wire(output_wire, intermediate_values[num_power_bits - 1]);
     }
+
+    fn id(&self) -> String {
+        "ExponentiationGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        self.gate.serialize(dst)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let gate = ExponentiationGate::<F, D>::deserialize(src)?;
+        Ok(Self { row, gate })
+    }
 }
 
 #[cfg(test)]
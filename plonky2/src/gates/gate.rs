@@ -7,6 +7,7 @@ use core::fmt::{Debug, Error, Formatter};
 use core::hash::{Hash, Hasher};
 use core::ops::Range;
 
+use anyhow::Result;
 use hashbrown::HashMap;
 
 use crate::field::batch_util::batch_multiply_inplace;
@@ -21,13 +22,21 @@ use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
 };
+use crate::util::serialization::{Buffer, IoResult};
 
 /// A custom gate.
 pub trait Gate<F: RichField + Extendable<D>, const D: usize>: 'static + Send + Sync {
     fn id(&self) -> String;
 
-    fn export_circom_verification_code(&self) -> String;
-    fn export_solidity_verification_code(&self) -> String;
+    fn export_circom_verification_code(&self) -> Result<String>;
+    fn export_solidity_verification_code(&self) -> Result<String>;
+
+    /// Writes the parameters of the gate, from which [`Gate::deserialize`] rebuilds it.
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()>;
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self>
+    where
+        Self: Sized;
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension>;
 
@@ -117,7 +126,6 @@ pub trait Gate<F: RichField + Extendable<D>, const D: usize>: 'static + Send + S
         selector_index: usize,
         group_range: Range<usize>,
         num_selectors: usize,
-        indices_batch: &[usize],
     ) -> Vec<F> {
         let filters: Vec<_> = vars_batch
             .iter()
@@ -131,18 +139,8 @@ pub trait Gate<F: RichField + Extendable<D>, const D: usize>: 'static + Send + S
             })
             .collect();
 
-        // if indices_batch[25] == 2086137 {
-        //     println!("i: {}, row: {}, filter: {:?}", indices_batch[25], row, filters[25]);
-        // }
-
         vars_batch.remove_prefix(num_selectors);
         let mut res_batch = self.eval_unfiltered_base_batch(vars_batch);
-
-        if indices_batch[25] == 1048576 {
-            let res = res_batch.chunks(filters.len()).map(|cs|cs[25]).collect::<Vec<_>>();
-            println!("i: {}, row: {}, terms: {:?}", indices_batch[25], row, res);
-        }
-
         for res_chunk in res_batch.chunks_exact_mut(filters.len()) {
             batch_multiply_inplace(res_chunk, &filters);
         }
@@ -235,6 +233,15 @@ impl<F: RichField + Extendable<D>, const D: usize> Debug for GateRef<F, D> {
     }
 }
 
+/// The name of a gate, i.e. the identifier at the start of its id, such as `BaseSumGate` in
+/// `BaseSumGate { num_limbs: 63 } + Base: 2`.
+pub(crate) fn gate_name(id: &str) -> &str {
+    let end = id
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(id.len());
+    &id[..end]
+}
+
 /// Map between gate parameters and available slots.
 /// An available slot is of the form `(row, op)`, meaning the current available slot
 /// is at gate index `row` in the `op`-th operation.
@@ -258,7 +265,12 @@ pub struct PrefixedGate<F: RichField + Extendable<D>, const D: usize> {
 }
 
 /// A gate's filter designed so that it is non-zero if `s = row`.
-fn compute_filter<K: Field>(row: usize, group_range: Range<usize>, s: K, many_selector: bool) -> K {
+pub(crate) fn compute_filter<K: Field>(
+    row: usize,
+    group_range: Range<usize>,
+    s: K,
+    many_selector: bool,
+) -> K {
     debug_assert!(group_range.contains(&row));
     group_range
         .filter(|&i| i != row)
@@ -267,7 +279,7 @@ fn compute_filter<K: Field>(row: usize, group_range: Range<usize>, s: K, many_se
         .product()
 }
 
-fn compute_filter_circuit<F: RichField + Extendable<D>, const D: usize>(
+pub(crate) fn compute_filter_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
     row: usize,
     group_range: Range<usize>,
@@ -1,3 +1,4 @@
+use alloc::collections::BTreeSet;
 use alloc::vec;
 use alloc::vec::Vec;
 
@@ -8,9 +9,12 @@ use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::field::types::{Field, Sample};
 use crate::gates::gate::Gate;
 use crate::hash::hash_types::{HashOut, RichField};
+use crate::iop::generator::{
+    generate_partial_witness, generate_partial_witness_sequentially, GeneratedValues,
+};
 use crate::iop::witness::{PartialWitness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
-use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
 use crate::plonk::config::GenericConfig;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBaseBatch};
 use crate::plonk::verifier::verify;
@@ -161,3 +165,42 @@ pub fn test_eval_fns<
     let proof = data.prove(pw)?;
     verify(proof, &data.verifier_only, &data.common)
 }
+
+/// Tests that the generators of `circuit` populate the same witness from `inputs` whether they run
+/// in waves, as when proving, or one at a time with the sequential worklist.
+pub fn test_witness_generation<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    circuit: &CircuitData<F, C, D>,
+    inputs: PartialWitness<F>,
+) -> Result<()> {
+    let witness = generate_partial_witness(inputs.clone(), &circuit.prover_only, &circuit.common)?;
+    let sequential_witness =
+        generate_partial_witness_sequentially(inputs, &circuit.prover_only, &circuit.common)?;
+
+    // `RandomValueGenerator`s set different values in each run, so their targets aren't compared.
+    let mut random_values = GeneratedValues::empty();
+    for generator in &circuit.prover_only.generators {
+        if generator.id() == "RandomValueGenerator" {
+            generator.run(&witness, &mut random_values);
+        }
+    }
+    let random_reps = random_values
+        .target_values
+        .iter()
+        .map(|&(t, _)| witness.representative_map[witness.target_index(t)])
+        .collect::<BTreeSet<_>>();
+    ensure!(
+        witness
+            .values
+            .iter()
+            .zip(&sequential_witness.values)
+            .enumerate()
+            .all(|(rep, (value, sequential_value))| value == sequential_value
+                || random_reps.contains(&rep)),
+        "The waves and the sequential worklist generated different witnesses"
+    );
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use anyhow::{bail, Result};
+
+use crate::field::extension::Extendable;
+use crate::gadgets::lookup::LookupGenerator;
+use crate::gates::gate::Gate;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::WitnessGenerator;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBaseBatch};
+use crate::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES};
+
+/// The number of slots summed by each helper polynomial of the lookup argument. It is also the
+/// degree of the lookup constraints, which the selectors of the lookup gates must leave room for.
+pub const LOOKUP_DEGREE: usize = 4;
+
+/// The number of slots of the `LookupGate`s and `LookupTableGate`s of circuits with the given
+/// config.
+pub(crate) fn num_lookup_slots(config: &CircuitConfig) -> usize {
+    (config.num_routed_wires / 2).min(config.num_wires / 3)
+}
+
+/// A gate holding `num_slots` pairs `(input, output)` looked up in a table, whose index is the
+/// constant of the gate.
+///
+/// The gate has no constraints of its own: the lookup argument checks that each pair is an entry
+/// of the `LookupTableGate`s of the same table. The outputs are set by a `LookupGenerator`, which
+/// finds the table in the witness.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LookupGate {
+    pub num_slots: usize,
+}
+
+impl LookupGate {
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_slots: num_lookup_slots(config),
+        }
+    }
+
+    pub const fn wire_input(i: usize) -> usize {
+        2 * i
+    }
+
+    pub const fn wire_output(i: usize) -> usize {
+        2 * i + 1
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.num_slots)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let num_slots = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self { num_slots })
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
+        bail!("{} has no Circom template.", Gate::<F, D>::id(self))
+    }
+    fn export_solidity_verification_code(&self) -> Result<String> {
+        bail!("{} has no Solidity library.", Gate::<F, D>::id(self))
+    }
+
+    fn eval_unfiltered(&self, _vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        Vec::new()
+    }
+
+    fn eval_unfiltered_base_batch(&self, _vars: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        Vec::new()
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        Vec::new()
+    }
+
+    fn generators(&self, row: usize, local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        vec![Box::new(LookupGenerator {
+            row,
+            num_slots: self.num_slots,
+            table_index: local_constants[0].to_canonical_u64() as usize,
+        })]
+    }
+
+    fn num_wires(&self) -> usize {
+        2 * self.num_slots
+    }
+
+    fn num_constants(&self) -> usize {
+        1
+    }
+
+    fn degree(&self) -> usize {
+        LOOKUP_DEGREE
+    }
+
+    fn num_constraints(&self) -> usize {
+        0
+    }
+
+    fn num_ops(&self) -> usize {
+        self.num_slots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate::Gate;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::lookup::LookupGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = LookupGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate)
+    }
+
+    #[test]
+    fn eval_fns() -> anyhow::Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = LookupGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+
+    #[test]
+    fn verifier_export_is_unsupported() {
+        const D: usize = 2;
+        type F = GoldilocksField;
+        let gate = LookupGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        assert!(Gate::<F, D>::export_circom_verification_code(&gate).is_err());
+        assert!(Gate::<F, D>::export_solidity_verification_code(&gate).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use anyhow::{bail, Result};
+
+use crate::field::extension::Extendable;
+use crate::gates::gate::Gate;
+use crate::gates::lookup::{num_lookup_slots, LookupGate, LOOKUP_DEGREE};
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::WitnessGenerator;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBaseBatch};
+use crate::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES};
+
+/// The `(input, output)` entries of a lookup table.
+pub type LookupTable = Arc<Vec<(u16, u16)>>;
+
+/// A gate holding `num_slots` entries `(input, output)` of a lookup table, whose index is the
+/// constant of the gate, along with the number of lookups of each entry.
+///
+/// The inputs and outputs are laid out like the ones of a `LookupGate`, and are copy constrained to
+/// the constants of the table. Like the `LookupGate`, this gate has no constraints of its own.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LookupTableGate {
+    pub num_slots: usize,
+}
+
+impl LookupTableGate {
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_slots: num_lookup_slots(config),
+        }
+    }
+
+    pub const fn wire_input(i: usize) -> usize {
+        LookupGate::wire_input(i)
+    }
+
+    pub const fn wire_output(i: usize) -> usize {
+        LookupGate::wire_output(i)
+    }
+
+    /// The number of lookups of the `i`th entry. It is not routed.
+    pub const fn wire_multiplicity(&self, i: usize) -> usize {
+        2 * self.num_slots + i
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupTableGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.num_slots)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let num_slots = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self { num_slots })
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
+        bail!("{} has no Circom template.", Gate::<F, D>::id(self))
+    }
+    fn export_solidity_verification_code(&self) -> Result<String> {
+        bail!("{} has no Solidity library.", Gate::<F, D>::id(self))
+    }
+
+    fn eval_unfiltered(&self, _vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        Vec::new()
+    }
+
+    fn eval_unfiltered_base_batch(&self, _vars: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        Vec::new()
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        Vec::new()
+    }
+
+    fn generators(&self, _row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        Vec::new()
+    }
+
+    fn num_wires(&self) -> usize {
+        3 * self.num_slots
+    }
+
+    fn num_constants(&self) -> usize {
+        1
+    }
+
+    fn degree(&self) -> usize {
+        LOOKUP_DEGREE
+    }
+
+    fn num_constraints(&self) -> usize {
+        0
+    }
+}
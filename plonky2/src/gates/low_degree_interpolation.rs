@@ -5,6 +5,8 @@ use alloc::{format, vec};
 use core::marker::PhantomData;
 use core::ops::Range;
 
+use anyhow::Result;
+
 use crate::field::extension::algebra::PolynomialCoeffsAlgebra;
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::interpolation::interpolant;
@@ -22,6 +24,7 @@ use crate::iop::wire::Wire;
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES_BITS};
 
 /// One of the instantiations of `InterpolationGate`: all constraints are degree <= 2.
 /// The lower degree is a tradeoff for more gates (`eval_unfiltered_recursively` for
@@ -86,7 +89,16 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LowDegreeInter
         format!("{self:?}<D={D}>")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.subgroup_bits)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let subgroup_bits = src.read_usize_in(0..=MAX_GATE_WIRES_BITS)?;
+        Ok(Self::new(subgroup_bits))
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "template LowDegreeInterpolation$SUBGROUP_BITS() {{
   signal input constants[NUM_OPENINGS_CONSTANTS()][2];
@@ -194,9 +206,9 @@ function two_adic_subgroup(i) {{
         }
         template_str = template_str.replace("  $SET_SUBGROUP;\n", &*subgroup_str);
 
-        template_str
+        Ok(template_str)
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "library LowDegreeInterpolation$SUBGROUP_BITSLib {{
     using GoldilocksFieldLib for uint64;
@@ -299,7 +311,7 @@ function two_adic_subgroup(i) {{
         }
         template_str = template_str.replace("        $SET_SUBGROUP;\n", &*subgroup_str);
 
-        template_str
+        Ok(template_str)
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -511,7 +523,7 @@ function two_adic_subgroup(i) {{
 }
 
 #[derive(Debug)]
-struct InterpolationGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct InterpolationGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     gate: LowDegreeInterpolationGate<F, D>,
     _phantom: PhantomData<F>,
@@ -597,6 +609,25 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
         let evaluation_value_wires = self.gate.wires_evaluation_value().map(local_wire);
         out_buffer.set_ext_wires(evaluation_value_wires, evaluation_value);
     }
+
+    fn id(&self) -> String {
+        "LowDegreeInterpolationGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        self.gate.serialize(dst)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let gate = LowDegreeInterpolationGate::<F, D>::deserialize(src)?;
+        Ok(Self {
+            row,
+            gate,
+            _phantom: PhantomData,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -9,6 +9,8 @@ pub mod exponentiation;
 pub mod gate;
 pub mod high_degree_interpolation;
 pub mod interpolation;
+pub mod lookup;
+pub mod lookup_table;
 pub mod low_degree_interpolation;
 pub mod multiplication_extension;
 pub mod noop;
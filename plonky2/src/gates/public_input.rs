@@ -3,6 +3,8 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use core::ops::Range;
 
+use anyhow::Result;
+
 use crate::field::extension::Extendable;
 use crate::field::packed::PackedField;
 use crate::gates::gate::Gate;
@@ -16,6 +18,7 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::serialization::{Buffer, IoResult};
 
 /// A gate whose first four wires will be equal to a hash of public inputs.
 pub struct PublicInputGate;
@@ -31,8 +34,16 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for PublicInputGat
         "PublicInputGate".into()
     }
 
-    fn export_circom_verification_code(&self) -> String {
-        format!(
+    fn serialize(&self, _dst: &mut Vec<u8>) -> IoResult<()> {
+        Ok(())
+    }
+
+    fn deserialize(_src: &mut Buffer) -> IoResult<Self> {
+        Ok(Self)
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
+        Ok(format!(
             "template PublicInputGateLib() {{
   signal input constants[NUM_OPENINGS_CONSTANTS()][2];
   signal input wires[NUM_OPENINGS_WIRES()][2];
@@ -53,10 +64,10 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for PublicInputGat
     out[i] <== constraints[i];
   }}
 }}"
-        )
+        ))
     }
-    fn export_solidity_verification_code(&self) -> String {
-        format!(
+    fn export_solidity_verification_code(&self) -> Result<String> {
+        Ok(format!(
         "library PublicInputGateLib {{
     using GoldilocksExtLib for uint64[2];
     function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {{
@@ -68,7 +79,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for PublicInputGat
         }}
     }}
 }}"
-        )
+        ))
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -4,6 +4,7 @@ use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::marker::PhantomData;
 
+use anyhow::Result;
 use itertools::Itertools;
 
 use crate::field::extension::Extendable;
@@ -24,6 +25,9 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::serialization::{
+    Buffer, IoResult, Read, Write, MAX_GATE_WIRES, MAX_GATE_WIRES_BITS,
+};
 
 /// A gate for checking that a particular element of a list matches a given value.
 #[derive(Copy, Clone, Debug)]
@@ -122,7 +126,20 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for RandomAccessGa
         format!("{self:?}<D={D}>")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.bits)?;
+        dst.write_usize(self.num_copies)?;
+        dst.write_usize(self.num_extra_constants)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let bits = src.read_usize_in(0..=MAX_GATE_WIRES_BITS)?;
+        let num_copies = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        let num_extra_constants = src.read_usize_in(0..=MAX_GATE_WIRES)?;
+        Ok(Self::new(num_copies, bits, num_extra_constants))
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "template RandomAccessB$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTS() {{
   signal input constants[NUM_OPENINGS_CONSTANTS()][2];
@@ -185,9 +202,9 @@ function ra_wire_bit(i, copy) {{
             "$NUM_EXTRA_CONSTANTS",
             &*self.num_extra_constants.to_string(),
         );
-        template_str
+        Ok(template_str)
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "library RandomAccessB$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTSLib {{
     using GoldilocksExtLib for uint64[2];
@@ -244,7 +261,7 @@ function ra_wire_bit(i, copy) {{
             "$NUM_EXTRA_CONSTANTS",
             &*self.num_extra_constants.to_string(),
         );
-        template_str
+        Ok(template_str)
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -451,7 +468,7 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
 }
 
 #[derive(Debug)]
-struct RandomAccessGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct RandomAccessGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     gate: RandomAccessGate<F, D>,
     copy: usize,
@@ -501,6 +518,23 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
             set_local_wire(self.gate.wire_bit(i, copy), bit);
         }
     }
+
+    fn id(&self) -> String {
+        "RandomAccessGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.copy)?;
+        self.gate.serialize(dst)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let copy = src.read_usize()?;
+        let gate = RandomAccessGate::<F, D>::deserialize(src)?;
+        Ok(Self { row, gate, copy })
+    }
 }
 
 #[cfg(test)]
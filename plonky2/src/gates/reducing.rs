@@ -4,6 +4,8 @@ use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::ops::Range;
 
+use anyhow::Result;
+
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::gates::gate::Gate;
 use crate::gates::util::StridedConstraintConsumer;
@@ -14,6 +16,7 @@ use crate::iop::target::Target;
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES};
 
 /// Computes `sum alpha^i c_i` for a vector `c_i` of `num_coeffs` elements of the base field.
 #[derive(Debug, Clone)]
@@ -60,7 +63,16 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ReducingGate<D
         format!("{self:?}")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.num_coeffs)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let num_coeffs = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self::new(num_coeffs))
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "template Reducing$NUM_COEFFS() {{
   signal input constants[NUM_OPENINGS_CONSTANTS()][2];
@@ -96,9 +108,9 @@ function r_wires_accs_start(i, num_coeffs) {{
         template_str = template_str.replace("$NUM_COEFFS", &*self.num_coeffs.to_string());
         template_str = template_str.replace("$D", &*D.to_string());
 
-        template_str
+        Ok(template_str)
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         let mut template_str = format!(
             "library Reducing$NUM_COEFFSLib {{
     using GoldilocksFieldLib for uint64;
@@ -130,7 +142,7 @@ function r_wires_accs_start(i, num_coeffs) {{
 
         template_str = template_str.replace("$NUM_COEFFS", &*self.num_coeffs.to_string());
 
-        template_str
+        Ok(template_str)
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -238,7 +250,7 @@ function r_wires_accs_start(i, num_coeffs) {{
 }
 
 #[derive(Debug)]
-struct ReducingGenerator<const D: usize> {
+pub(crate) struct ReducingGenerator<const D: usize> {
     row: usize,
     gate: ReducingGate<D>,
 }
@@ -280,6 +292,21 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F> for Reduci
         }
         out_buffer.set_extension_target(output, acc);
     }
+
+    fn id(&self) -> String {
+        "ReducingGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.gate.num_coeffs)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let gate = ReducingGate::new(src.read_usize()?);
+        Ok(Self { row, gate })
+    }
 }
 
 #[cfg(test)]
@@ -3,3 +3,10 @@
 // // - BMI2 (for MULX and SHRX)
 // #[cfg(all(target_feature = "avx2", target_feature = "bmi2"))]
 // pub(crate) mod poseidon_goldilocks_avx2_bmi2;
+
+#[cfg(all(
+    feature = "vectorized_poseidon",
+    target_feature = "avx2",
+    target_feature = "bmi2"
+))]
+pub(crate) mod poseidon_goldilocks_avx2_bmi2;
@@ -32,23 +32,22 @@ const fn make_fused_round_constants() -> [u64; WIDTH * N_ROUNDS] {
     }
     res
 }
-const FUSED_ROUND_CONSTANTS: [u64; WIDTH * N_ROUNDS] = make_fused_round_constants();
-
-// This is the top row of the MDS matrix. Concretely, it's the MDS exps vector at the following
-// indices: [0, 11, ..., 1].
-static TOP_ROW_EXPS: [usize; 12] = [0, 10, 16, 3, 12, 8, 1, 5, 3, 0, 1, 0];
+static FUSED_ROUND_CONSTANTS: [u64; WIDTH * N_ROUNDS] = make_fused_round_constants();
 
 // * Compile-time checks *
 
-/// The MDS matrix multiplication ASM is specific to the MDS matrix below. We want this file to
-/// fail to compile if it has been changed.
+/// The overflow bounds of the MDS matrix multiplication are specific to the MDS matrix below. We
+/// want this file to fail to compile if it has been changed.
 #[allow(dead_code)]
 const fn check_mds_matrix() -> bool {
     // Can't == two arrays in a const_assert! (:
     let mut i = 0;
-    let wanted_matrix_exps = [0, 0, 1, 0, 3, 5, 1, 8, 12, 3, 16, 10];
+    let wanted_matrix_circ = [17, 15, 41, 16, 2, 28, 13, 13, 39, 18, 34, 20];
+    let wanted_matrix_diag = [8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
     while i < WIDTH {
-        if <GoldilocksField as Poseidon>::MDS_MATRIX_EXPS[i] != wanted_matrix_exps[i] {
+        if <GoldilocksField as Poseidon>::MDS_MATRIX_CIRC[i] != wanted_matrix_circ[i]
+            || <GoldilocksField as Poseidon>::MDS_MATRIX_DIAG[i] != wanted_matrix_diag[i]
+        {
             return false;
         }
         i += 1;
@@ -61,12 +60,16 @@ const_assert!(check_mds_matrix());
 /// i.e. max(MDS(state)) <= mds_matrix_inf_norm() * max(state).
 const fn mds_matrix_inf_norm() -> u64 {
     let mut cumul = 0;
+    let mut max_diag = 0;
     let mut i = 0;
     while i < WIDTH {
-        cumul += 1 << <GoldilocksField as Poseidon>::MDS_MATRIX_EXPS[i];
+        cumul += <GoldilocksField as Poseidon>::MDS_MATRIX_CIRC[i];
+        if <GoldilocksField as Poseidon>::MDS_MATRIX_DIAG[i] > max_diag {
+            max_diag = <GoldilocksField as Poseidon>::MDS_MATRIX_DIAG[i];
+        }
         i += 1;
     }
-    cumul
+    cumul + max_diag
 }
 
 /// Ensure that adding round constants to the low result of the MDS multiplication can never
@@ -167,9 +170,9 @@ unsafe fn const_layer(
 ) -> (__m256i, __m256i, __m256i) {
     let sign_bit = _mm256_set1_epi64x(i64::MIN);
     let round_const = (
-        _mm256_loadu_si256((&round_const_arr[0..4]).as_ptr().cast::<__m256i>()),
-        _mm256_loadu_si256((&round_const_arr[4..8]).as_ptr().cast::<__m256i>()),
-        _mm256_loadu_si256((&round_const_arr[8..12]).as_ptr().cast::<__m256i>()),
+        _mm256_loadu_si256(round_const_arr[0..4].as_ptr().cast::<__m256i>()),
+        _mm256_loadu_si256(round_const_arr[4..8].as_ptr().cast::<__m256i>()),
+        _mm256_loadu_si256(round_const_arr[8..12].as_ptr().cast::<__m256i>()),
     );
     let state_s = map3!(_mm256_xor_si256, state, rep sign_bit); // Shift by 2**63.
     let res_maybe_wrapped_s = map3!(_mm256_add_epi64, state_s, round_const);
@@ -191,8 +194,7 @@ unsafe fn const_layer(
     // XOR commutes with the addition below. Placing it here helps mask latency.
     let res_maybe_wrapped = map3!(_mm256_xor_si256, res_maybe_wrapped_s, rep sign_bit);
     // Add EPSILON = subtract ORDER.
-    let res = map3!(_mm256_add_epi64, res_maybe_wrapped, wraparound_adjustment);
-    res
+    map3!(_mm256_add_epi64, res_maybe_wrapped, wraparound_adjustment)
 }
 
 #[inline(always)]
@@ -288,8 +290,7 @@ unsafe fn add_small(
     let res_wrapped_s = map3!(_mm256_add_epi64, x_s, y);
     let mask = map3!(_mm256_cmpgt_epi32, x_s, res_wrapped_s);
     let wrapback_amt = map3!(_mm256_srli_epi64::<32>, mask); // EPSILON if overflowed else 0.
-    let res_s = map3!(_mm256_add_epi64, res_wrapped_s, wrapback_amt);
-    res_s
+    map3!(_mm256_add_epi64, res_wrapped_s, wrapback_amt)
 }
 
 #[inline(always)]
@@ -319,8 +320,7 @@ unsafe fn sub_tiny(
 ) -> (__m256i, __m256i, __m256i) {
     let res_wrapped_s = map3!(_mm256_sub_epi64, x_s, y);
     let mask = map3!(_mm256_cmpgt_epi32, res_wrapped_s, x_s);
-    let res_s = map3!(maybe_adj_sub, res_wrapped_s, mask);
-    res_s
+    map3!(maybe_adj_sub, res_wrapped_s, mask)
 }
 
 #[inline(always)]
@@ -334,8 +334,7 @@ unsafe fn reduce3(
     let lo1_s = sub_tiny(lo0_s, hi_hi0);
     let t1 = map3!(_mm256_mul_epu32, hi0, rep epsilon);
     let lo2_s = add_small(lo1_s, t1);
-    let lo2 = map3!(_mm256_xor_si256, lo2_s, rep sign_bit);
-    lo2
+    map3!(_mm256_xor_si256, lo2_s, rep sign_bit)
 }
 
 #[inline(always)]
@@ -347,8 +346,7 @@ unsafe fn sbox_layer_full(state: (__m256i, __m256i, __m256i)) -> (__m256i, __m25
     let state4 = reduce3(state4_unreduced);
     let state3 = reduce3(state3_unreduced);
     let state7_unreduced = mul3(state3, state4);
-    let state7 = reduce3(state7_unreduced);
-    state7
+    reduce3(state7_unreduced)
 }
 
 #[inline(always)]
@@ -438,279 +436,61 @@ unsafe fn mds_multiply_and_add_round_const_s(
     state: (__m256i, __m256i, __m256i),
     (base, index): (*const u64, usize),
 ) -> ((__m256i, __m256i, __m256i), (__m256i, __m256i, __m256i)) {
-    // TODO: Would it be faster to save the input to memory and do unaligned
-    //   loads instead of swizzling? It would reduce pressure on port 5 but it
-    //   would also have high latency (no store forwarding).
-    // TODO: Would it be faster to store the lo and hi inputs and outputs on one
-    //   vector? I.e., we currently operate on [lo(s[0]), lo(s[1]), lo(s[2]),
-    //   lo(s[3])] and [hi(s[0]), hi(s[1]), hi(s[2]), hi(s[3])] separately. Using
-    //   [lo(s[0]), lo(s[1]), hi(s[0]), hi(s[1])] and [lo(s[2]), lo(s[3]),
-    //   hi(s[2]), hi(s[3])] would save us a few swizzles but would also need more
-    //   registers.
-    // TODO: Plain-vanilla matrix-vector multiplication might also work. We take
-    //   one element of the input (a scalar), multiply a column by it, and
-    //   accumulate. It would require shifts by amounts loaded from memory, but
-    //   would eliminate all swizzles. The downside is that we can no longer
-    //   special-case MDS == 0 and MDS == 1, so we end up with more shifts.
-    // TODO: Building on the above: FMA? It has high latency (4 cycles) but we
-    //   have enough operands to mask it. The main annoyance will be conversion
-    //   to/from floating-point.
-    // TODO: Try taking the complex Fourier transform and doing the convolution
-    //   with elementwise Fourier multiplication. Alternatively, try a Fourier
-    //   transform modulo Q, such that the prime field fits the result without
-    //   wraparound (i.e. Q > 0x1_1536_fffe_eac9) and has fast multiplication/-
-    //   reduction.
-
-    // At the end of the matrix-vector multiplication r = Ms,
-    // - ymm3 holds r[0:4]
-    // - ymm4 holds r[4:8]
-    // - ymm5 holds r[8:12]
-    // - ymm6 holds r[2:6]
-    // - ymm7 holds r[6:10]
-    // - ymm8 holds concat(r[10:12], r[0:2])
-    // Note that there are duplicates. E.g. r[0] is represented by ymm3[0] and
-    // ymm8[2]. To obtain the final result, we must sum the duplicate entries:
-    //   ymm3[0:2] += ymm8[2:4]
-    //   ymm3[2:4] += ymm6[0:2]
-    //   ymm4[0:2] += ymm6[2:4]
-    //   ymm4[2:4] += ymm7[0:2]
-    //   ymm5[0:2] += ymm7[2:4]
-    //   ymm5[2:4] += ymm8[0:2]
-    // Thus, the final result resides in ymm3, ymm4, ymm5.
-
-    // WARNING: This code assumes that sum(1 << exp for exp in MDS_EXPS) * 0xffffffff fits in a
-    // u64. If this guarantee ceases to hold, then it will no longer be correct.
-    let (unreduced_lo0_s, unreduced_lo1_s, unreduced_lo2_s): (__m256i, __m256i, __m256i);
-    let (unreduced_hi0, unreduced_hi1, unreduced_hi2): (__m256i, __m256i, __m256i);
-    let epsilon = _mm256_set1_epi64x(0xffffffff);
-    asm!(
-        // Extract low 32 bits of the word
-        "vpand ymm9,  ymm14, ymm0",
-        "vpand ymm10, ymm14, ymm1",
-        "vpand ymm11, ymm14, ymm2",
-
-        "mov eax, 1",
-
-        // Fall through for MDS matrix multiplication on low 32 bits
-
-        // This is a GCC _local label_. For details, see
-        // https://doc.rust-lang.org/beta/unstable-book/library-features/asm.html#labels
-        // In short, the assembler makes sure to assign a unique name to replace `2:` with a unique
-        // name, so the label does not clash with any compiler-generated label. `2:` can appear
-        // multiple times; to disambiguate, we must refer to it as `2b` or `2f`, specifying the
-        // direction as _backward_ or _forward_.
-        "2:",
-        // NB: This block is run twice: once on the low 32 bits and once for the
-        // high 32 bits. The 32-bit -> 64-bit matrix multiplication is responsible
-        // for the majority of the instructions in this routine. By reusing them,
-        // we decrease the burden on instruction caches by over one third.
-
-        // 32-bit -> 64-bit MDS matrix multiplication
-        // The scalar loop goes:
-        //     for r in 0..WIDTH {
-        //         let mut res = 0u128;
-        //         for i in 0..WIDTH {
-        //             res += (state[(i + r) % WIDTH] as u128) << MDS_MATRIX_EXPS[i];
-        //         }
-        //         result[r] = reduce(res);
-        //     }
-        //
-        // Here, we swap the loops. Equivalent to:
-        //     let mut res = [0u128; WIDTH];
-        //     for i in 0..WIDTH {
-        //         let mds_matrix_exp = MDS_MATRIX_EXPS[i];
-        //         for r in 0..WIDTH {
-        //             res[r] += (state[(i + r) % WIDTH] as u128) << mds_matrix_exp;
-        //         }
-        //     }
-        //     for r in 0..WIDTH {
-        //         result[r] = reduce(res[r]);
-        //     }
-        //
-        // Notice that in the lower version, all iterations of the inner loop
-        // shift by the same amount. In vector, we perform multiple iterations of
-        // the loop at once, and vector shifts are cheaper when all elements are
-        // shifted by the same amount.
-        //
-        // We use a trick to avoid rotating the state vector many times. We
-        // have as input the state vector and the state vector rotated by one. We
-        // also have two accumulators: an unrotated one and one that's rotated by
-        // two. Rotations by three are achieved by matching an input rotated by
-        // one with an accumulator rotated by two. Rotations by four are free:
-        // they are done by using a different register.
-
-        // mds[0 - 0] = 0 not done; would be a move from in0 to ymm3
-        // ymm3 not set
-        // mds[0 - 4] = 12
-        "vpsllq ymm4, ymm9, 12",
-        // mds[0 - 8] = 3
-        "vpsllq ymm5, ymm9, 3",
-        // mds[0 - 2] = 16
-        "vpsllq ymm6, ymm9, 16",
-        // mds[0 - 6] = mds[0 - 10] = 1
-        "vpaddq ymm7, ymm9, ymm9",
-        // ymm8 not written
-        // ymm3 and ymm8 have not been written to, because those would be unnecessary
-        // copies. Implicitly, ymm3 := in0 and ymm8 := ymm7.
-
-        // ymm12 := [ymm9[1], ymm9[2], ymm9[3], ymm10[0]]
-        "vperm2i128 ymm13, ymm9, ymm10, 0x21",
-        "vshufpd    ymm12, ymm9, ymm13, 0x5",
-
-        // ymm3 and ymm8 are not read because they have not been written to
-        // earlier. Instead, the "current value" of ymm3 is read from ymm9 and the
-        // "current value" of ymm8 is read from ymm7.
-        // mds[4 - 0] = 3
-        "vpsllq ymm13, ymm10, 3",
-        "vpaddq ymm3,  ymm9, ymm13",
-        // mds[4 - 4] = 0
-        "vpaddq ymm4,  ymm4, ymm10",
-        // mds[4 - 8] = 12
-        "vpsllq ymm13, ymm10, 12",
-        "vpaddq ymm5,  ymm5, ymm13",
-        // mds[4 - 2] = mds[4 - 10] = 1
-        "vpaddq ymm13, ymm10, ymm10",
-        "vpaddq ymm6,  ymm6, ymm13",
-        "vpaddq ymm8,  ymm7, ymm13",
-        // mds[4 - 6] = 16
-        "vpsllq ymm13, ymm10, 16",
-        "vpaddq ymm7,  ymm7, ymm13",
-
-        // mds[1 - 0] = 0
-        "vpaddq ymm3,  ymm3, ymm12",
-        // mds[1 - 4] = 3
-        "vpsllq ymm13, ymm12, 3",
-        "vpaddq ymm4,  ymm4, ymm13",
-        // mds[1 - 8] = 5
-        "vpsllq ymm13, ymm12, 5",
-        "vpaddq ymm5,  ymm5, ymm13",
-        // mds[1 - 2] = 10
-        "vpsllq ymm13, ymm12, 10",
-        "vpaddq ymm6,  ymm6, ymm13",
-        // mds[1 - 6] = 8
-        "vpsllq ymm13, ymm12, 8",
-        "vpaddq ymm7,  ymm7, ymm13",
-        // mds[1 - 10] = 0
-        "vpaddq ymm8, ymm8, ymm12",
-
-        // ymm10 := [ymm10[1], ymm10[2], ymm10[3], ymm11[0]]
-        "vperm2i128 ymm13, ymm10, ymm11, 0x21",
-        "vshufpd    ymm10, ymm10, ymm13, 0x5",
-
-        // mds[8 - 0] = 12
-        "vpsllq ymm13, ymm11, 12",
-        "vpaddq ymm3,  ymm3, ymm13",
-        // mds[8 - 4] = 3
-        "vpsllq ymm13, ymm11, 3",
-        "vpaddq ymm4,  ymm4, ymm13",
-        // mds[8 - 8] = 0
-        "vpaddq ymm5,  ymm5, ymm11",
-        // mds[8 - 2] = mds[8 - 6] = 1
-        "vpaddq ymm13, ymm11, ymm11",
-        "vpaddq ymm6,  ymm6, ymm13",
-        "vpaddq ymm7,  ymm7, ymm13",
-        // mds[8 - 10] = 16
-        "vpsllq ymm13, ymm11, 16",
-        "vpaddq ymm8,  ymm8, ymm13",
-
-        // ymm9 := [ymm11[1], ymm11[2], ymm11[3], ymm9[0]]
-        "vperm2i128 ymm13, ymm11, ymm9, 0x21",
-        "vshufpd    ymm9,  ymm11, ymm13, 0x5",
-
-        // mds[5 - 0] = 5
-        "vpsllq ymm13, ymm10, 5",
-        "vpaddq ymm3,  ymm3, ymm13",
-        // mds[5 - 4] = 0
-        "vpaddq ymm4,  ymm4, ymm10",
-        // mds[5 - 8] = 3
-        "vpsllq ymm13, ymm10, 3",
-        "vpaddq ymm5,  ymm5, ymm13",
-        // mds[5 - 2] = 0
-        "vpaddq ymm6,  ymm6, ymm10",
-        // mds[5 - 6] = 10
-        "vpsllq ymm13, ymm10, 10",
-        "vpaddq ymm7,  ymm7, ymm13",
-        // mds[5 - 10] = 8
-        "vpsllq ymm13, ymm10, 8",
-        "vpaddq ymm8,  ymm8, ymm13",
-
-        // mds[9 - 0] = 3
-        "vpsllq ymm13, ymm9, 3",
-        "vpaddq ymm3,  ymm3, ymm13",
-        // mds[9 - 4] = 5
-        "vpsllq ymm13, ymm9, 5",
-        "vpaddq ymm4,  ymm4, ymm13",
-        // mds[9 - 8] = 0
-        "vpaddq ymm5,  ymm5, ymm9",
-        // mds[9 - 2] = 8
-        "vpsllq ymm13, ymm9, 8",
-        "vpaddq ymm6,  ymm6, ymm13",
-        // mds[9 - 6] = 0
-        "vpaddq ymm7,  ymm7, ymm9",
-        // mds[9 - 10] = 10
-        "vpsllq ymm13, ymm9, 10",
-        "vpaddq ymm8,  ymm8, ymm13",
-
-        // Rotate ymm6-ymm8 and add to the corresponding elements of ymm3-ymm5
-        "vperm2i128 ymm13, ymm8, ymm6, 0x21",
-        "vpaddq     ymm3,  ymm3, ymm13",
-        "vperm2i128 ymm13, ymm6, ymm7, 0x21",
-        "vpaddq     ymm4,  ymm4, ymm13",
-        "vperm2i128 ymm13, ymm7, ymm8, 0x21",
-        "vpaddq     ymm5,  ymm5, ymm13",
-
-        // If this is the first time we have run 2: (low 32 bits) then continue.
-        // If second time (high 32 bits), then jump to 3:.
-        "dec eax",
-        // Jump to the _local label_ (see above) `3:`. `f` for _forward_ specifies the direction.
-        "jnz 3f",
-
-        // Extract high 32 bits
-        "vpsrlq ymm9,  ymm0, 32",
-        "vpsrlq ymm10, ymm1, 32",
-        "vpsrlq ymm11, ymm2, 32",
-
-        // Need to move the low result from ymm3-ymm5 to ymm0-13 so it is not
-        // overwritten. Save three instructions by combining the move with the constant layer,
-        // which would otherwise be done in 3:. The round constants include the shift by 2**63, so
-        // the resulting ymm0,1,2 are also shifted by 2**63.
-        // It is safe to add the round constants here without checking for overflow. The values in
-        // ymm3,4,5 are guaranteed to be <= 0x11536fffeeac9. All round constants are < 2**64
-        // - 0x11536fffeeac9.
-        // WARNING: If this guarantee ceases to hold due to a change in the MDS matrix or round
-        // constants, then this code will no longer be correct.
-        "vpaddq ymm0, ymm3, [{base} + {index}]",
-        "vpaddq ymm1, ymm4, [{base} + {index} + 32]",
-        "vpaddq ymm2, ymm5, [{base} + {index} + 64]",
-
-        // MDS matrix multiplication, again. This time on high 32 bits.
-        // Jump to the _local label_ (see above) `2:`. `b` for _backward_ specifies the direction.
-        "jmp 2b",
-
-        // `3:` is a _local label_ (see above).
-        "3:",
-        // Just done the MDS matrix multiplication on high 32 bits.
-        // The high results are in ymm3, ymm4, ymm5.
-        // The low results (shifted by 2**63 and including the following constant layer) are in
-        // ymm0, ymm1, ymm2.
-        base = in(reg) base,
-        index = in(reg) index,
-        inout("ymm0") state.0 => unreduced_lo0_s,
-        inout("ymm1") state.1 => unreduced_lo1_s,
-        inout("ymm2") state.2 => unreduced_lo2_s,
-        out("ymm3") unreduced_hi0,
-        out("ymm4") unreduced_hi1,
-        out("ymm5") unreduced_hi2,
-        out("ymm6") _,out("ymm7") _, out("ymm8") _, out("ymm9") _,
-        out("ymm10") _, out("ymm11") _, out("ymm12") _, out("ymm13") _,
-        in("ymm14") epsilon,
-        out("rax") _,
-        options(pure, nomem, nostack),
+    // The matrix is circulant plus a diagonal, so the scalar loop
+    //     for r in 0..WIDTH {
+    //         let mut res = 0u128;
+    //         for i in 0..WIDTH {
+    //             res += (state[(i + r) % WIDTH] as u128) * MDS_MATRIX_CIRC[i];
+    //         }
+    //         res += (state[r] as u128) * MDS_MATRIX_DIAG[r];
+    //         result[r] = reduce(res);
+    //     }
+    // is a sum over `i` of the state rotated by `i`, times the constant MDS_MATRIX_CIRC[i]. The
+    // rotations are unaligned loads from a copy of the state followed by itself.
+    //   The low and high 32 bits of the words are multiplied separately, so that the products fit
+    // in 64 bits: the sums are at most mds_matrix_inf_norm() * 0xffffffff.
+    let mut doubled = [0u64; 2 * WIDTH];
+    for offset in [0, WIDTH] {
+        let ptr = doubled[offset..].as_mut_ptr().cast::<__m256i>();
+        _mm256_storeu_si256(ptr, state.0);
+        _mm256_storeu_si256(ptr.add(1), state.1);
+        _mm256_storeu_si256(ptr.add(2), state.2);
+    }
+
+    let mds_matrix_diag = <GoldilocksField as Poseidon>::MDS_MATRIX_DIAG;
+    let diag = (
+        _mm256_loadu_si256(mds_matrix_diag[0..4].as_ptr().cast()),
+        _mm256_loadu_si256(mds_matrix_diag[4..8].as_ptr().cast()),
+        _mm256_loadu_si256(mds_matrix_diag[8..12].as_ptr().cast()),
     );
-    (
-        (unreduced_lo0_s, unreduced_lo1_s, unreduced_lo2_s),
-        (unreduced_hi0, unreduced_hi1, unreduced_hi2),
-    )
+    let state_hi = map3!(_mm256_srli_epi64::<32>, state);
+    let mut lo = map3!(_mm256_mul_epu32, state, diag);
+    let mut hi = map3!(_mm256_mul_epu32, state_hi, diag);
+    for i in 0..WIDTH {
+        let circ = _mm256_set1_epi64x(<GoldilocksField as Poseidon>::MDS_MATRIX_CIRC[i] as i64);
+        let rotated = (
+            _mm256_loadu_si256(doubled[i..].as_ptr().cast()),
+            _mm256_loadu_si256(doubled[i + 4..].as_ptr().cast()),
+            _mm256_loadu_si256(doubled[i + 8..].as_ptr().cast()),
+        );
+        let rotated_hi = map3!(_mm256_srli_epi64::<32>, rotated);
+        let lo_term = map3!(_mm256_mul_epu32, rotated, rep circ);
+        let hi_term = map3!(_mm256_mul_epu32, rotated_hi, rep circ);
+        lo = map3!(_mm256_add_epi64, lo, lo_term);
+        hi = map3!(_mm256_add_epi64, hi, hi_term);
+    }
+
+    // It is safe to add the round constants to the low result without checking for overflow, see
+    // `check_round_const_bounds_mds`. The round constants include the shift by 2**63, so the
+    // result is also shifted by 2**63.
+    let round_constants = base.add(index / size_of::<u64>()).cast::<__m256i>();
+    let round_constants = (
+        _mm256_loadu_si256(round_constants),
+        _mm256_loadu_si256(round_constants.add(1)),
+        _mm256_loadu_si256(round_constants.add(2)),
+    );
+    let lo_s = map3!(_mm256_add_epi64, lo, round_constants);
+    (lo_s, hi)
 }
 
 #[inline(always)]
@@ -811,71 +591,12 @@ unsafe fn partial_round(
     let state0ab: __m128i = _mm256_castsi256_si128(state0);
     let mut state0a = _mm_cvtsi128_si64(state0ab) as u64;
 
-    // Zero the low quadword
-    let zero = _mm256_setzero_si256();
-    let state0bcd = _mm256_blend_epi32::<0x3>(state0, zero);
-
     // Scalar exponentiation
     state0a = sbox_partial(state0a);
 
-    let epsilon = _mm256_set1_epi64x(0xffffffff);
-    let (
-        (mut unreduced_lo0_s, mut unreduced_lo1_s, mut unreduced_lo2_s),
-        (mut unreduced_hi0, mut unreduced_hi1, mut unreduced_hi2),
-    ) = mds_multiply_and_add_round_const_s((state0bcd, state1, state2), round_constants);
-    asm!(
-        // Just done the MDS matrix multiplication on high 32 bits.
-        // The high results are in ymm3, ymm4, ymm5.
-        // The low results (shifted by 2**63) are in ymm0, ymm1, ymm2
-
-        // The MDS matrix multiplication was done with state[0] set to 0.
-        // We must:
-        //  1. propagate the vector product to state[0], which is stored in rdx.
-        //  2. offset state[1..12] by the appropriate multiple of rdx
-        //  3. zero the lowest quadword in the vector registers
-        "vmovq xmm12, {state0a}",
-        "vpbroadcastq ymm12, xmm12",
-        "vpsrlq ymm13, ymm12, 32",
-        "vpand ymm12, ymm14, ymm12",
-
-        // The current matrix-vector product goes not include state[0] as an input. (Imagine Mv
-        // multiplication where we've set the first element to 0.) Add the remaining bits now.
-        // TODO: This is a bit of an afterthought, which is why these constants are loaded 22
-        //   times... There's likely a better way of merging those results.
-        "vmovdqu ymm6, [{mds_matrix}]",
-        "vmovdqu ymm7, [{mds_matrix} + 32]",
-        "vmovdqu ymm8, [{mds_matrix} + 64]",
-        "vpsllvq ymm9, ymm13, ymm6",
-        "vpsllvq ymm10, ymm13, ymm7",
-        "vpsllvq ymm11, ymm13, ymm8",
-        "vpsllvq ymm6, ymm12, ymm6",
-        "vpsllvq ymm7, ymm12, ymm7",
-        "vpsllvq ymm8, ymm12, ymm8",
-        "vpaddq  ymm3, ymm9, ymm3",
-        "vpaddq  ymm4, ymm10, ymm4",
-        "vpaddq  ymm5, ymm11, ymm5",
-        "vpaddq  ymm0, ymm6, ymm0",
-        "vpaddq  ymm1, ymm7, ymm1",
-        "vpaddq  ymm2, ymm8, ymm2",
-        // Reduction required.
-
-        state0a = in(reg) state0a,
-        mds_matrix = in(reg) &TOP_ROW_EXPS,
-        inout("ymm0") unreduced_lo0_s,
-        inout("ymm1") unreduced_lo1_s,
-        inout("ymm2") unreduced_lo2_s,
-        inout("ymm3") unreduced_hi0,
-        inout("ymm4") unreduced_hi1,
-        inout("ymm5") unreduced_hi2,
-        out("ymm6") _,out("ymm7") _, out("ymm8") _, out("ymm9") _,
-        out("ymm10") _, out("ymm11") _, out("ymm12") _, out("ymm13") _,
-        in("ymm14") epsilon,
-        options(pure, nomem, preserves_flags, nostack),
-    );
-    mds_layer_reduce(
-        (unreduced_lo0_s, unreduced_lo1_s, unreduced_lo2_s),
-        (unreduced_hi0, unreduced_hi1, unreduced_hi2),
-    )
+    // Replace the low quadword
+    let state0 = _mm256_blend_epi32::<0x3>(state0, _mm256_set1_epi64x(state0a as i64));
+    mds_const_layers_full((state0, state1, state2), round_constants)
 }
 
 #[inline(always)]
@@ -884,8 +605,7 @@ unsafe fn full_round(
     round_constants: (*const u64, usize),
 ) -> (__m256i, __m256i, __m256i) {
     let state = sbox_layer_full(state);
-    let state = mds_const_layers_full(state, round_constants);
-    state
+    mds_const_layers_full(state, round_constants)
 }
 
 #[inline] // Called twice; permit inlining but don't _require_ it
@@ -893,8 +613,8 @@ unsafe fn half_full_rounds(
     mut state: (__m256i, __m256i, __m256i),
     start_round: usize,
 ) -> (__m256i, __m256i, __m256i) {
-    let base = (&FUSED_ROUND_CONSTANTS
-        [WIDTH * start_round..WIDTH * start_round + WIDTH * HALF_N_FULL_ROUNDS])
+    let base = FUSED_ROUND_CONSTANTS
+        [WIDTH * start_round..WIDTH * start_round + WIDTH * HALF_N_FULL_ROUNDS]
         .as_ptr();
 
     for i in 0..HALF_N_FULL_ROUNDS {
@@ -908,8 +628,8 @@ unsafe fn all_partial_rounds(
     mut state: (__m256i, __m256i, __m256i),
     start_round: usize,
 ) -> (__m256i, __m256i, __m256i) {
-    let base = (&FUSED_ROUND_CONSTANTS
-        [WIDTH * start_round..WIDTH * start_round + WIDTH * N_PARTIAL_ROUNDS])
+    let base = FUSED_ROUND_CONSTANTS
+        [WIDTH * start_round..WIDTH * start_round + WIDTH * N_PARTIAL_ROUNDS]
         .as_ptr();
 
     for i in 0..N_PARTIAL_ROUNDS {
@@ -921,17 +641,17 @@ unsafe fn all_partial_rounds(
 #[inline(always)]
 unsafe fn load_state(state: &[GoldilocksField; 12]) -> (__m256i, __m256i, __m256i) {
     (
-        _mm256_loadu_si256((&state[0..4]).as_ptr().cast::<__m256i>()),
-        _mm256_loadu_si256((&state[4..8]).as_ptr().cast::<__m256i>()),
-        _mm256_loadu_si256((&state[8..12]).as_ptr().cast::<__m256i>()),
+        _mm256_loadu_si256(state[0..4].as_ptr().cast::<__m256i>()),
+        _mm256_loadu_si256(state[4..8].as_ptr().cast::<__m256i>()),
+        _mm256_loadu_si256(state[8..12].as_ptr().cast::<__m256i>()),
     )
 }
 
 #[inline(always)]
 unsafe fn store_state(buf: &mut [GoldilocksField; 12], state: (__m256i, __m256i, __m256i)) {
-    _mm256_storeu_si256((&mut buf[0..4]).as_mut_ptr().cast::<__m256i>(), state.0);
-    _mm256_storeu_si256((&mut buf[4..8]).as_mut_ptr().cast::<__m256i>(), state.1);
-    _mm256_storeu_si256((&mut buf[8..12]).as_mut_ptr().cast::<__m256i>(), state.2);
+    _mm256_storeu_si256(buf[0..4].as_mut_ptr().cast::<__m256i>(), state.0);
+    _mm256_storeu_si256(buf[4..8].as_mut_ptr().cast::<__m256i>(), state.1);
+    _mm256_storeu_si256(buf[8..12].as_mut_ptr().cast::<__m256i>(), state.2);
 }
 
 #[inline]
@@ -950,32 +670,3 @@ pub unsafe fn poseidon(state: &[GoldilocksField; 12]) -> [GoldilocksField; 12] {
     store_state(&mut res, state);
     res
 }
-
-#[inline(always)]
-pub unsafe fn constant_layer(state_arr: &mut [GoldilocksField; WIDTH], round_ctr: usize) {
-    let state = load_state(state_arr);
-    let round_consts = &ALL_ROUND_CONSTANTS[WIDTH * round_ctr..][..WIDTH]
-        .try_into()
-        .unwrap();
-    let state = const_layer(state, round_consts);
-    store_state(state_arr, state);
-}
-
-#[inline(always)]
-pub unsafe fn sbox_layer(state_arr: &mut [GoldilocksField; WIDTH]) {
-    let state = load_state(state_arr);
-    let state = sbox_layer_full(state);
-    store_state(state_arr, state);
-}
-
-#[inline(always)]
-pub unsafe fn mds_layer(state: &[GoldilocksField; WIDTH]) -> [GoldilocksField; WIDTH] {
-    let state = load_state(state);
-    // We want to do an MDS layer without the constant layer.
-    // The FUSED_ROUND_CONSTANTS for the last round are all 0 (shifted by 2**63 as required).
-    let round_consts = FUSED_ROUND_CONSTANTS[WIDTH * (N_ROUNDS - 1)..].as_ptr();
-    let state = mds_const_layers_full(state, (round_consts, 0));
-    let mut res = [GoldilocksField::ZERO; 12];
-    store_state(&mut res, state);
-    res
-}
@@ -74,6 +74,12 @@ pub fn compress<F: RichField, P: PlonkyPermutation<F>>(x: HashOut<F>, y: HashOut
 /// Permutation that can be used in the sponge construction for an algebraic hash.
 pub trait PlonkyPermutation<F: RichField> {
     fn permute(input: [F; SPONGE_WIDTH]) -> [F; SPONGE_WIDTH];
+
+    /// Same as `permute`, possibly faster on targets with a SIMD implementation of the
+    /// permutation.
+    fn permute_vectorized(input: [F; SPONGE_WIDTH]) -> [F; SPONGE_WIDTH] {
+        Self::permute(input)
+    }
 }
 
 /// Hash a message without any padding step. Note that this can enable length-extension attacks.
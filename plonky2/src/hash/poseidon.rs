@@ -615,6 +615,14 @@ pub trait Poseidon: PrimeField64 {
         state
     }
 
+    /// Same as `poseidon`, but with the SIMD implementation of the whole permutation when the field
+    /// has one for the target and the `vectorized_poseidon` feature is enabled. Meant for loops of
+    /// independent permutations, like the proof-of-work search.
+    #[inline]
+    fn poseidon_vectorized(input: [Self; WIDTH]) -> [Self; WIDTH] {
+        Self::poseidon(input)
+    }
+
     // For testing only, to ensure that various tricks are correct.
     #[inline]
     fn partial_rounds_naive(state: &mut [Self; WIDTH], round_ctr: &mut usize) {
@@ -645,6 +653,10 @@ impl<F: RichField> PlonkyPermutation<F> for PoseidonPermutation {
     fn permute(input: [F; SPONGE_WIDTH]) -> [F; SPONGE_WIDTH] {
         F::poseidon(input)
     }
+
+    fn permute_vectorized(input: [F; SPONGE_WIDTH]) -> [F; SPONGE_WIDTH] {
+        F::poseidon_vectorized(input)
+    }
 }
 
 /// Poseidon hash function.
@@ -711,15 +723,12 @@ impl<F: RichField> AlgebraicHasher<F> for PoseidonHash {
 
 #[cfg(test)]
 pub(crate) mod test_helpers {
-    use crate::field::types::Field;
     use crate::hash::hashing::SPONGE_WIDTH;
     use crate::hash::poseidon::Poseidon;
 
-    pub(crate) fn check_test_vectors<F: Field>(
+    pub(crate) fn check_test_vectors<F: Poseidon>(
         test_vectors: Vec<([u64; SPONGE_WIDTH], [u64; SPONGE_WIDTH])>,
-    ) where
-        F: Poseidon,
-    {
+    ) {
         for (input_, expected_output_) in test_vectors.into_iter() {
             let mut input = [F::ZERO; SPONGE_WIDTH];
             for i in 0..SPONGE_WIDTH {
@@ -733,10 +742,7 @@ pub(crate) mod test_helpers {
         }
     }
 
-    pub(crate) fn check_consistency<F: Field>()
-    where
-        F: Poseidon,
-    {
+    pub(crate) fn check_consistency<F: Poseidon>() {
         let mut input = [F::ZERO; SPONGE_WIDTH];
         for i in 0..SPONGE_WIDTH {
             input[i] = F::from_canonical_u64(i as u64);
@@ -747,4 +753,23 @@ pub(crate) mod test_helpers {
             assert_eq!(output[i], output_naive[i]);
         }
     }
+
+    /// Compares the SIMD permutation with the scalar one, so only built when there is one.
+    #[cfg(all(
+        feature = "vectorized_poseidon",
+        any(
+            all(
+                target_arch = "x86_64",
+                target_feature = "avx2",
+                target_feature = "bmi2"
+            ),
+            all(target_arch = "aarch64", target_feature = "neon")
+        )
+    ))]
+    pub(crate) fn check_vectorized<F: Poseidon>() {
+        for _ in 0..16 {
+            let input = F::rand_array::<SPONGE_WIDTH>();
+            assert_eq!(F::poseidon_vectorized(input), F::poseidon(input));
+        }
+    }
 }
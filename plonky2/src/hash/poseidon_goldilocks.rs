@@ -251,6 +251,22 @@ impl Poseidon for GoldilocksField {
     //     }
     // }
 
+    #[cfg(all(feature="vectorized_poseidon", target_arch="x86_64", target_feature="avx2", target_feature="bmi2"))]
+    #[inline]
+    fn poseidon_vectorized(input: [Self; 12]) -> [Self; 12] {
+        unsafe {
+            crate::hash::arch::x86_64::poseidon_goldilocks_avx2_bmi2::poseidon(&input)
+        }
+    }
+
+    #[cfg(all(feature="vectorized_poseidon", target_arch="aarch64", target_feature="neon"))]
+    #[inline]
+    fn poseidon_vectorized(input: [Self; 12]) -> [Self; 12] {
+        unsafe {
+            crate::hash::arch::aarch64::poseidon_goldilocks_neon::poseidon(input)
+        }
+    }
+
     #[cfg(all(target_arch="aarch64", target_feature="neon"))]
     #[inline(always)]
     fn sbox_layer(state: &mut [Self; 12]) {
@@ -316,4 +332,20 @@ mod tests {
     fn consistency() {
         check_consistency::<F>();
     }
+
+    #[cfg(all(
+        feature = "vectorized_poseidon",
+        any(
+            all(
+                target_arch = "x86_64",
+                target_feature = "avx2",
+                target_feature = "bmi2"
+            ),
+            all(target_arch = "aarch64", target_feature = "neon")
+        )
+    ))]
+    #[test]
+    fn vectorized() {
+        crate::hash::poseidon::test_helpers::check_vectorized::<F>();
+    }
 }
@@ -1,8 +1,13 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
-use core::fmt::Debug;
+use core::fmt::{Debug, Display, Formatter};
 use core::marker::PhantomData;
 
+use maybe_rayon::*;
+
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
 use crate::hash::hash_types::RichField;
@@ -12,9 +17,20 @@ use crate::iop::wire::Wire;
 use crate::iop::witness::{PartialWitness, PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::GenericConfig;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
 /// Given a `PartitionWitness` that has only inputs set, populates the rest of the witness using the
 /// given set of generators.
+///
+/// The generators run in waves: a generator joins a wave once every target it watches is
+/// populated, and the generators of a wave run in parallel against the witness left by the previous
+/// waves. Their outputs are merged in the order of the wave, which only depends on the circuit, so
+/// the witness is the same as with the sequential worklist. Generators which are still unfinished
+/// afterwards, i.e. those which don't wait for their whole watch list like a `SimpleGenerator`
+/// does, are then run by that worklist.
+///
+/// Returns an error describing the generators which never finished, e.g. because a `set_target`
+/// call is missing for one of the targets they watch.
 pub(crate) fn generate_partial_witness<
     'a,
     F: RichField + Extendable<D>,
@@ -24,28 +40,158 @@ pub(crate) fn generate_partial_witness<
     inputs: PartialWitness<F>,
     prover_data: &'a ProverOnlyCircuitData<F, C, D>,
     common_data: &'a CommonCircuitData<F, D>,
-) -> PartitionWitness<'a, F> {
-    let config = &common_data.config;
+) -> Result<PartitionWitness<'a, F>, WitnessGenerationError> {
     let generators = &prover_data.generators;
     let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
+    let mut witness = initial_witness(inputs, prover_data, common_data);
+
+    // The number of distinct representatives watched by each generator which are still unset.
+    let mut num_missing_watches: Vec<usize> = generators
+        .par_iter()
+        .map(|generator| {
+            let mut reps = generator
+                .watch_list()
+                .into_iter()
+                .map(|t| witness.representative_map[witness.target_index(t)])
+                .filter(|&rep| witness.values[rep].is_none())
+                .collect::<Vec<_>>();
+            reps.sort_unstable();
+            reps.dedup();
+            reps.len()
+        })
+        .collect();
+
+    let mut generator_is_expired = vec![false; generators.len()];
+    let mut remaining_generators = generators.len();
+
+    let mut wave: Vec<usize> = (0..generators.len())
+        .filter(|&i| num_missing_watches[i] == 0)
+        .collect();
+    while !wave.is_empty() {
+        let outputs = wave
+            .par_iter()
+            .map(|&generator_idx| {
+                let mut buffer = GeneratedValues::empty();
+                let finished = generators[generator_idx].run(&witness, &mut buffer);
+                (finished, buffer)
+            })
+            .collect::<Vec<_>>();
+
+        let mut next_wave = Vec::new();
+        for (&generator_idx, (finished, buffer)) in wave.iter().zip(outputs) {
+            if finished {
+                generator_is_expired[generator_idx] = true;
+                remaining_generators -= 1;
+            }
+
+            // Each representative is set once, so the watchers' counts never underflow.
+            for (t, v) in buffer.target_values {
+                let Some(rep) = witness.set_target_returning_rep(t, v) else {
+                    continue;
+                };
+                for &watcher in generator_indices_by_watches.get(&rep).into_iter().flatten() {
+                    num_missing_watches[watcher] -= 1;
+                    if num_missing_watches[watcher] == 0 && !generator_is_expired[watcher] {
+                        next_wave.push(watcher);
+                    }
+                }
+            }
+        }
+        wave = next_wave;
+    }
 
+    if remaining_generators > 0 {
+        let pending = (0..generators.len())
+            .filter(|&i| !generator_is_expired[i])
+            .collect();
+        run_worklist(
+            &mut witness,
+            prover_data,
+            pending,
+            &mut generator_is_expired,
+            &mut remaining_generators,
+        );
+    }
+
+    if remaining_generators > 0 {
+        return Err(WitnessGenerationError::new(
+            &witness,
+            prover_data,
+            common_data,
+            &generator_is_expired,
+        ));
+    }
+
+    Ok(witness)
+}
+
+/// Same as `generate_partial_witness`, but runs all the generators with the sequential worklist,
+/// to check that the waves populate the same witness.
+#[cfg(any(feature = "gate_testing", test))]
+pub(crate) fn generate_partial_witness_sequentially<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> Result<PartitionWitness<'a, F>, WitnessGenerationError> {
+    let generators = &prover_data.generators;
+    let mut witness = initial_witness(inputs, prover_data, common_data);
+    let mut generator_is_expired = vec![false; generators.len()];
+    let mut remaining_generators = generators.len();
+    run_worklist(
+        &mut witness,
+        prover_data,
+        (0..generators.len()).collect(),
+        &mut generator_is_expired,
+        &mut remaining_generators,
+    );
+
+    if remaining_generators > 0 {
+        return Err(WitnessGenerationError::new(
+            &witness,
+            prover_data,
+            common_data,
+            &generator_is_expired,
+        ));
+    }
+
+    Ok(witness)
+}
+
+/// The witness of the circuit with only `inputs` set.
+fn initial_witness<'a, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> PartitionWitness<'a, F> {
     let mut witness = PartitionWitness::new(
-        config.num_wires,
+        common_data.config.num_wires,
         common_data.degree(),
         &prover_data.representative_map,
+        &prover_data.lookup_tables,
     );
 
     for (t, v) in inputs.target_values.into_iter() {
         witness.set_target(t, v);
     }
+    witness
+}
 
-    // Build a list of "pending" generators which are queued to be run. Initially, all generators
-    // are queued.
-    let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
-
-    // We also track a list of "expired" generators which have already returned false.
-    let mut generator_is_expired = vec![false; generators.len()];
-    let mut remaining_generators = generators.len();
+/// Runs the `pending` generators one at a time, queueing the generators watching the targets each
+/// run populates, until no generator makes progress.
+fn run_worklist<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    witness: &mut PartitionWitness<F>,
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    mut pending_generator_indices: Vec<usize>,
+    generator_is_expired: &mut [bool],
+    remaining_generators: &mut usize,
+) {
+    let generators = &prover_data.generators;
+    let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
 
     let mut buffer = GeneratedValues::empty();
 
@@ -58,10 +204,10 @@ pub(crate) fn generate_partial_witness<
                 continue;
             }
 
-            let finished = generators[generator_idx].run(&witness, &mut buffer);
+            let finished = generators[generator_idx].run(witness, &mut buffer);
             if finished {
                 generator_is_expired[generator_idx] = true;
-                remaining_generators -= 1;
+                *remaining_generators -= 1;
             }
 
             // Merge any generated values into our witness, and get a list of newly-populated
@@ -86,16 +232,155 @@ pub(crate) fn generate_partial_witness<
 
         pending_generator_indices = next_pending_generator_indices;
     }
+}
 
-    assert_eq!(
-        remaining_generators, 0,
-        "{} generators weren't run",
-        remaining_generators,
-    );
+/// A target watched by a stalled generator, which was never populated.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MissingTarget {
+    pub target: Target,
+    /// A wire copied to `target`, or `target` itself if it is a wire.
+    pub wire: Option<Wire>,
+    /// The ID of the gate in the row of `wire`.
+    pub gate: Option<String>,
+    /// The scope in which `target` was created, like `root > verify proof`.
+    pub scope: Option<String>,
+}
 
-    witness
+impl Display for MissingTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self.target)?;
+        if let Some(wire) = self.wire {
+            write!(f, " at row {}, wire {}", wire.row, wire.column)?;
+        }
+        if let Some(gate) = &self.gate {
+            write!(f, " of {gate}")?;
+        }
+        if let Some(scope) = &self.scope {
+            write!(f, ", created in `{scope}`")?;
+        }
+        Ok(())
+    }
+}
+
+/// A generator which never finished.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StalledGenerator {
+    /// The `Debug` representation of the generator.
+    pub generator: String,
+    /// The targets watched by the generator which were never populated. It is empty if the
+    /// generator was run with its whole watch list populated, but didn't finish.
+    pub missing_targets: Vec<MissingTarget>,
 }
 
+/// Why witness generation stalled: the generators which never finished, in the order of
+/// `ProverOnlyCircuitData::generators`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WitnessGenerationError {
+    pub stalled_generators: Vec<StalledGenerator>,
+}
+
+impl WitnessGenerationError {
+    /// The number of stalled generators shown by `Display`.
+    const MAX_DISPLAYED: usize = 16;
+
+    fn new<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+        witness: &PartitionWitness<F>,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+        generator_is_expired: &[bool],
+    ) -> Self {
+        let origins = &prover_data.target_origins;
+        let rep = |t: Target| witness.representative_map[witness.target_index(t)];
+
+        let stalled = generator_is_expired
+            .iter()
+            .enumerate()
+            .filter(|&(_, &expired)| !expired)
+            .map(|(i, _)| {
+                let mut missing = prover_data.generators[i].watch_list();
+                missing.retain(|&t| witness.values[rep(t)].is_none());
+                (i, missing)
+            })
+            .collect::<Vec<_>>();
+
+        // Virtual targets have no row, so find a routed wire in the partition of each of them.
+        // Non-routed wires are never copied, so they can be skipped.
+        let mut wires_by_rep = stalled
+            .iter()
+            .flat_map(|(_, missing)| missing)
+            .filter(|t| matches!(t, Target::VirtualTarget { .. }))
+            .map(|&t| (rep(t), None))
+            .collect::<BTreeMap<_, Option<Wire>>>();
+        for row in 0..witness.degree {
+            for column in 0..common_data.config.num_routed_wires {
+                let wire = Wire { row, column };
+                if let Some(slot @ None) = wires_by_rep.get_mut(&rep(Target::Wire(wire))) {
+                    *slot = Some(wire);
+                }
+            }
+        }
+
+        let stalled_generators = stalled
+            .into_iter()
+            .map(|(i, missing)| StalledGenerator {
+                generator: format!("{:?}", prover_data.generators[i]),
+                missing_targets: missing
+                    .into_iter()
+                    .map(|target| {
+                        let wire = match target {
+                            Target::Wire(wire) => Some(wire),
+                            Target::VirtualTarget { .. } => wires_by_rep[&rep(target)],
+                        };
+                        let gate = wire
+                            .and_then(|wire| origins.row_gates.get(wire.row))
+                            .map(|&gate| common_data.gates[gate].0.id());
+                        MissingTarget {
+                            target,
+                            wire,
+                            gate,
+                            scope: origins.scope(target).map(String::from),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self { stalled_generators }
+    }
+}
+
+impl Display for WitnessGenerationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} generators weren't run",
+            self.stalled_generators.len()
+        )?;
+        for stalled in self.stalled_generators.iter().take(Self::MAX_DISPLAYED) {
+            write!(f, "\n- {}", stalled.generator)?;
+            if stalled.missing_targets.is_empty() {
+                write!(
+                    f,
+                    "\n    didn't finish although its watch list is populated"
+                )?;
+            }
+            for missing in &stalled.missing_targets {
+                write!(f, "\n    waits for {missing}")?;
+            }
+        }
+        if self.stalled_generators.len() > Self::MAX_DISPLAYED {
+            write!(
+                f,
+                "\n... and {} more",
+                self.stalled_generators.len() - Self::MAX_DISPLAYED
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Implemented without the `std` feature too, so that `?` turns it into an `anyhow::Error`.
+impl core::error::Error for WitnessGenerationError {}
+
 /// A generator participates in the generation of the witness.
 pub trait WitnessGenerator<F: Field>: 'static + Send + Sync + Debug {
     /// Targets to be "watched" by this generator. Whenever a target in the watch list is populated,
@@ -106,6 +391,17 @@ pub trait WitnessGenerator<F: Field>: 'static + Send + Sync + Debug {
     /// flag is true, the generator will never be run again, otherwise it will be queued for another
     /// run next time a target in its watch list is populated.
     fn run(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) -> bool;
+
+    /// Identifies the type of the generator, so that a
+    /// [`WitnessGeneratorSerializer`](crate::util::serialization::generator_serialization::WitnessGeneratorSerializer)
+    /// can find how to deserialize it.
+    fn id(&self) -> String;
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()>;
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self>
+    where
+        Self: Sized;
 }
 
 /// Values generated by a generator invocation.
@@ -162,6 +458,15 @@ pub trait SimpleGenerator<F: Field>: 'static + Send + Sync + Debug {
 
     fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>);
 
+    /// See [`WitnessGenerator::id`].
+    fn id(&self) -> String;
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()>;
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self>
+    where
+        Self: Sized;
+
     fn adapter(self) -> SimpleGeneratorAdapter<F, Self>
     where
         Self: Sized,
@@ -192,6 +497,18 @@ impl<F: Field, SG: SimpleGenerator<F>> WitnessGenerator<F> for SimpleGeneratorAd
             false
         }
     }
+
+    fn id(&self) -> String {
+        self.inner.id()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        self.inner.serialize(dst)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        Ok(SG::deserialize(src)?.adapter())
+    }
 }
 
 /// A generator which copies one wire to another.
@@ -210,6 +527,24 @@ impl<F: Field> SimpleGenerator<F> for CopyGenerator {
         let value = witness.get_target(self.src);
         out_buffer.set_target(self.dst, value);
     }
+
+    fn id(&self) -> String {
+        "CopyGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target(self.src)?;
+        dst.write_target(self.dst)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let src_target = src.read_target()?;
+        let dst = src.read_target()?;
+        Ok(Self {
+            src: src_target,
+            dst,
+        })
+    }
 }
 
 /// A generator for including a random value
@@ -228,6 +563,19 @@ impl<F: Field> SimpleGenerator<F> for RandomValueGenerator {
         // let random_value = F::from_canonical_u64(0x8838327483783);
         out_buffer.set_target(self.target, random_value);
     }
+
+    fn id(&self) -> String {
+        "RandomValueGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target(self.target)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let target = src.read_target()?;
+        Ok(Self { target })
+    }
 }
 
 /// A generator for testing if a value equals zero
@@ -253,6 +601,21 @@ impl<F: Field> SimpleGenerator<F> for NonzeroTestGenerator {
 
         out_buffer.set_target(self.dummy, dummy_value);
     }
+
+    fn id(&self) -> String {
+        "NonzeroTestGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target(self.to_test)?;
+        dst.write_target(self.dummy)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let to_test = src.read_target()?;
+        let dummy = src.read_target()?;
+        Ok(Self { to_test, dummy })
+    }
 }
 
 /// Generator used to fill an extra constant.
@@ -270,7 +633,7 @@ impl<F: Field> ConstantGenerator<F> {
     }
 }
 
-impl<F: Field> SimpleGenerator<F> for ConstantGenerator<F> {
+impl<F: RichField> SimpleGenerator<F> for ConstantGenerator<F> {
     fn dependencies(&self) -> Vec<Target> {
         vec![]
     }
@@ -278,4 +641,89 @@ impl<F: Field> SimpleGenerator<F> for ConstantGenerator<F> {
     fn run_once(&self, _witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
         out_buffer.set_target(Target::wire(self.row, self.wire_index), self.constant);
     }
+
+    fn id(&self) -> String {
+        "ConstantGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.constant_index)?;
+        dst.write_usize(self.wire_index)?;
+        dst.write_field(self.constant)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let constant_index = src.read_usize()?;
+        let wire_index = src.read_usize()?;
+        let constant = src.read_field()?;
+        Ok(Self {
+            row,
+            constant_index,
+            wire_index,
+            constant,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+
+    use crate::field::types::Field;
+    use crate::iop::generator::{generate_partial_witness, WitnessGenerationError};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn stalled_generators_are_reported() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        builder.push_context(Level::Debug, "multiply");
+        let z = builder.mul(x, y);
+        builder.pop_context();
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        // `x` is never set.
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(y, F::TWO);
+        let err = generate_partial_witness(inputs.clone(), &data.prover_only, &data.common)
+            .err()
+            .unwrap();
+
+        let missing_product_inputs = err
+            .stalled_generators
+            .iter()
+            .flat_map(|stalled| &stalled.missing_targets)
+            .filter(|missing| {
+                missing
+                    .gate
+                    .as_ref()
+                    .is_some_and(|gate| gate.starts_with("ArithmeticGate"))
+            })
+            .collect::<Vec<_>>();
+        assert!(!missing_product_inputs.is_empty());
+        for missing in missing_product_inputs {
+            assert!(missing.wire.is_some());
+            assert_eq!(missing.scope.as_deref(), Some("root > multiply"));
+        }
+        assert!(err.to_string().contains("generators weren't run"));
+
+        // The prover keeps the error, rather than its message.
+        let prove_err = data.prove(inputs).unwrap_err();
+        assert_eq!(
+            prove_err.downcast_ref::<WitnessGenerationError>(),
+            Some(&err)
+        );
+    }
 }
@@ -5,10 +5,12 @@ use hashbrown::HashMap;
 use itertools::Itertools;
 use maybe_rayon::{MaybeIntoParIter, MaybeParChunksMut, MaybeParIter, ParallelIterator};
 
+use crate::backend::buffer_pool::{BufferPool, PooledBuffer};
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::types::Field;
 use crate::fri::structure::{FriOpenings, FriOpeningsTarget};
 use crate::fri::witness_util::set_fri_proof_target;
+use crate::gadgets::lookup::ProverLookupTable;
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
@@ -246,7 +248,8 @@ pub trait Witness<F: Field>: WitnessWrite<F> {
 #[derive(Clone, Debug)]
 pub struct MatrixWitness<F: Field> {
     pub(crate) wire_values: Vec<Vec<F>>,
-    pub my_wire_values: Vec<F>,
+    /// The wire values, one column after the other, as computed by `my_full_witness`.
+    pub my_wire_values: PooledBuffer<F>,
     pub degree: usize,
 }
 
@@ -300,15 +303,23 @@ pub struct PartitionWitness<'a, F: Field> {
     pub representative_map: &'a [usize],
     pub num_wires: usize,
     pub degree: usize,
+    /// The lookup tables of the circuit, which the lookup generators refer to by index.
+    pub lookup_tables: &'a [ProverLookupTable],
 }
 
 impl<'a, F: Field> PartitionWitness<'a, F> {
-    pub fn new(num_wires: usize, degree: usize, representative_map: &'a [usize]) -> Self {
+    pub fn new(
+        num_wires: usize,
+        degree: usize,
+        representative_map: &'a [usize],
+        lookup_tables: &'a [ProverLookupTable],
+    ) -> Self {
         Self {
             values: vec![None; representative_map.len()],
             representative_map,
             num_wires,
             degree,
+            lookup_tables,
         }
     }
 
@@ -345,11 +356,16 @@ impl<'a, F: Field> PartitionWitness<'a, F> {
             }
         }
 
-        MatrixWitness { wire_values, my_wire_values: vec![], degree: 0 }
+        MatrixWitness { wire_values, my_wire_values: PooledBuffer::default(), degree: 0 }
     }
 
     pub fn my_full_witness(self) -> MatrixWitness<F> {
-        let mut my_wire_values = vec![F::ZERO; self.degree * self.num_wires];
+        self.my_full_witness_in(None)
+    }
+
+    /// Like `my_full_witness`, but takes the buffer of the wire values from `pool`, if any.
+    pub fn my_full_witness_in(self, pool: Option<&BufferPool<F>>) -> MatrixWitness<F> {
+        let mut my_wire_values = PooledBuffer::take_from(pool, self.degree * self.num_wires);
         my_wire_values.par_chunks_mut(self.degree).enumerate().for_each(|(j, values)| {
             for i in 0..self.degree {
                 let t = Target::Wire(Wire { row: i, column: j });
@@ -9,6 +9,7 @@ extern crate alloc;
 #[doc(inline)]
 pub use plonky2_field as field;
 
+pub mod backend;
 pub mod fri;
 pub mod gadgets;
 pub mod gates;
@@ -0,0 +1,212 @@
+//! Proves many witnesses of the same circuit together.
+//!
+//! Each witness gets its own [`Prover`], and the provers run each round side by side, so that the
+//! FFTs and Merkle trees of the proofs are spread over the threads together. The per-circuit
+//! values, such as the quotient domain, the FFT twiddles and the constants at its points, are
+//! computed once for the whole batch. The proofs are the same as the ones `prove` gives for the
+//! same witnesses.
+//!
+//! The provers hold all the commitments of their proofs until the last round, so the witnesses
+//! are proven in chunks of one proof per thread.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use anyhow::Result;
+use maybe_rayon::*;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::generate_partial_witness;
+use crate::iop::witness::{PartialWitness, PartitionWitness};
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::GenericConfig;
+use crate::plonk::proof::ProofWithPublicInputs;
+use crate::plonk::prover::QuotientDomain;
+use crate::plonk::prover_rounds::{Prover, ProverRound};
+use crate::timed;
+use crate::util::timing::TimingTree;
+
+/// The rounds run for each proof of a batch.
+const ROUNDS: [ProverRound; 5] = [
+    ProverRound::CommitWires,
+    ProverRound::CommitZsPartialProducts,
+    ProverRound::CommitQuotient,
+    ProverRound::Open,
+    ProverRound::Fri,
+];
+
+/// Generates the witness of each of `inputs` and proves them, returning the proofs in the same
+/// order.
+pub fn prove_batch<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: &[PartialWitness<F>],
+    timing: &mut TimingTree,
+) -> Result<Vec<ProofWithPublicInputs<F, C, D>>>
+where
+    C::Hasher: Send + Sync,
+{
+    let witnesses = timed!(
+        timing,
+        &format!("generate {} witnesses", inputs.len()),
+        inputs
+            .par_iter()
+            .map(|inputs| {
+                generate_partial_witness(inputs.clone(), prover_data, common_data)
+                    .map_err(anyhow::Error::from)
+            })
+            .collect::<Result<Vec<_>>>()
+    )?;
+    prove_batch_from_witnesses(prover_data, common_data, witnesses, timing)
+}
+
+/// Proves each of `witnesses`, returning the proofs in the same order.
+pub fn prove_batch_from_witnesses<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    witnesses: Vec<PartitionWitness<F>>,
+    timing: &mut TimingTree,
+) -> Result<Vec<ProofWithPublicInputs<F, C, D>>>
+where
+    C::Hasher: Send + Sync,
+{
+    prove_batch_in_chunks(
+        prover_data,
+        common_data,
+        witnesses,
+        current_num_threads(),
+        timing,
+    )
+}
+
+/// Proves `witnesses` `chunk_size` at a time, sharing the per-circuit values between the chunks.
+fn prove_batch_in_chunks<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    witnesses: Vec<PartitionWitness<F>>,
+    chunk_size: usize,
+    timing: &mut TimingTree,
+) -> Result<Vec<ProofWithPublicInputs<F, C, D>>>
+where
+    C::Hasher: Send + Sync,
+{
+    let domain = timed!(
+        timing,
+        "compute quotient domain",
+        QuotientDomain::new(common_data).with_local_constants(prover_data, common_data)
+    );
+
+    let mut proofs = Vec::with_capacity(witnesses.len());
+    let mut witnesses = witnesses.into_iter().peekable();
+    while witnesses.peek().is_some() {
+        let chunk = witnesses.by_ref().take(chunk_size).collect::<Vec<_>>();
+        let num_proofs = chunk.len();
+
+        // The timings of the proofs overlap, so only the rounds of the whole chunk are timed.
+        let mut provers = timed!(
+            timing,
+            &format!("compute {num_proofs} full witnesses"),
+            chunk
+                .into_par_iter()
+                .map(|witness| {
+                    Prover::new(
+                        prover_data,
+                        common_data,
+                        witness,
+                        &mut TimingTree::default(),
+                        &mut None,
+                    )
+                    .with_quotient_domain(&domain)
+                })
+                .collect::<Vec<_>>()
+        );
+        for round in ROUNDS {
+            timed!(
+                timing,
+                &format!("run round {round:?} of {num_proofs} proofs"),
+                provers.par_iter_mut().try_for_each(|prover| {
+                    prover.run_round(&mut TimingTree::default(), &mut None)
+                })
+            )?;
+        }
+        for prover in provers {
+            proofs.push(prover.into_proof()?);
+        }
+    }
+    Ok(proofs)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::batch_prover::{prove_batch_from_witnesses, prove_batch_in_chunks};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::util::timing::TimingTree;
+
+    #[test]
+    fn batch_matches_sequential_proofs() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.exp_u64(x, 3);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let inputs = (1..4)
+            .map(|i| {
+                let mut inputs = PartialWitness::new();
+                inputs.set_target(x, F::from_canonical_u64(i));
+                inputs
+            })
+            .collect::<Vec<_>>();
+        for proof in data.prove_batch(&inputs)? {
+            data.verify(proof)?;
+        }
+
+        // Fix the random wires, so that the batch gives the same proofs as `prove`.
+        let witnesses = inputs
+            .into_iter()
+            .map(|inputs| data.record_witness(inputs))
+            .collect::<Result<Vec<_>>>()?;
+        let expected = witnesses
+            .iter()
+            .map(|witness| data.prove_from_witness(witness.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let partition_witnesses = witnesses
+            .into_iter()
+            .map(|witness| witness.into_partition_witness(&data.prover_only, &data.common))
+            .collect::<Result<Vec<_>>>()?;
+        let proofs = prove_batch_from_witnesses(
+            &data.prover_only,
+            &data.common,
+            partition_witnesses.clone(),
+            &mut TimingTree::default(),
+        )?;
+        assert_eq!(proofs, expected);
+        assert_eq!(proofs[1].public_inputs, vec![F::from_canonical_u64(8)]);
+
+        let proofs = prove_batch_in_chunks(
+            &data.prover_only,
+            &data.common,
+            partition_witnesses,
+            2,
+            &mut TimingTree::default(),
+        )?;
+        assert_eq!(proofs, expected);
+        Ok(())
+    }
+}
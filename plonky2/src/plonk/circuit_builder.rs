@@ -1,5 +1,6 @@
 use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::max;
@@ -10,6 +11,7 @@ use std::sync::Arc;
 #[cfg(feature = "std")]
 use std::time::Instant;
 
+use anyhow::{ensure, Result};
 use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
 use log::{debug, info, Level};
@@ -24,11 +26,14 @@ use crate::fri::oracle::PolynomialBatch;
 use crate::fri::{FriConfig, FriParams};
 use crate::gadgets::arithmetic::BaseArithmeticOperation;
 use crate::gadgets::arithmetic_extension::ExtensionArithmeticOperation;
+use crate::gadgets::lookup::{LookupTableGenerator, ProverLookupTable};
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::gates::arithmetic_base::ArithmeticGate;
 use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
 use crate::gates::constant::ConstantGate;
 use crate::gates::gate::{CurrentSlot, Gate, GateInstance, GateRef};
+use crate::gates::lookup::LookupGate;
+use crate::gates::lookup_table::{LookupTable, LookupTableGate};
 use crate::gates::noop::NoopGate;
 use crate::gates::public_input::PublicInputGate;
 use crate::gates::selectors::selector_polynomials;
@@ -43,12 +48,13 @@ use crate::iop::target::{BoolTarget, Target};
 use crate::iop::wire::Wire;
 use crate::plonk::circuit_data::{
     CircuitConfig, CircuitData, CommonCircuitData, ProverCircuitData, ProverOnlyCircuitData,
-    VerifierCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
+    TargetOrigins, VerifierCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
 };
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::copy_constraint::CopyConstraint;
 use crate::plonk::permutation_argument::Forest;
 use crate::plonk::plonk_common::PlonkOracle;
+use crate::plonk::security::SecurityPolicy;
 use crate::timed;
 use crate::util::context_tree::ContextTree;
 use crate::util::partial_products::num_partial_products;
@@ -83,6 +89,16 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     /// A tree of named scopes, used for debugging.
     context_log: ContextTree,
 
+    /// The scope in which each row and virtual target was created. The gates of the rows are only
+    /// filled in by `build`.
+    target_origins: TargetOrigins,
+
+    /// The index of each scope within `target_origins.scopes`.
+    scope_indices: HashMap<String, usize>,
+
+    /// The index of the currently open scope within `target_origins.scopes`.
+    current_scope: usize,
+
     /// Generators used to generate the witness.
     generators: Vec<Box<dyn WitnessGenerator<F>>>,
 
@@ -109,11 +125,20 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     /// Optional verifier data that is registered as public inputs.
     /// This is used in cyclic recursion to hold the circuit's own verifier key.
     pub(crate) verifier_data_public_input: Option<VerifierCircuitTarget>,
+
+    /// What `build` does when the circuit falls short of `config.security_bits`.
+    security_policy: SecurityPolicy,
+
+    /// The tables registered by `add_lookup_table`.
+    pub(crate) lookup_tables: Vec<LookupTable>,
+
+    /// The inputs looked up in each table, which `build` counts to fill in the multiplicities.
+    pub(crate) lookups: Vec<Vec<Target>>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     pub fn new(config: CircuitConfig) -> Self {
-        let builder = CircuitBuilder {
+        let mut builder = CircuitBuilder {
             config,
             domain_separator: None,
             gates: HashSet::new(),
@@ -122,6 +147,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             virtual_target_index: 0,
             copy_constraints: Vec::new(),
             context_log: ContextTree::new(),
+            target_origins: TargetOrigins::default(),
+            scope_indices: HashMap::new(),
+            current_scope: 0,
             generators: Vec::new(),
             constants_to_targets: HashMap::new(),
             targets_to_constants: HashMap::new(),
@@ -131,8 +159,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             constant_generators: Vec::new(),
             goal_common_data: None,
             verifier_data_public_input: None,
+            security_policy: SecurityPolicy::default(),
+            lookup_tables: Vec::new(),
+            lookups: Vec::new(),
         };
         builder.check_config();
+        builder.update_current_scope();
         builder
     }
 
@@ -164,6 +196,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.domain_separator = Some(separator);
     }
 
+    /// Sets what `build` does when the security of the circuit, as computed by
+    /// [`SecurityReport`](crate::plonk::security::SecurityReport), is below `config.security_bits`.
+    pub fn set_security_policy(&mut self, policy: SecurityPolicy) {
+        self.security_policy = policy;
+    }
+
     pub fn num_gates(&self) -> usize {
         self.gate_instances.len()
     }
@@ -189,6 +227,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     pub fn add_virtual_target(&mut self) -> Target {
         let index = self.virtual_target_index;
         self.virtual_target_index += 1;
+        self.target_origins
+            .virtual_target_scopes
+            .push(self.current_scope);
         Target::VirtualTarget { index }
     }
 
@@ -309,6 +350,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             gate_ref,
             constants,
         });
+        self.target_origins.row_scopes.push(self.current_scope);
 
         row
     }
@@ -484,10 +526,43 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
     pub fn push_context(&mut self, level: log::Level, ctx: &str) {
         self.context_log.push(ctx, level, self.num_gates());
+        self.update_current_scope();
     }
 
     pub fn pop_context(&mut self) {
         self.context_log.pop(self.num_gates());
+        self.update_current_scope();
+    }
+
+    /// Points `current_scope` to the stack of contexts which are open, adding it to the scopes of
+    /// `target_origins` if it wasn't seen before.
+    fn update_current_scope(&mut self) {
+        let scope = self.context_log.open_stack();
+        self.current_scope = match self.scope_indices.get(&scope) {
+            Some(&index) => index,
+            None => {
+                let index = self.target_origins.scopes.len();
+                self.target_origins.scopes.push(scope.clone());
+                self.scope_indices.insert(scope, index);
+                index
+            }
+        };
+    }
+
+    /// Takes the origins of the targets, filling in the gate of each row as an index in `gates`.
+    fn take_target_origins(&mut self, gates: &[GateRef<F, D>]) -> TargetOrigins {
+        let gate_indices = gates
+            .iter()
+            .enumerate()
+            .map(|(i, gate)| (gate, i))
+            .collect::<HashMap<_, _>>();
+        let mut target_origins = core::mem::take(&mut self.target_origins);
+        target_origins.row_gates = self
+            .gate_instances
+            .iter()
+            .map(|inst| gate_indices[&inst.gate_ref])
+            .collect();
+        target_origins
     }
 
     /// Find an available slot, of the form `(row, op)` for gate `G` using parameters `params`
@@ -591,6 +666,73 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         }
     }
 
+    /// Lookups are not supported with zero knowledge, as the polynomials of the lookup argument
+    /// aren't blinded.
+    fn check_lookups_supported(&self) -> Result<()> {
+        ensure!(
+            !self.config.zero_knowledge || self.lookup_tables.is_empty(),
+            "Lookups are not supported with zero knowledge"
+        );
+        Ok(())
+    }
+
+    /// Fills the free slots of the `LookupGate`s with lookups of the first entry of their table, then
+    /// adds the `LookupTableGate`s of each table which is looked up, along with the generator of
+    /// their multiplicities.
+    fn fill_lookups(&mut self) {
+        if self.lookups.iter().all(|lookups| lookups.is_empty()) {
+            return;
+        }
+
+        let lookup_gate = LookupGate::new_from_config(&self.config);
+        let incomplete_rows = self
+            .current_slots
+            .get(&GateRef::new(lookup_gate))
+            .map(|slots| {
+                slots
+                    .current_slot
+                    .iter()
+                    .map(|(params, &(row, slot))| (row, slot, params[0]))
+                    .sorted_by_key(|&(row, _, _)| row)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        for (_, slot, table_index) in incomplete_rows {
+            let table_index = table_index.to_canonical_u64() as usize;
+            let input = self.constant(F::from_canonical_u16(self.lookup_tables[table_index][0].0));
+            for _ in slot..lookup_gate.num_slots {
+                self.add_lookup(table_index, input);
+            }
+        }
+
+        let table_gate = LookupTableGate::new_from_config(&self.config);
+        let lookups = core::mem::take(&mut self.lookups);
+        for (table_index, lookups) in lookups.into_iter().enumerate() {
+            if lookups.is_empty() {
+                continue;
+            }
+            let table = self.lookup_tables[table_index].clone();
+            let mut multiplicities = Vec::new();
+            for entries in table.chunks(table_gate.num_slots) {
+                let row = self.add_gate(table_gate, vec![F::from_canonical_usize(table_index)]);
+                for slot in 0..table_gate.num_slots {
+                    // The last row is padded with the first entry, which is never looked up there.
+                    let (input, output) = entries.get(slot).copied().unwrap_or(table[0]);
+                    let input = self.constant(F::from_canonical_u16(input));
+                    let output = self.constant(F::from_canonical_u16(output));
+                    self.connect(input, Target::wire(row, LookupTableGate::wire_input(slot)));
+                    self.connect(output, Target::wire(row, LookupTableGate::wire_output(slot)));
+                    multiplicities.push(Target::wire(row, table_gate.wire_multiplicity(slot)));
+                }
+            }
+            self.add_simple_generator(LookupTableGenerator {
+                table_index,
+                lookups,
+                multiplicities,
+            });
+        }
+    }
+
     fn blind_and_pad(&mut self) {
         if self.config.zero_knowledge {
             self.blind();
@@ -762,13 +904,25 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     }
 
     /// Builds a "full circuit", with both prover and verifier data.
-    pub fn build<C: GenericConfig<D, F = F>>(mut self) -> CircuitData<F, C, D> {
+    ///
+    /// Panics if the circuit is not supported; see [`Self::try_build`].
+    pub fn build<C: GenericConfig<D, F = F>>(self) -> CircuitData<F, C, D> {
+        self.try_build().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Builds a "full circuit", with both prover and verifier data. Fails if the circuit has lookup
+    /// tables and `config.zero_knowledge` is set, as the polynomials of the lookup argument aren't
+    /// blinded, or if its security is too low under [`SecurityPolicy::Fail`].
+    pub fn try_build<C: GenericConfig<D, F = F>>(mut self) -> Result<CircuitData<F, C, D>> {
         let mut timing = TimingTree::new("preprocess", Level::Trace);
         #[cfg(feature = "std")]
         let start = Instant::now();
         let rate_bits = self.config.fri_config.rate_bits;
         let cap_height = self.config.fri_config.cap_height;
 
+        self.check_lookups_supported()?;
+        self.fill_lookups();
+
         // Hash the public inputs, and route them to a `PublicInputGate` which will enforce that
         // those hash wires match the claimed public inputs.
         let num_public_inputs = self.public_inputs.len();
@@ -831,6 +985,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let mut gates = self.gates.iter().cloned().collect::<Vec<_>>();
         // Gates need to be sorted by their degrees (and ID to make the ordering deterministic) to compute the selector polynomials.
         gates.sort_unstable_by_key(|g| (g.0.degree(), g.0.id()));
+        let target_origins = self.take_target_origins(&gates);
         let (mut constant_vecs, selectors_info) =
             selector_polynomials(&gates, &self.gate_instances, quotient_degree_factor + 1);
         constant_vecs.extend(self.constant_polys());
@@ -912,6 +1067,13 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let num_partial_products =
             num_partial_products(self.config.num_routed_wires, quotient_degree_factor);
 
+        let lookup_gate = LookupGate::new_from_config(&self.config);
+        let num_lookup_slots = if gates.contains(&GateRef::new(lookup_gate)) {
+            lookup_gate.num_slots
+        } else {
+            0
+        };
+
         let constants_sigmas_cap = constants_sigmas_commitment.merkle_tree.cap.clone();
         let domain_separator = self.domain_separator.unwrap_or_default();
         let domain_separator_digest = C::Hasher::hash_pad(&domain_separator);
@@ -937,7 +1099,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             num_public_inputs,
             k_is,
             num_partial_products,
+            num_lookup_slots,
         };
+        self.security_policy.check(&common)?;
         if let Some(goal_data) = self.goal_common_data {
             assert_eq!(goal_data, common, "The expected circuit data passed to cyclic recursion method did not match the actual circuit");
         }
@@ -953,6 +1117,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             fft_root_table: Some(fft_root_table_max),
             fft_root_table_deg,
             circuit_digest,
+            target_origins,
+            lookup_tables: self
+                .lookup_tables
+                .into_iter()
+                .map(ProverLookupTable::new)
+                .collect(),
         };
 
         let verifier_only = VerifierOnlyCircuitData {
@@ -963,20 +1133,29 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         timing.print();
         #[cfg(feature = "std")]
         debug!("Building circuit took {}s", start.elapsed().as_secs_f32());
-        CircuitData {
+        Ok(CircuitData {
             prover_only,
             verifier_only,
             common,
-        }
+        })
+    }
+
+    /// Panics if the circuit is not supported; see [`Self::try_my_build`].
+    pub fn my_build<C: GenericConfig<D, F = F>>(self) -> CircuitData<F, C, D> {
+        self.try_my_build().unwrap_or_else(|e| panic!("{e}"))
     }
 
-    pub fn my_build<C: GenericConfig<D, F = F>>(mut self) -> CircuitData<F, C, D> {
+    /// Same as [`Self::try_build`], for `my_build`.
+    pub fn try_my_build<C: GenericConfig<D, F = F>>(mut self) -> Result<CircuitData<F, C, D>> {
         let mut timing = TimingTree::new("preprocess", Level::Trace);
         #[cfg(feature = "std")]
             let start = Instant::now();
         let rate_bits = self.config.fri_config.rate_bits;
         let cap_height = self.config.fri_config.cap_height;
 
+        self.check_lookups_supported()?;
+        self.fill_lookups();
+
         // Hash the public inputs, and route them to a `PublicInputGate` which will enforce that
         // those hash wires match the claimed public inputs.
         let num_public_inputs = self.public_inputs.len();
@@ -1039,6 +1218,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let mut gates = self.gates.iter().cloned().collect::<Vec<_>>();
         // Gates need to be sorted by their degrees (and ID to make the ordering deterministic) to compute the selector polynomials.
         gates.sort_unstable_by_key(|g| (g.0.degree(), g.0.id()));
+        let target_origins = self.take_target_origins(&gates);
         let (mut constant_vecs, selectors_info) =
             selector_polynomials(&gates, &self.gate_instances, quotient_degree_factor + 1);
         // constant_vecs.extend(self.constant_polys());
@@ -1234,6 +1414,13 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let num_partial_products =
             num_partial_products(self.config.num_routed_wires, quotient_degree_factor);
 
+        let lookup_gate = LookupGate::new_from_config(&self.config);
+        let num_lookup_slots = if gates.contains(&GateRef::new(lookup_gate)) {
+            lookup_gate.num_slots
+        } else {
+            0
+        };
+
         let constants_sigmas_cap = constants_sigmas_commitment.merkle_tree.cap.clone();
         let domain_separator = self.domain_separator.unwrap_or_default();
         let domain_separator_digest = C::Hasher::hash_pad(&domain_separator);
@@ -1258,7 +1445,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             num_public_inputs,
             k_is,
             num_partial_products,
+            num_lookup_slots,
         };
+        self.security_policy.check(&common)?;
         if let Some(goal_data) = self.goal_common_data {
             assert_eq!(goal_data, common, "The expected circuit data passed to cyclic recursion method did not match the actual circuit");
         }
@@ -1274,6 +1463,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             fft_root_table: Some(fft_root_table_max),
             fft_root_table_deg,
             circuit_digest,
+            target_origins,
+            lookup_tables: self
+                .lookup_tables
+                .into_iter()
+                .map(ProverLookupTable::new)
+                .collect(),
         };
 
         let verifier_only = VerifierOnlyCircuitData {
@@ -1284,11 +1479,11 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         timing.print();
         #[cfg(feature = "std")]
         debug!("Building circuit took {}s", start.elapsed().as_secs_f32());
-        CircuitData {
+        Ok(CircuitData {
             prover_only,
             verifier_only,
             common,
-        }
+        })
     }
 
     /// Builds a "prover circuit", with data needed to generate proofs but not verify them.
@@ -1,11 +1,16 @@
 use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
-use core::ops::{Range, RangeFrom};
+use core::mem::size_of;
+use core::ops::Range;
+use core::time::Duration;
 
 use anyhow::Result;
+use maybe_rayon::*;
 
+use crate::backend::ProverLayout;
 use crate::field::extension::Extendable;
 use crate::field::fft::FftRootTable;
 use crate::field::types::Field;
@@ -16,21 +21,32 @@ use crate::fri::structure::{
     FriPolynomialInfo,
 };
 use crate::fri::{FriConfig, FriParams};
+use crate::gadgets::lookup::ProverLookupTable;
 use crate::gates::gate::GateRef;
+use crate::gates::lookup::{LookupGate, LOOKUP_DEGREE};
+use crate::gates::lookup_table::LookupTableGate;
 use crate::gates::selectors::SelectorsInfo;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
+use crate::hash::hashing::SPONGE_RATE;
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::generator::WitnessGenerator;
 use crate::iop::target::Target;
 use crate::iop::witness::PartialWitness;
+use crate::plonk::batch_prover::prove_batch;
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::{GenericConfig, Hasher};
-use crate::plonk::plonk_common::PlonkOracle;
+use crate::plonk::mock_prover::{check_witness, ConstraintViolation};
+use crate::plonk::plonk_common::{salt_size, PlonkOracle};
 use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
-use crate::plonk::prover::prove;
+use crate::plonk::prover::{prove, prove_from_witness};
+use crate::plonk::recorded_witness::RecordedWitness;
 use crate::plonk::verifier::verify;
+use crate::util::serialization::gate_serialization::GateSerializer;
+use crate::util::serialization::generator_serialization::WitnessGeneratorSerializer;
+use crate::util::serialization::{Buffer, IoError, IoResult, Read, Remaining, Write};
 use crate::util::timing::TimingTree;
+use crate::util::{ceil_div_usize, log2_ceil, transpose};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CircuitConfig {
@@ -124,6 +140,46 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         )
     }
 
+    /// Proves each of `inputs`, sharing the per-circuit work between the proofs. See
+    /// [`prove_batch`].
+    pub fn prove_batch(
+        &self,
+        inputs: &[PartialWitness<F>],
+    ) -> Result<Vec<ProofWithPublicInputs<F, C, D>>>
+    where
+        C::Hasher: Send + Sync,
+    {
+        prove_batch(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Generates the witness from `inputs` and returns the constraints it violates, without
+    /// proving anything. See [`check_witness`].
+    pub fn check_witness(&self, inputs: PartialWitness<F>) -> Result<Vec<ConstraintViolation>> {
+        check_witness(&self.prover_only, &self.common, inputs)
+    }
+
+    /// Generates the witness from `inputs`, to be proven later with `prove_from_witness`.
+    pub fn record_witness(&self, inputs: PartialWitness<F>) -> Result<RecordedWitness<F>> {
+        RecordedWitness::generate(&self.prover_only, &self.common, inputs)
+    }
+
+    pub fn prove_from_witness(
+        &self,
+        witness: RecordedWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        prove_from_witness(
+            &self.prover_only,
+            &self.common,
+            witness.into_partition_witness(&self.prover_only, &self.common)?,
+            &mut TimingTree::default(),
+        )
+    }
+
     pub fn verify(&self, proof_with_pis: ProofWithPublicInputs<F, C, D>) -> Result<()> {
         verify(proof_with_pis, &self.verifier_only, &self.common)
     }
@@ -135,6 +191,29 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         compressed_proof_with_pis.verify(&self.verifier_only, &self.common)
     }
 
+    pub fn to_bytes(
+        &self,
+        gate_serializer: &GateSerializer<F, D>,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        buffer.write_circuit_data(self, gate_serializer, generator_serializer)?;
+        Ok(buffer)
+    }
+
+    pub fn from_bytes(
+        bytes: Vec<u8>,
+        gate_serializer: &GateSerializer<F, D>,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let circuit_data = buffer.read_circuit_data(gate_serializer, generator_serializer)?;
+        if !buffer.is_empty() {
+            return Err(IoError);
+        }
+        Ok(circuit_data)
+    }
+
     pub fn compress(
         &self,
         proof: ProofWithPublicInputs<F, C, D>,
@@ -201,6 +280,46 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             &mut TimingTree::default(),
         )
     }
+
+    /// Proves each of `inputs`, sharing the per-circuit work between the proofs. See
+    /// [`prove_batch`].
+    pub fn prove_batch(
+        &self,
+        inputs: &[PartialWitness<F>],
+    ) -> Result<Vec<ProofWithPublicInputs<F, C, D>>>
+    where
+        C::Hasher: Send + Sync,
+    {
+        prove_batch(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Generates the witness from `inputs` and returns the constraints it violates, without
+    /// proving anything. See [`check_witness`].
+    pub fn check_witness(&self, inputs: PartialWitness<F>) -> Result<Vec<ConstraintViolation>> {
+        check_witness(&self.prover_only, &self.common, inputs)
+    }
+
+    /// Generates the witness from `inputs`, to be proven later with `prove_from_witness`.
+    pub fn record_witness(&self, inputs: PartialWitness<F>) -> Result<RecordedWitness<F>> {
+        RecordedWitness::generate(&self.prover_only, &self.common, inputs)
+    }
+
+    pub fn prove_from_witness(
+        &self,
+        witness: RecordedWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        prove_from_witness(
+            &self.prover_only,
+            &self.common,
+            witness.into_partition_witness(&self.prover_only, &self.common)?,
+            &mut TimingTree::default(),
+        )
+    }
 }
 
 /// Circuit data required by the prover.
@@ -256,6 +375,82 @@ pub struct ProverOnlyCircuitData<
     /// A digest of the "circuit" (i.e. the instance, minus public inputs), which can be used to
     /// seed Fiat-Shamir.
     pub circuit_digest: <<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash,
+    /// Where the targets of the circuit were created, used to report stalled witness generation.
+    pub target_origins: TargetOrigins,
+    /// The tables registered by `CircuitBuilder::add_lookup_table`, in order.
+    pub lookup_tables: Vec<ProverLookupTable>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProverOnlyCircuitData<F, C, D>
+{
+    /// The constants of the gate in each row, i.e. the values on the subgroup of the constant
+    /// polynomials without the selector polynomials. The commitment's LDE is on a coset of the
+    /// subgroup, so they are recomputed from the coefficients of `constants_sigmas_commitment`.
+    pub fn row_constants(&self, common_data: &CommonCircuitData<F, D>) -> Vec<Vec<F>> {
+        let constants_range = common_data.selectors_info.num_selectors()..common_data.num_constants;
+        let constant_values = self.constants_sigmas_commitment.polynomials[constants_range]
+            .par_iter()
+            .map(|poly| poly.clone().fft().values)
+            .collect::<Vec<_>>();
+        if constant_values.is_empty() {
+            return vec![Vec::new(); common_data.degree()];
+        }
+        transpose(&constant_values)
+    }
+
+    pub fn to_bytes(
+        &self,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        buffer.write_prover_only_circuit_data(self, generator_serializer)?;
+        Ok(buffer)
+    }
+
+    pub fn from_bytes(
+        bytes: Vec<u8>,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let prover_data = buffer.read_prover_only_circuit_data(generator_serializer)?;
+        if !buffer.is_empty() {
+            return Err(IoError);
+        }
+        Ok(prover_data)
+    }
+}
+
+/// The gate of each row of a circuit, and the scope in which each row and virtual target was
+/// created. A scope is the stack of contexts opened with `CircuitBuilder::push_context` at the
+/// time, like `root > verify proof > verify FRI proof`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TargetOrigins {
+    /// The distinct scopes of the circuit.
+    pub scopes: Vec<String>,
+    /// The index, within `CommonCircuitData::gates`, of the gate of each row.
+    pub row_gates: Vec<usize>,
+    /// The index, within `scopes`, of the scope of each row.
+    pub row_scopes: Vec<usize>,
+    /// The index, within `scopes`, of the scope of each virtual target.
+    pub virtual_target_scopes: Vec<usize>,
+}
+
+impl TargetOrigins {
+    /// The scope in which `target` was created, if it is known.
+    pub fn scope(&self, target: Target) -> Option<&str> {
+        let scope = match target {
+            Target::Wire(wire) => return self.row_scope(wire.row),
+            Target::VirtualTarget { index } => self.virtual_target_scopes.get(index)?,
+        };
+        self.scopes.get(*scope).map(String::as_str)
+    }
+
+    /// The scope in which the gate of `row` was created, if it is known.
+    pub fn row_scope(&self, row: usize) -> Option<&str> {
+        let scope = self.row_scopes.get(row)?;
+        self.scopes.get(*scope).map(String::as_str)
+    }
 }
 
 /// Circuit data required by the verifier, but not the prover.
@@ -268,6 +463,25 @@ pub struct VerifierOnlyCircuitData<C: GenericConfig<D>, const D: usize> {
     pub circuit_digest: <<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
 }
 
+impl<C: GenericConfig<D>, const D: usize> VerifierOnlyCircuitData<C, D> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_verifier_only_circuit_data(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> IoResult<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let verifier_data = buffer.read_verifier_only_circuit_data()?;
+        if !buffer.is_empty() {
+            return Err(IoError);
+        }
+        Ok(verifier_data)
+    }
+}
+
 /// Circuit data required by both the prover and the verifier.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CommonCircuitData<F: RichField + Extendable<D>, const D: usize> {
@@ -297,9 +511,28 @@ pub struct CommonCircuitData<F: RichField + Extendable<D>, const D: usize> {
 
     /// The number of partial products needed to compute the `Z` polynomials.
     pub num_partial_products: usize,
+
+    /// The number of slots of the `LookupGate`s and `LookupTableGate`s, or 0 if the circuit has no
+    /// lookups.
+    pub num_lookup_slots: usize,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
+    pub fn to_bytes(&self, gate_serializer: &GateSerializer<F, D>) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        buffer.write_common_circuit_data(self, gate_serializer)?;
+        Ok(buffer)
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>, gate_serializer: &GateSerializer<F, D>) -> IoResult<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let common_data = buffer.read_common_circuit_data(gate_serializer)?;
+        if !buffer.is_empty() {
+            return Err(IoError);
+        }
+        Ok(common_data)
+    }
+
     pub const fn degree_bits(&self) -> usize {
         self.fri_params.degree_bits
     }
@@ -344,8 +577,61 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
     }
 
     /// Range of the partial products polynomials in the `zs_partial_products_commitment`.
-    pub fn partial_products_range(&self) -> RangeFrom<usize> {
-        self.config.num_challenges..
+    pub fn partial_products_range(&self) -> Range<usize> {
+        self.config.num_challenges..self.config.num_challenges * (1 + self.num_partial_products)
+    }
+
+    pub fn has_lookups(&self) -> bool {
+        self.num_lookup_slots > 0
+    }
+
+    /// The number of challenges of the lookup argument: a pair `(alpha, delta)` for each of the
+    /// `num_challenges` repetitions, or none if the circuit has no lookups.
+    pub(crate) fn num_lookup_challenges(&self) -> usize {
+        if self.has_lookups() {
+            2 * self.config.num_challenges
+        } else {
+            0
+        }
+    }
+
+    /// The number of helper polynomials of the lookup argument for each challenge.
+    pub(crate) fn num_lookup_helpers(&self) -> usize {
+        ceil_div_usize(self.num_lookup_slots, LOOKUP_DEGREE)
+    }
+
+    /// Range of the polynomials of the lookup argument in the `zs_partial_products_commitment`:
+    /// the running sum `Z_L` of each challenge, then the helpers of each challenge.
+    pub fn lookup_polys_range(&self) -> Range<usize> {
+        let start = self.partial_products_range().end;
+        let num_lookup_polys = if self.has_lookups() {
+            self.config.num_challenges * (1 + self.num_lookup_helpers())
+        } else {
+            0
+        };
+        start..start + num_lookup_polys
+    }
+
+    /// Range of the running sums `Z_L` of the lookup argument in the
+    /// `zs_partial_products_commitment`.
+    pub fn lookup_zs_range(&self) -> Range<usize> {
+        let start = self.lookup_polys_range().start;
+        let num_lookup_zs = if self.has_lookups() {
+            self.config.num_challenges
+        } else {
+            0
+        };
+        start..start + num_lookup_zs
+    }
+
+    /// The indices in `gates` of the `LookupGate` and of the `LookupTableGate`, if they are used.
+    pub(crate) fn lookup_gate_indices(&self) -> (Option<usize>, Option<usize>) {
+        let index = |gate: GateRef<F, D>| self.gates.iter().position(|g| *g == gate);
+        let num_slots = self.num_lookup_slots;
+        (
+            index(GateRef::new(LookupGate { num_slots })),
+            index(GateRef::new(LookupTableGate { num_slots })),
+        )
     }
 
     pub(crate) fn get_fri_instance(&self, zeta: F::Extension) -> FriInstanceInfo<F, D> {
@@ -355,7 +641,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
             polynomials: self.fri_all_polys(),
         };
 
-        // The Z polynomials are also opened at g * zeta.
+        // The Z polynomials, and the running sums of the lookup argument, are also opened at
+        // g * zeta.
         let g = F::Extension::primitive_root_of_unity(self.degree_bits());
         let zeta_next = g * zeta;
         let zeta_next_batch = FriBatchInfo {
@@ -381,7 +668,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
             polynomials: self.fri_all_polys(),
         };
 
-        // The Z polynomials are also opened at g * zeta.
+        // The Z polynomials, and the running sums of the lookup argument, are also opened at
+        // g * zeta.
         let g = F::primitive_root_of_unity(self.degree_bits());
         let zeta_next = builder.mul_const_extension(g, zeta);
         let zeta_next_batch = FriBatchInfoTarget {
@@ -441,11 +729,18 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
     }
 
     pub(crate) fn num_zs_partial_products_polys(&self) -> usize {
-        self.config.num_challenges * (1 + self.num_partial_products)
+        self.lookup_polys_range().end
     }
 
     fn fri_zs_polys(&self) -> Vec<FriPolynomialInfo> {
-        FriPolynomialInfo::from_range(PlonkOracle::ZS_PARTIAL_PRODUCTS.index, self.zs_range())
+        [
+            FriPolynomialInfo::from_range(PlonkOracle::ZS_PARTIAL_PRODUCTS.index, self.zs_range()),
+            FriPolynomialInfo::from_range(
+                PlonkOracle::ZS_PARTIAL_PRODUCTS.index,
+                self.lookup_zs_range(),
+            ),
+        ]
+        .concat()
     }
 
     fn fri_quotient_polys(&self) -> Vec<FriPolynomialInfo> {
@@ -467,6 +762,285 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
     }
 }
 
+/// The time taken by the basic operations of the prover, used by
+/// [`CommonCircuitData::estimate_proof`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OperationCosts {
+    /// The time of an FFT of size `n`, divided by `n log2(n)`, in nanoseconds.
+    pub fft_ns: f64,
+    /// The time of a single permutation of the hash, in nanoseconds.
+    pub permutation_ns: f64,
+    /// The time of `MerkleTree::new`, divided by the number of permutations it performs, in
+    /// nanoseconds. It is below `permutation_ns` on multicore machines.
+    pub merkle_permutation_ns: f64,
+    /// The time of evaluating a single constraint of the vanishing polynomial at a point, in
+    /// nanoseconds.
+    pub constraint_ns: f64,
+}
+
+impl Default for OperationCosts {
+    /// Rough costs on a recent multicore x86 machine.
+    fn default() -> Self {
+        Self {
+            fft_ns: 1.0,
+            permutation_ns: 2000.0,
+            merkle_permutation_ns: 150.0,
+            constraint_ns: 2.0,
+        }
+    }
+}
+
+impl OperationCosts {
+    /// Measures the costs of FFTs, hashes and Merkle trees on the current machine.
+    /// `constraint_ns` is left to its default, as it depends on the gates.
+    #[cfg(feature = "std")]
+    pub fn calibrate<F: RichField, H: Hasher<F>>() -> Self {
+        use core::hint::black_box;
+        use std::time::Instant;
+
+        use crate::field::polynomial::PolynomialCoeffs;
+        use crate::hash::merkle_tree::MerkleTree;
+
+        const FFT_LOG_SIZE: usize = 14;
+        const FFT_REPETITIONS: usize = 16;
+        const PERMUTATIONS: usize = 1 << 12;
+        const MERKLE_LOG_LEAVES: usize = 12;
+        const MERKLE_LEAF_LEN: usize = 2 * SPONGE_RATE;
+
+        let poly = PolynomialCoeffs::new(F::rand_vec(1 << FFT_LOG_SIZE));
+        let start = Instant::now();
+        for _ in 0..FFT_REPETITIONS {
+            black_box(poly.clone().fft());
+        }
+        let fft_ns = start.elapsed().as_nanos() as f64
+            / (FFT_REPETITIONS * (FFT_LOG_SIZE << FFT_LOG_SIZE)) as f64;
+
+        let input = F::rand_vec(SPONGE_RATE);
+        let start = Instant::now();
+        for _ in 0..PERMUTATIONS {
+            black_box(H::hash_no_pad(black_box(&input)));
+        }
+        let permutation_ns = start.elapsed().as_nanos() as f64 / PERMUTATIONS as f64;
+
+        let leaves = (0..1 << MERKLE_LOG_LEAVES)
+            .map(|_| F::rand_vec(MERKLE_LEAF_LEN))
+            .collect();
+        let start = Instant::now();
+        black_box(MerkleTree::<F, H>::new(leaves, 0));
+        let merkle_permutation_ns = start.elapsed().as_nanos() as f64
+            / merkle_permutations(1 << MERKLE_LOG_LEAVES, MERKLE_LEAF_LEN, 0) as f64;
+
+        Self {
+            fft_ns,
+            permutation_ns,
+            merkle_permutation_ns,
+            ..Self::default()
+        }
+    }
+}
+
+/// The number of permutations needed to build a Merkle tree.
+fn merkle_permutations(num_leaves: usize, leaf_len: usize, cap_height: usize) -> usize {
+    // Leaves of at most 4 elements aren't hashed, see `Hasher::hash_or_noop`.
+    let leaf_permutations = if leaf_len <= 4 {
+        0
+    } else {
+        ceil_div_usize(leaf_len, SPONGE_RATE)
+    };
+    num_leaves * leaf_permutations + num_leaves - (1 << cap_height)
+}
+
+/// An estimate of the work and memory of one stage of the prover.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StageEstimate {
+    pub name: &'static str,
+    /// The size of the LDE of the stage's commitment.
+    pub lde_size: usize,
+    pub merkle_leaves: usize,
+    pub merkle_leaf_len: usize,
+    pub merkle_digests: usize,
+    /// The number of FFTs and inverse FFTs, over the base field.
+    pub ffts: usize,
+    /// The sum of `n log2(n)` over the FFTs, of size `n`.
+    pub fft_work: usize,
+    /// The number of permutations of the hash, building Merkle trees.
+    pub merkle_permutations: usize,
+    /// The number of other permutations of the hash, e.g. to grind the proof of work.
+    pub permutations: usize,
+    /// The number of evaluations of a constraint of the vanishing polynomial.
+    pub constraint_evaluations: usize,
+    /// The bytes the stage keeps until the end of the proof.
+    pub retained_bytes: usize,
+    /// The bytes the stage only needs while it runs.
+    pub transient_bytes: usize,
+    pub time: Duration,
+}
+
+impl StageEstimate {
+    fn commitment(
+        name: &'static str,
+        num_polys: usize,
+        degree_bits: usize,
+        rate_bits: usize,
+        salt_size: usize,
+        cap_height: usize,
+    ) -> Self {
+        let lde_bits = degree_bits + rate_bits;
+        let lde_size = 1 << lde_bits;
+        let merkle_leaf_len = num_polys + salt_size;
+        let cap_height = cap_height.min(lde_bits);
+        let merkle_digests = 2 * (lde_size - (1 << cap_height));
+        let words = (num_polys << degree_bits) + merkle_leaf_len * lde_size + merkle_digests * 4;
+        Self {
+            name,
+            lde_size,
+            merkle_leaves: lde_size,
+            merkle_leaf_len,
+            merkle_digests,
+            ffts: num_polys,
+            fft_work: num_polys * (lde_bits << lde_bits),
+            merkle_permutations: merkle_permutations(lde_size, merkle_leaf_len, cap_height),
+            retained_bytes: words * size_of::<u64>(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds `count` FFTs of size `2^log_size`.
+    fn add_ffts(&mut self, count: usize, log_size: usize) {
+        self.ffts += count;
+        self.fft_work += count * (log_size << log_size);
+    }
+
+    fn with_time(mut self, costs: &OperationCosts) -> Self {
+        let ns = self.fft_work as f64 * costs.fft_ns
+            + self.merkle_permutations as f64 * costs.merkle_permutation_ns
+            + self.permutations as f64 * costs.permutation_ns
+            + self.constraint_evaluations as f64 * costs.constraint_ns;
+        self.time = Duration::from_nanos(ns as u64);
+        self
+    }
+}
+
+/// An estimate of the time and memory a proof takes, see [`CommonCircuitData::estimate_proof`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofEstimate {
+    /// The stages of the prover: the preprocessed constants and sigmas commitment, which is part
+    /// of the circuit data, then the stages of a proof.
+    pub stages: Vec<StageEstimate>,
+    /// The peak memory of `prove`: everything the stages retain, plus the largest transient
+    /// allocation.
+    pub peak_host_bytes: usize,
+    /// The backend memory `my_prove` needs, plus the constants and sigmas leaves it keeps.
+    pub device_bytes: usize,
+    /// The time of the stages of the proof, witness generation excluded.
+    pub time: Duration,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
+    /// Estimates the work, time and memory of a proof of this circuit, given the costs of the
+    /// basic operations, e.g. as measured by `OperationCosts::calibrate`.
+    pub fn estimate_proof(&self, costs: &OperationCosts) -> ProofEstimate {
+        let config = &self.config;
+        let fri_config = &config.fri_config;
+        let degree_bits = self.degree_bits();
+        let rate_bits = fri_config.rate_bits;
+        let cap_height = fri_config.cap_height;
+        let lde_bits = degree_bits + rate_bits;
+        let salt = |oracle: PlonkOracle| salt_size(config.zero_knowledge && oracle.blinding);
+
+        let preprocessed = StageEstimate::commitment(
+            "constants_sigmas",
+            self.num_preprocessed_polys(),
+            degree_bits,
+            rate_bits,
+            salt(PlonkOracle::CONSTANTS_SIGMAS),
+            cap_height,
+        );
+
+        let mut wires = StageEstimate::commitment(
+            "wires",
+            config.num_wires,
+            degree_bits,
+            rate_bits,
+            salt(PlonkOracle::WIRES),
+            cap_height,
+        );
+        wires.add_ffts(config.num_wires, degree_bits);
+
+        let num_zs_partial_products = self.num_zs_partial_products_polys();
+        let mut zs_partial_products = StageEstimate::commitment(
+            "zs_partial_products",
+            num_zs_partial_products,
+            degree_bits,
+            rate_bits,
+            salt(PlonkOracle::ZS_PARTIAL_PRODUCTS),
+            cap_height,
+        );
+        zs_partial_products.add_ffts(num_zs_partial_products, degree_bits);
+
+        // The quotient polynomials are evaluated on an LDE of degree `quotient_degree_factor`,
+        // interpolated, then split into chunks of degree `degree`, which are committed to.
+        let quotient_degree_bits = log2_ceil(self.quotient_degree_factor);
+        let num_points = 1 << (degree_bits + quotient_degree_bits);
+        let num_vanishing_terms = self.num_gate_constraints
+            + config.num_challenges * (self.num_partial_products + 2)
+            + self.lookup_polys_range().len();
+        let mut quotient = StageEstimate::commitment(
+            "quotient",
+            self.num_quotient_polys(),
+            degree_bits,
+            rate_bits,
+            salt(PlonkOracle::QUOTIENT),
+            cap_height,
+        );
+        quotient.add_ffts(config.num_challenges, degree_bits + quotient_degree_bits);
+        quotient.constraint_evaluations = num_points * num_vanishing_terms;
+        quotient.transient_bytes = 2 * config.num_challenges * num_points * size_of::<u64>();
+
+        // FRI reduces all the polynomials into one, whose LDE, over the extension field, is
+        // committed to and folded round after round.
+        let mut fri = StageEstimate {
+            name: "fri",
+            lde_size: 1 << lde_bits,
+            ..StageEstimate::default()
+        };
+        fri.add_ffts(D, lde_bits);
+        let mut fri_words = D << lde_bits;
+        let mut values_bits = lde_bits;
+        for &arity_bits in &self.fri_params.reduction_arity_bits {
+            let num_leaves = 1 << (values_bits - arity_bits);
+            let leaf_len = D << arity_bits;
+            let cap_height = cap_height.min(values_bits - arity_bits);
+            let digests = 2 * (num_leaves - (1 << cap_height));
+            fri.merkle_leaves += num_leaves;
+            fri.merkle_digests += digests;
+            fri.merkle_permutations += merkle_permutations(num_leaves, leaf_len, cap_height);
+            fri_words += num_leaves * leaf_len + digests * 4;
+            values_bits -= arity_bits;
+        }
+        fri.permutations = 1 << fri_config.proof_of_work_bits;
+        fri.retained_bytes = fri_words * size_of::<u64>();
+
+        let stages = [preprocessed, wires, zs_partial_products, quotient, fri]
+            .into_iter()
+            .map(|s| s.with_time(costs))
+            .collect::<Vec<_>>();
+        let peak_host_bytes = stages.iter().map(|s| s.retained_bytes).sum::<usize>()
+            + stages.iter().map(|s| s.transient_bytes).max().unwrap_or(0);
+        let layout = ProverLayout::new(self, ProverLayout::min_second_stage_offset(self));
+        let device_bytes =
+            (layout.end() + stages[0].merkle_leaf_len * stages[0].merkle_leaves) * size_of::<u64>();
+        let time = stages[1..].iter().map(|s| s.time).sum();
+
+        ProofEstimate {
+            stages,
+            peak_host_bytes,
+            device_bytes,
+            time,
+        }
+    }
+}
+
 /// The `Target` version of `VerifierCircuitData`, for use inside recursive circuits. Note that this
 /// is intentionally missing certain fields, such as `CircuitConfig`, because we support only a
 /// limited form of dynamic inner circuits. We can't practically make things like the wire count
@@ -479,3 +1053,226 @@ pub struct VerifierCircuitTarget {
     /// seed Fiat-Shamir.
     pub circuit_digest: HashOutTarget,
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::witness::WitnessWrite;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    #[test]
+    fn test_circuit_data_serialization() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let e = builder.add_virtual_target();
+        let index = builder.add_virtual_target();
+        let z = builder.mul_add(x, y, x);
+        let w = builder.exp(z, e, 8);
+        let v = builder.random_access(index, vec![x, y, z, w]);
+        let eq = builder.is_equal(v, y);
+        let (low, _high) = builder.split_low_high(x, 16, 64);
+        builder.split_le(low, 16);
+        let h = builder.hash_n_to_hash_no_pad::<PoseidonHash>(vec![w, v, eq.target]);
+        builder.register_public_inputs(&h.elements);
+        let mut data = builder.build::<C>();
+
+        let gate_serializer = GateSerializer::plonky2();
+        let generator_serializer = WitnessGeneratorSerializer::plonky2();
+        let bytes = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let data_from_bytes = CircuitData::<F, C, D>::from_bytes(
+            bytes.clone(),
+            &gate_serializer,
+            &generator_serializer,
+        )
+        .map_err(anyhow::Error::msg)?;
+        assert_eq!(data_from_bytes.common, data.common);
+        assert_eq!(data_from_bytes.verifier_only, data.verifier_only);
+        assert_eq!(
+            data_from_bytes
+                .to_bytes(&gate_serializer, &generator_serializer)
+                .map_err(anyhow::Error::msg)?,
+            bytes
+        );
+
+        // Gates and generators missing from the registries are rejected.
+        assert!(data.common.to_bytes(&GateSerializer::new()).is_err());
+        assert!(data
+            .prover_only
+            .to_bytes(&WitnessGeneratorSerializer::new())
+            .is_err());
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        pw.set_target(e, F::from_canonical_u64(5));
+        pw.set_target(index, F::ONE);
+        let proof = data_from_bytes.prove(pw)?;
+        data.verify(proof)?;
+
+        // So are rows whose gate is not one of `common.gates`.
+        data.prover_only.target_origins.row_gates[0] = data.common.gates.len();
+        let bytes = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(anyhow::Error::msg)?;
+        assert!(
+            CircuitData::<F, C, D>::from_bytes(bytes, &gate_serializer, &generator_serializer)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_common_circuit_data() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let index = builder.add_virtual_target();
+        let y = builder.mul(x, x);
+        let z = builder.random_access(index, vec![x, y]);
+        builder.register_public_input(z);
+        let common = builder.build::<C>().common;
+
+        let gate_serializer = GateSerializer::plonky2();
+        let from_bytes = |common: &CommonCircuitData<F, D>| -> Result<_> {
+            let bytes = common
+                .to_bytes(&gate_serializer)
+                .map_err(anyhow::Error::msg)?;
+            Ok(CommonCircuitData::<F, D>::from_bytes(
+                bytes,
+                &gate_serializer,
+            ))
+        };
+        assert_eq!(from_bytes(&common)?.map_err(anyhow::Error::msg)?, common);
+
+        // `k_is` must have one shift per routed wire.
+        let mut malformed = common.clone();
+        malformed.k_is.pop();
+        assert!(from_bytes(&malformed)?.is_err());
+
+        // Each gate must use the selector of the group that contains it.
+        let mut malformed = common.clone();
+        malformed.selectors_info.selector_indices[0] = malformed.selectors_info.groups.len();
+        assert!(from_bytes(&malformed)?.is_err());
+        let mut malformed = common.clone();
+        malformed.selectors_info.groups.last_mut().unwrap().end += 1;
+        assert!(from_bytes(&malformed)?.is_err());
+
+        // The gates must fit in the wires of the config.
+        let mut malformed = common.clone();
+        malformed.config.num_wires = 1;
+        assert!(from_bytes(&malformed)?.is_err());
+
+        // Sizes of gates which would overflow their wire indices are rejected.
+        let sizes = |sizes: &[usize]| -> Result<Buffer> {
+            let mut bytes = Vec::new();
+            for &size in sizes {
+                bytes.write_usize(size).map_err(anyhow::Error::msg)?;
+            }
+            Ok(Buffer::new(bytes))
+        };
+        let bits = usize::BITS as usize;
+        assert!(gate_serializer
+            .read("RandomAccessGate", &mut sizes(&[bits, 1, 0])?)
+            .is_err());
+        assert!(gate_serializer
+            .read("HighDegreeInterpolationGate", &mut sizes(&[usize::MAX])?)
+            .is_err());
+        assert!(gate_serializer
+            .read("ArithmeticGate", &mut sizes(&[usize::MAX])?)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merkle_permutations() {
+        // Leaves of 4 elements are used as their own digests.
+        assert_eq!(merkle_permutations(8, 4, 0), 7);
+        assert_eq!(merkle_permutations(8, 4, 2), 4);
+        assert_eq!(merkle_permutations(8, 16, 0), 8 * 2 + 7);
+        assert_eq!(merkle_permutations(8, 17, 3), 8 * 3);
+    }
+
+    #[test]
+    fn test_estimate_proof() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.mul(x, x);
+        builder.register_public_input(y);
+        let common = builder.build::<C>().common;
+
+        let costs = OperationCosts::default();
+        let estimate = common.estimate_proof(&costs);
+        let names = estimate.stages.iter().map(|s| s.name).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "constants_sigmas",
+                "wires",
+                "zs_partial_products",
+                "quotient",
+                "fri"
+            ]
+        );
+
+        let wires = &estimate.stages[1];
+        assert_eq!(wires.lde_size, common.lde_size());
+        assert_eq!(wires.merkle_leaves, common.lde_size());
+        assert_eq!(wires.merkle_leaf_len, common.config.num_wires);
+        assert_eq!(wires.ffts, 2 * common.config.num_wires);
+        assert_eq!(
+            wires.merkle_digests,
+            2 * (common.lde_size() - (1 << common.config.fri_config.cap_height))
+        );
+        let quotient = &estimate.stages[3];
+        assert_eq!(quotient.merkle_leaf_len, common.num_quotient_polys());
+        assert!(quotient.constraint_evaluations > 0);
+        let fri = &estimate.stages[4];
+        assert_eq!(
+            fri.permutations,
+            1 << common.config.fri_config.proof_of_work_bits
+        );
+
+        let retained = estimate
+            .stages
+            .iter()
+            .map(|s| s.retained_bytes)
+            .sum::<usize>();
+        assert!(estimate.peak_host_bytes >= retained);
+        assert!(estimate.device_bytes > wires.merkle_leaves * wires.merkle_leaf_len * 8);
+
+        // The time scales with the costs, and excludes the preprocessing.
+        let doubled = OperationCosts {
+            fft_ns: 2.0 * costs.fft_ns,
+            permutation_ns: 2.0 * costs.permutation_ns,
+            merkle_permutation_ns: 2.0 * costs.merkle_permutation_ns,
+            constraint_ns: 2.0 * costs.constraint_ns,
+        };
+        let time = common.estimate_proof(&doubled).time;
+        assert!(time > estimate.time && time <= 2 * estimate.time + Duration::from_nanos(4));
+        assert_eq!(
+            estimate.time,
+            estimate.stages[1..]
+                .iter()
+                .map(|s| s.time)
+                .sum::<Duration>()
+        );
+    }
+}
@@ -0,0 +1,357 @@
+//! Cross-checking of the accelerated prover against the reference one.
+//!
+//! `prove` and `my_prove` are two implementations of the same protocol. With
+//! [`ProveMode::CrossCheck`], the witness is generated once and both provers run on it; their
+//! commitments, challenger states, challenges and openings, then their final proofs, are compared.
+//! Witness generation isn't deterministic, as the unused wires of the public input gate and the
+//! blinding wires are randomized, but proving a given witness is once the salts of the hiding
+//! commitments are derived from a shared seed. So any difference is a bug of the backend (or of
+//! `my_prove`).
+
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, Result};
+
+use crate::backend::ProverBackend;
+use crate::field::extension::Extendable;
+use crate::field::types::Sample;
+use crate::fri::oracle::SaltSource;
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::iop::generator::generate_partial_witness;
+use crate::iop::witness::PartialWitness;
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::GenericConfig;
+use crate::plonk::proof::ProofWithPublicInputs;
+use crate::plonk::prover::{my_prove, my_prove_from_witness_with_salt_source, prove};
+use crate::plonk::prover_rounds::Prover;
+use crate::plonk::prover_trace::{ProverTrace, TraceBundle, TraceDivergence, TraceStage};
+use crate::timed;
+use crate::util::timing::TimingTree;
+
+/// Which prover `prove_with_mode` runs.
+pub enum ProveMode<'a, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    /// The reference `prove`.
+    Cpu,
+    /// `my_prove` on the given backend.
+    Backend(&'a mut dyn ProverBackend<F, C, D>),
+    /// Both, failing with the [`CrossCheckReport`] if they disagree. The backend's proof is
+    /// returned otherwise.
+    CrossCheck(&'a mut dyn ProverBackend<F, C, D>),
+}
+
+pub fn prove_with_mode<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+    mode: ProveMode<F, C, D>,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    match mode {
+        ProveMode::Cpu => prove(prover_data, common_data, inputs, timing),
+        ProveMode::Backend(backend) => my_prove(prover_data, common_data, inputs, timing, backend),
+        ProveMode::CrossCheck(backend) => {
+            cross_check(prover_data, common_data, inputs, timing, backend)?.into_result()
+        }
+    }
+}
+
+/// Generates the witness of `inputs`, proves it with both `prove` and `my_prove` on `backend`, and
+/// reports how they differ. With zero-knowledge, both provers salt their commitments with the
+/// backend's `SaltSource::Seeded` seed, or with a fresh random one if the backend's salts are
+/// random, so that the proofs still hide the witness.
+pub fn cross_check<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+    backend: &mut dyn ProverBackend<F, C, D>,
+) -> Result<CrossCheckReport<F, C, D>> {
+    let salt_source = match backend.salt_source() {
+        SaltSource::Random => SaltSource::Seeded(HashOut::rand()),
+        seeded => seeded,
+    };
+
+    // The backend doesn't report the quotient values, so there is no point in keeping them.
+    let stages = TraceStage::ALL
+        .into_iter()
+        .filter(|&s| s != TraceStage::QuotientValues)
+        .collect::<Vec<_>>();
+    let partition_witness = timed!(
+        timing,
+        &format!("run {} generators", prover_data.generators.len()),
+        generate_partial_witness(inputs, prover_data, common_data)
+    )?;
+    let mut cpu_trace = TraceBundle::with_stages(&stages);
+    let cpu_trace_ref: &mut dyn ProverTrace<F> = &mut cpu_trace;
+    let mut cpu_trace_ref = Some(cpu_trace_ref);
+    let cpu_proof = Prover::new(
+        prover_data,
+        common_data,
+        partition_witness.clone(),
+        timing,
+        &mut cpu_trace_ref,
+    )
+    .with_salt_source(salt_source)
+    .finish(timing, &mut cpu_trace_ref)?;
+    let mut backend_trace = TraceBundle::with_stages(&stages);
+    let backend_proof = my_prove_from_witness_with_salt_source(
+        prover_data,
+        common_data,
+        partition_witness,
+        timing,
+        backend,
+        salt_source,
+        &mut Some(&mut backend_trace),
+    )?;
+
+    let mut mismatches = Vec::new();
+    if let Some(divergence) = backend_trace.first_divergence(&cpu_trace) {
+        mismatches.push(CrossCheckMismatch::Trace(divergence));
+    }
+    mismatches.extend(
+        proof_mismatches(&backend_proof, &cpu_proof)
+            .into_iter()
+            .map(CrossCheckMismatch::Proof),
+    );
+
+    Ok(CrossCheckReport {
+        cpu_proof,
+        backend_proof,
+        mismatches,
+    })
+}
+
+/// The names of the parts of the two proofs which differ.
+fn proof_mismatches<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    left: &ProofWithPublicInputs<F, C, D>,
+    right: &ProofWithPublicInputs<F, C, D>,
+) -> Vec<&'static str> {
+    let (l, r) = (&left.proof, &right.proof);
+    let (l_fri, r_fri) = (&l.opening_proof, &r.opening_proof);
+    [
+        ("public_inputs", left.public_inputs == right.public_inputs),
+        ("wires_cap", l.wires_cap == r.wires_cap),
+        (
+            "plonk_zs_partial_products_cap",
+            l.plonk_zs_partial_products_cap == r.plonk_zs_partial_products_cap,
+        ),
+        (
+            "quotient_polys_cap",
+            l.quotient_polys_cap == r.quotient_polys_cap,
+        ),
+        ("openings", l.openings == r.openings),
+        (
+            "opening_proof.commit_phase_merkle_caps",
+            l_fri.commit_phase_merkle_caps == r_fri.commit_phase_merkle_caps,
+        ),
+        (
+            "opening_proof.query_round_proofs",
+            l_fri.query_round_proofs == r_fri.query_round_proofs,
+        ),
+        (
+            "opening_proof.final_poly",
+            l_fri.final_poly == r_fri.final_poly,
+        ),
+        (
+            "opening_proof.pow_witness",
+            l_fri.pow_witness == r_fri.pow_witness,
+        ),
+    ]
+    .into_iter()
+    .filter(|&(_, equal)| !equal)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+/// A difference between the CPU and the backend provers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CrossCheckMismatch {
+    /// The first intermediate value at which the provers diverge. The later ones usually differ
+    /// as a consequence, so they aren't reported.
+    Trace(TraceDivergence),
+    /// A part of the final proof, named after its field, e.g. `opening_proof.final_poly`.
+    Proof(&'static str),
+}
+
+impl Display for CrossCheckMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CrossCheckMismatch::Trace(divergence) => write!(f, "{divergence}"),
+            CrossCheckMismatch::Proof(part) => write!(f, "{part} of the proofs differ"),
+        }
+    }
+}
+
+/// The outcome of [`cross_check`].
+#[derive(Debug)]
+pub struct CrossCheckReport<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub cpu_proof: ProofWithPublicInputs<F, C, D>,
+    pub backend_proof: ProofWithPublicInputs<F, C, D>,
+    pub mismatches: Vec<CrossCheckMismatch>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CrossCheckReport<F, C, D>
+{
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// The backend's proof if both provers agree, or an error describing their differences.
+    pub fn into_result(self) -> Result<ProofWithPublicInputs<F, C, D>> {
+        if self.is_consistent() {
+            Ok(self.backend_proof)
+        } else {
+            Err(anyhow!("{}", self))
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> Display
+    for CrossCheckReport<F, C, D>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.is_consistent() {
+            return write!(f, "The CPU and backend provers agree");
+        }
+        write!(f, "The CPU and backend provers disagree:")?;
+        for mismatch in &self.mismatches {
+            write!(f, "\n- {mismatch}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::cpu::CpuBackend;
+    use crate::backend::{CommitmentLayout, QuotientPolysArgs};
+    use crate::field::types::{Field, PrimeField64, Sample};
+    use crate::iop::witness::WitnessWrite;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// A `CpuBackend` which corrupts the first coefficient of the quotient polynomials.
+    struct FaultyBackend(CpuBackend<F, C, D>);
+
+    impl ProverBackend<F, C, D> for FaultyBackend {
+        fn memory_len(&self) -> usize {
+            self.0.memory_len()
+        }
+
+        fn second_stage_offset(&self) -> usize {
+            self.0.second_stage_offset()
+        }
+
+        fn write_words(&mut self, offset: usize, words: &[u64]) {
+            self.0.write_words(offset, words)
+        }
+
+        fn read_words(&mut self, offset: usize, words: &mut [u64]) {
+            self.0.read_words(offset, words)
+        }
+
+        fn ifft(
+            &mut self,
+            offset: usize,
+            poly_num: usize,
+            values_num_per_poly: usize,
+        ) -> Result<()> {
+            self.0.ifft(offset, poly_num, values_num_per_poly)
+        }
+
+        fn merkle_tree_from_coeffs(&mut self, layout: &CommitmentLayout) -> Result<()> {
+            self.0.merkle_tree_from_coeffs(layout)
+        }
+
+        fn compute_quotient_polys(&mut self, args: &QuotientPolysArgs<F>) -> Result<()> {
+            self.0.compute_quotient_polys(args)?;
+            let mut word = [0];
+            self.0.read_words(args.quotient_polys_offset, &mut word);
+            self.0.write_words(
+                args.quotient_polys_offset,
+                &[(F::from_canonical_u64(word[0]) + F::ONE).to_canonical_u64()],
+            );
+            Ok(())
+        }
+    }
+
+    fn circuit(config: CircuitConfig) -> (CircuitData<F, C, D>, PartialWitness<F>) {
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let xy = builder.mul(x, y);
+        builder.register_public_input(xy);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        (data, pw)
+    }
+
+    #[test]
+    fn test_cross_check() -> Result<()> {
+        let (data, pw) = circuit(CircuitConfig::standard_recursion_config());
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        let proof = prove_with_mode(
+            &data.prover_only,
+            &data.common,
+            pw,
+            &mut TimingTree::default(),
+            ProveMode::CrossCheck(&mut backend),
+        )?;
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_cross_check_zero_knowledge() -> Result<()> {
+        let (data, pw) = circuit(CircuitConfig::standard_recursion_zk_config());
+        let mut backend = CpuBackend::new(&data.prover_only, &data.common);
+        let report = cross_check(
+            &data.prover_only,
+            &data.common,
+            pw,
+            &mut TimingTree::default(),
+            &mut backend,
+        )?;
+        assert!(report.is_consistent());
+        data.verify(report.into_result()?)
+    }
+
+    #[test]
+    fn test_cross_check_reports_first_divergence() -> Result<()> {
+        let (data, pw) = circuit(CircuitConfig::standard_recursion_config());
+        let mut backend = FaultyBackend(CpuBackend::new(&data.prover_only, &data.common));
+        let report = cross_check(
+            &data.prover_only,
+            &data.common,
+            pw,
+            &mut TimingTree::default(),
+            &mut backend,
+        )?;
+
+        assert!(!report.is_consistent());
+        assert!(matches!(
+            report.mismatches[0],
+            CrossCheckMismatch::Trace(d) if d.stage() == TraceStage::QuotientChunks
+        ));
+        assert!(report
+            .mismatches
+            .contains(&CrossCheckMismatch::Proof("quotient_polys_cap")));
+        assert!(report.into_result().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,1261 @@
+//! Export of the verifier of a circuit as a [Circom](https://docs.circom.io) circuit, so that
+//! plonky2 proofs can be wrapped in Groth16 proofs.
+//!
+//! [`export_circom_verifier`] emits a self-contained project, whose `verifier.circom` declares the
+//! main component. It recomputes the Fiat-Shamir challenges, checks the vanishing polynomial
+//! identity at `zeta` with the gates' own Circom templates, then checks the FRI proof, Merkle paths
+//! included. [`circom_input_json`] turns a proof of the circuit into the input of that component.
+//!
+//! Only proofs over the Goldilocks field and its quadratic extension, hashed with Poseidon, are
+//! supported.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::any::type_name;
+use core::fmt::Write;
+
+use anyhow::{bail, ensure, Result};
+
+use crate::field::extension::{flatten, Extendable};
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::{Field64, PrimeField64};
+use crate::gates::gate::gate_name;
+use crate::gates::selectors::UNUSED_SELECTOR;
+use crate::hash::hash_types::RichField;
+use crate::hash::hashing::{SPONGE_RATE, SPONGE_WIDTH};
+use crate::hash::merkle_tree::MerkleCap;
+use crate::hash::poseidon::{Poseidon, PoseidonHash, ALL_ROUND_CONSTANTS, N_ROUNDS};
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, GenericHashOut};
+use crate::plonk::plonk_common::{salt_size, PlonkOracle};
+use crate::plonk::proof::ProofWithPublicInputs;
+use crate::plonk::validate_shape::validate_proof_with_pis_shape;
+
+const GOLDILOCKS_CIRCOM: &str = include_str!("circom/goldilocks.circom");
+const POSEIDON_CIRCOM: &str = include_str!("circom/poseidon.circom");
+const FRI_CIRCOM: &str = include_str!("circom/fri.circom");
+const PLONK_CIRCOM: &str = include_str!("circom/plonk.circom");
+
+/// The file declaring the main component.
+pub const MAIN_FILE: &str = "verifier.circom";
+
+/// The files of a Circom project.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircomProject {
+    /// The name and the content of each file.
+    pub files: Vec<(String, String)>,
+}
+
+impl CircomProject {
+    pub fn file(&self, name: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, content)| content.as_str())
+    }
+
+    /// Writes the files to `dir`, which is created if needed.
+    #[cfg(feature = "std")]
+    pub fn write_to(&self, dir: &std::path::Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        for (name, content) in &self.files {
+            std::fs::write(dir.join(name), content)?;
+        }
+        Ok(())
+    }
+}
+
+/// The Circom project verifying the proofs of the given circuit.
+pub fn export_circom_verifier<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+    common: &CommonCircuitData<F, D>,
+) -> Result<CircomProject> {
+    check_supported::<F, C, D>(common)?;
+    let files = vec![
+        ("goldilocks.circom", GOLDILOCKS_CIRCOM.to_string()),
+        ("poseidon.circom", POSEIDON_CIRCOM.to_string()),
+        ("fri.circom", FRI_CIRCOM.to_string()),
+        ("plonk.circom", PLONK_CIRCOM.to_string()),
+        (
+            "constants.circom",
+            constants_circom::<F, C, D>(verifier_only, common),
+        ),
+        ("gates.circom", gates_circom(common)?),
+        (MAIN_FILE, verifier_circom(common)),
+    ];
+    Ok(CircomProject {
+        files: files
+            .into_iter()
+            .map(|(name, content)| (name.to_string(), content))
+            .collect(),
+    })
+}
+
+/// The input of the main component of [`export_circom_verifier`]'s project for the given proof, as
+/// a JSON object.
+pub fn circom_input_json<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+    common: &CommonCircuitData<F, D>,
+) -> Result<String> {
+    check_supported::<F, C, D>(common)?;
+    validate_proof_with_pis_shape(proof_with_pis, common)?;
+
+    let inputs = verifier_inputs(common);
+    let values = verifier_input_values(proof_with_pis, common);
+    ensure!(inputs.len() == values.len(), "Missing verifier inputs.");
+    let mut json = String::from("{\n");
+    for (i, (input, (name, values))) in inputs.iter().zip(&values).enumerate() {
+        ensure!(
+            input.name == *name && input.len() == values.len(),
+            "The value of `{}` doesn't match its shape.",
+            input.name
+        );
+        if i > 0 {
+            json.push_str(",\n");
+        }
+        write!(json, "  \"{name}\": ").unwrap();
+        write_json_array(&mut json, values, &input.dims);
+    }
+    json.push_str("\n}\n");
+    Ok(json)
+}
+
+/// The gates whose `export_circom_verification_code` gives a Circom template, named as at the start
+/// of their id. `NoopGate` has no constraints, so it needs no template.
+const CIRCOM_GATES: [&str; 14] = [
+    "ArithmeticExtensionGate",
+    "ArithmeticGate",
+    "BaseSumGate",
+    "ConstantGate",
+    "ExponentiationGate",
+    "LowDegreeInterpolationGate",
+    "MulExtensionGate",
+    "NoopGate",
+    "PoseidonGate",
+    "PoseidonMdsGate",
+    "PublicInputGate",
+    "RandomAccessGate",
+    "ReducingExtensionGate",
+    "ReducingGate",
+];
+
+fn check_supported<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    common: &CommonCircuitData<F, D>,
+) -> Result<()> {
+    ensure!(
+        D == 2,
+        "The Circom verifier only supports the quadratic extension."
+    );
+    ensure!(
+        F::ORDER == GoldilocksField::ORDER,
+        "The Circom verifier only supports the Goldilocks field."
+    );
+    ensure!(
+        type_name::<C::Hasher>() == type_name::<PoseidonHash>()
+            && type_name::<C::InnerHasher>() == type_name::<PoseidonHash>(),
+        "The Circom verifier only supports Poseidon."
+    );
+    ensure!(
+        !common.has_lookups(),
+        "The Circom verifier does not support lookups."
+    );
+    for gate in &common.gates {
+        let id = gate.0.id();
+        ensure!(
+            CIRCOM_GATES.contains(&gate_name(&id)),
+            "{id} has no Circom template."
+        );
+    }
+    // Circom has no empty arrays. Public inputs are the only optional input.
+    if let Some(input) = verifier_inputs(common)
+        .iter()
+        .find(|input| input.dims.contains(&0))
+    {
+        bail!(
+            "The verifier input `{}` would be empty, of shape {:?}.",
+            input.name,
+            input.dims
+        );
+    }
+    Ok(())
+}
+
+/// A signal input of the `Verifier` template.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct VerifierInput {
+    name: String,
+    dims: Vec<usize>,
+}
+
+impl VerifierInput {
+    fn new(name: impl Into<String>, dims: &[usize]) -> Self {
+        Self {
+            name: name.into(),
+            dims: dims.to_vec(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.dims.iter().product()
+    }
+}
+
+/// The openings at `zeta` and their number, in the order of `OpeningSet::to_fri_openings`.
+fn zeta_openings<F: RichField + Extendable<D>, const D: usize>(
+    common: &CommonCircuitData<F, D>,
+) -> [(&'static str, usize); 6] {
+    let config = &common.config;
+    [
+        ("openings_constants", common.num_constants),
+        ("openings_plonk_sigmas", config.num_routed_wires),
+        ("openings_wires", config.num_wires),
+        ("openings_plonk_zs", config.num_challenges),
+        (
+            "openings_partial_products",
+            config.num_challenges * common.num_partial_products,
+        ),
+        (
+            "openings_quotient_polys",
+            config.num_challenges * common.quotient_degree_factor,
+        ),
+    ]
+}
+
+/// The oracles, with their number of polynomials and the length of their leaves, salt included.
+fn oracles<F: RichField + Extendable<D>, const D: usize>(
+    common: &CommonCircuitData<F, D>,
+) -> [(&'static str, usize, usize); 4] {
+    let config = &common.config;
+    let oracle = |name, oracle: PlonkOracle, num_polys| {
+        let salt = salt_size(common.fri_params.hiding && oracle.blinding);
+        (name, num_polys, num_polys + salt)
+    };
+    [
+        oracle(
+            "constants_sigmas",
+            PlonkOracle::CONSTANTS_SIGMAS,
+            common.num_preprocessed_polys(),
+        ),
+        oracle("wires", PlonkOracle::WIRES, config.num_wires),
+        oracle(
+            "zs_partial_products",
+            PlonkOracle::ZS_PARTIAL_PRODUCTS,
+            common.num_zs_partial_products_polys(),
+        ),
+        oracle(
+            "quotient",
+            PlonkOracle::QUOTIENT,
+            config.num_challenges * common.quotient_degree_factor,
+        ),
+    ]
+}
+
+/// The caps of the oracles, in the order of [`oracles`].
+const ORACLE_CAPS: [&str; 4] = [
+    "constants_sigmas_cap",
+    "wires_cap",
+    "plonk_zs_partial_products_cap",
+    "quotient_polys_cap",
+];
+
+/// The inputs of the `Verifier` template. Field elements are canonical; extension elements and
+/// hashes are arrays of 2 and 4 field elements.
+fn verifier_inputs<F: RichField + Extendable<D>, const D: usize>(
+    common: &CommonCircuitData<F, D>,
+) -> Vec<VerifierInput> {
+    let config = &common.config;
+    let params = &common.fri_params;
+    let cap_height = config.fri_config.cap_height;
+    let cap_len = 1 << cap_height;
+    let num_queries = config.fri_config.num_query_rounds;
+
+    let mut inputs = Vec::new();
+    if common.num_public_inputs > 0 {
+        inputs.push(VerifierInput::new(
+            "public_inputs",
+            &[common.num_public_inputs],
+        ));
+    }
+    for cap in &ORACLE_CAPS[1..] {
+        inputs.push(VerifierInput::new(*cap, &[cap_len, 4]));
+    }
+    for (name, len) in zeta_openings(common) {
+        inputs.push(VerifierInput::new(name, &[len, 2]));
+    }
+    inputs.push(VerifierInput::new(
+        "openings_plonk_zs_next",
+        &[config.num_challenges, 2],
+    ));
+
+    let num_reductions = params.reduction_arity_bits.len();
+    if num_reductions > 0 {
+        inputs.push(VerifierInput::new(
+            "fri_commit_phase_merkle_caps",
+            &[num_reductions, cap_len, 4],
+        ));
+    }
+    let mut depth = params.lde_bits().saturating_sub(cap_height);
+    for (name, _, leaf_len) in oracles(common) {
+        inputs.push(VerifierInput::new(
+            format!("fri_query_init_{name}_v"),
+            &[num_queries, leaf_len],
+        ));
+        inputs.push(VerifierInput::new(
+            format!("fri_query_init_{name}_p"),
+            &[num_queries, depth, 4],
+        ));
+    }
+    for (i, &arity_bits) in params.reduction_arity_bits.iter().enumerate() {
+        depth = depth.saturating_sub(arity_bits);
+        inputs.push(VerifierInput::new(
+            format!("fri_query_step{i}_v"),
+            &[num_queries, 1 << arity_bits, 2],
+        ));
+        inputs.push(VerifierInput::new(
+            format!("fri_query_step{i}_p"),
+            &[num_queries, depth, 4],
+        ));
+    }
+    inputs.push(VerifierInput::new(
+        "fri_final_poly",
+        &[params.final_poly_len(), 2],
+    ));
+    inputs.push(VerifierInput::new("fri_pow_witness", &[]));
+    inputs
+}
+
+/// The values of the inputs of the `Verifier` template, flattened, in the order of
+/// [`verifier_inputs`].
+fn verifier_input_values<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+    common: &CommonCircuitData<F, D>,
+) -> Vec<(String, Vec<F>)> {
+    let ProofWithPublicInputs {
+        proof,
+        public_inputs,
+    } = proof_with_pis;
+    let cap =
+        |cap: &MerkleCap<F, C::Hasher>| cap.0.iter().flat_map(|h| h.to_vec()).collect::<Vec<_>>();
+
+    let mut values = Vec::new();
+    if !public_inputs.is_empty() {
+        values.push(("public_inputs".to_string(), public_inputs.clone()));
+    }
+    values.push(("wires_cap".to_string(), cap(&proof.wires_cap)));
+    values.push((
+        "plonk_zs_partial_products_cap".to_string(),
+        cap(&proof.plonk_zs_partial_products_cap),
+    ));
+    values.push((
+        "quotient_polys_cap".to_string(),
+        cap(&proof.quotient_polys_cap),
+    ));
+    let openings = &proof.openings;
+    for (name, evals) in [
+        ("openings_constants", &openings.constants),
+        ("openings_plonk_sigmas", &openings.plonk_sigmas),
+        ("openings_wires", &openings.wires),
+        ("openings_plonk_zs", &openings.plonk_zs),
+        ("openings_partial_products", &openings.partial_products),
+        ("openings_quotient_polys", &openings.quotient_polys),
+        ("openings_plonk_zs_next", &openings.plonk_zs_next),
+    ] {
+        values.push((name.to_string(), flatten::<F, D>(evals)));
+    }
+
+    let fri = &proof.opening_proof;
+    if !fri.commit_phase_merkle_caps.is_empty() {
+        values.push((
+            "fri_commit_phase_merkle_caps".to_string(),
+            fri.commit_phase_merkle_caps.iter().flat_map(cap).collect(),
+        ));
+    }
+    for (i, (name, _, _)) in oracles(common).into_iter().enumerate() {
+        let rounds = &fri.query_round_proofs;
+        values.push((
+            format!("fri_query_init_{name}_v"),
+            rounds
+                .iter()
+                .flat_map(|r| r.initial_trees_proof.evals_proofs[i].0.clone())
+                .collect(),
+        ));
+        values.push((
+            format!("fri_query_init_{name}_p"),
+            rounds
+                .iter()
+                .flat_map(|r| &r.initial_trees_proof.evals_proofs[i].1.siblings)
+                .flat_map(|h| h.to_vec())
+                .collect(),
+        ));
+    }
+    for i in 0..common.fri_params.reduction_arity_bits.len() {
+        let steps = fri.query_round_proofs.iter().map(|r| &r.steps[i]);
+        values.push((
+            format!("fri_query_step{i}_v"),
+            steps
+                .clone()
+                .flat_map(|s| flatten::<F, D>(&s.evals))
+                .collect(),
+        ));
+        values.push((
+            format!("fri_query_step{i}_p"),
+            steps
+                .flat_map(|s| &s.merkle_proof.siblings)
+                .flat_map(|h| h.to_vec())
+                .collect(),
+        ));
+    }
+    values.push((
+        "fri_final_poly".to_string(),
+        flatten::<F, D>(&fri.final_poly.coeffs),
+    ));
+    values.push(("fri_pow_witness".to_string(), vec![fri.pow_witness]));
+    values
+}
+
+/// Writes `values` as nested arrays of the given dimensions, whose elements are decimal strings.
+fn write_json_array<F: PrimeField64>(json: &mut String, values: &[F], dims: &[usize]) {
+    match dims.split_first() {
+        None => write!(json, "\"{}\"", values[0].to_canonical_u64()).unwrap(),
+        Some((_, rest)) => {
+            json.push('[');
+            let stride = rest.iter().product::<usize>();
+            for (i, chunk) in values.chunks(stride).enumerate() {
+                if i > 0 {
+                    json.push_str(", ");
+                }
+                write_json_array(json, chunk, rest);
+            }
+            json.push(']');
+        }
+    }
+}
+
+fn write_constant(code: &mut String, name: &str, value: impl ToString) {
+    writeln!(
+        code,
+        "function {name}() {{\n  return {};\n}}",
+        value.to_string()
+    )
+    .unwrap();
+}
+
+fn write_table(code: &mut String, name: &str, values: &[u64]) {
+    writeln!(
+        code,
+        "function {name}(i) {{\n  var value[{}];",
+        values.len()
+    )
+    .unwrap();
+    for (i, v) in values.iter().enumerate() {
+        writeln!(code, "  value[{i}] = {v};").unwrap();
+    }
+    code.push_str("  return value[i];\n}\n");
+}
+
+fn write_table_2d(code: &mut String, name: &str, rows: &[Vec<u64>]) {
+    writeln!(
+        code,
+        "function {name}(i, j) {{\n  var value[{}][{}];",
+        rows.len(),
+        rows[0].len()
+    )
+    .unwrap();
+    for (i, row) in rows.iter().enumerate() {
+        for (j, v) in row.iter().enumerate() {
+            writeln!(code, "  value[{i}][{j}] = {v};").unwrap();
+        }
+    }
+    code.push_str("  return value[i][j];\n}\n");
+}
+
+/// `constants.circom`: the parameters and commitments of the circuit, and the constants of the
+/// field and of Poseidon.
+fn constants_circom<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+    common: &CommonCircuitData<F, D>,
+) -> String {
+    let config = &common.config;
+    let params = &common.fri_params;
+    let canonical = |xs: &[F]| xs.iter().map(|x| x.to_canonical_u64()).collect::<Vec<_>>();
+
+    let mut code = String::from("pragma circom 2.1.0;\n\n");
+    for (name, len) in zeta_openings(common) {
+        write_constant(&mut code, &format!("NUM_{}", name.to_uppercase()), len);
+    }
+    write_constant(
+        &mut code,
+        "NUM_OPENINGS_PLONK_ZS_NEXT",
+        config.num_challenges,
+    );
+    write_constant(&mut code, "NUM_CHALLENGES", config.num_challenges);
+    write_constant(&mut code, "NUM_ROUTED_WIRES", config.num_routed_wires);
+    write_constant(
+        &mut code,
+        "NUM_PARTIAL_PRODUCTS",
+        common.num_partial_products,
+    );
+    write_constant(
+        &mut code,
+        "QUOTIENT_DEGREE_FACTOR",
+        common.quotient_degree_factor,
+    );
+    write_constant(&mut code, "DEGREE_BITS", common.degree_bits());
+    write_constant(
+        &mut code,
+        "NUM_GATE_CONSTRAINTS",
+        common.num_gate_constraints,
+    );
+    write_constant(
+        &mut code,
+        "NUM_SELECTORS",
+        common.selectors_info.num_selectors(),
+    );
+    write_constant(&mut code, "NUM_PUBLIC_INPUTS", common.num_public_inputs);
+    write_constant(&mut code, "CAP_HEIGHT", config.fri_config.cap_height);
+    write_constant(&mut code, "LDE_BITS", params.lde_bits());
+    write_constant(
+        &mut code,
+        "NUM_FRI_QUERY_ROUND",
+        config.fri_config.num_query_rounds,
+    );
+    write_constant(
+        &mut code,
+        "FRI_POW_BITS",
+        config.fri_config.proof_of_work_bits,
+    );
+    write_constant(&mut code, "NUM_FRI_FINAL_POLY", params.final_poly_len());
+    write_table(&mut code, "K_IS", &canonical(&common.k_is));
+    write_constant(
+        &mut code,
+        "G_FROM_DEGREE_BITS",
+        F::primitive_root_of_unity(common.degree_bits()).to_canonical_u64(),
+    );
+
+    write_constant(
+        &mut code,
+        "GL_MULTIPLICATIVE_GENERATOR",
+        F::MULTIPLICATIVE_GROUP_GENERATOR.to_canonical_u64(),
+    );
+    let roots_of_unity = (0..=F::TWO_ADICITY)
+        .map(F::primitive_root_of_unity)
+        .collect::<Vec<_>>();
+    write_table(&mut code, "GL_ROOT_OF_UNITY", &canonical(&roots_of_unity));
+    write_table(
+        &mut code,
+        "GL_CONST",
+        &ALL_ROUND_CONSTANTS[..SPONGE_WIDTH * N_ROUNDS],
+    );
+    write_table(&mut code, "GL_MDS_CIRC", &<F as Poseidon>::MDS_MATRIX_CIRC);
+    write_table(&mut code, "GL_MDS_DIAG", &<F as Poseidon>::MDS_MATRIX_DIAG);
+
+    write_table(
+        &mut code,
+        "CIRCUIT_DIGEST",
+        &canonical(&verifier_only.circuit_digest.to_vec()),
+    );
+    let sigmas_cap = verifier_only
+        .constants_sigmas_cap
+        .0
+        .iter()
+        .map(|h| canonical(&h.to_vec()))
+        .collect::<Vec<_>>();
+    write_table_2d(&mut code, "SIGMAS_CAP", &sigmas_cap);
+    code
+}
+
+/// The filter of the gate at `row` of `common.gates`, as `compute_filter` computes it.
+fn filter_expression<F: RichField + Extendable<D>, const D: usize>(
+    common: &CommonCircuitData<F, D>,
+    row: usize,
+) -> String {
+    let selectors = &common.selectors_info;
+    let selector_index = selectors.selector_indices[row];
+    selectors.groups[selector_index]
+        .clone()
+        .filter(|&i| i != row)
+        .chain((selectors.num_selectors() > 1).then_some(UNUSED_SELECTOR))
+        .map(|i| format!("GlExtSub()(GlExt({i}, 0)(), constants[{selector_index}])"))
+        .reduce(|acc, factor| format!("GlExtMul()({acc}, {factor})"))
+        .unwrap_or_else(|| "GlExt(1, 0)()".to_string())
+}
+
+/// `gates.circom`: the templates of the gates of the circuit, and `EvalGateConstraints`, which sums
+/// their filtered constraints as `evaluate_gate_constraints` does.
+fn gates_circom<F: RichField + Extendable<D>, const D: usize>(
+    common: &CommonCircuitData<F, D>,
+) -> Result<String> {
+    let mut code = String::from(
+        "pragma circom 2.1.0;\n\ninclude \"constants.circom\";\ninclude \"goldilocks.circom\";\n\n",
+    );
+    let mut template_names = Vec::new();
+    for (row, gate) in common.gates.iter().enumerate() {
+        if gate.0.num_constraints() == 0 {
+            continue;
+        }
+        let template = gate
+            .0
+            .export_circom_verification_code()?
+            .replace(
+                "$SET_FILTER",
+                &format!("filter <== {}", filter_expression(common, row)),
+            )
+            .replace(
+                "$NUM_SELECTORS",
+                &common.selectors_info.num_selectors().to_string(),
+            );
+        let start = template.find("template ").expect("No template") + "template ".len();
+        let end = start + template[start..].find('(').expect("No template");
+        template_names.push(template[start..end].to_string());
+        code.push_str(&template);
+        code.push_str("\n\n");
+    }
+    // The MDS gate relies on the MDS functions of the Poseidon gate's template.
+    if template_names.iter().any(|n| n == "PoseidonMdsGate12")
+        && !template_names.iter().any(|n| n == "Poseidon12")
+    {
+        code.push_str(
+            "function MDS_MATRIX_CIRC(i) {\n  return GL_MDS_CIRC(i);\n}\nfunction MDS_MATRIX_DIAG(i) {\n  return GL_MDS_DIAG(i);\n}\n\n",
+        );
+    }
+
+    code.push_str(
+        "template EvalGateConstraints() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal zeros[NUM_GATE_CONSTRAINTS()][2];
+  for (var i = 0; i < NUM_GATE_CONSTRAINTS(); i++) {
+    zeros[i][0] <== 0;
+    zeros[i][1] <== 0;
+  }
+",
+    );
+    let mut constraints = "zeros".to_string();
+    for (i, name) in template_names.iter().enumerate() {
+        writeln!(
+            code,
+            "  component gate_{i} = {name}();
+  gate_{i}.constants <== constants;
+  gate_{i}.wires <== wires;
+  gate_{i}.public_input_hash <== public_input_hash;
+  gate_{i}.constraints <== {constraints};"
+        )
+        .unwrap();
+        constraints = format!("gate_{i}.out");
+    }
+    writeln!(code, "  out <== {constraints};\n}}").unwrap();
+    Ok(code)
+}
+
+/// Generates the Circom code of a `Challenger` whose inputs are Circom expressions: each
+/// duplexing becomes a `Poseidon` component, and challenges are outputs of these components.
+struct CircomChallenger {
+    sponge_state: Vec<String>,
+    input_buffer: Vec<String>,
+    output_buffer: Vec<String>,
+    num_duplexings: usize,
+    code: String,
+}
+
+impl CircomChallenger {
+    fn new() -> Self {
+        Self {
+            sponge_state: vec!["0".to_string(); SPONGE_WIDTH],
+            input_buffer: Vec::new(),
+            output_buffer: Vec::new(),
+            num_duplexings: 0,
+            code: String::new(),
+        }
+    }
+
+    fn observe_element(&mut self, element: String) {
+        self.output_buffer.clear();
+        self.input_buffer.push(element);
+        if self.input_buffer.len() == SPONGE_RATE {
+            self.duplexing();
+        }
+    }
+
+    fn observe_elements(&mut self, elements: impl IntoIterator<Item = String>) {
+        for element in elements {
+            self.observe_element(element);
+        }
+    }
+
+    /// Observes the extension elements `name[0..len]`.
+    fn observe_extension_elements(&mut self, name: &str, len: usize) {
+        self.observe_elements(
+            (0..len).flat_map(|i| (0..2).map(move |j| format!("{name}[{i}][{j}]"))),
+        );
+    }
+
+    /// Observes the cap `name`, of `len` hashes.
+    fn observe_cap(&mut self, name: &str, len: usize) {
+        self.observe_elements(
+            (0..len).flat_map(|i| (0..4).map(move |j| format!("{name}[{i}][{j}]"))),
+        );
+    }
+
+    fn get_challenge(&mut self) -> String {
+        if !self.input_buffer.is_empty() || self.output_buffer.is_empty() {
+            self.duplexing();
+        }
+        self.output_buffer
+            .pop()
+            .expect("Output buffer should be non-empty")
+    }
+
+    fn get_n_challenges(&mut self, n: usize) -> Vec<String> {
+        (0..n).map(|_| self.get_challenge()).collect()
+    }
+
+    fn duplexing(&mut self) {
+        let permutation = format!("challenger_{}", self.num_duplexings);
+        self.num_duplexings += 1;
+        for (i, input) in self.input_buffer.drain(..).enumerate() {
+            self.sponge_state[i] = input;
+        }
+        writeln!(self.code, "  component {permutation} = Poseidon();").unwrap();
+        for (i, s) in self.sponge_state.iter().enumerate() {
+            writeln!(self.code, "  {permutation}.in[{i}] <== {s};").unwrap();
+        }
+        self.sponge_state = (0..SPONGE_WIDTH)
+            .map(|i| format!("{permutation}.out[{i}]"))
+            .collect();
+        self.output_buffer = self.sponge_state[..SPONGE_RATE].to_vec();
+    }
+}
+
+/// Declares the signal `name` and assigns `values` to it, flattened if `width > 1`.
+fn write_signals(code: &mut String, name: &str, values: &[String], width: usize) {
+    let len = values.len() / width;
+    match width {
+        1 => writeln!(code, "  signal {name}[{len}];").unwrap(),
+        _ => writeln!(code, "  signal {name}[{len}][{width}];").unwrap(),
+    }
+    for (i, v) in values.iter().enumerate() {
+        match width {
+            1 => writeln!(code, "  {name}[{i}] <== {v};").unwrap(),
+            _ => writeln!(code, "  {name}[{}][{}] <== {v};", i / width, i % width).unwrap(),
+        }
+    }
+}
+
+/// Checks that each element of `input` is canonical.
+fn write_range_check(code: &mut String, input: &VerifierInput) {
+    let component = format!("{}_canonical", input.name);
+    writeln!(code, "  component {component}[{}];", input.len()).unwrap();
+    let mut indent = "  ".to_string();
+    let mut index = "0".to_string();
+    let mut access = input.name.clone();
+    for (k, d) in input.dims.iter().enumerate() {
+        writeln!(code, "{indent}for (var i{k} = 0; i{k} < {d}; i{k}++) {{").unwrap();
+        indent.push_str("  ");
+        index = match k {
+            0 => format!("i{k}"),
+            _ => format!("({index}) * {d} + i{k}"),
+        };
+        write!(access, "[i{k}]").unwrap();
+    }
+    writeln!(code, "{indent}{component}[{index}] = GlCanonical();").unwrap();
+    writeln!(code, "{indent}{component}[{index}].in <== {access};").unwrap();
+    for _ in &input.dims {
+        indent.truncate(indent.len() - 2);
+        writeln!(code, "{indent}}}").unwrap();
+    }
+}
+
+/// `verifier.circom`: the `Verifier` template, as `verify` with the challenges of
+/// `get_challenges`, and the main component.
+fn verifier_circom<F: RichField + Extendable<D>, const D: usize>(
+    common: &CommonCircuitData<F, D>,
+) -> String {
+    let config = &common.config;
+    let params = &common.fri_params;
+    let num_challenges = config.num_challenges;
+    let cap_height = config.fri_config.cap_height;
+    let cap_len = 1 << cap_height;
+    let num_queries = config.fri_config.num_query_rounds;
+    let num_reductions = params.reduction_arity_bits.len();
+    let inputs = verifier_inputs(common);
+
+    let mut code = String::from(
+        "pragma circom 2.1.0;
+
+include \"constants.circom\";
+include \"goldilocks.circom\";
+include \"poseidon.circom\";
+include \"fri.circom\";
+include \"plonk.circom\";
+
+template Verifier() {
+",
+    );
+    for input in &inputs {
+        let dims = input
+            .dims
+            .iter()
+            .map(|d| format!("[{d}]"))
+            .collect::<String>();
+        writeln!(code, "  signal input {}{dims};", input.name).unwrap();
+    }
+    code.push_str("\n  // The inputs are canonical field elements.\n");
+    for input in &inputs {
+        write_range_check(&mut code, input);
+    }
+
+    code.push_str("\n  signal public_input_hash[4];\n");
+    if common.num_public_inputs > 0 {
+        code.push_str("  public_input_hash <== HashNoPad(NUM_PUBLIC_INPUTS())(public_inputs);\n");
+    } else {
+        code.push_str("  for (var i = 0; i < 4; i++) {\n    public_input_hash[i] <== 0;\n  }\n");
+    }
+    writeln!(
+        code,
+        "  signal constants_sigmas_cap[{cap_len}][4];
+  for (var i = 0; i < {cap_len}; i++) {{
+    for (var j = 0; j < 4; j++) {{
+      constants_sigmas_cap[i][j] <== SIGMAS_CAP(i, j);
+    }}
+  }}"
+    )
+    .unwrap();
+
+    // The challenges, in the order of `get_challenges`.
+    code.push_str("\n  // Fiat-Shamir.\n");
+    let mut challenger = CircomChallenger::new();
+    challenger.observe_elements((0..4).map(|i| format!("CIRCUIT_DIGEST({i})")));
+    challenger.observe_elements((0..4).map(|i| format!("public_input_hash[{i}]")));
+    challenger.observe_cap("wires_cap", cap_len);
+    let betas = challenger.get_n_challenges(num_challenges);
+    let gammas = challenger.get_n_challenges(num_challenges);
+    challenger.observe_cap("plonk_zs_partial_products_cap", cap_len);
+    let alphas = challenger.get_n_challenges(num_challenges);
+    challenger.observe_cap("quotient_polys_cap", cap_len);
+    let zeta = challenger.get_n_challenges(2);
+    for (name, len) in zeta_openings(common) {
+        challenger.observe_extension_elements(name, len);
+    }
+    challenger.observe_extension_elements("openings_plonk_zs_next", num_challenges);
+    let fri_alpha = challenger.get_n_challenges(2);
+    let mut fri_betas = Vec::new();
+    for i in 0..num_reductions {
+        challenger.observe_cap(&format!("fri_commit_phase_merkle_caps[{i}]"), cap_len);
+        fri_betas.extend(challenger.get_n_challenges(2));
+    }
+    challenger.observe_extension_elements("fri_final_poly", params.final_poly_len());
+    challenger.observe_element("fri_pow_witness".to_string());
+    let fri_pow_response = challenger.get_challenge();
+    let fri_query_indices = challenger.get_n_challenges(num_queries);
+    code.push_str(&challenger.code);
+    write_signals(&mut code, "betas", &betas, 1);
+    write_signals(&mut code, "gammas", &gammas, 1);
+    write_signals(&mut code, "alphas", &alphas, 1);
+    writeln!(
+        code,
+        "  signal zeta[2];\n  zeta[0] <== {};\n  zeta[1] <== {};",
+        zeta[0], zeta[1]
+    )
+    .unwrap();
+    writeln!(
+        code,
+        "  signal fri_alpha[2];\n  fri_alpha[0] <== {};\n  fri_alpha[1] <== {};",
+        fri_alpha[0], fri_alpha[1]
+    )
+    .unwrap();
+    if num_reductions > 0 {
+        write_signals(&mut code, "fri_betas", &fri_betas, 2);
+    }
+    writeln!(
+        code,
+        "  signal fri_pow_response;\n  fri_pow_response <== {fri_pow_response};"
+    )
+    .unwrap();
+    write_signals(&mut code, "fri_query_indices", &fri_query_indices, 1);
+
+    code.push_str(
+        "
+  // The vanishing polynomial at zeta.
+  signal zeta_pow_deg[2];
+  zeta_pow_deg <== GlExtExpPowerOf2(DEGREE_BITS())(zeta);
+  component vanishing = VanishingPolyCheck();
+",
+    );
+    for (name, _) in zeta_openings(common)
+        .into_iter()
+        .chain([("openings_plonk_zs_next", num_challenges)])
+    {
+        writeln!(
+            code,
+            "  vanishing.{} <== {name};",
+            name.trim_start_matches("openings_")
+        )
+        .unwrap();
+    }
+    code.push_str(
+        "  vanishing.public_input_hash <== public_input_hash;
+  vanishing.betas <== betas;
+  vanishing.gammas <== gammas;
+  vanishing.alphas <== alphas;
+  vanishing.zeta <== zeta;
+  vanishing.zeta_pow_deg <== zeta_pow_deg;
+",
+    );
+
+    // The openings reduced with `fri_alpha`, as `PrecomputedReducedOpenings` does.
+    let num_zeta_openings = zeta_openings(common)
+        .iter()
+        .map(|&(_, len)| len)
+        .sum::<usize>();
+    writeln!(
+        code,
+        "
+  // FRI.
+  signal zeta_next[2];
+  zeta_next <== GlExtScalarMul()(zeta, G_FROM_DEGREE_BITS());
+  component reduced_zeta = Reduce({num_zeta_openings});"
+    )
+    .unwrap();
+    let mut offset = 0;
+    for (name, len) in zeta_openings(common) {
+        writeln!(
+            code,
+            "  for (var i = 0; i < {len}; i++) {{\n    reduced_zeta.in[{offset} + i] <== {name}[i];\n  }}"
+        )
+        .unwrap();
+        offset += len;
+    }
+    let alpha_pow_bits = (usize::BITS - num_challenges.leading_zeros()).max(1);
+    writeln!(
+        code,
+        "  reduced_zeta.alpha <== fri_alpha;
+  reduced_zeta.old_eval <== GlExt(0, 0)();
+  signal reduced_openings[2][2];
+  reduced_openings[0] <== reduced_zeta.out;
+  reduced_openings[1] <== Reduce({num_challenges})(openings_plonk_zs_next, fri_alpha, GlExt(0, 0)());
+  signal fri_alpha_pow[2];
+  fri_alpha_pow <== GlExtExpN({alpha_pow_bits})(fri_alpha, {num_challenges});
+
+  component pow_check = Num2Bits(64 - FRI_POW_BITS());
+  pow_check.in <== fri_pow_response;
+"
+    )
+    .unwrap();
+
+    // The query rounds, as `fri_verifier_query_round`.
+    let oracles = oracles(common);
+    let mut components = vec![
+        "query_index_bits".to_string(),
+        "subgroup_x".to_string(),
+        "combine".to_string(),
+        "final_eval".to_string(),
+    ];
+    components.extend(oracles.iter().map(|(name, _, _)| format!("init_{name}")));
+    for i in 0..num_reductions {
+        components.extend([
+            format!("step{i}_select"),
+            format!("step{i}_eval"),
+            format!("step{i}_proof"),
+        ]);
+    }
+    for component in &components {
+        writeln!(code, "  component {component}[{num_queries}];").unwrap();
+    }
+    let init_depth = params.lde_bits() - cap_height;
+    writeln!(
+        code,
+        "  for (var q = 0; q < {num_queries}; q++) {{
+    query_index_bits[q] = Num2Bits(64);
+    query_index_bits[q].in <== fri_query_indices[q];
+    subgroup_x[q] = SubgroupX(LDE_BITS());
+    for (var b = 0; b < LDE_BITS(); b++) {{
+      subgroup_x[q].index_bits[b] <== query_index_bits[q].out[b];
+    }}
+"
+    )
+    .unwrap();
+    for ((name, _, leaf_len), cap) in oracles.iter().zip(ORACLE_CAPS) {
+        writeln!(
+            code,
+            "    init_{name}[q] = MerkleProof({leaf_len}, {init_depth}, CAP_HEIGHT());
+    init_{name}[q].leaf <== fri_query_init_{name}_v[q];
+    init_{name}[q].siblings <== fri_query_init_{name}_p[q];
+    for (var b = 0; b < LDE_BITS(); b++) {{
+      init_{name}[q].index_bits[b] <== query_index_bits[q].out[b];
+    }}
+    init_{name}[q].cap <== {cap};"
+        )
+        .unwrap();
+    }
+    writeln!(
+        code,
+        "
+    combine[q] = FriCombineInitial({num_zeta_openings}, {num_challenges});"
+    )
+    .unwrap();
+    let mut offset = 0;
+    for (name, num_polys, _) in &oracles {
+        writeln!(
+            code,
+            "    for (var i = 0; i < {num_polys}; i++) {{
+      combine[q].zeta_evals[{offset} + i] <== fri_query_init_{name}_v[q][i];
+    }}"
+        )
+        .unwrap();
+        offset += num_polys;
+    }
+    writeln!(
+        code,
+        "    for (var i = 0; i < {num_challenges}; i++) {{
+      combine[q].zeta_next_evals[i] <== fri_query_init_zs_partial_products_v[q][i];
+    }}
+    combine[q].alpha <== fri_alpha;
+    combine[q].alpha_pow <== fri_alpha_pow;
+    combine[q].reduced_openings <== reduced_openings;
+    combine[q].zeta <== zeta;
+    combine[q].zeta_next <== zeta_next;
+    combine[q].x <== subgroup_x[q].out;"
+    )
+    .unwrap();
+
+    let mut old_eval = "combine[q].out".to_string();
+    let mut x = "subgroup_x[q].out".to_string();
+    let mut index_offset = 0;
+    let mut depth = init_depth;
+    for (i, &arity_bits) in params.reduction_arity_bits.iter().enumerate() {
+        let arity = 1 << arity_bits;
+        depth -= arity_bits;
+        writeln!(
+            code,
+            "
+    step{i}_select[q] = SelectByBits({arity_bits}, 2);
+    for (var b = 0; b < {arity_bits}; b++) {{
+      step{i}_select[q].bits[b] <== query_index_bits[q].out[{index_offset} + b];
+    }}
+    step{i}_select[q].in <== fri_query_step{i}_v[q];
+    step{i}_select[q].out[0] === {old_eval}[0];
+    step{i}_select[q].out[1] === {old_eval}[1];
+    step{i}_eval[q] = ComputeEvaluation({arity_bits});
+    step{i}_eval[q].x <== {x};
+    for (var b = 0; b < {arity_bits}; b++) {{
+      step{i}_eval[q].index_bits[b] <== query_index_bits[q].out[{index_offset} + b];
+    }}
+    step{i}_eval[q].evals <== fri_query_step{i}_v[q];
+    step{i}_eval[q].beta <== fri_betas[{i}];
+    step{i}_proof[q] = MerkleProof({}, {depth}, CAP_HEIGHT());
+    for (var e = 0; e < {arity}; e++) {{
+      step{i}_proof[q].leaf[2 * e] <== fri_query_step{i}_v[q][e][0];
+      step{i}_proof[q].leaf[2 * e + 1] <== fri_query_step{i}_v[q][e][1];
+    }}
+    step{i}_proof[q].siblings <== fri_query_step{i}_p[q];
+    for (var b = 0; b < {}; b++) {{
+      step{i}_proof[q].index_bits[b] <== query_index_bits[q].out[{} + b];
+    }}
+    step{i}_proof[q].cap <== fri_commit_phase_merkle_caps[{i}];",
+            2 * arity,
+            depth + cap_height,
+            index_offset + arity_bits,
+        )
+        .unwrap();
+        old_eval = format!("step{i}_eval[q].out");
+        x = format!("step{i}_eval[q].x_next");
+        index_offset += arity_bits;
+    }
+    writeln!(
+        code,
+        "
+    final_eval[q] = FinalPolyEval(NUM_FRI_FINAL_POLY());
+    final_eval[q].coeffs <== fri_final_poly;
+    final_eval[q].x <== {x};
+    final_eval[q].out[0] === {old_eval}[0];
+    final_eval[q].out[1] === {old_eval}[1];
+  }}
+}}
+"
+    )
+    .unwrap();
+
+    if common.num_public_inputs > 0 {
+        code.push_str("component main {public [public_inputs]} = Verifier();\n");
+    } else {
+        code.push_str("component main = Verifier();\n");
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::gates::high_degree_interpolation::HighDegreeInterpolationGate;
+    use crate::gates::interpolation::InterpolationGate;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::{KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// A circuit proving the knowledge of `y` such that `x * y = z`, for public `x` and `z`.
+    fn small_circuit<C: GenericConfig<D, F = F>>(
+    ) -> Result<(CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>)> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(x);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(y, F::from_canonical_u64(5));
+        let proof = data.prove(pw)?;
+        Ok((data, proof))
+    }
+
+    /// The files of the project generated for the circuit, as opposed to the static templates.
+    const GENERATED_FILES: [&str; 3] = ["constants.circom", "gates.circom", MAIN_FILE];
+
+    /// Compares the generated files with those of `testdata/circom`, which are written instead if
+    /// `PLONKY2_BLESS` is set. A missing golden file is a failure.
+    #[test]
+    fn test_circom_verifier_golden_files() -> Result<()> {
+        let (data, _) = small_circuit::<C>()?;
+        let project = export_circom_verifier(&data.verifier_only, &data.common)?;
+
+        let gates = project.file("gates.circom").unwrap();
+        assert!(gates.contains("template EvalGateConstraints()"));
+        assert!(!gates.contains('$'));
+        assert!(project.file(MAIN_FILE).unwrap().contains("component main"));
+
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/plonk/export/testdata/circom");
+        for name in GENERATED_FILES {
+            let content = project.file(name).unwrap();
+            if std::env::var_os("PLONKY2_BLESS").is_some() {
+                std::fs::create_dir_all(&dir)?;
+                std::fs::write(dir.join(name), content)?;
+            }
+            let expected = std::fs::read_to_string(dir.join(name)).map_err(|e| {
+                anyhow!("Cannot read the golden file of {name} ({e}); rerun with PLONKY2_BLESS=1 to create it.")
+            })?;
+            assert!(
+                content == expected,
+                "{name} differs from its golden file; rerun with PLONKY2_BLESS=1 to update it."
+            );
+        }
+        Ok(())
+    }
+
+    /// Compiles the exported verifier with `circom`, then checks with `snarkjs` that the witness of
+    /// a proof satisfies its constraints, and that a tampered proof has no witness. `circom`,
+    /// `node` and `snarkjs` must be on the `PATH`.
+    #[test]
+    #[ignore]
+    fn test_circom_verifier_accepts_proof() -> Result<()> {
+        use std::process::Command;
+
+        let (data, proof) = small_circuit::<C>()?;
+        let dir = std::env::temp_dir().join(format!("plonky2_circom_{}", std::process::id()));
+        export_circom_verifier(&data.verifier_only, &data.common)?.write_to(&dir)?;
+
+        let run = |program: &str, args: &[&str]| -> Result<bool> {
+            let status = Command::new(program)
+                .args(args)
+                .current_dir(&dir)
+                .status()
+                .map_err(|e| anyhow!("Cannot run {program} ({e})."))?;
+            Ok(status.success())
+        };
+        ensure!(
+            run("circom", &[MAIN_FILE, "--r1cs", "--wasm", "-o", "."])?,
+            "circom cannot compile the verifier."
+        );
+        // Witness generation fails on the first unsatisfied `===`, so the check of the R1CS is only
+        // reached by an accepted proof.
+        let accepts = |name: &str, proof: &ProofWithPublicInputs<F, C, D>| -> Result<bool> {
+            let input = format!("{name}.json");
+            let witness = format!("{name}.wtns");
+            std::fs::write(dir.join(&input), circom_input_json(proof, &data.common)?)?;
+            Ok(run(
+                "node",
+                &[
+                    "verifier_js/generate_witness.js",
+                    "verifier_js/verifier.wasm",
+                    &input,
+                    &witness,
+                ],
+            )? && run("snarkjs", &["wtns", "check", "verifier.r1cs", &witness])?)
+        };
+        assert!(accepts("proof", &proof)?);
+
+        let mut tampered = proof;
+        tampered.public_inputs[1] += F::ONE;
+        assert!(!accepts("tampered", &tampered)?);
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_circom_input_json() -> Result<()> {
+        let (data, proof) = small_circuit::<C>()?;
+        let json = circom_input_json(&proof, &data.common)?;
+
+        let declared = export_circom_verifier(&data.verifier_only, &data.common)?;
+        let verifier = declared.file(MAIN_FILE).unwrap();
+        for input in verifier_inputs(&data.common) {
+            assert!(json.contains(&format!("\"{}\": ", input.name)));
+            assert!(verifier.contains(&format!("signal input {}", input.name)));
+        }
+        assert!(json.contains("\"public_inputs\": [\"3\", \"15\"]"));
+        assert!(json.contains(&format!(
+            "\"fri_pow_witness\": \"{}\"",
+            proof.proof.opening_proof.pow_witness.to_canonical_u64()
+        )));
+
+        // The shape of the proof is checked.
+        let mut truncated = proof;
+        truncated.public_inputs.pop();
+        assert!(circom_input_json(&truncated, &data.common).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_hasher() -> Result<()> {
+        let (data, proof) = small_circuit::<KeccakGoldilocksConfig>()?;
+        assert!(export_circom_verifier(&data.verifier_only, &data.common).is_err());
+        assert!(circom_input_json(&proof, &data.common).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_gate() {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        builder.add_gate(HighDegreeInterpolationGate::<F, D>::new(2), vec![]);
+        let data = builder.build::<C>();
+        let err = export_circom_verifier(&data.verifier_only, &data.common).unwrap_err();
+        assert!(err.to_string().contains("has no Circom template"));
+    }
+}
@@ -0,0 +1,127 @@
+pragma circom 2.1.0;
+
+include "constants.circom";
+include "goldilocks.circom";
+
+// The point of the LDE domain at the given index, `g * w^reverse_bits(index)`, where `w` generates
+// the subgroup of order 2^LOG_N and `g` the multiplicative group. The index is given by its
+// little-endian bits.
+template SubgroupX(LOG_N) {
+  signal input index_bits[LOG_N];
+  signal output out;
+
+  var w = GL_ROOT_OF_UNITY(LOG_N);
+  signal acc[LOG_N + 1];
+  acc[0] <== GL_MULTIPLICATIVE_GENERATOR();
+  for (var i = 0; i < LOG_N; i++) {
+    var c = gl_exp(w, 1 << (LOG_N - 1 - i));
+    acc[i + 1] <== GlMul()(acc[i], 1 + index_bits[i] * (c - 1));
+  }
+  out <== acc[LOG_N];
+}
+
+// `fri_combine_initial`: the combination of the initial polynomials at `x`, whose openings at
+// `zeta` and `zeta_next` have been reduced to `reduced_openings`. `alpha_pow` is
+// `alpha^NUM_ZETA_NEXT_EVALS`.
+template FriCombineInitial(NUM_ZETA_EVALS, NUM_ZETA_NEXT_EVALS) {
+  signal input zeta_evals[NUM_ZETA_EVALS];
+  signal input zeta_next_evals[NUM_ZETA_NEXT_EVALS];
+  signal input alpha[2];
+  signal input alpha_pow[2];
+  signal input reduced_openings[2][2];
+  signal input zeta[2];
+  signal input zeta_next[2];
+  signal input x;
+  signal output out[2];
+
+  signal x_ext[2];
+  x_ext[0] <== x;
+  x_ext[1] <== 0;
+
+  component reduce_zeta = Reduce(NUM_ZETA_EVALS);
+  for (var i = 0; i < NUM_ZETA_EVALS; i++) {
+    reduce_zeta.in[i][0] <== zeta_evals[i];
+    reduce_zeta.in[i][1] <== 0;
+  }
+  reduce_zeta.alpha <== alpha;
+  reduce_zeta.old_eval <== GlExt(0, 0)();
+  signal zeta_term[2];
+  zeta_term <== GlExtDiv()(GlExtSub()(reduce_zeta.out, reduced_openings[0]), GlExtSub()(x_ext, zeta));
+
+  component reduce_zeta_next = Reduce(NUM_ZETA_NEXT_EVALS);
+  for (var i = 0; i < NUM_ZETA_NEXT_EVALS; i++) {
+    reduce_zeta_next.in[i][0] <== zeta_next_evals[i];
+    reduce_zeta_next.in[i][1] <== 0;
+  }
+  reduce_zeta_next.alpha <== alpha;
+  reduce_zeta_next.old_eval <== GlExt(0, 0)();
+  signal zeta_next_term[2];
+  zeta_next_term <== GlExtDiv()(GlExtSub()(reduce_zeta_next.out, reduced_openings[1]), GlExtSub()(x_ext, zeta_next));
+
+  signal sum[2];
+  sum <== GlExtAdd()(GlExtMul()(zeta_term, alpha_pow), zeta_next_term);
+  out <== GlExtScalarMul()(sum, x);
+}
+
+// `compute_evaluation`: the value at `beta` of the polynomial interpolating `evals` on the coset of
+// `x`, of order 2^ARITY_BITS. Also outputs `x^(2^ARITY_BITS)`, the point of the next round.
+template ComputeEvaluation(ARITY_BITS) {
+  var ARITY = 1 << ARITY_BITS;
+  signal input x;
+  signal input index_bits[ARITY_BITS];
+  signal input evals[ARITY][2];
+  signal input beta[2];
+  signal output out[2];
+  signal output x_next;
+
+  var g = GL_ROOT_OF_UNITY(ARITY_BITS);
+  var g_inv = gl_inverse(g);
+
+  // The coset starts at x * g^-reverse_bits(index).
+  signal coset_start[ARITY_BITS + 1];
+  coset_start[0] <== x;
+  for (var i = 0; i < ARITY_BITS; i++) {
+    var c = gl_exp(g_inv, 1 << (ARITY_BITS - 1 - i));
+    coset_start[i + 1] <== GlMul()(coset_start[i], 1 + index_bits[i] * (c - 1));
+  }
+  x_next <== GlExpPowerOf2(ARITY_BITS)(x);
+
+  // The points are coset_start * g^i, at which the evaluations are in bit-reversed order. The
+  // interpolant at beta is
+  //   (beta^ARITY - x_next) / (ARITY * x_next) * sum_i evals'[i] * points[i] / (beta - points[i]).
+  signal points[ARITY];
+  signal points_ext[ARITY][2];
+  signal terms[ARITY][2];
+  signal sum[ARITY + 1][2];
+  sum[0] <== GlExt(0, 0)();
+  for (var i = 0; i < ARITY; i++) {
+    points[i] <== GlMul()(coset_start[ARITY_BITS], gl_exp(g, i));
+    points_ext[i][0] <== points[i];
+    points_ext[i][1] <== 0;
+    var e = reverse_bits(i, ARITY_BITS);
+    terms[i] <== GlExtScalarMul()(GlExtDiv()(evals[e], GlExtSub()(beta, points_ext[i])), points[i]);
+    sum[i + 1] <== GlExtAdd()(sum[i], terms[i]);
+  }
+  signal beta_pow[2];
+  beta_pow <== GlExtExpPowerOf2(ARITY_BITS)(beta);
+  signal x_next_ext[2];
+  x_next_ext[0] <== x_next;
+  x_next_ext[1] <== 0;
+  signal scale;
+  scale <== GlInv()(GlMul()(ARITY, x_next));
+  out <== GlExtScalarMul()(GlExtMul()(sum[ARITY], GlExtSub()(beta_pow, x_next_ext)), scale);
+}
+
+// The evaluation at `x` of the polynomial of coefficients `coeffs`.
+template FinalPolyEval(N) {
+  signal input coeffs[N][2];
+  signal input x;
+  signal output out[2];
+
+  signal acc[N][2];
+  acc[N - 1] <== coeffs[N - 1];
+  for (var i = N - 1; i > 0; i--) {
+    acc[i - 1] <== GlExtAdd()(GlExtScalarMul()(acc[i], x), coeffs[i - 1]);
+  }
+  out <== acc[0];
+}
@@ -0,0 +1,324 @@
+pragma circom 2.1.0;
+
+// Arithmetic in the Goldilocks field, p = 2^64 - 2^32 + 1, and in its quadratic extension
+// F_p[X] / (X^2 - 7), whose elements are pairs of field elements.
+//
+// Every value handed from one template to another is canonical, i.e. an integer below p. Sums and
+// products are computed over the native field, which is large enough for them not to wrap, then
+// reduced with `GlReduce`.
+
+function GL_P() {
+  return 18446744069414584321;
+}
+
+// The non-residue defining the quadratic extension.
+function GL_W() {
+  return 7;
+}
+
+function gl_mul(a, b) {
+  return (a * b) % GL_P();
+}
+
+function gl_exp(x, e) {
+  var result = 1;
+  var base = x;
+  var n = e;
+  while (n > 0) {
+    if (n % 2 == 1) {
+      result = gl_mul(result, base);
+    }
+    base = gl_mul(base, base);
+    n = n \ 2;
+  }
+  return result;
+}
+
+function gl_inverse(x) {
+  return gl_exp(x, GL_P() - 2);
+}
+
+function reverse_bits(x, bits) {
+  var result = 0;
+  for (var i = 0; i < bits; i++) {
+    result = result * 2 + ((x >> i) & 1);
+  }
+  return result;
+}
+
+template Num2Bits(N) {
+  signal input in;
+  signal output out[N];
+
+  var acc = 0;
+  var e = 1;
+  for (var i = 0; i < N; i++) {
+    out[i] <-- (in >> i) & 1;
+    out[i] * (out[i] - 1) === 0;
+    acc += out[i] * e;
+    e = e + e;
+  }
+  acc === in;
+}
+
+template IsZero() {
+  signal input in;
+  signal output out;
+
+  signal inv;
+  inv <-- in != 0 ? 1 / in : 0;
+  out <== 1 - in * inv;
+  in * out === 0;
+}
+
+// Checks that `in` is below p.
+template GlCanonical() {
+  signal input in;
+
+  component bits = Num2Bits(64);
+  bits.in <== in;
+  var lo = 0;
+  var hi = 0;
+  var e = 1;
+  for (var i = 0; i < 32; i++) {
+    lo += bits.out[i] * e;
+    hi += bits.out[32 + i] * e;
+    e = e + e;
+  }
+  // Values whose 32 high bits are all set are only canonical if their low bits are all zero.
+  signal hi_is_max;
+  hi_is_max <== IsZero()(hi - 4294967295);
+  hi_is_max * lo === 0;
+}
+
+// Checks that each of the `N` values is below p.
+template GlCanonicalArray(N) {
+  signal input in[N];
+
+  component check[N];
+  for (var i = 0; i < N; i++) {
+    check[i] = GlCanonical();
+    check[i].in <== in[i];
+  }
+}
+
+// Reduces `x`, which must be below 2^(64 + N), modulo p.
+template GlReduce(N) {
+  signal input x;
+  signal output out;
+
+  signal q;
+  q <-- x \ GL_P();
+  out <-- x % GL_P();
+  x === q * GL_P() + out;
+  component q_bits = Num2Bits(N);
+  q_bits.in <== q;
+  component canonical = GlCanonical();
+  canonical.in <== out;
+}
+
+template GlAdd() {
+  signal input a;
+  signal input b;
+  signal output out;
+  out <== GlReduce(1)(a + b);
+}
+
+template GlSub() {
+  signal input a;
+  signal input b;
+  signal output out;
+  out <== GlReduce(1)(a + GL_P() - b);
+}
+
+template GlMul() {
+  signal input a;
+  signal input b;
+  signal output out;
+  out <== GlReduce(64)(a * b);
+}
+
+template GlInv() {
+  signal input in;
+  signal output out;
+
+  out <-- gl_inverse(in);
+  component canonical = GlCanonical();
+  canonical.in <== out;
+  signal one;
+  one <== GlMul()(in, out);
+  one === 1;
+}
+
+// x^(2^N).
+template GlExpPowerOf2(N) {
+  signal input in;
+  signal output out;
+
+  signal acc[N + 1];
+  acc[0] <== in;
+  for (var i = 0; i < N; i++) {
+    acc[i + 1] <== GlMul()(acc[i], acc[i]);
+  }
+  out <== acc[N];
+}
+
+template GlExt(x, y) {
+  signal output out[2];
+  out[0] <== x;
+  out[1] <== y;
+}
+
+template GlExtAdd() {
+  signal input a[2];
+  signal input b[2];
+  signal output out[2];
+  out[0] <== GlReduce(1)(a[0] + b[0]);
+  out[1] <== GlReduce(1)(a[1] + b[1]);
+}
+
+template GlExtSub() {
+  signal input a[2];
+  signal input b[2];
+  signal output out[2];
+  out[0] <== GlReduce(1)(a[0] + GL_P() - b[0]);
+  out[1] <== GlReduce(1)(a[1] + GL_P() - b[1]);
+}
+
+template GlExtMul() {
+  signal input a[2];
+  signal input b[2];
+  signal output out[2];
+
+  signal a1_b1;
+  a1_b1 <== a[1] * b[1];
+  signal a0_b1;
+  a0_b1 <== a[0] * b[1];
+  out[0] <== GlReduce(67)(a[0] * b[0] + GL_W() * a1_b1);
+  out[1] <== GlReduce(65)(a[1] * b[0] + a0_b1);
+}
+
+template GlExtSquare() {
+  signal input in[2];
+  signal output out[2];
+  out <== GlExtMul()(in, in);
+}
+
+// Multiplies an extension element by a base field element.
+template GlExtScalarMul() {
+  signal input x[2];
+  signal input a;
+  signal output out[2];
+  out[0] <== GlReduce(64)(x[0] * a);
+  out[1] <== GlReduce(64)(x[1] * a);
+}
+
+template GlExtInv() {
+  signal input in[2];
+  signal output out[2];
+
+  // (a + bX)^-1 = (a - bX) / (a^2 - 7b^2).
+  var norm = (gl_mul(in[0], in[0]) + GL_P() - gl_mul(GL_W(), gl_mul(in[1], in[1]))) % GL_P();
+  var norm_inv = gl_inverse(norm);
+  out[0] <-- gl_mul(in[0], norm_inv);
+  out[1] <-- gl_mul(GL_P() - in[1], norm_inv);
+  component canonical = GlCanonicalArray(2);
+  canonical.in <== out;
+  signal one[2];
+  one <== GlExtMul()(in, out);
+  one[0] === 1;
+  one[1] === 0;
+}
+
+template GlExtDiv() {
+  signal input a[2];
+  signal input b[2];
+  signal output out[2];
+  out <== GlExtMul()(a, GlExtInv()(b));
+}
+
+// x^n, for an exponent `n` of at most N bits.
+template GlExtExpN(N) {
+  signal input x[2];
+  signal input n;
+  signal output out[2];
+
+  component bits = Num2Bits(N);
+  bits.in <== n;
+  signal acc[N + 1][2];
+  signal factor[N][2];
+  acc[0][0] <== 1;
+  acc[0][1] <== 0;
+  for (var i = 0; i < N; i++) {
+    var bit = bits.out[N - 1 - i];
+    // x if the bit is set, 1 otherwise.
+    factor[i][0] <== bit * (x[0] - 1) + 1;
+    factor[i][1] <== bit * x[1];
+    acc[i + 1] <== GlExtMul()(GlExtSquare()(acc[i]), factor[i]);
+  }
+  out <== acc[N];
+}
+
+// x^(2^N).
+template GlExtExpPowerOf2(N) {
+  signal input in[2];
+  signal output out[2];
+
+  signal acc[N + 1][2];
+  acc[0] <== in;
+  for (var i = 0; i < N; i++) {
+    acc[i + 1] <== GlExtSquare()(acc[i]);
+  }
+  out <== acc[N];
+}
+
+// Adds the filtered constraint `filter * value` to the accumulated `constraint`.
+template ConstraintPush() {
+  signal input constraint[2];
+  signal input filter[2];
+  signal input value[2];
+  signal output out[2];
+  out <== GlExtAdd()(constraint, GlExtMul()(filter, value));
+}
+
+// Multiplies the elements of the extension algebra stored at `wires[L..L + 2]` and
+// `wires[R..R + 2]`.
+template WiresAlgebraMul(L, R) {
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal output out[2][2];
+  out[0] <== GlExtAdd()(GlExtMul()(wires[L], wires[R]), GlExtScalarMul()(GlExtMul()(wires[L + 1], wires[R + 1]), GL_W()));
+  out[1] <== GlExtAdd()(GlExtMul()(wires[L], wires[R + 1]), GlExtMul()(wires[L + 1], wires[R]));
+}
+
+// old_eval * alpha^N + sum_i in[i] * alpha^i.
+template Reduce(N) {
+  signal input in[N][2];
+  signal input alpha[2];
+  signal input old_eval[2];
+  signal output out[2];
+
+  signal acc[N + 1][2];
+  acc[N] <== old_eval;
+  for (var i = N; i > 0; i--) {
+    acc[i - 1] <== GlExtAdd()(GlExtMul()(acc[i], alpha), in[i - 1]);
+  }
+  out <== acc[0];
+}
+
+// Selects `in[index]` where `index` is given by its little-endian bits.
+template SelectByBits(BITS, WIDTH) {
+  signal input bits[BITS];
+  signal input in[1 << BITS][WIDTH];
+  signal output out[WIDTH];
+
+  signal level[BITS + 1][1 << BITS][WIDTH];
+  level[0] <== in;
+  for (var l = 0; l < BITS; l++) {
+    for (var i = 0; i < (1 << (BITS - l - 1)); i++) {
+      for (var j = 0; j < WIDTH; j++) {
+        level[l + 1][i][j] <== level[l][2 * i][j] + bits[l] * (level[l][2 * i + 1][j] - level[l][2 * i][j]);
+      }
+    }
+  }
+  out <== level[BITS][0];
+}
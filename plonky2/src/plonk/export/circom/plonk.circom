@@ -0,0 +1,109 @@
+pragma circom 2.1.0;
+
+include "constants.circom";
+include "goldilocks.circom";
+include "gates.circom";
+
+// Checks the identities `vanishing(zeta) = Z_H(zeta) quotient(zeta)`, where the vanishing
+// polynomial is computed from the openings as `eval_vanishing_poly` does. `zeta_pow_deg` is
+// `zeta^(2^DEGREE_BITS())`.
+template VanishingPolyCheck() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input plonk_sigmas[NUM_OPENINGS_PLONK_SIGMAS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input plonk_zs[NUM_OPENINGS_PLONK_ZS()][2];
+  signal input plonk_zs_next[NUM_OPENINGS_PLONK_ZS_NEXT()][2];
+  signal input partial_products[NUM_OPENINGS_PARTIAL_PRODUCTS()][2];
+  signal input quotient_polys[NUM_OPENINGS_QUOTIENT_POLYS()][2];
+  signal input public_input_hash[4];
+  signal input betas[NUM_CHALLENGES()];
+  signal input gammas[NUM_CHALLENGES()];
+  signal input alphas[NUM_CHALLENGES()];
+  signal input zeta[2];
+  signal input zeta_pow_deg[2];
+
+  var NUM_CHUNKS = NUM_PARTIAL_PRODUCTS() + 1;
+  var NUM_Z_1_TERMS = NUM_CHALLENGES();
+  var NUM_PARTIAL_PRODUCT_TERMS = NUM_CHALLENGES() * NUM_CHUNKS;
+  var NUM_TERMS = NUM_Z_1_TERMS + NUM_PARTIAL_PRODUCT_TERMS + NUM_GATE_CONSTRAINTS();
+  var QDF = QUOTIENT_DEGREE_FACTOR();
+  signal terms[NUM_TERMS][2];
+
+  // L_0(zeta) = Z_H(zeta) / (n (zeta - 1)).
+  signal z_h_zeta[2];
+  z_h_zeta <== GlExtSub()(zeta_pow_deg, GlExt(1, 0)());
+  signal l_0[2];
+  l_0 <== GlExtDiv()(z_h_zeta, GlExtScalarMul()(GlExtSub()(zeta, GlExt(1, 0)()), 1 << DEGREE_BITS()));
+
+  signal s_ids[NUM_ROUTED_WIRES()][2];
+  for (var j = 0; j < NUM_ROUTED_WIRES(); j++) {
+    s_ids[j] <== GlExtScalarMul()(zeta, K_IS(j));
+  }
+
+  signal gamma_ext[NUM_CHALLENGES()][2];
+  signal numerators[NUM_CHALLENGES()][NUM_ROUTED_WIRES()][2];
+  signal denominators[NUM_CHALLENGES()][NUM_ROUTED_WIRES()][2];
+  // The products of the numerators and denominators since the start of their chunk.
+  signal numerator_prods[NUM_CHALLENGES()][NUM_ROUTED_WIRES()][2];
+  signal denominator_prods[NUM_CHALLENGES()][NUM_ROUTED_WIRES()][2];
+  // Z(x), the partial products, then Z(g x).
+  signal accs[NUM_CHALLENGES()][NUM_CHUNKS + 1][2];
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    terms[i] <== GlExtMul()(l_0, GlExtSub()(plonk_zs[i], GlExt(1, 0)()));
+
+    gamma_ext[i][0] <== gammas[i];
+    gamma_ext[i][1] <== 0;
+    for (var j = 0; j < NUM_ROUTED_WIRES(); j++) {
+      numerators[i][j] <== GlExtAdd()(GlExtAdd()(wires[j], GlExtScalarMul()(s_ids[j], betas[i])), gamma_ext[i]);
+      denominators[i][j] <== GlExtAdd()(GlExtAdd()(wires[j], GlExtScalarMul()(plonk_sigmas[j], betas[i])), gamma_ext[i]);
+      if (j % QDF == 0) {
+        numerator_prods[i][j] <== numerators[i][j];
+        denominator_prods[i][j] <== denominators[i][j];
+      } else {
+        numerator_prods[i][j] <== GlExtMul()(numerator_prods[i][j - 1], numerators[i][j]);
+        denominator_prods[i][j] <== GlExtMul()(denominator_prods[i][j - 1], denominators[i][j]);
+      }
+    }
+
+    accs[i][0] <== plonk_zs[i];
+    for (var c = 0; c < NUM_PARTIAL_PRODUCTS(); c++) {
+      accs[i][c + 1] <== partial_products[i * NUM_PARTIAL_PRODUCTS() + c];
+    }
+    accs[i][NUM_CHUNKS] <== plonk_zs_next[i];
+
+    for (var c = 0; c < NUM_CHUNKS; c++) {
+      var last = (c + 1) * QDF - 1;
+      if (last >= NUM_ROUTED_WIRES()) {
+        last = NUM_ROUTED_WIRES() - 1;
+      }
+      terms[NUM_Z_1_TERMS + i * NUM_CHUNKS + c] <== GlExtSub()(GlExtMul()(accs[i][c], numerator_prods[i][last]), GlExtMul()(accs[i][c + 1], denominator_prods[i][last]));
+    }
+  }
+
+  component gates = EvalGateConstraints();
+  gates.constants <== constants;
+  gates.wires <== wires;
+  gates.public_input_hash <== public_input_hash;
+  for (var k = 0; k < NUM_GATE_CONSTRAINTS(); k++) {
+    terms[NUM_Z_1_TERMS + NUM_PARTIAL_PRODUCT_TERMS + k] <== gates.out[k];
+  }
+
+  signal alpha_ext[NUM_CHALLENGES()][2];
+  signal vanishing[NUM_CHALLENGES()][2];
+  signal quotient_chunks[NUM_CHALLENGES()][QDF][2];
+  signal quotient[NUM_CHALLENGES()][2];
+  signal expected[NUM_CHALLENGES()][2];
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    alpha_ext[i][0] <== alphas[i];
+    alpha_ext[i][1] <== 0;
+    vanishing[i] <== Reduce(NUM_TERMS)(terms, alpha_ext[i], GlExt(0, 0)());
+
+    for (var k = 0; k < QDF; k++) {
+      quotient_chunks[i][k] <== quotient_polys[i * QDF + k];
+    }
+    quotient[i] <== Reduce(QDF)(quotient_chunks[i], zeta_pow_deg, GlExt(0, 0)());
+    expected[i] <== GlExtMul()(z_h_zeta, quotient[i]);
+    vanishing[i][0] === expected[i][0];
+    vanishing[i][1] === expected[i][1];
+  }
+}
@@ -0,0 +1,151 @@
+pragma circom 2.1.0;
+
+include "constants.circom";
+include "goldilocks.circom";
+
+// The Poseidon permutation of width 12 over the Goldilocks field, with 8 full rounds and 22 partial
+// rounds, as `Poseidon::poseidon_naive`. The round constants and the MDS matrix come from
+// `constants.circom`.
+
+// x^7, for x below 2^65.
+template PoseidonSbox() {
+  signal input in;
+  signal output out;
+
+  signal x2;
+  x2 <== GlReduce(66)(in * in);
+  signal x3;
+  x3 <== GlReduce(66)(x2 * in);
+  signal x4;
+  x4 <== GlReduce(64)(x2 * x2);
+  out <== GlReduce(64)(x3 * x4);
+}
+
+template Poseidon() {
+  signal input in[12];
+  signal output out[12];
+
+  var N_ROUNDS = 30;
+  var HALF_N_FULL_ROUNDS = 4;
+  signal state[N_ROUNDS + 1][12];
+  signal sboxed[N_ROUNDS][12];
+  state[0] <== in;
+  for (var r = 0; r < N_ROUNDS; r++) {
+    var full = r < HALF_N_FULL_ROUNDS || r >= N_ROUNDS - HALF_N_FULL_ROUNDS;
+    for (var i = 0; i < 12; i++) {
+      if (full || i == 0) {
+        sboxed[r][i] <== PoseidonSbox()(state[r][i] + GL_CONST(i + 12 * r));
+      } else {
+        sboxed[r][i] <== state[r][i] + GL_CONST(i + 12 * r);
+      }
+    }
+    // The entries of the MDS matrix are small, so each row is reduced only once.
+    for (var i = 0; i < 12; i++) {
+      var acc = sboxed[r][i] * GL_MDS_DIAG(i);
+      for (var j = 0; j < 12; j++) {
+        acc += sboxed[r][(i + j) % 12] * GL_MDS_CIRC(j);
+      }
+      state[r + 1][i] <== GlReduce(16)(acc);
+    }
+  }
+  out <== state[N_ROUNDS];
+}
+
+// `hash_n_to_hash_no_pad`, for N > 0 inputs.
+template HashNoPad(N) {
+  signal input in[N];
+  signal output out[4];
+
+  var NUM_CHUNKS = (N + 7) \ 8;
+  component perm[NUM_CHUNKS];
+  for (var c = 0; c < NUM_CHUNKS; c++) {
+    perm[c] = Poseidon();
+    for (var j = 0; j < 12; j++) {
+      if (j < 8 && 8 * c + j < N) {
+        perm[c].in[j] <== in[8 * c + j];
+      } else if (c == 0) {
+        perm[c].in[j] <== 0;
+      } else {
+        perm[c].in[j] <== perm[c - 1].out[j];
+      }
+    }
+  }
+  for (var j = 0; j < 4; j++) {
+    out[j] <== perm[NUM_CHUNKS - 1].out[j];
+  }
+}
+
+// `Hasher::hash_or_noop`: leaves of at most 4 elements are their own digests.
+template HashOrNoop(N) {
+  signal input in[N];
+  signal output out[4];
+
+  if (N <= 4) {
+    for (var j = 0; j < 4; j++) {
+      if (j < N) {
+        out[j] <== in[j];
+      } else {
+        out[j] <== 0;
+      }
+    }
+  } else {
+    out <== HashNoPad(N)(in);
+  }
+}
+
+template TwoToOne() {
+  signal input left[4];
+  signal input right[4];
+  signal output out[4];
+
+  component perm = Poseidon();
+  for (var j = 0; j < 4; j++) {
+    perm.in[j] <== left[j];
+    perm.in[4 + j] <== right[j];
+    perm.in[8 + j] <== 0;
+  }
+  for (var j = 0; j < 4; j++) {
+    out[j] <== perm.out[j];
+  }
+}
+
+// `verify_merkle_proof_to_cap`, for a proof of DEPTH > 0 siblings. The leaf index is given by its
+// little-endian bits, the last CAP_HEIGHT of which select the element of the cap.
+template MerkleProof(LEAF_LEN, DEPTH, CAP_HEIGHT) {
+  signal input leaf[LEAF_LEN];
+  signal input siblings[DEPTH][4];
+  signal input index_bits[DEPTH + CAP_HEIGHT];
+  signal input cap[1 << CAP_HEIGHT][4];
+
+  signal digest[DEPTH + 1][4];
+  signal left[DEPTH][4];
+  signal right[DEPTH][4];
+  digest[0] <== HashOrNoop(LEAF_LEN)(leaf);
+  for (var i = 0; i < DEPTH; i++) {
+    for (var j = 0; j < 4; j++) {
+      left[i][j] <== digest[i][j] + index_bits[i] * (siblings[i][j] - digest[i][j]);
+      right[i][j] <== siblings[i][j] + digest[i][j] - left[i][j];
+    }
+    digest[i + 1] <== TwoToOne()(left[i], right[i]);
+  }
+
+  // One-hot encoding of the index of the cap element.
+  signal one_hot[CAP_HEIGHT + 1][1 << CAP_HEIGHT];
+  one_hot[0][0] <== 1;
+  for (var l = 0; l < CAP_HEIGHT; l++) {
+    var bit = index_bits[DEPTH + l];
+    for (var i = 0; i < (1 << l); i++) {
+      one_hot[l + 1][i + (1 << l)] <== one_hot[l][i] * bit;
+      one_hot[l + 1][i] <== one_hot[l][i] - one_hot[l + 1][i + (1 << l)];
+    }
+  }
+  signal selected[1 << CAP_HEIGHT][4];
+  for (var j = 0; j < 4; j++) {
+    var root = 0;
+    for (var i = 0; i < (1 << CAP_HEIGHT); i++) {
+      selected[i][j] <== one_hot[CAP_HEIGHT][i] * cap[i][j];
+      root += selected[i][j];
+    }
+    root === digest[DEPTH][j];
+  }
+}
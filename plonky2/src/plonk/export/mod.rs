@@ -0,0 +1,3 @@
+//! Exports of the verifier of a circuit to other proof systems.
+
+pub mod circom;
@@ -0,0 +1,661 @@
+pragma circom 2.1.0;
+
+function NUM_OPENINGS_CONSTANTS() {
+  return 4;
+}
+function NUM_OPENINGS_PLONK_SIGMAS() {
+  return 80;
+}
+function NUM_OPENINGS_WIRES() {
+  return 135;
+}
+function NUM_OPENINGS_PLONK_ZS() {
+  return 2;
+}
+function NUM_OPENINGS_PARTIAL_PRODUCTS() {
+  return 18;
+}
+function NUM_OPENINGS_QUOTIENT_POLYS() {
+  return 16;
+}
+function NUM_OPENINGS_PLONK_ZS_NEXT() {
+  return 2;
+}
+function NUM_CHALLENGES() {
+  return 2;
+}
+function NUM_ROUTED_WIRES() {
+  return 80;
+}
+function NUM_PARTIAL_PRODUCTS() {
+  return 9;
+}
+function QUOTIENT_DEGREE_FACTOR() {
+  return 8;
+}
+function DEGREE_BITS() {
+  return 2;
+}
+function NUM_GATE_CONSTRAINTS() {
+  return 123;
+}
+function NUM_SELECTORS() {
+  return 2;
+}
+function NUM_PUBLIC_INPUTS() {
+  return 2;
+}
+function CAP_HEIGHT() {
+  return 4;
+}
+function LDE_BITS() {
+  return 5;
+}
+function NUM_FRI_QUERY_ROUND() {
+  return 28;
+}
+function FRI_POW_BITS() {
+  return 16;
+}
+function NUM_FRI_FINAL_POLY() {
+  return 4;
+}
+function K_IS(i) {
+  var value[80];
+  value[0] = 1;
+  value[1] = 7;
+  value[2] = 49;
+  value[3] = 343;
+  value[4] = 2401;
+  value[5] = 16807;
+  value[6] = 117649;
+  value[7] = 823543;
+  value[8] = 5764801;
+  value[9] = 40353607;
+  value[10] = 282475249;
+  value[11] = 1977326743;
+  value[12] = 13841287201;
+  value[13] = 96889010407;
+  value[14] = 678223072849;
+  value[15] = 4747561509943;
+  value[16] = 33232930569601;
+  value[17] = 232630513987207;
+  value[18] = 1628413597910449;
+  value[19] = 11398895185373143;
+  value[20] = 79792266297612001;
+  value[21] = 558545864083284007;
+  value[22] = 3909821048582988049;
+  value[23] = 8922003270666332022;
+  value[24] = 7113790686420571191;
+  value[25] = 12903046666114829695;
+  value[26] = 16534350385145470581;
+  value[27] = 5059988279530788141;
+  value[28] = 16973173887300932666;
+  value[29] = 8131752794619022736;
+  value[30] = 1582037354089406189;
+  value[31] = 11074261478625843323;
+  value[32] = 3732854072722565977;
+  value[33] = 7683234439643377518;
+  value[34] = 16889152938674473984;
+  value[35] = 7543606154233811962;
+  value[36] = 15911754940807515092;
+  value[37] = 701820169165099718;
+  value[38] = 4912741184155698026;
+  value[39] = 15942444219675301861;
+  value[40] = 916645121239607101;
+  value[41] = 6416515848677249707;
+  value[42] = 8022122801911579307;
+  value[43] = 814627405137302186;
+  value[44] = 5702391835961115302;
+  value[45] = 3023254712898638472;
+  value[46] = 2716038920875884983;
+  value[47] = 565528376716610560;
+  value[48] = 3958698637016273920;
+  value[49] = 9264146389699333119;
+  value[50] = 9508792519651578870;
+  value[51] = 11221315429317299127;
+  value[52] = 4762231727562756605;
+  value[53] = 14888878023524711914;
+  value[54] = 11988425817600061793;
+  value[55] = 10132004445542095267;
+  value[56] = 15583798910550913906;
+  value[57] = 16852872026783475737;
+  value[58] = 7289639770996824233;
+  value[59] = 14133990258148600989;
+  value[60] = 6704211459967285318;
+  value[61] = 10035992080941828584;
+  value[62] = 14911712358349047125;
+  value[63] = 12148266161370408270;
+  value[64] = 11250886851934520606;
+  value[65] = 4969231685883306958;
+  value[66] = 16337877731768564385;
+  value[67] = 3684679705892444769;
+  value[68] = 7346013871832529062;
+  value[69] = 14528608963998534792;
+  value[70] = 9466542400916821939;
+  value[71] = 10925564598174000610;
+  value[72] = 2691975909559666986;
+  value[73] = 397087297503084581;
+  value[74] = 2779611082521592067;
+  value[75] = 1010533508236560148;
+  value[76] = 7073734557655921036;
+  value[77] = 12622653764762278610;
+  value[78] = 14571600075677612986;
+  value[79] = 9767480182670369297;
+  return value[i];
+}
+function G_FROM_DEGREE_BITS() {
+  return 281474976710656;
+}
+function GL_MULTIPLICATIVE_GENERATOR() {
+  return 7;
+}
+function GL_ROOT_OF_UNITY(i) {
+  var value[33];
+  value[0] = 1;
+  value[1] = 18446744069414584320;
+  value[2] = 281474976710656;
+  value[3] = 18446744069397807105;
+  value[4] = 17293822564807737345;
+  value[5] = 70368744161280;
+  value[6] = 549755813888;
+  value[7] = 17870292113338400769;
+  value[8] = 13797081185216407910;
+  value[9] = 1803076106186727246;
+  value[10] = 11353340290879379826;
+  value[11] = 455906449640507599;
+  value[12] = 17492915097719143606;
+  value[13] = 1532612707718625687;
+  value[14] = 16207902636198568418;
+  value[15] = 17776499369601055404;
+  value[16] = 6115771955107415310;
+  value[17] = 12380578893860276750;
+  value[18] = 9306717745644682924;
+  value[19] = 18146160046829613826;
+  value[20] = 3511170319078647661;
+  value[21] = 17654865857378133588;
+  value[22] = 5416168637041100469;
+  value[23] = 16905767614792059275;
+  value[24] = 9713644485405565297;
+  value[25] = 5456943929260765144;
+  value[26] = 17096174751763063430;
+  value[27] = 1213594585890690845;
+  value[28] = 6414415596519834757;
+  value[29] = 16116352524544190054;
+  value[30] = 9123114210336311365;
+  value[31] = 4614640910117430873;
+  value[32] = 1753635133440165772;
+  return value[i];
+}
+function GL_CONST(i) {
+  var value[360];
+  value[0] = 13080132714287612933;
+  value[1] = 8594738767457295063;
+  value[2] = 12896916465481390516;
+  value[3] = 1109962092811921367;
+  value[4] = 16216730422861946898;
+  value[5] = 10137062673499593713;
+  value[6] = 15292064466732465823;
+  value[7] = 17255573294985989181;
+  value[8] = 14827154241873003558;
+  value[9] = 2846171647972703231;
+  value[10] = 16246264663680317601;
+  value[11] = 14214208087951879286;
+  value[12] = 9667108687426275457;
+  value[13] = 6470857420712283733;
+  value[14] = 14103331940138337652;
+  value[15] = 11854816473550292865;
+  value[16] = 3498097497301325516;
+  value[17] = 7947235692523864220;
+  value[18] = 11110078701231901946;
+  value[19] = 16384314112672821048;
+  value[20] = 15404405912655775739;
+  value[21] = 14077880830714445579;
+  value[22] = 9555554662709218279;
+  value[23] = 13859595358210603949;
+  value[24] = 16859897325061800066;
+  value[25] = 17685474420222222349;
+  value[26] = 17858764734618734949;
+  value[27] = 9410011022665866671;
+  value[28] = 12495243629579414666;
+  value[29] = 12416945298171515742;
+  value[30] = 5776666812364270983;
+  value[31] = 6314421662864060481;
+  value[32] = 7402742471423223171;
+  value[33] = 982536713192432718;
+  value[34] = 17321168865775127905;
+  value[35] = 2934354895005980211;
+  value[36] = 10567510598607410195;
+  value[37] = 8135543733717919110;
+  value[38] = 116353493081713692;
+  value[39] = 8029688163494945618;
+  value[40] = 9003846637224807585;
+  value[41] = 7052445132467233849;
+  value[42] = 9645665432288852853;
+  value[43] = 5446430061030868787;
+  value[44] = 16770910634346036823;
+  value[45] = 17708360571433944729;
+  value[46] = 4661556288322237631;
+  value[47] = 11977051899316327985;
+  value[48] = 4378616569090929672;
+  value[49] = 3334807502817538491;
+  value[50] = 8019184735943344966;
+  value[51] = 2395043908812246395;
+  value[52] = 6558421058331732611;
+  value[53] = 11735894060727326369;
+  value[54] = 8143540538889204488;
+  value[55] = 5991753489563751169;
+  value[56] = 12235918791502088007;
+  value[57] = 2880312033702687139;
+  value[58] = 18224748115308382355;
+  value[59] = 18070411013125314165;
+  value[60] = 8156487614120951180;
+  value[61] = 10615269510047010719;
+  value[62] = 12489426404754222075;
+  value[63] = 5055279340069995710;
+  value[64] = 7231927319780248664;
+  value[65] = 2602078848106763799;
+  value[66] = 12445944369334781425;
+  value[67] = 3978905923892496205;
+  value[68] = 16711272944329818038;
+  value[69] = 10439032361227108922;
+  value[70] = 15110119871725214866;
+  value[71] = 821141790655890946;
+  value[72] = 11073536380651186235;
+  value[73] = 4866839313097607757;
+  value[74] = 13118391689513956636;
+  value[75] = 14527674973762312380;
+  value[76] = 7612751959265567999;
+  value[77] = 6808090907814178161;
+  value[78] = 6899703779492644997;
+  value[79] = 3664666286336986826;
+  value[80] = 783179505424462608;
+  value[81] = 8990689241814097697;
+  value[82] = 9646603555412825679;
+  value[83] = 7351246026167205041;
+  value[84] = 16970959813722173256;
+  value[85] = 15735726858241466429;
+  value[86] = 10347018221892268419;
+  value[87] = 12195545878449322889;
+  value[88] = 7423314197114049891;
+  value[89] = 14908016116973904153;
+  value[90] = 5840340122527363265;
+  value[91] = 17740311462440614128;
+  value[92] = 815306421953744623;
+  value[93] = 17456357368219253949;
+  value[94] = 6982651076559329072;
+  value[95] = 11970987324614963868;
+  value[96] = 8167785008538063246;
+  value[97] = 9483259819397403968;
+  value[98] = 954550221664291548;
+  value[99] = 10339565171024313256;
+  value[100] = 8651171084286500102;
+  value[101] = 16974445528003515956;
+  value[102] = 15104530047940621190;
+  value[103] = 103271880867179718;
+  value[104] = 14654666245504492663;
+  value[105] = 12445769555936887967;
+  value[106] = 11250582358051997490;
+  value[107] = 6730977207490590241;
+  value[108] = 15919951556166196935;
+  value[109] = 4423540216573360915;
+  value[110] = 16317664700341473511;
+  value[111] = 4723997214951767765;
+  value[112] = 10098756619006575500;
+  value[113] = 3223149401237667964;
+  value[114] = 6870494874300767682;
+  value[115] = 2902095711130291898;
+  value[116] = 7159372652788439733;
+  value[117] = 11500508372997952671;
+  value[118] = 13348148181479462670;
+  value[119] = 12729401155983882093;
+  value[120] = 15021242795466053388;
+  value[121] = 3802990509227527157;
+  value[122] = 4665459515680145682;
+  value[123] = 13165553315407675603;
+  value[124] = 6496364397926233172;
+  value[125] = 12800832566287577810;
+  value[126] = 9737592377590267426;
+  value[127] = 8687131091302514939;
+  value[128] = 1488200421755445892;
+  value[129] = 11004377668730991641;
+  value[130] = 13516338734600228410;
+  value[131] = 2953581820660217936;
+  value[132] = 3505040783153922951;
+  value[133] = 3710332827435113697;
+  value[134] = 15414874040873320221;
+  value[135] = 8602547649919482301;
+  value[136] = 13971349938398812007;
+  value[137] = 187239246702636066;
+  value[138] = 12886019973971254144;
+  value[139] = 4512274763990493707;
+  value[140] = 2986635507805503192;
+  value[141] = 2315252455709119454;
+  value[142] = 12537995864054210246;
+  value[143] = 2039491936479859267;
+  value[144] = 1558644089185031256;
+  value[145] = 4074089203264759305;
+  value[146] = 2522268501749395707;
+  value[147] = 3414760436185256196;
+  value[148] = 17420887529146466921;
+  value[149] = 2817020417938125001;
+  value[150] = 16538346563888261485;
+  value[151] = 5592270336833998770;
+  value[152] = 16876602064684906232;
+  value[153] = 1793025614521516343;
+  value[154] = 2178510518148748532;
+  value[155] = 2726440714374752509;
+  value[156] = 6502946837278398021;
+  value[157] = 15816362857667988792;
+  value[158] = 12997958454165692924;
+  value[159] = 5314892854495903792;
+  value[160] = 15533907063555687782;
+  value[161] = 12312015675698548715;
+  value[162] = 14140016464013350248;
+  value[163] = 16325589062962838690;
+  value[164] = 6796145646370327654;
+  value[165] = 1168753512742361735;
+  value[166] = 4100789820704709368;
+  value[167] = 15947554381540469177;
+  value[168] = 8597377839806076919;
+  value[169] = 9704018824195918000;
+  value[170] = 12763288618765762688;
+  value[171] = 17249257732622847695;
+  value[172] = 1998710993415069759;
+  value[173] = 923759906393011543;
+  value[174] = 1271051229666811593;
+  value[175] = 17822362132088738077;
+  value[176] = 11797234543722669271;
+  value[177] = 5864538787265942447;
+  value[178] = 15975583211110506970;
+  value[179] = 7258516085733671960;
+  value[180] = 17999926471875633100;
+  value[181] = 635992114476018166;
+  value[182] = 17205047318256576347;
+  value[183] = 17384900867876315312;
+  value[184] = 16484825562915784226;
+  value[185] = 16694130609036138894;
+  value[186] = 10575069350371260875;
+  value[187] = 8330575162062887277;
+  value[188] = 6212375704691932880;
+  value[189] = 15965138197626618226;
+  value[190] = 14285453069600046939;
+  value[191] = 10005163510208402517;
+  value[192] = 885298637936952595;
+  value[193] = 541790758138118921;
+  value[194] = 5985203084790372993;
+  value[195] = 4685030219775483721;
+  value[196] = 1411106851304815020;
+  value[197] = 11290732479954096478;
+  value[198] = 208280581124868513;
+  value[199] = 10979018648467968495;
+  value[200] = 8600643745023338215;
+  value[201] = 3477453626867126061;
+  value[202] = 6428436309340258604;
+  value[203] = 5695415667275657934;
+  value[204] = 15952065508715623490;
+  value[205] = 15571300830419767248;
+  value[206] = 17259785660502616862;
+  value[207] = 4298425495274316083;
+  value[208] = 9023601070579319352;
+  value[209] = 7353589709321807492;
+  value[210] = 2988848909076209475;
+  value[211] = 10439527789422046135;
+  value[212] = 6097734044161429459;
+  value[213] = 1113429873817861476;
+  value[214] = 1639063372386966591;
+  value[215] = 7863102812716788759;
+  value[216] = 216040220732135364;
+  value[217] = 14252611488623712688;
+  value[218] = 9543395466794536974;
+  value[219] = 2714461051639810934;
+  value[220] = 2588317208781407279;
+  value[221] = 15458529123534594916;
+  value[222] = 15748417817551040856;
+  value[223] = 16414455697114422951;
+  value[224] = 13378164466674639511;
+  value[225] = 13894319928411294675;
+  value[226] = 5032680892090751540;
+  value[227] = 17201338494743078916;
+  value[228] = 4397422800601932505;
+  value[229] = 11285062031581972327;
+  value[230] = 7309354640676468207;
+  value[231] = 10457152817239331848;
+  value[232] = 8855911538863247046;
+  value[233] = 4301853449821814398;
+  value[234] = 13001502396339103326;
+  value[235] = 10218424535115580246;
+  value[236] = 8628244713920681895;
+  value[237] = 17410423622514037261;
+  value[238] = 14080683768439215375;
+  value[239] = 11453161143447188100;
+  value[240] = 16761509772042181939;
+  value[241] = 6688821660695954082;
+  value[242] = 12083434295263160416;
+  value[243] = 8540021431714616589;
+  value[244] = 6891616215679974226;
+  value[245] = 10229217098454812721;
+  value[246] = 3292165387203778711;
+  value[247] = 6090113424998243490;
+  value[248] = 13431780521962358660;
+  value[249] = 6061081364215809883;
+  value[250] = 16792066504222214142;
+  value[251] = 16134314044798124799;
+  value[252] = 17070233710126619765;
+  value[253] = 6915716851370550800;
+  value[254] = 9505009849073026581;
+  value[255] = 6422700465081897153;
+  value[256] = 17977653991560529185;
+  value[257] = 5800870252836247255;
+  value[258] = 12096124733159345520;
+  value[259] = 7679273623392321940;
+  value[260] = 17835783910585744964;
+  value[261] = 2478664878205754377;
+  value[262] = 1720314468413114967;
+  value[263] = 10376757819003248056;
+  value[264] = 10376377187857634245;
+  value[265] = 13344930747504284997;
+  value[266] = 11579281865160153596;
+  value[267] = 10300256980048736962;
+  value[268] = 378765236515040565;
+  value[269] = 11412420941557253424;
+  value[270] = 12931662470734252786;
+  value[271] = 43018908376346374;
+  value[272] = 3589810689190160071;
+  value[273] = 4688229274750659741;
+  value[274] = 13688957436484306091;
+  value[275] = 11424740943016984272;
+  value[276] = 16001900718237913960;
+  value[277] = 5548469743008097574;
+  value[278] = 14584404916672178680;
+  value[279] = 3396622135873576824;
+  value[280] = 7861729246871155992;
+  value[281] = 16112271126908045545;
+  value[282] = 16988163966860016012;
+  value[283] = 273641680619529493;
+  value[284] = 15222677154027327363;
+  value[285] = 4070328078309830604;
+  value[286] = 13520458500363296391;
+  value[287] = 8235111705801363015;
+  value[288] = 5575990058472514138;
+  value[289] = 2751301609188252989;
+  value[290] = 6478598528223547074;
+  value[291] = 386565553848556638;
+  value[292] = 9417729078939938713;
+  value[293] = 15204315939835727483;
+  value[294] = 14942015033780606261;
+  value[295] = 18369423901636582012;
+  value[296] = 4715338437538604447;
+  value[297] = 6840590980607806319;
+  value[298] = 5535471161490539014;
+  value[299] = 5341328005359029952;
+  value[300] = 1475161295215894444;
+  value[301] = 7999197814297036636;
+  value[302] = 2984233088665867938;
+  value[303] = 3097746028144832229;
+  value[304] = 8849530863480031517;
+  value[305] = 7464920943249009773;
+  value[306] = 3802996844641460514;
+  value[307] = 6284458522545927646;
+  value[308] = 2307388003445002779;
+  value[309] = 4461479354745457623;
+  value[310] = 1649739722664588460;
+  value[311] = 3008391274160432867;
+  value[312] = 5142217010456550622;
+  value[313] = 1775580461722730120;
+  value[314] = 161694268822794344;
+  value[315] = 1518963253808031703;
+  value[316] = 16475258091652710137;
+  value[317] = 119575899007375159;
+  value[318] = 1275863735937973999;
+  value[319] = 16539412514520642374;
+  value[320] = 2303365191438051950;
+  value[321] = 6435126839960916075;
+  value[322] = 17794599201026020053;
+  value[323] = 13847097589277840330;
+  value[324] = 16645869274577729720;
+  value[325] = 8039205965509554440;
+  value[326] = 4788586935019371140;
+  value[327] = 15129007200040077746;
+  value[328] = 2055561615223771341;
+  value[329] = 4149731103701412892;
+  value[330] = 10268130195734144189;
+  value[331] = 13406631635880074708;
+  value[332] = 11429218277824986203;
+  value[333] = 15773968030812198565;
+  value[334] = 16050275277550506872;
+  value[335] = 11858586752031736643;
+  value[336] = 8927746344866569756;
+  value[337] = 11802068403177695792;
+  value[338] = 157833420806751556;
+  value[339] = 4698875910749767878;
+  value[340] = 1616722774788291698;
+  value[341] = 3990951895163748090;
+  value[342] = 16758609224720795472;
+  value[343] = 3045571693290741477;
+  value[344] = 9281634245289836419;
+  value[345] = 13517688176723875370;
+  value[346] = 7961395585333219380;
+  value[347] = 1606574359105691080;
+  value[348] = 17564372683613562171;
+  value[349] = 4664015225343144418;
+  value[350] = 6133721340680280128;
+  value[351] = 2667022304383014929;
+  value[352] = 12316557761857340230;
+  value[353] = 10375614850625292317;
+  value[354] = 8141542666379135068;
+  value[355] = 9185476451083834432;
+  value[356] = 4991072365274649547;
+  value[357] = 17398204971778820365;
+  value[358] = 16127888338958422584;
+  value[359] = 13586792051317758204;
+  return value[i];
+}
+function GL_MDS_CIRC(i) {
+  var value[12];
+  value[0] = 17;
+  value[1] = 15;
+  value[2] = 41;
+  value[3] = 16;
+  value[4] = 2;
+  value[5] = 28;
+  value[6] = 13;
+  value[7] = 13;
+  value[8] = 39;
+  value[9] = 18;
+  value[10] = 34;
+  value[11] = 20;
+  return value[i];
+}
+function GL_MDS_DIAG(i) {
+  var value[12];
+  value[0] = 8;
+  value[1] = 0;
+  value[2] = 0;
+  value[3] = 0;
+  value[4] = 0;
+  value[5] = 0;
+  value[6] = 0;
+  value[7] = 0;
+  value[8] = 0;
+  value[9] = 0;
+  value[10] = 0;
+  value[11] = 0;
+  return value[i];
+}
+function CIRCUIT_DIGEST(i) {
+  var value[4];
+  value[0] = 5660705557407586940;
+  value[1] = 4954751937869318437;
+  value[2] = 4615782742913009780;
+  value[3] = 8205856866103648701;
+  return value[i];
+}
+function SIGMAS_CAP(i, j) {
+  var value[16][4];
+  value[0][0] = 6043294023340134781;
+  value[0][1] = 8252434982394959440;
+  value[0][2] = 16369085056547111297;
+  value[0][3] = 7127186402416020503;
+  value[1][0] = 13745513212925691783;
+  value[1][1] = 17306894927106209094;
+  value[1][2] = 8246978812698014992;
+  value[1][3] = 3049331580923676557;
+  value[2][0] = 11095497616770610169;
+  value[2][1] = 4045998780980570212;
+  value[2][2] = 12285648644399131561;
+  value[2][3] = 9888370257823052036;
+  value[3][0] = 17039070325304902997;
+  value[3][1] = 4199258740876907746;
+  value[3][2] = 2506921449022154360;
+  value[3][3] = 8684901995844654090;
+  value[4][0] = 5662816843417412602;
+  value[4][1] = 5574055324354355857;
+  value[4][2] = 15404934446294568134;
+  value[4][3] = 17020142361497799249;
+  value[5][0] = 1340772526124803594;
+  value[5][1] = 16135902174829514385;
+  value[5][2] = 10151508191366998946;
+  value[5][3] = 11373517568046348032;
+  value[6][0] = 10477330358940412318;
+  value[6][1] = 1984409990150745011;
+  value[6][2] = 13138285037351971364;
+  value[6][3] = 2935794333814610461;
+  value[7][0] = 401112892962939046;
+  value[7][1] = 1612000821912524721;
+  value[7][2] = 16773855315447379918;
+  value[7][3] = 9560915921061835291;
+  value[8][0] = 2867837886781938498;
+  value[8][1] = 12650577705815767431;
+  value[8][2] = 8975354514172135274;
+  value[8][3] = 16404964009437877243;
+  value[9][0] = 535121084130323240;
+  value[9][1] = 4215650809718079016;
+  value[9][2] = 1336522167170458872;
+  value[9][3] = 9143706875259981115;
+  value[10][0] = 13125725845816479235;
+  value[10][1] = 11314659850841658696;
+  value[10][2] = 3325404771780229271;
+  value[10][3] = 6139090549747307661;
+  value[11][0] = 12364406502045659772;
+  value[11][1] = 4194230737439805646;
+  value[11][2] = 12599118737999302536;
+  value[11][3] = 9790706834613357195;
+  value[12][0] = 18198297150754754482;
+  value[12][1] = 7498792646537081610;
+  value[12][2] = 9696427671419084232;
+  value[12][3] = 11279695527422413739;
+  value[13][0] = 3735763103039546390;
+  value[13][1] = 13206732025748593568;
+  value[13][2] = 16498729895936942199;
+  value[13][3] = 5772351971546231554;
+  value[14][0] = 10529225113873422027;
+  value[14][1] = 9774904469969643546;
+  value[14][2] = 12614707283449150844;
+  value[14][3] = 10962994562667874570;
+  value[15][0] = 2598729659014713248;
+  value[15][1] = 5614788481011894379;
+  value[15][2] = 18309307849059060118;
+  value[15][3] = 2910696568667597548;
+  return value[i][j];
+}
@@ -0,0 +1,1602 @@
+pragma circom 2.1.0;
+
+include "constants.circom";
+include "goldilocks.circom";
+include "poseidon.circom";
+include "fri.circom";
+include "plonk.circom";
+
+template Verifier() {
+  signal input public_inputs[2];
+  signal input wires_cap[16][4];
+  signal input plonk_zs_partial_products_cap[16][4];
+  signal input quotient_polys_cap[16][4];
+  signal input openings_constants[4][2];
+  signal input openings_plonk_sigmas[80][2];
+  signal input openings_wires[135][2];
+  signal input openings_plonk_zs[2][2];
+  signal input openings_partial_products[18][2];
+  signal input openings_quotient_polys[16][2];
+  signal input openings_plonk_zs_next[2][2];
+  signal input fri_query_init_constants_sigmas_v[28][84];
+  signal input fri_query_init_constants_sigmas_p[28][1][4];
+  signal input fri_query_init_wires_v[28][135];
+  signal input fri_query_init_wires_p[28][1][4];
+  signal input fri_query_init_zs_partial_products_v[28][20];
+  signal input fri_query_init_zs_partial_products_p[28][1][4];
+  signal input fri_query_init_quotient_v[28][16];
+  signal input fri_query_init_quotient_p[28][1][4];
+  signal input fri_final_poly[4][2];
+  signal input fri_pow_witness;
+
+  // The inputs are canonical field elements.
+  component public_inputs_canonical[2];
+  for (var i0 = 0; i0 < 2; i0++) {
+    public_inputs_canonical[i0] = GlCanonical();
+    public_inputs_canonical[i0].in <== public_inputs[i0];
+  }
+  component wires_cap_canonical[64];
+  for (var i0 = 0; i0 < 16; i0++) {
+    for (var i1 = 0; i1 < 4; i1++) {
+      wires_cap_canonical[(i0) * 4 + i1] = GlCanonical();
+      wires_cap_canonical[(i0) * 4 + i1].in <== wires_cap[i0][i1];
+    }
+  }
+  component plonk_zs_partial_products_cap_canonical[64];
+  for (var i0 = 0; i0 < 16; i0++) {
+    for (var i1 = 0; i1 < 4; i1++) {
+      plonk_zs_partial_products_cap_canonical[(i0) * 4 + i1] = GlCanonical();
+      plonk_zs_partial_products_cap_canonical[(i0) * 4 + i1].in <== plonk_zs_partial_products_cap[i0][i1];
+    }
+  }
+  component quotient_polys_cap_canonical[64];
+  for (var i0 = 0; i0 < 16; i0++) {
+    for (var i1 = 0; i1 < 4; i1++) {
+      quotient_polys_cap_canonical[(i0) * 4 + i1] = GlCanonical();
+      quotient_polys_cap_canonical[(i0) * 4 + i1].in <== quotient_polys_cap[i0][i1];
+    }
+  }
+  component openings_constants_canonical[8];
+  for (var i0 = 0; i0 < 4; i0++) {
+    for (var i1 = 0; i1 < 2; i1++) {
+      openings_constants_canonical[(i0) * 2 + i1] = GlCanonical();
+      openings_constants_canonical[(i0) * 2 + i1].in <== openings_constants[i0][i1];
+    }
+  }
+  component openings_plonk_sigmas_canonical[160];
+  for (var i0 = 0; i0 < 80; i0++) {
+    for (var i1 = 0; i1 < 2; i1++) {
+      openings_plonk_sigmas_canonical[(i0) * 2 + i1] = GlCanonical();
+      openings_plonk_sigmas_canonical[(i0) * 2 + i1].in <== openings_plonk_sigmas[i0][i1];
+    }
+  }
+  component openings_wires_canonical[270];
+  for (var i0 = 0; i0 < 135; i0++) {
+    for (var i1 = 0; i1 < 2; i1++) {
+      openings_wires_canonical[(i0) * 2 + i1] = GlCanonical();
+      openings_wires_canonical[(i0) * 2 + i1].in <== openings_wires[i0][i1];
+    }
+  }
+  component openings_plonk_zs_canonical[4];
+  for (var i0 = 0; i0 < 2; i0++) {
+    for (var i1 = 0; i1 < 2; i1++) {
+      openings_plonk_zs_canonical[(i0) * 2 + i1] = GlCanonical();
+      openings_plonk_zs_canonical[(i0) * 2 + i1].in <== openings_plonk_zs[i0][i1];
+    }
+  }
+  component openings_partial_products_canonical[36];
+  for (var i0 = 0; i0 < 18; i0++) {
+    for (var i1 = 0; i1 < 2; i1++) {
+      openings_partial_products_canonical[(i0) * 2 + i1] = GlCanonical();
+      openings_partial_products_canonical[(i0) * 2 + i1].in <== openings_partial_products[i0][i1];
+    }
+  }
+  component openings_quotient_polys_canonical[32];
+  for (var i0 = 0; i0 < 16; i0++) {
+    for (var i1 = 0; i1 < 2; i1++) {
+      openings_quotient_polys_canonical[(i0) * 2 + i1] = GlCanonical();
+      openings_quotient_polys_canonical[(i0) * 2 + i1].in <== openings_quotient_polys[i0][i1];
+    }
+  }
+  component openings_plonk_zs_next_canonical[4];
+  for (var i0 = 0; i0 < 2; i0++) {
+    for (var i1 = 0; i1 < 2; i1++) {
+      openings_plonk_zs_next_canonical[(i0) * 2 + i1] = GlCanonical();
+      openings_plonk_zs_next_canonical[(i0) * 2 + i1].in <== openings_plonk_zs_next[i0][i1];
+    }
+  }
+  component fri_query_init_constants_sigmas_v_canonical[2352];
+  for (var i0 = 0; i0 < 28; i0++) {
+    for (var i1 = 0; i1 < 84; i1++) {
+      fri_query_init_constants_sigmas_v_canonical[(i0) * 84 + i1] = GlCanonical();
+      fri_query_init_constants_sigmas_v_canonical[(i0) * 84 + i1].in <== fri_query_init_constants_sigmas_v[i0][i1];
+    }
+  }
+  component fri_query_init_constants_sigmas_p_canonical[112];
+  for (var i0 = 0; i0 < 28; i0++) {
+    for (var i1 = 0; i1 < 1; i1++) {
+      for (var i2 = 0; i2 < 4; i2++) {
+        fri_query_init_constants_sigmas_p_canonical[((i0) * 1 + i1) * 4 + i2] = GlCanonical();
+        fri_query_init_constants_sigmas_p_canonical[((i0) * 1 + i1) * 4 + i2].in <== fri_query_init_constants_sigmas_p[i0][i1][i2];
+      }
+    }
+  }
+  component fri_query_init_wires_v_canonical[3780];
+  for (var i0 = 0; i0 < 28; i0++) {
+    for (var i1 = 0; i1 < 135; i1++) {
+      fri_query_init_wires_v_canonical[(i0) * 135 + i1] = GlCanonical();
+      fri_query_init_wires_v_canonical[(i0) * 135 + i1].in <== fri_query_init_wires_v[i0][i1];
+    }
+  }
+  component fri_query_init_wires_p_canonical[112];
+  for (var i0 = 0; i0 < 28; i0++) {
+    for (var i1 = 0; i1 < 1; i1++) {
+      for (var i2 = 0; i2 < 4; i2++) {
+        fri_query_init_wires_p_canonical[((i0) * 1 + i1) * 4 + i2] = GlCanonical();
+        fri_query_init_wires_p_canonical[((i0) * 1 + i1) * 4 + i2].in <== fri_query_init_wires_p[i0][i1][i2];
+      }
+    }
+  }
+  component fri_query_init_zs_partial_products_v_canonical[560];
+  for (var i0 = 0; i0 < 28; i0++) {
+    for (var i1 = 0; i1 < 20; i1++) {
+      fri_query_init_zs_partial_products_v_canonical[(i0) * 20 + i1] = GlCanonical();
+      fri_query_init_zs_partial_products_v_canonical[(i0) * 20 + i1].in <== fri_query_init_zs_partial_products_v[i0][i1];
+    }
+  }
+  component fri_query_init_zs_partial_products_p_canonical[112];
+  for (var i0 = 0; i0 < 28; i0++) {
+    for (var i1 = 0; i1 < 1; i1++) {
+      for (var i2 = 0; i2 < 4; i2++) {
+        fri_query_init_zs_partial_products_p_canonical[((i0) * 1 + i1) * 4 + i2] = GlCanonical();
+        fri_query_init_zs_partial_products_p_canonical[((i0) * 1 + i1) * 4 + i2].in <== fri_query_init_zs_partial_products_p[i0][i1][i2];
+      }
+    }
+  }
+  component fri_query_init_quotient_v_canonical[448];
+  for (var i0 = 0; i0 < 28; i0++) {
+    for (var i1 = 0; i1 < 16; i1++) {
+      fri_query_init_quotient_v_canonical[(i0) * 16 + i1] = GlCanonical();
+      fri_query_init_quotient_v_canonical[(i0) * 16 + i1].in <== fri_query_init_quotient_v[i0][i1];
+    }
+  }
+  component fri_query_init_quotient_p_canonical[112];
+  for (var i0 = 0; i0 < 28; i0++) {
+    for (var i1 = 0; i1 < 1; i1++) {
+      for (var i2 = 0; i2 < 4; i2++) {
+        fri_query_init_quotient_p_canonical[((i0) * 1 + i1) * 4 + i2] = GlCanonical();
+        fri_query_init_quotient_p_canonical[((i0) * 1 + i1) * 4 + i2].in <== fri_query_init_quotient_p[i0][i1][i2];
+      }
+    }
+  }
+  component fri_final_poly_canonical[8];
+  for (var i0 = 0; i0 < 4; i0++) {
+    for (var i1 = 0; i1 < 2; i1++) {
+      fri_final_poly_canonical[(i0) * 2 + i1] = GlCanonical();
+      fri_final_poly_canonical[(i0) * 2 + i1].in <== fri_final_poly[i0][i1];
+    }
+  }
+  component fri_pow_witness_canonical[1];
+  fri_pow_witness_canonical[0] = GlCanonical();
+  fri_pow_witness_canonical[0].in <== fri_pow_witness;
+
+  signal public_input_hash[4];
+  public_input_hash <== HashNoPad(NUM_PUBLIC_INPUTS())(public_inputs);
+  signal constants_sigmas_cap[16][4];
+  for (var i = 0; i < 16; i++) {
+    for (var j = 0; j < 4; j++) {
+      constants_sigmas_cap[i][j] <== SIGMAS_CAP(i, j);
+    }
+  }
+
+  // Fiat-Shamir.
+  component challenger_0 = Poseidon();
+  challenger_0.in[0] <== CIRCUIT_DIGEST(0);
+  challenger_0.in[1] <== CIRCUIT_DIGEST(1);
+  challenger_0.in[2] <== CIRCUIT_DIGEST(2);
+  challenger_0.in[3] <== CIRCUIT_DIGEST(3);
+  challenger_0.in[4] <== public_input_hash[0];
+  challenger_0.in[5] <== public_input_hash[1];
+  challenger_0.in[6] <== public_input_hash[2];
+  challenger_0.in[7] <== public_input_hash[3];
+  challenger_0.in[8] <== 0;
+  challenger_0.in[9] <== 0;
+  challenger_0.in[10] <== 0;
+  challenger_0.in[11] <== 0;
+  component challenger_1 = Poseidon();
+  challenger_1.in[0] <== wires_cap[0][0];
+  challenger_1.in[1] <== wires_cap[0][1];
+  challenger_1.in[2] <== wires_cap[0][2];
+  challenger_1.in[3] <== wires_cap[0][3];
+  challenger_1.in[4] <== wires_cap[1][0];
+  challenger_1.in[5] <== wires_cap[1][1];
+  challenger_1.in[6] <== wires_cap[1][2];
+  challenger_1.in[7] <== wires_cap[1][3];
+  challenger_1.in[8] <== challenger_0.out[8];
+  challenger_1.in[9] <== challenger_0.out[9];
+  challenger_1.in[10] <== challenger_0.out[10];
+  challenger_1.in[11] <== challenger_0.out[11];
+  component challenger_2 = Poseidon();
+  challenger_2.in[0] <== wires_cap[2][0];
+  challenger_2.in[1] <== wires_cap[2][1];
+  challenger_2.in[2] <== wires_cap[2][2];
+  challenger_2.in[3] <== wires_cap[2][3];
+  challenger_2.in[4] <== wires_cap[3][0];
+  challenger_2.in[5] <== wires_cap[3][1];
+  challenger_2.in[6] <== wires_cap[3][2];
+  challenger_2.in[7] <== wires_cap[3][3];
+  challenger_2.in[8] <== challenger_1.out[8];
+  challenger_2.in[9] <== challenger_1.out[9];
+  challenger_2.in[10] <== challenger_1.out[10];
+  challenger_2.in[11] <== challenger_1.out[11];
+  component challenger_3 = Poseidon();
+  challenger_3.in[0] <== wires_cap[4][0];
+  challenger_3.in[1] <== wires_cap[4][1];
+  challenger_3.in[2] <== wires_cap[4][2];
+  challenger_3.in[3] <== wires_cap[4][3];
+  challenger_3.in[4] <== wires_cap[5][0];
+  challenger_3.in[5] <== wires_cap[5][1];
+  challenger_3.in[6] <== wires_cap[5][2];
+  challenger_3.in[7] <== wires_cap[5][3];
+  challenger_3.in[8] <== challenger_2.out[8];
+  challenger_3.in[9] <== challenger_2.out[9];
+  challenger_3.in[10] <== challenger_2.out[10];
+  challenger_3.in[11] <== challenger_2.out[11];
+  component challenger_4 = Poseidon();
+  challenger_4.in[0] <== wires_cap[6][0];
+  challenger_4.in[1] <== wires_cap[6][1];
+  challenger_4.in[2] <== wires_cap[6][2];
+  challenger_4.in[3] <== wires_cap[6][3];
+  challenger_4.in[4] <== wires_cap[7][0];
+  challenger_4.in[5] <== wires_cap[7][1];
+  challenger_4.in[6] <== wires_cap[7][2];
+  challenger_4.in[7] <== wires_cap[7][3];
+  challenger_4.in[8] <== challenger_3.out[8];
+  challenger_4.in[9] <== challenger_3.out[9];
+  challenger_4.in[10] <== challenger_3.out[10];
+  challenger_4.in[11] <== challenger_3.out[11];
+  component challenger_5 = Poseidon();
+  challenger_5.in[0] <== wires_cap[8][0];
+  challenger_5.in[1] <== wires_cap[8][1];
+  challenger_5.in[2] <== wires_cap[8][2];
+  challenger_5.in[3] <== wires_cap[8][3];
+  challenger_5.in[4] <== wires_cap[9][0];
+  challenger_5.in[5] <== wires_cap[9][1];
+  challenger_5.in[6] <== wires_cap[9][2];
+  challenger_5.in[7] <== wires_cap[9][3];
+  challenger_5.in[8] <== challenger_4.out[8];
+  challenger_5.in[9] <== challenger_4.out[9];
+  challenger_5.in[10] <== challenger_4.out[10];
+  challenger_5.in[11] <== challenger_4.out[11];
+  component challenger_6 = Poseidon();
+  challenger_6.in[0] <== wires_cap[10][0];
+  challenger_6.in[1] <== wires_cap[10][1];
+  challenger_6.in[2] <== wires_cap[10][2];
+  challenger_6.in[3] <== wires_cap[10][3];
+  challenger_6.in[4] <== wires_cap[11][0];
+  challenger_6.in[5] <== wires_cap[11][1];
+  challenger_6.in[6] <== wires_cap[11][2];
+  challenger_6.in[7] <== wires_cap[11][3];
+  challenger_6.in[8] <== challenger_5.out[8];
+  challenger_6.in[9] <== challenger_5.out[9];
+  challenger_6.in[10] <== challenger_5.out[10];
+  challenger_6.in[11] <== challenger_5.out[11];
+  component challenger_7 = Poseidon();
+  challenger_7.in[0] <== wires_cap[12][0];
+  challenger_7.in[1] <== wires_cap[12][1];
+  challenger_7.in[2] <== wires_cap[12][2];
+  challenger_7.in[3] <== wires_cap[12][3];
+  challenger_7.in[4] <== wires_cap[13][0];
+  challenger_7.in[5] <== wires_cap[13][1];
+  challenger_7.in[6] <== wires_cap[13][2];
+  challenger_7.in[7] <== wires_cap[13][3];
+  challenger_7.in[8] <== challenger_6.out[8];
+  challenger_7.in[9] <== challenger_6.out[9];
+  challenger_7.in[10] <== challenger_6.out[10];
+  challenger_7.in[11] <== challenger_6.out[11];
+  component challenger_8 = Poseidon();
+  challenger_8.in[0] <== wires_cap[14][0];
+  challenger_8.in[1] <== wires_cap[14][1];
+  challenger_8.in[2] <== wires_cap[14][2];
+  challenger_8.in[3] <== wires_cap[14][3];
+  challenger_8.in[4] <== wires_cap[15][0];
+  challenger_8.in[5] <== wires_cap[15][1];
+  challenger_8.in[6] <== wires_cap[15][2];
+  challenger_8.in[7] <== wires_cap[15][3];
+  challenger_8.in[8] <== challenger_7.out[8];
+  challenger_8.in[9] <== challenger_7.out[9];
+  challenger_8.in[10] <== challenger_7.out[10];
+  challenger_8.in[11] <== challenger_7.out[11];
+  component challenger_9 = Poseidon();
+  challenger_9.in[0] <== plonk_zs_partial_products_cap[0][0];
+  challenger_9.in[1] <== plonk_zs_partial_products_cap[0][1];
+  challenger_9.in[2] <== plonk_zs_partial_products_cap[0][2];
+  challenger_9.in[3] <== plonk_zs_partial_products_cap[0][3];
+  challenger_9.in[4] <== plonk_zs_partial_products_cap[1][0];
+  challenger_9.in[5] <== plonk_zs_partial_products_cap[1][1];
+  challenger_9.in[6] <== plonk_zs_partial_products_cap[1][2];
+  challenger_9.in[7] <== plonk_zs_partial_products_cap[1][3];
+  challenger_9.in[8] <== challenger_8.out[8];
+  challenger_9.in[9] <== challenger_8.out[9];
+  challenger_9.in[10] <== challenger_8.out[10];
+  challenger_9.in[11] <== challenger_8.out[11];
+  component challenger_10 = Poseidon();
+  challenger_10.in[0] <== plonk_zs_partial_products_cap[2][0];
+  challenger_10.in[1] <== plonk_zs_partial_products_cap[2][1];
+  challenger_10.in[2] <== plonk_zs_partial_products_cap[2][2];
+  challenger_10.in[3] <== plonk_zs_partial_products_cap[2][3];
+  challenger_10.in[4] <== plonk_zs_partial_products_cap[3][0];
+  challenger_10.in[5] <== plonk_zs_partial_products_cap[3][1];
+  challenger_10.in[6] <== plonk_zs_partial_products_cap[3][2];
+  challenger_10.in[7] <== plonk_zs_partial_products_cap[3][3];
+  challenger_10.in[8] <== challenger_9.out[8];
+  challenger_10.in[9] <== challenger_9.out[9];
+  challenger_10.in[10] <== challenger_9.out[10];
+  challenger_10.in[11] <== challenger_9.out[11];
+  component challenger_11 = Poseidon();
+  challenger_11.in[0] <== plonk_zs_partial_products_cap[4][0];
+  challenger_11.in[1] <== plonk_zs_partial_products_cap[4][1];
+  challenger_11.in[2] <== plonk_zs_partial_products_cap[4][2];
+  challenger_11.in[3] <== plonk_zs_partial_products_cap[4][3];
+  challenger_11.in[4] <== plonk_zs_partial_products_cap[5][0];
+  challenger_11.in[5] <== plonk_zs_partial_products_cap[5][1];
+  challenger_11.in[6] <== plonk_zs_partial_products_cap[5][2];
+  challenger_11.in[7] <== plonk_zs_partial_products_cap[5][3];
+  challenger_11.in[8] <== challenger_10.out[8];
+  challenger_11.in[9] <== challenger_10.out[9];
+  challenger_11.in[10] <== challenger_10.out[10];
+  challenger_11.in[11] <== challenger_10.out[11];
+  component challenger_12 = Poseidon();
+  challenger_12.in[0] <== plonk_zs_partial_products_cap[6][0];
+  challenger_12.in[1] <== plonk_zs_partial_products_cap[6][1];
+  challenger_12.in[2] <== plonk_zs_partial_products_cap[6][2];
+  challenger_12.in[3] <== plonk_zs_partial_products_cap[6][3];
+  challenger_12.in[4] <== plonk_zs_partial_products_cap[7][0];
+  challenger_12.in[5] <== plonk_zs_partial_products_cap[7][1];
+  challenger_12.in[6] <== plonk_zs_partial_products_cap[7][2];
+  challenger_12.in[7] <== plonk_zs_partial_products_cap[7][3];
+  challenger_12.in[8] <== challenger_11.out[8];
+  challenger_12.in[9] <== challenger_11.out[9];
+  challenger_12.in[10] <== challenger_11.out[10];
+  challenger_12.in[11] <== challenger_11.out[11];
+  component challenger_13 = Poseidon();
+  challenger_13.in[0] <== plonk_zs_partial_products_cap[8][0];
+  challenger_13.in[1] <== plonk_zs_partial_products_cap[8][1];
+  challenger_13.in[2] <== plonk_zs_partial_products_cap[8][2];
+  challenger_13.in[3] <== plonk_zs_partial_products_cap[8][3];
+  challenger_13.in[4] <== plonk_zs_partial_products_cap[9][0];
+  challenger_13.in[5] <== plonk_zs_partial_products_cap[9][1];
+  challenger_13.in[6] <== plonk_zs_partial_products_cap[9][2];
+  challenger_13.in[7] <== plonk_zs_partial_products_cap[9][3];
+  challenger_13.in[8] <== challenger_12.out[8];
+  challenger_13.in[9] <== challenger_12.out[9];
+  challenger_13.in[10] <== challenger_12.out[10];
+  challenger_13.in[11] <== challenger_12.out[11];
+  component challenger_14 = Poseidon();
+  challenger_14.in[0] <== plonk_zs_partial_products_cap[10][0];
+  challenger_14.in[1] <== plonk_zs_partial_products_cap[10][1];
+  challenger_14.in[2] <== plonk_zs_partial_products_cap[10][2];
+  challenger_14.in[3] <== plonk_zs_partial_products_cap[10][3];
+  challenger_14.in[4] <== plonk_zs_partial_products_cap[11][0];
+  challenger_14.in[5] <== plonk_zs_partial_products_cap[11][1];
+  challenger_14.in[6] <== plonk_zs_partial_products_cap[11][2];
+  challenger_14.in[7] <== plonk_zs_partial_products_cap[11][3];
+  challenger_14.in[8] <== challenger_13.out[8];
+  challenger_14.in[9] <== challenger_13.out[9];
+  challenger_14.in[10] <== challenger_13.out[10];
+  challenger_14.in[11] <== challenger_13.out[11];
+  component challenger_15 = Poseidon();
+  challenger_15.in[0] <== plonk_zs_partial_products_cap[12][0];
+  challenger_15.in[1] <== plonk_zs_partial_products_cap[12][1];
+  challenger_15.in[2] <== plonk_zs_partial_products_cap[12][2];
+  challenger_15.in[3] <== plonk_zs_partial_products_cap[12][3];
+  challenger_15.in[4] <== plonk_zs_partial_products_cap[13][0];
+  challenger_15.in[5] <== plonk_zs_partial_products_cap[13][1];
+  challenger_15.in[6] <== plonk_zs_partial_products_cap[13][2];
+  challenger_15.in[7] <== plonk_zs_partial_products_cap[13][3];
+  challenger_15.in[8] <== challenger_14.out[8];
+  challenger_15.in[9] <== challenger_14.out[9];
+  challenger_15.in[10] <== challenger_14.out[10];
+  challenger_15.in[11] <== challenger_14.out[11];
+  component challenger_16 = Poseidon();
+  challenger_16.in[0] <== plonk_zs_partial_products_cap[14][0];
+  challenger_16.in[1] <== plonk_zs_partial_products_cap[14][1];
+  challenger_16.in[2] <== plonk_zs_partial_products_cap[14][2];
+  challenger_16.in[3] <== plonk_zs_partial_products_cap[14][3];
+  challenger_16.in[4] <== plonk_zs_partial_products_cap[15][0];
+  challenger_16.in[5] <== plonk_zs_partial_products_cap[15][1];
+  challenger_16.in[6] <== plonk_zs_partial_products_cap[15][2];
+  challenger_16.in[7] <== plonk_zs_partial_products_cap[15][3];
+  challenger_16.in[8] <== challenger_15.out[8];
+  challenger_16.in[9] <== challenger_15.out[9];
+  challenger_16.in[10] <== challenger_15.out[10];
+  challenger_16.in[11] <== challenger_15.out[11];
+  component challenger_17 = Poseidon();
+  challenger_17.in[0] <== quotient_polys_cap[0][0];
+  challenger_17.in[1] <== quotient_polys_cap[0][1];
+  challenger_17.in[2] <== quotient_polys_cap[0][2];
+  challenger_17.in[3] <== quotient_polys_cap[0][3];
+  challenger_17.in[4] <== quotient_polys_cap[1][0];
+  challenger_17.in[5] <== quotient_polys_cap[1][1];
+  challenger_17.in[6] <== quotient_polys_cap[1][2];
+  challenger_17.in[7] <== quotient_polys_cap[1][3];
+  challenger_17.in[8] <== challenger_16.out[8];
+  challenger_17.in[9] <== challenger_16.out[9];
+  challenger_17.in[10] <== challenger_16.out[10];
+  challenger_17.in[11] <== challenger_16.out[11];
+  component challenger_18 = Poseidon();
+  challenger_18.in[0] <== quotient_polys_cap[2][0];
+  challenger_18.in[1] <== quotient_polys_cap[2][1];
+  challenger_18.in[2] <== quotient_polys_cap[2][2];
+  challenger_18.in[3] <== quotient_polys_cap[2][3];
+  challenger_18.in[4] <== quotient_polys_cap[3][0];
+  challenger_18.in[5] <== quotient_polys_cap[3][1];
+  challenger_18.in[6] <== quotient_polys_cap[3][2];
+  challenger_18.in[7] <== quotient_polys_cap[3][3];
+  challenger_18.in[8] <== challenger_17.out[8];
+  challenger_18.in[9] <== challenger_17.out[9];
+  challenger_18.in[10] <== challenger_17.out[10];
+  challenger_18.in[11] <== challenger_17.out[11];
+  component challenger_19 = Poseidon();
+  challenger_19.in[0] <== quotient_polys_cap[4][0];
+  challenger_19.in[1] <== quotient_polys_cap[4][1];
+  challenger_19.in[2] <== quotient_polys_cap[4][2];
+  challenger_19.in[3] <== quotient_polys_cap[4][3];
+  challenger_19.in[4] <== quotient_polys_cap[5][0];
+  challenger_19.in[5] <== quotient_polys_cap[5][1];
+  challenger_19.in[6] <== quotient_polys_cap[5][2];
+  challenger_19.in[7] <== quotient_polys_cap[5][3];
+  challenger_19.in[8] <== challenger_18.out[8];
+  challenger_19.in[9] <== challenger_18.out[9];
+  challenger_19.in[10] <== challenger_18.out[10];
+  challenger_19.in[11] <== challenger_18.out[11];
+  component challenger_20 = Poseidon();
+  challenger_20.in[0] <== quotient_polys_cap[6][0];
+  challenger_20.in[1] <== quotient_polys_cap[6][1];
+  challenger_20.in[2] <== quotient_polys_cap[6][2];
+  challenger_20.in[3] <== quotient_polys_cap[6][3];
+  challenger_20.in[4] <== quotient_polys_cap[7][0];
+  challenger_20.in[5] <== quotient_polys_cap[7][1];
+  challenger_20.in[6] <== quotient_polys_cap[7][2];
+  challenger_20.in[7] <== quotient_polys_cap[7][3];
+  challenger_20.in[8] <== challenger_19.out[8];
+  challenger_20.in[9] <== challenger_19.out[9];
+  challenger_20.in[10] <== challenger_19.out[10];
+  challenger_20.in[11] <== challenger_19.out[11];
+  component challenger_21 = Poseidon();
+  challenger_21.in[0] <== quotient_polys_cap[8][0];
+  challenger_21.in[1] <== quotient_polys_cap[8][1];
+  challenger_21.in[2] <== quotient_polys_cap[8][2];
+  challenger_21.in[3] <== quotient_polys_cap[8][3];
+  challenger_21.in[4] <== quotient_polys_cap[9][0];
+  challenger_21.in[5] <== quotient_polys_cap[9][1];
+  challenger_21.in[6] <== quotient_polys_cap[9][2];
+  challenger_21.in[7] <== quotient_polys_cap[9][3];
+  challenger_21.in[8] <== challenger_20.out[8];
+  challenger_21.in[9] <== challenger_20.out[9];
+  challenger_21.in[10] <== challenger_20.out[10];
+  challenger_21.in[11] <== challenger_20.out[11];
+  component challenger_22 = Poseidon();
+  challenger_22.in[0] <== quotient_polys_cap[10][0];
+  challenger_22.in[1] <== quotient_polys_cap[10][1];
+  challenger_22.in[2] <== quotient_polys_cap[10][2];
+  challenger_22.in[3] <== quotient_polys_cap[10][3];
+  challenger_22.in[4] <== quotient_polys_cap[11][0];
+  challenger_22.in[5] <== quotient_polys_cap[11][1];
+  challenger_22.in[6] <== quotient_polys_cap[11][2];
+  challenger_22.in[7] <== quotient_polys_cap[11][3];
+  challenger_22.in[8] <== challenger_21.out[8];
+  challenger_22.in[9] <== challenger_21.out[9];
+  challenger_22.in[10] <== challenger_21.out[10];
+  challenger_22.in[11] <== challenger_21.out[11];
+  component challenger_23 = Poseidon();
+  challenger_23.in[0] <== quotient_polys_cap[12][0];
+  challenger_23.in[1] <== quotient_polys_cap[12][1];
+  challenger_23.in[2] <== quotient_polys_cap[12][2];
+  challenger_23.in[3] <== quotient_polys_cap[12][3];
+  challenger_23.in[4] <== quotient_polys_cap[13][0];
+  challenger_23.in[5] <== quotient_polys_cap[13][1];
+  challenger_23.in[6] <== quotient_polys_cap[13][2];
+  challenger_23.in[7] <== quotient_polys_cap[13][3];
+  challenger_23.in[8] <== challenger_22.out[8];
+  challenger_23.in[9] <== challenger_22.out[9];
+  challenger_23.in[10] <== challenger_22.out[10];
+  challenger_23.in[11] <== challenger_22.out[11];
+  component challenger_24 = Poseidon();
+  challenger_24.in[0] <== quotient_polys_cap[14][0];
+  challenger_24.in[1] <== quotient_polys_cap[14][1];
+  challenger_24.in[2] <== quotient_polys_cap[14][2];
+  challenger_24.in[3] <== quotient_polys_cap[14][3];
+  challenger_24.in[4] <== quotient_polys_cap[15][0];
+  challenger_24.in[5] <== quotient_polys_cap[15][1];
+  challenger_24.in[6] <== quotient_polys_cap[15][2];
+  challenger_24.in[7] <== quotient_polys_cap[15][3];
+  challenger_24.in[8] <== challenger_23.out[8];
+  challenger_24.in[9] <== challenger_23.out[9];
+  challenger_24.in[10] <== challenger_23.out[10];
+  challenger_24.in[11] <== challenger_23.out[11];
+  component challenger_25 = Poseidon();
+  challenger_25.in[0] <== openings_constants[0][0];
+  challenger_25.in[1] <== openings_constants[0][1];
+  challenger_25.in[2] <== openings_constants[1][0];
+  challenger_25.in[3] <== openings_constants[1][1];
+  challenger_25.in[4] <== openings_constants[2][0];
+  challenger_25.in[5] <== openings_constants[2][1];
+  challenger_25.in[6] <== openings_constants[3][0];
+  challenger_25.in[7] <== openings_constants[3][1];
+  challenger_25.in[8] <== challenger_24.out[8];
+  challenger_25.in[9] <== challenger_24.out[9];
+  challenger_25.in[10] <== challenger_24.out[10];
+  challenger_25.in[11] <== challenger_24.out[11];
+  component challenger_26 = Poseidon();
+  challenger_26.in[0] <== openings_plonk_sigmas[0][0];
+  challenger_26.in[1] <== openings_plonk_sigmas[0][1];
+  challenger_26.in[2] <== openings_plonk_sigmas[1][0];
+  challenger_26.in[3] <== openings_plonk_sigmas[1][1];
+  challenger_26.in[4] <== openings_plonk_sigmas[2][0];
+  challenger_26.in[5] <== openings_plonk_sigmas[2][1];
+  challenger_26.in[6] <== openings_plonk_sigmas[3][0];
+  challenger_26.in[7] <== openings_plonk_sigmas[3][1];
+  challenger_26.in[8] <== challenger_25.out[8];
+  challenger_26.in[9] <== challenger_25.out[9];
+  challenger_26.in[10] <== challenger_25.out[10];
+  challenger_26.in[11] <== challenger_25.out[11];
+  component challenger_27 = Poseidon();
+  challenger_27.in[0] <== openings_plonk_sigmas[4][0];
+  challenger_27.in[1] <== openings_plonk_sigmas[4][1];
+  challenger_27.in[2] <== openings_plonk_sigmas[5][0];
+  challenger_27.in[3] <== openings_plonk_sigmas[5][1];
+  challenger_27.in[4] <== openings_plonk_sigmas[6][0];
+  challenger_27.in[5] <== openings_plonk_sigmas[6][1];
+  challenger_27.in[6] <== openings_plonk_sigmas[7][0];
+  challenger_27.in[7] <== openings_plonk_sigmas[7][1];
+  challenger_27.in[8] <== challenger_26.out[8];
+  challenger_27.in[9] <== challenger_26.out[9];
+  challenger_27.in[10] <== challenger_26.out[10];
+  challenger_27.in[11] <== challenger_26.out[11];
+  component challenger_28 = Poseidon();
+  challenger_28.in[0] <== openings_plonk_sigmas[8][0];
+  challenger_28.in[1] <== openings_plonk_sigmas[8][1];
+  challenger_28.in[2] <== openings_plonk_sigmas[9][0];
+  challenger_28.in[3] <== openings_plonk_sigmas[9][1];
+  challenger_28.in[4] <== openings_plonk_sigmas[10][0];
+  challenger_28.in[5] <== openings_plonk_sigmas[10][1];
+  challenger_28.in[6] <== openings_plonk_sigmas[11][0];
+  challenger_28.in[7] <== openings_plonk_sigmas[11][1];
+  challenger_28.in[8] <== challenger_27.out[8];
+  challenger_28.in[9] <== challenger_27.out[9];
+  challenger_28.in[10] <== challenger_27.out[10];
+  challenger_28.in[11] <== challenger_27.out[11];
+  component challenger_29 = Poseidon();
+  challenger_29.in[0] <== openings_plonk_sigmas[12][0];
+  challenger_29.in[1] <== openings_plonk_sigmas[12][1];
+  challenger_29.in[2] <== openings_plonk_sigmas[13][0];
+  challenger_29.in[3] <== openings_plonk_sigmas[13][1];
+  challenger_29.in[4] <== openings_plonk_sigmas[14][0];
+  challenger_29.in[5] <== openings_plonk_sigmas[14][1];
+  challenger_29.in[6] <== openings_plonk_sigmas[15][0];
+  challenger_29.in[7] <== openings_plonk_sigmas[15][1];
+  challenger_29.in[8] <== challenger_28.out[8];
+  challenger_29.in[9] <== challenger_28.out[9];
+  challenger_29.in[10] <== challenger_28.out[10];
+  challenger_29.in[11] <== challenger_28.out[11];
+  component challenger_30 = Poseidon();
+  challenger_30.in[0] <== openings_plonk_sigmas[16][0];
+  challenger_30.in[1] <== openings_plonk_sigmas[16][1];
+  challenger_30.in[2] <== openings_plonk_sigmas[17][0];
+  challenger_30.in[3] <== openings_plonk_sigmas[17][1];
+  challenger_30.in[4] <== openings_plonk_sigmas[18][0];
+  challenger_30.in[5] <== openings_plonk_sigmas[18][1];
+  challenger_30.in[6] <== openings_plonk_sigmas[19][0];
+  challenger_30.in[7] <== openings_plonk_sigmas[19][1];
+  challenger_30.in[8] <== challenger_29.out[8];
+  challenger_30.in[9] <== challenger_29.out[9];
+  challenger_30.in[10] <== challenger_29.out[10];
+  challenger_30.in[11] <== challenger_29.out[11];
+  component challenger_31 = Poseidon();
+  challenger_31.in[0] <== openings_plonk_sigmas[20][0];
+  challenger_31.in[1] <== openings_plonk_sigmas[20][1];
+  challenger_31.in[2] <== openings_plonk_sigmas[21][0];
+  challenger_31.in[3] <== openings_plonk_sigmas[21][1];
+  challenger_31.in[4] <== openings_plonk_sigmas[22][0];
+  challenger_31.in[5] <== openings_plonk_sigmas[22][1];
+  challenger_31.in[6] <== openings_plonk_sigmas[23][0];
+  challenger_31.in[7] <== openings_plonk_sigmas[23][1];
+  challenger_31.in[8] <== challenger_30.out[8];
+  challenger_31.in[9] <== challenger_30.out[9];
+  challenger_31.in[10] <== challenger_30.out[10];
+  challenger_31.in[11] <== challenger_30.out[11];
+  component challenger_32 = Poseidon();
+  challenger_32.in[0] <== openings_plonk_sigmas[24][0];
+  challenger_32.in[1] <== openings_plonk_sigmas[24][1];
+  challenger_32.in[2] <== openings_plonk_sigmas[25][0];
+  challenger_32.in[3] <== openings_plonk_sigmas[25][1];
+  challenger_32.in[4] <== openings_plonk_sigmas[26][0];
+  challenger_32.in[5] <== openings_plonk_sigmas[26][1];
+  challenger_32.in[6] <== openings_plonk_sigmas[27][0];
+  challenger_32.in[7] <== openings_plonk_sigmas[27][1];
+  challenger_32.in[8] <== challenger_31.out[8];
+  challenger_32.in[9] <== challenger_31.out[9];
+  challenger_32.in[10] <== challenger_31.out[10];
+  challenger_32.in[11] <== challenger_31.out[11];
+  component challenger_33 = Poseidon();
+  challenger_33.in[0] <== openings_plonk_sigmas[28][0];
+  challenger_33.in[1] <== openings_plonk_sigmas[28][1];
+  challenger_33.in[2] <== openings_plonk_sigmas[29][0];
+  challenger_33.in[3] <== openings_plonk_sigmas[29][1];
+  challenger_33.in[4] <== openings_plonk_sigmas[30][0];
+  challenger_33.in[5] <== openings_plonk_sigmas[30][1];
+  challenger_33.in[6] <== openings_plonk_sigmas[31][0];
+  challenger_33.in[7] <== openings_plonk_sigmas[31][1];
+  challenger_33.in[8] <== challenger_32.out[8];
+  challenger_33.in[9] <== challenger_32.out[9];
+  challenger_33.in[10] <== challenger_32.out[10];
+  challenger_33.in[11] <== challenger_32.out[11];
+  component challenger_34 = Poseidon();
+  challenger_34.in[0] <== openings_plonk_sigmas[32][0];
+  challenger_34.in[1] <== openings_plonk_sigmas[32][1];
+  challenger_34.in[2] <== openings_plonk_sigmas[33][0];
+  challenger_34.in[3] <== openings_plonk_sigmas[33][1];
+  challenger_34.in[4] <== openings_plonk_sigmas[34][0];
+  challenger_34.in[5] <== openings_plonk_sigmas[34][1];
+  challenger_34.in[6] <== openings_plonk_sigmas[35][0];
+  challenger_34.in[7] <== openings_plonk_sigmas[35][1];
+  challenger_34.in[8] <== challenger_33.out[8];
+  challenger_34.in[9] <== challenger_33.out[9];
+  challenger_34.in[10] <== challenger_33.out[10];
+  challenger_34.in[11] <== challenger_33.out[11];
+  component challenger_35 = Poseidon();
+  challenger_35.in[0] <== openings_plonk_sigmas[36][0];
+  challenger_35.in[1] <== openings_plonk_sigmas[36][1];
+  challenger_35.in[2] <== openings_plonk_sigmas[37][0];
+  challenger_35.in[3] <== openings_plonk_sigmas[37][1];
+  challenger_35.in[4] <== openings_plonk_sigmas[38][0];
+  challenger_35.in[5] <== openings_plonk_sigmas[38][1];
+  challenger_35.in[6] <== openings_plonk_sigmas[39][0];
+  challenger_35.in[7] <== openings_plonk_sigmas[39][1];
+  challenger_35.in[8] <== challenger_34.out[8];
+  challenger_35.in[9] <== challenger_34.out[9];
+  challenger_35.in[10] <== challenger_34.out[10];
+  challenger_35.in[11] <== challenger_34.out[11];
+  component challenger_36 = Poseidon();
+  challenger_36.in[0] <== openings_plonk_sigmas[40][0];
+  challenger_36.in[1] <== openings_plonk_sigmas[40][1];
+  challenger_36.in[2] <== openings_plonk_sigmas[41][0];
+  challenger_36.in[3] <== openings_plonk_sigmas[41][1];
+  challenger_36.in[4] <== openings_plonk_sigmas[42][0];
+  challenger_36.in[5] <== openings_plonk_sigmas[42][1];
+  challenger_36.in[6] <== openings_plonk_sigmas[43][0];
+  challenger_36.in[7] <== openings_plonk_sigmas[43][1];
+  challenger_36.in[8] <== challenger_35.out[8];
+  challenger_36.in[9] <== challenger_35.out[9];
+  challenger_36.in[10] <== challenger_35.out[10];
+  challenger_36.in[11] <== challenger_35.out[11];
+  component challenger_37 = Poseidon();
+  challenger_37.in[0] <== openings_plonk_sigmas[44][0];
+  challenger_37.in[1] <== openings_plonk_sigmas[44][1];
+  challenger_37.in[2] <== openings_plonk_sigmas[45][0];
+  challenger_37.in[3] <== openings_plonk_sigmas[45][1];
+  challenger_37.in[4] <== openings_plonk_sigmas[46][0];
+  challenger_37.in[5] <== openings_plonk_sigmas[46][1];
+  challenger_37.in[6] <== openings_plonk_sigmas[47][0];
+  challenger_37.in[7] <== openings_plonk_sigmas[47][1];
+  challenger_37.in[8] <== challenger_36.out[8];
+  challenger_37.in[9] <== challenger_36.out[9];
+  challenger_37.in[10] <== challenger_36.out[10];
+  challenger_37.in[11] <== challenger_36.out[11];
+  component challenger_38 = Poseidon();
+  challenger_38.in[0] <== openings_plonk_sigmas[48][0];
+  challenger_38.in[1] <== openings_plonk_sigmas[48][1];
+  challenger_38.in[2] <== openings_plonk_sigmas[49][0];
+  challenger_38.in[3] <== openings_plonk_sigmas[49][1];
+  challenger_38.in[4] <== openings_plonk_sigmas[50][0];
+  challenger_38.in[5] <== openings_plonk_sigmas[50][1];
+  challenger_38.in[6] <== openings_plonk_sigmas[51][0];
+  challenger_38.in[7] <== openings_plonk_sigmas[51][1];
+  challenger_38.in[8] <== challenger_37.out[8];
+  challenger_38.in[9] <== challenger_37.out[9];
+  challenger_38.in[10] <== challenger_37.out[10];
+  challenger_38.in[11] <== challenger_37.out[11];
+  component challenger_39 = Poseidon();
+  challenger_39.in[0] <== openings_plonk_sigmas[52][0];
+  challenger_39.in[1] <== openings_plonk_sigmas[52][1];
+  challenger_39.in[2] <== openings_plonk_sigmas[53][0];
+  challenger_39.in[3] <== openings_plonk_sigmas[53][1];
+  challenger_39.in[4] <== openings_plonk_sigmas[54][0];
+  challenger_39.in[5] <== openings_plonk_sigmas[54][1];
+  challenger_39.in[6] <== openings_plonk_sigmas[55][0];
+  challenger_39.in[7] <== openings_plonk_sigmas[55][1];
+  challenger_39.in[8] <== challenger_38.out[8];
+  challenger_39.in[9] <== challenger_38.out[9];
+  challenger_39.in[10] <== challenger_38.out[10];
+  challenger_39.in[11] <== challenger_38.out[11];
+  component challenger_40 = Poseidon();
+  challenger_40.in[0] <== openings_plonk_sigmas[56][0];
+  challenger_40.in[1] <== openings_plonk_sigmas[56][1];
+  challenger_40.in[2] <== openings_plonk_sigmas[57][0];
+  challenger_40.in[3] <== openings_plonk_sigmas[57][1];
+  challenger_40.in[4] <== openings_plonk_sigmas[58][0];
+  challenger_40.in[5] <== openings_plonk_sigmas[58][1];
+  challenger_40.in[6] <== openings_plonk_sigmas[59][0];
+  challenger_40.in[7] <== openings_plonk_sigmas[59][1];
+  challenger_40.in[8] <== challenger_39.out[8];
+  challenger_40.in[9] <== challenger_39.out[9];
+  challenger_40.in[10] <== challenger_39.out[10];
+  challenger_40.in[11] <== challenger_39.out[11];
+  component challenger_41 = Poseidon();
+  challenger_41.in[0] <== openings_plonk_sigmas[60][0];
+  challenger_41.in[1] <== openings_plonk_sigmas[60][1];
+  challenger_41.in[2] <== openings_plonk_sigmas[61][0];
+  challenger_41.in[3] <== openings_plonk_sigmas[61][1];
+  challenger_41.in[4] <== openings_plonk_sigmas[62][0];
+  challenger_41.in[5] <== openings_plonk_sigmas[62][1];
+  challenger_41.in[6] <== openings_plonk_sigmas[63][0];
+  challenger_41.in[7] <== openings_plonk_sigmas[63][1];
+  challenger_41.in[8] <== challenger_40.out[8];
+  challenger_41.in[9] <== challenger_40.out[9];
+  challenger_41.in[10] <== challenger_40.out[10];
+  challenger_41.in[11] <== challenger_40.out[11];
+  component challenger_42 = Poseidon();
+  challenger_42.in[0] <== openings_plonk_sigmas[64][0];
+  challenger_42.in[1] <== openings_plonk_sigmas[64][1];
+  challenger_42.in[2] <== openings_plonk_sigmas[65][0];
+  challenger_42.in[3] <== openings_plonk_sigmas[65][1];
+  challenger_42.in[4] <== openings_plonk_sigmas[66][0];
+  challenger_42.in[5] <== openings_plonk_sigmas[66][1];
+  challenger_42.in[6] <== openings_plonk_sigmas[67][0];
+  challenger_42.in[7] <== openings_plonk_sigmas[67][1];
+  challenger_42.in[8] <== challenger_41.out[8];
+  challenger_42.in[9] <== challenger_41.out[9];
+  challenger_42.in[10] <== challenger_41.out[10];
+  challenger_42.in[11] <== challenger_41.out[11];
+  component challenger_43 = Poseidon();
+  challenger_43.in[0] <== openings_plonk_sigmas[68][0];
+  challenger_43.in[1] <== openings_plonk_sigmas[68][1];
+  challenger_43.in[2] <== openings_plonk_sigmas[69][0];
+  challenger_43.in[3] <== openings_plonk_sigmas[69][1];
+  challenger_43.in[4] <== openings_plonk_sigmas[70][0];
+  challenger_43.in[5] <== openings_plonk_sigmas[70][1];
+  challenger_43.in[6] <== openings_plonk_sigmas[71][0];
+  challenger_43.in[7] <== openings_plonk_sigmas[71][1];
+  challenger_43.in[8] <== challenger_42.out[8];
+  challenger_43.in[9] <== challenger_42.out[9];
+  challenger_43.in[10] <== challenger_42.out[10];
+  challenger_43.in[11] <== challenger_42.out[11];
+  component challenger_44 = Poseidon();
+  challenger_44.in[0] <== openings_plonk_sigmas[72][0];
+  challenger_44.in[1] <== openings_plonk_sigmas[72][1];
+  challenger_44.in[2] <== openings_plonk_sigmas[73][0];
+  challenger_44.in[3] <== openings_plonk_sigmas[73][1];
+  challenger_44.in[4] <== openings_plonk_sigmas[74][0];
+  challenger_44.in[5] <== openings_plonk_sigmas[74][1];
+  challenger_44.in[6] <== openings_plonk_sigmas[75][0];
+  challenger_44.in[7] <== openings_plonk_sigmas[75][1];
+  challenger_44.in[8] <== challenger_43.out[8];
+  challenger_44.in[9] <== challenger_43.out[9];
+  challenger_44.in[10] <== challenger_43.out[10];
+  challenger_44.in[11] <== challenger_43.out[11];
+  component challenger_45 = Poseidon();
+  challenger_45.in[0] <== openings_plonk_sigmas[76][0];
+  challenger_45.in[1] <== openings_plonk_sigmas[76][1];
+  challenger_45.in[2] <== openings_plonk_sigmas[77][0];
+  challenger_45.in[3] <== openings_plonk_sigmas[77][1];
+  challenger_45.in[4] <== openings_plonk_sigmas[78][0];
+  challenger_45.in[5] <== openings_plonk_sigmas[78][1];
+  challenger_45.in[6] <== openings_plonk_sigmas[79][0];
+  challenger_45.in[7] <== openings_plonk_sigmas[79][1];
+  challenger_45.in[8] <== challenger_44.out[8];
+  challenger_45.in[9] <== challenger_44.out[9];
+  challenger_45.in[10] <== challenger_44.out[10];
+  challenger_45.in[11] <== challenger_44.out[11];
+  component challenger_46 = Poseidon();
+  challenger_46.in[0] <== openings_wires[0][0];
+  challenger_46.in[1] <== openings_wires[0][1];
+  challenger_46.in[2] <== openings_wires[1][0];
+  challenger_46.in[3] <== openings_wires[1][1];
+  challenger_46.in[4] <== openings_wires[2][0];
+  challenger_46.in[5] <== openings_wires[2][1];
+  challenger_46.in[6] <== openings_wires[3][0];
+  challenger_46.in[7] <== openings_wires[3][1];
+  challenger_46.in[8] <== challenger_45.out[8];
+  challenger_46.in[9] <== challenger_45.out[9];
+  challenger_46.in[10] <== challenger_45.out[10];
+  challenger_46.in[11] <== challenger_45.out[11];
+  component challenger_47 = Poseidon();
+  challenger_47.in[0] <== openings_wires[4][0];
+  challenger_47.in[1] <== openings_wires[4][1];
+  challenger_47.in[2] <== openings_wires[5][0];
+  challenger_47.in[3] <== openings_wires[5][1];
+  challenger_47.in[4] <== openings_wires[6][0];
+  challenger_47.in[5] <== openings_wires[6][1];
+  challenger_47.in[6] <== openings_wires[7][0];
+  challenger_47.in[7] <== openings_wires[7][1];
+  challenger_47.in[8] <== challenger_46.out[8];
+  challenger_47.in[9] <== challenger_46.out[9];
+  challenger_47.in[10] <== challenger_46.out[10];
+  challenger_47.in[11] <== challenger_46.out[11];
+  component challenger_48 = Poseidon();
+  challenger_48.in[0] <== openings_wires[8][0];
+  challenger_48.in[1] <== openings_wires[8][1];
+  challenger_48.in[2] <== openings_wires[9][0];
+  challenger_48.in[3] <== openings_wires[9][1];
+  challenger_48.in[4] <== openings_wires[10][0];
+  challenger_48.in[5] <== openings_wires[10][1];
+  challenger_48.in[6] <== openings_wires[11][0];
+  challenger_48.in[7] <== openings_wires[11][1];
+  challenger_48.in[8] <== challenger_47.out[8];
+  challenger_48.in[9] <== challenger_47.out[9];
+  challenger_48.in[10] <== challenger_47.out[10];
+  challenger_48.in[11] <== challenger_47.out[11];
+  component challenger_49 = Poseidon();
+  challenger_49.in[0] <== openings_wires[12][0];
+  challenger_49.in[1] <== openings_wires[12][1];
+  challenger_49.in[2] <== openings_wires[13][0];
+  challenger_49.in[3] <== openings_wires[13][1];
+  challenger_49.in[4] <== openings_wires[14][0];
+  challenger_49.in[5] <== openings_wires[14][1];
+  challenger_49.in[6] <== openings_wires[15][0];
+  challenger_49.in[7] <== openings_wires[15][1];
+  challenger_49.in[8] <== challenger_48.out[8];
+  challenger_49.in[9] <== challenger_48.out[9];
+  challenger_49.in[10] <== challenger_48.out[10];
+  challenger_49.in[11] <== challenger_48.out[11];
+  component challenger_50 = Poseidon();
+  challenger_50.in[0] <== openings_wires[16][0];
+  challenger_50.in[1] <== openings_wires[16][1];
+  challenger_50.in[2] <== openings_wires[17][0];
+  challenger_50.in[3] <== openings_wires[17][1];
+  challenger_50.in[4] <== openings_wires[18][0];
+  challenger_50.in[5] <== openings_wires[18][1];
+  challenger_50.in[6] <== openings_wires[19][0];
+  challenger_50.in[7] <== openings_wires[19][1];
+  challenger_50.in[8] <== challenger_49.out[8];
+  challenger_50.in[9] <== challenger_49.out[9];
+  challenger_50.in[10] <== challenger_49.out[10];
+  challenger_50.in[11] <== challenger_49.out[11];
+  component challenger_51 = Poseidon();
+  challenger_51.in[0] <== openings_wires[20][0];
+  challenger_51.in[1] <== openings_wires[20][1];
+  challenger_51.in[2] <== openings_wires[21][0];
+  challenger_51.in[3] <== openings_wires[21][1];
+  challenger_51.in[4] <== openings_wires[22][0];
+  challenger_51.in[5] <== openings_wires[22][1];
+  challenger_51.in[6] <== openings_wires[23][0];
+  challenger_51.in[7] <== openings_wires[23][1];
+  challenger_51.in[8] <== challenger_50.out[8];
+  challenger_51.in[9] <== challenger_50.out[9];
+  challenger_51.in[10] <== challenger_50.out[10];
+  challenger_51.in[11] <== challenger_50.out[11];
+  component challenger_52 = Poseidon();
+  challenger_52.in[0] <== openings_wires[24][0];
+  challenger_52.in[1] <== openings_wires[24][1];
+  challenger_52.in[2] <== openings_wires[25][0];
+  challenger_52.in[3] <== openings_wires[25][1];
+  challenger_52.in[4] <== openings_wires[26][0];
+  challenger_52.in[5] <== openings_wires[26][1];
+  challenger_52.in[6] <== openings_wires[27][0];
+  challenger_52.in[7] <== openings_wires[27][1];
+  challenger_52.in[8] <== challenger_51.out[8];
+  challenger_52.in[9] <== challenger_51.out[9];
+  challenger_52.in[10] <== challenger_51.out[10];
+  challenger_52.in[11] <== challenger_51.out[11];
+  component challenger_53 = Poseidon();
+  challenger_53.in[0] <== openings_wires[28][0];
+  challenger_53.in[1] <== openings_wires[28][1];
+  challenger_53.in[2] <== openings_wires[29][0];
+  challenger_53.in[3] <== openings_wires[29][1];
+  challenger_53.in[4] <== openings_wires[30][0];
+  challenger_53.in[5] <== openings_wires[30][1];
+  challenger_53.in[6] <== openings_wires[31][0];
+  challenger_53.in[7] <== openings_wires[31][1];
+  challenger_53.in[8] <== challenger_52.out[8];
+  challenger_53.in[9] <== challenger_52.out[9];
+  challenger_53.in[10] <== challenger_52.out[10];
+  challenger_53.in[11] <== challenger_52.out[11];
+  component challenger_54 = Poseidon();
+  challenger_54.in[0] <== openings_wires[32][0];
+  challenger_54.in[1] <== openings_wires[32][1];
+  challenger_54.in[2] <== openings_wires[33][0];
+  challenger_54.in[3] <== openings_wires[33][1];
+  challenger_54.in[4] <== openings_wires[34][0];
+  challenger_54.in[5] <== openings_wires[34][1];
+  challenger_54.in[6] <== openings_wires[35][0];
+  challenger_54.in[7] <== openings_wires[35][1];
+  challenger_54.in[8] <== challenger_53.out[8];
+  challenger_54.in[9] <== challenger_53.out[9];
+  challenger_54.in[10] <== challenger_53.out[10];
+  challenger_54.in[11] <== challenger_53.out[11];
+  component challenger_55 = Poseidon();
+  challenger_55.in[0] <== openings_wires[36][0];
+  challenger_55.in[1] <== openings_wires[36][1];
+  challenger_55.in[2] <== openings_wires[37][0];
+  challenger_55.in[3] <== openings_wires[37][1];
+  challenger_55.in[4] <== openings_wires[38][0];
+  challenger_55.in[5] <== openings_wires[38][1];
+  challenger_55.in[6] <== openings_wires[39][0];
+  challenger_55.in[7] <== openings_wires[39][1];
+  challenger_55.in[8] <== challenger_54.out[8];
+  challenger_55.in[9] <== challenger_54.out[9];
+  challenger_55.in[10] <== challenger_54.out[10];
+  challenger_55.in[11] <== challenger_54.out[11];
+  component challenger_56 = Poseidon();
+  challenger_56.in[0] <== openings_wires[40][0];
+  challenger_56.in[1] <== openings_wires[40][1];
+  challenger_56.in[2] <== openings_wires[41][0];
+  challenger_56.in[3] <== openings_wires[41][1];
+  challenger_56.in[4] <== openings_wires[42][0];
+  challenger_56.in[5] <== openings_wires[42][1];
+  challenger_56.in[6] <== openings_wires[43][0];
+  challenger_56.in[7] <== openings_wires[43][1];
+  challenger_56.in[8] <== challenger_55.out[8];
+  challenger_56.in[9] <== challenger_55.out[9];
+  challenger_56.in[10] <== challenger_55.out[10];
+  challenger_56.in[11] <== challenger_55.out[11];
+  component challenger_57 = Poseidon();
+  challenger_57.in[0] <== openings_wires[44][0];
+  challenger_57.in[1] <== openings_wires[44][1];
+  challenger_57.in[2] <== openings_wires[45][0];
+  challenger_57.in[3] <== openings_wires[45][1];
+  challenger_57.in[4] <== openings_wires[46][0];
+  challenger_57.in[5] <== openings_wires[46][1];
+  challenger_57.in[6] <== openings_wires[47][0];
+  challenger_57.in[7] <== openings_wires[47][1];
+  challenger_57.in[8] <== challenger_56.out[8];
+  challenger_57.in[9] <== challenger_56.out[9];
+  challenger_57.in[10] <== challenger_56.out[10];
+  challenger_57.in[11] <== challenger_56.out[11];
+  component challenger_58 = Poseidon();
+  challenger_58.in[0] <== openings_wires[48][0];
+  challenger_58.in[1] <== openings_wires[48][1];
+  challenger_58.in[2] <== openings_wires[49][0];
+  challenger_58.in[3] <== openings_wires[49][1];
+  challenger_58.in[4] <== openings_wires[50][0];
+  challenger_58.in[5] <== openings_wires[50][1];
+  challenger_58.in[6] <== openings_wires[51][0];
+  challenger_58.in[7] <== openings_wires[51][1];
+  challenger_58.in[8] <== challenger_57.out[8];
+  challenger_58.in[9] <== challenger_57.out[9];
+  challenger_58.in[10] <== challenger_57.out[10];
+  challenger_58.in[11] <== challenger_57.out[11];
+  component challenger_59 = Poseidon();
+  challenger_59.in[0] <== openings_wires[52][0];
+  challenger_59.in[1] <== openings_wires[52][1];
+  challenger_59.in[2] <== openings_wires[53][0];
+  challenger_59.in[3] <== openings_wires[53][1];
+  challenger_59.in[4] <== openings_wires[54][0];
+  challenger_59.in[5] <== openings_wires[54][1];
+  challenger_59.in[6] <== openings_wires[55][0];
+  challenger_59.in[7] <== openings_wires[55][1];
+  challenger_59.in[8] <== challenger_58.out[8];
+  challenger_59.in[9] <== challenger_58.out[9];
+  challenger_59.in[10] <== challenger_58.out[10];
+  challenger_59.in[11] <== challenger_58.out[11];
+  component challenger_60 = Poseidon();
+  challenger_60.in[0] <== openings_wires[56][0];
+  challenger_60.in[1] <== openings_wires[56][1];
+  challenger_60.in[2] <== openings_wires[57][0];
+  challenger_60.in[3] <== openings_wires[57][1];
+  challenger_60.in[4] <== openings_wires[58][0];
+  challenger_60.in[5] <== openings_wires[58][1];
+  challenger_60.in[6] <== openings_wires[59][0];
+  challenger_60.in[7] <== openings_wires[59][1];
+  challenger_60.in[8] <== challenger_59.out[8];
+  challenger_60.in[9] <== challenger_59.out[9];
+  challenger_60.in[10] <== challenger_59.out[10];
+  challenger_60.in[11] <== challenger_59.out[11];
+  component challenger_61 = Poseidon();
+  challenger_61.in[0] <== openings_wires[60][0];
+  challenger_61.in[1] <== openings_wires[60][1];
+  challenger_61.in[2] <== openings_wires[61][0];
+  challenger_61.in[3] <== openings_wires[61][1];
+  challenger_61.in[4] <== openings_wires[62][0];
+  challenger_61.in[5] <== openings_wires[62][1];
+  challenger_61.in[6] <== openings_wires[63][0];
+  challenger_61.in[7] <== openings_wires[63][1];
+  challenger_61.in[8] <== challenger_60.out[8];
+  challenger_61.in[9] <== challenger_60.out[9];
+  challenger_61.in[10] <== challenger_60.out[10];
+  challenger_61.in[11] <== challenger_60.out[11];
+  component challenger_62 = Poseidon();
+  challenger_62.in[0] <== openings_wires[64][0];
+  challenger_62.in[1] <== openings_wires[64][1];
+  challenger_62.in[2] <== openings_wires[65][0];
+  challenger_62.in[3] <== openings_wires[65][1];
+  challenger_62.in[4] <== openings_wires[66][0];
+  challenger_62.in[5] <== openings_wires[66][1];
+  challenger_62.in[6] <== openings_wires[67][0];
+  challenger_62.in[7] <== openings_wires[67][1];
+  challenger_62.in[8] <== challenger_61.out[8];
+  challenger_62.in[9] <== challenger_61.out[9];
+  challenger_62.in[10] <== challenger_61.out[10];
+  challenger_62.in[11] <== challenger_61.out[11];
+  component challenger_63 = Poseidon();
+  challenger_63.in[0] <== openings_wires[68][0];
+  challenger_63.in[1] <== openings_wires[68][1];
+  challenger_63.in[2] <== openings_wires[69][0];
+  challenger_63.in[3] <== openings_wires[69][1];
+  challenger_63.in[4] <== openings_wires[70][0];
+  challenger_63.in[5] <== openings_wires[70][1];
+  challenger_63.in[6] <== openings_wires[71][0];
+  challenger_63.in[7] <== openings_wires[71][1];
+  challenger_63.in[8] <== challenger_62.out[8];
+  challenger_63.in[9] <== challenger_62.out[9];
+  challenger_63.in[10] <== challenger_62.out[10];
+  challenger_63.in[11] <== challenger_62.out[11];
+  component challenger_64 = Poseidon();
+  challenger_64.in[0] <== openings_wires[72][0];
+  challenger_64.in[1] <== openings_wires[72][1];
+  challenger_64.in[2] <== openings_wires[73][0];
+  challenger_64.in[3] <== openings_wires[73][1];
+  challenger_64.in[4] <== openings_wires[74][0];
+  challenger_64.in[5] <== openings_wires[74][1];
+  challenger_64.in[6] <== openings_wires[75][0];
+  challenger_64.in[7] <== openings_wires[75][1];
+  challenger_64.in[8] <== challenger_63.out[8];
+  challenger_64.in[9] <== challenger_63.out[9];
+  challenger_64.in[10] <== challenger_63.out[10];
+  challenger_64.in[11] <== challenger_63.out[11];
+  component challenger_65 = Poseidon();
+  challenger_65.in[0] <== openings_wires[76][0];
+  challenger_65.in[1] <== openings_wires[76][1];
+  challenger_65.in[2] <== openings_wires[77][0];
+  challenger_65.in[3] <== openings_wires[77][1];
+  challenger_65.in[4] <== openings_wires[78][0];
+  challenger_65.in[5] <== openings_wires[78][1];
+  challenger_65.in[6] <== openings_wires[79][0];
+  challenger_65.in[7] <== openings_wires[79][1];
+  challenger_65.in[8] <== challenger_64.out[8];
+  challenger_65.in[9] <== challenger_64.out[9];
+  challenger_65.in[10] <== challenger_64.out[10];
+  challenger_65.in[11] <== challenger_64.out[11];
+  component challenger_66 = Poseidon();
+  challenger_66.in[0] <== openings_wires[80][0];
+  challenger_66.in[1] <== openings_wires[80][1];
+  challenger_66.in[2] <== openings_wires[81][0];
+  challenger_66.in[3] <== openings_wires[81][1];
+  challenger_66.in[4] <== openings_wires[82][0];
+  challenger_66.in[5] <== openings_wires[82][1];
+  challenger_66.in[6] <== openings_wires[83][0];
+  challenger_66.in[7] <== openings_wires[83][1];
+  challenger_66.in[8] <== challenger_65.out[8];
+  challenger_66.in[9] <== challenger_65.out[9];
+  challenger_66.in[10] <== challenger_65.out[10];
+  challenger_66.in[11] <== challenger_65.out[11];
+  component challenger_67 = Poseidon();
+  challenger_67.in[0] <== openings_wires[84][0];
+  challenger_67.in[1] <== openings_wires[84][1];
+  challenger_67.in[2] <== openings_wires[85][0];
+  challenger_67.in[3] <== openings_wires[85][1];
+  challenger_67.in[4] <== openings_wires[86][0];
+  challenger_67.in[5] <== openings_wires[86][1];
+  challenger_67.in[6] <== openings_wires[87][0];
+  challenger_67.in[7] <== openings_wires[87][1];
+  challenger_67.in[8] <== challenger_66.out[8];
+  challenger_67.in[9] <== challenger_66.out[9];
+  challenger_67.in[10] <== challenger_66.out[10];
+  challenger_67.in[11] <== challenger_66.out[11];
+  component challenger_68 = Poseidon();
+  challenger_68.in[0] <== openings_wires[88][0];
+  challenger_68.in[1] <== openings_wires[88][1];
+  challenger_68.in[2] <== openings_wires[89][0];
+  challenger_68.in[3] <== openings_wires[89][1];
+  challenger_68.in[4] <== openings_wires[90][0];
+  challenger_68.in[5] <== openings_wires[90][1];
+  challenger_68.in[6] <== openings_wires[91][0];
+  challenger_68.in[7] <== openings_wires[91][1];
+  challenger_68.in[8] <== challenger_67.out[8];
+  challenger_68.in[9] <== challenger_67.out[9];
+  challenger_68.in[10] <== challenger_67.out[10];
+  challenger_68.in[11] <== challenger_67.out[11];
+  component challenger_69 = Poseidon();
+  challenger_69.in[0] <== openings_wires[92][0];
+  challenger_69.in[1] <== openings_wires[92][1];
+  challenger_69.in[2] <== openings_wires[93][0];
+  challenger_69.in[3] <== openings_wires[93][1];
+  challenger_69.in[4] <== openings_wires[94][0];
+  challenger_69.in[5] <== openings_wires[94][1];
+  challenger_69.in[6] <== openings_wires[95][0];
+  challenger_69.in[7] <== openings_wires[95][1];
+  challenger_69.in[8] <== challenger_68.out[8];
+  challenger_69.in[9] <== challenger_68.out[9];
+  challenger_69.in[10] <== challenger_68.out[10];
+  challenger_69.in[11] <== challenger_68.out[11];
+  component challenger_70 = Poseidon();
+  challenger_70.in[0] <== openings_wires[96][0];
+  challenger_70.in[1] <== openings_wires[96][1];
+  challenger_70.in[2] <== openings_wires[97][0];
+  challenger_70.in[3] <== openings_wires[97][1];
+  challenger_70.in[4] <== openings_wires[98][0];
+  challenger_70.in[5] <== openings_wires[98][1];
+  challenger_70.in[6] <== openings_wires[99][0];
+  challenger_70.in[7] <== openings_wires[99][1];
+  challenger_70.in[8] <== challenger_69.out[8];
+  challenger_70.in[9] <== challenger_69.out[9];
+  challenger_70.in[10] <== challenger_69.out[10];
+  challenger_70.in[11] <== challenger_69.out[11];
+  component challenger_71 = Poseidon();
+  challenger_71.in[0] <== openings_wires[100][0];
+  challenger_71.in[1] <== openings_wires[100][1];
+  challenger_71.in[2] <== openings_wires[101][0];
+  challenger_71.in[3] <== openings_wires[101][1];
+  challenger_71.in[4] <== openings_wires[102][0];
+  challenger_71.in[5] <== openings_wires[102][1];
+  challenger_71.in[6] <== openings_wires[103][0];
+  challenger_71.in[7] <== openings_wires[103][1];
+  challenger_71.in[8] <== challenger_70.out[8];
+  challenger_71.in[9] <== challenger_70.out[9];
+  challenger_71.in[10] <== challenger_70.out[10];
+  challenger_71.in[11] <== challenger_70.out[11];
+  component challenger_72 = Poseidon();
+  challenger_72.in[0] <== openings_wires[104][0];
+  challenger_72.in[1] <== openings_wires[104][1];
+  challenger_72.in[2] <== openings_wires[105][0];
+  challenger_72.in[3] <== openings_wires[105][1];
+  challenger_72.in[4] <== openings_wires[106][0];
+  challenger_72.in[5] <== openings_wires[106][1];
+  challenger_72.in[6] <== openings_wires[107][0];
+  challenger_72.in[7] <== openings_wires[107][1];
+  challenger_72.in[8] <== challenger_71.out[8];
+  challenger_72.in[9] <== challenger_71.out[9];
+  challenger_72.in[10] <== challenger_71.out[10];
+  challenger_72.in[11] <== challenger_71.out[11];
+  component challenger_73 = Poseidon();
+  challenger_73.in[0] <== openings_wires[108][0];
+  challenger_73.in[1] <== openings_wires[108][1];
+  challenger_73.in[2] <== openings_wires[109][0];
+  challenger_73.in[3] <== openings_wires[109][1];
+  challenger_73.in[4] <== openings_wires[110][0];
+  challenger_73.in[5] <== openings_wires[110][1];
+  challenger_73.in[6] <== openings_wires[111][0];
+  challenger_73.in[7] <== openings_wires[111][1];
+  challenger_73.in[8] <== challenger_72.out[8];
+  challenger_73.in[9] <== challenger_72.out[9];
+  challenger_73.in[10] <== challenger_72.out[10];
+  challenger_73.in[11] <== challenger_72.out[11];
+  component challenger_74 = Poseidon();
+  challenger_74.in[0] <== openings_wires[112][0];
+  challenger_74.in[1] <== openings_wires[112][1];
+  challenger_74.in[2] <== openings_wires[113][0];
+  challenger_74.in[3] <== openings_wires[113][1];
+  challenger_74.in[4] <== openings_wires[114][0];
+  challenger_74.in[5] <== openings_wires[114][1];
+  challenger_74.in[6] <== openings_wires[115][0];
+  challenger_74.in[7] <== openings_wires[115][1];
+  challenger_74.in[8] <== challenger_73.out[8];
+  challenger_74.in[9] <== challenger_73.out[9];
+  challenger_74.in[10] <== challenger_73.out[10];
+  challenger_74.in[11] <== challenger_73.out[11];
+  component challenger_75 = Poseidon();
+  challenger_75.in[0] <== openings_wires[116][0];
+  challenger_75.in[1] <== openings_wires[116][1];
+  challenger_75.in[2] <== openings_wires[117][0];
+  challenger_75.in[3] <== openings_wires[117][1];
+  challenger_75.in[4] <== openings_wires[118][0];
+  challenger_75.in[5] <== openings_wires[118][1];
+  challenger_75.in[6] <== openings_wires[119][0];
+  challenger_75.in[7] <== openings_wires[119][1];
+  challenger_75.in[8] <== challenger_74.out[8];
+  challenger_75.in[9] <== challenger_74.out[9];
+  challenger_75.in[10] <== challenger_74.out[10];
+  challenger_75.in[11] <== challenger_74.out[11];
+  component challenger_76 = Poseidon();
+  challenger_76.in[0] <== openings_wires[120][0];
+  challenger_76.in[1] <== openings_wires[120][1];
+  challenger_76.in[2] <== openings_wires[121][0];
+  challenger_76.in[3] <== openings_wires[121][1];
+  challenger_76.in[4] <== openings_wires[122][0];
+  challenger_76.in[5] <== openings_wires[122][1];
+  challenger_76.in[6] <== openings_wires[123][0];
+  challenger_76.in[7] <== openings_wires[123][1];
+  challenger_76.in[8] <== challenger_75.out[8];
+  challenger_76.in[9] <== challenger_75.out[9];
+  challenger_76.in[10] <== challenger_75.out[10];
+  challenger_76.in[11] <== challenger_75.out[11];
+  component challenger_77 = Poseidon();
+  challenger_77.in[0] <== openings_wires[124][0];
+  challenger_77.in[1] <== openings_wires[124][1];
+  challenger_77.in[2] <== openings_wires[125][0];
+  challenger_77.in[3] <== openings_wires[125][1];
+  challenger_77.in[4] <== openings_wires[126][0];
+  challenger_77.in[5] <== openings_wires[126][1];
+  challenger_77.in[6] <== openings_wires[127][0];
+  challenger_77.in[7] <== openings_wires[127][1];
+  challenger_77.in[8] <== challenger_76.out[8];
+  challenger_77.in[9] <== challenger_76.out[9];
+  challenger_77.in[10] <== challenger_76.out[10];
+  challenger_77.in[11] <== challenger_76.out[11];
+  component challenger_78 = Poseidon();
+  challenger_78.in[0] <== openings_wires[128][0];
+  challenger_78.in[1] <== openings_wires[128][1];
+  challenger_78.in[2] <== openings_wires[129][0];
+  challenger_78.in[3] <== openings_wires[129][1];
+  challenger_78.in[4] <== openings_wires[130][0];
+  challenger_78.in[5] <== openings_wires[130][1];
+  challenger_78.in[6] <== openings_wires[131][0];
+  challenger_78.in[7] <== openings_wires[131][1];
+  challenger_78.in[8] <== challenger_77.out[8];
+  challenger_78.in[9] <== challenger_77.out[9];
+  challenger_78.in[10] <== challenger_77.out[10];
+  challenger_78.in[11] <== challenger_77.out[11];
+  component challenger_79 = Poseidon();
+  challenger_79.in[0] <== openings_wires[132][0];
+  challenger_79.in[1] <== openings_wires[132][1];
+  challenger_79.in[2] <== openings_wires[133][0];
+  challenger_79.in[3] <== openings_wires[133][1];
+  challenger_79.in[4] <== openings_wires[134][0];
+  challenger_79.in[5] <== openings_wires[134][1];
+  challenger_79.in[6] <== openings_plonk_zs[0][0];
+  challenger_79.in[7] <== openings_plonk_zs[0][1];
+  challenger_79.in[8] <== challenger_78.out[8];
+  challenger_79.in[9] <== challenger_78.out[9];
+  challenger_79.in[10] <== challenger_78.out[10];
+  challenger_79.in[11] <== challenger_78.out[11];
+  component challenger_80 = Poseidon();
+  challenger_80.in[0] <== openings_plonk_zs[1][0];
+  challenger_80.in[1] <== openings_plonk_zs[1][1];
+  challenger_80.in[2] <== openings_partial_products[0][0];
+  challenger_80.in[3] <== openings_partial_products[0][1];
+  challenger_80.in[4] <== openings_partial_products[1][0];
+  challenger_80.in[5] <== openings_partial_products[1][1];
+  challenger_80.in[6] <== openings_partial_products[2][0];
+  challenger_80.in[7] <== openings_partial_products[2][1];
+  challenger_80.in[8] <== challenger_79.out[8];
+  challenger_80.in[9] <== challenger_79.out[9];
+  challenger_80.in[10] <== challenger_79.out[10];
+  challenger_80.in[11] <== challenger_79.out[11];
+  component challenger_81 = Poseidon();
+  challenger_81.in[0] <== openings_partial_products[3][0];
+  challenger_81.in[1] <== openings_partial_products[3][1];
+  challenger_81.in[2] <== openings_partial_products[4][0];
+  challenger_81.in[3] <== openings_partial_products[4][1];
+  challenger_81.in[4] <== openings_partial_products[5][0];
+  challenger_81.in[5] <== openings_partial_products[5][1];
+  challenger_81.in[6] <== openings_partial_products[6][0];
+  challenger_81.in[7] <== openings_partial_products[6][1];
+  challenger_81.in[8] <== challenger_80.out[8];
+  challenger_81.in[9] <== challenger_80.out[9];
+  challenger_81.in[10] <== challenger_80.out[10];
+  challenger_81.in[11] <== challenger_80.out[11];
+  component challenger_82 = Poseidon();
+  challenger_82.in[0] <== openings_partial_products[7][0];
+  challenger_82.in[1] <== openings_partial_products[7][1];
+  challenger_82.in[2] <== openings_partial_products[8][0];
+  challenger_82.in[3] <== openings_partial_products[8][1];
+  challenger_82.in[4] <== openings_partial_products[9][0];
+  challenger_82.in[5] <== openings_partial_products[9][1];
+  challenger_82.in[6] <== openings_partial_products[10][0];
+  challenger_82.in[7] <== openings_partial_products[10][1];
+  challenger_82.in[8] <== challenger_81.out[8];
+  challenger_82.in[9] <== challenger_81.out[9];
+  challenger_82.in[10] <== challenger_81.out[10];
+  challenger_82.in[11] <== challenger_81.out[11];
+  component challenger_83 = Poseidon();
+  challenger_83.in[0] <== openings_partial_products[11][0];
+  challenger_83.in[1] <== openings_partial_products[11][1];
+  challenger_83.in[2] <== openings_partial_products[12][0];
+  challenger_83.in[3] <== openings_partial_products[12][1];
+  challenger_83.in[4] <== openings_partial_products[13][0];
+  challenger_83.in[5] <== openings_partial_products[13][1];
+  challenger_83.in[6] <== openings_partial_products[14][0];
+  challenger_83.in[7] <== openings_partial_products[14][1];
+  challenger_83.in[8] <== challenger_82.out[8];
+  challenger_83.in[9] <== challenger_82.out[9];
+  challenger_83.in[10] <== challenger_82.out[10];
+  challenger_83.in[11] <== challenger_82.out[11];
+  component challenger_84 = Poseidon();
+  challenger_84.in[0] <== openings_partial_products[15][0];
+  challenger_84.in[1] <== openings_partial_products[15][1];
+  challenger_84.in[2] <== openings_partial_products[16][0];
+  challenger_84.in[3] <== openings_partial_products[16][1];
+  challenger_84.in[4] <== openings_partial_products[17][0];
+  challenger_84.in[5] <== openings_partial_products[17][1];
+  challenger_84.in[6] <== openings_quotient_polys[0][0];
+  challenger_84.in[7] <== openings_quotient_polys[0][1];
+  challenger_84.in[8] <== challenger_83.out[8];
+  challenger_84.in[9] <== challenger_83.out[9];
+  challenger_84.in[10] <== challenger_83.out[10];
+  challenger_84.in[11] <== challenger_83.out[11];
+  component challenger_85 = Poseidon();
+  challenger_85.in[0] <== openings_quotient_polys[1][0];
+  challenger_85.in[1] <== openings_quotient_polys[1][1];
+  challenger_85.in[2] <== openings_quotient_polys[2][0];
+  challenger_85.in[3] <== openings_quotient_polys[2][1];
+  challenger_85.in[4] <== openings_quotient_polys[3][0];
+  challenger_85.in[5] <== openings_quotient_polys[3][1];
+  challenger_85.in[6] <== openings_quotient_polys[4][0];
+  challenger_85.in[7] <== openings_quotient_polys[4][1];
+  challenger_85.in[8] <== challenger_84.out[8];
+  challenger_85.in[9] <== challenger_84.out[9];
+  challenger_85.in[10] <== challenger_84.out[10];
+  challenger_85.in[11] <== challenger_84.out[11];
+  component challenger_86 = Poseidon();
+  challenger_86.in[0] <== openings_quotient_polys[5][0];
+  challenger_86.in[1] <== openings_quotient_polys[5][1];
+  challenger_86.in[2] <== openings_quotient_polys[6][0];
+  challenger_86.in[3] <== openings_quotient_polys[6][1];
+  challenger_86.in[4] <== openings_quotient_polys[7][0];
+  challenger_86.in[5] <== openings_quotient_polys[7][1];
+  challenger_86.in[6] <== openings_quotient_polys[8][0];
+  challenger_86.in[7] <== openings_quotient_polys[8][1];
+  challenger_86.in[8] <== challenger_85.out[8];
+  challenger_86.in[9] <== challenger_85.out[9];
+  challenger_86.in[10] <== challenger_85.out[10];
+  challenger_86.in[11] <== challenger_85.out[11];
+  component challenger_87 = Poseidon();
+  challenger_87.in[0] <== openings_quotient_polys[9][0];
+  challenger_87.in[1] <== openings_quotient_polys[9][1];
+  challenger_87.in[2] <== openings_quotient_polys[10][0];
+  challenger_87.in[3] <== openings_quotient_polys[10][1];
+  challenger_87.in[4] <== openings_quotient_polys[11][0];
+  challenger_87.in[5] <== openings_quotient_polys[11][1];
+  challenger_87.in[6] <== openings_quotient_polys[12][0];
+  challenger_87.in[7] <== openings_quotient_polys[12][1];
+  challenger_87.in[8] <== challenger_86.out[8];
+  challenger_87.in[9] <== challenger_86.out[9];
+  challenger_87.in[10] <== challenger_86.out[10];
+  challenger_87.in[11] <== challenger_86.out[11];
+  component challenger_88 = Poseidon();
+  challenger_88.in[0] <== openings_quotient_polys[13][0];
+  challenger_88.in[1] <== openings_quotient_polys[13][1];
+  challenger_88.in[2] <== openings_quotient_polys[14][0];
+  challenger_88.in[3] <== openings_quotient_polys[14][1];
+  challenger_88.in[4] <== openings_quotient_polys[15][0];
+  challenger_88.in[5] <== openings_quotient_polys[15][1];
+  challenger_88.in[6] <== openings_plonk_zs_next[0][0];
+  challenger_88.in[7] <== openings_plonk_zs_next[0][1];
+  challenger_88.in[8] <== challenger_87.out[8];
+  challenger_88.in[9] <== challenger_87.out[9];
+  challenger_88.in[10] <== challenger_87.out[10];
+  challenger_88.in[11] <== challenger_87.out[11];
+  component challenger_89 = Poseidon();
+  challenger_89.in[0] <== openings_plonk_zs_next[1][0];
+  challenger_89.in[1] <== openings_plonk_zs_next[1][1];
+  challenger_89.in[2] <== challenger_88.out[2];
+  challenger_89.in[3] <== challenger_88.out[3];
+  challenger_89.in[4] <== challenger_88.out[4];
+  challenger_89.in[5] <== challenger_88.out[5];
+  challenger_89.in[6] <== challenger_88.out[6];
+  challenger_89.in[7] <== challenger_88.out[7];
+  challenger_89.in[8] <== challenger_88.out[8];
+  challenger_89.in[9] <== challenger_88.out[9];
+  challenger_89.in[10] <== challenger_88.out[10];
+  challenger_89.in[11] <== challenger_88.out[11];
+  component challenger_90 = Poseidon();
+  challenger_90.in[0] <== fri_final_poly[0][0];
+  challenger_90.in[1] <== fri_final_poly[0][1];
+  challenger_90.in[2] <== fri_final_poly[1][0];
+  challenger_90.in[3] <== fri_final_poly[1][1];
+  challenger_90.in[4] <== fri_final_poly[2][0];
+  challenger_90.in[5] <== fri_final_poly[2][1];
+  challenger_90.in[6] <== fri_final_poly[3][0];
+  challenger_90.in[7] <== fri_final_poly[3][1];
+  challenger_90.in[8] <== challenger_89.out[8];
+  challenger_90.in[9] <== challenger_89.out[9];
+  challenger_90.in[10] <== challenger_89.out[10];
+  challenger_90.in[11] <== challenger_89.out[11];
+  component challenger_91 = Poseidon();
+  challenger_91.in[0] <== fri_pow_witness;
+  challenger_91.in[1] <== challenger_90.out[1];
+  challenger_91.in[2] <== challenger_90.out[2];
+  challenger_91.in[3] <== challenger_90.out[3];
+  challenger_91.in[4] <== challenger_90.out[4];
+  challenger_91.in[5] <== challenger_90.out[5];
+  challenger_91.in[6] <== challenger_90.out[6];
+  challenger_91.in[7] <== challenger_90.out[7];
+  challenger_91.in[8] <== challenger_90.out[8];
+  challenger_91.in[9] <== challenger_90.out[9];
+  challenger_91.in[10] <== challenger_90.out[10];
+  challenger_91.in[11] <== challenger_90.out[11];
+  component challenger_92 = Poseidon();
+  challenger_92.in[0] <== challenger_91.out[0];
+  challenger_92.in[1] <== challenger_91.out[1];
+  challenger_92.in[2] <== challenger_91.out[2];
+  challenger_92.in[3] <== challenger_91.out[3];
+  challenger_92.in[4] <== challenger_91.out[4];
+  challenger_92.in[5] <== challenger_91.out[5];
+  challenger_92.in[6] <== challenger_91.out[6];
+  challenger_92.in[7] <== challenger_91.out[7];
+  challenger_92.in[8] <== challenger_91.out[8];
+  challenger_92.in[9] <== challenger_91.out[9];
+  challenger_92.in[10] <== challenger_91.out[10];
+  challenger_92.in[11] <== challenger_91.out[11];
+  component challenger_93 = Poseidon();
+  challenger_93.in[0] <== challenger_92.out[0];
+  challenger_93.in[1] <== challenger_92.out[1];
+  challenger_93.in[2] <== challenger_92.out[2];
+  challenger_93.in[3] <== challenger_92.out[3];
+  challenger_93.in[4] <== challenger_92.out[4];
+  challenger_93.in[5] <== challenger_92.out[5];
+  challenger_93.in[6] <== challenger_92.out[6];
+  challenger_93.in[7] <== challenger_92.out[7];
+  challenger_93.in[8] <== challenger_92.out[8];
+  challenger_93.in[9] <== challenger_92.out[9];
+  challenger_93.in[10] <== challenger_92.out[10];
+  challenger_93.in[11] <== challenger_92.out[11];
+  component challenger_94 = Poseidon();
+  challenger_94.in[0] <== challenger_93.out[0];
+  challenger_94.in[1] <== challenger_93.out[1];
+  challenger_94.in[2] <== challenger_93.out[2];
+  challenger_94.in[3] <== challenger_93.out[3];
+  challenger_94.in[4] <== challenger_93.out[4];
+  challenger_94.in[5] <== challenger_93.out[5];
+  challenger_94.in[6] <== challenger_93.out[6];
+  challenger_94.in[7] <== challenger_93.out[7];
+  challenger_94.in[8] <== challenger_93.out[8];
+  challenger_94.in[9] <== challenger_93.out[9];
+  challenger_94.in[10] <== challenger_93.out[10];
+  challenger_94.in[11] <== challenger_93.out[11];
+  signal betas[2];
+  betas[0] <== challenger_8.out[7];
+  betas[1] <== challenger_8.out[6];
+  signal gammas[2];
+  gammas[0] <== challenger_8.out[5];
+  gammas[1] <== challenger_8.out[4];
+  signal alphas[2];
+  alphas[0] <== challenger_16.out[7];
+  alphas[1] <== challenger_16.out[6];
+  signal zeta[2];
+  zeta[0] <== challenger_24.out[7];
+  zeta[1] <== challenger_24.out[6];
+  signal fri_alpha[2];
+  fri_alpha[0] <== challenger_89.out[7];
+  fri_alpha[1] <== challenger_89.out[6];
+  signal fri_pow_response;
+  fri_pow_response <== challenger_91.out[7];
+  signal fri_query_indices[28];
+  fri_query_indices[0] <== challenger_91.out[6];
+  fri_query_indices[1] <== challenger_91.out[5];
+  fri_query_indices[2] <== challenger_91.out[4];
+  fri_query_indices[3] <== challenger_91.out[3];
+  fri_query_indices[4] <== challenger_91.out[2];
+  fri_query_indices[5] <== challenger_91.out[1];
+  fri_query_indices[6] <== challenger_91.out[0];
+  fri_query_indices[7] <== challenger_92.out[7];
+  fri_query_indices[8] <== challenger_92.out[6];
+  fri_query_indices[9] <== challenger_92.out[5];
+  fri_query_indices[10] <== challenger_92.out[4];
+  fri_query_indices[11] <== challenger_92.out[3];
+  fri_query_indices[12] <== challenger_92.out[2];
+  fri_query_indices[13] <== challenger_92.out[1];
+  fri_query_indices[14] <== challenger_92.out[0];
+  fri_query_indices[15] <== challenger_93.out[7];
+  fri_query_indices[16] <== challenger_93.out[6];
+  fri_query_indices[17] <== challenger_93.out[5];
+  fri_query_indices[18] <== challenger_93.out[4];
+  fri_query_indices[19] <== challenger_93.out[3];
+  fri_query_indices[20] <== challenger_93.out[2];
+  fri_query_indices[21] <== challenger_93.out[1];
+  fri_query_indices[22] <== challenger_93.out[0];
+  fri_query_indices[23] <== challenger_94.out[7];
+  fri_query_indices[24] <== challenger_94.out[6];
+  fri_query_indices[25] <== challenger_94.out[5];
+  fri_query_indices[26] <== challenger_94.out[4];
+  fri_query_indices[27] <== challenger_94.out[3];
+
+  // The vanishing polynomial at zeta.
+  signal zeta_pow_deg[2];
+  zeta_pow_deg <== GlExtExpPowerOf2(DEGREE_BITS())(zeta);
+  component vanishing = VanishingPolyCheck();
+  vanishing.constants <== openings_constants;
+  vanishing.plonk_sigmas <== openings_plonk_sigmas;
+  vanishing.wires <== openings_wires;
+  vanishing.plonk_zs <== openings_plonk_zs;
+  vanishing.partial_products <== openings_partial_products;
+  vanishing.quotient_polys <== openings_quotient_polys;
+  vanishing.plonk_zs_next <== openings_plonk_zs_next;
+  vanishing.public_input_hash <== public_input_hash;
+  vanishing.betas <== betas;
+  vanishing.gammas <== gammas;
+  vanishing.alphas <== alphas;
+  vanishing.zeta <== zeta;
+  vanishing.zeta_pow_deg <== zeta_pow_deg;
+
+  // FRI.
+  signal zeta_next[2];
+  zeta_next <== GlExtScalarMul()(zeta, G_FROM_DEGREE_BITS());
+  component reduced_zeta = Reduce(255);
+  for (var i = 0; i < 4; i++) {
+    reduced_zeta.in[0 + i] <== openings_constants[i];
+  }
+  for (var i = 0; i < 80; i++) {
+    reduced_zeta.in[4 + i] <== openings_plonk_sigmas[i];
+  }
+  for (var i = 0; i < 135; i++) {
+    reduced_zeta.in[84 + i] <== openings_wires[i];
+  }
+  for (var i = 0; i < 2; i++) {
+    reduced_zeta.in[219 + i] <== openings_plonk_zs[i];
+  }
+  for (var i = 0; i < 18; i++) {
+    reduced_zeta.in[221 + i] <== openings_partial_products[i];
+  }
+  for (var i = 0; i < 16; i++) {
+    reduced_zeta.in[239 + i] <== openings_quotient_polys[i];
+  }
+  reduced_zeta.alpha <== fri_alpha;
+  reduced_zeta.old_eval <== GlExt(0, 0)();
+  signal reduced_openings[2][2];
+  reduced_openings[0] <== reduced_zeta.out;
+  reduced_openings[1] <== Reduce(2)(openings_plonk_zs_next, fri_alpha, GlExt(0, 0)());
+  signal fri_alpha_pow[2];
+  fri_alpha_pow <== GlExtExpN(2)(fri_alpha, 2);
+
+  component pow_check = Num2Bits(64 - FRI_POW_BITS());
+  pow_check.in <== fri_pow_response;
+
+  component query_index_bits[28];
+  component subgroup_x[28];
+  component combine[28];
+  component final_eval[28];
+  component init_constants_sigmas[28];
+  component init_wires[28];
+  component init_zs_partial_products[28];
+  component init_quotient[28];
+  for (var q = 0; q < 28; q++) {
+    query_index_bits[q] = Num2Bits(64);
+    query_index_bits[q].in <== fri_query_indices[q];
+    subgroup_x[q] = SubgroupX(LDE_BITS());
+    for (var b = 0; b < LDE_BITS(); b++) {
+      subgroup_x[q].index_bits[b] <== query_index_bits[q].out[b];
+    }
+
+    init_constants_sigmas[q] = MerkleProof(84, 1, CAP_HEIGHT());
+    init_constants_sigmas[q].leaf <== fri_query_init_constants_sigmas_v[q];
+    init_constants_sigmas[q].siblings <== fri_query_init_constants_sigmas_p[q];
+    for (var b = 0; b < LDE_BITS(); b++) {
+      init_constants_sigmas[q].index_bits[b] <== query_index_bits[q].out[b];
+    }
+    init_constants_sigmas[q].cap <== constants_sigmas_cap;
+    init_wires[q] = MerkleProof(135, 1, CAP_HEIGHT());
+    init_wires[q].leaf <== fri_query_init_wires_v[q];
+    init_wires[q].siblings <== fri_query_init_wires_p[q];
+    for (var b = 0; b < LDE_BITS(); b++) {
+      init_wires[q].index_bits[b] <== query_index_bits[q].out[b];
+    }
+    init_wires[q].cap <== wires_cap;
+    init_zs_partial_products[q] = MerkleProof(20, 1, CAP_HEIGHT());
+    init_zs_partial_products[q].leaf <== fri_query_init_zs_partial_products_v[q];
+    init_zs_partial_products[q].siblings <== fri_query_init_zs_partial_products_p[q];
+    for (var b = 0; b < LDE_BITS(); b++) {
+      init_zs_partial_products[q].index_bits[b] <== query_index_bits[q].out[b];
+    }
+    init_zs_partial_products[q].cap <== plonk_zs_partial_products_cap;
+    init_quotient[q] = MerkleProof(16, 1, CAP_HEIGHT());
+    init_quotient[q].leaf <== fri_query_init_quotient_v[q];
+    init_quotient[q].siblings <== fri_query_init_quotient_p[q];
+    for (var b = 0; b < LDE_BITS(); b++) {
+      init_quotient[q].index_bits[b] <== query_index_bits[q].out[b];
+    }
+    init_quotient[q].cap <== quotient_polys_cap;
+
+    combine[q] = FriCombineInitial(255, 2);
+    for (var i = 0; i < 84; i++) {
+      combine[q].zeta_evals[0 + i] <== fri_query_init_constants_sigmas_v[q][i];
+    }
+    for (var i = 0; i < 135; i++) {
+      combine[q].zeta_evals[84 + i] <== fri_query_init_wires_v[q][i];
+    }
+    for (var i = 0; i < 20; i++) {
+      combine[q].zeta_evals[219 + i] <== fri_query_init_zs_partial_products_v[q][i];
+    }
+    for (var i = 0; i < 16; i++) {
+      combine[q].zeta_evals[239 + i] <== fri_query_init_quotient_v[q][i];
+    }
+    for (var i = 0; i < 2; i++) {
+      combine[q].zeta_next_evals[i] <== fri_query_init_zs_partial_products_v[q][i];
+    }
+    combine[q].alpha <== fri_alpha;
+    combine[q].alpha_pow <== fri_alpha_pow;
+    combine[q].reduced_openings <== reduced_openings;
+    combine[q].zeta <== zeta;
+    combine[q].zeta_next <== zeta_next;
+    combine[q].x <== subgroup_x[q].out;
+
+    final_eval[q] = FinalPolyEval(NUM_FRI_FINAL_POLY());
+    final_eval[q].coeffs <== fri_final_poly;
+    final_eval[q].x <== subgroup_x[q].out;
+    final_eval[q].out[0] === combine[q].out[0];
+    final_eval[q].out[1] === combine[q].out[1];
+  }
+}
+
+component main {public [public_inputs]} = Verifier();
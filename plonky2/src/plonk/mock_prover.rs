@@ -0,0 +1,374 @@
+//! A mock prover, which checks a witness against the constraints of a circuit directly, without
+//! any FFT or commitment. When `prove` fails because the quotient polynomial isn't divisible by
+//! `Z_H`, [`check_witness`] tells which gate, row and constraint the witness violates.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use anyhow::Result;
+use hashbrown::HashMap;
+use maybe_rayon::*;
+
+use crate::field::extension::Extendable;
+use crate::gates::lookup::LookupGate;
+use crate::gates::lookup_table::LookupTableGate;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::generate_partial_witness;
+use crate::iop::wire::Wire;
+use crate::iop::witness::{MatrixWitness, PartialWitness, Witness};
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::vars::EvaluationVarsBaseBatch;
+
+/// A constraint of the circuit which doesn't hold for the witness.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConstraintViolation {
+    /// The ID of the gate in `row`.
+    pub gate: String,
+    pub row: usize,
+    pub constraint: ViolatedConstraint,
+    /// The scope in which the gate was created, like `root > verify proof`.
+    pub scope: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ViolatedConstraint {
+    /// The constraint of the gate with this index.
+    Gate(usize),
+    /// The copy constraint between the wire in `column` and the next wire of its partition in
+    /// the permutation, `sigma`.
+    Copy { column: usize, sigma: Wire },
+    /// The pair `(input, output)` in this slot of a `LookupGate` isn't an entry of its table.
+    Lookup { slot: usize },
+    /// The multiplicities of the entry in this slot of a `LookupTableGate`, summed over the slots
+    /// holding the same entry, don't match the number of times it is looked up.
+    Multiplicity { slot: usize },
+}
+
+impl Display for ConstraintViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.constraint {
+            ViolatedConstraint::Gate(index) => write!(
+                f,
+                "constraint {index} of {} at row {} is violated",
+                self.gate, self.row
+            )?,
+            ViolatedConstraint::Copy { column, sigma } => write!(
+                f,
+                "wire {column} of {} at row {} differs from wire {} at row {}, which it is \
+                 copied to",
+                self.gate, self.row, sigma.column, sigma.row
+            )?,
+            ViolatedConstraint::Lookup { slot } => write!(
+                f,
+                "lookup {slot} of {} at row {} is not an entry of its table",
+                self.gate, self.row
+            )?,
+            ViolatedConstraint::Multiplicity { slot } => write!(
+                f,
+                "the multiplicity of entry {slot} of {} at row {} doesn't match its lookups",
+                self.gate, self.row
+            )?,
+        }
+        if let Some(scope) = &self.scope {
+            write!(f, ", in `{scope}`")?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates the witness of the circuit from `inputs`, and returns the constraints it violates,
+/// ordered by row. An empty list means that `prove` would succeed.
+pub fn check_witness<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+) -> Result<Vec<ConstraintViolation>> {
+    let config = &common_data.config;
+    let origins = &prover_data.target_origins;
+
+    let partition_witness = generate_partial_witness(inputs, prover_data, common_data)?;
+    let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
+    let public_inputs_hash = C::InnerHasher::hash_public_inputs(&public_inputs);
+    let witness = partition_witness.full_witness();
+    let row_constants = prover_data.row_constants(common_data);
+
+    // The wire which each sigma value points to. The sigma of the wire in row `r` and column `c`
+    // is `k_is[c] * subgroup[r]`.
+    let wires_by_sigma = common_data
+        .k_is
+        .iter()
+        .take(config.num_routed_wires)
+        .enumerate()
+        .flat_map(|(column, &k_i)| {
+            prover_data
+                .subgroup
+                .iter()
+                .enumerate()
+                .map(move |(row, &g)| (k_i * g, Wire { row, column }))
+        })
+        .collect::<HashMap<_, _>>();
+
+    let violation = |row: usize, constraint| ConstraintViolation {
+        gate: common_data.gates[origins.row_gates[row]].0.id(),
+        row,
+        constraint,
+        scope: origins.row_scope(row).map(String::from),
+    };
+
+    let mut violations = (0..common_data.degree())
+        .into_par_iter()
+        .flat_map_iter(|row| {
+            let gate = &common_data.gates[origins.row_gates[row]].0;
+            let local_wires = witness
+                .wire_values
+                .iter()
+                .map(|column| column[row])
+                .collect::<Vec<_>>();
+            let vars = EvaluationVarsBaseBatch::new(
+                1,
+                &row_constants[row],
+                &local_wires,
+                &public_inputs_hash,
+            );
+            // Some gates, e.g. `ArithmeticGate`, only implement the batched evaluation.
+            let constraints = gate.eval_unfiltered_base_batch(vars);
+            let gate_violations = constraints
+                .into_iter()
+                .enumerate()
+                .filter(|(_, c)| c.is_nonzero())
+                .map(|(i, _)| ViolatedConstraint::Gate(i));
+
+            let copy_violations = (0..config.num_routed_wires).filter_map(|column| {
+                let sigma = wires_by_sigma[&prover_data.sigmas[row][column]];
+                (local_wires[column] != witness.wire_values[sigma.column][sigma.row])
+                    .then_some(ViolatedConstraint::Copy { column, sigma })
+            });
+
+            gate_violations
+                .chain(copy_violations)
+                .map(|constraint| violation(row, constraint))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    violations.extend(
+        lookup_violations(prover_data, common_data, &witness, &row_constants)
+            .into_iter()
+            .map(|(row, constraint)| violation(row, constraint)),
+    );
+    violations.sort_by_key(|v| v.row);
+    Ok(violations)
+}
+
+/// The violations of the lookup argument: the lookups which aren't entries of their table, and the
+/// entries whose multiplicities don't match the number of their lookups.
+fn lookup_violations<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    witness: &MatrixWitness<F>,
+    row_constants: &[Vec<F>],
+) -> Vec<(usize, ViolatedConstraint)> {
+    let (lookup_gate_index, table_gate_index) = match common_data.lookup_gate_indices() {
+        (Some(lookup_gate_index), Some(table_gate_index)) => (lookup_gate_index, table_gate_index),
+        _ => return Vec::new(),
+    };
+    let num_slots = common_data.num_lookup_slots;
+    let table_gate = LookupTableGate { num_slots };
+    let row_gates = &prover_data.target_origins.row_gates;
+    let rows_of =
+        |gate_index| (0..common_data.degree()).filter(move |&row| row_gates[row] == gate_index);
+    let wire = |row: usize, column: usize| witness.wire_values[column][row];
+
+    // The entries of the tables, keyed by table index, input and output, with the first slot
+    // holding each of them and the sum of their multiplicities minus their lookups so far.
+    let mut entries = HashMap::<(F, F, F), ((usize, usize), F)>::new();
+    for row in rows_of(table_gate_index) {
+        let table_index = row_constants[row][0];
+        for slot in 0..num_slots {
+            let key = (
+                table_index,
+                wire(row, LookupTableGate::wire_input(slot)),
+                wire(row, LookupTableGate::wire_output(slot)),
+            );
+            let (_, multiplicity) = entries.entry(key).or_insert(((row, slot), F::ZERO));
+            *multiplicity += wire(row, table_gate.wire_multiplicity(slot));
+        }
+    }
+
+    let mut violations = Vec::new();
+    for row in rows_of(lookup_gate_index) {
+        let table_index = row_constants[row][0];
+        for slot in 0..num_slots {
+            let key = (
+                table_index,
+                wire(row, LookupGate::wire_input(slot)),
+                wire(row, LookupGate::wire_output(slot)),
+            );
+            match entries.get_mut(&key) {
+                Some((_, multiplicity)) => *multiplicity -= F::ONE,
+                None => violations.push((row, ViolatedConstraint::Lookup { slot })),
+            }
+        }
+    }
+    let mut unbalanced_entries = entries
+        .into_values()
+        .filter(|(_, multiplicity)| multiplicity.is_nonzero())
+        .map(|(position, _)| position)
+        .collect::<Vec<_>>();
+    unbalanced_entries.sort_unstable();
+    violations.extend(
+        unbalanced_entries
+            .into_iter()
+            .map(|(row, slot)| (row, ViolatedConstraint::Multiplicity { slot })),
+    );
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use anyhow::Result;
+    use log::Level;
+
+    use crate::field::types::Field;
+    use crate::gates::lookup::LookupGate;
+    use crate::gates::lookup_table::LookupTableGate;
+    use crate::gates::public_input::PublicInputGate;
+    use crate::iop::target::Target;
+    use crate::iop::wire::Wire;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::mock_prover::{ConstraintViolation, ViolatedConstraint};
+
+    #[test]
+    fn check_witness() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.square(x);
+        builder.register_public_input(y);
+        // Nothing generates the wires of this gate, and zeros don't match the public inputs hash.
+        builder.push_context(Level::Debug, "stray gate");
+        let row = builder.add_gate(PublicInputGate, vec![]);
+        builder.pop_context();
+        let data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, F::TWO);
+        for wire in PublicInputGate::wires_public_inputs_hash() {
+            inputs.set_target(Target::wire(row, wire), F::ZERO);
+        }
+        let violations = data.check_witness(inputs)?;
+
+        let expected = (0..4)
+            .map(|i| ConstraintViolation {
+                gate: "PublicInputGate".into(),
+                row,
+                constraint: ViolatedConstraint::Gate(i),
+                scope: Some("root > stray gate".into()),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(violations, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn check_witness_copies() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let xy = builder.mul(x, y);
+        builder.register_public_input(xy);
+        let mut data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, F::TWO);
+        inputs.set_target(y, F::from_canonical_u64(3));
+        assert_eq!(data.check_witness(inputs.clone())?, vec![]);
+
+        // Copy the first multiplicand to the wire of the second one, as if they were the same
+        // target.
+        let row_gates = &data.prover_only.target_origins.row_gates;
+        let row = (0..data.common.degree())
+            .find(|&row| {
+                data.common.gates[row_gates[row]]
+                    .0
+                    .id()
+                    .starts_with("ArithmeticGate")
+            })
+            .unwrap();
+        let sigmas = &mut data.prover_only.sigmas[row];
+        sigmas[0] = sigmas[1];
+        let violations = data.check_witness(inputs)?;
+        let constraints = violations
+            .iter()
+            .map(|v| (v.row, v.constraint))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            constraints,
+            [(
+                row,
+                ViolatedConstraint::Copy {
+                    column: 0,
+                    sigma: Wire { row, column: 1 },
+                }
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn check_witness_lookups() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let table = builder.add_lookup_table(Arc::new(vec![(0, 1), (1, 0), (2, 3)]));
+        let x = builder.add_virtual_target();
+        let y = builder.add_lookup(table, x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, F::TWO);
+        assert_eq!(data.check_witness(inputs.clone())?, vec![]);
+
+        // The generator leaves the output alone, so that the pair `(2, 5)` isn't in the table, and
+        // the entry `(2, 3)` is counted for a lookup which doesn't use it.
+        inputs.set_target(y, F::from_canonical_u64(5));
+        let violations = data.check_witness(inputs)?;
+        let constraints = violations
+            .iter()
+            .map(|v| (v.gate.as_str(), v.constraint))
+            .collect::<Vec<_>>();
+        let lookup_gate = format!("{:?}", LookupGate::new_from_config(&config));
+        let table_gate = format!("{:?}", LookupTableGate::new_from_config(&config));
+        assert_eq!(
+            constraints,
+            [
+                (lookup_gate.as_str(), ViolatedConstraint::Lookup { slot: 0 }),
+                (
+                    table_gate.as_str(),
+                    ViolatedConstraint::Multiplicity { slot: 2 }
+                ),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,12 +1,20 @@
+pub mod batch_prover;
 pub mod circuit_builder;
 pub mod circuit_data;
 pub mod config;
 pub(crate) mod copy_constraint;
+pub mod cross_check;
+pub mod export;
 mod get_challenges;
+pub mod mock_prover;
 pub(crate) mod permutation_argument;
 pub mod plonk_common;
 pub mod proof;
 pub mod prover;
+pub mod prover_rounds;
+pub mod prover_trace;
+pub mod recorded_witness;
+pub mod security;
 mod validate_shape;
 pub(crate) mod vanishing_poly;
 pub mod vars;
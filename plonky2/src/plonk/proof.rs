@@ -163,7 +163,11 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             plonk_zs_partial_products_cap,
             quotient_polys_cap,
             openings,
-            opening_proof: opening_proof.decompress::<C>(challenges, fri_inferred_elements, params),
+            opening_proof: opening_proof.decompress::<C>(
+                &challenges.fri_challenges,
+                fri_inferred_elements,
+                params,
+            ),
         }
     }
 }
@@ -261,6 +265,11 @@ pub(crate) struct ProofChallenges<F: RichField + Extendable<D>, const D: usize>
     /// Random values used in Plonk's permutation argument.
     pub plonk_gammas: Vec<F>,
 
+    /// Random values used in the lookup argument: for each repetition, the `alpha` combining the
+    /// looked up values, then the `delta` they are subtracted from. Empty if the circuit has no
+    /// lookups.
+    pub plonk_deltas: Vec<F>,
+
     /// Random values used to combine PLONK constraints.
     pub plonk_alphas: Vec<F>,
 
@@ -273,15 +282,14 @@ pub(crate) struct ProofChallenges<F: RichField + Extendable<D>, const D: usize>
 pub(crate) struct ProofChallengesTarget<const D: usize> {
     pub plonk_betas: Vec<Target>,
     pub plonk_gammas: Vec<Target>,
+    pub plonk_deltas: Vec<Target>,
     pub plonk_alphas: Vec<Target>,
     pub plonk_zeta: ExtensionTarget<D>,
     pub fri_challenges: FriChallengesTarget<D>,
 }
 
 /// Coset elements that can be inferred in the FRI reduction steps.
-pub(crate) struct FriInferredElements<F: RichField + Extendable<D>, const D: usize>(
-    pub Vec<F::Extension>,
-);
+pub struct FriInferredElements<F: RichField + Extendable<D>, const D: usize>(pub Vec<F::Extension>);
 
 #[derive(Clone, Debug)]
 pub struct ProofWithPublicInputsTarget<const D: usize> {
@@ -298,6 +306,10 @@ pub struct OpeningSet<F: RichField + Extendable<D>, const D: usize> {
     pub plonk_zs: Vec<F::Extension>,
     pub plonk_zs_next: Vec<F::Extension>,
     pub partial_products: Vec<F::Extension>,
+    /// The running sums and helpers of the lookup argument. Empty if the circuit has no lookups.
+    pub lookup_polys: Vec<F::Extension>,
+    /// The running sums of the lookup argument, at `g * zeta`.
+    pub lookup_zs_next: Vec<F::Extension>,
     pub quotient_polys: Vec<F::Extension>,
 }
 
@@ -319,16 +331,18 @@ impl<F: RichField + Extendable<D>, const D: usize> OpeningSet<F, D> {
         };
         let constants_sigmas_eval = eval_commitment(zeta, constants_sigmas_commitment);
         let zs_partial_products_eval = eval_commitment(zeta, zs_partial_products_commitment);
+        let zs_partial_products_next_eval =
+            eval_commitment(g * zeta, zs_partial_products_commitment);
         Self {
             constants: constants_sigmas_eval[common_data.constants_range()].to_vec(),
             plonk_sigmas: constants_sigmas_eval[common_data.sigmas_range()].to_vec(),
             wires: eval_commitment(zeta, wires_commitment),
             plonk_zs: zs_partial_products_eval[common_data.zs_range()].to_vec(),
-            plonk_zs_next: eval_commitment(g * zeta, zs_partial_products_commitment)
-                [common_data.zs_range()]
-            .to_vec(),
+            plonk_zs_next: zs_partial_products_next_eval[common_data.zs_range()].to_vec(),
             partial_products: zs_partial_products_eval[common_data.partial_products_range()]
                 .to_vec(),
+            lookup_polys: zs_partial_products_eval[common_data.lookup_polys_range()].to_vec(),
+            lookup_zs_next: zs_partial_products_next_eval[common_data.lookup_zs_range()].to_vec(),
             quotient_polys: eval_commitment(zeta, quotient_polys_commitment),
         }
     }
@@ -341,12 +355,17 @@ impl<F: RichField + Extendable<D>, const D: usize> OpeningSet<F, D> {
                 self.wires.as_slice(),
                 self.plonk_zs.as_slice(),
                 self.partial_products.as_slice(),
+                self.lookup_polys.as_slice(),
                 self.quotient_polys.as_slice(),
             ]
             .concat(),
         };
         let zeta_next_batch = FriOpeningBatch {
-            values: self.plonk_zs_next.clone(),
+            values: [
+                self.plonk_zs_next.as_slice(),
+                self.lookup_zs_next.as_slice(),
+            ]
+            .concat(),
         };
         FriOpenings {
             batches: vec![zeta_batch, zeta_next_batch],
@@ -363,6 +382,8 @@ pub struct OpeningSetTarget<const D: usize> {
     pub plonk_zs: Vec<ExtensionTarget<D>>,
     pub plonk_zs_next: Vec<ExtensionTarget<D>>,
     pub partial_products: Vec<ExtensionTarget<D>>,
+    pub lookup_polys: Vec<ExtensionTarget<D>>,
+    pub lookup_zs_next: Vec<ExtensionTarget<D>>,
     pub quotient_polys: Vec<ExtensionTarget<D>>,
 }
 
@@ -375,12 +396,17 @@ impl<const D: usize> OpeningSetTarget<D> {
                 self.wires.as_slice(),
                 self.plonk_zs.as_slice(),
                 self.partial_products.as_slice(),
+                self.lookup_polys.as_slice(),
                 self.quotient_polys.as_slice(),
             ]
             .concat(),
         };
         let zeta_next_batch = FriOpeningBatchTarget {
-            values: self.plonk_zs_next.clone(),
+            values: [
+                self.plonk_zs_next.as_slice(),
+                self.lookup_zs_next.as_slice(),
+            ]
+            .concat(),
         };
         FriOpeningsTarget {
             batches: vec![zeta_batch, zeta_next_batch],
@@ -2,326 +2,278 @@ use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::mem::swap;
 
-#[cfg(feature = "cuda")]
-use std::ffi::c_void;
-
-use std::fs::File;
-use std::io::Write;
-use std::mem::transmute;
-use std::process::exit;
-use std::time;
-
 use anyhow::{ensure, Result};
 use maybe_rayon::*;
 
+use crate::backend::{
+    lde_values_fn, CommitmentLayout, ProverBackend, ProverLayout, QuotientPolysArgs,
+};
 use crate::field::extension::Extendable;
+use crate::field::fft::{fft_root_table, FftRootTable};
 use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::field::types::Field;
 use crate::field::zero_poly_coset::ZeroPolyOnCoset;
-use crate::fri::oracle::PolynomialBatch;
-use crate::hash::hash_types::RichField;
+use crate::fri::oracle::{PolynomialBatch, SaltSource};
+use crate::gates::lookup::{LookupGate, LOOKUP_DEGREE};
+use crate::gates::lookup_table::LookupTableGate;
+use crate::hash::hash_types::{HashOut, RichField};
 use crate::iop::challenger::Challenger;
 use crate::iop::generator::generate_partial_witness;
-use crate::iop::witness::{MatrixWitness, PartialWitness, Witness};
+use crate::iop::witness::{MatrixWitness, PartialWitness, PartitionWitness, Witness};
 use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
-use crate::plonk::plonk_common::PlonkOracle;
+use crate::plonk::plonk_common::{reduce_with_powers_multi, PlonkOracle};
 use crate::plonk::proof::{OpeningSet, Proof, ProofWithPublicInputs};
-use crate::plonk::vanishing_poly::eval_vanishing_poly_base_batch;
+use crate::plonk::prover_rounds::Prover;
+use crate::plonk::prover_trace::{
+    ext_values, record_cap, record_challenger, record_stage, ProverTrace, TraceStage,
+};
+use crate::plonk::vanishing_poly::{
+    eval_vanishing_poly_base_batch, evaluate_gate_constraints_base_batch_for_rows,
+};
 use crate::plonk::vars::EvaluationVarsBaseBatch;
 use crate::timed;
 use crate::util::partial_products::{partial_products_and_z_gx, quotient_chunk_products};
+use crate::util::strided_view::PackedStridedView;
 use crate::util::timing::TimingTree;
-use crate::util::{ceil_div_usize, log2_ceil, transpose};
-use plonky2_util::log2_strict;
-
-#[cfg(feature = "cuda")]
-use crate::fri::oracle::CudaInnerContext;
-#[cfg(feature = "cuda")]
-use plonky2_cuda;
-#[cfg(feature = "cuda")]
-use plonky2_cuda::DataSlice;
-#[cfg(feature = "cuda")]
-use rustacuda::memory::DeviceSlice;
-#[cfg(feature = "cuda")]
-use rustacuda::prelude::CopyDestination;
-#[cfg(feature = "cuda")]
-use rustacuda::memory::AsyncCopyDestination;
-
-pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F=F>, const D: usize>(
+use crate::util::{ceil_div_usize, log2_ceil, log2_strict, transpose};
+
+pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
     prover_data: &ProverOnlyCircuitData<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
     inputs: PartialWitness<F>,
     timing: &mut TimingTree,
 ) -> Result<ProofWithPublicInputs<F, C, D>> {
-    let config = &common_data.config;
-    let num_challenges = config.num_challenges;
-    let quotient_degree = common_data.quotient_degree();
-    let degree = common_data.degree();
+    prove_with_trace(prover_data, common_data, inputs, timing, &mut None)
+}
 
+/// Like `prove`, but reports the intermediate values of the prover to `trace`.
+pub fn prove_with_trace<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
     let partition_witness = timed!(
         timing,
         &format!("run {} generators", prover_data.generators.len()),
         generate_partial_witness(inputs, prover_data, common_data)
-    );
-
-    let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
-    let public_inputs_hash = C::InnerHasher::hash_public_inputs(&public_inputs);
-
-    let witness = timed!(
-        timing,
-        "compute full witness",
-        partition_witness.full_witness()
-    );
-
-    let wires_values: Vec<PolynomialValues<F>> = timed!(
-        timing,
-        "compute wire polynomials",
-        witness
-            .wire_values
-            .par_iter()
-            .map(|column| PolynomialValues::new(column.clone()))
-            .collect()
-    );
-
-    let wires_commitment = timed!(
-        timing,
-        "compute wires commitment",
-        PolynomialBatch::from_values(
-            wires_values,
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::WIRES.blinding,
-            config.fri_config.cap_height,
-            timing,
-            // &prover_data.fft_root_table_deg,
-            prover_data.fft_root_table.as_ref(),
-        )
-    );
-    let mut challenger = Challenger::<F, C::Hasher>::new();
-
-    // Observe the instance.
-    challenger.observe_hash::<C::Hasher>(prover_data.circuit_digest);
-    challenger.observe_hash::<C::InnerHasher>(public_inputs_hash);
-
-    challenger.observe_cap(&wires_commitment.merkle_tree.cap);
-    let betas = challenger.get_n_challenges(num_challenges);
-    let gammas = challenger.get_n_challenges(num_challenges);
-
-    assert!(
-        common_data.quotient_degree_factor < common_data.config.num_routed_wires,
-        "When the number of routed wires is smaller that the degree, we should change the logic to avoid computing partial products."
-    );
-    let mut partial_products_and_zs = timed!(
-        timing,
-        "compute partial products",
-        all_wires_permutation_partial_products(&witness, &betas, &gammas, prover_data, common_data)
-    );
-
-    // Z is expected at the front of our batch; see `zs_range` and `partial_products_range`.
-    let plonk_z_vecs = partial_products_and_zs
-        .iter_mut()
-        .map(|partial_products_and_z| partial_products_and_z.pop().unwrap())
-        .collect();
-    let zs_partial_products = [plonk_z_vecs, partial_products_and_zs.concat()].concat();
+    )?;
+    prove_from_witness_with_trace(prover_data, common_data, partition_witness, timing, trace)
+}
 
-    let partial_products_and_zs_commitment = timed!(
+/// Like `prove`, but starts from an already generated witness, e.g. a replayed
+/// [`RecordedWitness`](crate::plonk::recorded_witness::RecordedWitness), instead of running the
+/// generators.
+pub fn prove_from_witness<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    partition_witness: PartitionWitness<F>,
+    timing: &mut TimingTree,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    prove_from_witness_with_trace(
+        prover_data,
+        common_data,
+        partition_witness,
         timing,
-        "commit to partial products and Z's",
-        PolynomialBatch::from_values(
-            zs_partial_products,
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::ZS_PARTIAL_PRODUCTS.blinding,
-            config.fri_config.cap_height,
-            timing,
-            prover_data.fft_root_table.as_ref(),
-        )
-    );
+        &mut None,
+    )
+}
 
-    challenger.observe_cap(&partial_products_and_zs_commitment.merkle_tree.cap);
+/// Like `prove_from_witness`, but reports the intermediate values of the prover to `trace`.
+pub fn prove_from_witness_with_trace<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    partition_witness: PartitionWitness<F>,
+    timing: &mut TimingTree,
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    Prover::new(prover_data, common_data, partition_witness, timing, trace).finish(timing, trace)
+}
 
-    let alphas = challenger.get_n_challenges(num_challenges);
+/// Like `prove`, but runs the commitments and the quotient computation on `backend`.
+pub fn my_prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+    backend: &mut dyn ProverBackend<F, C, D>,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    my_prove_with_trace(prover_data, common_data, inputs, timing, backend, &mut None)
+}
 
-    let quotient_polys = timed!(
-        timing,
-        "compute quotient polys",
-        compute_quotient_polys(
-            common_data,
-            prover_data,
-            &public_inputs_hash,
-            &wires_commitment,
-            &partial_products_and_zs_commitment,
-            &betas,
-            &gammas,
-            &alphas,
-            timing,
-        )
-    );
+/// Like `my_prove`, but reports the intermediate values of the prover to `trace`. The stages are
+/// the same as `prove_with_trace`'s, except for `TraceStage::QuotientValues`, which the backend
+/// doesn't expose.
+pub fn my_prove_with_trace<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+    backend: &mut dyn ProverBackend<F, C, D>,
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    // Fail before running the generators if the backend can't prove the circuit.
+    backend_layout(common_data, backend)?;
 
-    // Compute the quotient polynomials, aka `t` in the Plonk paper.
-    let all_quotient_poly_chunks :Vec<PolynomialCoeffs<F>> = timed!(
+    let partition_witness = timed!(
         timing,
-        "split up quotient polys",
-        quotient_polys
-            .into_par_iter()
-            .flat_map(|mut quotient_poly| {
-                quotient_poly.trim_to_len(quotient_degree).expect(
-                    "Quotient has failed, the vanishing polynomial is not divisible by Z_H",
-                );
-                // Split quotient into degree-n chunks.
-                quotient_poly.chunks(degree)
-            })
-            .collect()
-    );
-
-    let quotient_polys_commitment = timed!(
+        &format!("run {} generators", prover_data.generators.len()),
+        generate_partial_witness(inputs, prover_data, common_data)
+    )?;
+    my_prove_from_witness_with_trace(
+        prover_data,
+        common_data,
+        partition_witness,
         timing,
-        "commit to quotient polys",
-        PolynomialBatch::from_coeffs(
-            all_quotient_poly_chunks,
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::QUOTIENT.blinding,
-            config.fri_config.cap_height,
-            timing,
-            prover_data.fft_root_table.as_ref(),
-        )
-    );
-
-    challenger.observe_cap(&quotient_polys_commitment.merkle_tree.cap);
-
-    let zeta = challenger.get_extension_challenge::<D>();
-    // To avoid leaking witness data, we want to ensure that our opening locations, `zeta` and
-    // `g * zeta`, are not in our subgroup `H`. It suffices to check `zeta` only, since
-    // `(g * zeta)^n = zeta^n`, where `n` is the order of `g`.
-    let g = F::Extension::primitive_root_of_unity(common_data.degree_bits());
-    ensure!(
-        zeta.exp_power_of_2(common_data.degree_bits()) != F::Extension::ONE,
-        "Opening point is in the subgroup."
-    );
+        backend,
+        trace,
+    )
+}
 
-    let openings = timed!(
+/// Like `my_prove`, but starts from an already generated witness instead of running the
+/// generators.
+pub fn my_prove_from_witness<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    partition_witness: PartitionWitness<F>,
+    timing: &mut TimingTree,
+    backend: &mut dyn ProverBackend<F, C, D>,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    my_prove_from_witness_with_trace(
+        prover_data,
+        common_data,
+        partition_witness,
         timing,
-        "construct the opening set",
-        OpeningSet::new(
-            zeta,
-            g,
-            &prover_data.constants_sigmas_commitment,
-            &wires_commitment,
-            &partial_products_and_zs_commitment,
-            &quotient_polys_commitment,
-            common_data,
-        )
-    );
-    challenger.observe_openings(&openings.to_fri_openings());
+        backend,
+        &mut None,
+    )
+}
 
-    let opening_proof = timed!(
+/// Like `my_prove_from_witness`, but reports the intermediate values of the prover to `trace`.
+pub fn my_prove_from_witness_with_trace<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    partition_witness: PartitionWitness<F>,
+    timing: &mut TimingTree,
+    backend: &mut dyn ProverBackend<F, C, D>,
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    let salt_source = backend.salt_source();
+    my_prove_from_witness_with_salt_source(
+        prover_data,
+        common_data,
+        partition_witness,
         timing,
-        "compute opening proofs",
-        PolynomialBatch::prove_openings(
-            &common_data.get_fri_instance(zeta),
-            &[
-                &prover_data.constants_sigmas_commitment,
-                &wires_commitment,
-                &partial_products_and_zs_commitment,
-                &quotient_polys_commitment,
-            ],
-            &mut challenger,
-            &common_data.fri_params,
-            timing,
-            &mut None,
-        )
-    );
-
-    let proof = Proof {
-        wires_cap: wires_commitment.merkle_tree.cap,
-        plonk_zs_partial_products_cap: partial_products_and_zs_commitment.merkle_tree.cap,
-        quotient_polys_cap: quotient_polys_commitment.merkle_tree.cap,
-        openings,
-        opening_proof,
-    };
-    Ok(ProofWithPublicInputs {
-        proof,
-        public_inputs,
-    })
+        backend,
+        salt_source,
+        trace,
+    )
 }
 
-#[cfg(feature = "cuda")]
-pub fn my_prove<F: RichField + Extendable<D>, C: GenericConfig<D, F=F>, const D: usize>(
+/// Like `my_prove_from_witness_with_trace`, but salts the hiding commitments from `salt_source`
+/// instead of the backend's.
+pub(crate) fn my_prove_from_witness_with_salt_source<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
     prover_data: &ProverOnlyCircuitData<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
-    inputs: PartialWitness<F>,
+    partition_witness: PartitionWitness<F>,
     timing: &mut TimingTree,
-    ctx: &mut crate::fri::oracle::CudaInvContext<F, C, D>,
+    backend: &mut dyn ProverBackend<F, C, D>,
+    salt_source: SaltSource<F>,
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
 ) -> Result<ProofWithPublicInputs<F, C, D>> {
     let config = &common_data.config;
     let num_challenges = config.num_challenges;
-    let quotient_degree = common_data.quotient_degree();
     let degree = common_data.degree();
+    let layout = backend_layout(common_data, backend)?;
+    let salts = |oracle: PlonkOracle, layout: &CommitmentLayout| {
+        salt_source.salts::<C::Hasher>(oracle.index, layout.salt_size, layout.lde_size())
+    };
 
-    let partition_witness = timed!(
-        timing,
-        &format!("run {} generators", prover_data.generators.len()),
-        generate_partial_witness(inputs, prover_data, common_data)
-    );
+    let (public_inputs_hash, public_inputs) = timed!(timing, "get public_inputs_hash", {
+        let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
+        let public_inputs_hash = C::InnerHasher::hash_public_inputs(&public_inputs);
+        (public_inputs_hash, public_inputs)
+    });
+    record_stage(trace, TraceStage::PublicInputsHash, 4, || {
+        public_inputs_hash.elements.to_vec()
+    });
 
-    let (public_inputs_hash, public_inputs) = timed!(
-        timing,
-        "get public_inputs_hash",
-        {
-            let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
-            let public_inputs_hash = C::InnerHasher::hash_public_inputs(&public_inputs);
-            (public_inputs_hash, public_inputs)
-        });
-
-    let mut witness = timed!(
+    let witness = timed!(
         timing,
         "compute full witness",
-        partition_witness.my_full_witness()
+        partition_witness.my_full_witness_in(backend.host_buffers().map(|b| &b.values))
     );
 
     let wires_values = &witness.my_wire_values;
-    // let wires_values: Vec<PolynomialValues<F>> = timed!(
-    //     timing,
-    //     "compute wire polynomials",
-    //     witness
-    //         .wire_values
-    //         .par_iter()
-    //         .map(|column| PolynomialValues::new(column.clone()))
-    //         .collect()
-    // );
     assert!(wires_values.len() % degree == 0);
 
     let wires_commitment = timed!(
         timing,
         "compute wires commitment",
-        PolynomialBatch::from_values_with_gpu(
-        // PolynomialBatch::from_values(
+        PolynomialBatch::from_values_with_backend(
             wires_values,
-            common_data.config.num_wires,
-            degree,
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::WIRES.blinding,
-            config.fri_config.cap_height,
+            &layout.wires,
+            &salts(PlonkOracle::WIRES, &layout.wires),
             timing,
-            prover_data.fft_root_table.as_ref(),
-            &prover_data.fft_root_table_deg,
-            ctx,
+            backend,
         )
+    )?;
+    record_stage(
+        trace,
+        TraceStage::WiresLeaves,
+        layout.wires.leaf_len(),
+        || backend.read_values(layout.wires.offset, layout.wires.ext_values_len()),
+    );
+    record_cap(
+        trace,
+        TraceStage::WiresCap,
+        &wires_commitment.merkle_tree.cap,
     );
     let mut challenger = Challenger::<F, C::Hasher>::new();
 
-    let (betas, gammas) = timed!(
-        timing,
-        "observe_hash for betas and gammas",
-        {
-            // Observe the instance.
-            challenger.observe_hash::<C::Hasher>(prover_data.circuit_digest);
-            challenger.observe_hash::<C::InnerHasher>(public_inputs_hash);
-
-            challenger.observe_cap(&wires_commitment.merkle_tree.cap);
-            let betas = challenger.get_n_challenges(num_challenges);
-            let gammas = challenger.get_n_challenges(num_challenges);
-            (betas, gammas)
-        });
+    let (betas, gammas) = timed!(timing, "observe_hash for betas and gammas", {
+        // Observe the instance.
+        challenger.observe_hash::<C::Hasher>(prover_data.circuit_digest);
+        challenger.observe_hash::<C::InnerHasher>(public_inputs_hash);
+
+        challenger.observe_cap(&wires_commitment.merkle_tree.cap);
+        record_challenger(trace, TraceStage::WiresChallenger, &challenger);
+        let betas = challenger.get_n_challenges(num_challenges);
+        let gammas = challenger.get_n_challenges(num_challenges);
+        (betas, gammas)
+    });
+    record_stage(trace, TraceStage::Betas, 1, || betas.clone());
+    record_stage(trace, TraceStage::Gammas, 1, || gammas.clone());
 
     assert!(
         common_data.quotient_degree_factor < common_data.config.num_routed_wires,
@@ -333,329 +285,162 @@ pub fn my_prove<F: RichField + Extendable<D>, C: GenericConfig<D, F=F>, const D:
         all_wires_permutation_partial_products(&witness, &betas, &gammas, prover_data, common_data)
     );
 
-    // let zs_partial_products = timed!(
-    //     timing,
-    //     "get zs_partial_products",
-    //     {
-    //         // Z is expected at the front of our batch; see `zs_range` and `partial_products_range`.
-    //         let plonk_z_vecs = partial_products_and_zs
-    //             .iter_mut()
-    //             .map(|partial_products_and_z| partial_products_and_z.pop().unwrap())
-    //             .collect();
-    //         let zs_partial_products =
-    //             [plonk_z_vecs, partial_products_and_zs.concat()].concat().into_iter().flat_map(|v| v.values).collect::<Vec<_>>();
-    //         zs_partial_products
-    //     });
-    // let zs_partial_products = &zs_partial_products;
-    //
-    // unsafe {
-    //     let v = zs_partial_products;
-    //     let mut file = File::create("zs_partial_products-new.bin").unwrap();
-    //     file.write_all(std::slice::from_raw_parts(v.as_ptr() as *const u8, v.len()*8));
-    // }
-    // exit(0);
-
     // Z is expected at the front of our batch; see `zs_range` and `partial_products_range`.
     let plonk_z_vecs = partial_products_and_zs
         .iter_mut()
         .map(|partial_products_and_z| partial_products_and_z.pop().unwrap())
         .collect();
     let zs_partial_products = [plonk_z_vecs, partial_products_and_zs.concat()].concat();
-    println!("zs_partial_products len:{}, itemLen:{}", zs_partial_products.len(), zs_partial_products[0].values.len());
 
-
-    // unsafe {
-    //     let v = zs_partial_products.iter().flat_map(|p|p.values.to_vec()).collect::<Vec<_>>();
-    //     let mut file = File::create("zs_partial_products-old.bin").unwrap();
-    //     file.write_all(std::slice::from_raw_parts(v.as_ptr() as *const u8, v.len()*8));
-    // }
-
-    let zs_partial_products = &zs_partial_products.iter().flat_map(|p|p.values.to_vec()).collect::<Vec<_>>();
+    let mut zs_partial_products_values = backend.host_values(zs_partial_products.len() * degree);
+    for (chunk, p) in zs_partial_products_values
+        .chunks_exact_mut(degree)
+        .zip(&zs_partial_products)
+    {
+        chunk.copy_from_slice(&p.values);
+    }
+    let zs_partial_products = &zs_partial_products_values;
+    record_stage(trace, TraceStage::ZsPartialProducts, degree, || {
+        zs_partial_products.to_vec()
+    });
     let partial_products_and_zs_commitment = timed!(
         timing,
         "commit to partial products and Z's",
-        // PolynomialBatch::from_values(
-        PolynomialBatch::from_values_with_gpu(
+        PolynomialBatch::from_values_with_backend(
             zs_partial_products,
-            zs_partial_products.len()/degree,
-            degree,
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::ZS_PARTIAL_PRODUCTS.blinding,
-            config.fri_config.cap_height,
+            &layout.zs_partial_products,
+            &salts(
+                PlonkOracle::ZS_PARTIAL_PRODUCTS,
+                &layout.zs_partial_products
+            ),
             timing,
-            prover_data.fft_root_table.as_ref(),
-            &prover_data.fft_root_table_deg,
-            ctx,
+            backend,
         )
+    )?;
+    record_stage(
+        trace,
+        TraceStage::ZsPartialProductsLeaves,
+        layout.zs_partial_products.leaf_len(),
+        || {
+            backend.read_values(
+                layout.zs_partial_products.offset,
+                layout.zs_partial_products.ext_values_len(),
+            )
+        },
+    );
+    record_cap(
+        trace,
+        TraceStage::ZsPartialProductsCap,
+        &partial_products_and_zs_commitment.merkle_tree.cap,
     );
 
-
-    // let partial_products_and_zs_commitment = timed!(
-    //     timing,
-    //     "commit to partial products and Z's",
-    //     PolynomialBatch::from_values(
-    //     // PolynomialBatch::from_values_with_gpu(
-    //         zs_partial_products,
-    //         // zs_partial_products.len()/degree,
-    //         // degree,
-    //         config.fri_config.rate_bits,
-    //         config.zero_knowledge && PlonkOracle::ZS_PARTIAL_PRODUCTS.blinding,
-    //         config.fri_config.cap_height,
-    //         timing,
-    //         prover_data.fft_root_table.as_ref(),
-    //         // &prover_data.fft_root_table_deg,
-    //         // ctx,
-    //     )
-    // );
-
-    let alphas = timed!(
-        timing,
-        "observe_cap for alphas",
-        {
-            challenger.observe_cap(&partial_products_and_zs_commitment.merkle_tree.cap);
-
-            let alphas = challenger.get_n_challenges(num_challenges);
-            alphas
-        });
-
-    // let quotient_polys = timed!(
-    //     timing,
-    //     "compute quotient polys",
-    //     compute_quotient_polys(
-    //         common_data,
-    //         prover_data,
-    //         &public_inputs_hash,
-    //         &wires_commitment,
-    //         &partial_products_and_zs_commitment,
-    //         &betas,
-    //         &gammas,
-    //         &alphas,
-    //         timing,
-    //     )
-    // );
-
+    let alphas = timed!(timing, "observe_cap for alphas", {
+        challenger.observe_cap(&partial_products_and_zs_commitment.merkle_tree.cap);
+        record_challenger(trace, TraceStage::ZsPartialProductsChallenger, &challenger);
+
+        challenger.get_n_challenges(num_challenges)
+    });
+    record_stage(trace, TraceStage::Alphas, 1, || alphas.clone());
+
+    // Gates the backend doesn't support are evaluated here, and merged by the backend into its
+    // quotient values.
+    let gate_support = backend.gate_registry().support(common_data);
+    let fallback_quotient_values = if gate_support.is_fully_accelerated() {
+        None
+    } else {
+        Some(timed!(
+            timing,
+            &format!("evaluate {} unsupported gates", gate_support.fallback.len()),
+            {
+                let wires_leaves =
+                    backend.read_values(layout.wires.offset, layout.wires.ext_values_len());
+                let get_wires_values = lde_values_fn(
+                    &wires_leaves,
+                    layout.wires.leaf_len(),
+                    layout.wires.poly_num,
+                    log2_strict(layout.wires.lde_size()),
+                );
+                compute_fallback_quotient_values::<F, C, D>(
+                    common_data,
+                    &gate_support.fallback,
+                    &public_inputs_hash,
+                    &|i, step| {
+                        prover_data
+                            .constants_sigmas_commitment
+                            .get_lde_values(i, step)
+                    },
+                    &get_wires_values,
+                    &alphas,
+                )
+            }
+        ))
+    };
     timed!(
         timing,
         "compute quotient polys",
-        {
-            let poly_num = common_data.config.num_wires;
-            let values_num_per_poly = degree;
-            let lg_n = log2_strict(values_num_per_poly );
-            let values_flatten_len = poly_num*values_num_per_poly;
-
-            let rate_bits = config.fri_config.rate_bits;
-            let blinding = config.zero_knowledge && PlonkOracle::WIRES.blinding;
-            let salt_size = if blinding { 4 } else { 0 };
-
-            let ext_values_flatten_len = (values_flatten_len+salt_size*values_num_per_poly) * (1<<rate_bits);
-            let pad_extvalues_len = ext_values_flatten_len;
-            let values_num_per_extpoly = values_num_per_poly*(1<<rate_bits);
-
-            let (ext_values_device, remained) = ctx.cache_mem_device.split_at_mut(ctx.second_stage_offset);
-            // let (_, ext_values_device) = front_msm.split_at(values_flatten_len);
-            let root_table_device2 = &mut ctx.root_table_device2;
-            let shift_inv_powers_device = &mut ctx.shift_inv_powers_device;
-
-
-            let (partial_products_and_zs_commitment_leaves_device, alphas_device, betas_device, gammas_device,
-                d_outs, d_quotient_polys) = timed!(
-                timing,
-                "copy params",
-                {
-                    let mut useCnt = 0;
-                    // let partial_products_and_zs_commitment_leaves = if partial_products_and_zs_commitment.merkle_tree.my_leaves.is_empty() {
-                    //     partial_products_and_zs_commitment.merkle_tree.leaves.concat()
-                    // } else {
-                    //     partial_products_and_zs_commitment.merkle_tree.my_leaves.to_vec()
-                    // };
-                    // // unsafe
-                    // // {
-                    // //     let mut file = File::create("partial_products_and_zs_commitment_leaves.bin").unwrap();
-                    // //     file.write_all(std::slice::from_raw_parts(partial_products_and_zs_commitment_leaves.as_ptr() as *const u8, partial_products_and_zs_commitment_leaves.len()*8));
-                    // // }
-                    //
-                    // useCnt = partial_products_and_zs_commitment_leaves.len();
-
-                    // let (_, remained) = remained.split_at_mut(ctx.values_flatten2.len());
-
-                    useCnt = zs_partial_products.len() << rate_bits;
-                    let (data, remained) = remained.split_at_mut(useCnt);
-
-                    let partial_products_and_zs_commitment_leaves_device =
-                        DataSlice{ptr: data.as_ptr() as *const c_void, len: useCnt as i32 };
-                    // unsafe {
-                    //     transmute::<&mut DeviceSlice<F>, &mut DeviceSlice<u64>>(data).async_copy_from(
-                    //         transmute::<&Vec<F>, &Vec<u64>>(&partial_products_and_zs_commitment_leaves),
-                    //         &ctx.inner.stream
-                    //     ).unwrap();
-                    // }
-
-                    useCnt = values_num_per_extpoly*2;
-                    let (d_quotient_polys, remained) = remained.split_at_mut(useCnt);
-
-                    useCnt = values_num_per_extpoly*2;
-                    let (d_outs, remained) = remained.split_at_mut(useCnt);
-
-                    useCnt = num_challenges;
-                    let (d_alphas, remained) = remained.split_at_mut(useCnt);
-                    unsafe {
-                        transmute::<&mut DeviceSlice<F>, &mut DeviceSlice<u64>>(d_alphas).async_copy_from(
-                            transmute::<&Vec<F>, &Vec<u64>>(&alphas),
-                            &ctx.inner.stream
-                        ).unwrap();
-                    }
-                    let alphas_device = DataSlice{ptr: d_alphas.as_ptr() as *const c_void, len: alphas.len() as i32 };
-
-                    let (d_betas, remained) = remained.split_at_mut(useCnt);
-                    unsafe {
-                        transmute::<&mut DeviceSlice<F>, &mut DeviceSlice<u64>>(d_betas).async_copy_from(
-                            transmute::<&Vec<F>, &Vec<u64>>(&betas),
-                            &ctx.inner.stream
-                        ).unwrap();
-                    }
-                    let betas_device = DataSlice{ptr: d_betas.as_ptr() as *const c_void, len: betas.len() as i32 };
-
-                    let (d_gammas, remained) = remained.split_at_mut(useCnt);
-                    unsafe {
-                        transmute::<&mut DeviceSlice<F>, &mut DeviceSlice<u64>>(d_gammas).async_copy_from(
-                            transmute::<&Vec<F>, &Vec<u64>>(&gammas),
-                            &ctx.inner.stream
-                        ).unwrap();
-                    }
-                    let gammas_device = DataSlice{ptr: d_gammas.as_ptr() as *const c_void, len: gammas.len() as i32 };
-
-                    ctx.inner.stream.synchronize().unwrap();
-
-                    (partial_products_and_zs_commitment_leaves_device, alphas_device, betas_device, gammas_device, d_outs, d_quotient_polys)
-                }
-            );
+        backend.compute_quotient_polys(&QuotientPolysArgs {
+            wires: layout.wires,
+            zs_partial_products: layout.zs_partial_products,
+            quotient_polys_offset: layout.quotient_polys.offset,
+            public_inputs_hash: &public_inputs_hash,
+            alphas: &alphas,
+            betas: &betas,
+            gammas: &gammas,
+            gates: &gate_support.accelerated,
+            fallback_quotient_values: fallback_quotient_values.as_deref(),
+        })
+    )?;
 
-            let points_device = DataSlice{ptr: ctx.points_device.as_ptr() as *const c_void, len: ctx.points_device.len() as i32 };
-            let z_h_on_coset_evals_device = DataSlice{ptr: ctx.z_h_on_coset_evals_device.as_ptr() as *const c_void, len: ctx.z_h_on_coset_evals_device.len() as i32 };
-            let z_h_on_coset_inverses_device = DataSlice{ptr: ctx.z_h_on_coset_inverses_device.as_ptr() as *const c_void, len: ctx.z_h_on_coset_inverses_device.len() as i32 };
-            let k_is_device = DataSlice{ptr: ctx.k_is_device.as_ptr() as *const c_void, len: ctx.k_is_device.len() as i32 };
-
-            let constants_sigmas_commitment_leaves_device = DataSlice{
-                ptr: ctx.constants_sigmas_commitment_leaves_device.as_ptr() as *const c_void,
-                len: ctx.constants_sigmas_commitment_leaves_device.len() as i32,
-            };
-            let ctx_ptr :*mut CudaInnerContext = &mut ctx.inner;
-            timed!(
-                timing,
-                "compute quotient polys with GPU",
-                unsafe {
-                    plonky2_cuda::compute_quotient_polys(
-                        ext_values_device.as_ptr() as *const u64,
-
-                        poly_num as i32,
-                        values_num_per_poly as i32,
-                        lg_n as i32,
-                        root_table_device2.as_ptr() as *const u64,
-                        shift_inv_powers_device.as_ptr() as *const u64,
-                        rate_bits as i32,
-                        salt_size as i32,
-
-                        &partial_products_and_zs_commitment_leaves_device,
-                        &constants_sigmas_commitment_leaves_device,
-
-                        d_outs.as_mut_ptr() as *mut c_void,
-                        d_quotient_polys.as_mut_ptr() as *mut c_void,
-
-                        &points_device,
-                        &z_h_on_coset_evals_device,
-                        &z_h_on_coset_inverses_device,
-                        &k_is_device,
-
-                        &alphas_device,
-                        &betas_device,
-                        &gammas_device,
-
-                        ctx_ptr as *mut core::ffi::c_void,
-                    )
-                }
-            );
-            // let mut quotient_polys_flatten :Vec<F> = vec![F::ZERO; values_num_per_extpoly*2];
-            // timed!(
-            //         timing,
-            //         "copy result",
-            //         {
-            //             unsafe {
-            //                 transmute::<&DeviceSlice<F>, &DeviceSlice<u64>>(d_quotient_polys).async_copy_to(
-            //                 transmute::<&mut Vec<F>, &mut Vec<u64>>(&mut quotient_polys_flatten),
-            //                 &ctx.inner.stream).unwrap();
-            //                 ctx.inner.stream.synchronize().unwrap();
-            //             }
-            //         }
-            //     );
-            //
-            // (quotient_polys_flatten.chunks(values_num_per_extpoly).map(|c|PolynomialCoeffs{coeffs: c.to_vec()}).collect::<Vec<_>>(), d_quotient_polys)
-        });
-
-    // // Compute the quotient polynomials, aka `t` in the Plonk paper.
-    // let all_quotient_poly_chunks :Vec<PolynomialCoeffs<F>> = timed!(
-    //     timing,
-    //     "split up quotient polys",
-    //     quotient_polys
-    //         .into_par_iter()
-    //         .flat_map(|mut quotient_poly| {
-    //             quotient_poly.trim_to_len(quotient_degree).expect(
-    //                 "Quotient has failed, the vanishing polynomial is not divisible by Z_H",
-    //             );
-    //             // Split quotient into degree-n chunks.
-    //             quotient_poly.chunks(degree)
-    //         })
-    //         .collect()
-    // );
-    // println!("all_quotient_poly_chunks len:{}, itemLen:{}", all_quotient_poly_chunks.len(), all_quotient_poly_chunks[0].coeffs.len());
-
-    assert!(quotient_degree == (degree << config.fri_config.rate_bits));
-    // let quotient_polys_commitment = timed!(
-    //     timing,
-    //     "commit to quotient polys",
-    //     PolynomialBatch::from_coeffs(
-    //         all_quotient_poly_chunks,
-    //         config.fri_config.rate_bits,
-    //         config.zero_knowledge && PlonkOracle::QUOTIENT.blinding,
-    //         config.fri_config.cap_height,
-    //         timing,
-    //         prover_data.fft_root_table.as_ref(),
-    //     )
-    // );
-
-    println!("offset: {}, values: {}, zs product: {}",
-             ctx.second_stage_offset, ctx.values_flatten2.len(), zs_partial_products.len()<<config.fri_config.rate_bits);
     let quotient_polys_commitment = timed!(
         timing,
         "commit to quotient polys",
-        PolynomialBatch::from_coeffs_with_gpu(
-            ctx.second_stage_offset+(zs_partial_products.len()<<config.fri_config.rate_bits),
-            degree,
-            num_challenges*(1 << config.fri_config.rate_bits),
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::QUOTIENT.blinding,
-            config.fri_config.cap_height,
+        PolynomialBatch::from_coeffs_with_backend(
+            &layout.quotient_polys,
+            &salts(PlonkOracle::QUOTIENT, &layout.quotient_polys),
             timing,
-            ctx,
+            backend,
         )
+    )?;
+    record_stage(trace, TraceStage::QuotientChunks, degree, || {
+        quotient_polys_commitment
+            .polynomials
+            .iter()
+            .flat_map(|p| p.coeffs.clone())
+            .collect()
+    });
+    record_stage(
+        trace,
+        TraceStage::QuotientPolysLeaves,
+        layout.quotient_polys.leaf_len(),
+        || {
+            backend.read_values(
+                layout.quotient_polys.offset,
+                layout.quotient_polys.ext_values_len(),
+            )
+        },
+    );
+    record_cap(
+        trace,
+        TraceStage::QuotientPolysCap,
+        &quotient_polys_commitment.merkle_tree.cap,
     );
 
-    let (zeta, g) = timed!(
-        timing,
-        "get zeta and g",
-        {
-            challenger.observe_cap(&quotient_polys_commitment.merkle_tree.cap);
-
-            let zeta = challenger.get_extension_challenge::<D>();
-            // To avoid leaking witness data, we want to ensure that our opening locations, `zeta` and
-            // `g * zeta`, are not in our subgroup `H`. It suffices to check `zeta` only, since
-            // `(g * zeta)^n = zeta^n`, where `n` is the order of `g`.
-            let g = F::Extension::primitive_root_of_unity(common_data.degree_bits());
-            ensure!(
-                zeta.exp_power_of_2(common_data.degree_bits()) != F::Extension::ONE,
-                "Opening point is in the subgroup."
-            );
-                    (zeta, g)
-        });
+    let (zeta, g) = timed!(timing, "get zeta and g", {
+        challenger.observe_cap(&quotient_polys_commitment.merkle_tree.cap);
+        record_challenger(trace, TraceStage::QuotientPolysChallenger, &challenger);
+
+        let zeta = challenger.get_extension_challenge::<D>();
+        // To avoid leaking witness data, we want to ensure that our opening locations, `zeta` and
+        // `g * zeta`, are not in our subgroup `H`. It suffices to check `zeta` only, since
+        // `(g * zeta)^n = zeta^n`, where `n` is the order of `g`.
+        let g = F::Extension::primitive_root_of_unity(common_data.degree_bits());
+        ensure!(
+            zeta.exp_power_of_2(common_data.degree_bits()) != F::Extension::ONE,
+            "Opening point is in the subgroup."
+        );
+        (zeta, g)
+    });
+    record_stage(trace, TraceStage::Zeta, D, || ext_values::<F, D>(&[zeta]));
     let openings = timed!(
         timing,
         "construct the opening set",
@@ -673,10 +458,10 @@ pub fn my_prove<F: RichField + Extendable<D>, C: GenericConfig<D, F=F>, const D:
     timed!(
         timing,
         "observe_openings",
-            challenger.observe_openings(&openings.to_fri_openings())
-        );
+        challenger.observe_openings(&openings.to_fri_openings())
+    );
 
-    let opening_proof = timed!(
+    let (opening_proof, _) = timed!(
         timing,
         "compute opening proofs",
         PolynomialBatch::prove_openings(
@@ -690,10 +475,26 @@ pub fn my_prove<F: RichField + Extendable<D>, C: GenericConfig<D, F=F>, const D:
             &mut challenger,
             &common_data.fri_params,
             timing,
-            &mut Some(ctx),
+            &mut Some(backend),
         )
     );
 
+    record_stage(trace, TraceStage::Openings, D, || {
+        let fri_openings = openings.to_fri_openings();
+        let values = fri_openings
+            .batches
+            .iter()
+            .flat_map(|b| b.values.clone())
+            .collect::<Vec<_>>();
+        ext_values::<F, D>(&values)
+    });
+    record_stage(trace, TraceStage::FriFinalPoly, D, || {
+        ext_values::<F, D>(&opening_proof.final_poly.coeffs)
+    });
+    record_stage(trace, TraceStage::PowWitness, 1, || {
+        vec![opening_proof.pow_witness]
+    });
+
     let proof = Proof {
         wires_cap: wires_commitment.merkle_tree.cap,
         plonk_zs_partial_products_cap: partial_products_and_zs_commitment.merkle_tree.cap,
@@ -706,10 +507,40 @@ pub fn my_prove<F: RichField + Extendable<D>, C: GenericConfig<D, F=F>, const D:
         public_inputs,
     })
 }
+
+/// The layout of the prover in the memory of `backend`, checking that the backends support the
+/// circuit and that it fits.
+fn backend_layout<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+    backend: &dyn ProverBackend<F, C, D>,
+) -> Result<ProverLayout> {
+    ensure!(
+        !common_data.has_lookups(),
+        "Lookups are not supported by the backends, use `prove` instead"
+    );
+    // The quotient polynomials are computed on the whole LDE, so that their chunks are exactly
+    // the polynomials of the quotient commitment.
+    let rate_bits = common_data.config.fri_config.rate_bits;
+    ensure!(
+        common_data.quotient_degree_factor == 1 << rate_bits,
+        "The backends need a quotient degree factor of 2^rate_bits = {}, not {}, use `prove` instead",
+        1 << rate_bits,
+        common_data.quotient_degree_factor
+    );
+    let layout = ProverLayout::new(common_data, backend.second_stage_offset());
+    ensure!(
+        layout.end() <= backend.memory_len(),
+        "The backend memory is too small: {} words needed, {} available",
+        layout.end(),
+        backend.memory_len()
+    );
+    Ok(layout)
+}
+
 /// Compute the partial products used in the `Z` polynomials.
-fn all_wires_permutation_partial_products<
+pub(crate) fn all_wires_permutation_partial_products<
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F=F>,
+    C: GenericConfig<D, F = F>,
     const D: usize,
 >(
     witness: &MatrixWitness<F>,
@@ -736,7 +567,7 @@ fn all_wires_permutation_partial_products<
 /// where `f, g` are the products in the definition of `Z`: `Z(g^i) = f / g`.
 fn wires_permutation_partial_products_and_zs<
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F=F>,
+    C: GenericConfig<D, F = F>,
     const D: usize,
 >(
     witness: &MatrixWitness<F>,
@@ -793,12 +624,90 @@ fn wires_permutation_partial_products_and_zs<
         .collect()
 }
 
+/// Computes the polynomials of the lookup argument, checked by `eval_lookup_terms`: the running
+/// sum `Z_L` of each challenge, then the helpers of each challenge.
+pub(crate) fn compute_lookup_polys<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    witness: &MatrixWitness<F>,
+    deltas: &[F],
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Vec<PolynomialValues<F>> {
+    let num_slots = common_data.num_lookup_slots;
+    let num_helpers = common_data.num_lookup_helpers();
+    let table_gate = LookupTableGate { num_slots };
+    let (lookup_gate_index, table_gate_index) = common_data.lookup_gate_indices();
+    let row_gates = &prover_data.target_origins.row_gates;
+    let row_constants = prover_data.row_constants(common_data);
+
+    let (zs, helpers): (Vec<_>, Vec<_>) = (0..common_data.config.num_challenges)
+        .map(|i| {
+            let alpha = deltas[2 * i];
+            let delta = deltas[2 * i + 1];
+            // The helpers of each row: the sums of `weight / (delta - c)` over chunks of slots.
+            let row_helpers = (0..common_data.degree())
+                .into_par_iter()
+                .map(|row| {
+                    let gate = Some(row_gates[row]);
+                    let weights = if gate == lookup_gate_index {
+                        vec![F::ONE; num_slots]
+                    } else if gate == table_gate_index {
+                        (0..num_slots)
+                            .map(|s| -witness.get_wire(row, table_gate.wire_multiplicity(s)))
+                            .collect()
+                    } else {
+                        return vec![F::ZERO; num_helpers];
+                    };
+                    let kappa = row_constants[row][0];
+                    let denominators = (0..num_slots)
+                        .map(|s| {
+                            let input = witness.get_wire(row, LookupGate::wire_input(s));
+                            let output = witness.get_wire(row, LookupGate::wire_output(s));
+                            delta - (input + alpha * (output + alpha * kappa))
+                        })
+                        .collect::<Vec<_>>();
+                    let terms = F::batch_multiplicative_inverse(&denominators)
+                        .into_iter()
+                        .zip(weights)
+                        .map(|(inverse, weight)| weight * inverse)
+                        .collect::<Vec<_>>();
+                    terms
+                        .chunks(LOOKUP_DEGREE)
+                        .map(|chunk| chunk.iter().copied().sum())
+                        .collect()
+                })
+                .collect::<Vec<Vec<F>>>();
+
+            // `Z_L` starts at 0, and adds up the helpers of each row.
+            let mut z = F::ZERO;
+            let z_values = row_helpers
+                .iter()
+                .map(|helpers| {
+                    let z_x = z;
+                    z += helpers.iter().copied().sum::<F>();
+                    z_x
+                })
+                .collect();
+            let helper_polys = transpose(&row_helpers)
+                .into_iter()
+                .map(PolynomialValues::new)
+                .collect::<Vec<_>>();
+            (PolynomialValues::new(z_values), helper_polys)
+        })
+        .unzip();
+
+    [zs, helpers.concat()].concat()
+}
+
 const BATCH_SIZE: usize = 32;
 
-fn compute_quotient_polys<
+pub(crate) fn compute_quotient_polys<
     'a,
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F=F>,
+    C: GenericConfig<D, F = F>,
     const D: usize,
 >(
     common_data: &CommonCircuitData<F, D>,
@@ -808,8 +717,151 @@ fn compute_quotient_polys<
     zs_partial_products_commitment: &'a PolynomialBatch<F, C, D>,
     betas: &[F],
     gammas: &[F],
+    deltas: &[F],
     alphas: &[F],
+    domain: &QuotientDomain<F>,
     timing: &mut TimingTree,
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+) -> Vec<PolynomialCoeffs<F>> {
+    compute_quotient_polys_from_ldes::<F, C, D>(
+        common_data,
+        domain,
+        public_inputs_hash,
+        &|i, step| {
+            prover_data
+                .constants_sigmas_commitment
+                .get_lde_values(i, step)
+        },
+        &|i, step| wires_commitment.get_lde_values(i, step),
+        &|i, step| zs_partial_products_commitment.get_lde_values(i, step),
+        betas,
+        gammas,
+        deltas,
+        alphas,
+        &(0..common_data.gates.len()).collect::<Vec<_>>(),
+        None,
+        timing,
+        trace,
+    )
+}
+
+/// The points of the LDE on which the quotient polynomials are computed, and the other values of
+/// the prover which only depend on the circuit. Proofs of the same circuit can share them, see
+/// `prove_batch`.
+pub struct QuotientDomain<F: Field> {
+    /// The points `g * w^i` of the coset, `g` being the coset shift.
+    pub shifted_points: Vec<F>,
+    pub z_h_on_coset: ZeroPolyOnCoset<F>,
+    /// The twiddles of the iFFT of the quotient values.
+    pub quotient_root_table: FftRootTable<F>,
+    /// The twiddles of the iFFTs of the wires and the `Z`s, the first rows of
+    /// `quotient_root_table`.
+    pub degree_root_table: FftRootTable<F>,
+    /// The constants at the points of each batch of `BATCH_SIZE` points, laid out as
+    /// `EvaluationVarsBaseBatch` reads them, if set by `with_local_constants`.
+    pub local_constants_batches: Option<Vec<Vec<F>>>,
+}
+
+impl<F: Field> QuotientDomain<F> {
+    pub fn new<const D: usize>(common_data: &CommonCircuitData<F, D>) -> Self
+    where
+        F: RichField + Extendable<D>,
+    {
+        let quotient_degree_bits = log2_ceil(common_data.quotient_degree_factor);
+        let shifted_points = F::two_adic_subgroup(common_data.degree_bits() + quotient_degree_bits)
+            .into_par_iter()
+            .map(|x| F::coset_shift() * x)
+            .collect();
+        let quotient_root_table = fft_root_table(common_data.degree() << quotient_degree_bits);
+        let degree_root_table = quotient_root_table[..common_data.degree_bits()].to_vec();
+        Self {
+            shifted_points,
+            z_h_on_coset: ZeroPolyOnCoset::new(common_data.degree_bits(), quotient_degree_bits),
+            quotient_root_table,
+            degree_root_table,
+            local_constants_batches: None,
+        }
+    }
+
+    /// Reads the constants at the points from the constants-sigmas commitment once, instead of
+    /// once per proof.
+    pub fn with_local_constants<C: GenericConfig<D, F = F>, const D: usize>(
+        mut self,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Self
+    where
+        F: RichField + Extendable<D>,
+    {
+        let step = quotient_lde_step(common_data);
+        let batches = (0..self.shifted_points.len())
+            .collect::<Vec<_>>()
+            .par_chunks(BATCH_SIZE)
+            .map(|indices_batch| {
+                let local_constants_batch_refs = indices_batch
+                    .iter()
+                    .map(|&i| {
+                        &prover_data
+                            .constants_sigmas_commitment
+                            .get_lde_values(i, step)[common_data.constants_range()]
+                    })
+                    .collect::<Vec<_>>();
+                transpose_batch(&local_constants_batch_refs)
+            })
+            .collect();
+        self.local_constants_batches = Some(batches);
+        self
+    }
+}
+
+/// The step between the points of the quotient domain in the LDE of the commitments.
+fn quotient_lde_step<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+) -> usize {
+    1 << (common_data.config.fri_config.rate_bits - log2_ceil(common_data.quotient_degree_factor))
+}
+
+/// Lays out the values at the points of a batch as `EvaluationVarsBaseBatch` reads them, i.e.
+/// the `i`th value of all the points, then the `i + 1`th, and so on.
+fn transpose_batch<F: Field>(batch_refs: &[&[F]]) -> Vec<F> {
+    // NB (JN): I'm not sure how (in)efficient the below is. It needs measuring.
+    let mut batch = vec![F::ZERO; batch_refs.len() * batch_refs[0].len()];
+    for i in 0..batch_refs[0].len() {
+        for (j, values) in batch_refs.iter().enumerate() {
+            batch[i * batch_refs.len() + j] = values[i];
+        }
+    }
+    batch
+}
+
+/// Fetches the (unsalted) LDE values of a commitment at the `index * step`th point.
+pub(crate) type LdeValuesFn<'a, F> = dyn Fn(usize, usize) -> &'a [F] + Sync + 'a;
+
+/// Like `compute_quotient_polys`, but reads the committed LDEs through the given functions, so
+/// that it can be shared with backends which keep their commitments elsewhere. Only the gates at
+/// the indices `gate_rows` of `common_data.gates` are evaluated, the contribution of the others
+/// being given by `fallback_quotient_values`, as computed by `compute_fallback_quotient_values`.
+/// The quotient values are reported to `trace` before being interpolated.
+pub(crate) fn compute_quotient_polys_from_ldes<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common_data: &CommonCircuitData<F, D>,
+    domain: &QuotientDomain<F>,
+    public_inputs_hash: &HashOut<F>,
+    constants_sigmas_lde_values: &LdeValuesFn<'a, F>,
+    wires_lde_values: &LdeValuesFn<'a, F>,
+    zs_partial_products_lde_values: &LdeValuesFn<'a, F>,
+    betas: &[F],
+    gammas: &[F],
+    deltas: &[F],
+    alphas: &[F],
+    gate_rows: &[usize],
+    fallback_quotient_values: Option<&[F]>,
+    timing: &mut TimingTree,
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
 ) -> Vec<PolynomialCoeffs<F>> {
     let num_challenges = common_data.config.num_challenges;
     let quotient_degree_bits = log2_ceil(common_data.quotient_degree_factor);
@@ -821,222 +873,226 @@ fn compute_quotient_polys<
 
     // We reuse the LDE computed in `PolynomialBatch` and extract every `step` points to get
     // an LDE matching `max_filtered_constraint_degree`.
-    let step = 1 << (common_data.config.fri_config.rate_bits - quotient_degree_bits);
+    let step = quotient_lde_step(common_data);
     // When opening the `Z`s polys at the "next" point in Plonk, need to look at the point `next_step`
     // steps away since we work on an LDE of degree `max_filtered_constraint_degree`.
     let next_step = 1 << quotient_degree_bits;
 
-    let points = F::two_adic_subgroup(common_data.degree_bits() + quotient_degree_bits);
+    let points = &domain.shifted_points;
     let lde_size = points.len();
+    debug_assert_eq!(lde_size, common_data.degree() << quotient_degree_bits);
 
-    let z_h_on_coset = ZeroPolyOnCoset::new(common_data.degree_bits(), quotient_degree_bits);
-    println!("z_h_on_coset, n: {}, rate: {}", z_h_on_coset.n, z_h_on_coset.rate);
-    println!("step: {}, next_step: {}, lde_size: {}", step, next_step, lde_size);
-    println!("public_inputs_hash: {:?}", public_inputs_hash);
-    unsafe {
-    //     let mut file = File::create("zs_partial_products_commitment.polynomials.bin").unwrap();
-    //     for value in zs_partial_products_commitment.polynomials.iter().flat_map(|v| v.coeffs.clone()) {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(&value)).unwrap();
-    //     }
-    //     let mut file = File::create("zs_partial_products_commitment.leaves.bin").unwrap();
-    //     for value in zs_partial_products_commitment.merkle_tree.leaves.concat() {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(&value)).unwrap();
-    //     }
-    //     let mut file = File::create("zs_partial_products_commitment.digests.bin").unwrap();
-    //     for value in zs_partial_products_commitment.merkle_tree.digests.iter() {
-    //         file.write_all(std::mem::transmute::<&_, &[u8; 32]>(value)).unwrap();
-    //     }
-    //     let mut file = File::create("zs_partial_products_commitment.caps.bin").unwrap();
-    //     for value in zs_partial_products_commitment.merkle_tree.cap.0.iter() {
-    //         file.write_all(std::mem::transmute::<&_, &[u8; 32]>(value)).unwrap();
-    //     }
-    //
-    //     let mut file = File::create("alphas.bin").unwrap();
-    //     for value in alphas {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(value)).unwrap();
-    //     }
-    //
-    //     let mut file = File::create("betas.bin").unwrap();
-    //     for value in betas {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(value)).unwrap();
-    //     }
-    //     let mut file = File::create("gammas.bin").unwrap();
-    //     for value in gammas {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(value)).unwrap();
-    //     }
-    //     let mut file = File::create("k_is.bin").unwrap();
-    //     for value in common_data.k_is.iter() {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(value)).unwrap();
-    //     }
-    //
-    //     let mut file = File::create("points.bin").unwrap();
-    //     for value in points.iter() {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(value)).unwrap();
-    //     }
-    //
-    //     let mut file = File::create("z_h_on_coset.evals.bin").unwrap();
-    //     println!("z_h_on_coset.evals len: {}", z_h_on_coset.evals.len());
-    //     for value in z_h_on_coset.evals.iter() {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(value)).unwrap();
-    //     }
-    //     let mut file = File::create("z_h_on_coset.inverses.bin").unwrap();
-    //     for value in z_h_on_coset.inverses.iter() {
-    //         file.write_all(std::mem::transmute::<&F, &[u8; 8]>(value)).unwrap();
-    //     }
-    }
-
-    println!("alphas: {:?}", alphas);
-    println!("betas: {:?}", betas);
-    println!("gammas: {:?}", gammas);
+    let z_h_on_coset = &domain.z_h_on_coset;
 
     let points_batches = points.par_chunks(BATCH_SIZE);
     let num_batches = ceil_div_usize(points.len(), BATCH_SIZE);
-    let quotient_values: Vec<Vec<F>> =  timed!(
+    let quotient_values: Vec<Vec<F>> = timed!(
         timing,
         "compute quotient values",
         points_batches
-        .enumerate()
-        .flat_map(|(batch_i, xs_batch)| {
-            // Each batch must be the same size, except the last one, which may be smaller.
-            debug_assert!(
-                xs_batch.len() == BATCH_SIZE
-                    || (batch_i == num_batches - 1 && xs_batch.len() <= BATCH_SIZE)
-            );
+            .enumerate()
+            .flat_map(|(batch_i, xs_batch)| {
+                // Each batch must be the same size, except the last one, which may be smaller.
+                debug_assert!(
+                    xs_batch.len() == BATCH_SIZE
+                        || (batch_i == num_batches - 1 && xs_batch.len() <= BATCH_SIZE)
+                );
 
-            let indices_batch: Vec<usize> =
-                (BATCH_SIZE * batch_i..BATCH_SIZE * batch_i + xs_batch.len()).collect();
-
-            let mut shifted_xs_batch = Vec::with_capacity(xs_batch.len());
-            let mut local_zs_batch = Vec::with_capacity(xs_batch.len());
-            let mut next_zs_batch = Vec::with_capacity(xs_batch.len());
-            let mut partial_products_batch = Vec::with_capacity(xs_batch.len());
-            let mut s_sigmas_batch = Vec::with_capacity(xs_batch.len());
-
-            let mut local_constants_batch_refs = Vec::with_capacity(xs_batch.len());
-            let mut local_wires_batch_refs = Vec::with_capacity(xs_batch.len());
-
-            for (&i, &x) in indices_batch.iter().zip(xs_batch) {
-                let shifted_x = F::coset_shift() * x;
-                let i_next = (i + next_step) % lde_size;
-                let local_constants_sigmas = prover_data
-                    .constants_sigmas_commitment
-                    .get_lde_values(i, step);
-                let local_constants = &local_constants_sigmas[common_data.constants_range()];
-                let s_sigmas = &local_constants_sigmas[common_data.sigmas_range()];
-                let local_wires = wires_commitment.get_lde_values(i, step);
-                let local_zs_partial_products =
-                    zs_partial_products_commitment.get_lde_values(i, step);
-                let local_zs = &local_zs_partial_products[common_data.zs_range()];
-                let next_zs = &zs_partial_products_commitment.get_lde_values(i_next, step)
-                    [common_data.zs_range()];
-                let partial_products =
-                    &local_zs_partial_products[common_data.partial_products_range()];
-
-                if i == 1048576 {
-                    println!("i: {}, len: {}, lcs: {:?}", i, local_constants_sigmas.len(), local_constants_sigmas);
-                    println!("i: {}, len: {}, lw: {:?}", i, local_wires.len(), local_wires);
-                    println!("i: {}, len: {}, lzpp: {:?}", i, local_zs_partial_products.len(), local_zs_partial_products);
-                    println!("i: {}, len: {}, nzs: {:?}", i, next_zs.len(), next_zs);
+                let indices_batch: Vec<usize> =
+                    (BATCH_SIZE * batch_i..BATCH_SIZE * batch_i + xs_batch.len()).collect();
+
+                let mut shifted_xs_batch = Vec::with_capacity(xs_batch.len());
+                let mut local_zs_batch = Vec::with_capacity(xs_batch.len());
+                let mut next_zs_batch = Vec::with_capacity(xs_batch.len());
+                let mut partial_products_batch = Vec::with_capacity(xs_batch.len());
+                let mut lookup_polys_batch = Vec::with_capacity(xs_batch.len());
+                let mut next_lookup_zs_batch = Vec::with_capacity(xs_batch.len());
+                let mut s_sigmas_batch = Vec::with_capacity(xs_batch.len());
+
+                let mut local_constants_batch_refs = Vec::with_capacity(xs_batch.len());
+                let mut local_wires_batch_refs = Vec::with_capacity(xs_batch.len());
+
+                for (&i, &shifted_x) in indices_batch.iter().zip(xs_batch) {
+                    let i_next = (i + next_step) % lde_size;
+                    let local_constants_sigmas = constants_sigmas_lde_values(i, step);
+                    let local_constants = &local_constants_sigmas[common_data.constants_range()];
+                    let s_sigmas = &local_constants_sigmas[common_data.sigmas_range()];
+                    let local_wires = wires_lde_values(i, step);
+                    let local_zs_partial_products = zs_partial_products_lde_values(i, step);
+                    let next_zs_partial_products = zs_partial_products_lde_values(i_next, step);
+                    let local_zs = &local_zs_partial_products[common_data.zs_range()];
+                    let next_zs = &next_zs_partial_products[common_data.zs_range()];
+                    let partial_products =
+                        &local_zs_partial_products[common_data.partial_products_range()];
+                    let lookup_polys = &local_zs_partial_products[common_data.lookup_polys_range()];
+                    let next_lookup_zs = &next_zs_partial_products[common_data.lookup_zs_range()];
+
+                    debug_assert_eq!(local_wires.len(), common_data.config.num_wires);
+                    debug_assert_eq!(local_zs.len(), num_challenges);
+
+                    local_constants_batch_refs.push(local_constants);
+                    local_wires_batch_refs.push(local_wires);
+
+                    shifted_xs_batch.push(shifted_x);
+                    local_zs_batch.push(local_zs);
+                    next_zs_batch.push(next_zs);
+                    partial_products_batch.push(partial_products);
+                    lookup_polys_batch.push(lookup_polys);
+                    next_lookup_zs_batch.push(next_lookup_zs);
+                    s_sigmas_batch.push(s_sigmas);
                 }
-                debug_assert_eq!(local_wires.len(), common_data.config.num_wires);
-                debug_assert_eq!(local_zs.len(), num_challenges);
 
-                local_constants_batch_refs.push(local_constants);
-                local_wires_batch_refs.push(local_wires);
+                let own_local_constants_batch;
+                let local_constants_batch = match &domain.local_constants_batches {
+                    Some(batches) => &batches[batch_i],
+                    None => {
+                        own_local_constants_batch = transpose_batch(&local_constants_batch_refs);
+                        &own_local_constants_batch
+                    }
+                };
+                let local_wires_batch = transpose_batch(&local_wires_batch_refs);
+
+                let vars_batch = EvaluationVarsBaseBatch::new(
+                    xs_batch.len(),
+                    local_constants_batch,
+                    &local_wires_batch,
+                    public_inputs_hash,
+                );
 
-                shifted_xs_batch.push(shifted_x);
-                local_zs_batch.push(local_zs);
-                next_zs_batch.push(next_zs);
-                partial_products_batch.push(partial_products);
-                s_sigmas_batch.push(s_sigmas);
-            }
+                let mut quotient_values_batch = eval_vanishing_poly_base_batch::<F, C, D>(
+                    common_data,
+                    &indices_batch,
+                    &shifted_xs_batch,
+                    vars_batch,
+                    &local_zs_batch,
+                    &next_zs_batch,
+                    &partial_products_batch,
+                    &lookup_polys_batch,
+                    &next_lookup_zs_batch,
+                    &s_sigmas_batch,
+                    betas,
+                    gammas,
+                    deltas,
+                    alphas,
+                    z_h_on_coset,
+                    gate_rows,
+                );
 
-            // NB (JN): I'm not sure how (in)efficient the below is. It needs measuring.
-            let mut local_constants_batch =
-                vec![F::ZERO; xs_batch.len() * local_constants_batch_refs[0].len()];
-            for i in 0..local_constants_batch_refs[0].len() {
-                for (j, constants) in local_constants_batch_refs.iter().enumerate() {
-                    local_constants_batch[i * xs_batch.len() + j] = constants[i];
+                for (&i, quotient_values) in
+                    indices_batch.iter().zip(quotient_values_batch.iter_mut())
+                {
+                    let denominator_inv = z_h_on_coset.eval_inverse(i);
+                    quotient_values
+                        .iter_mut()
+                        .for_each(|v| *v *= denominator_inv);
+                    if let Some(fallback_values) = fallback_quotient_values {
+                        quotient_values
+                            .iter_mut()
+                            .zip(&fallback_values[i * num_challenges..(i + 1) * num_challenges])
+                            .for_each(|(v, &f)| *v += f);
+                    }
                 }
-            }
+                quotient_values_batch
+            })
+            .collect()
+    );
+
+    record_stage(trace, TraceStage::QuotientValues, num_challenges, || {
+        quotient_values.concat()
+    });
+
+    let values = timed!(timing, "transpose", transpose(&quotient_values));
+
+    let res: Vec<PolynomialCoeffs<F>> = timed!(
+        timing,
+        "coset ifft",
+        values
+            .into_par_iter()
+            .map(PolynomialValues::new)
+            .map(|values| {
+                values.coset_ifft_with_options(F::coset_shift(), Some(&domain.quotient_root_table))
+            })
+            .collect()
+    );
 
-            let mut local_wires_batch =
-                vec![F::ZERO; xs_batch.len() * local_wires_batch_refs[0].len()];
-            for i in 0..local_wires_batch_refs[0].len() {
-                for (j, wires) in local_wires_batch_refs.iter().enumerate() {
-                    local_wires_batch[i * xs_batch.len() + j] = wires[i];
+    res
+}
+
+/// Evaluates the constraints of the gates at the indices `gate_rows` of `common_data.gates` on the
+/// LDE, and returns their contribution to the quotient values: the `num_challenges` values of each
+/// point, one point after the other. Used for the gates a backend cannot evaluate itself.
+pub(crate) fn compute_fallback_quotient_values<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common_data: &CommonCircuitData<F, D>,
+    gate_rows: &[usize],
+    public_inputs_hash: &HashOut<F>,
+    constants_sigmas_lde_values: &LdeValuesFn<'a, F>,
+    wires_lde_values: &LdeValuesFn<'a, F>,
+    alphas: &[F],
+) -> Vec<F> {
+    let quotient_degree_bits = log2_ceil(common_data.quotient_degree_factor);
+    let step = 1 << (common_data.config.fri_config.rate_bits - quotient_degree_bits);
+    let lde_size = common_data.degree() << quotient_degree_bits;
+    let z_h_on_coset = ZeroPolyOnCoset::new(common_data.degree_bits(), quotient_degree_bits);
+
+    // In the vanishing terms, the gate constraints come after the `L_0(x) (Z(x) - 1)` terms and
+    // the partial products checks, one per partial product and one for `Z(gx)`.
+    let num_preceding_terms =
+        common_data.config.num_challenges * (common_data.num_partial_products + 2);
+    let alphas_shifts = alphas
+        .iter()
+        .map(|a| a.exp_u64(num_preceding_terms as u64))
+        .collect::<Vec<_>>();
+
+    let indices = (0..lde_size).collect::<Vec<_>>();
+    indices
+        .par_chunks(BATCH_SIZE)
+        .flat_map(|indices_batch| {
+            let n = indices_batch.len();
+            let num_constants = common_data.num_constants;
+            let num_wires = common_data.config.num_wires;
+            let mut local_constants_batch = vec![F::ZERO; n * num_constants];
+            let mut local_wires_batch = vec![F::ZERO; n * num_wires];
+            for (j, &i) in indices_batch.iter().enumerate() {
+                let local_constants =
+                    &constants_sigmas_lde_values(i, step)[common_data.constants_range()];
+                for (k, &c) in local_constants.iter().enumerate() {
+                    local_constants_batch[k * n + j] = c;
+                }
+                for (k, &w) in wires_lde_values(i, step).iter().enumerate() {
+                    local_wires_batch[k * n + j] = w;
                 }
             }
-
             let vars_batch = EvaluationVarsBaseBatch::new(
-                xs_batch.len(),
+                n,
                 &local_constants_batch,
                 &local_wires_batch,
                 public_inputs_hash,
             );
-
-            let mut quotient_values_batch = eval_vanishing_poly_base_batch::<F, C, D>(
+            let constraint_terms_batch = evaluate_gate_constraints_base_batch_for_rows::<F, C, D>(
                 common_data,
-                &indices_batch,
-                &shifted_xs_batch,
                 vars_batch,
-                &local_zs_batch,
-                &next_zs_batch,
-                &partial_products_batch,
-                &s_sigmas_batch,
-                betas,
-                gammas,
-                alphas,
-                &z_h_on_coset,
+                gate_rows,
             );
 
-            for (&i, quotient_values) in indices_batch.iter().zip(quotient_values_batch.iter_mut())
-            {
-                let denominator_inv = z_h_on_coset.eval_inverse(i);
-                quotient_values
-                    .iter_mut()
-                    .for_each(|v| *v *= denominator_inv);
-
-                if i == 1048576 {
-                    println!("i: {}, res: {:?}", i, quotient_values);
-                }
-            }
-            quotient_values_batch
+            indices_batch
+                .iter()
+                .enumerate()
+                .flat_map(|(j, &i)| {
+                    let constraint_terms =
+                        PackedStridedView::<F>::new(&constraint_terms_batch, n, j);
+                    let denominator_inv = z_h_on_coset.eval_inverse(i);
+                    reduce_with_powers_multi(constraint_terms, alphas)
+                        .into_iter()
+                        .zip(&alphas_shifts)
+                        .map(|(v, &shift)| v * shift * denominator_inv)
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
         })
         .collect()
-    );
-
-    println!("quotient_values len:{}, itemLen:{}", quotient_values.len(), quotient_values[0].len());
-    // unsafe
-    // {
-    //     let mut file = File::create("quotient_values.bin").unwrap();
-    //     let v = quotient_values.concat();
-    //     file.write_all(std::slice::from_raw_parts(v.as_ptr() as *const u8, v.len()*8));
-    // }
-
-    let values = timed!(
-        timing,
-        "transpose",
-        transpose(&quotient_values));
-
-    let res: Vec<PolynomialCoeffs<F>> = timed!(
-        timing,
-        "coset ifft",
-        values.into_par_iter()
-            .map(PolynomialValues::new)
-            .map(|values| values.coset_ifft(F::coset_shift()))
-            .collect()
-    );
-
-    // unsafe
-    // {
-    //     let mut file = File::create("quotient_values2.bin").unwrap();
-    //     let v = res.iter().flat_map(|f|f.coeffs.clone()).collect::<Vec<_>>();
-    //     file.write_all(std::slice::from_raw_parts(v.as_ptr() as *const u8, v.len()*8));
-    // }
-
-    // let n_inv = F::inverse_2exp(21);
-    // println!("n_inv with 21: {:?}", n_inv);
-    // println!("v1: {:?}, v2: {:?}", res[0].coeffs[1048576], res[1].coeffs[1048576]);
-    res
 }
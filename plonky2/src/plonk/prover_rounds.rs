@@ -0,0 +1,759 @@
+//! The CPU prover as a state machine, one method per round of the protocol.
+//!
+//! `prove` runs a [`Prover`] from start to end. Driving it round by round instead allows to pause
+//! between rounds, e.g. to generate the witness of the next proof while this one runs FRI, and to
+//! inspect the commitments and the `Challenger` of the rounds done so far.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use anyhow::{anyhow, ensure, Result};
+use maybe_rayon::*;
+
+use crate::field::extension::Extendable;
+use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
+use crate::field::types::Field;
+use crate::fri::oracle::{PolynomialBatch, SaltSource};
+use crate::fri::proof::FriProof;
+use crate::fri::prover::PowStatistics;
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::iop::challenger::Challenger;
+use crate::iop::witness::{MatrixWitness, PartitionWitness, Witness};
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::plonk_common::{reduce_with_powers, salt_size, PlonkOracle};
+use crate::plonk::proof::{OpeningSet, Proof, ProofWithPublicInputs};
+use crate::plonk::prover::{
+    all_wires_permutation_partial_products, compute_lookup_polys, compute_quotient_polys,
+    QuotientDomain,
+};
+use crate::plonk::prover_trace::{
+    ext_values, record_cap, record_challenger, record_leaves, record_stage, ProverTrace, TraceStage,
+};
+use crate::plonk::vanishing_poly::eval_vanishing_poly;
+use crate::plonk::vars::EvaluationVars;
+use crate::timed;
+use crate::util::timing::TimingTree;
+
+/// The rounds of the prover, in order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ProverRound {
+    /// Commit to the wire polynomials, then draw the permutation challenges `betas` and `gammas`,
+    /// and the lookup challenges `deltas`.
+    CommitWires,
+    /// Commit to the `Z` and partial products polynomials, then draw the `alphas`.
+    CommitZsPartialProducts,
+    /// Commit to the chunks of the quotient polynomials, then draw `zeta`.
+    CommitQuotient,
+    /// Evaluate all the polynomials at `zeta` and `g * zeta`.
+    Open,
+    /// Prove the openings with FRI.
+    Fri,
+    /// The proof is complete.
+    Done,
+}
+
+/// A proof in progress. The values computed by a round are available once it has run.
+pub struct Prover<'a, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    round: ProverRound,
+    /// The quotient domain shared with other proofs, if any.
+    quotient_domain: Option<&'a QuotientDomain<F>>,
+    salt_source: SaltSource<F>,
+    /// The wire values, kept until the partial products are computed.
+    witness: Option<MatrixWitness<F>>,
+    public_inputs: Vec<F>,
+    public_inputs_hash: HashOut<F>,
+    /// The transcript, which has observed the instance and the messages of the rounds done so far.
+    challenger: Challenger<F, C::Hasher>,
+    wires_commitment: Option<PolynomialBatch<F, C, D>>,
+    betas: Vec<F>,
+    gammas: Vec<F>,
+    deltas: Vec<F>,
+    partial_products_and_zs_commitment: Option<PolynomialBatch<F, C, D>>,
+    alphas: Vec<F>,
+    quotient_polys_commitment: Option<PolynomialBatch<F, C, D>>,
+    zeta: Option<F::Extension>,
+    openings: Option<OpeningSet<F, D>>,
+    opening_proof: Option<FriProof<F, C::Hasher, D>>,
+    pow_statistics: Option<PowStatistics>,
+}
+
+impl<'a, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    Prover<'a, F, C, D>
+{
+    /// Starts a proof of `partition_witness`, observing the instance.
+    pub fn new(
+        prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+        common_data: &'a CommonCircuitData<F, D>,
+        partition_witness: PartitionWitness<F>,
+        timing: &mut TimingTree,
+        trace: &mut Option<&mut dyn ProverTrace<F>>,
+    ) -> Self {
+        let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
+        let public_inputs_hash = C::InnerHasher::hash_public_inputs(&public_inputs);
+        record_stage(trace, TraceStage::PublicInputsHash, 4, || {
+            public_inputs_hash.elements.to_vec()
+        });
+
+        let witness = timed!(
+            timing,
+            "compute full witness",
+            partition_witness.full_witness()
+        );
+
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        challenger.observe_hash::<C::Hasher>(prover_data.circuit_digest);
+        challenger.observe_hash::<C::InnerHasher>(public_inputs_hash);
+
+        Self {
+            prover_data,
+            common_data,
+            round: ProverRound::CommitWires,
+            quotient_domain: None,
+            salt_source: SaltSource::Random,
+            witness: Some(witness),
+            public_inputs,
+            public_inputs_hash,
+            challenger,
+            wires_commitment: None,
+            betas: Vec::new(),
+            gammas: Vec::new(),
+            deltas: Vec::new(),
+            partial_products_and_zs_commitment: None,
+            alphas: Vec::new(),
+            quotient_polys_commitment: None,
+            zeta: None,
+            openings: None,
+            opening_proof: None,
+            pow_statistics: None,
+        }
+    }
+
+    /// Uses `domain` and its twiddles to compute the polynomials, instead of recomputing them.
+    pub fn with_quotient_domain(mut self, domain: &'a QuotientDomain<F>) -> Self {
+        self.quotient_domain = Some(domain);
+        self
+    }
+
+    /// Salts the leaves of hiding commitments with `salt_source`, instead of random salts.
+    pub fn with_salt_source(mut self, salt_source: SaltSource<F>) -> Self {
+        self.salt_source = salt_source;
+        self
+    }
+
+    /// The next round to run.
+    pub fn round(&self) -> ProverRound {
+        self.round
+    }
+
+    pub fn public_inputs(&self) -> &[F] {
+        &self.public_inputs
+    }
+
+    pub fn public_inputs_hash(&self) -> HashOut<F> {
+        self.public_inputs_hash
+    }
+
+    /// The transcript, which has observed the instance and the messages of the rounds done so far.
+    pub fn challenger(&self) -> &Challenger<F, C::Hasher> {
+        &self.challenger
+    }
+
+    pub fn wires_commitment(&self) -> Option<&PolynomialBatch<F, C, D>> {
+        self.wires_commitment.as_ref()
+    }
+
+    /// The permutation challenges, empty until the wires are committed.
+    pub fn betas(&self) -> &[F] {
+        &self.betas
+    }
+
+    /// The permutation challenges, empty until the wires are committed.
+    pub fn gammas(&self) -> &[F] {
+        &self.gammas
+    }
+
+    /// The lookup challenges, empty until the wires are committed.
+    pub fn deltas(&self) -> &[F] {
+        &self.deltas
+    }
+
+    pub fn partial_products_and_zs_commitment(&self) -> Option<&PolynomialBatch<F, C, D>> {
+        self.partial_products_and_zs_commitment.as_ref()
+    }
+
+    /// The challenges combining the constraints, empty until the `Z`s are committed.
+    pub fn alphas(&self) -> &[F] {
+        &self.alphas
+    }
+
+    pub fn quotient_polys_commitment(&self) -> Option<&PolynomialBatch<F, C, D>> {
+        self.quotient_polys_commitment.as_ref()
+    }
+
+    pub fn zeta(&self) -> Option<F::Extension> {
+        self.zeta
+    }
+
+    pub fn openings(&self) -> Option<&OpeningSet<F, D>> {
+        self.openings.as_ref()
+    }
+
+    pub fn opening_proof(&self) -> Option<&FriProof<F, C::Hasher, D>> {
+        self.opening_proof.as_ref()
+    }
+
+    pub fn pow_statistics(&self) -> Option<&PowStatistics> {
+        self.pow_statistics.as_ref()
+    }
+
+    /// Runs the next round.
+    pub fn run_round(
+        &mut self,
+        timing: &mut TimingTree,
+        trace: &mut Option<&mut dyn ProverTrace<F>>,
+    ) -> Result<()> {
+        match self.round {
+            ProverRound::CommitWires => self.commit_wires(timing, trace),
+            ProverRound::CommitZsPartialProducts => self.commit_zs_partial_products(timing, trace),
+            ProverRound::CommitQuotient => self.commit_quotient(timing, trace),
+            ProverRound::Open => self.open(timing, trace),
+            ProverRound::Fri => self.prove_openings(timing, trace),
+            ProverRound::Done => Ok(()),
+        }
+    }
+
+    /// Runs the remaining rounds, and returns the proof.
+    pub fn finish(
+        mut self,
+        timing: &mut TimingTree,
+        trace: &mut Option<&mut dyn ProverTrace<F>>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        while self.round != ProverRound::Done {
+            self.run_round(timing, trace)?;
+        }
+        self.into_proof()
+    }
+
+    /// The proof, once all the rounds have run.
+    pub fn into_proof(self) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            self.round == ProverRound::Done,
+            "The proof is incomplete, the next round is {:?}",
+            self.round
+        );
+        let proof = Proof {
+            wires_cap: self.wires_commitment.unwrap().merkle_tree.cap,
+            plonk_zs_partial_products_cap: self
+                .partial_products_and_zs_commitment
+                .unwrap()
+                .merkle_tree
+                .cap,
+            quotient_polys_cap: self.quotient_polys_commitment.unwrap().merkle_tree.cap,
+            openings: self.openings.unwrap(),
+            opening_proof: self.opening_proof.unwrap(),
+        };
+        Ok(ProofWithPublicInputs {
+            proof,
+            public_inputs: self.public_inputs,
+        })
+    }
+
+    /// The salts of the leaves of `oracle`, if the circuit is zero-knowledge.
+    fn salts(&self, oracle: PlonkOracle) -> Vec<Vec<F>> {
+        let config = &self.common_data.config;
+        self.salt_source.salts::<C::Hasher>(
+            oracle.index,
+            salt_size(config.zero_knowledge && oracle.blinding),
+            self.common_data.lde_size(),
+        )
+    }
+
+    fn start_round(&self, round: ProverRound) -> Result<()> {
+        ensure!(
+            self.round == round,
+            "Cannot run round {:?}, the next round is {:?}",
+            round,
+            self.round
+        );
+        Ok(())
+    }
+
+    pub fn commit_wires(
+        &mut self,
+        timing: &mut TimingTree,
+        trace: &mut Option<&mut dyn ProverTrace<F>>,
+    ) -> Result<()> {
+        self.start_round(ProverRound::CommitWires)?;
+        let config = &self.common_data.config;
+        let witness = self.witness.as_ref().unwrap();
+
+        let wires_values: Vec<PolynomialValues<F>> = timed!(
+            timing,
+            "compute wire polynomials",
+            witness
+                .wire_values
+                .par_iter()
+                .map(|column| PolynomialValues::new(column.clone()))
+                .collect()
+        );
+
+        let wires_commitment = timed!(
+            timing,
+            "compute wires commitment",
+            PolynomialBatch::from_values_with_salts(
+                wires_values,
+                config.fri_config.rate_bits,
+                self.salts(PlonkOracle::WIRES),
+                config.fri_config.cap_height,
+                timing,
+                self.quotient_domain.map(|domain| &domain.degree_root_table),
+                self.prover_data.fft_root_table.as_ref(),
+            )
+        );
+        record_leaves(
+            trace,
+            TraceStage::WiresLeaves,
+            &wires_commitment.merkle_tree,
+        );
+        record_cap(
+            trace,
+            TraceStage::WiresCap,
+            &wires_commitment.merkle_tree.cap,
+        );
+
+        self.challenger
+            .observe_cap(&wires_commitment.merkle_tree.cap);
+        record_challenger(trace, TraceStage::WiresChallenger, &self.challenger);
+        self.betas = self.challenger.get_n_challenges(config.num_challenges);
+        self.gammas = self.challenger.get_n_challenges(config.num_challenges);
+        self.deltas = self
+            .challenger
+            .get_n_challenges(self.common_data.num_lookup_challenges());
+        record_stage(trace, TraceStage::Betas, 1, || self.betas.clone());
+        record_stage(trace, TraceStage::Gammas, 1, || self.gammas.clone());
+
+        self.wires_commitment = Some(wires_commitment);
+        self.round = ProverRound::CommitZsPartialProducts;
+        Ok(())
+    }
+
+    pub fn commit_zs_partial_products(
+        &mut self,
+        timing: &mut TimingTree,
+        trace: &mut Option<&mut dyn ProverTrace<F>>,
+    ) -> Result<()> {
+        self.start_round(ProverRound::CommitZsPartialProducts)?;
+        let common_data = self.common_data;
+        let config = &common_data.config;
+        // The wire values aren't needed after this round.
+        let witness = self.witness.take().unwrap();
+
+        assert!(
+            common_data.quotient_degree_factor < common_data.config.num_routed_wires,
+            "When the number of routed wires is smaller that the degree, we should change the logic to avoid computing partial products."
+        );
+        let mut partial_products_and_zs = timed!(
+            timing,
+            "compute partial products",
+            all_wires_permutation_partial_products(
+                &witness,
+                &self.betas,
+                &self.gammas,
+                self.prover_data,
+                common_data
+            )
+        );
+
+        // Z is expected at the front of our batch; see `zs_range` and `partial_products_range`.
+        let plonk_z_vecs = partial_products_and_zs
+            .iter_mut()
+            .map(|partial_products_and_z| partial_products_and_z.pop().unwrap())
+            .collect();
+        let mut zs_partial_products: Vec<PolynomialValues<F>> =
+            [plonk_z_vecs, partial_products_and_zs.concat()].concat();
+        // The polynomials of the lookup argument come last; see `lookup_polys_range`.
+        if common_data.has_lookups() {
+            zs_partial_products.extend(timed!(
+                timing,
+                "compute lookup polys",
+                compute_lookup_polys(&witness, &self.deltas, self.prover_data, common_data)
+            ));
+        }
+        record_stage(
+            trace,
+            TraceStage::ZsPartialProducts,
+            common_data.degree(),
+            || {
+                zs_partial_products
+                    .iter()
+                    .flat_map(|p| p.values.clone())
+                    .collect()
+            },
+        );
+
+        let partial_products_and_zs_commitment = timed!(
+            timing,
+            "commit to partial products and Z's",
+            PolynomialBatch::from_values_with_salts(
+                zs_partial_products,
+                config.fri_config.rate_bits,
+                self.salts(PlonkOracle::ZS_PARTIAL_PRODUCTS),
+                config.fri_config.cap_height,
+                timing,
+                self.quotient_domain.map(|domain| &domain.degree_root_table),
+                self.prover_data.fft_root_table.as_ref(),
+            )
+        );
+        record_leaves(
+            trace,
+            TraceStage::ZsPartialProductsLeaves,
+            &partial_products_and_zs_commitment.merkle_tree,
+        );
+        record_cap(
+            trace,
+            TraceStage::ZsPartialProductsCap,
+            &partial_products_and_zs_commitment.merkle_tree.cap,
+        );
+
+        self.challenger
+            .observe_cap(&partial_products_and_zs_commitment.merkle_tree.cap);
+        record_challenger(
+            trace,
+            TraceStage::ZsPartialProductsChallenger,
+            &self.challenger,
+        );
+
+        self.alphas = self.challenger.get_n_challenges(config.num_challenges);
+        record_stage(trace, TraceStage::Alphas, 1, || self.alphas.clone());
+
+        self.partial_products_and_zs_commitment = Some(partial_products_and_zs_commitment);
+        self.round = ProverRound::CommitQuotient;
+        Ok(())
+    }
+
+    pub fn commit_quotient(
+        &mut self,
+        timing: &mut TimingTree,
+        trace: &mut Option<&mut dyn ProverTrace<F>>,
+    ) -> Result<()> {
+        self.start_round(ProverRound::CommitQuotient)?;
+        let common_data = self.common_data;
+        let config = &common_data.config;
+        let quotient_degree = common_data.quotient_degree();
+        let degree = common_data.degree();
+
+        let own_domain;
+        let domain = match self.quotient_domain {
+            Some(domain) => domain,
+            None => {
+                own_domain = QuotientDomain::new(common_data);
+                &own_domain
+            }
+        };
+        let quotient_polys = timed!(
+            timing,
+            "compute quotient polys",
+            compute_quotient_polys(
+                common_data,
+                self.prover_data,
+                &self.public_inputs_hash,
+                self.wires_commitment.as_ref().unwrap(),
+                self.partial_products_and_zs_commitment.as_ref().unwrap(),
+                &self.betas,
+                &self.gammas,
+                &self.deltas,
+                &self.alphas,
+                domain,
+                timing,
+                trace,
+            )
+        );
+
+        // Compute the quotient polynomials, aka `t` in the Plonk paper.
+        let quotient_poly_chunks = timed!(
+            timing,
+            "split up quotient polys",
+            quotient_polys
+                .into_par_iter()
+                .map(|mut quotient_poly| {
+                    quotient_poly.trim_to_len(quotient_degree).map_err(|_| {
+                        anyhow!(
+                            "Quotient has failed, the vanishing polynomial is not divisible by Z_H"
+                        )
+                    })?;
+                    // Split quotient into degree-n chunks.
+                    Ok(quotient_poly.chunks(degree))
+                })
+                .collect::<Result<Vec<_>>>()
+        )?;
+        let all_quotient_poly_chunks: Vec<PolynomialCoeffs<F>> =
+            quotient_poly_chunks.into_iter().flatten().collect();
+
+        let quotient_polys_commitment = timed!(
+            timing,
+            "commit to quotient polys",
+            PolynomialBatch::from_coeffs_with_salts(
+                all_quotient_poly_chunks,
+                config.fri_config.rate_bits,
+                self.salts(PlonkOracle::QUOTIENT),
+                config.fri_config.cap_height,
+                timing,
+                self.prover_data.fft_root_table.as_ref(),
+            )
+        );
+        record_stage(trace, TraceStage::QuotientChunks, degree, || {
+            quotient_polys_commitment
+                .polynomials
+                .iter()
+                .flat_map(|p| p.coeffs.clone())
+                .collect()
+        });
+        record_leaves(
+            trace,
+            TraceStage::QuotientPolysLeaves,
+            &quotient_polys_commitment.merkle_tree,
+        );
+        record_cap(
+            trace,
+            TraceStage::QuotientPolysCap,
+            &quotient_polys_commitment.merkle_tree.cap,
+        );
+
+        self.challenger
+            .observe_cap(&quotient_polys_commitment.merkle_tree.cap);
+        record_challenger(trace, TraceStage::QuotientPolysChallenger, &self.challenger);
+
+        self.quotient_polys_commitment = Some(quotient_polys_commitment);
+        self.round = ProverRound::Open;
+        Ok(())
+    }
+
+    /// Checks that `vanishing(zeta) = Z_H(zeta) quotient(zeta)`, like the verifier does. The quotient
+    /// values are interpolated on a domain of the size of its degree, so a quotient polynomial is
+    /// found even if the vanishing polynomial isn't divisible by `Z_H`, i.e. if the witness doesn't
+    /// satisfy the constraints; this only shows outside of that domain.
+    fn check_openings(&self, zeta: F::Extension, openings: &OpeningSet<F, D>) -> Result<()> {
+        let common_data = self.common_data;
+        let vanishing_polys_zeta = eval_vanishing_poly::<F, C, D>(
+            common_data,
+            zeta,
+            EvaluationVars {
+                local_constants: &openings.constants,
+                local_wires: &openings.wires,
+                public_inputs_hash: &self.public_inputs_hash,
+            },
+            &openings.plonk_zs,
+            &openings.plonk_zs_next,
+            &openings.partial_products,
+            &openings.lookup_polys,
+            &openings.lookup_zs_next,
+            &openings.plonk_sigmas,
+            &self.betas,
+            &self.gammas,
+            &self.deltas,
+            &self.alphas,
+        );
+
+        let zeta_pow_deg = zeta.exp_power_of_2(common_data.degree_bits());
+        let z_h_zeta = zeta_pow_deg - F::Extension::ONE;
+        for (vanishing_poly_zeta, chunk) in vanishing_polys_zeta.into_iter().zip(
+            openings
+                .quotient_polys
+                .chunks(common_data.quotient_degree_factor),
+        ) {
+            ensure!(
+                vanishing_poly_zeta == z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg),
+                "Quotient has failed, the vanishing polynomial is not divisible by Z_H"
+            );
+        }
+        Ok(())
+    }
+
+    pub fn open(
+        &mut self,
+        timing: &mut TimingTree,
+        trace: &mut Option<&mut dyn ProverTrace<F>>,
+    ) -> Result<()> {
+        self.start_round(ProverRound::Open)?;
+        let common_data = self.common_data;
+
+        let zeta = self.challenger.get_extension_challenge::<D>();
+        // To avoid leaking witness data, we want to ensure that our opening locations, `zeta` and
+        // `g * zeta`, are not in our subgroup `H`. It suffices to check `zeta` only, since
+        // `(g * zeta)^n = zeta^n`, where `n` is the order of `g`.
+        let g = F::Extension::primitive_root_of_unity(common_data.degree_bits());
+        ensure!(
+            zeta.exp_power_of_2(common_data.degree_bits()) != F::Extension::ONE,
+            "Opening point is in the subgroup."
+        );
+        record_stage(trace, TraceStage::Zeta, D, || ext_values::<F, D>(&[zeta]));
+
+        let openings = timed!(
+            timing,
+            "construct the opening set",
+            OpeningSet::new(
+                zeta,
+                g,
+                &self.prover_data.constants_sigmas_commitment,
+                self.wires_commitment.as_ref().unwrap(),
+                self.partial_products_and_zs_commitment.as_ref().unwrap(),
+                self.quotient_polys_commitment.as_ref().unwrap(),
+                common_data,
+            )
+        );
+        timed!(
+            timing,
+            "check the openings",
+            self.check_openings(zeta, &openings)
+        )?;
+        self.challenger
+            .observe_openings(&openings.to_fri_openings());
+
+        self.zeta = Some(zeta);
+        self.openings = Some(openings);
+        self.round = ProverRound::Fri;
+        Ok(())
+    }
+
+    pub fn prove_openings(
+        &mut self,
+        timing: &mut TimingTree,
+        trace: &mut Option<&mut dyn ProverTrace<F>>,
+    ) -> Result<()> {
+        self.start_round(ProverRound::Fri)?;
+        let common_data = self.common_data;
+        let openings = self.openings.as_ref().unwrap();
+
+        let (opening_proof, pow_statistics) = timed!(
+            timing,
+            "compute opening proofs",
+            PolynomialBatch::prove_openings(
+                &common_data.get_fri_instance(self.zeta.unwrap()),
+                &[
+                    &self.prover_data.constants_sigmas_commitment,
+                    self.wires_commitment.as_ref().unwrap(),
+                    self.partial_products_and_zs_commitment.as_ref().unwrap(),
+                    self.quotient_polys_commitment.as_ref().unwrap(),
+                ],
+                &mut self.challenger,
+                &common_data.fri_params,
+                timing,
+                &mut None,
+            )
+        );
+
+        record_stage(trace, TraceStage::Openings, D, || {
+            let fri_openings = openings.to_fri_openings();
+            let values = fri_openings
+                .batches
+                .iter()
+                .flat_map(|b| b.values.clone())
+                .collect::<Vec<_>>();
+            ext_values::<F, D>(&values)
+        });
+        record_stage(trace, TraceStage::FriFinalPoly, D, || {
+            ext_values::<F, D>(&opening_proof.final_poly.coeffs)
+        });
+        record_stage(trace, TraceStage::PowWitness, 1, || {
+            vec![opening_proof.pow_witness]
+        });
+
+        self.opening_proof = Some(opening_proof);
+        self.pow_statistics = Some(pow_statistics);
+        self.round = ProverRound::Done;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::prover_rounds::{Prover, ProverRound};
+    use crate::util::timing::TimingTree;
+
+    #[test]
+    fn rounds_match_prove() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.exp_u64(x, 7);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, F::TWO);
+        // Fix the random wires, so that both proofs are the same.
+        let witness = data.record_witness(inputs)?;
+        let expected = data.prove_from_witness(witness.clone())?;
+
+        let timing = &mut TimingTree::default();
+        let mut prover = Prover::new(
+            &data.prover_only,
+            &data.common,
+            witness.into_partition_witness(&data.prover_only, &data.common)?,
+            timing,
+            &mut None,
+        );
+        assert!(prover.prove_openings(timing, &mut None).is_err());
+
+        prover.commit_wires(timing, &mut None)?;
+        assert_eq!(prover.round(), ProverRound::CommitZsPartialProducts);
+        assert_eq!(
+            prover.wires_commitment().unwrap().merkle_tree.cap,
+            expected.proof.wires_cap
+        );
+
+        let proof = prover.finish(timing, &mut None)?;
+        assert_eq!(proof, expected);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn unsatisfied_witness_is_an_error() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.exp_u64(x, 7);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, F::TWO);
+        let mut witness = data.record_witness(inputs)?;
+        // Shift all the values, so that the copy constraints hold but not the gate constraints.
+        for value in witness.values.iter_mut().flatten() {
+            *value += F::ONE;
+        }
+
+        let timing = &mut TimingTree::default();
+        let mut prover = Prover::new(
+            &data.prover_only,
+            &data.common,
+            witness.into_partition_witness(&data.prover_only, &data.common)?,
+            timing,
+            &mut None,
+        );
+        prover.commit_wires(timing, &mut None)?;
+        prover.commit_zs_partial_products(timing, &mut None)?;
+        prover.commit_quotient(timing, &mut None)?;
+        assert!(prover.open(timing, &mut None).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,446 @@
+//! Diagnostics of the prover.
+//!
+//! `prove_with_trace` and `my_prove_with_trace` report their intermediate values, stage after
+//! stage, to a [`ProverTrace`]. A [`TraceBundle`] keeps them, can be saved to disk, and compared
+//! with another bundle to find the first stage at which two provers diverge, e.g. `my_prove` on a
+//! given backend and the reference `prove`. A [`LogTrace`] only logs a summary of each stage.
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use log::{log, Level};
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::hash::hash_types::RichField;
+use crate::hash::hashing::SPONGE_WIDTH;
+use crate::hash::merkle_tree::{MerkleCap, MerkleTree};
+use crate::iop::challenger::Challenger;
+use crate::plonk::config::{GenericHashOut, Hasher};
+use crate::util::serialization::{Buffer, IoError, IoResult, Read, Remaining, Write};
+
+/// Version of the [`TraceBundle`] encoding, bumped whenever stages or their contents change.
+pub const TRACE_BUNDLE_VERSION: u32 = 2;
+
+const TRACE_BUNDLE_MAGIC: [u8; 4] = *b"PTRC";
+
+/// The intermediate values of the prover, in the order in which they are computed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TraceStage {
+    PublicInputsHash,
+    /// The leaves of the wires commitment, in the order of the Merkle tree.
+    WiresLeaves,
+    WiresCap,
+    /// The state of the challenger after observing the wires cap: its sponge state followed by
+    /// its pending inputs. Likewise for the other `*Challenger` stages.
+    WiresChallenger,
+    Betas,
+    Gammas,
+    /// The values of the `Z`s and partial products, one polynomial after the other.
+    ZsPartialProducts,
+    ZsPartialProductsLeaves,
+    ZsPartialProductsCap,
+    ZsPartialProductsChallenger,
+    Alphas,
+    /// The quotient values of each point of the LDE, before interpolation. Only recorded by the
+    /// CPU quotient computation.
+    QuotientValues,
+    /// The coefficients of the quotient polynomials chunks, one chunk after the other.
+    QuotientChunks,
+    QuotientPolysLeaves,
+    QuotientPolysCap,
+    QuotientPolysChallenger,
+    Zeta,
+    /// The opening set, in the order of `OpeningSet::to_fri_openings`.
+    Openings,
+    FriFinalPoly,
+    PowWitness,
+}
+
+impl TraceStage {
+    pub const ALL: [TraceStage; 20] = [
+        TraceStage::PublicInputsHash,
+        TraceStage::WiresLeaves,
+        TraceStage::WiresCap,
+        TraceStage::WiresChallenger,
+        TraceStage::Betas,
+        TraceStage::Gammas,
+        TraceStage::ZsPartialProducts,
+        TraceStage::ZsPartialProductsLeaves,
+        TraceStage::ZsPartialProductsCap,
+        TraceStage::ZsPartialProductsChallenger,
+        TraceStage::Alphas,
+        TraceStage::QuotientValues,
+        TraceStage::QuotientChunks,
+        TraceStage::QuotientPolysLeaves,
+        TraceStage::QuotientPolysCap,
+        TraceStage::QuotientPolysChallenger,
+        TraceStage::Zeta,
+        TraceStage::Openings,
+        TraceStage::FriFinalPoly,
+        TraceStage::PowWitness,
+    ];
+
+    fn tag(self) -> u8 {
+        Self::ALL.iter().position(|&s| s == self).unwrap() as u8
+    }
+
+    fn from_tag(tag: u8) -> IoResult<Self> {
+        Self::ALL.get(tag as usize).copied().ok_or(IoError)
+    }
+}
+
+/// A sink for the intermediate values of the prover.
+pub trait ProverTrace<F: RichField> {
+    /// Whether the values of `stage` should be recorded. The prover doesn't compute (or copy back
+    /// from its backend) the values of the stages which are not wanted.
+    fn wants(&self, _stage: TraceStage) -> bool {
+        true
+    }
+
+    /// Records the values of `stage`, laid out in rows of `width` values: one row per leaf, per
+    /// point, per polynomial, etc. depending on the stage.
+    fn record(&mut self, stage: TraceStage, width: usize, values: &[F]);
+}
+
+/// Records `stage` in `trace`, if any, computing its values only if they are wanted.
+pub(crate) fn record_stage<F: RichField>(
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+    stage: TraceStage,
+    width: usize,
+    values: impl FnOnce() -> Vec<F>,
+) {
+    if let Some(trace) = trace {
+        if trace.wants(stage) {
+            trace.record(stage, width, &values());
+        }
+    }
+}
+
+/// Records the digests of `cap`, one per row.
+pub(crate) fn record_cap<F: RichField, H: Hasher<F>>(
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+    stage: TraceStage,
+    cap: &MerkleCap<F, H>,
+) {
+    let width = cap.0.first().map_or(0, |h| h.to_vec().len());
+    record_stage(trace, stage, width, || {
+        cap.0.iter().flat_map(|h| h.to_vec()).collect()
+    });
+}
+
+/// Records the state of `challenger`, as a single row.
+pub(crate) fn record_challenger<F: RichField, H: Hasher<F>>(
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+    stage: TraceStage,
+    challenger: &Challenger<F, H>,
+) {
+    let width = SPONGE_WIDTH + challenger.input_buffer.len();
+    record_stage(trace, stage, width, || {
+        [&challenger.sponge_state[..], &challenger.input_buffer].concat()
+    });
+}
+
+/// Records the leaves of `tree`, one per row.
+pub(crate) fn record_leaves<F: RichField, H: Hasher<F>>(
+    trace: &mut Option<&mut dyn ProverTrace<F>>,
+    stage: TraceStage,
+    tree: &MerkleTree<F, H>,
+) {
+    let width = tree.leaves.first().map_or(0, |l| l.len());
+    record_stage(trace, stage, width, || tree.leaves.concat());
+}
+
+pub(crate) fn ext_values<F: RichField + Extendable<D>, const D: usize>(
+    values: &[F::Extension],
+) -> Vec<F> {
+    values.iter().flat_map(|v| v.to_basefield_array()).collect()
+}
+
+/// Logs the shape and the first row of each stage.
+#[derive(Copy, Clone, Debug)]
+pub struct LogTrace {
+    pub level: Level,
+}
+
+impl Default for LogTrace {
+    fn default() -> Self {
+        Self {
+            level: Level::Debug,
+        }
+    }
+}
+
+impl<F: RichField> ProverTrace<F> for LogTrace {
+    fn wants(&self, _stage: TraceStage) -> bool {
+        log::log_enabled!(self.level)
+    }
+
+    fn record(&mut self, stage: TraceStage, width: usize, values: &[F]) {
+        let first_row = &values[..width.min(values.len())];
+        log!(
+            self.level,
+            "{:?}: {} values, {} per row, first row: {:?}",
+            stage,
+            values.len(),
+            width,
+            first_row
+        );
+    }
+}
+
+/// The values recorded for a stage.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TraceEntry<F: RichField> {
+    pub stage: TraceStage,
+    pub width: usize,
+    pub values: Vec<F>,
+}
+
+/// Keeps the values of all the recorded stages, or only of some of them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TraceBundle<F: RichField> {
+    pub entries: Vec<TraceEntry<F>>,
+    /// The stages to record, or `None` to record all of them.
+    stages: Option<Vec<TraceStage>>,
+}
+
+impl<F: RichField> TraceBundle<F> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            stages: None,
+        }
+    }
+
+    /// A bundle recording only the given stages.
+    pub fn with_stages(stages: &[TraceStage]) -> Self {
+        Self {
+            entries: Vec::new(),
+            stages: Some(stages.to_vec()),
+        }
+    }
+
+    /// The first entry recorded for `stage`.
+    pub fn get(&self, stage: TraceStage) -> Option<&TraceEntry<F>> {
+        self.entries.iter().find(|e| e.stage == stage)
+    }
+
+    /// Compares the stages recorded by both bundles, in the order in which they were recorded in
+    /// `self`, and returns the first difference. Stages missing from either bundle are ignored.
+    pub fn first_divergence(&self, other: &Self) -> Option<TraceDivergence> {
+        self.entries.iter().find_map(|left| {
+            let right = other.get(left.stage)?;
+            if left.width != right.width || left.values.len() != right.values.len() {
+                return Some(TraceDivergence::Shape {
+                    stage: left.stage,
+                    left: (left.width, left.values.len()),
+                    right: (right.width, right.values.len()),
+                });
+            }
+            let index = left
+                .values
+                .iter()
+                .zip(&right.values)
+                .position(|(l, r)| l != r)?;
+            let width = left.width.max(1);
+            Some(TraceDivergence::Value {
+                stage: left.stage,
+                row: index / width,
+                column: index % width,
+                left: left.values[index].to_canonical_u64(),
+                right: right.values[index].to_canonical_u64(),
+            })
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.write_to(&mut buffer)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    fn write_to(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        buffer.write_all(&TRACE_BUNDLE_MAGIC)?;
+        buffer.write_u32(TRACE_BUNDLE_VERSION)?;
+        buffer.write_usize(self.entries.len())?;
+        for entry in &self.entries {
+            buffer.write_u8(entry.stage.tag())?;
+            buffer.write_usize(entry.width)?;
+            buffer.write_usize(entry.values.len())?;
+            buffer.write_field_vec(&entry.values)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a bundle written by `to_bytes`, failing if it was written by another version.
+    pub fn from_bytes(bytes: Vec<u8>) -> IoResult<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let mut magic = [0; 4];
+        buffer.read_exact(&mut magic)?;
+        if magic != TRACE_BUNDLE_MAGIC || buffer.read_u32()? != TRACE_BUNDLE_VERSION {
+            return Err(IoError);
+        }
+        let num_entries = buffer.read_usize()?;
+        let entries = (0..num_entries)
+            .map(|_| {
+                let stage = TraceStage::from_tag(buffer.read_u8()?)?;
+                let width = buffer.read_usize()?;
+                let len = buffer.read_usize()?;
+                let values = buffer.read_field_vec(len)?;
+                Ok(TraceEntry {
+                    stage,
+                    width,
+                    values,
+                })
+            })
+            .collect::<IoResult<_>>()?;
+        if !buffer.is_empty() {
+            return Err(IoError);
+        }
+        Ok(Self {
+            entries,
+            stages: None,
+        })
+    }
+
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(bytes).map_err(|_| anyhow::anyhow!("Invalid or outdated trace bundle"))
+    }
+}
+
+impl<F: RichField> ProverTrace<F> for TraceBundle<F> {
+    fn wants(&self, stage: TraceStage) -> bool {
+        match &self.stages {
+            Some(stages) => stages.contains(&stage),
+            None => true,
+        }
+    }
+
+    fn record(&mut self, stage: TraceStage, width: usize, values: &[F]) {
+        self.entries.push(TraceEntry {
+            stage,
+            width,
+            values: values.to_vec(),
+        });
+    }
+}
+
+/// The first difference between two [`TraceBundle`]s.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TraceDivergence {
+    /// The stage has different `(width, number of values)` in the two bundles.
+    Shape {
+        stage: TraceStage,
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    /// The first differing value of the stage, in canonical form.
+    Value {
+        stage: TraceStage,
+        row: usize,
+        column: usize,
+        left: u64,
+        right: u64,
+    },
+}
+
+impl TraceDivergence {
+    pub fn stage(&self) -> TraceStage {
+        match *self {
+            TraceDivergence::Shape { stage, .. } | TraceDivergence::Value { stage, .. } => stage,
+        }
+    }
+}
+
+impl Display for TraceDivergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TraceDivergence::Shape { stage, left, right } => write!(
+                f,
+                "{stage:?}: {} values in rows of {} vs {} values in rows of {}",
+                left.1, left.0, right.1, right.0
+            ),
+            TraceDivergence::Value {
+                stage,
+                row,
+                column,
+                left,
+                right,
+            } => write!(
+                f,
+                "{stage:?}: row {row}, column {column}: {left:#018x} vs {right:#018x}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::field::types::{Field, PrimeField64, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_trace_bundle_serialization() {
+        let mut bundle = TraceBundle::<F>::new();
+        bundle.record(TraceStage::Betas, 1, &F::rand_vec(2));
+        bundle.record(TraceStage::WiresLeaves, 3, &F::rand_vec(12));
+
+        let read = TraceBundle::from_bytes(bundle.to_bytes()).unwrap();
+        assert_eq!(read.entries, bundle.entries);
+
+        let mut bytes = bundle.to_bytes();
+        bytes[4] ^= 1;
+        assert!(TraceBundle::<F>::from_bytes(bytes).is_err());
+    }
+
+    #[test]
+    fn test_first_divergence() {
+        let leaves = F::rand_vec(12);
+        let mut left = TraceBundle::<F>::new();
+        left.record(TraceStage::Betas, 1, &[F::ONE]);
+        left.record(TraceStage::QuotientValues, 2, &[F::ONE; 4]);
+        left.record(TraceStage::WiresLeaves, 3, &leaves);
+
+        // Stages recorded by only one bundle are ignored.
+        let mut right = TraceBundle::with_stages(&[TraceStage::Betas, TraceStage::WiresLeaves]);
+        for entry in &left.entries {
+            if right.wants(entry.stage) {
+                right.record(entry.stage, entry.width, &entry.values);
+            }
+        }
+        assert_eq!(right.entries.len(), 2);
+        assert_eq!(left.first_divergence(&right), None);
+
+        let mut leaves2 = leaves.clone();
+        leaves2[7] += F::ONE;
+        right.entries[1].values = leaves2;
+        assert_eq!(
+            left.first_divergence(&right),
+            Some(TraceDivergence::Value {
+                stage: TraceStage::WiresLeaves,
+                row: 2,
+                column: 1,
+                left: leaves[7].to_canonical_u64(),
+                right: (leaves[7] + F::ONE).to_canonical_u64(),
+            })
+        );
+
+        right.entries[0].values.push(F::ONE);
+        assert_eq!(
+            left.first_divergence(&right).map(|d| d.stage()),
+            Some(TraceStage::Betas)
+        );
+    }
+}
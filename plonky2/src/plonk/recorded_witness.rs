@@ -0,0 +1,241 @@
+//! Witnesses saved after witness generation, to be proven again later without the application
+//! which filled the `PartialWitness`. A [`RecordedWitness`] also pins the values of random
+//! generators, so the replayed proofs are deterministic, e.g. to compare `prove` with `my_prove`.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::generate_partial_witness;
+use crate::iop::witness::{PartialWitness, PartitionWitness};
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, GenericHashOut};
+use crate::util::serialization::{Buffer, IoError, IoResult, Read, Remaining, Write};
+
+/// Version of the [`RecordedWitness`] encoding.
+pub const RECORDED_WITNESS_VERSION: u32 = 1;
+
+const RECORDED_WITNESS_MAGIC: [u8; 4] = *b"PWIT";
+
+/// A fully generated witness, i.e. the values of a `PartitionWitness`, along with the digest of
+/// the circuit it belongs to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordedWitness<F: RichField> {
+    /// The bytes of the circuit digest.
+    pub circuit_digest: Vec<u8>,
+    pub num_wires: usize,
+    pub degree: usize,
+    /// The values of the partitions, indexed like `PartitionWitness::values`.
+    pub values: Vec<Option<F>>,
+}
+
+impl<F: RichField> RecordedWitness<F> {
+    /// Runs the generators of the circuit on `inputs`, and records the resulting witness.
+    pub fn generate<C: GenericConfig<D, F = F>, const D: usize>(
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+        inputs: PartialWitness<F>,
+    ) -> Result<Self>
+    where
+        F: Extendable<D>,
+    {
+        let witness = generate_partial_witness(inputs, prover_data, common_data)?;
+        Ok(Self::new(prover_data, &witness))
+    }
+
+    pub fn new<C: GenericConfig<D, F = F>, const D: usize>(
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        witness: &PartitionWitness<F>,
+    ) -> Self
+    where
+        F: Extendable<D>,
+    {
+        Self {
+            circuit_digest: prover_data.circuit_digest.to_bytes(),
+            num_wires: witness.num_wires,
+            degree: witness.degree,
+            values: witness.values.clone(),
+        }
+    }
+
+    /// The recorded witness, as generated for the circuit of `prover_data`. Fails if it was
+    /// recorded for another circuit.
+    pub fn into_partition_witness<'a, C: GenericConfig<D, F = F>, const D: usize>(
+        self,
+        prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<PartitionWitness<'a, F>>
+    where
+        F: Extendable<D>,
+    {
+        ensure!(
+            self.circuit_digest == prover_data.circuit_digest.to_bytes(),
+            "The witness was recorded for another circuit"
+        );
+        ensure!(
+            self.values.len() == prover_data.representative_map.len(),
+            "The witness has {} targets, the circuit has {}",
+            self.values.len(),
+            prover_data.representative_map.len()
+        );
+        ensure!(
+            self.num_wires == common_data.config.num_wires && self.degree == common_data.degree(),
+            "The witness has {} wires and degree {}, the circuit has {} wires and degree {}",
+            self.num_wires,
+            self.degree,
+            common_data.config.num_wires,
+            common_data.degree()
+        );
+        Ok(PartitionWitness {
+            values: self.values,
+            representative_map: &prover_data.representative_map,
+            num_wires: self.num_wires,
+            degree: self.degree,
+            lookup_tables: &prover_data.lookup_tables,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.write_to(&mut buffer)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Writes the values as a bitmap of the set partitions, followed by their values.
+    fn write_to(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        buffer.write_all(&RECORDED_WITNESS_MAGIC)?;
+        buffer.write_u32(RECORDED_WITNESS_VERSION)?;
+        buffer.write_bytes(&self.circuit_digest)?;
+        buffer.write_usize(self.num_wires)?;
+        buffer.write_usize(self.degree)?;
+        buffer.write_usize(self.values.len())?;
+        let mut is_set = vec![0u8; self.values.len().div_ceil(8)];
+        for (i, value) in self.values.iter().enumerate() {
+            if value.is_some() {
+                is_set[i / 8] |= 1 << (i % 8);
+            }
+        }
+        buffer.write_all(&is_set)?;
+        let set_values = self.values.iter().flatten().copied().collect::<Vec<_>>();
+        buffer.write_field_vec(&set_values)
+    }
+
+    /// Reads a witness written by `to_bytes`, failing if it was written by another version.
+    pub fn from_bytes(bytes: Vec<u8>) -> IoResult<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let mut magic = [0; 4];
+        buffer.read_exact(&mut magic)?;
+        if magic != RECORDED_WITNESS_MAGIC || buffer.read_u32()? != RECORDED_WITNESS_VERSION {
+            return Err(IoError);
+        }
+        let digest_len = buffer.read_usize()?;
+        // Both lengths are checked against the input before allocating.
+        if digest_len > buffer.remaining() {
+            return Err(IoError);
+        }
+        let mut circuit_digest = vec![0u8; digest_len];
+        buffer.read_exact(&mut circuit_digest)?;
+        let num_wires = buffer.read_usize()?;
+        let degree = buffer.read_usize()?;
+        let num_values = buffer.read_usize()?;
+        if num_values.div_ceil(8) > buffer.remaining() {
+            return Err(IoError);
+        }
+        let mut is_set = vec![0u8; num_values.div_ceil(8)];
+        buffer.read_exact(&mut is_set)?;
+        let num_set = is_set.iter().map(|b| b.count_ones() as usize).sum();
+        let mut set_values = buffer.read_field_vec(num_set)?.into_iter();
+        let values = (0..num_values)
+            .map(|i| {
+                if is_set[i / 8] & (1 << (i % 8)) != 0 {
+                    set_values.next()
+                } else {
+                    None
+                }
+            })
+            .collect();
+        // A set padding bit leaves a value unread.
+        if set_values.next().is_some() || !buffer.is_empty() {
+            return Err(IoError);
+        }
+        Ok(Self {
+            circuit_digest,
+            num_wires,
+            degree,
+            values,
+        })
+    }
+
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(bytes).map_err(|_| anyhow::anyhow!("Invalid or outdated recorded witness"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::recorded_witness::RecordedWitness;
+
+    #[test]
+    fn replay_recorded_witness() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.exp_u64(x, 5);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, F::TWO);
+        let recorded = data.record_witness(inputs)?;
+        let replayed = RecordedWitness::from_bytes(recorded.to_bytes()).unwrap();
+        assert_eq!(replayed, recorded);
+
+        // A digest or number of values longer than the input is rejected before allocating.
+        let mut bytes = recorded.to_bytes();
+        bytes[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(RecordedWitness::<F>::from_bytes(bytes).is_err());
+        let mut bytes = recorded.to_bytes();
+        let num_values_offset = 4 + 4 + 8 + recorded.circuit_digest.len() + 8 + 8;
+        bytes[num_values_offset..num_values_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(RecordedWitness::<F>::from_bytes(bytes).is_err());
+
+        // The random wires are part of the witness, so replays give the same proof.
+        let proof = data.prove_from_witness(recorded)?;
+        assert_eq!(proof, data.prove_from_witness(replayed.clone())?);
+        assert_eq!(proof.public_inputs, vec![F::from_canonical_u64(32)]);
+        data.verify(proof)?;
+
+        let mut other_degree = replayed.clone();
+        other_degree.degree *= 2;
+        assert!(data.prove_from_witness(other_degree).is_err());
+
+        let mut other_circuit = replayed;
+        other_circuit.circuit_digest[0] ^= 1;
+        assert!(data.prove_from_witness(other_circuit).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+//! Estimates of the soundness of the proofs of a circuit, from its `CircuitConfig` and its size.
+//!
+//! `CircuitConfig::security_bits` is only a target. [`SecurityReport`] computes the security which
+//! the FRI parameters, the number of challenges and the extension degree give, and
+//! `CircuitBuilder::build` checks it against the target according to its [`SecurityPolicy`].
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use anyhow::{bail, Result};
+use log::warn;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_data::CommonCircuitData;
+
+/// What `CircuitBuilder::build` does when the conjectured security of the circuit is below
+/// `CircuitConfig::security_bits`.
+///
+/// Checking is opt-in: the standard configs reach their 100 bits in FRI, but with two challenges
+/// drawn from `F`, the permutation argument of their 80 routed wires falls below 100 bits for
+/// circuits of more than `2^7` rows, e.g. to about 91 bits for `2^12` rows.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum SecurityPolicy {
+    #[default]
+    Ignore,
+    /// Log a warning.
+    Warn,
+    /// Fail `CircuitBuilder::try_build`, which makes `CircuitBuilder::build` panic.
+    Fail,
+}
+
+impl SecurityPolicy {
+    /// Checks the security of the circuit against `CircuitConfig::security_bits`, according to
+    /// the policy.
+    pub fn check<F: RichField + Extendable<D>, const D: usize>(
+        self,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<()> {
+        if self == SecurityPolicy::Ignore {
+            return Ok(());
+        }
+        let report = SecurityReport::new(common_data);
+        if let Err(e) = report.validate(common_data.config.security_bits) {
+            if self == SecurityPolicy::Fail {
+                bail!("{e}\n{report}");
+            }
+            warn!("{e}\n{report}");
+        }
+        Ok(())
+    }
+}
+
+/// The security of each part of the protocol, in bits, i.e. `-log2` of its soundness error.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SecurityReport {
+    /// The security of the FRI queries and proof of work, under the conjecture of the ethSTARK
+    /// paper that each query gives `rate_bits` bits.
+    pub fri_conjectured_bits: f64,
+    /// The security of the FRI queries and proof of work in the unique decoding regime, where each
+    /// query gives `-log2((1 + rate) / 2)` bits.
+    pub fri_proven_bits: f64,
+    /// The security of the FRI commit phase, whose error is about `|L| * sum(arities) / |F_ext|`
+    /// for an LDE of size `|L|`.
+    pub fri_commit_phase_bits: f64,
+    /// The security of the permutation argument, repeated for each of the `num_challenges` pairs
+    /// of `betas` and `gammas`, which are drawn from `F`. The grand product runs over the
+    /// `num_routed_wires` columns of each row, so each repetition has an error of about
+    /// `num_routed_wires * degree / |F|`.
+    pub permutation_bits: f64,
+    /// The security of the random linear combination of the constraints by each of the
+    /// `num_challenges` `alphas`, which are drawn from `F`.
+    pub constraints_combination_bits: f64,
+    /// The security of checking the quotient identity at `zeta`, which is drawn from `F_ext`.
+    pub quotient_bits: f64,
+}
+
+impl SecurityReport {
+    pub fn new<F: RichField + Extendable<D>, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Self {
+        let config = &common_data.config;
+        let fri_params = &common_data.fri_params;
+        let rate_bits = config.fri_config.rate_bits;
+        let num_queries = config.fri_config.num_query_rounds as f64;
+        let pow_bits = config.fri_config.proof_of_work_bits as f64;
+        let num_challenges = config.num_challenges as f64;
+        let field_bits = log2(F::ORDER as u128);
+        let extension_field_bits = D as f64 * field_bits;
+
+        let sum_arities = fri_params
+            .reduction_arity_bits
+            .iter()
+            .map(|&arity_bits| 1 << arity_bits)
+            .sum::<u128>();
+        let fri_commit_phase_bits =
+            extension_field_bits - fri_params.lde_bits() as f64 - log2(sum_arities.max(1));
+        // A query fails to catch a cheating prover with probability `(1 + rate) / 2`, i.e.
+        // `(2^rate_bits + 1) / 2^(rate_bits + 1)`.
+        let proven_query_bits = (rate_bits + 1) as f64 - log2((1 << rate_bits) + 1);
+
+        let num_combined_terms = config.num_challenges * (common_data.num_partial_products + 2)
+            + common_data.num_gate_constraints
+            + common_data.lookup_polys_range().len();
+        let vanishing_degree = common_data.quotient_degree() + common_data.degree();
+
+        Self {
+            fri_conjectured_bits: (num_queries * rate_bits as f64 + pow_bits)
+                .min(fri_commit_phase_bits),
+            fri_proven_bits: (num_queries * proven_query_bits + pow_bits)
+                .min(fri_commit_phase_bits),
+            fri_commit_phase_bits,
+            permutation_bits: num_challenges
+                * (field_bits
+                    - common_data.degree_bits() as f64
+                    - log2(config.num_routed_wires as u128)),
+            constraints_combination_bits: num_challenges
+                * (field_bits - log2(num_combined_terms as u128)),
+            quotient_bits: extension_field_bits - log2(vanishing_degree as u128),
+        }
+    }
+
+    /// The security of the whole protocol, using the conjectured security of FRI.
+    pub fn conjectured_bits(&self) -> f64 {
+        self.fri_conjectured_bits.min(self.plonk_bits())
+    }
+
+    /// The security of the whole protocol, using the proven security of FRI.
+    pub fn proven_bits(&self) -> f64 {
+        self.fri_proven_bits.min(self.plonk_bits())
+    }
+
+    fn plonk_bits(&self) -> f64 {
+        self.permutation_bits
+            .min(self.constraints_combination_bits)
+            .min(self.quotient_bits)
+    }
+
+    /// Fails if the conjectured security is below `security_bits`, listing the parts which fall
+    /// short.
+    pub fn validate(&self, security_bits: usize) -> Result<()> {
+        let target = security_bits as f64;
+        let shortfalls = [
+            ("FRI", self.fri_conjectured_bits),
+            ("permutation argument", self.permutation_bits),
+            ("constraints combination", self.constraints_combination_bits),
+            ("quotient check", self.quotient_bits),
+        ]
+        .into_iter()
+        .filter(|&(_, bits)| bits < target)
+        .map(|(part, bits)| format!("{part} ({bits:.1} bits)"))
+        .collect::<Vec<String>>();
+        if !shortfalls.is_empty() {
+            bail!(
+                "The circuit falls short of {security_bits} bits of security: {}",
+                shortfalls.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Display for SecurityReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "security: {:.1} bits conjectured, {:.1} bits proven",
+            self.conjectured_bits(),
+            self.proven_bits()
+        )?;
+        writeln!(
+            f,
+            "  FRI conjectured: {:.1} bits",
+            self.fri_conjectured_bits
+        )?;
+        writeln!(f, "  FRI proven: {:.1} bits", self.fri_proven_bits)?;
+        writeln!(
+            f,
+            "  FRI commit phase: {:.1} bits",
+            self.fri_commit_phase_bits
+        )?;
+        writeln!(
+            f,
+            "  permutation argument: {:.1} bits",
+            self.permutation_bits
+        )?;
+        writeln!(
+            f,
+            "  constraints combination: {:.1} bits",
+            self.constraints_combination_bits
+        )?;
+        write!(f, "  quotient check: {:.1} bits", self.quotient_bits)
+    }
+}
+
+/// The base-2 logarithm of `x > 0`, to about 9 decimal places. `f64::log2` needs `std`.
+fn log2(x: u128) -> f64 {
+    let integer_part = 127 - x.leading_zeros();
+    // The mantissa, in `[1, 2)`, whose logarithm is computed bit by bit by squaring it.
+    let mut y = x as f64 / (1u128 << integer_part) as f64;
+    let mut result = integer_part as f64;
+    let mut bit = 0.5;
+    for _ in 0..32 {
+        y *= y;
+        if y >= 2.0 {
+            y /= 2.0;
+            result += bit;
+        }
+        bit /= 2.0;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::types::Field64;
+    use crate::gates::noop::NoopGate;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::security::{log2, SecurityPolicy, SecurityReport};
+
+    #[test]
+    fn test_log2() {
+        assert_eq!(log2(1), 0.0);
+        assert_eq!(log2(1 << 100), 100.0);
+        assert!((log2(3) - 1.584962500721156).abs() < 1e-9);
+        assert!((log2(u64::MAX as u128) - 64.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn standard_recursion_config() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        builder.register_public_input(x);
+        let data = builder.build::<C>();
+
+        let report = SecurityReport::new(&data.common);
+        // 28 queries with rate 1/8, and 16 bits of proof of work.
+        assert!((report.fri_conjectured_bits - 100.0).abs() < 1e-6);
+        assert!(report.fri_proven_bits < 40.0);
+        assert_eq!(report.conjectured_bits(), 100.0);
+        assert!(report.validate(100).is_ok());
+        assert!(report.validate(101).is_err());
+        // Two challenges, each of error `num_routed_wires * degree / |F|`.
+        let permutation_bits =
+            2.0 * (log2(F::ORDER as u128) - data.common.degree_bits() as f64 - log2(80));
+        assert!((report.permutation_bits - permutation_bits).abs() < 1e-6);
+    }
+
+    #[test]
+    fn security_policies() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let build = |num_rows, policy| {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let x = builder.add_virtual_target();
+            builder.register_public_input(x);
+            for _ in 0..num_rows {
+                builder.add_gate(NoopGate, vec![]);
+            }
+            builder.set_security_policy(policy);
+            builder.try_build::<C>()
+        };
+        assert!(build(0, SecurityPolicy::Fail).is_ok());
+        // With `2^9` rows, the permutation argument only gives about 97 bits.
+        assert!(build(300, SecurityPolicy::default()).is_ok());
+        assert!(build(300, SecurityPolicy::Warn).is_ok());
+        let err = build(300, SecurityPolicy::Fail).err().unwrap();
+        assert!(err.to_string().contains("permutation argument"));
+    }
+}
@@ -1,10 +1,14 @@
 use alloc::vec::Vec;
 use alloc::{format, vec};
+use core::ops::Index;
 
 use crate::field::batch_util::batch_add_inplace;
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::types::Field;
 use crate::field::zero_poly_coset::ZeroPolyOnCoset;
+use crate::gates::gate::{compute_filter, compute_filter_circuit};
+use crate::gates::lookup::{LookupGate, LOOKUP_DEGREE};
+use crate::gates::lookup_table::LookupTableGate;
 use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
@@ -33,9 +37,12 @@ pub(crate) fn eval_vanishing_poly<
     local_zs: &[F::Extension],
     next_zs: &[F::Extension],
     partial_products: &[F::Extension],
+    lookup_polys: &[F::Extension],
+    next_lookup_zs: &[F::Extension],
     s_sigmas: &[F::Extension],
     betas: &[F],
     gammas: &[F],
+    deltas: &[F],
     alphas: &[F],
 ) -> Vec<F::Extension> {
     let max_degree = common_data.quotient_degree_factor;
@@ -85,10 +92,21 @@ pub(crate) fn eval_vanishing_poly<
         vanishing_partial_products_terms.extend(partial_product_checks);
     }
 
+    let lookup_terms = eval_lookup_terms(
+        common_data,
+        common_data.lookup_gate_indices(),
+        vars.local_constants,
+        vars.local_wires,
+        lookup_polys,
+        next_lookup_zs,
+        deltas,
+    );
+
     let vanishing_terms = [
         vanishing_z_1_terms,
         vanishing_partial_products_terms,
         constraint_terms,
+        lookup_terms,
     ]
     .concat();
 
@@ -96,7 +114,8 @@ pub(crate) fn eval_vanishing_poly<
     plonk_common::reduce_with_powers_multi(&vanishing_terms, alphas)
 }
 
-/// Like `eval_vanishing_poly`, but specialized for base field points. Batched.
+/// Like `eval_vanishing_poly`, but specialized for base field points. Batched. Only the constraints
+/// of the gates at the indices `gate_rows` of `common_data.gates` are included.
 pub(crate) fn eval_vanishing_poly_base_batch<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -109,11 +128,15 @@ pub(crate) fn eval_vanishing_poly_base_batch<
     local_zs_batch: &[&[F]],
     next_zs_batch: &[&[F]],
     partial_products_batch: &[&[F]],
+    lookup_polys_batch: &[&[F]],
+    next_lookup_zs_batch: &[&[F]],
     s_sigmas_batch: &[&[F]],
     betas: &[F],
     gammas: &[F],
+    deltas: &[F],
     alphas: &[F],
     z_h_on_coset: &ZeroPolyOnCoset<F>,
+    gate_rows: &[usize],
 ) -> Vec<Vec<F>> {
     let n = indices_batch.len();
     assert_eq!(xs_batch.len(), n);
@@ -121,6 +144,8 @@ pub(crate) fn eval_vanishing_poly_base_batch<
     assert_eq!(local_zs_batch.len(), n);
     assert_eq!(next_zs_batch.len(), n);
     assert_eq!(partial_products_batch.len(), n);
+    assert_eq!(lookup_polys_batch.len(), n);
+    assert_eq!(next_lookup_zs_batch.len(), n);
     assert_eq!(s_sigmas_batch.len(), n);
 
     let max_degree = common_data.quotient_degree_factor;
@@ -128,12 +153,16 @@ pub(crate) fn eval_vanishing_poly_base_batch<
 
     let num_gate_constraints = common_data.num_gate_constraints;
 
-    let constraint_terms_batch =
-        evaluate_gate_constraints_base_batch::<F, C, D>(common_data, vars_batch, indices_batch);
+    let constraint_terms_batch = evaluate_gate_constraints_base_batch_for_rows::<F, C, D>(
+        common_data,
+        vars_batch,
+        gate_rows,
+    );
     debug_assert!(constraint_terms_batch.len() == n * num_gate_constraints);
 
     let num_challenges = common_data.config.num_challenges;
     let num_routed_wires = common_data.config.num_routed_wires;
+    let lookup_gates = common_data.lookup_gate_indices();
 
     let mut numerator_values = Vec::with_capacity(num_routed_wires);
     let mut denominator_values = Vec::with_capacity(num_routed_wires);
@@ -154,17 +183,8 @@ pub(crate) fn eval_vanishing_poly_base_batch<
         let s_sigmas = s_sigmas_batch[k];
 
         let constraint_terms = PackedStridedView::new(&constraint_terms_batch, n, k);
-        if (index == 1048576 ) {
-            let  res = constraint_terms.iter().map(|f| *f).collect::<Vec<_>>();
-            println!("i: {}, constraint_terms: {:?}", index, res);
-        }
 
         let l_0_x = z_h_on_coset.eval_l_0(index, x);
-        if index == 1048576 {
-            let ev = z_h_on_coset.eval(index);
-            let den = (z_h_on_coset.n * (x - F::ONE));
-            println!("l_0_x: {:?}, ev: {:?}, den: {:?}, denv: {:?}", l_0_x, ev, den, den.inverse());
-        }
         for i in 0..num_challenges {
             let z_x = local_zs[i];
             let z_gx = next_zs[i];
@@ -174,11 +194,7 @@ pub(crate) fn eval_vanishing_poly_base_batch<
                 let wire_value = vars.local_wires[j];
                 let k_i = common_data.k_is[j];
                 let s_id = k_i * x;
-                let v = wire_value + betas[i] * s_id + gammas[i];
-                // if index == 1048576 {
-                //     println!("i: {}, wi: {}, wire_value: {:?}, ki: {:?}, x: {:?}, v: {:?}", index, j, wire_value, k_i, x, v);
-                // }
-                v
+                wire_value + betas[i] * s_id + gammas[i]
             }));
             denominator_values.extend((0..num_routed_wires).map(|j| {
                 let wire_value = vars.local_wires[j];
@@ -199,23 +215,25 @@ pub(crate) fn eval_vanishing_poly_base_batch<
             );
             vanishing_partial_products_terms.extend(partial_product_checks);
 
-            // if (index == 1048576 ) {
-            //     println!("i: {}, numerator_values: {:?}", index, numerator_values);
-            //     println!("i: {}, denominator_values: {:?}", index, denominator_values);
-            //     println!("i: {}, partial_product_checks: {:?}", index, partial_product_checks);
-            // }
-
             numerator_values.clear();
             denominator_values.clear();
         }
 
-        if (index == 1048576 ) {
-            println!("i: {}, term: {:?}", index, vanishing_partial_products_terms);
-        }
+        let lookup_terms = eval_lookup_terms(
+            common_data,
+            lookup_gates,
+            &vars.local_constants,
+            &vars.local_wires,
+            lookup_polys_batch[k],
+            next_lookup_zs_batch[k],
+            deltas,
+        );
+
         let vanishing_terms = vanishing_z_1_terms
             .iter()
             .chain(vanishing_partial_products_terms.iter())
-            .chain(constraint_terms);
+            .chain(constraint_terms)
+            .chain(lookup_terms.iter());
         let res = plonk_common::reduce_with_powers_multi(vanishing_terms, alphas);
         res_batch.push(res);
 
@@ -225,6 +243,116 @@ pub(crate) fn eval_vanishing_poly_base_batch<
     res_batch
 }
 
+/// Evaluates the terms of the lookup argument. For each pair `(alpha, delta)` of `deltas`, a slot
+/// `(input, output)` of a row of table `kappa` is compressed into
+/// `c = input + alpha * (output + alpha * kappa)`, and weighted by `1` on the rows of `LookupGate`s
+/// and by `-multiplicity` on the rows of `LookupTableGate`s. Each helper `h` holds the sum of
+/// `weight / (delta - c)` over `LOOKUP_DEGREE` slots, and the running sum `Z_L` adds up the helpers
+/// of each row. As it starts at 0 and wraps around to 0, the lookups match the tables.
+///
+/// The terms are the checks of the helpers, then the check of `Z_L`, for each pair.
+fn eval_lookup_terms<F, const D: usize, K, V>(
+    common_data: &CommonCircuitData<F, D>,
+    lookup_gates: (Option<usize>, Option<usize>),
+    local_constants: &V,
+    local_wires: &V,
+    lookup_polys: &[K],
+    next_lookup_zs: &[K],
+    deltas: &[F],
+) -> Vec<K>
+where
+    F: RichField + Extendable<D>,
+    K: Field + From<F>,
+    V: Index<usize, Output = K> + ?Sized,
+{
+    if !common_data.has_lookups() {
+        return Vec::new();
+    }
+    let num_slots = common_data.num_lookup_slots;
+    let num_challenges = common_data.config.num_challenges;
+    let num_helpers = common_data.num_lookup_helpers();
+    let table_gate = LookupTableGate { num_slots };
+
+    let lookup_filter = normalized_filter(common_data, lookup_gates.0, local_constants);
+    let table_filter = normalized_filter(common_data, lookup_gates.1, local_constants);
+    let kappa = local_constants[common_data.selectors_info.num_selectors()];
+
+    let mut terms = Vec::with_capacity(num_challenges * (num_helpers + 1));
+    for i in 0..num_challenges {
+        let alpha = K::from(deltas[2 * i]);
+        let delta = K::from(deltas[2 * i + 1]);
+        let helpers = &lookup_polys
+            [num_challenges + i * num_helpers..num_challenges + (i + 1) * num_helpers];
+
+        for (j, &helper) in helpers.iter().enumerate() {
+            let slots = j * LOOKUP_DEGREE..((j + 1) * LOOKUP_DEGREE).min(num_slots);
+            let denominators = slots
+                .clone()
+                .map(|s| {
+                    let input = local_wires[LookupGate::wire_input(s)];
+                    let output = local_wires[LookupGate::wire_output(s)];
+                    delta - (input + alpha * (output + alpha * kappa))
+                })
+                .collect::<Vec<_>>();
+            let numerators = slots
+                .map(|s| {
+                    lookup_filter - table_filter * local_wires[table_gate.wire_multiplicity(s)]
+                })
+                .collect::<Vec<_>>();
+            let sum = (0..numerators.len())
+                .map(|k| {
+                    numerators[k]
+                        * denominators
+                            .iter()
+                            .enumerate()
+                            .filter(|&(l, _)| l != k)
+                            .map(|(_, &d)| d)
+                            .product::<K>()
+                })
+                .sum::<K>();
+            terms.push(helper * denominators.into_iter().product::<K>() - sum);
+        }
+
+        let z_x = lookup_polys[i];
+        let z_gx = next_lookup_zs[i];
+        terms.push(z_gx - z_x - helpers.iter().copied().sum::<K>());
+    }
+    terms
+}
+
+/// The filter of the gate at index `gate` of `common_data.gates`, normalized to be 1 on its rows,
+/// or 0 if the gate is unused.
+fn normalized_filter<F, const D: usize, K, V>(
+    common_data: &CommonCircuitData<F, D>,
+    gate: Option<usize>,
+    local_constants: &V,
+) -> K
+where
+    F: RichField + Extendable<D>,
+    K: Field + From<F>,
+    V: Index<usize, Output = K> + ?Sized,
+{
+    let Some(gate) = gate else {
+        return K::ZERO;
+    };
+    let selector_index = common_data.selectors_info.selector_indices[gate];
+    let group_range = common_data.selectors_info.groups[selector_index].clone();
+    let many_selectors = common_data.selectors_info.num_selectors() > 1;
+    let normalization = compute_filter(
+        gate,
+        group_range.clone(),
+        F::from_canonical_usize(gate),
+        many_selectors,
+    )
+    .inverse();
+    compute_filter(
+        gate,
+        group_range,
+        local_constants[selector_index],
+        many_selectors,
+    ) * K::from(normalization)
+}
+
 /// Evaluates all gate constraints.
 ///
 /// `num_gate_constraints` is the largest number of constraints imposed by any gate. It is not
@@ -259,30 +387,24 @@ pub fn evaluate_gate_constraints<
     constraints
 }
 
-/// Evaluate all gate constraints in the base field.
+/// Evaluate the constraints of the gates at the given indices of `common_data.gates` in the base
+/// field.
 ///
 /// Returns a vector of `num_gate_constraints * vars_batch.len()` field elements. The constraints
 /// corresponding to `vars_batch[i]` are found in `result[i], result[vars_batch.len() + i],
 /// result[2 * vars_batch.len() + i], ...`.
-pub fn evaluate_gate_constraints_base_batch<
+pub(crate) fn evaluate_gate_constraints_base_batch_for_rows<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     const D: usize,
 >(
     common_data: &CommonCircuitData<F, D>,
     vars_batch: EvaluationVarsBaseBatch<F>,
-    indices_batch: &[usize],
+    rows: &[usize],
 ) -> Vec<F> {
     let mut constraints_batch = vec![F::ZERO; common_data.num_gate_constraints * vars_batch.len()];
-    if indices_batch[25] == 1048576 {
-        let vars_view = vars_batch.view(25);
-        let res = vars_view.local_constants.iter().map(|f| *f).collect::<Vec<_>>();
-        println!("i: {}, local_constants: {:?}", indices_batch[25], res);
-        let res = vars_view.local_wires.iter().map(|f| *f).collect::<Vec<_>>();
-        println!("i: {}, local_wires: {:?}",     indices_batch[25], res);
-
-    }
-    for (i, gate) in common_data.gates.iter().enumerate() {
+    for &i in rows {
+        let gate = &common_data.gates[i];
         let selector_index = common_data.selectors_info.selector_indices[i];
         let gate_constraints_batch = gate.0.eval_filtered_base_batch(
             vars_batch,
@@ -290,7 +412,6 @@ pub fn evaluate_gate_constraints_base_batch<
             selector_index,
             common_data.selectors_info.groups[selector_index].clone(),
             common_data.selectors_info.num_selectors(),
-            indices_batch,
         );
         debug_assert!(
             gate_constraints_batch.len() <= constraints_batch.len(),
@@ -334,6 +455,116 @@ pub fn evaluate_gate_constraints_circuit<
     all_gate_constraints
 }
 
+/// Like `eval_lookup_terms`, but in the circuit.
+fn eval_lookup_terms_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+    vars: EvaluationTargets<D>,
+    lookup_polys: &[ExtensionTarget<D>],
+    next_lookup_zs: &[ExtensionTarget<D>],
+    deltas: &[Target],
+) -> Vec<ExtensionTarget<D>> {
+    if !common_data.has_lookups() {
+        return Vec::new();
+    }
+    let num_slots = common_data.num_lookup_slots;
+    let num_challenges = common_data.config.num_challenges;
+    let num_helpers = common_data.num_lookup_helpers();
+    let table_gate = LookupTableGate { num_slots };
+
+    let (lookup_gate, table_gate_index) = common_data.lookup_gate_indices();
+    let lookup_filter = normalized_filter_circuit(builder, common_data, lookup_gate, vars);
+    let table_filter = normalized_filter_circuit(builder, common_data, table_gate_index, vars);
+    let kappa = vars.local_constants[common_data.selectors_info.num_selectors()];
+
+    let numerators = (0..num_slots)
+        .map(|s| {
+            let multiplicity = vars.local_wires[table_gate.wire_multiplicity(s)];
+            builder.arithmetic_extension(
+                F::NEG_ONE,
+                F::ONE,
+                table_filter,
+                multiplicity,
+                lookup_filter,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut terms = Vec::with_capacity(num_challenges * (num_helpers + 1));
+    for i in 0..num_challenges {
+        let alpha = builder.convert_to_ext(deltas[2 * i]);
+        let delta = builder.convert_to_ext(deltas[2 * i + 1]);
+        let helpers = &lookup_polys
+            [num_challenges + i * num_helpers..num_challenges + (i + 1) * num_helpers];
+
+        let denominators = (0..num_slots)
+            .map(|s| {
+                let input = vars.local_wires[LookupGate::wire_input(s)];
+                let output = vars.local_wires[LookupGate::wire_output(s)];
+                let c = builder.mul_add_extension(alpha, kappa, output);
+                let c = builder.mul_add_extension(alpha, c, input);
+                builder.sub_extension(delta, c)
+            })
+            .collect::<Vec<_>>();
+
+        for (j, &helper) in helpers.iter().enumerate() {
+            let slots = j * LOOKUP_DEGREE..((j + 1) * LOOKUP_DEGREE).min(num_slots);
+            let sum_terms = slots
+                .clone()
+                .map(|k| {
+                    let factors = slots
+                        .clone()
+                        .filter(|&l| l != k)
+                        .map(|l| denominators[l])
+                        .chain([numerators[k]])
+                        .collect::<Vec<_>>();
+                    builder.mul_many_extension(factors)
+                })
+                .collect::<Vec<_>>();
+            let sum = builder.add_many_extension(sum_terms);
+            let product = builder.mul_many_extension(&denominators[slots]);
+            terms.push(builder.mul_sub_extension(helper, product, sum));
+        }
+
+        let z_x = lookup_polys[i];
+        let z_gx = next_lookup_zs[i];
+        let helpers_sum = builder.add_many_extension(helpers);
+        let diff = builder.sub_extension(z_gx, z_x);
+        terms.push(builder.sub_extension(diff, helpers_sum));
+    }
+    terms
+}
+
+/// Like `normalized_filter`, but in the circuit.
+fn normalized_filter_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+    gate: Option<usize>,
+    vars: EvaluationTargets<D>,
+) -> ExtensionTarget<D> {
+    let Some(gate) = gate else {
+        return builder.zero_extension();
+    };
+    let selector_index = common_data.selectors_info.selector_indices[gate];
+    let group_range = common_data.selectors_info.groups[selector_index].clone();
+    let many_selectors = common_data.selectors_info.num_selectors() > 1;
+    let normalization = compute_filter(
+        gate,
+        group_range.clone(),
+        F::from_canonical_usize(gate),
+        many_selectors,
+    )
+    .inverse();
+    let filter = compute_filter_circuit(
+        builder,
+        gate,
+        group_range,
+        vars.local_constants[selector_index],
+        many_selectors,
+    );
+    builder.mul_const_extension(normalization, filter)
+}
+
 /// Evaluate the vanishing polynomial at `x`. In this context, the vanishing polynomial is a random
 /// linear combination of gate constraints, plus some other terms relating to the permutation
 /// argument. All such terms should vanish on `H`.
@@ -353,9 +584,12 @@ pub(crate) fn eval_vanishing_poly_circuit<
     local_zs: &[ExtensionTarget<D>],
     next_zs: &[ExtensionTarget<D>],
     partial_products: &[ExtensionTarget<D>],
+    lookup_polys: &[ExtensionTarget<D>],
+    next_lookup_zs: &[ExtensionTarget<D>],
     s_sigmas: &[ExtensionTarget<D>],
     betas: &[Target],
     gammas: &[Target],
+    deltas: &[Target],
     alphas: &[Target],
 ) -> Vec<ExtensionTarget<D>> {
     let max_degree = common_data.quotient_degree_factor;
@@ -421,10 +655,24 @@ pub(crate) fn eval_vanishing_poly_circuit<
         vanishing_partial_products_terms.extend(partial_product_checks);
     }
 
+    let lookup_terms = with_context!(
+        builder,
+        "evaluate lookup terms",
+        eval_lookup_terms_circuit(
+            builder,
+            common_data,
+            vars,
+            lookup_polys,
+            next_lookup_zs,
+            deltas
+        )
+    );
+
     let vanishing_terms = [
         vanishing_z_1_terms,
         vanishing_partial_products_terms,
         constraint_terms,
+        lookup_terms,
     ]
     .concat();
 
@@ -58,6 +58,8 @@ pub(crate) fn verify_with_challenges<
     let next_zs = &proof.openings.plonk_zs_next;
     let s_sigmas = &proof.openings.plonk_sigmas;
     let partial_products = &proof.openings.partial_products;
+    let lookup_polys = &proof.openings.lookup_polys;
+    let next_lookup_zs = &proof.openings.lookup_zs_next;
 
     // Evaluate the vanishing polynomial at our challenge point, zeta.
     let vanishing_polys_zeta = eval_vanishing_poly::<F, C, D>(
@@ -67,9 +69,12 @@ pub(crate) fn verify_with_challenges<
         local_zs,
         next_zs,
         partial_products,
+        lookup_polys,
+        next_lookup_zs,
         s_sigmas,
         &challenges.plonk_betas,
         &challenges.plonk_gammas,
+        &challenges.plonk_deltas,
         &challenges.plonk_alphas,
     );
 
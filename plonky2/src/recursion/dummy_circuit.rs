@@ -1,3 +1,4 @@
+use alloc::string::String;
 use alloc::vec;
 
 use hashbrown::HashMap;
@@ -15,6 +16,7 @@ use crate::plonk::circuit_data::{
 };
 use crate::plonk::config::{AlgebraicHasher, GenericConfig};
 use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use crate::util::serialization::{Buffer, IoError, IoResult};
 
 /// Creates a dummy proof which is suitable for use as a base proof in a cyclic recursion tree.
 /// Such a base proof will not actually be verified, so most of its data is arbitrary. However, its
@@ -156,4 +158,18 @@ where
         out_buffer.set_proof_with_pis_target(&self.proof_with_pis_target, &self.proof_with_pis);
         out_buffer.set_verifier_data_target(&self.verifier_data_target, &self.verifier_data);
     }
+
+    fn id(&self) -> String {
+        "DummyProofGenerator".into()
+    }
+
+    /// The dummy proof can only be read back with the common data of the dummy circuit, which is
+    /// not available to a generator, so this generator is not serializable.
+    fn serialize(&self, _dst: &mut Vec<u8>) -> IoResult<()> {
+        Err(IoError)
+    }
+
+    fn deserialize(_src: &mut Buffer) -> IoResult<Self> {
+        Err(IoError)
+    }
 }
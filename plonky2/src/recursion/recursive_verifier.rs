@@ -68,6 +68,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let next_zs = &proof.openings.plonk_zs_next;
         let s_sigmas = &proof.openings.plonk_sigmas;
         let partial_products = &proof.openings.partial_products;
+        let lookup_polys = &proof.openings.lookup_polys;
+        let next_lookup_zs = &proof.openings.lookup_zs_next;
 
         let zeta_pow_deg =
             self.exp_power_of_2_extension(challenges.plonk_zeta, inner_common_data.degree_bits());
@@ -83,9 +85,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
                 local_zs,
                 next_zs,
                 partial_products,
+                lookup_polys,
+                next_lookup_zs,
                 s_sigmas,
                 &challenges.plonk_betas,
                 &challenges.plonk_gammas,
+                &challenges.plonk_deltas,
                 &challenges.plonk_alphas,
             )
         );
@@ -177,6 +182,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             plonk_zs: self.add_virtual_extension_targets(num_challenges),
             plonk_zs_next: self.add_virtual_extension_targets(num_challenges),
             partial_products: self.add_virtual_extension_targets(total_partial_products),
+            lookup_polys: self
+                .add_virtual_extension_targets(common_data.lookup_polys_range().len()),
+            lookup_zs_next: self.add_virtual_extension_targets(common_data.lookup_zs_range().len()),
             quotient_polys: self.add_virtual_extension_targets(common_data.num_quotient_polys()),
         }
     }
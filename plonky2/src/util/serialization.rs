@@ -1,27 +1,52 @@
+pub mod gate_serialization;
+pub mod generator_serialization;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::convert::Infallible;
 use core::fmt::{Debug, Display, Formatter};
 use core::mem::size_of;
+use core::ops::RangeInclusive;
 
 use hashbrown::HashMap;
 
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::polynomial::PolynomialCoeffs;
 use crate::field::types::{Field64, PrimeField64};
+use crate::fri::oracle::PolynomialBatch;
 use crate::fri::proof::{
     CompressedFriProof, CompressedFriQueryRounds, FriInitialTreeProof, FriProof, FriQueryRound,
     FriQueryStep,
 };
+use crate::fri::reduction_strategies::FriReductionStrategy;
+use crate::fri::{FriConfig, FriParams};
+use crate::gadgets::lookup::ProverLookupTable;
+use crate::gates::gate::{gate_name, GateRef};
+use crate::gates::lookup_table::LookupTable;
+use crate::gates::selectors::SelectorsInfo;
 use crate::hash::hash_types::RichField;
 use crate::hash::merkle_proofs::MerkleProof;
-use crate::hash::merkle_tree::MerkleCap;
-use crate::plonk::circuit_data::CommonCircuitData;
+use crate::hash::merkle_tree::{MerkleCap, MerkleTree};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::WitnessGenerator;
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::wire::Wire;
+use crate::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, ProverOnlyCircuitData, TargetOrigins,
+    VerifierOnlyCircuitData,
+};
 use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::plonk_common::salt_size;
 use crate::plonk::proof::{
     CompressedProof, CompressedProofWithPublicInputs, OpeningSet, Proof, ProofWithPublicInputs,
 };
+use crate::util::log2_strict;
+use crate::util::serialization::gate_serialization::GateSerializer;
+use crate::util::serialization::generator_serialization::WitnessGeneratorSerializer;
 
 /// A no_std compatible variant of `std::io::Error`
 #[derive(Debug)]
@@ -36,6 +61,13 @@ impl Display for IoError {
 /// A no_std compatible variant of `std::io::Result`
 pub type IoResult<T> = Result<T, IoError>;
 
+/// The `log2` of the largest number of wires of a gate read from bytes.
+pub const MAX_GATE_WIRES_BITS: usize = 16;
+
+/// The largest number of wires of a gate read from bytes, which also bounds its number of
+/// operations or copies. The gates of a circuit are further checked to fit its config.
+pub const MAX_GATE_WIRES: usize = 1 << MAX_GATE_WIRES_BITS;
+
 /// A `Read` which is able to report how many bytes are remaining.
 pub trait Remaining: Read {
     /// Returns the number of bytes remaining in the buffer.
@@ -47,6 +79,28 @@ pub trait Remaining: Read {
     }
 }
 
+/// Whether the groups of `selectors_info` partition the `num_gates` gates into contiguous ranges,
+/// and each gate is assigned the group that contains it.
+fn selectors_fit_gates(selectors_info: &SelectorsInfo, num_gates: usize) -> bool {
+    let SelectorsInfo {
+        selector_indices,
+        groups,
+    } = selectors_info;
+    let mut end = 0;
+    for group in groups {
+        if group.start != end || group.end < group.start {
+            return false;
+        }
+        end = group.end;
+    }
+    end == num_gates
+        && selector_indices.len() == num_gates
+        && selector_indices
+            .iter()
+            .enumerate()
+            .all(|(i, &group)| group < groups.len() && groups[group].contains(&i))
+}
+
 /// Similar to `std::io::Read`, but works with no_std.
 pub trait Read {
     /// Reads exactly the length of `bytes` from `self` and writes it to `bytes`.
@@ -68,6 +122,67 @@ pub trait Read {
         Ok(u32::from_le_bytes(buf))
     }
 
+    /// Reads a `bool` value from `self`.
+    #[inline]
+    fn read_bool(&mut self) -> IoResult<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(IoError),
+        }
+    }
+
+    /// Reads a `usize` value, written as a `u64`, from `self`.
+    #[inline]
+    fn read_usize(&mut self) -> IoResult<usize> {
+        let mut buf = [0; size_of::<u64>()];
+        self.read_exact(&mut buf)?;
+        usize::try_from(u64::from_le_bytes(buf)).map_err(|_| IoError)
+    }
+
+    /// Reads a `usize` value from `self`, failing if it isn't in `range`. Sizes of gates are read
+    /// with it, so that the wire indices computed from them can't overflow.
+    #[inline]
+    fn read_usize_in(&mut self, range: RangeInclusive<usize>) -> IoResult<usize> {
+        let value = self.read_usize()?;
+        if !range.contains(&value) {
+            return Err(IoError);
+        }
+        Ok(value)
+    }
+
+    /// Reads a length-prefixed vector of `usize` values from `self`.
+    #[inline]
+    fn read_usize_vec(&mut self) -> IoResult<Vec<usize>> {
+        let length = self.read_usize()?;
+        (0..length).map(|_| self.read_usize()).collect()
+    }
+
+    /// Reads a length-prefixed byte string from `self`.
+    ///
+    /// The length prefix is untrusted, so the bytes are read in bounded chunks rather than
+    /// allocated upfront: a length beyond the end of the input fails without allocating more
+    /// than the input holds.
+    #[inline]
+    fn read_bytes(&mut self) -> IoResult<Vec<u8>> {
+        const CHUNK_SIZE: usize = 4096;
+        let length = self.read_usize()?;
+        let mut bytes = Vec::with_capacity(length.min(CHUNK_SIZE));
+        let mut chunk = [0; CHUNK_SIZE];
+        while bytes.len() < length {
+            let n = (length - bytes.len()).min(CHUNK_SIZE);
+            self.read_exact(&mut chunk[..n])?;
+            bytes.extend_from_slice(&chunk[..n]);
+        }
+        Ok(bytes)
+    }
+
+    /// Reads a length-prefixed UTF-8 string from `self`.
+    #[inline]
+    fn read_string(&mut self) -> IoResult<String> {
+        String::from_utf8(self.read_bytes()?).map_err(|_| IoError)
+    }
+
     /// Reads a element from the field `F` with size less than `2^64` from `self.`
     #[inline]
     fn read_field<F>(&mut self) -> IoResult<F>
@@ -162,6 +277,10 @@ pub trait Read {
         let plonk_zs_next = self.read_field_ext_vec::<F, D>(config.num_challenges)?;
         let partial_products = self
             .read_field_ext_vec::<F, D>(common_data.num_partial_products * config.num_challenges)?;
+        let lookup_polys =
+            self.read_field_ext_vec::<F, D>(common_data.lookup_polys_range().len())?;
+        let lookup_zs_next =
+            self.read_field_ext_vec::<F, D>(common_data.lookup_zs_range().len())?;
         let quotient_polys = self.read_field_ext_vec::<F, D>(
             common_data.quotient_degree_factor * config.num_challenges,
         )?;
@@ -172,6 +291,8 @@ pub trait Read {
             plonk_zs,
             plonk_zs_next,
             partial_products,
+            lookup_polys,
+            lookup_zs_next,
             quotient_polys,
         })
     }
@@ -214,9 +335,8 @@ pub trait Read {
         let wires_p = self.read_merkle_proof()?;
         evals_proofs.push((wires_v, wires_p));
 
-        let zs_partial_v = self.read_field_vec(
-            config.num_challenges * (1 + common_data.num_partial_products) + salt,
-        )?;
+        let zs_partial_v =
+            self.read_field_vec(common_data.num_zs_partial_products_polys() + salt)?;
         let zs_partial_p = self.read_merkle_proof()?;
         evals_proofs.push((zs_partial_v, zs_partial_p));
 
@@ -465,6 +585,461 @@ pub trait Read {
             public_inputs,
         })
     }
+
+    /// Reads a value of type [`Target`] from `self`.
+    #[inline]
+    fn read_target(&mut self) -> IoResult<Target> {
+        match self.read_u8()? {
+            0 => {
+                let row = self.read_usize()?;
+                let column = self.read_usize()?;
+                Ok(Target::wire(row, column))
+            }
+            1 => Ok(Target::VirtualTarget {
+                index: self.read_usize()?,
+            }),
+            _ => Err(IoError),
+        }
+    }
+
+    /// Reads an array of `N` values of type [`Target`] from `self`.
+    #[inline]
+    fn read_target_array<const N: usize>(&mut self) -> IoResult<[Target; N]> {
+        let mut arr = [Target::VirtualTarget { index: 0 }; N];
+        for t in arr.iter_mut() {
+            *t = self.read_target()?;
+        }
+        Ok(arr)
+    }
+
+    /// Reads a length-prefixed vector of values of type [`Target`] from `self`.
+    #[inline]
+    fn read_target_vec(&mut self) -> IoResult<Vec<Target>> {
+        let length = self.read_usize()?;
+        (0..length).map(|_| self.read_target()).collect()
+    }
+
+    /// Reads a value of type [`ExtensionTarget`] from `self`.
+    #[inline]
+    fn read_target_ext<const D: usize>(&mut self) -> IoResult<ExtensionTarget<D>> {
+        Ok(ExtensionTarget(self.read_target_array()?))
+    }
+
+    /// Reads a length-prefixed vector of values of type [`ExtensionTarget`] from `self`.
+    #[inline]
+    fn read_target_ext_vec<const D: usize>(&mut self) -> IoResult<Vec<ExtensionTarget<D>>> {
+        let length = self.read_usize()?;
+        (0..length).map(|_| self.read_target_ext()).collect()
+    }
+
+    /// Reads a value of type [`BoolTarget`] from `self`.
+    #[inline]
+    fn read_bool_target(&mut self) -> IoResult<BoolTarget> {
+        Ok(BoolTarget::new_unsafe(self.read_target()?))
+    }
+
+    /// Reads a length-prefixed vector of values of type [`BoolTarget`] from `self`.
+    #[inline]
+    fn read_bool_target_vec(&mut self) -> IoResult<Vec<BoolTarget>> {
+        let length = self.read_usize()?;
+        (0..length).map(|_| self.read_bool_target()).collect()
+    }
+
+    /// Reads a value of type [`FriConfig`] from `self`.
+    #[inline]
+    fn read_fri_config(&mut self) -> IoResult<FriConfig> {
+        let rate_bits = self.read_usize()?;
+        let cap_height = self.read_usize()?;
+        let proof_of_work_bits = self.read_u32()?;
+        let reduction_strategy = match self.read_u8()? {
+            0 => FriReductionStrategy::Fixed(self.read_usize_vec()?),
+            1 => {
+                let arity_bits = self.read_usize()?;
+                let final_poly_bits = self.read_usize()?;
+                FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits)
+            }
+            2 => FriReductionStrategy::MinSize(if self.read_bool()? {
+                Some(self.read_usize()?)
+            } else {
+                None
+            }),
+            _ => return Err(IoError),
+        };
+        let num_query_rounds = self.read_usize()?;
+        Ok(FriConfig {
+            rate_bits,
+            cap_height,
+            proof_of_work_bits,
+            reduction_strategy,
+            num_query_rounds,
+        })
+    }
+
+    /// Reads a value of type [`FriParams`] from `self`.
+    #[inline]
+    fn read_fri_params(&mut self) -> IoResult<FriParams> {
+        let config = self.read_fri_config()?;
+        let hiding = self.read_bool()?;
+        let degree_bits = self.read_usize()?;
+        let reduction_arity_bits = self.read_usize_vec()?;
+        Ok(FriParams {
+            config,
+            hiding,
+            degree_bits,
+            reduction_arity_bits,
+        })
+    }
+
+    /// Reads a value of type [`CircuitConfig`] from `self`.
+    #[inline]
+    fn read_circuit_config(&mut self) -> IoResult<CircuitConfig> {
+        let num_wires = self.read_usize()?;
+        let num_routed_wires = self.read_usize()?;
+        let num_constants = self.read_usize()?;
+        let use_base_arithmetic_gate = self.read_bool()?;
+        let security_bits = self.read_usize()?;
+        let num_challenges = self.read_usize()?;
+        let zero_knowledge = self.read_bool()?;
+        let max_quotient_degree_factor = self.read_usize()?;
+        let fri_config = self.read_fri_config()?;
+        Ok(CircuitConfig {
+            num_wires,
+            num_routed_wires,
+            num_constants,
+            use_base_arithmetic_gate,
+            security_bits,
+            num_challenges,
+            zero_knowledge,
+            max_quotient_degree_factor,
+            fri_config,
+        })
+    }
+
+    /// Reads a value of type [`SelectorsInfo`] from `self`.
+    #[inline]
+    fn read_selectors_info(&mut self) -> IoResult<SelectorsInfo> {
+        let selector_indices = self.read_usize_vec()?;
+        let num_groups = self.read_usize()?;
+        let groups = (0..num_groups)
+            .map(|_| {
+                let start = self.read_usize()?;
+                let end = self.read_usize()?;
+                Ok(start..end)
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        Ok(SelectorsInfo {
+            selector_indices,
+            groups,
+        })
+    }
+
+    /// Reads a gate written by [`Write::write_gate`] from `self`.
+    #[inline]
+    fn read_gate<F, const D: usize>(
+        &mut self,
+        gate_serializer: &GateSerializer<F, D>,
+    ) -> IoResult<GateRef<F, D>>
+    where
+        F: RichField + Extendable<D>,
+    {
+        let name = self.read_string()?;
+        let mut src = Buffer::new(self.read_bytes()?);
+        let gate = gate_serializer.read(&name, &mut src)?;
+        if !src.is_empty() {
+            return Err(IoError);
+        }
+        Ok(gate)
+    }
+
+    /// Reads a generator written by [`Write::write_generator`] from `self`.
+    #[inline]
+    fn read_generator<F, const D: usize>(
+        &mut self,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<Box<dyn WitnessGenerator<F>>>
+    where
+        F: RichField + Extendable<D>,
+    {
+        let id = self.read_string()?;
+        let mut src = Buffer::new(self.read_bytes()?);
+        let generator = generator_serializer.read(&id, &mut src)?;
+        if !src.is_empty() {
+            return Err(IoError);
+        }
+        Ok(generator)
+    }
+
+    /// Reads a value of type [`CommonCircuitData`] from `self`, using `gate_serializer` to read
+    /// its gates.
+    #[inline]
+    fn read_common_circuit_data<F, const D: usize>(
+        &mut self,
+        gate_serializer: &GateSerializer<F, D>,
+    ) -> IoResult<CommonCircuitData<F, D>>
+    where
+        F: RichField + Extendable<D>,
+    {
+        let config = self.read_circuit_config()?;
+        let fri_params = self.read_fri_params()?;
+        let num_gates = self.read_usize()?;
+        let gates = (0..num_gates)
+            .map(|_| self.read_gate(gate_serializer))
+            .collect::<IoResult<Vec<_>>>()?;
+        if gates.iter().any(|gate| {
+            gate.0.num_wires() > config.num_wires || gate.0.num_constants() > config.num_constants
+        }) {
+            return Err(IoError);
+        }
+        let selectors_info = self.read_selectors_info()?;
+        if !selectors_fit_gates(&selectors_info, gates.len()) {
+            return Err(IoError);
+        }
+        let quotient_degree_factor = self.read_usize()?;
+        let num_gate_constraints = self.read_usize()?;
+        let num_constants = self.read_usize()?;
+        let num_public_inputs = self.read_usize()?;
+        let num_k_is = self.read_usize()?;
+        if num_k_is != config.num_routed_wires {
+            return Err(IoError);
+        }
+        let k_is = self.read_field_vec(num_k_is)?;
+        let num_partial_products = self.read_usize()?;
+        let num_lookup_slots = self.read_usize()?;
+        Ok(CommonCircuitData {
+            config,
+            fri_params,
+            gates,
+            selectors_info,
+            quotient_degree_factor,
+            num_gate_constraints,
+            num_constants,
+            num_public_inputs,
+            k_is,
+            num_partial_products,
+            num_lookup_slots,
+        })
+    }
+
+    /// Reads a value of type [`VerifierOnlyCircuitData`] from `self`.
+    #[inline]
+    fn read_verifier_only_circuit_data<C, const D: usize>(
+        &mut self,
+    ) -> IoResult<VerifierOnlyCircuitData<C, D>>
+    where
+        C: GenericConfig<D>,
+    {
+        let cap_height = self.read_usize()?;
+        let constants_sigmas_cap = self.read_merkle_cap(cap_height)?;
+        let circuit_digest = self.read_hash::<C::F, C::Hasher>()?;
+        Ok(VerifierOnlyCircuitData {
+            constants_sigmas_cap,
+            circuit_digest,
+        })
+    }
+
+    /// Reads a value of type [`PolynomialBatch`] from `self`. Device-side copies of the batch are
+    /// not serialized, so the batch is read back as host data only.
+    #[inline]
+    fn read_polynomial_batch<F, C, const D: usize>(&mut self) -> IoResult<PolynomialBatch<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let polynomials = self.read_polynomial_coeffs_vec()?;
+        let num_leaves = self.read_usize()?;
+        let leaves = (0..num_leaves)
+            .map(|_| {
+                let length = self.read_usize()?;
+                self.read_field_vec(length)
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        let num_digests = self.read_usize()?;
+        let digests = (0..num_digests)
+            .map(|_| self.read_hash::<F, C::Hasher>())
+            .collect::<IoResult<Vec<_>>>()?;
+        let cap_height = self.read_usize()?;
+        let cap = self.read_merkle_cap(cap_height)?;
+        let degree_log = self.read_usize()?;
+        let rate_bits = self.read_usize()?;
+        let blinding = self.read_bool()?;
+        let my_polynomials = self.read_polynomial_coeffs_vec()?;
+        Ok(PolynomialBatch {
+            polynomials,
+            merkle_tree: MerkleTree {
+                leaves,
+                digests,
+                cap,
+                my_leaf_len: 0,
+                my_leaves: Arc::new(vec![]),
+                my_leaves_len: 0,
+                my_leaves_dev_offset: -1,
+                my_digests: Arc::new(vec![]),
+            },
+            degree_log,
+            rate_bits,
+            blinding,
+            my_polynomials,
+        })
+    }
+
+    /// Reads a length-prefixed vector of values of type [`PolynomialCoeffs`] from `self`.
+    #[inline]
+    fn read_polynomial_coeffs_vec<F>(&mut self) -> IoResult<Vec<PolynomialCoeffs<F>>>
+    where
+        F: Field64,
+    {
+        let num_polys = self.read_usize()?;
+        (0..num_polys)
+            .map(|_| {
+                let length = self.read_usize()?;
+                Ok(PolynomialCoeffs::new(self.read_field_vec(length)?))
+            })
+            .collect()
+    }
+
+    /// Reads a value of type [`ProverOnlyCircuitData`] from `self`, using `generator_serializer`
+    /// to read its generators.
+    #[inline]
+    fn read_prover_only_circuit_data<F, C, const D: usize>(
+        &mut self,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<ProverOnlyCircuitData<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let num_generators = self.read_usize()?;
+        let generators = (0..num_generators)
+            .map(|_| self.read_generator(generator_serializer))
+            .collect::<IoResult<Vec<_>>>()?;
+        let num_watches = self.read_usize()?;
+        let mut generator_indices_by_watches = BTreeMap::new();
+        for _ in 0..num_watches {
+            let watch = self.read_usize()?;
+            let indices = self.read_usize_vec()?;
+            if indices.iter().any(|&i| i >= num_generators) {
+                return Err(IoError);
+            }
+            generator_indices_by_watches.insert(watch, indices);
+        }
+        let constants_sigmas_commitment = self.read_polynomial_batch()?;
+        let num_sigmas = self.read_usize()?;
+        let sigmas = (0..num_sigmas)
+            .map(|_| {
+                let length = self.read_usize()?;
+                self.read_field_vec(length)
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        let subgroup_len = self.read_usize()?;
+        let subgroup = self.read_field_vec(subgroup_len)?;
+        let public_inputs = self.read_target_vec()?;
+        let representative_map = self.read_usize_vec()?;
+        let fft_root_table = if self.read_bool()? {
+            let num_layers = self.read_usize()?;
+            Some(
+                (0..num_layers)
+                    .map(|_| {
+                        let length = self.read_usize()?;
+                        self.read_field_vec(length)
+                    })
+                    .collect::<IoResult<Vec<_>>>()?,
+            )
+        } else {
+            None
+        };
+        let fft_root_table_deg_len = self.read_usize()?;
+        let fft_root_table_deg = self.read_field_vec(fft_root_table_deg_len)?;
+        let circuit_digest = self.read_hash::<F, C::Hasher>()?;
+        let target_origins = self.read_target_origins()?;
+        // The origins have the gate of each row.
+        if target_origins.row_gates.len() != subgroup.len() {
+            return Err(IoError);
+        }
+        let num_lookup_tables = self.read_usize()?;
+        let lookup_tables = (0..num_lookup_tables)
+            .map(|_| Ok(ProverLookupTable::new(self.read_lookup_table()?)))
+            .collect::<IoResult<Vec<_>>>()?;
+        Ok(ProverOnlyCircuitData {
+            generators,
+            generator_indices_by_watches,
+            constants_sigmas_commitment,
+            sigmas,
+            subgroup,
+            public_inputs,
+            representative_map,
+            fft_root_table,
+            fft_root_table_deg,
+            circuit_digest,
+            target_origins,
+            lookup_tables,
+        })
+    }
+
+    /// Reads a value of type [`LookupTable`] from `self`.
+    #[inline]
+    fn read_lookup_table(&mut self) -> IoResult<LookupTable> {
+        let len = self.read_usize()?;
+        let table = (0..len)
+            .map(|_| {
+                let input = u16::try_from(self.read_u32()?).map_err(|_| IoError)?;
+                let output = u16::try_from(self.read_u32()?).map_err(|_| IoError)?;
+                Ok((input, output))
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        Ok(Arc::new(table))
+    }
+
+    /// Reads a value of type [`TargetOrigins`] from `self`.
+    #[inline]
+    fn read_target_origins(&mut self) -> IoResult<TargetOrigins> {
+        let num_scopes = self.read_usize()?;
+        let scopes = (0..num_scopes)
+            .map(|_| self.read_string())
+            .collect::<IoResult<Vec<_>>>()?;
+        let row_gates = self.read_usize_vec()?;
+        let row_scopes = self.read_usize_vec()?;
+        let virtual_target_scopes = self.read_usize_vec()?;
+        if row_scopes
+            .iter()
+            .chain(&virtual_target_scopes)
+            .any(|&i| i >= num_scopes)
+        {
+            return Err(IoError);
+        }
+        Ok(TargetOrigins {
+            scopes,
+            row_gates,
+            row_scopes,
+            virtual_target_scopes,
+        })
+    }
+
+    /// Reads a value of type [`CircuitData`] from `self`.
+    #[inline]
+    fn read_circuit_data<F, C, const D: usize>(
+        &mut self,
+        gate_serializer: &GateSerializer<F, D>,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<CircuitData<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let common = self.read_common_circuit_data(gate_serializer)?;
+        let prover_only = self.read_prover_only_circuit_data(generator_serializer)?;
+        let verifier_only = self.read_verifier_only_circuit_data()?;
+        // The prover data must have a gate of `common.gates` for each row of the circuit.
+        let row_gates = &prover_only.target_origins.row_gates;
+        if row_gates.len() != common.degree() || row_gates.iter().any(|&g| g >= common.gates.len())
+        {
+            return Err(IoError);
+        }
+        Ok(CircuitData {
+            prover_only,
+            verifier_only,
+            common,
+        })
+    }
 }
 
 /// Writing
@@ -487,6 +1062,42 @@ pub trait Write {
         self.write_all(&x.to_le_bytes())
     }
 
+    /// Writes a `bool` value `x` to `self`.
+    #[inline]
+    fn write_bool(&mut self, x: bool) -> IoResult<()> {
+        self.write_u8(u8::from(x))
+    }
+
+    /// Writes a `usize` value `x` to `self`, as a `u64` so that the encoding does not depend on
+    /// the platform.
+    #[inline]
+    fn write_usize(&mut self, x: usize) -> IoResult<()> {
+        self.write_all(&(x as u64).to_le_bytes())
+    }
+
+    /// Writes a vector `v` of `usize` values to `self`, prefixed by its length.
+    #[inline]
+    fn write_usize_vec(&mut self, v: &[usize]) -> IoResult<()> {
+        self.write_usize(v.len())?;
+        for &x in v {
+            self.write_usize(x)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `bytes` to `self`, prefixed by their length.
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> IoResult<()> {
+        self.write_usize(bytes.len())?;
+        self.write_all(bytes)
+    }
+
+    /// Writes a string `s` to `self`, prefixed by its length.
+    #[inline]
+    fn write_string(&mut self, s: &str) -> IoResult<()> {
+        self.write_bytes(s.as_bytes())
+    }
+
     /// Writes an element `x` from the field `F` to `self`.
     #[inline]
     fn write_field<F>(&mut self, x: F) -> IoResult<()>
@@ -567,6 +1178,8 @@ pub trait Write {
         self.write_field_ext_vec::<F, D>(&os.plonk_zs)?;
         self.write_field_ext_vec::<F, D>(&os.plonk_zs_next)?;
         self.write_field_ext_vec::<F, D>(&os.partial_products)?;
+        self.write_field_ext_vec::<F, D>(&os.lookup_polys)?;
+        self.write_field_ext_vec::<F, D>(&os.lookup_zs_next)?;
         self.write_field_ext_vec::<F, D>(&os.quotient_polys)
     }
 
@@ -769,6 +1382,356 @@ pub trait Write {
         self.write_compressed_proof(proof)?;
         self.write_field_vec(public_inputs)
     }
+
+    /// Writes a target `x` to `self`.
+    #[inline]
+    fn write_target(&mut self, x: Target) -> IoResult<()> {
+        match x {
+            Target::Wire(Wire { row, column }) => {
+                self.write_u8(0)?;
+                self.write_usize(row)?;
+                self.write_usize(column)
+            }
+            Target::VirtualTarget { index } => {
+                self.write_u8(1)?;
+                self.write_usize(index)
+            }
+        }
+    }
+
+    /// Writes an array `v` of targets to `self`.
+    #[inline]
+    fn write_target_array<const N: usize>(&mut self, v: &[Target; N]) -> IoResult<()> {
+        for &x in v {
+            self.write_target(x)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a vector `v` of targets to `self`, prefixed by its length.
+    #[inline]
+    fn write_target_vec(&mut self, v: &[Target]) -> IoResult<()> {
+        self.write_usize(v.len())?;
+        for &x in v {
+            self.write_target(x)?;
+        }
+        Ok(())
+    }
+
+    /// Writes an extension target `x` to `self`.
+    #[inline]
+    fn write_target_ext<const D: usize>(&mut self, x: ExtensionTarget<D>) -> IoResult<()> {
+        self.write_target_array(&x.0)
+    }
+
+    /// Writes a vector `v` of extension targets to `self`, prefixed by its length.
+    #[inline]
+    fn write_target_ext_vec<const D: usize>(&mut self, v: &[ExtensionTarget<D>]) -> IoResult<()> {
+        self.write_usize(v.len())?;
+        for &x in v {
+            self.write_target_ext(x)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a boolean target `x` to `self`.
+    #[inline]
+    fn write_bool_target(&mut self, x: BoolTarget) -> IoResult<()> {
+        self.write_target(x.target)
+    }
+
+    /// Writes a vector `v` of boolean targets to `self`, prefixed by its length.
+    #[inline]
+    fn write_bool_target_vec(&mut self, v: &[BoolTarget]) -> IoResult<()> {
+        self.write_usize(v.len())?;
+        for &x in v {
+            self.write_bool_target(x)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value `config` of type [`FriConfig`] to `self`.
+    #[inline]
+    fn write_fri_config(&mut self, config: &FriConfig) -> IoResult<()> {
+        self.write_usize(config.rate_bits)?;
+        self.write_usize(config.cap_height)?;
+        self.write_u32(config.proof_of_work_bits)?;
+        match &config.reduction_strategy {
+            FriReductionStrategy::Fixed(arity_bits) => {
+                self.write_u8(0)?;
+                self.write_usize_vec(arity_bits)?;
+            }
+            &FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits) => {
+                self.write_u8(1)?;
+                self.write_usize(arity_bits)?;
+                self.write_usize(final_poly_bits)?;
+            }
+            &FriReductionStrategy::MinSize(opt_max_arity_bits) => {
+                self.write_u8(2)?;
+                self.write_bool(opt_max_arity_bits.is_some())?;
+                if let Some(max_arity_bits) = opt_max_arity_bits {
+                    self.write_usize(max_arity_bits)?;
+                }
+            }
+        }
+        self.write_usize(config.num_query_rounds)
+    }
+
+    /// Writes a value `params` of type [`FriParams`] to `self`.
+    #[inline]
+    fn write_fri_params(&mut self, params: &FriParams) -> IoResult<()> {
+        self.write_fri_config(&params.config)?;
+        self.write_bool(params.hiding)?;
+        self.write_usize(params.degree_bits)?;
+        self.write_usize_vec(&params.reduction_arity_bits)
+    }
+
+    /// Writes a value `config` of type [`CircuitConfig`] to `self`.
+    #[inline]
+    fn write_circuit_config(&mut self, config: &CircuitConfig) -> IoResult<()> {
+        self.write_usize(config.num_wires)?;
+        self.write_usize(config.num_routed_wires)?;
+        self.write_usize(config.num_constants)?;
+        self.write_bool(config.use_base_arithmetic_gate)?;
+        self.write_usize(config.security_bits)?;
+        self.write_usize(config.num_challenges)?;
+        self.write_bool(config.zero_knowledge)?;
+        self.write_usize(config.max_quotient_degree_factor)?;
+        self.write_fri_config(&config.fri_config)
+    }
+
+    /// Writes a value `info` of type [`SelectorsInfo`] to `self`.
+    #[inline]
+    fn write_selectors_info(&mut self, info: &SelectorsInfo) -> IoResult<()> {
+        self.write_usize_vec(&info.selector_indices)?;
+        self.write_usize(info.groups.len())?;
+        for group in &info.groups {
+            self.write_usize(group.start)?;
+            self.write_usize(group.end)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `gate` to `self`: the name of the gate, then the bytes of [`Gate::serialize`]. Fails
+    /// if `gate_serializer` would not be able to read the gate back.
+    ///
+    /// [`Gate::serialize`]: crate::gates::gate::Gate::serialize
+    #[inline]
+    fn write_gate<F, const D: usize>(
+        &mut self,
+        gate: &GateRef<F, D>,
+        gate_serializer: &GateSerializer<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+    {
+        let id = gate.0.id();
+        let name = gate_name(&id);
+        if !gate_serializer.contains(name) {
+            return Err(IoError);
+        }
+        let mut bytes = Vec::new();
+        gate.0.serialize(&mut bytes)?;
+        self.write_string(name)?;
+        self.write_bytes(&bytes)
+    }
+
+    /// Writes `generator` to `self`: the id of the generator, then the bytes of
+    /// [`WitnessGenerator::serialize`]. Fails if `generator_serializer` would not be able to read
+    /// the generator back.
+    #[inline]
+    fn write_generator<F, const D: usize>(
+        &mut self,
+        generator: &dyn WitnessGenerator<F>,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+    {
+        let id = generator.id();
+        if !generator_serializer.contains(&id) {
+            return Err(IoError);
+        }
+        let mut bytes = Vec::new();
+        generator.serialize(&mut bytes)?;
+        self.write_string(&id)?;
+        self.write_bytes(&bytes)
+    }
+
+    /// Writes a value `common_data` of type [`CommonCircuitData`] to `self`.
+    #[inline]
+    fn write_common_circuit_data<F, const D: usize>(
+        &mut self,
+        common_data: &CommonCircuitData<F, D>,
+        gate_serializer: &GateSerializer<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+    {
+        self.write_circuit_config(&common_data.config)?;
+        self.write_fri_params(&common_data.fri_params)?;
+        self.write_usize(common_data.gates.len())?;
+        for gate in &common_data.gates {
+            self.write_gate(gate, gate_serializer)?;
+        }
+        self.write_selectors_info(&common_data.selectors_info)?;
+        self.write_usize(common_data.quotient_degree_factor)?;
+        self.write_usize(common_data.num_gate_constraints)?;
+        self.write_usize(common_data.num_constants)?;
+        self.write_usize(common_data.num_public_inputs)?;
+        self.write_usize(common_data.k_is.len())?;
+        self.write_field_vec(&common_data.k_is)?;
+        self.write_usize(common_data.num_partial_products)?;
+        self.write_usize(common_data.num_lookup_slots)
+    }
+
+    /// Writes a value `verifier_data` of type [`VerifierOnlyCircuitData`] to `self`.
+    #[inline]
+    fn write_verifier_only_circuit_data<C, const D: usize>(
+        &mut self,
+        verifier_data: &VerifierOnlyCircuitData<C, D>,
+    ) -> IoResult<()>
+    where
+        C: GenericConfig<D>,
+    {
+        let cap = &verifier_data.constants_sigmas_cap;
+        self.write_usize(log2_strict(cap.0.len()))?;
+        self.write_merkle_cap(cap)?;
+        self.write_hash::<C::F, C::Hasher>(verifier_data.circuit_digest)
+    }
+
+    /// Writes a value `batch` of type [`PolynomialBatch`] to `self`. Only the host data of the
+    /// batch is written.
+    #[inline]
+    fn write_polynomial_batch<F, C, const D: usize>(
+        &mut self,
+        batch: &PolynomialBatch<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_polynomial_coeffs_vec(&batch.polynomials)?;
+        let merkle_tree = &batch.merkle_tree;
+        self.write_usize(merkle_tree.leaves.len())?;
+        for leaf in &merkle_tree.leaves {
+            self.write_usize(leaf.len())?;
+            self.write_field_vec(leaf)?;
+        }
+        self.write_usize(merkle_tree.digests.len())?;
+        for &digest in &merkle_tree.digests {
+            self.write_hash::<F, C::Hasher>(digest)?;
+        }
+        self.write_usize(log2_strict(merkle_tree.cap.0.len()))?;
+        self.write_merkle_cap(&merkle_tree.cap)?;
+        self.write_usize(batch.degree_log)?;
+        self.write_usize(batch.rate_bits)?;
+        self.write_bool(batch.blinding)?;
+        self.write_polynomial_coeffs_vec(&batch.my_polynomials)
+    }
+
+    /// Writes a vector `polys` of [`PolynomialCoeffs`] to `self`, prefixed by its length.
+    #[inline]
+    fn write_polynomial_coeffs_vec<F>(&mut self, polys: &[PolynomialCoeffs<F>]) -> IoResult<()>
+    where
+        F: PrimeField64,
+    {
+        self.write_usize(polys.len())?;
+        for poly in polys {
+            self.write_usize(poly.coeffs.len())?;
+            self.write_field_vec(&poly.coeffs)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value `prover_data` of type [`ProverOnlyCircuitData`] to `self`.
+    #[inline]
+    fn write_prover_only_circuit_data<F, C, const D: usize>(
+        &mut self,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_usize(prover_data.generators.len())?;
+        for generator in &prover_data.generators {
+            self.write_generator(generator.as_ref(), generator_serializer)?;
+        }
+        self.write_usize(prover_data.generator_indices_by_watches.len())?;
+        for (&watch, indices) in &prover_data.generator_indices_by_watches {
+            self.write_usize(watch)?;
+            self.write_usize_vec(indices)?;
+        }
+        self.write_polynomial_batch(&prover_data.constants_sigmas_commitment)?;
+        self.write_usize(prover_data.sigmas.len())?;
+        for sigma in &prover_data.sigmas {
+            self.write_usize(sigma.len())?;
+            self.write_field_vec(sigma)?;
+        }
+        self.write_usize(prover_data.subgroup.len())?;
+        self.write_field_vec(&prover_data.subgroup)?;
+        self.write_target_vec(&prover_data.public_inputs)?;
+        self.write_usize_vec(&prover_data.representative_map)?;
+        self.write_bool(prover_data.fft_root_table.is_some())?;
+        if let Some(fft_root_table) = &prover_data.fft_root_table {
+            self.write_usize(fft_root_table.len())?;
+            for layer in fft_root_table {
+                self.write_usize(layer.len())?;
+                self.write_field_vec(layer)?;
+            }
+        }
+        self.write_usize(prover_data.fft_root_table_deg.len())?;
+        self.write_field_vec(&prover_data.fft_root_table_deg)?;
+        self.write_hash::<F, C::Hasher>(prover_data.circuit_digest)?;
+        self.write_target_origins(&prover_data.target_origins)?;
+        self.write_usize(prover_data.lookup_tables.len())?;
+        for lookup_table in &prover_data.lookup_tables {
+            self.write_lookup_table(&lookup_table.table)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value `table` of type [`LookupTable`] to `self`.
+    #[inline]
+    fn write_lookup_table(&mut self, table: &LookupTable) -> IoResult<()> {
+        self.write_usize(table.len())?;
+        for &(input, output) in table.iter() {
+            self.write_u32(input as u32)?;
+            self.write_u32(output as u32)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value `origins` of type [`TargetOrigins`] to `self`.
+    #[inline]
+    fn write_target_origins(&mut self, origins: &TargetOrigins) -> IoResult<()> {
+        self.write_usize(origins.scopes.len())?;
+        for scope in &origins.scopes {
+            self.write_string(scope)?;
+        }
+        self.write_usize_vec(&origins.row_gates)?;
+        self.write_usize_vec(&origins.row_scopes)?;
+        self.write_usize_vec(&origins.virtual_target_scopes)
+    }
+
+    /// Writes a value `circuit_data` of type [`CircuitData`] to `self`.
+    #[inline]
+    fn write_circuit_data<F, C, const D: usize>(
+        &mut self,
+        circuit_data: &CircuitData<F, C, D>,
+        gate_serializer: &GateSerializer<F, D>,
+        generator_serializer: &WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_common_circuit_data(&circuit_data.common, gate_serializer)?;
+        self.write_prover_only_circuit_data(&circuit_data.prover_only, generator_serializer)?;
+        self.write_verifier_only_circuit_data(&circuit_data.verifier_only)
+    }
 }
 
 impl Write for Vec<u8> {
@@ -782,14 +1745,12 @@ impl Write for Vec<u8> {
 }
 
 /// Buffer
-#[cfg(feature = "std")]
 #[derive(Debug)]
 pub struct Buffer {
     bytes: Vec<u8>,
     pos: usize,
 }
 
-#[cfg(feature = "std")]
 impl Buffer {
     /// Builds a new [`Buffer`] over `buffer`.
     #[inline]
@@ -0,0 +1,115 @@
+//! Reading gates back from bytes.
+//!
+//! [`Write::write_gate`] writes the name at the start of the gate's [`Gate::id`], then the bytes
+//! of [`Gate::serialize`]. A [`GateSerializer`] maps each name to the [`Gate::deserialize`] of the
+//! corresponding gate type. Crates defining their own gates register them on top of
+//! [`GateSerializer::plonky2`].
+//!
+//! [`Write::write_gate`]: crate::util::serialization::Write::write_gate
+
+use alloc::string::{String, ToString};
+
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::gates::arithmetic_base::ArithmeticGate;
+use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+use crate::gates::base_sum::BaseSumGate;
+use crate::gates::constant::ConstantGate;
+use crate::gates::exponentiation::ExponentiationGate;
+use crate::gates::gate::{Gate, GateRef};
+use crate::gates::high_degree_interpolation::HighDegreeInterpolationGate;
+use crate::gates::lookup::LookupGate;
+use crate::gates::lookup_table::LookupTableGate;
+use crate::gates::low_degree_interpolation::LowDegreeInterpolationGate;
+use crate::gates::multiplication_extension::MulExtensionGate;
+use crate::gates::noop::NoopGate;
+use crate::gates::poseidon::PoseidonGate;
+use crate::gates::poseidon_mds::PoseidonMdsGate;
+use crate::gates::public_input::PublicInputGate;
+use crate::gates::random_access::RandomAccessGate;
+use crate::gates::reducing::ReducingGate;
+use crate::gates::reducing_extension::ReducingExtensionGate;
+use crate::hash::hash_types::RichField;
+use crate::util::serialization::{Buffer, IoError, IoResult, Read, MAX_GATE_WIRES};
+
+/// Reads a gate of a given type from the bytes written by its [`Gate::serialize`].
+pub type GateReader<F, const D: usize> = fn(&mut Buffer) -> IoResult<GateRef<F, D>>;
+
+/// Gates that can be deserialized, keyed by the name at the start of their id.
+#[derive(Clone, Debug)]
+pub struct GateSerializer<F: RichField + Extendable<D>, const D: usize> {
+    readers: HashMap<String, GateReader<F, D>>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Default for GateSerializer<F, D> {
+    fn default() -> Self {
+        Self {
+            readers: HashMap::new(),
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The gates of `plonky2::gates`.
+    pub fn plonky2() -> Self {
+        let mut serializer = Self::new();
+        serializer.register::<ArithmeticGate>("ArithmeticGate");
+        serializer.register::<ArithmeticExtensionGate<D>>("ArithmeticExtensionGate");
+        serializer.register_reader("BaseSumGate", read_base_sum_gate);
+        serializer.register::<ConstantGate>("ConstantGate");
+        serializer.register::<ExponentiationGate<F, D>>("ExponentiationGate");
+        serializer.register::<HighDegreeInterpolationGate<F, D>>("HighDegreeInterpolationGate");
+        serializer.register::<LookupGate>("LookupGate");
+        serializer.register::<LookupTableGate>("LookupTableGate");
+        serializer.register::<LowDegreeInterpolationGate<F, D>>("LowDegreeInterpolationGate");
+        serializer.register::<MulExtensionGate<D>>("MulExtensionGate");
+        serializer.register::<NoopGate>("NoopGate");
+        serializer.register::<PoseidonGate<F, D>>("PoseidonGate");
+        serializer.register::<PoseidonMdsGate<F, D>>("PoseidonMdsGate");
+        serializer.register::<PublicInputGate>("PublicInputGate");
+        serializer.register::<RandomAccessGate<F, D>>("RandomAccessGate");
+        serializer.register::<ReducingGate<D>>("ReducingGate");
+        serializer.register::<ReducingExtensionGate<D>>("ReducingExtensionGate");
+        serializer
+    }
+
+    /// Registers the gate type `G`, whose ids start with `name`.
+    pub fn register<G: Gate<F, D>>(&mut self, name: &str) {
+        self.register_reader(name, |src| Ok(GateRef::new(G::deserialize(src)?)));
+    }
+
+    pub fn register_reader(&mut self, name: &str, reader: GateReader<F, D>) {
+        self.readers.insert(name.to_string(), reader);
+    }
+
+    /// Whether gates whose ids start with `name` can be deserialized.
+    pub fn contains(&self, name: &str) -> bool {
+        self.readers.contains_key(name)
+    }
+
+    /// Reads a gate whose id starts with `name` from the bytes written by its
+    /// [`Gate::serialize`].
+    pub fn read(&self, name: &str, src: &mut Buffer) -> IoResult<GateRef<F, D>> {
+        let reader = self.readers.get(name).ok_or(IoError)?;
+        reader(src)
+    }
+}
+
+/// `BaseSumGate`s of all bases share a name, so their serialization starts with the base. Only the
+/// bases used by the gadgets of this workspace are supported.
+fn read_base_sum_gate<F: RichField + Extendable<D>, const D: usize>(
+    src: &mut Buffer,
+) -> IoResult<GateRef<F, D>> {
+    let base = src.read_usize()?;
+    let num_limbs = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+    match base {
+        2 => Ok(GateRef::new(BaseSumGate::<2>::new(num_limbs))),
+        4 => Ok(GateRef::new(BaseSumGate::<4>::new(num_limbs))),
+        _ => Err(IoError),
+    }
+}
@@ -0,0 +1,131 @@
+//! Reading witness generators back from bytes.
+//!
+//! [`Write::write_generator`] writes the [`WitnessGenerator::id`] of the generator, then the bytes
+//! of [`WitnessGenerator::serialize`]. A [`WitnessGeneratorSerializer`] maps each id to the
+//! deserialization of the corresponding generator type. Crates defining their own generators
+//! register them on top of [`WitnessGeneratorSerializer::plonky2`].
+//!
+//! [`Write::write_generator`]: crate::util::serialization::Write::write_generator
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::gadgets::arithmetic::EqualityGenerator;
+use crate::gadgets::arithmetic_extension::QuotientGeneratorExtension;
+use crate::gadgets::lookup::{LookupGenerator, LookupTableGenerator};
+use crate::gadgets::range_check::LowHighGenerator;
+use crate::gadgets::split_base::BaseSumGenerator;
+use crate::gadgets::split_join::{SplitGenerator, WireSplitGenerator};
+use crate::gates::arithmetic_base::ArithmeticBaseGenerator;
+use crate::gates::arithmetic_extension::ArithmeticExtensionGenerator;
+use crate::gates::base_sum::BaseSplitGenerator;
+use crate::gates::exponentiation::ExponentiationGenerator;
+use crate::gates::multiplication_extension::MulExtensionGenerator;
+use crate::gates::poseidon::PoseidonGenerator;
+use crate::gates::poseidon_mds::PoseidonMdsGenerator;
+use crate::gates::random_access::RandomAccessGenerator;
+use crate::gates::{
+    high_degree_interpolation, low_degree_interpolation, reducing, reducing_extension,
+};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{
+    ConstantGenerator, CopyGenerator, NonzeroTestGenerator, RandomValueGenerator, SimpleGenerator,
+    WitnessGenerator,
+};
+use crate::util::serialization::{Buffer, IoError, IoResult};
+
+/// Reads a generator of a given type from the bytes written by its
+/// [`WitnessGenerator::serialize`].
+pub type GeneratorReader<F> = fn(&mut Buffer) -> IoResult<Box<dyn WitnessGenerator<F>>>;
+
+/// Generators that can be deserialized, keyed by their id.
+///
+/// `DummyProofGenerator`, added by conditional recursion, cannot be serialized: its dummy proof can
+/// only be read back with the common data of the dummy circuit.
+#[derive(Clone, Debug)]
+pub struct WitnessGeneratorSerializer<F: RichField + Extendable<D>, const D: usize> {
+    readers: HashMap<String, GeneratorReader<F>>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Default for WitnessGeneratorSerializer<F, D> {
+    fn default() -> Self {
+        Self {
+            readers: HashMap::new(),
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> WitnessGeneratorSerializer<F, D> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The generators of `plonky2`, i.e. those of its gates, of its gadgets and of the circuit
+    /// builder.
+    pub fn plonky2() -> Self {
+        let mut serializer = Self::new();
+        serializer.register_simple::<ArithmeticBaseGenerator<F, D>>("ArithmeticBaseGenerator");
+        serializer
+            .register_simple::<ArithmeticExtensionGenerator<F, D>>("ArithmeticExtensionGenerator");
+        serializer.register_simple::<BaseSplitGenerator<2>>("BaseSplitGenerator + Base: 2");
+        serializer.register_simple::<BaseSplitGenerator<4>>("BaseSplitGenerator + Base: 4");
+        serializer.register_simple::<BaseSumGenerator<2>>("BaseSumGenerator + Base: 2");
+        serializer.register_simple::<BaseSumGenerator<4>>("BaseSumGenerator + Base: 4");
+        serializer.register_simple::<ConstantGenerator<F>>("ConstantGenerator");
+        serializer.register_simple::<CopyGenerator>("CopyGenerator");
+        serializer.register_simple::<EqualityGenerator>("EqualityGenerator");
+        serializer.register_simple::<ExponentiationGenerator<F, D>>("ExponentiationGenerator");
+        serializer.register_simple::<high_degree_interpolation::InterpolationGenerator<F, D>>(
+            "HighDegreeInterpolationGenerator",
+        );
+        serializer.register_simple::<low_degree_interpolation::InterpolationGenerator<F, D>>(
+            "LowDegreeInterpolationGenerator",
+        );
+        serializer.register::<LookupGenerator>("LookupGenerator");
+        serializer.register_simple::<LookupTableGenerator>("LookupTableGenerator");
+        serializer.register_simple::<LowHighGenerator>("LowHighGenerator");
+        serializer.register_simple::<MulExtensionGenerator<F, D>>("MulExtensionGenerator");
+        serializer.register_simple::<NonzeroTestGenerator>("NonzeroTestGenerator");
+        serializer.register_simple::<PoseidonGenerator<F, D>>("PoseidonGenerator");
+        serializer.register_simple::<PoseidonMdsGenerator<D>>("PoseidonMdsGenerator");
+        serializer.register_simple::<QuotientGeneratorExtension<D>>("QuotientGeneratorExtension");
+        serializer.register_simple::<RandomAccessGenerator<F, D>>("RandomAccessGenerator");
+        serializer.register_simple::<RandomValueGenerator>("RandomValueGenerator");
+        serializer.register_simple::<reducing::ReducingGenerator<D>>("ReducingGenerator");
+        serializer.register_simple::<reducing_extension::ReducingGenerator<D>>(
+            "ReducingExtensionGenerator",
+        );
+        serializer.register_simple::<SplitGenerator>("SplitGenerator");
+        serializer.register_simple::<WireSplitGenerator>("WireSplitGenerator");
+        serializer
+    }
+
+    /// Registers the generator type `G`, whose [`WitnessGenerator::id`] is `id`.
+    pub fn register<G: WitnessGenerator<F>>(&mut self, id: &str) {
+        self.register_reader(id, |src| Ok(Box::new(G::deserialize(src)?)));
+    }
+
+    /// Registers the generator type `G`, whose [`SimpleGenerator::id`] is `id`.
+    pub fn register_simple<G: SimpleGenerator<F>>(&mut self, id: &str) {
+        self.register_reader(id, |src| Ok(Box::new(G::deserialize(src)?.adapter())));
+    }
+
+    pub fn register_reader(&mut self, id: &str, reader: GeneratorReader<F>) {
+        self.readers.insert(id.to_string(), reader);
+    }
+
+    /// Whether generators with the given id can be deserialized.
+    pub fn contains(&self, id: &str) -> bool {
+        self.readers.contains_key(id)
+    }
+
+    /// Reads a generator with the given id from the bytes written by its
+    /// [`WitnessGenerator::serialize`].
+    pub fn read(&self, id: &str, src: &mut Buffer) -> IoResult<Box<dyn WitnessGenerator<F>>> {
+        let reader = self.readers.get(id).ok_or(IoError)?;
+        reader(src)
+    }
+}
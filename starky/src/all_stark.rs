@@ -0,0 +1,35 @@
+//! Proving several STARKs, the tables, together: they share a Fiat-Shamir transcript, so that
+//! cross-table lookups can link their columns.
+
+use alloc::vec::Vec;
+
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+
+use crate::cross_table_lookup::CrossTableLookup;
+use crate::stark::Stark;
+
+/// A set of tables proven together. The tables are numbered from `0` to `num_tables() - 1`, which
+/// is how the cross-table lookups refer to them.
+///
+/// As the tables are STARKs of distinct types, they are reached through a `TableVisitor`: an
+/// implementation typically matches on `table` and calls `visitor.visit(table, &self.some_stark)`.
+pub trait AllStark<F: RichField + Extendable<D>, const D: usize> {
+    /// The number of tables.
+    fn num_tables(&self) -> usize;
+
+    /// The cross-table lookups between the tables.
+    fn cross_table_lookups(&self) -> Vec<CrossTableLookup<F>>;
+
+    /// Calls `visitor` on the STARK of the given table.
+    fn visit_table<V: TableVisitor<F, D>>(&self, table: usize, visitor: &mut V) -> Result<()>;
+}
+
+/// An operation applied to the tables of an `AllStark`, e.g. proving or verifying them.
+pub trait TableVisitor<F: RichField + Extendable<D>, const D: usize> {
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:;
+}
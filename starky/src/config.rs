@@ -9,6 +9,12 @@ pub struct StarkConfig {
     pub num_challenges: usize,
 
     pub fri_config: FriConfig,
+
+    /// Whether the proofs should hide the trace. If so, the leaves of the Merkle trees are salted,
+    /// and each committed polynomial `p` is randomized into `p + Z_H r`, for a random `r` of degree
+    /// less than the trace length `n`. This doubles the degree of the committed polynomials, and
+    /// hides the trace as long as `n` exceeds the number of evaluations revealed by the proof.
+    pub zero_knowledge: bool,
 }
 
 impl StarkConfig {
@@ -25,10 +31,17 @@ impl StarkConfig {
                 reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
                 num_query_rounds: 84,
             },
+            zero_knowledge: false,
         }
     }
 
+    /// The log of the degree of the committed polynomials, for a trace of `2^degree_bits` rows.
+    pub(crate) fn committed_degree_bits(&self, degree_bits: usize) -> usize {
+        degree_bits + usize::from(self.zero_knowledge)
+    }
+
     pub(crate) fn fri_params(&self, degree_bits: usize) -> FriParams {
-        self.fri_config.fri_params(degree_bits, false)
+        self.fri_config
+            .fri_params(self.committed_degree_bits(degree_bits), self.zero_knowledge)
     }
 }
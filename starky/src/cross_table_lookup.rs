@@ -0,0 +1,505 @@
+//! Cross-table lookups, which check that some columns of a set of tables, the looking tables, hold
+//! the same rows as some columns of another table, the looked table, up to a permutation. Each
+//! table can have a filter column, which selects the rows taking part in the lookup.
+//!
+//! For each lookup and each challenge, every table involved commits to a running product
+//! `Z(x) = Z(g x) * (filter(x) * (combine(x) - 1) + 1)`, starting from the last row. The verifier
+//! then checks that the product of the `Z(1)`s of the looking tables is the `Z(1)` of the looked
+//! table.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::once;
+
+use anyhow::{ensure, Result};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::iop::target::Target;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::plonk_common::reduce_with_powers;
+use plonky2::util::reducing::{ReducingFactor, ReducingFactorTarget};
+
+use crate::config::StarkConfig;
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::permutation::{PermutationChallenge, PermutationChallengeSet};
+use crate::proof::{StarkOpeningSet, StarkOpeningSetTarget};
+
+/// A linear combination of the columns of a table, plus a constant.
+#[derive(Clone, Debug)]
+pub struct Column<F: Field> {
+    linear_combination: Vec<(usize, F)>,
+    constant: F,
+}
+
+impl<F: Field> Column<F> {
+    pub fn single(c: usize) -> Self {
+        Self {
+            linear_combination: vec![(c, F::ONE)],
+            constant: F::ZERO,
+        }
+    }
+
+    pub fn singles<I: IntoIterator<Item = usize>>(cs: I) -> impl Iterator<Item = Self> {
+        cs.into_iter().map(Self::single)
+    }
+
+    pub fn constant(constant: F) -> Self {
+        Self {
+            linear_combination: vec![],
+            constant,
+        }
+    }
+
+    pub fn linear_combination_with_constant<I: IntoIterator<Item = (usize, F)>>(
+        iter: I,
+        constant: F,
+    ) -> Self {
+        Self {
+            linear_combination: iter.into_iter().collect(),
+            constant,
+        }
+    }
+
+    pub fn linear_combination<I: IntoIterator<Item = (usize, F)>>(iter: I) -> Self {
+        Self::linear_combination_with_constant(iter, F::ZERO)
+    }
+
+    /// Evaluates the column on the values of a row.
+    pub fn eval<FE, P, const D: usize>(&self, v: &[P]) -> P
+    where
+        FE: FieldExtension<D, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        self.linear_combination
+            .iter()
+            .map(|&(c, f)| v[c] * FE::from_basefield(f))
+            .sum::<P>()
+            + FE::from_basefield(self.constant)
+    }
+
+    /// Evaluates the column on the given row of a trace.
+    pub fn eval_table(&self, table: &[PolynomialValues<F>], row: usize) -> F {
+        self.linear_combination
+            .iter()
+            .map(|&(c, f)| table[c].values[row] * f)
+            .sum::<F>()
+            + self.constant
+    }
+
+    pub fn eval_circuit<const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        v: &[ExtensionTarget<D>],
+    ) -> ExtensionTarget<D>
+    where
+        F: RichField + Extendable<D>,
+    {
+        let pairs = self
+            .linear_combination
+            .iter()
+            .map(|&(c, f)| {
+                (
+                    v[c],
+                    builder.constant_extension(F::Extension::from_basefield(f)),
+                )
+            })
+            .collect();
+        let constant = builder.constant_extension(F::Extension::from_basefield(self.constant));
+        builder.inner_product_extension(F::ONE, constant, pairs)
+    }
+}
+
+/// The columns of a table taking part in a cross-table lookup, and the filter selecting its rows.
+/// Without a filter, every row takes part in the lookup.
+#[derive(Clone, Debug)]
+pub struct TableWithColumns<F: Field> {
+    table: usize,
+    columns: Vec<Column<F>>,
+    filter_column: Option<Column<F>>,
+}
+
+impl<F: Field> TableWithColumns<F> {
+    pub fn new(table: usize, columns: Vec<Column<F>>, filter_column: Option<Column<F>>) -> Self {
+        Self {
+            table,
+            columns,
+            filter_column,
+        }
+    }
+}
+
+/// A cross-table lookup: the filtered rows of all the looking tables, taken together, are a
+/// permutation of the filtered rows of the looked table.
+///
+/// Filters must be binary. The Z polynomials of a table make for constraints of degree 3, so the
+/// tables involved must have a `constraint_degree` of at least 3.
+#[derive(Clone, Debug)]
+pub struct CrossTableLookup<F: Field> {
+    looking_tables: Vec<TableWithColumns<F>>,
+    looked_table: TableWithColumns<F>,
+}
+
+impl<F: Field> CrossTableLookup<F> {
+    pub fn new(
+        looking_tables: Vec<TableWithColumns<F>>,
+        looked_table: TableWithColumns<F>,
+    ) -> Self {
+        assert!(looking_tables
+            .iter()
+            .all(|twc| twc.columns.len() == looked_table.columns.len()));
+        Self {
+            looking_tables,
+            looked_table,
+        }
+    }
+
+    /// The number of Z polynomials of `table` for the given lookups.
+    pub fn num_ctl_zs(cross_table_lookups: &[Self], table: usize, num_challenges: usize) -> usize {
+        cross_table_lookups
+            .iter()
+            .map(|ctl| {
+                ctl.looking_tables
+                    .iter()
+                    .chain(once(&ctl.looked_table))
+                    .filter(|twc| twc.table == table)
+                    .count()
+                    * num_challenges
+            })
+            .sum()
+    }
+}
+
+/// The Z polynomials of a table, along with what is needed to check them.
+#[derive(Clone, Default)]
+pub struct CtlData<F: Field> {
+    pub(crate) zs_columns: Vec<CtlZData<F>>,
+}
+
+#[derive(Clone)]
+pub(crate) struct CtlZData<F: Field> {
+    pub(crate) z: PolynomialValues<F>,
+    pub(crate) challenge: PermutationChallenge<F>,
+    pub(crate) columns: Vec<Column<F>>,
+    pub(crate) filter_column: Option<Column<F>>,
+}
+
+impl<F: Field> CtlData<F> {
+    pub fn len(&self) -> usize {
+        self.zs_columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.zs_columns.is_empty()
+    }
+
+    pub fn z_polys(&self) -> Vec<PolynomialValues<F>> {
+        self.zs_columns.iter().map(|zs| zs.z.clone()).collect()
+    }
+}
+
+/// Computes the Z polynomials of each table. A table gets one per lookup it takes part in and per
+/// challenge, in the order of `ctl_zs_of_table`.
+pub(crate) fn cross_table_lookup_data<F: RichField>(
+    trace_poly_values: &[Vec<PolynomialValues<F>>],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    ctl_challenges: &PermutationChallengeSet<F>,
+) -> Result<Vec<CtlData<F>>> {
+    let mut ctl_data_per_table = vec![CtlData::default(); trace_poly_values.len()];
+    for table in 0..trace_poly_values.len() {
+        for (challenge, twc) in ctl_zs_of_table(cross_table_lookups, ctl_challenges, table) {
+            let z = partial_products(
+                &trace_poly_values[table],
+                &twc.columns,
+                &twc.filter_column,
+                challenge,
+            )?;
+            ctl_data_per_table[table].zs_columns.push(CtlZData {
+                z,
+                challenge,
+                columns: twc.columns.clone(),
+                filter_column: twc.filter_column.clone(),
+            });
+        }
+    }
+    Ok(ctl_data_per_table)
+}
+
+/// The challenges and columns of the Z polynomials of `table`, in order: for each lookup, for each
+/// challenge, the looking tables then the looked table.
+fn ctl_zs_of_table<'a, F: Field, T: Copy>(
+    cross_table_lookups: &'a [CrossTableLookup<F>],
+    ctl_challenges: &PermutationChallengeSet<T>,
+    table: usize,
+) -> Vec<(PermutationChallenge<T>, &'a TableWithColumns<F>)> {
+    let mut zs = Vec::new();
+    for CrossTableLookup {
+        looking_tables,
+        looked_table,
+    } in cross_table_lookups
+    {
+        for &challenge in &ctl_challenges.challenges {
+            for twc in looking_tables.iter().chain(once(looked_table)) {
+                if twc.table == table {
+                    zs.push((challenge, twc));
+                }
+            }
+        }
+    }
+    zs
+}
+
+/// The running product of `combine(x)` over the filtered rows, from the last row to the first.
+/// Fails if a filter is neither zero nor one.
+fn partial_products<F: Field>(
+    trace: &[PolynomialValues<F>],
+    columns: &[Column<F>],
+    filter_column: &Option<Column<F>>,
+    challenge: PermutationChallenge<F>,
+) -> Result<PolynomialValues<F>> {
+    let degree = trace[0].len();
+    let mut partial_prod = F::ONE;
+    let mut res = Vec::with_capacity(degree);
+    for i in (0..degree).rev() {
+        let filter = filter_column
+            .as_ref()
+            .map_or(F::ONE, |column| column.eval_table(trace, i));
+        if filter.is_one() {
+            let evals = columns
+                .iter()
+                .map(|column| column.eval_table(trace, i))
+                .collect::<Vec<_>>();
+            partial_prod *= reduce_with_powers(&evals, challenge.beta) + challenge.gamma;
+        } else {
+            ensure!(
+                filter.is_zero(),
+                "Filter columns must be binary, but row {} has filter {}",
+                i,
+                filter
+            );
+        }
+        res.push(partial_prod);
+    }
+    res.reverse();
+    Ok(PolynomialValues::new(res))
+}
+
+/// The values of a Z polynomial of a table at a point and the next one, and how to check them.
+pub struct CtlCheckVars<'a, F, FE, P, const D2: usize>
+where
+    F: Field,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    pub(crate) local_z: P,
+    pub(crate) next_z: P,
+    pub(crate) challenge: PermutationChallenge<F>,
+    pub(crate) columns: &'a [Column<F>],
+    pub(crate) filter_column: &'a Option<Column<F>>,
+}
+
+impl<'a, F: RichField + Extendable<D>, const D: usize>
+    CtlCheckVars<'a, F, F::Extension, F::Extension, D>
+{
+    /// The `CtlCheckVars` of `table`, from the openings of its proof.
+    pub(crate) fn from_openings(
+        table: usize,
+        openings: &StarkOpeningSet<F, D>,
+        cross_table_lookups: &'a [CrossTableLookup<F>],
+        ctl_challenges: &PermutationChallengeSet<F>,
+    ) -> Vec<Self> {
+        let ctl_zs = openings
+            .ctl_zs
+            .iter()
+            .flatten()
+            .zip(openings.ctl_zs_next.iter().flatten());
+        ctl_zs_of_table(cross_table_lookups, ctl_challenges, table)
+            .into_iter()
+            .zip(ctl_zs)
+            .map(|((challenge, twc), (&local_z, &next_z))| Self {
+                local_z,
+                next_z,
+                challenge,
+                columns: &twc.columns,
+                filter_column: &twc.filter_column,
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn eval_cross_table_lookup_checks<F, FE, P, const D2: usize>(
+    local_values: &[P],
+    ctl_vars: &[CtlCheckVars<F, FE, P, D2>],
+    consumer: &mut ConstraintConsumer<P>,
+) where
+    F: RichField,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    for lookup_vars in ctl_vars {
+        let CtlCheckVars {
+            local_z,
+            next_z,
+            challenge: PermutationChallenge { beta, gamma },
+            columns,
+            filter_column,
+        } = lookup_vars;
+        let evals = columns
+            .iter()
+            .map(|column| column.eval(local_values))
+            .collect::<Vec<_>>();
+        let combination =
+            ReducingFactor::new(*beta).reduce_ext(evals.into_iter()) + FE::from_basefield(*gamma);
+        let filter = filter_column
+            .as_ref()
+            .map_or(P::ONES, |column| column.eval(local_values));
+        // Rows outside of the filter contribute a factor of one.
+        let factor = filter * combination - filter + P::ONES;
+
+        consumer.constraint_last_row(*local_z - factor);
+        consumer.constraint_transition(*local_z - *next_z * factor);
+    }
+}
+
+/// Like `CtlCheckVars`, in the context of a recursive circuit.
+pub struct CtlCheckVarsTarget<'a, F: Field, const D: usize> {
+    pub(crate) local_z: ExtensionTarget<D>,
+    pub(crate) next_z: ExtensionTarget<D>,
+    pub(crate) challenge: PermutationChallenge<Target>,
+    pub(crate) columns: &'a [Column<F>],
+    pub(crate) filter_column: &'a Option<Column<F>>,
+}
+
+impl<'a, F: Field, const D: usize> CtlCheckVarsTarget<'a, F, D> {
+    /// The `CtlCheckVarsTarget` of `table`, from the openings of its proof.
+    pub(crate) fn from_openings(
+        table: usize,
+        openings: &StarkOpeningSetTarget<D>,
+        cross_table_lookups: &'a [CrossTableLookup<F>],
+        ctl_challenges: &PermutationChallengeSet<Target>,
+    ) -> Vec<Self> {
+        let ctl_zs = openings
+            .ctl_zs
+            .iter()
+            .flatten()
+            .zip(openings.ctl_zs_next.iter().flatten());
+        ctl_zs_of_table(cross_table_lookups, ctl_challenges, table)
+            .into_iter()
+            .zip(ctl_zs)
+            .map(|((challenge, twc), (&local_z, &next_z))| Self {
+                local_z,
+                next_z,
+                challenge,
+                columns: &twc.columns,
+                filter_column: &twc.filter_column,
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn eval_cross_table_lookup_checks_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    ctl_vars: &[CtlCheckVarsTarget<F, D>],
+    consumer: &mut RecursiveConstraintConsumer<F, D>,
+) {
+    let one = builder.one_extension();
+    for lookup_vars in ctl_vars {
+        let CtlCheckVarsTarget {
+            local_z,
+            next_z,
+            challenge: PermutationChallenge { beta, gamma },
+            columns,
+            filter_column,
+        } = lookup_vars;
+        let evals = columns
+            .iter()
+            .map(|column| column.eval_circuit(builder, local_values))
+            .collect::<Vec<_>>();
+        let beta = builder.convert_to_ext(*beta);
+        let gamma = builder.convert_to_ext(*gamma);
+        let combination = ReducingFactorTarget::new(beta).reduce(&evals, builder);
+        let combination = builder.add_extension(combination, gamma);
+        let filter = match filter_column {
+            Some(column) => column.eval_circuit(builder, local_values),
+            None => one,
+        };
+        // factor = filter * (combination - 1) + 1
+        let factor = {
+            let tmp = builder.mul_sub_extension(filter, combination, filter);
+            builder.add_extension(tmp, one)
+        };
+
+        let last_row_constraint = builder.sub_extension(*local_z, factor);
+        consumer.constraint_last_row(builder, last_row_constraint);
+        // local_z - next_z * factor
+        let transition_constraint =
+            builder.arithmetic_extension(F::NEG_ONE, F::ONE, *next_z, factor, *local_z);
+        consumer.constraint_transition(builder, transition_constraint);
+    }
+}
+
+/// Checks that, for each lookup and challenge, the product of the `Z(1)`s of the looking tables is
+/// the `Z(1)` of the looked table. `ctl_zs_first` holds the `Z(1)`s of each table.
+pub(crate) fn verify_cross_table_lookups<F: RichField + Extendable<D>, const D: usize>(
+    cross_table_lookups: &[CrossTableLookup<F>],
+    ctl_zs_first: &[Vec<F::Extension>],
+    config: &StarkConfig,
+) -> Result<()> {
+    let mut ctl_zs_openings = ctl_zs_first.iter().map(|zs| zs.iter()).collect::<Vec<_>>();
+    for (
+        i,
+        CrossTableLookup {
+            looking_tables,
+            looked_table,
+        },
+    ) in cross_table_lookups.iter().enumerate()
+    {
+        for _ in 0..config.num_challenges {
+            let looking_zs_prod = looking_tables
+                .iter()
+                .map(|twc| *ctl_zs_openings[twc.table].next().unwrap())
+                .product::<F::Extension>();
+            let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
+            ensure!(
+                looking_zs_prod == looked_z,
+                "Cross-table lookup {} verification failed.",
+                i
+            );
+        }
+    }
+    debug_assert!(ctl_zs_openings.iter_mut().all(|iter| iter.next().is_none()));
+
+    Ok(())
+}
+
+pub(crate) fn verify_cross_table_lookups_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    cross_table_lookups: &[CrossTableLookup<F>],
+    ctl_zs_first: &[Vec<ExtensionTarget<D>>],
+    inner_config: &StarkConfig,
+) {
+    let mut ctl_zs_openings = ctl_zs_first.iter().map(|zs| zs.iter()).collect::<Vec<_>>();
+    for CrossTableLookup {
+        looking_tables,
+        looked_table,
+    } in cross_table_lookups
+    {
+        for _ in 0..inner_config.num_challenges {
+            let looking_zs = looking_tables
+                .iter()
+                .map(|twc| *ctl_zs_openings[twc.table].next().unwrap())
+                .collect::<Vec<_>>();
+            let looking_zs_prod = builder.mul_many_extension(looking_zs);
+            let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
+            builder.connect_extension(looking_zs_prod, looked_z);
+        }
+    }
+    debug_assert!(ctl_zs_openings.iter_mut().all(|iter| iter.next().is_none()));
+}
@@ -137,7 +137,7 @@ mod tests {
 
     use crate::config::StarkConfig;
     use crate::fibonacci_stark::FibonacciStark;
-    use crate::proof::StarkProofWithPublicInputs;
+    use crate::proof::{CompressedStarkProofWithPublicInputs, StarkProofWithPublicInputs};
     use crate::prover::prove;
     use crate::recursive_verifier::{
         add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
@@ -174,6 +174,125 @@ mod tests {
         verify_stark_proof(stark, proof, &config)
     }
 
+    #[test]
+    fn test_fibonacci_stark_zero_knowledge() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig {
+            zero_knowledge: true,
+            ..StarkConfig::standard_fast_config()
+        };
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace.clone(),
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        assert_eq!(proof.proof.recover_degree_bits(&config), 5);
+
+        // The trace is blinded afresh by each proof.
+        let other_proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        assert_ne!(other_proof.proof.trace_cap, proof.proof.trace_cap);
+        assert_ne!(other_proof.to_bytes(), proof.to_bytes());
+
+        verify_stark_proof(stark, proof, &config)?;
+        verify_stark_proof(stark, other_proof, &config)
+    }
+
+    #[test]
+    fn test_fibonacci_stark_serialization() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+
+        let bytes = proof.to_bytes();
+        let mut truncated = bytes.clone();
+        truncated.pop();
+        assert!(
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(truncated, &stark, &config, 5)
+                .is_err()
+        );
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(extended, &stark, &config, 5)
+                .is_err()
+        );
+
+        let proof = StarkProofWithPublicInputs::<F, C, D>::from_bytes(bytes, &stark, &config, 5)?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn test_fibonacci_stark_compression() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        for zero_knowledge in [false, true] {
+            let config = StarkConfig {
+                zero_knowledge,
+                ..StarkConfig::standard_fast_config()
+            };
+            let num_rows = 1 << 5;
+            let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+            let stark = S::new(num_rows);
+            let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace,
+                public_inputs,
+                &mut TimingTree::default(),
+            )?;
+
+            let compressed_proof = proof.clone().compress(&stark, &config)?;
+            let compressed_bytes = compressed_proof.to_bytes();
+            assert!(compressed_bytes.len() < proof.to_bytes().len());
+
+            let compressed_proof = CompressedStarkProofWithPublicInputs::<F, C, D>::from_bytes(
+                compressed_bytes,
+                &stark,
+                &config,
+                5,
+            )?;
+            let decompressed_proof = compressed_proof.decompress(&stark, &config, 5)?;
+            assert_eq!(decompressed_proof.to_bytes(), proof.to_bytes());
+            verify_stark_proof(stark, decompressed_proof, &config)?;
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_fibonacci_stark_degree() -> Result<()> {
         const D: usize = 2;
@@ -223,6 +342,34 @@ mod tests {
         recursive_proof::<F, C, S, C, D>(stark, proof, &config, true)
     }
 
+    #[test]
+    fn test_recursive_stark_verifier_zero_knowledge() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig {
+            zero_knowledge: true,
+            ..StarkConfig::standard_fast_config()
+        };
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false)
+    }
+
     fn recursive_proof<
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
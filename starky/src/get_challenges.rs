@@ -2,7 +2,7 @@ use alloc::vec::Vec;
 
 use plonky2::field::extension::Extendable;
 use plonky2::field::polynomial::PolynomialCoeffs;
-use plonky2::fri::proof::{FriProof, FriProofTarget};
+use plonky2::fri::proof::{CompressedFriProof, FriProof, FriProofTarget};
 use plonky2::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
 use plonky2::hash::merkle_tree::MerkleCap;
@@ -18,10 +18,14 @@ use crate::permutation::{
 use crate::proof::*;
 use crate::stark::Stark;
 
+/// Computes the challenges of a STARK proof with a challenger which has already observed its trace
+/// cap, and possibly the ones of other tables.
 fn get_challenges<F, C, S, const D: usize>(
+    challenger: &mut Challenger<F, C::Hasher>,
     stark: &S,
-    trace_cap: &MerkleCap<F, C::Hasher>,
     permutation_zs_cap: Option<&MerkleCap<F, C::Hasher>>,
+    lookup_helpers_cap: Option<&MerkleCap<F, C::Hasher>>,
+    ctl_zs_cap: Option<&MerkleCap<F, C::Hasher>>,
     quotient_polys_cap: &MerkleCap<F, C::Hasher>,
     openings: &StarkOpeningSet<F, D>,
     commit_phase_merkle_caps: &[MerkleCap<F, C::Hasher>],
@@ -37,19 +41,23 @@ where
 {
     let num_challenges = config.num_challenges;
 
-    let mut challenger = Challenger::<F, C::Hasher>::new();
-
-    challenger.observe_cap(trace_cap);
-
     let permutation_challenge_sets = permutation_zs_cap.map(|permutation_zs_cap| {
         let tmp = get_n_permutation_challenge_sets(
-            &mut challenger,
+            challenger,
             num_challenges,
             stark.permutation_batch_size(),
         );
         challenger.observe_cap(permutation_zs_cap);
         tmp
     });
+    let lookup_challenges = lookup_helpers_cap.map(|lookup_helpers_cap| {
+        let tmp = challenger.get_n_challenges(num_challenges);
+        challenger.observe_cap(lookup_helpers_cap);
+        tmp
+    });
+    if let Some(ctl_zs_cap) = ctl_zs_cap {
+        challenger.observe_cap(ctl_zs_cap);
+    }
 
     let stark_alphas = challenger.get_n_challenges(num_challenges);
 
@@ -60,13 +68,14 @@ where
 
     StarkProofChallenges {
         permutation_challenge_sets,
+        lookup_challenges,
         stark_alphas,
         stark_zeta,
         fri_challenges: challenger.fri_challenges::<C, D>(
             commit_phase_merkle_caps,
             final_poly,
             pow_witness,
-            degree_bits,
+            config.committed_degree_bits(degree_bits),
             &config.fri_config,
         ),
     }
@@ -77,8 +86,6 @@ where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
 {
-    // TODO: Should be used later in compression?
-    #![allow(dead_code)]
     pub(crate) fn fri_query_indices<S: Stark<F, D>>(
         &self,
         stark: &S,
@@ -96,10 +103,33 @@ where
         stark: &S,
         config: &StarkConfig,
         degree_bits: usize,
+    ) -> StarkProofChallenges<F, D> {
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        challenger.observe_cap(&self.proof.trace_cap);
+        self.proof
+            .get_challenges(&mut challenger, stark, config, degree_bits)
+    }
+}
+
+impl<F, C, const D: usize> StarkProof<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// Computes the Fiat-Shamir challenges of the proof of a table, with a challenger which has
+    /// already observed its trace cap.
+    pub(crate) fn get_challenges<S: Stark<F, D>>(
+        &self,
+        challenger: &mut Challenger<F, C::Hasher>,
+        stark: &S,
+        config: &StarkConfig,
+        degree_bits: usize,
     ) -> StarkProofChallenges<F, D> {
         let StarkProof {
-            trace_cap,
+            trace_cap: _,
             permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
             quotient_polys_cap,
             openings,
             opening_proof:
@@ -109,12 +139,80 @@ where
                     pow_witness,
                     ..
                 },
-        } = &self.proof;
+        } = self;
+
+        get_challenges::<F, C, S, D>(
+            challenger,
+            stark,
+            permutation_zs_cap.as_ref(),
+            lookup_helpers_cap.as_ref(),
+            ctl_zs_cap.as_ref(),
+            quotient_polys_cap,
+            openings,
+            commit_phase_merkle_caps,
+            final_poly,
+            *pow_witness,
+            config,
+            degree_bits,
+        )
+    }
+}
+
+impl<F, C, const D: usize> CompressedStarkProofWithPublicInputs<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// Computes all Fiat-Shamir challenges used in the STARK proof.
+    pub(crate) fn get_challenges<S: Stark<F, D>>(
+        &self,
+        stark: &S,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> StarkProofChallenges<F, D> {
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        challenger.observe_cap(&self.proof.trace_cap);
+        self.proof
+            .get_challenges(&mut challenger, stark, config, degree_bits)
+    }
+}
+
+impl<F, C, const D: usize> CompressedStarkProof<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// Computes the Fiat-Shamir challenges of the compressed proof of a table, with a challenger
+    /// which has already observed its trace cap.
+    pub(crate) fn get_challenges<S: Stark<F, D>>(
+        &self,
+        challenger: &mut Challenger<F, C::Hasher>,
+        stark: &S,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> StarkProofChallenges<F, D> {
+        let CompressedStarkProof {
+            trace_cap: _,
+            permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof:
+                CompressedFriProof {
+                    commit_phase_merkle_caps,
+                    final_poly,
+                    pow_witness,
+                    ..
+                },
+        } = self;
 
         get_challenges::<F, C, S, D>(
+            challenger,
             stark,
-            trace_cap,
             permutation_zs_cap.as_ref(),
+            lookup_helpers_cap.as_ref(),
+            ctl_zs_cap.as_ref(),
             quotient_polys_cap,
             openings,
             commit_phase_merkle_caps,
@@ -134,9 +232,11 @@ pub(crate) fn get_challenges_target<
     const D: usize,
 >(
     builder: &mut CircuitBuilder<F, D>,
+    challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
     stark: &S,
-    trace_cap: &MerkleCapTarget,
     permutation_zs_cap: Option<&MerkleCapTarget>,
+    lookup_helpers_cap: Option<&MerkleCapTarget>,
+    ctl_zs_cap: Option<&MerkleCapTarget>,
     quotient_polys_cap: &MerkleCapTarget,
     openings: &StarkOpeningSetTarget<D>,
     commit_phase_merkle_caps: &[MerkleCapTarget],
@@ -149,20 +249,24 @@ where
 {
     let num_challenges = config.num_challenges;
 
-    let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
-
-    challenger.observe_cap(trace_cap);
-
     let permutation_challenge_sets = permutation_zs_cap.map(|permutation_zs_cap| {
         let tmp = get_n_permutation_challenge_sets_target(
             builder,
-            &mut challenger,
+            challenger,
             num_challenges,
             stark.permutation_batch_size(),
         );
         challenger.observe_cap(permutation_zs_cap);
         tmp
     });
+    let lookup_challenges = lookup_helpers_cap.map(|lookup_helpers_cap| {
+        let tmp = challenger.get_n_challenges(builder, num_challenges);
+        challenger.observe_cap(lookup_helpers_cap);
+        tmp
+    });
+    if let Some(ctl_zs_cap) = ctl_zs_cap {
+        challenger.observe_cap(ctl_zs_cap);
+    }
 
     let stark_alphas = challenger.get_n_challenges(builder, num_challenges);
 
@@ -173,6 +277,7 @@ where
 
     StarkProofChallengesTarget {
         permutation_challenge_sets,
+        lookup_challenges,
         stark_alphas,
         stark_zeta,
         fri_challenges: challenger.fri_challenges::<C>(
@@ -196,12 +301,38 @@ impl<const D: usize> StarkProofWithPublicInputsTarget<D> {
         stark: &S,
         config: &StarkConfig,
     ) -> StarkProofChallengesTarget<D>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
+        challenger.observe_cap(&self.proof.trace_cap);
+        self.proof
+            .get_challenges::<F, C, S>(builder, &mut challenger, stark, config)
+    }
+}
+
+impl<const D: usize> StarkProofTarget<D> {
+    /// Computes the Fiat-Shamir challenges of the proof of a table, with a challenger which has
+    /// already observed its trace cap.
+    pub(crate) fn get_challenges<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    >(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> StarkProofChallengesTarget<D>
     where
         C::Hasher: AlgebraicHasher<F>,
     {
         let StarkProofTarget {
-            trace_cap,
+            trace_cap: _,
             permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
             quotient_polys_cap,
             openings,
             opening_proof:
@@ -211,13 +342,15 @@ impl<const D: usize> StarkProofWithPublicInputsTarget<D> {
                     pow_witness,
                     ..
                 },
-        } = &self.proof;
+        } = self;
 
         get_challenges_target::<F, C, S, D>(
             builder,
+            challenger,
             stark,
-            trace_cap,
             permutation_zs_cap.as_ref(),
+            lookup_helpers_cap.as_ref(),
+            ctl_zs_cap.as_ref(),
             quotient_polys_cap,
             openings,
             commit_phase_merkle_caps,
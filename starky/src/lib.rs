@@ -8,12 +8,16 @@ extern crate alloc;
 
 mod get_challenges;
 
+pub mod all_stark;
 pub mod config;
 pub mod constraint_consumer;
+pub mod cross_table_lookup;
+pub mod lookup;
 pub mod permutation;
 pub mod proof;
 pub mod prover;
 pub mod recursive_verifier;
+pub mod serialization;
 pub mod stark;
 pub mod stark_testing;
 pub mod util;
@@ -23,3 +27,7 @@ pub mod verifier;
 
 #[cfg(test)]
 pub mod fibonacci_stark;
+#[cfg(test)]
+pub mod range_check_stark;
+#[cfg(test)]
+pub mod sum_of_squares_stark;
@@ -0,0 +1,281 @@
+//! Lookup arguments, based on logarithmic derivatives.
+//!
+//! A lookup checks that every value of some looking columns appears in a table column, by checking
+//! that for a random `alpha`,
+//!     \sum_i \sum_{f \in columns} 1 / (alpha + f(w^i)) = \sum_i m(w^i) / (alpha + t(w^i))
+//! where `t` is the table column and `m` its frequencies column, i.e. the number of times each
+//! table value is looked up.
+
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::iop::target::Target;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::util::ceil_div_usize;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::stark::Stark;
+use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+
+/// A lookup of the values of `columns` into `table_column`. In each row, `frequencies_column` holds
+/// the number of times the table value of that row is looked up.
+#[derive(Clone, Debug)]
+pub struct Lookup {
+    /// The looking columns.
+    pub columns: Vec<usize>,
+    pub table_column: usize,
+    pub frequencies_column: usize,
+}
+
+impl Lookup {
+    pub fn new(columns: Vec<usize>, table_column: usize, frequencies_column: usize) -> Self {
+        Self {
+            columns,
+            table_column,
+            frequencies_column,
+        }
+    }
+
+    /// The number of helper columns of the lookup, for each challenge. A helper column holds
+    /// `\sum 1 / (alpha + f)` over a batch of `constraint_degree - 1` looking columns `f`, and the
+    /// last one is the running sum `Z` of the difference between the two sides of the lookup.
+    pub fn num_helper_columns(&self, constraint_degree: usize) -> usize {
+        ceil_div_usize(self.columns.len(), constraint_degree - 1) + 1
+    }
+}
+
+/// Compute the helper columns of all the lookups of `stark`: for each challenge, the helper columns
+/// of each lookup.
+pub(crate) fn compute_lookup_helper_columns<F, S, const D: usize>(
+    stark: &S,
+    trace_poly_values: &[PolynomialValues<F>],
+    lookup_challenges: &[F],
+) -> Vec<PolynomialValues<F>>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let constraint_degree = stark.constraint_degree();
+    let lookups = stark.lookups();
+    let mut helper_columns = Vec::new();
+    for &challenge in lookup_challenges {
+        for lookup in &lookups {
+            helper_columns.extend(lookup_helper_columns(
+                lookup,
+                trace_poly_values,
+                challenge,
+                constraint_degree,
+            ));
+        }
+    }
+    helper_columns
+}
+
+/// Compute the helper columns of a single lookup.
+fn lookup_helper_columns<F: Field>(
+    lookup: &Lookup,
+    trace_poly_values: &[PolynomialValues<F>],
+    challenge: F,
+    constraint_degree: usize,
+) -> Vec<PolynomialValues<F>> {
+    let degree = trace_poly_values[0].len();
+    let shifted_inverses = |column: usize| {
+        let shifted = trace_poly_values[column]
+            .values
+            .iter()
+            .map(|&v| challenge + v)
+            .collect_vec();
+        F::batch_multiplicative_inverse(&shifted)
+    };
+
+    let mut helper_columns = lookup
+        .columns
+        .chunks(constraint_degree - 1)
+        .map(|batch| {
+            let mut helper_column = PolynomialValues::zero(degree);
+            for &column in batch {
+                for (h, inverse) in helper_column
+                    .values
+                    .iter_mut()
+                    .zip(shifted_inverses(column))
+                {
+                    *h += inverse;
+                }
+            }
+            helper_column
+        })
+        .collect_vec();
+
+    // Compute Z, with Z(1) = 0 and Z(g x) = Z(x) + \sum h_i(x) - m(x) / (alpha + t(x)).
+    let table_inverses = shifted_inverses(lookup.table_column);
+    let frequencies = &trace_poly_values[lookup.frequencies_column].values;
+    let mut z = Vec::with_capacity(degree);
+    let mut acc = F::ZERO;
+    for i in 0..degree {
+        z.push(acc);
+        acc += helper_columns.iter().map(|h| h.values[i]).sum::<F>()
+            - frequencies[i] * table_inverses[i];
+    }
+    helper_columns.push(PolynomialValues::new(z));
+    helper_columns
+}
+
+pub struct LookupCheckVars<F, FE, P, const D2: usize>
+where
+    F: Field,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    pub(crate) local_values: Vec<P>,
+    pub(crate) next_values: Vec<P>,
+    pub(crate) challenges: Vec<F>,
+}
+
+pub(crate) fn eval_lookup_checks<F, FE, P, S, const D: usize, const D2: usize>(
+    stark: &S,
+    vars: StarkEvaluationVars<FE, P, { S::COLUMNS }, { S::PUBLIC_INPUTS }>,
+    lookup_vars: LookupCheckVars<F, FE, P, D2>,
+    consumer: &mut ConstraintConsumer<P>,
+) where
+    F: RichField + Extendable<D>,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+    S: Stark<F, D>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+{
+    let LookupCheckVars {
+        local_values,
+        next_values,
+        challenges,
+    } = lookup_vars;
+    let constraint_degree = stark.constraint_degree();
+    let lookups = stark.lookups();
+
+    let mut start = 0;
+    for challenge in challenges {
+        let challenge = FE::from_basefield(challenge);
+        for lookup in &lookups {
+            let num_helper_columns = lookup.num_helper_columns(constraint_degree);
+            let z_index = start + num_helper_columns - 1;
+
+            // h(x) \prod_j (alpha + f_j(x)) = \sum_j \prod_{k != j} (alpha + f_k(x))
+            for (i, batch) in lookup.columns.chunks(constraint_degree - 1).enumerate() {
+                let shifted = batch
+                    .iter()
+                    .map(|&column| vars.local_values[column] + challenge)
+                    .collect_vec();
+                let product = shifted.iter().copied().product::<P>();
+                let sum_of_products = (0..shifted.len())
+                    .map(|j| {
+                        shifted
+                            .iter()
+                            .enumerate()
+                            .filter(|&(k, _)| k != j)
+                            .map(|(_, &s)| s)
+                            .product::<P>()
+                    })
+                    .sum::<P>();
+                consumer.constraint(local_values[start + i] * product - sum_of_products);
+            }
+
+            // Check that Z(1) = 0.
+            consumer.constraint_first_row(local_values[z_index]);
+            // (Z(g x) - Z(x) - \sum h_i(x)) (alpha + t(x)) + m(x) = 0. This also holds on the last
+            // row, where Z(g x) = Z(1), so both sides of the lookup must sum to the same value.
+            let helpers_sum = local_values[start..z_index].iter().copied().sum::<P>();
+            let table_with_challenge = vars.local_values[lookup.table_column] + challenge;
+            consumer.constraint(
+                (next_values[z_index] - local_values[z_index] - helpers_sum) * table_with_challenge
+                    + vars.local_values[lookup.frequencies_column],
+            );
+
+            start += num_helper_columns;
+        }
+    }
+}
+
+pub struct LookupCheckVarsTarget<const D: usize> {
+    pub(crate) local_values: Vec<ExtensionTarget<D>>,
+    pub(crate) next_values: Vec<ExtensionTarget<D>>,
+    pub(crate) challenges: Vec<Target>,
+}
+
+pub(crate) fn eval_lookup_checks_circuit<F, S, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: &S,
+    vars: StarkEvaluationTargets<D, { S::COLUMNS }, { S::PUBLIC_INPUTS }>,
+    lookup_vars: LookupCheckVarsTarget<D>,
+    consumer: &mut RecursiveConstraintConsumer<F, D>,
+) where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+{
+    let LookupCheckVarsTarget {
+        local_values,
+        next_values,
+        challenges,
+    } = lookup_vars;
+    let constraint_degree = stark.constraint_degree();
+    let lookups = stark.lookups();
+
+    let mut start = 0;
+    for challenge in challenges {
+        let challenge = builder.convert_to_ext(challenge);
+        for lookup in &lookups {
+            let num_helper_columns = lookup.num_helper_columns(constraint_degree);
+            let z_index = start + num_helper_columns - 1;
+
+            // h(x) \prod_j (alpha + f_j(x)) = \sum_j \prod_{k != j} (alpha + f_k(x))
+            for (i, batch) in lookup.columns.chunks(constraint_degree - 1).enumerate() {
+                let shifted = batch
+                    .iter()
+                    .map(|&column| builder.add_extension(vars.local_values[column], challenge))
+                    .collect_vec();
+                let product = builder.mul_many_extension(&shifted);
+                let products = (0..shifted.len())
+                    .map(|j| {
+                        let others = shifted
+                            .iter()
+                            .enumerate()
+                            .filter(|&(k, _)| k != j)
+                            .map(|(_, &s)| s)
+                            .collect_vec();
+                        builder.mul_many_extension(others)
+                    })
+                    .collect_vec();
+                let sum_of_products = builder.add_many_extension(products);
+                let constraint =
+                    builder.mul_sub_extension(local_values[start + i], product, sum_of_products);
+                consumer.constraint(builder, constraint);
+            }
+
+            // Check that Z(1) = 0.
+            consumer.constraint_first_row(builder, local_values[z_index]);
+            // (Z(g x) - Z(x) - \sum h_i(x)) (alpha + t(x)) + m(x) = 0
+            let helpers_sum = builder.add_many_extension(&local_values[start..z_index]);
+            let table_with_challenge =
+                builder.add_extension(vars.local_values[lookup.table_column], challenge);
+            let constraint = {
+                let tmp = builder.sub_extension(next_values[z_index], local_values[z_index]);
+                let tmp = builder.sub_extension(tmp, helpers_sum);
+                builder.mul_add_extension(
+                    tmp,
+                    table_with_challenge,
+                    vars.local_values[lookup.frequencies_column],
+                )
+            };
+            consumer.constraint(builder, constraint);
+
+            start += num_helper_columns;
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
+use anyhow::{anyhow, ensure, Result};
 use itertools::Itertools;
 use maybe_rayon::*;
 use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::types::Field;
 use plonky2::fri::oracle::PolynomialBatch;
 use plonky2::fri::proof::{
     CompressedFriProof, FriChallenges, FriChallengesTarget, FriProof, FriProofTarget,
@@ -11,14 +13,25 @@ use plonky2::fri::proof::{
 use plonky2::fri::structure::{
     FriOpeningBatch, FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget,
 };
+use plonky2::fri::FriParams;
 use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
 use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::iop::challenger::Challenger;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::proof::FriInferredElements;
+#[cfg(feature = "std")]
+use plonky2::util::serialization::{Buffer, Remaining};
 
+use crate::all_stark::{AllStark, TableVisitor};
 use crate::config::StarkConfig;
-use crate::permutation::PermutationChallengeSet;
+use crate::cross_table_lookup::CrossTableLookup;
+use crate::permutation::{get_permutation_challenge_set, PermutationChallengeSet};
+#[cfg(feature = "std")]
+use crate::serialization::StarkRead;
+use crate::serialization::StarkWrite;
+use crate::stark::Stark;
 
 #[derive(Debug, Clone)]
 pub struct StarkProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
@@ -26,6 +39,10 @@ pub struct StarkProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>,
     pub trace_cap: MerkleCap<F, C::Hasher>,
     /// Merkle cap of LDEs of permutation Z values.
     pub permutation_zs_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of lookup helper columns.
+    pub lookup_helpers_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of cross-table lookup Z values.
+    pub ctl_zs_cap: Option<MerkleCap<F, C::Hasher>>,
     /// Merkle cap of LDEs of trace values.
     pub quotient_polys_cap: MerkleCap<F, C::Hasher>,
     /// Purported values of each polynomial at the challenge point.
@@ -42,13 +59,38 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> S
             .evals_proofs[0]
             .1;
         let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+        lde_bits - config.fri_config.rate_bits - usize::from(config.zero_knowledge)
+    }
+
+    /// Compress the proof, given the FRI query indices.
+    pub fn compress(self, indices: &[usize], params: &FriParams) -> CompressedStarkProof<F, C, D> {
+        let StarkProof {
+            trace_cap,
+            permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        } = self;
+
+        CompressedStarkProof {
+            trace_cap,
+            permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof: opening_proof.compress::<C>(indices, params),
+        }
     }
 }
 
 pub struct StarkProofTarget<const D: usize> {
     pub trace_cap: MerkleCapTarget,
     pub permutation_zs_cap: Option<MerkleCapTarget>,
+    pub lookup_helpers_cap: Option<MerkleCapTarget>,
+    pub ctl_zs_cap: Option<MerkleCapTarget>,
     pub quotient_polys_cap: MerkleCapTarget,
     pub openings: StarkOpeningSetTarget<D>,
     pub opening_proof: FriProofTarget<D>,
@@ -62,7 +104,7 @@ impl<const D: usize> StarkProofTarget<D> {
             .evals_proofs[0]
             .1;
         let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+        lde_bits - config.fri_config.rate_bits - usize::from(config.zero_knowledge)
     }
 }
 
@@ -77,11 +119,184 @@ pub struct StarkProofWithPublicInputs<
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkProofWithPublicInputs<F, C, D>
+{
+    /// Compress the FRI query paths of the proof. The proof of a table of an `AllStark` is
+    /// rejected, as its challenges depend on the other tables: use `AllProof::compress` instead.
+    pub fn compress<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> Result<CompressedStarkProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            self.proof.ctl_zs_cap.is_none(),
+            "The proof has cross-table lookups, use `AllProof::compress`."
+        );
+        let degree_bits = self.proof.recover_degree_bits(config);
+        let indices = self.fri_query_indices(stark, config, degree_bits);
+        Ok(self.compress_with_indices(&indices, config, degree_bits))
+    }
+
+    fn compress_with_indices(
+        self,
+        indices: &[usize],
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> CompressedStarkProofWithPublicInputs<F, C, D> {
+        let compressed_proof = self
+            .proof
+            .compress(indices, &config.fri_params(degree_bits));
+        CompressedStarkProofWithPublicInputs {
+            public_inputs: self.public_inputs,
+            proof: compressed_proof,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_stark_proof_with_public_inputs(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Reads a proof of a trace of `2^degree_bits` rows, whose shape must match `stark` and
+    /// `config`. The proofs of the tables of an `AllStark` are read with `AllProof::from_bytes`.
+    #[cfg(feature = "std")]
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let proof = buffer
+            .read_stark_proof_with_public_inputs(stark, 0, config, degree_bits)
+            .map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after the proof.");
+        Ok(proof)
+    }
+}
+
 pub struct StarkProofWithPublicInputsTarget<const D: usize> {
     pub proof: StarkProofTarget<D>,
     pub public_inputs: Vec<Target>,
 }
 
+/// The proofs of the tables of an `AllStark`, in order.
+#[derive(Debug, Clone)]
+pub struct AllProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    pub stark_proofs: Vec<StarkProofWithPublicInputs<F, C, D>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> AllProof<F, C, D> {
+    /// Recover the length of the trace of each table.
+    pub fn degree_bits(&self, config: &StarkConfig) -> Vec<usize> {
+        self.stark_proofs
+            .iter()
+            .map(|proof| proof.proof.recover_degree_bits(config))
+            .collect()
+    }
+
+    /// Compress the FRI query paths of the proofs of the tables of `all_stark`. The query indices
+    /// are drawn from the challenger shared by the tables, as in `verify_all_proof`.
+    pub fn compress<A: AllStark<F, D>>(
+        self,
+        all_stark: &A,
+        config: &StarkConfig,
+    ) -> Result<CompressedAllProof<F, C, D>> {
+        let num_tables = all_stark.num_tables();
+        ensure!(
+            self.stark_proofs.len() == num_tables,
+            "Expected the proofs of {} tables, got {}",
+            num_tables,
+            self.stark_proofs.len()
+        );
+        let degree_bits = self.degree_bits(config);
+        let mut challenger = all_proof_challenger::<F, C, D>(
+            &self
+                .stark_proofs
+                .iter()
+                .map(|proof| &proof.proof.trace_cap)
+                .collect::<Vec<_>>(),
+            config,
+        );
+
+        let mut query_indices_reader = TableQueryIndicesReader {
+            config,
+            stark_proofs: &self.stark_proofs,
+            degree_bits: &degree_bits,
+            challenger: &mut challenger,
+            fri_query_indices: Vec::with_capacity(num_tables),
+        };
+        for table in 0..num_tables {
+            all_stark.visit_table(table, &mut query_indices_reader)?;
+        }
+        let fri_query_indices = query_indices_reader.fri_query_indices;
+
+        let stark_proofs = self
+            .stark_proofs
+            .into_iter()
+            .zip(fri_query_indices)
+            .zip(degree_bits)
+            .map(|((proof, indices), degree_bits)| {
+                proof.compress_with_indices(&indices, config, degree_bits)
+            })
+            .collect();
+        Ok(CompressedAllProof { stark_proofs })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        for proof in &self.stark_proofs {
+            buffer
+                .write_stark_proof_with_public_inputs(proof)
+                .expect("Writing to a byte-vector cannot fail.");
+        }
+        buffer
+    }
+
+    /// Reads the proofs of the tables of `all_stark`, with traces of `2^degree_bits[table]` rows.
+    #[cfg(feature = "std")]
+    pub fn from_bytes<A: AllStark<F, D>>(
+        bytes: Vec<u8>,
+        all_stark: &A,
+        config: &StarkConfig,
+        degree_bits: &[usize],
+    ) -> Result<Self> {
+        let num_tables = all_stark.num_tables();
+        ensure!(
+            degree_bits.len() == num_tables,
+            "Expected the degrees of {} tables, got {}",
+            num_tables,
+            degree_bits.len()
+        );
+        let mut table_reader = TableProofReader {
+            buffer: Buffer::new(bytes),
+            config,
+            degree_bits,
+            cross_table_lookups: all_stark.cross_table_lookups(),
+            stark_proofs: Vec::with_capacity(num_tables),
+        };
+        for table in 0..num_tables {
+            all_stark.visit_table(table, &mut table_reader)?;
+        }
+        ensure!(
+            table_reader.buffer.is_empty(),
+            "Trailing bytes after the proof."
+        );
+        Ok(AllProof {
+            stark_proofs: table_reader.stark_proofs,
+        })
+    }
+}
+
+pub struct AllProofTarget<const D: usize> {
+    pub stark_proofs: Vec<StarkProofWithPublicInputsTarget<D>>,
+}
+
+#[derive(Debug, Clone)]
 pub struct CompressedStarkProof<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -89,12 +304,57 @@ pub struct CompressedStarkProof<
 > {
     /// Merkle cap of LDEs of trace values.
     pub trace_cap: MerkleCap<F, C::Hasher>,
+    /// Merkle cap of LDEs of permutation Z values.
+    pub permutation_zs_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of lookup helper columns.
+    pub lookup_helpers_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of cross-table lookup Z values.
+    pub ctl_zs_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of the quotient polynomial chunks.
+    pub quotient_polys_cap: MerkleCap<F, C::Hasher>,
     /// Purported values of each polynomial at the challenge point.
     pub openings: StarkOpeningSet<F, D>,
-    /// A batch FRI argument for all openings.
+    /// A compressed batch FRI argument for all openings.
     pub opening_proof: CompressedFriProof<F, C::Hasher, D>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedStarkProof<F, C, D>
+{
+    /// Decompress the proof.
+    pub(crate) fn decompress(
+        self,
+        challenges: &StarkProofChallenges<F, D>,
+        fri_inferred_elements: FriInferredElements<F, D>,
+        params: &FriParams,
+    ) -> StarkProof<F, C, D> {
+        let CompressedStarkProof {
+            trace_cap,
+            permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        } = self;
+
+        StarkProof {
+            trace_cap,
+            permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof: opening_proof.decompress::<C>(
+                &challenges.fri_challenges,
+                fri_inferred_elements,
+                params,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct CompressedStarkProofWithPublicInputs<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -104,10 +364,385 @@ pub struct CompressedStarkProofWithPublicInputs<
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedStarkProofWithPublicInputs<F, C, D>
+{
+    /// Decompress the proof of a trace of `2^degree_bits` rows. The proof of a table of an
+    /// `AllStark` is rejected, as its challenges depend on the other tables: use
+    /// `CompressedAllProof::decompress` instead.
+    pub fn decompress<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            self.proof.ctl_zs_cap.is_none(),
+            "The proof has cross-table lookups, use `CompressedAllProof::decompress`."
+        );
+        let challenges = self.get_challenges(stark, config, degree_bits);
+        Ok(self.decompress_with_challenges(stark, &challenges, 0, config, degree_bits))
+    }
+
+    fn decompress_with_challenges<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        challenges: &StarkProofChallenges<F, D>,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> StarkProofWithPublicInputs<F, C, D> {
+        let fri_params = config.fri_params(degree_bits);
+        let fri_inferred_elements = self.proof.opening_proof.inferred_elements::<C>(
+            &stark.fri_instance(
+                challenges.stark_zeta,
+                F::primitive_root_of_unity(degree_bits),
+                num_ctl_zs,
+                config,
+            ),
+            &self.proof.openings.to_fri_openings(),
+            &challenges.fri_challenges,
+            &fri_params,
+        );
+        let decompressed_proof =
+            self.proof
+                .decompress(challenges, fri_inferred_elements, &fri_params);
+        StarkProofWithPublicInputs {
+            public_inputs: self.public_inputs,
+            proof: decompressed_proof,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_compressed_stark_proof_with_public_inputs(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Reads a compressed proof of a trace of `2^degree_bits` rows, whose shape must match `stark`
+    /// and `config`. The proofs of the tables of an `AllStark` are read with
+    /// `CompressedAllProof::from_bytes`.
+    #[cfg(feature = "std")]
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let proof = buffer
+            .read_compressed_stark_proof_with_public_inputs(stark, 0, config, degree_bits)
+            .map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after the proof.");
+        Ok(proof)
+    }
+}
+
+/// The compressed proofs of the tables of an `AllStark`, in order.
+#[derive(Debug, Clone)]
+pub struct CompressedAllProof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub stark_proofs: Vec<CompressedStarkProofWithPublicInputs<F, C, D>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedAllProof<F, C, D>
+{
+    /// Decompress the proofs of the tables of `all_stark`, with traces of `2^degree_bits[table]`
+    /// rows.
+    pub fn decompress<A: AllStark<F, D>>(
+        self,
+        all_stark: &A,
+        config: &StarkConfig,
+        degree_bits: &[usize],
+    ) -> Result<AllProof<F, C, D>> {
+        let num_tables = all_stark.num_tables();
+        ensure!(
+            self.stark_proofs.len() == num_tables,
+            "Expected the proofs of {} tables, got {}",
+            num_tables,
+            self.stark_proofs.len()
+        );
+        ensure!(
+            degree_bits.len() == num_tables,
+            "Expected the degrees of {} tables, got {}",
+            num_tables,
+            degree_bits.len()
+        );
+        let mut challenger = all_proof_challenger::<F, C, D>(
+            &self
+                .stark_proofs
+                .iter()
+                .map(|proof| &proof.proof.trace_cap)
+                .collect::<Vec<_>>(),
+            config,
+        );
+
+        let mut table_decompressor = TableDecompressor {
+            config,
+            degree_bits,
+            cross_table_lookups: all_stark.cross_table_lookups(),
+            challenger: &mut challenger,
+            compressed_proofs: self.stark_proofs.into_iter(),
+            stark_proofs: Vec::with_capacity(num_tables),
+        };
+        for table in 0..num_tables {
+            all_stark.visit_table(table, &mut table_decompressor)?;
+        }
+        Ok(AllProof {
+            stark_proofs: table_decompressor.stark_proofs,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        for proof in &self.stark_proofs {
+            buffer
+                .write_compressed_stark_proof_with_public_inputs(proof)
+                .expect("Writing to a byte-vector cannot fail.");
+        }
+        buffer
+    }
+
+    /// Reads the compressed proofs of the tables of `all_stark`, with traces of
+    /// `2^degree_bits[table]` rows.
+    #[cfg(feature = "std")]
+    pub fn from_bytes<A: AllStark<F, D>>(
+        bytes: Vec<u8>,
+        all_stark: &A,
+        config: &StarkConfig,
+        degree_bits: &[usize],
+    ) -> Result<Self> {
+        let num_tables = all_stark.num_tables();
+        ensure!(
+            degree_bits.len() == num_tables,
+            "Expected the degrees of {} tables, got {}",
+            num_tables,
+            degree_bits.len()
+        );
+        let mut table_reader = CompressedTableProofReader {
+            buffer: Buffer::new(bytes),
+            config,
+            degree_bits,
+            cross_table_lookups: all_stark.cross_table_lookups(),
+            stark_proofs: Vec::with_capacity(num_tables),
+        };
+        for table in 0..num_tables {
+            all_stark.visit_table(table, &mut table_reader)?;
+        }
+        ensure!(
+            table_reader.buffer.is_empty(),
+            "Trailing bytes after the proof."
+        );
+        Ok(CompressedAllProof {
+            stark_proofs: table_reader.stark_proofs,
+        })
+    }
+}
+
+/// A challenger in the state the tables of an `AllStark` start from: it has observed the trace caps
+/// of all the tables and drawn the challenges of the cross-table lookups.
+fn all_proof_challenger<F, C, const D: usize>(
+    trace_caps: &[&MerkleCap<F, C::Hasher>],
+    config: &StarkConfig,
+) -> Challenger<F, C::Hasher>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let mut challenger = Challenger::new();
+    for trace_cap in trace_caps {
+        challenger.observe_cap(trace_cap);
+    }
+    get_permutation_challenge_set(&mut challenger, config.num_challenges);
+    challenger
+}
+
+/// Computes the FRI query indices of the proof of each table visited, in order.
+struct TableQueryIndicesReader<'a, F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    config: &'a StarkConfig,
+    stark_proofs: &'a [StarkProofWithPublicInputs<F, C, D>],
+    degree_bits: &'a [usize],
+    challenger: &'a mut Challenger<F, C::Hasher>,
+    fri_query_indices: Vec<Vec<usize>>,
+}
+
+impl<'a, F, C, const D: usize> TableVisitor<F, D> for TableQueryIndicesReader<'a, F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+    {
+        let challenges = self.stark_proofs[table].proof.get_challenges(
+            self.challenger,
+            stark,
+            self.config,
+            self.degree_bits[table],
+        );
+        self.fri_query_indices
+            .push(challenges.fri_challenges.fri_query_indices);
+        Ok(())
+    }
+}
+
+/// Decompresses the proof of each table visited, in order.
+struct TableDecompressor<'a, F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    config: &'a StarkConfig,
+    degree_bits: &'a [usize],
+    cross_table_lookups: Vec<CrossTableLookup<F>>,
+    challenger: &'a mut Challenger<F, C::Hasher>,
+    compressed_proofs: vec::IntoIter<CompressedStarkProofWithPublicInputs<F, C, D>>,
+    stark_proofs: Vec<StarkProofWithPublicInputs<F, C, D>>,
+}
+
+impl<'a, F, C, const D: usize> TableVisitor<F, D> for TableDecompressor<'a, F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+    {
+        let compressed_proof = self
+            .compressed_proofs
+            .next()
+            .ok_or_else(|| anyhow!("Missing the proof of table {}", table))?;
+        let degree_bits = self.degree_bits[table];
+        let challenges =
+            compressed_proof
+                .proof
+                .get_challenges(self.challenger, stark, self.config, degree_bits);
+        let num_ctl_zs = CrossTableLookup::num_ctl_zs(
+            &self.cross_table_lookups,
+            table,
+            self.config.num_challenges,
+        );
+        self.stark_proofs
+            .push(compressed_proof.decompress_with_challenges(
+                stark,
+                &challenges,
+                num_ctl_zs,
+                self.config,
+                degree_bits,
+            ));
+        Ok(())
+    }
+}
+
+/// Reads the proof of each table visited, in order.
+#[cfg(feature = "std")]
+struct TableProofReader<'a, F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    buffer: Buffer,
+    config: &'a StarkConfig,
+    degree_bits: &'a [usize],
+    cross_table_lookups: Vec<CrossTableLookup<F>>,
+    stark_proofs: Vec<StarkProofWithPublicInputs<F, C, D>>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, F, C, const D: usize> TableVisitor<F, D> for TableProofReader<'a, F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+    {
+        let num_ctl_zs = CrossTableLookup::num_ctl_zs(
+            &self.cross_table_lookups,
+            table,
+            self.config.num_challenges,
+        );
+        let proof = self
+            .buffer
+            .read_stark_proof_with_public_inputs(
+                stark,
+                num_ctl_zs,
+                self.config,
+                self.degree_bits[table],
+            )
+            .map_err(anyhow::Error::msg)?;
+        self.stark_proofs.push(proof);
+        Ok(())
+    }
+}
+
+/// Reads the compressed proof of each table visited, in order.
+#[cfg(feature = "std")]
+struct CompressedTableProofReader<'a, F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    buffer: Buffer,
+    config: &'a StarkConfig,
+    degree_bits: &'a [usize],
+    cross_table_lookups: Vec<CrossTableLookup<F>>,
+    stark_proofs: Vec<CompressedStarkProofWithPublicInputs<F, C, D>>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, F, C, const D: usize> TableVisitor<F, D> for CompressedTableProofReader<'a, F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+    {
+        let num_ctl_zs = CrossTableLookup::num_ctl_zs(
+            &self.cross_table_lookups,
+            table,
+            self.config.num_challenges,
+        );
+        let proof = self
+            .buffer
+            .read_compressed_stark_proof_with_public_inputs(
+                stark,
+                num_ctl_zs,
+                self.config,
+                self.degree_bits[table],
+            )
+            .map_err(anyhow::Error::msg)?;
+        self.stark_proofs.push(proof);
+        Ok(())
+    }
+}
+
 pub(crate) struct StarkProofChallenges<F: RichField + Extendable<D>, const D: usize> {
     /// Randomness used in any permutation arguments.
     pub permutation_challenge_sets: Option<Vec<PermutationChallengeSet<F>>>,
 
+    /// Randomness used in any lookup arguments.
+    pub lookup_challenges: Option<Vec<F>>,
+
     /// Random values used to combine STARK constraints.
     pub stark_alphas: Vec<F>,
 
@@ -119,6 +754,7 @@ pub(crate) struct StarkProofChallenges<F: RichField + Extendable<D>, const D: us
 
 pub(crate) struct StarkProofChallengesTarget<const D: usize> {
     pub permutation_challenge_sets: Option<Vec<PermutationChallengeSet<Target>>>,
+    pub lookup_challenges: Option<Vec<Target>>,
     pub stark_alphas: Vec<Target>,
     pub stark_zeta: ExtensionTarget<D>,
     pub fri_challenges: FriChallengesTarget<D>,
@@ -131,6 +767,13 @@ pub struct StarkOpeningSet<F: RichField + Extendable<D>, const D: usize> {
     pub next_values: Vec<F::Extension>,
     pub permutation_zs: Option<Vec<F::Extension>>,
     pub permutation_zs_next: Option<Vec<F::Extension>>,
+    pub lookup_helpers: Option<Vec<F::Extension>>,
+    pub lookup_helpers_next: Option<Vec<F::Extension>>,
+    pub ctl_zs: Option<Vec<F::Extension>>,
+    pub ctl_zs_next: Option<Vec<F::Extension>>,
+    /// The cross-table lookup Z values at the first row, i.e. the products of the lookups.
+    pub ctl_zs_first: Option<Vec<F::Extension>>,
+    /// The chunks of the quotient polynomials, `num_quotient_chunks` for each challenge.
     pub quotient_polys: Vec<F::Extension>,
 }
 
@@ -140,6 +783,8 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
         g: F,
         trace_commitment: &PolynomialBatch<F, C, D>,
         permutation_zs_commitment: Option<&PolynomialBatch<F, C, D>>,
+        lookup_helpers_commitment: Option<&PolynomialBatch<F, C, D>>,
+        ctl_zs_commitment: Option<&PolynomialBatch<F, C, D>>,
         quotient_commitment: &PolynomialBatch<F, C, D>,
     ) -> Self {
         let eval_commitment = |z: F::Extension, c: &PolynomialBatch<F, C, D>| {
@@ -154,6 +799,11 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
             next_values: eval_commitment(zeta_next, trace_commitment),
             permutation_zs: permutation_zs_commitment.map(|c| eval_commitment(zeta, c)),
             permutation_zs_next: permutation_zs_commitment.map(|c| eval_commitment(zeta_next, c)),
+            lookup_helpers: lookup_helpers_commitment.map(|c| eval_commitment(zeta, c)),
+            lookup_helpers_next: lookup_helpers_commitment.map(|c| eval_commitment(zeta_next, c)),
+            ctl_zs: ctl_zs_commitment.map(|c| eval_commitment(zeta, c)),
+            ctl_zs_next: ctl_zs_commitment.map(|c| eval_commitment(zeta_next, c)),
+            ctl_zs_first: ctl_zs_commitment.map(|c| eval_commitment(F::Extension::ONE, c)),
             quotient_polys: eval_commitment(zeta, quotient_commitment),
         }
     }
@@ -164,6 +814,8 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
                 .local_values
                 .iter()
                 .chain(self.permutation_zs.iter().flatten())
+                .chain(self.lookup_helpers.iter().flatten())
+                .chain(self.ctl_zs.iter().flatten())
                 .chain(&self.quotient_polys)
                 .copied()
                 .collect_vec(),
@@ -173,12 +825,18 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
                 .next_values
                 .iter()
                 .chain(self.permutation_zs_next.iter().flatten())
+                .chain(self.lookup_helpers_next.iter().flatten())
+                .chain(self.ctl_zs_next.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
-        FriOpenings {
-            batches: vec![zeta_batch, zeta_next_batch],
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        if let Some(ctl_zs_first) = &self.ctl_zs_first {
+            batches.push(FriOpeningBatch {
+                values: ctl_zs_first.clone(),
+            });
         }
+        FriOpenings { batches }
     }
 }
 
@@ -187,6 +845,11 @@ pub struct StarkOpeningSetTarget<const D: usize> {
     pub next_values: Vec<ExtensionTarget<D>>,
     pub permutation_zs: Option<Vec<ExtensionTarget<D>>>,
     pub permutation_zs_next: Option<Vec<ExtensionTarget<D>>>,
+    pub lookup_helpers: Option<Vec<ExtensionTarget<D>>>,
+    pub lookup_helpers_next: Option<Vec<ExtensionTarget<D>>>,
+    pub ctl_zs: Option<Vec<ExtensionTarget<D>>>,
+    pub ctl_zs_next: Option<Vec<ExtensionTarget<D>>>,
+    pub ctl_zs_first: Option<Vec<ExtensionTarget<D>>>,
     pub quotient_polys: Vec<ExtensionTarget<D>>,
 }
 
@@ -197,6 +860,8 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
                 .local_values
                 .iter()
                 .chain(self.permutation_zs.iter().flatten())
+                .chain(self.lookup_helpers.iter().flatten())
+                .chain(self.ctl_zs.iter().flatten())
                 .chain(&self.quotient_polys)
                 .copied()
                 .collect_vec(),
@@ -206,11 +871,17 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
                 .next_values
                 .iter()
                 .chain(self.permutation_zs_next.iter().flatten())
+                .chain(self.lookup_helpers_next.iter().flatten())
+                .chain(self.ctl_zs_next.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
-        FriOpeningsTarget {
-            batches: vec![zeta_batch, zeta_next_batch],
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        if let Some(ctl_zs_first) = &self.ctl_zs_first {
+            batches.push(FriOpeningBatchTarget {
+                values: ctl_zs_first.clone(),
+            });
         }
+        FriOpeningsTarget { batches }
     }
 }
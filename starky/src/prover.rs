@@ -1,7 +1,8 @@
+use alloc::format;
 use alloc::vec::Vec;
 use core::iter::once;
 
-use anyhow::{ensure, Result};
+use anyhow::{anyhow, ensure, Result};
 use itertools::Itertools;
 use maybe_rayon::*;
 use plonky2::field::extension::Extendable;
@@ -18,13 +19,16 @@ use plonky2::timed;
 use plonky2::util::timing::TimingTree;
 use plonky2::util::{log2_ceil, log2_strict, transpose};
 
+use crate::all_stark::{AllStark, TableVisitor};
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
+use crate::cross_table_lookup::{cross_table_lookup_data, CtlCheckVars, CtlData};
+use crate::lookup::{compute_lookup_helper_columns, LookupCheckVars};
 use crate::permutation::{
-    compute_permutation_z_polys, get_n_permutation_challenge_sets, PermutationChallengeSet,
-    PermutationCheckVars,
+    compute_permutation_z_polys, get_n_permutation_challenge_sets, get_permutation_challenge_set,
+    PermutationChallengeSet, PermutationCheckVars,
 };
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
+use crate::proof::{AllProof, StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
 use crate::vars::StarkEvaluationVars;
@@ -36,6 +40,271 @@ pub fn prove<F, C, S, const D: usize>(
     public_inputs: [F; S::PUBLIC_INPUTS],
     timing: &mut TimingTree,
 ) -> Result<StarkProofWithPublicInputs<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    let trace_commitment = timed!(
+        timing,
+        "compute trace commitment",
+        commit_trace::<F, C, D>(&trace_poly_values, config, timing)
+    );
+
+    let mut challenger = Challenger::new();
+    challenger.observe_cap(&trace_commitment.merkle_tree.cap);
+
+    prove_with_commitment(
+        &stark,
+        config,
+        &trace_poly_values,
+        &trace_commitment,
+        &CtlData::default(),
+        public_inputs,
+        &mut challenger,
+        timing,
+    )
+}
+
+/// Proves the tables of `all_stark` together. The traces of all the tables are committed to first,
+/// then the challenges of the cross-table lookups are drawn, and each table is proven in turn with
+/// the same challenger.
+pub fn prove_all<F, C, A, const D: usize>(
+    all_stark: &A,
+    config: &StarkConfig,
+    trace_poly_values: Vec<Vec<PolynomialValues<F>>>,
+    public_inputs: Vec<Vec<F>>,
+    timing: &mut TimingTree,
+) -> Result<AllProof<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    A: AllStark<F, D>,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    let num_tables = all_stark.num_tables();
+    ensure!(
+        trace_poly_values.len() == num_tables,
+        "Expected {} traces, got {}",
+        num_tables,
+        trace_poly_values.len()
+    );
+    ensure!(
+        public_inputs.len() == num_tables,
+        "Expected the public inputs of {} tables, got {}",
+        num_tables,
+        public_inputs.len()
+    );
+
+    let trace_commitments = timed!(
+        timing,
+        "compute trace commitments",
+        trace_poly_values
+            .iter()
+            .map(|trace| commit_trace::<F, C, D>(trace, config, timing))
+            .collect::<Vec<_>>()
+    );
+
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for trace_commitment in &trace_commitments {
+        challenger.observe_cap(&trace_commitment.merkle_tree.cap);
+    }
+    let ctl_challenges = get_permutation_challenge_set(&mut challenger, config.num_challenges);
+    let ctl_data_per_table = timed!(
+        timing,
+        "compute CTL data",
+        cross_table_lookup_data::<F>(
+            &trace_poly_values,
+            &all_stark.cross_table_lookups(),
+            &ctl_challenges,
+        )?
+    );
+
+    let mut table_prover = TableProver {
+        config,
+        trace_poly_values: &trace_poly_values,
+        trace_commitments: &trace_commitments,
+        ctl_data_per_table: &ctl_data_per_table,
+        public_inputs: &public_inputs,
+        challenger: &mut challenger,
+        timing,
+        stark_proofs: Vec::with_capacity(num_tables),
+    };
+    for table in 0..num_tables {
+        all_stark.visit_table(table, &mut table_prover)?;
+    }
+
+    Ok(AllProof {
+        stark_proofs: table_prover.stark_proofs,
+    })
+}
+
+/// Proves each table visited, in order.
+struct TableProver<'a, F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    config: &'a StarkConfig,
+    trace_poly_values: &'a [Vec<PolynomialValues<F>>],
+    trace_commitments: &'a [PolynomialBatch<F, C, D>],
+    ctl_data_per_table: &'a [CtlData<F>],
+    public_inputs: &'a [Vec<F>],
+    challenger: &'a mut Challenger<F, C::Hasher>,
+    timing: &'a mut TimingTree,
+    stark_proofs: Vec<StarkProofWithPublicInputs<F, C, D>>,
+}
+
+impl<'a, F, C, const D: usize> TableVisitor<F, D> for TableProver<'a, F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+    {
+        let public_inputs = self.public_inputs[table]
+            .clone()
+            .try_into()
+            .map_err(|_| anyhow!("Wrong number of public inputs for table {}", table))?;
+        let proof = timed!(
+            self.timing,
+            &format!("prove table {}", table),
+            prove_with_commitment(
+                stark,
+                self.config,
+                &self.trace_poly_values[table],
+                &self.trace_commitments[table],
+                &self.ctl_data_per_table[table],
+                public_inputs,
+                self.challenger,
+                self.timing,
+            )
+        )?;
+        self.stark_proofs.push(proof);
+        Ok(())
+    }
+}
+
+fn commit_trace<F, C, const D: usize>(
+    trace_poly_values: &[PolynomialValues<F>],
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    commit_values(
+        // TODO: Cloning this isn't great; consider having `from_values` accept a reference,
+        // or having `compute_permutation_z_polys` read trace values from the `PolynomialBatch`.
+        trace_poly_values.to_vec(),
+        config,
+        timing,
+    )
+}
+
+/// Commits to polynomials given by their values on the subgroup `H`. With zero knowledge, they are
+/// randomized by multiples of `Z_H`, and the leaves of the Merkle tree are salted.
+fn commit_values<F, C, const D: usize>(
+    values: Vec<PolynomialValues<F>>,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    if !config.zero_knowledge {
+        return PolynomialBatch::from_values(
+            values,
+            config.fri_config.rate_bits,
+            false,
+            config.fri_config.cap_height,
+            timing,
+            None,
+        );
+    }
+
+    let blinded_polys = timed!(
+        timing,
+        "IFFT and blinding",
+        values
+            .into_par_iter()
+            .map(|v| blind_with_z_h(v.ifft()))
+            .collect()
+    );
+    PolynomialBatch::from_coeffs(
+        blinded_polys,
+        config.fri_config.rate_bits,
+        true,
+        config.fri_config.cap_height,
+        timing,
+        None,
+    )
+}
+
+/// Returns `p + Z_H r` for a random `r` of degree less than `n = |H|`, which has the same values as
+/// `p` on `H`. As `Z_H(X) = X^n - 1`, its coefficients are those of `p - r` followed by those of `r`.
+fn blind_with_z_h<F: Field>(poly: PolynomialCoeffs<F>) -> PolynomialCoeffs<F> {
+    let r = F::rand_vec(poly.len());
+    let mut coeffs = poly
+        .coeffs
+        .iter()
+        .zip(&r)
+        .map(|(&c, &r_i)| c - r_i)
+        .collect_vec();
+    coeffs.extend(r);
+    PolynomialCoeffs::new(coeffs)
+}
+
+/// Randomizes the degree `n` chunks `t_0, ..., t_{k-1}` of a quotient polynomial into
+/// `t_i + X^n r_i - r_{i-1}`, for random `r_i` of degree less than `n`, and `r_{-1} = r_{k-1} = 0`.
+/// The chunks then have degree `2n`, and `t(X) = \sum_i X^{n i} t_i(X)` is unchanged as the `r_i`
+/// terms telescope.
+fn blind_quotient_chunks<F: Field>(chunks: Vec<PolynomialCoeffs<F>>) -> Vec<PolynomialCoeffs<F>> {
+    let n = chunks[0].len();
+    let num_chunks = chunks.len();
+    let rs = (1..num_chunks).map(|_| F::rand_vec(n)).collect_vec();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut coeffs = chunk.coeffs;
+            if i > 0 {
+                for (c, &r) in coeffs.iter_mut().zip(&rs[i - 1]) {
+                    *c -= r;
+                }
+            }
+            match rs.get(i) {
+                Some(r) => coeffs.extend(r),
+                None => coeffs.resize(2 * n, F::ZERO),
+            }
+            PolynomialCoeffs::new(coeffs)
+        })
+        .collect()
+}
+
+/// Proves a table whose trace is committed to in `trace_commitment`, with a challenger which has
+/// already observed the trace cap, and possibly the ones of other tables.
+pub(crate) fn prove_with_commitment<F, C, S, const D: usize>(
+    stark: &S,
+    config: &StarkConfig,
+    trace_poly_values: &[PolynomialValues<F>],
+    trace_commitment: &PolynomialBatch<F, C, D>,
+    ctl_data: &CtlData<F>,
+    public_inputs: [F; S::PUBLIC_INPUTS],
+    challenger: &mut Challenger<F, C::Hasher>,
+    timing: &mut TimingTree,
+) -> Result<StarkProofWithPublicInputs<F, C, D>>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -50,54 +319,60 @@ where
     let rate_bits = config.fri_config.rate_bits;
     let cap_height = config.fri_config.cap_height;
     assert!(
-        fri_params.total_arities() <= degree_bits + rate_bits - cap_height,
+        fri_params.total_arities()
+            <= config.committed_degree_bits(degree_bits) + rate_bits - cap_height,
         "FRI total reduction arity is too large.",
     );
-
-    let trace_commitment = timed!(
-        timing,
-        "compute trace commitment",
-        PolynomialBatch::<F, C, D>::from_values(
-            // TODO: Cloning this isn't great; consider having `from_values` accept a reference,
-            // or having `compute_permutation_z_polys` read trace values from the `PolynomialBatch`.
-            trace_poly_values.clone(),
-            rate_bits,
-            false,
-            cap_height,
-            timing,
-            None,
-        )
+    ensure!(
+        !stark.uses_lookups() || stark.constraint_degree() >= 2,
+        "Lookups need a constraint degree of at least 2."
+    );
+    ensure!(
+        ctl_data.is_empty() || stark.constraint_degree() >= 3,
+        "Cross-table lookups need a constraint degree of at least 3."
+    );
+    // A single quotient chunk could not be blinded.
+    ensure!(
+        !config.zero_knowledge || stark.constraint_degree() >= 2,
+        "Zero knowledge needs a constraint degree of at least 2."
+    );
+    // The quotient is evaluated on a coset `2^quotient_degree_bits` times larger than the trace,
+    // which the LDE of the committed polynomials must cover. With zero knowledge, there are
+    // `2 * constraint_degree - 1` quotient chunks, so degree 3 constraints need `rate_bits >= 2`.
+    let quotient_degree_bits = log2_ceil(stark.num_quotient_chunks(config));
+    let min_rate_bits = (degree_bits + quotient_degree_bits)
+        .saturating_sub(config.committed_degree_bits(degree_bits));
+    ensure!(
+        rate_bits >= min_rate_bits,
+        "A constraint degree of {} needs a rate_bits of at least {min_rate_bits}{}.",
+        stark.constraint_degree(),
+        if config.zero_knowledge {
+            " with zero knowledge"
+        } else {
+            ""
+        }
     );
 
     let trace_cap = trace_commitment.merkle_tree.cap.clone();
-    let mut challenger = Challenger::new();
-    challenger.observe_cap(&trace_cap);
 
     // Permutation arguments.
     let permutation_zs_commitment_challenges = stark.uses_permutation_args().then(|| {
         let permutation_challenge_sets = get_n_permutation_challenge_sets(
-            &mut challenger,
+            challenger,
             config.num_challenges,
             stark.permutation_batch_size(),
         );
         let permutation_z_polys = compute_permutation_z_polys::<F, C, S, D>(
-            &stark,
+            stark,
             config,
-            &trace_poly_values,
+            trace_poly_values,
             &permutation_challenge_sets,
         );
 
         let permutation_zs_commitment = timed!(
             timing,
             "compute permutation Z commitments",
-            PolynomialBatch::from_values(
-                permutation_z_polys,
-                rate_bits,
-                false,
-                config.fri_config.cap_height,
-                timing,
-                None,
-            )
+            commit_values(permutation_z_polys, config, timing)
         );
         (permutation_zs_commitment, permutation_challenge_sets)
     });
@@ -111,11 +386,52 @@ where
         challenger.observe_cap(cap);
     }
 
+    // Lookup arguments.
+    let lookup_helpers_commitment_challenges = stark.uses_lookups().then(|| {
+        let lookup_challenges = challenger.get_n_challenges(config.num_challenges);
+        let lookup_helper_columns =
+            compute_lookup_helper_columns::<F, S, D>(stark, trace_poly_values, &lookup_challenges);
+
+        let lookup_helpers_commitment = timed!(
+            timing,
+            "compute lookup helper commitments",
+            commit_values(lookup_helper_columns, config, timing)
+        );
+        (lookup_helpers_commitment, lookup_challenges)
+    });
+    let lookup_helpers_commitment = lookup_helpers_commitment_challenges
+        .as_ref()
+        .map(|(comm, _)| comm);
+    let lookup_helpers_cap = lookup_helpers_commitment
+        .as_ref()
+        .map(|commit| commit.merkle_tree.cap.clone());
+    if let Some(cap) = &lookup_helpers_cap {
+        challenger.observe_cap(cap);
+    }
+
+    // Cross-table lookups.
+    let ctl_zs_commitment = (!ctl_data.is_empty()).then(|| {
+        timed!(
+            timing,
+            "compute CTL Z commitments",
+            commit_values(ctl_data.z_polys(), config, timing)
+        )
+    });
+    let ctl_zs_cap = ctl_zs_commitment
+        .as_ref()
+        .map(|commit| commit.merkle_tree.cap.clone());
+    if let Some(cap) = &ctl_zs_cap {
+        challenger.observe_cap(cap);
+    }
+
     let alphas = challenger.get_n_challenges(config.num_challenges);
     let quotient_polys = compute_quotient_polys::<F, <F as Packable>::Packing, C, S, D>(
-        &stark,
-        &trace_commitment,
+        stark,
+        trace_commitment,
         &permutation_zs_commitment_challenges,
+        &lookup_helpers_commitment_challenges,
+        ctl_zs_commitment.as_ref(),
+        ctl_data,
         public_inputs,
         alphas,
         degree_bits,
@@ -125,10 +441,15 @@ where
         .into_par_iter()
         .flat_map(|mut quotient_poly| {
             quotient_poly
-                .trim_to_len(degree * stark.quotient_degree_factor())
+                .trim_to_len(degree * stark.num_quotient_chunks(config))
                 .expect("Quotient has failed, the vanishing polynomial is not divisible by Z_H");
             // Split quotient into degree-n chunks.
-            quotient_poly.chunks(degree)
+            let chunks = quotient_poly.chunks(degree);
+            if config.zero_knowledge {
+                blind_quotient_chunks(chunks)
+            } else {
+                chunks
+            }
         })
         .collect();
     let quotient_commitment = timed!(
@@ -137,7 +458,7 @@ where
         PolynomialBatch::from_coeffs(
             all_quotient_chunks,
             rate_bits,
-            false,
+            config.zero_knowledge,
             config.fri_config.cap_height,
             timing,
             None,
@@ -158,24 +479,28 @@ where
     let openings = StarkOpeningSet::new(
         zeta,
         g,
-        &trace_commitment,
+        trace_commitment,
         permutation_zs_commitment,
+        lookup_helpers_commitment,
+        ctl_zs_commitment.as_ref(),
         &quotient_commitment,
     );
     challenger.observe_openings(&openings.to_fri_openings());
 
-    let initial_merkle_trees = once(&trace_commitment)
+    let initial_merkle_trees = once(trace_commitment)
         .chain(permutation_zs_commitment)
+        .chain(lookup_helpers_commitment)
+        .chain(ctl_zs_commitment.as_ref())
         .chain(once(&quotient_commitment))
         .collect_vec();
 
-    let opening_proof = timed!(
+    let (opening_proof, _) = timed!(
         timing,
         "compute openings proof",
         PolynomialBatch::prove_openings(
-            &stark.fri_instance(zeta, g, config),
+            &stark.fri_instance(zeta, g, ctl_data.len(), config),
             &initial_merkle_trees,
-            &mut challenger,
+            challenger,
             &fri_params,
             timing,
             &mut None,
@@ -184,6 +509,8 @@ where
     let proof = StarkProof {
         trace_cap,
         permutation_zs_cap,
+        lookup_helpers_cap,
+        ctl_zs_cap,
         quotient_polys_cap,
         openings,
         opening_proof,
@@ -204,6 +531,9 @@ fn compute_quotient_polys<'a, F, P, C, S, const D: usize>(
         PolynomialBatch<F, C, D>,
         Vec<PermutationChallengeSet<F>>,
     )>,
+    lookup_helpers_commitment_challenges: &'a Option<(PolynomialBatch<F, C, D>, Vec<F>)>,
+    ctl_zs_commitment: Option<&'a PolynomialBatch<F, C, D>>,
+    ctl_data: &CtlData<F>,
     public_inputs: [F; S::PUBLIC_INPUTS],
     alphas: Vec<F>,
     degree_bits: usize,
@@ -220,12 +550,12 @@ where
     let degree = 1 << degree_bits;
     let rate_bits = config.fri_config.rate_bits;
 
-    let quotient_degree_bits = log2_ceil(stark.quotient_degree_factor());
-    assert!(
-        quotient_degree_bits <= rate_bits,
-        "Having constraints of degree higher than the rate is not supported yet."
-    );
-    let step = 1 << (rate_bits - quotient_degree_bits);
+    // The committed polynomials have degree `2^committed_degree_bits`, which is twice the trace
+    // length with zero knowledge.
+    let lde_bits = config.committed_degree_bits(degree_bits) + rate_bits;
+    // `prove_with_commitment` checks that this doesn't underflow.
+    let quotient_degree_bits = log2_ceil(stark.num_quotient_chunks(config));
+    let step = 1 << (lde_bits - degree_bits - quotient_degree_bits);
     // When opening the `Z`s polys at the "next" point, need to look at the point `next_step` steps away.
     let next_step = 1 << quotient_degree_bits;
 
@@ -286,11 +616,37 @@ where
                     permutation_challenge_sets: permutation_challenge_sets.to_vec(),
                 },
             );
+            let lookup_check_data = lookup_helpers_commitment_challenges.as_ref().map(
+                |(lookup_helpers_commitment, lookup_challenges)| LookupCheckVars {
+                    local_values: lookup_helpers_commitment.get_lde_values_packed(i_start, step),
+                    next_values: lookup_helpers_commitment
+                        .get_lde_values_packed(i_next_start, step),
+                    challenges: lookup_challenges.to_vec(),
+                },
+            );
+            let ctl_vars = ctl_zs_commitment.map_or_else(Vec::new, |ctl_zs_commitment| {
+                let local_zs = ctl_zs_commitment.get_lde_values_packed(i_start, step);
+                let next_zs = ctl_zs_commitment.get_lde_values_packed(i_next_start, step);
+                ctl_data
+                    .zs_columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, zs_columns)| CtlCheckVars::<F, F, P, 1> {
+                        local_z: local_zs[i],
+                        next_z: next_zs[i],
+                        challenge: zs_columns.challenge,
+                        columns: &zs_columns.columns,
+                        filter_column: &zs_columns.filter_column,
+                    })
+                    .collect()
+            });
             eval_vanishing_poly::<F, F, P, C, S, D, 1>(
                 stark,
                 config,
                 vars,
                 permutation_check_data,
+                lookup_check_data,
+                &ctl_vars,
                 &mut consumer,
             );
 
@@ -318,3 +674,50 @@ where
         .map(|values| values.coset_ifft(F::coset_shift()))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::polynomial::{PolynomialCoeffs, PolynomialValues};
+    use plonky2::field::types::{Field, Sample};
+
+    use crate::prover::{blind_quotient_chunks, blind_with_z_h};
+
+    #[test]
+    fn test_blind_with_z_h() {
+        type F = GoldilocksField;
+        let log_n = 4;
+        let n = 1 << log_n;
+
+        let values = PolynomialValues::new(F::rand_vec(n));
+        let blinded = blind_with_z_h(values.clone().ifft());
+        assert_eq!(blinded.len(), 2 * n);
+
+        // The blinded polynomial keeps its values on the subgroup.
+        let g = F::primitive_root_of_unity(log_n);
+        for (i, &v) in values.values.iter().enumerate() {
+            assert_eq!(blinded.eval(g.exp_u64(i as u64)), v);
+        }
+    }
+
+    #[test]
+    fn test_blind_quotient_chunks() {
+        type F = GoldilocksField;
+        let n = 1 << 4;
+        let num_chunks = 3;
+
+        let quotient = PolynomialCoeffs::new(F::rand_vec(num_chunks * n));
+        let blinded = blind_quotient_chunks(quotient.chunks(n));
+        assert_eq!(blinded.len(), num_chunks);
+        assert!(blinded.iter().all(|chunk| chunk.len() == 2 * n));
+
+        // Recombining the chunks with powers of `x^n` gives back the quotient.
+        let x = F::rand();
+        let x_n = x.exp_u64(n as u64);
+        let recombined = blinded
+            .iter()
+            .rev()
+            .fold(F::ZERO, |acc, chunk| acc * x_n + chunk.eval(x));
+        assert_eq!(recombined, quotient.eval(x));
+    }
+}
@@ -0,0 +1,337 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::lookup::Lookup;
+use crate::stark::Stark;
+use crate::util::trace_rows_to_poly_values;
+use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+
+/// Toy STARK system used for testing lookups.
+/// Computes `sum = a + b` in each row, and range checks `a, b, sum` to `0..num_rows` by looking
+/// them up in a table column holding `0, 1, ..., num_rows - 1`.
+#[derive(Copy, Clone)]
+struct RangeCheckStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> RangeCheckStark<F, D> {
+    const A: usize = 0;
+    const B: usize = 1;
+    const SUM: usize = 2;
+    const TABLE: usize = 3;
+    const FREQUENCIES: usize = 4;
+
+    fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate the trace with the given values of `a` and `b`, one per row. Values outside of the
+    /// range are not counted in the frequencies.
+    fn generate_trace(&self, a: &[u64], b: &[u64]) -> Vec<PolynomialValues<F>> {
+        let mut frequencies = vec![0; self.num_rows];
+        let mut count = |v: u64| {
+            if let Some(frequency) = frequencies.get_mut(v as usize) {
+                *frequency += 1;
+            }
+        };
+        let values = a
+            .iter()
+            .zip(b)
+            .map(|(&a, &b)| {
+                count(a);
+                count(b);
+                count(a + b);
+                (a, b)
+            })
+            .collect::<Vec<_>>();
+
+        let trace_rows = values
+            .into_iter()
+            .zip(frequencies)
+            .enumerate()
+            .map(|(i, ((a, b), frequency))| {
+                [a, b, a + b, i as u64, frequency].map(F::from_canonical_u64)
+            })
+            .collect::<Vec<_>>();
+        trace_rows_to_poly_values(trace_rows)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for RangeCheckStark<F, D> {
+    const COLUMNS: usize = 5;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        // sum = a + b
+        yield_constr.constraint(
+            vars.local_values[Self::SUM] - vars.local_values[Self::A] - vars.local_values[Self::B],
+        );
+
+        // The table starts at 0.
+        yield_constr.constraint_first_row(vars.local_values[Self::TABLE]);
+        // table' <- table + 1
+        yield_constr.constraint_transition(
+            vars.next_values[Self::TABLE] - vars.local_values[Self::TABLE] - P::ONES,
+        );
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        // sum = a + b
+        let sum_constraint = {
+            let tmp =
+                builder.sub_extension(vars.local_values[Self::SUM], vars.local_values[Self::A]);
+            builder.sub_extension(tmp, vars.local_values[Self::B])
+        };
+        yield_constr.constraint(builder, sum_constraint);
+
+        // The table starts at 0.
+        yield_constr.constraint_first_row(builder, vars.local_values[Self::TABLE]);
+        // table' <- table + 1
+        let table_constraint = {
+            let tmp = builder.sub_extension(
+                vars.next_values[Self::TABLE],
+                vars.local_values[Self::TABLE],
+            );
+            builder.add_const_extension(tmp, F::NEG_ONE)
+        };
+        yield_constr.constraint_transition(builder, table_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+
+    fn lookups(&self) -> Vec<Lookup> {
+        vec![Lookup::new(
+            vec![Self::A, Self::B, Self::SUM],
+            Self::TABLE,
+            Self::FREQUENCIES,
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use anyhow::Result;
+    use plonky2::field::extension::Extendable;
+    use plonky2::hash::hash_types::RichField;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{
+        AlgebraicHasher, GenericConfig, Hasher, PoseidonGoldilocksConfig,
+    };
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::proof::{CompressedStarkProofWithPublicInputs, StarkProofWithPublicInputs};
+    use crate::prover::prove;
+    use crate::range_check_stark::RangeCheckStark;
+    use crate::recursive_verifier::{
+        add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
+        verify_stark_proof_circuit,
+    };
+    use crate::stark::Stark;
+    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::verifier::verify_stark_proof;
+
+    const NUM_ROWS: usize = 1 << 8;
+
+    /// Values of `a` and `b` whose sums stay below `NUM_ROWS`.
+    fn in_range_values() -> (Vec<u64>, Vec<u64>) {
+        let half = NUM_ROWS as u64 / 2;
+        (0..NUM_ROWS as u64)
+            .map(|i| (i % half, (7 * i + 3) % half))
+            .unzip()
+    }
+
+    #[test]
+    fn test_range_check_stark() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(NUM_ROWS);
+        let (a, b) = in_range_values();
+        let trace = stark.generate_trace(&a, &b);
+        let proof = prove::<F, C, S, D>(stark, &config, trace, [], &mut TimingTree::default())?;
+
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn test_range_check_stark_zero_knowledge() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let mut config = StarkConfig {
+            zero_knowledge: true,
+            ..StarkConfig::standard_fast_config()
+        };
+        let stark = S::new(NUM_ROWS);
+        let (a, b) = in_range_values();
+        let trace = stark.generate_trace(&a, &b);
+        // The 5 blinded quotient chunks of degree 3 constraints need an LDE of rate 1/4.
+        assert!(prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace.clone(),
+            [],
+            &mut TimingTree::default()
+        )
+        .is_err());
+
+        config.fri_config.rate_bits = 2;
+        let proof = prove::<F, C, S, D>(stark, &config, trace, [], &mut TimingTree::default())?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    #[should_panic(expected = "Quotient has failed")]
+    fn test_range_check_stark_out_of_range() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(NUM_ROWS);
+        let (mut a, mut b) = in_range_values();
+        // The sum of the first row is out of range.
+        a[0] = 200;
+        b[0] = 100;
+        let trace = stark.generate_trace(&a, &b);
+        let _ = prove::<F, C, S, D>(stark, &config, trace, [], &mut TimingTree::default());
+    }
+
+    #[test]
+    fn test_range_check_stark_compression() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(NUM_ROWS);
+        let (a, b) = in_range_values();
+        let trace = stark.generate_trace(&a, &b);
+        let proof = prove::<F, C, S, D>(stark, &config, trace, [], &mut TimingTree::default())?;
+
+        let proof = StarkProofWithPublicInputs::<F, C, D>::from_bytes(
+            proof.to_bytes(),
+            &stark,
+            &config,
+            8,
+        )?;
+        let compressed_proof = CompressedStarkProofWithPublicInputs::<F, C, D>::from_bytes(
+            proof.clone().compress(&stark, &config)?.to_bytes(),
+            &stark,
+            &config,
+            8,
+        )?;
+        let decompressed_proof = compressed_proof.decompress(&stark, &config, 8)?;
+        assert_eq!(decompressed_proof.to_bytes(), proof.to_bytes());
+
+        verify_stark_proof(stark, decompressed_proof, &config)
+    }
+
+    #[test]
+    fn test_range_check_stark_degree() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let stark = S::new(NUM_ROWS);
+        test_stark_low_degree(stark)
+    }
+
+    #[test]
+    fn test_range_check_stark_circuit() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let stark = S::new(NUM_ROWS);
+        test_stark_circuit_constraints::<F, C, S, D>(stark)
+    }
+
+    #[test]
+    fn test_recursive_range_check_stark() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(NUM_ROWS);
+        let (a, b) = in_range_values();
+        let trace = stark.generate_trace(&a, &b);
+        let proof = prove::<F, C, S, D>(stark, &config, trace, [], &mut TimingTree::default())?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config)
+    }
+
+    fn recursive_proof<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D> + Copy,
+        InnerC: GenericConfig<D, F = F>,
+        const D: usize,
+    >(
+        stark: S,
+        inner_proof: StarkProofWithPublicInputs<F, InnerC, D>,
+        inner_config: &StarkConfig,
+    ) -> Result<()>
+    where
+        InnerC::Hasher: AlgebraicHasher<F>,
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+        [(); C::Hasher::HASH_SIZE]:,
+    {
+        let circuit_config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
+        let mut pw = PartialWitness::new();
+        let degree_bits = inner_proof.proof.recover_degree_bits(inner_config);
+        let pt = add_virtual_stark_proof_with_pis(&mut builder, stark, inner_config, degree_bits);
+        set_stark_proof_with_pis_target(&mut pw, &pt, &inner_proof);
+
+        verify_stark_proof_circuit::<F, InnerC, S, D>(&mut builder, stark, pt, inner_config);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::format;
 use alloc::vec::Vec;
 use core::iter::once;
 
@@ -7,19 +8,29 @@ use plonky2::field::extension::Extendable;
 use plonky2::field::types::Field;
 use plonky2::fri::witness_util::set_fri_proof_target;
 use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::RecursiveChallenger;
 use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::iop::target::Target;
 use plonky2::iop::witness::Witness;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::plonk::plonk_common::salt_size;
 use plonky2::util::reducing::ReducingFactorTarget;
 use plonky2::with_context;
 
+use crate::all_stark::{AllStark, TableVisitor};
 use crate::config::StarkConfig;
 use crate::constraint_consumer::RecursiveConstraintConsumer;
-use crate::permutation::PermutationCheckDataTarget;
+use crate::cross_table_lookup::{
+    verify_cross_table_lookups_circuit, CrossTableLookup, CtlCheckVarsTarget,
+};
+use crate::lookup::LookupCheckVarsTarget;
+use crate::permutation::{
+    get_permutation_challenge_set_target, PermutationChallengeSet, PermutationCheckDataTarget,
+};
 use crate::proof::{
-    StarkOpeningSetTarget, StarkProof, StarkProofChallengesTarget, StarkProofTarget,
-    StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget,
+    AllProof, AllProofTarget, StarkOpeningSetTarget, StarkProof, StarkProofChallengesTarget,
+    StarkProofTarget, StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget,
 };
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly_circuit;
@@ -50,14 +61,139 @@ pub fn verify_stark_proof_circuit<
 
     verify_stark_proof_with_challenges_circuit::<F, C, S, D>(
         builder,
-        stark,
-        proof_with_pis,
+        &stark,
+        &proof_with_pis,
         challenges,
+        &[],
         inner_config,
         degree_bits,
     );
 }
 
+/// Recursively verifies the proofs of the tables of `all_stark`, along with their cross-table
+/// lookups.
+pub fn verify_all_proof_circuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    A: AllStark<F, D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    all_stark: &A,
+    all_proof: &AllProofTarget<D>,
+    inner_config: &StarkConfig,
+) where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let num_tables = all_stark.num_tables();
+    assert_eq!(all_proof.stark_proofs.len(), num_tables);
+    let cross_table_lookups = all_stark.cross_table_lookups();
+
+    let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
+    for proof_with_pis in &all_proof.stark_proofs {
+        challenger.observe_cap(&proof_with_pis.proof.trace_cap);
+    }
+    let ctl_challenges =
+        get_permutation_challenge_set_target(builder, &mut challenger, inner_config.num_challenges);
+
+    let mut table_verifier = TableVerifierCircuit::<F, C, D> {
+        builder,
+        inner_config,
+        cross_table_lookups: &cross_table_lookups,
+        ctl_challenges: &ctl_challenges,
+        stark_proofs: &all_proof.stark_proofs,
+        challenger: &mut challenger,
+    };
+    for table in 0..num_tables {
+        all_stark.visit_table(table, &mut table_verifier).unwrap();
+    }
+
+    let ctl_zs_first = all_proof
+        .stark_proofs
+        .iter()
+        .map(|proof_with_pis| {
+            proof_with_pis
+                .proof
+                .openings
+                .ctl_zs_first
+                .clone()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    with_context!(
+        builder,
+        "verify cross-table lookups",
+        verify_cross_table_lookups_circuit::<F, D>(
+            builder,
+            &cross_table_lookups,
+            &ctl_zs_first,
+            inner_config,
+        )
+    );
+}
+
+/// Recursively verifies the proof of each table visited, in order.
+struct TableVerifierCircuit<'a, F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    builder: &'a mut CircuitBuilder<F, D>,
+    inner_config: &'a StarkConfig,
+    cross_table_lookups: &'a [CrossTableLookup<F>],
+    ctl_challenges: &'a PermutationChallengeSet<Target>,
+    stark_proofs: &'a [StarkProofWithPublicInputsTarget<D>],
+    challenger: &'a mut RecursiveChallenger<F, C::Hasher, D>,
+}
+
+impl<'a, F, C, const D: usize> TableVisitor<F, D> for TableVerifierCircuit<'a, F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+    {
+        let proof_with_pis = &self.stark_proofs[table];
+        ensure!(proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
+        let degree_bits = proof_with_pis.proof.recover_degree_bits(self.inner_config);
+        let challenges = with_context!(
+            self.builder,
+            &format!("compute challenges of table {}", table),
+            proof_with_pis.proof.get_challenges::<F, C, S>(
+                self.builder,
+                self.challenger,
+                stark,
+                self.inner_config,
+            )
+        );
+        let ctl_vars = CtlCheckVarsTarget::from_openings(
+            table,
+            &proof_with_pis.proof.openings,
+            self.cross_table_lookups,
+            self.ctl_challenges,
+        );
+        with_context!(
+            self.builder,
+            &format!("verify table {}", table),
+            verify_stark_proof_with_challenges_circuit::<F, C, S, D>(
+                self.builder,
+                stark,
+                proof_with_pis,
+                challenges,
+                &ctl_vars,
+                self.inner_config,
+                degree_bits,
+            )
+        );
+        Ok(())
+    }
+}
+
 /// Recursively verifies an inner proof.
 fn verify_stark_proof_with_challenges_circuit<
     F: RichField + Extendable<D>,
@@ -66,9 +202,10 @@ fn verify_stark_proof_with_challenges_circuit<
     const D: usize,
 >(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
-    proof_with_pis: StarkProofWithPublicInputsTarget<D>,
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
     challenges: StarkProofChallengesTarget<D>,
+    ctl_vars: &[CtlCheckVarsTarget<F, D>],
     inner_config: &StarkConfig,
     degree_bits: usize,
 ) where
@@ -76,7 +213,8 @@ fn verify_stark_proof_with_challenges_circuit<
     [(); S::COLUMNS]:,
     [(); S::PUBLIC_INPUTS]:,
 {
-    check_permutation_options(&stark, &proof_with_pis, &challenges).unwrap();
+    check_permutation_options(stark, proof_with_pis, &challenges).unwrap();
+    check_lookup_options(stark, proof_with_pis, &challenges).unwrap();
     let one = builder.one_extension();
 
     let StarkProofWithPublicInputsTarget {
@@ -88,14 +226,17 @@ fn verify_stark_proof_with_challenges_circuit<
         next_values,
         permutation_zs,
         permutation_zs_next,
+        lookup_helpers,
+        lookup_helpers_next,
         quotient_polys,
+        ..
     } = &proof.openings;
     let vars = StarkEvaluationTargets {
         local_values: &local_values.to_vec().try_into().unwrap(),
         next_values: &next_values.to_vec().try_into().unwrap(),
         public_inputs: &public_inputs
-            .into_iter()
-            .map(|t| builder.convert_to_ext(t))
+            .iter()
+            .map(|&t| builder.convert_to_ext(t))
             .collect::<Vec<_>>()
             .try_into()
             .unwrap(),
@@ -124,16 +265,23 @@ fn verify_stark_proof_with_challenges_circuit<
             next_zs: permutation_zs_next.as_ref().unwrap().clone(),
             permutation_challenge_sets: challenges.permutation_challenge_sets.unwrap(),
         });
+    let lookup_data = stark.uses_lookups().then(|| LookupCheckVarsTarget {
+        local_values: lookup_helpers.as_ref().unwrap().clone(),
+        next_values: lookup_helpers_next.as_ref().unwrap().clone(),
+        challenges: challenges.lookup_challenges.unwrap(),
+    });
 
     with_context!(
         builder,
         "evaluate vanishing polynomial",
         eval_vanishing_poly_circuit::<F, C, S, D>(
             builder,
-            &stark,
+            stark,
             inner_config,
             vars,
             permutation_data,
+            lookup_data,
+            ctl_vars,
             &mut consumer,
         )
     );
@@ -142,7 +290,7 @@ fn verify_stark_proof_with_challenges_circuit<
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
     let mut scale = ReducingFactorTarget::new(zeta_pow_deg);
     for (i, chunk) in quotient_polys
-        .chunks(stark.quotient_degree_factor())
+        .chunks(stark.num_quotient_chunks(inner_config))
         .enumerate()
     {
         let recombined_quotient = scale.reduce(chunk, builder);
@@ -150,15 +298,18 @@ fn verify_stark_proof_with_challenges_circuit<
         builder.connect_extension(vanishing_polys_zeta[i], computed_vanishing_poly);
     }
 
-    let merkle_caps = once(proof.trace_cap)
-        .chain(proof.permutation_zs_cap)
-        .chain(once(proof.quotient_polys_cap))
+    let merkle_caps = once(proof.trace_cap.clone())
+        .chain(proof.permutation_zs_cap.clone())
+        .chain(proof.lookup_helpers_cap.clone())
+        .chain(proof.ctl_zs_cap.clone())
+        .chain(once(proof.quotient_polys_cap.clone()))
         .collect_vec();
 
     let fri_instance = stark.fri_instance_target(
         builder,
         challenges.stark_zeta,
         F::primitive_root_of_unity(degree_bits),
+        ctl_vars.len(),
         inner_config,
     );
     builder.verify_fri_proof::<C>(
@@ -200,7 +351,7 @@ pub fn add_virtual_stark_proof_with_pis<
     config: &StarkConfig,
     degree_bits: usize,
 ) -> StarkProofWithPublicInputsTarget<D> {
-    let proof = add_virtual_stark_proof::<F, S, D>(builder, stark, config, degree_bits);
+    let proof = add_virtual_stark_proof::<F, S, D>(builder, &stark, config, degree_bits, 0);
     let public_inputs = builder.add_virtual_targets(S::PUBLIC_INPUTS);
     StarkProofWithPublicInputsTarget {
         proof,
@@ -208,43 +359,120 @@ pub fn add_virtual_stark_proof_with_pis<
     }
 }
 
+/// Adds a virtual `AllProof`, whose table `i` has `2^degree_bits[i]` rows.
+pub fn add_virtual_all_proof<F: RichField + Extendable<D>, A: AllStark<F, D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    all_stark: &A,
+    config: &StarkConfig,
+    degree_bits: &[usize],
+) -> AllProofTarget<D> {
+    let num_tables = all_stark.num_tables();
+    assert_eq!(degree_bits.len(), num_tables);
+    let mut virtual_proofs = VirtualProofs {
+        builder,
+        config,
+        cross_table_lookups: all_stark.cross_table_lookups(),
+        degree_bits,
+        stark_proofs: Vec::with_capacity(num_tables),
+    };
+    for table in 0..num_tables {
+        all_stark.visit_table(table, &mut virtual_proofs).unwrap();
+    }
+    AllProofTarget {
+        stark_proofs: virtual_proofs.stark_proofs,
+    }
+}
+
+/// Adds a virtual proof for each table visited, in order.
+struct VirtualProofs<'a, F: RichField + Extendable<D>, const D: usize> {
+    builder: &'a mut CircuitBuilder<F, D>,
+    config: &'a StarkConfig,
+    cross_table_lookups: Vec<CrossTableLookup<F>>,
+    degree_bits: &'a [usize],
+    stark_proofs: Vec<StarkProofWithPublicInputsTarget<D>>,
+}
+
+impl<'a, F: RichField + Extendable<D>, const D: usize> TableVisitor<F, D>
+    for VirtualProofs<'a, F, D>
+{
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+    {
+        let num_ctl_zs = CrossTableLookup::num_ctl_zs(
+            &self.cross_table_lookups,
+            table,
+            self.config.num_challenges,
+        );
+        let proof = add_virtual_stark_proof::<F, S, D>(
+            self.builder,
+            stark,
+            self.config,
+            self.degree_bits[table],
+            num_ctl_zs,
+        );
+        let public_inputs = self.builder.add_virtual_targets(S::PUBLIC_INPUTS);
+        self.stark_proofs.push(StarkProofWithPublicInputsTarget {
+            proof,
+            public_inputs,
+        });
+        Ok(())
+    }
+}
+
 pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
+    stark: &S,
     config: &StarkConfig,
     degree_bits: usize,
+    num_ctl_zs: usize,
 ) -> StarkProofTarget<D> {
     let fri_params = config.fri_params(degree_bits);
     let cap_height = fri_params.config.cap_height;
 
+    let salt = salt_size(config.zero_knowledge);
     let num_leaves_per_oracle = once(S::COLUMNS)
         .chain(
             stark
                 .uses_permutation_args()
                 .then(|| stark.num_permutation_batches(config)),
         )
-        .chain(once(stark.quotient_degree_factor() * config.num_challenges))
+        .chain(
+            stark
+                .uses_lookups()
+                .then(|| stark.num_lookup_helper_columns(config)),
+        )
+        .chain((num_ctl_zs > 0).then_some(num_ctl_zs))
+        .chain(once(stark.num_quotient_polys(config)))
+        .map(|num_polys| num_polys + salt)
         .collect_vec();
 
     let permutation_zs_cap = stark
         .uses_permutation_args()
         .then(|| builder.add_virtual_cap(cap_height));
+    let lookup_helpers_cap = stark
+        .uses_lookups()
+        .then(|| builder.add_virtual_cap(cap_height));
+    let ctl_zs_cap = (num_ctl_zs > 0).then(|| builder.add_virtual_cap(cap_height));
 
     StarkProofTarget {
         trace_cap: builder.add_virtual_cap(cap_height),
         permutation_zs_cap,
+        lookup_helpers_cap,
+        ctl_zs_cap,
         quotient_polys_cap: builder.add_virtual_cap(cap_height),
-        openings: add_stark_opening_set_target::<F, S, D>(builder, stark, config),
+        openings: add_stark_opening_set_target::<F, S, D>(builder, stark, config, num_ctl_zs),
         opening_proof: builder.add_virtual_fri_proof(&num_leaves_per_oracle, &fri_params),
     }
 }
 
 fn add_stark_opening_set_target<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
+    stark: &S,
     config: &StarkConfig,
+    num_ctl_zs: usize,
 ) -> StarkOpeningSetTarget<D> {
-    let num_challenges = config.num_challenges;
     StarkOpeningSetTarget {
         local_values: builder.add_virtual_extension_targets(S::COLUMNS),
         next_values: builder.add_virtual_extension_targets(S::COLUMNS),
@@ -254,8 +482,16 @@ fn add_stark_opening_set_target<F: RichField + Extendable<D>, S: Stark<F, D>, co
         permutation_zs_next: stark
             .uses_permutation_args()
             .then(|| builder.add_virtual_extension_targets(stark.num_permutation_batches(config))),
-        quotient_polys: builder
-            .add_virtual_extension_targets(stark.quotient_degree_factor() * num_challenges),
+        lookup_helpers: stark.uses_lookups().then(|| {
+            builder.add_virtual_extension_targets(stark.num_lookup_helper_columns(config))
+        }),
+        lookup_helpers_next: stark.uses_lookups().then(|| {
+            builder.add_virtual_extension_targets(stark.num_lookup_helper_columns(config))
+        }),
+        ctl_zs: (num_ctl_zs > 0).then(|| builder.add_virtual_extension_targets(num_ctl_zs)),
+        ctl_zs_next: (num_ctl_zs > 0).then(|| builder.add_virtual_extension_targets(num_ctl_zs)),
+        ctl_zs_first: (num_ctl_zs > 0).then(|| builder.add_virtual_extension_targets(num_ctl_zs)),
+        quotient_polys: builder.add_virtual_extension_targets(stark.num_quotient_polys(config)),
     }
 }
 
@@ -285,6 +521,24 @@ pub fn set_stark_proof_with_pis_target<F, C: GenericConfig<D, F = F>, W, const D
     set_stark_proof_target(witness, pt, proof);
 }
 
+pub fn set_all_proof_target<F, C: GenericConfig<D, F = F>, W, const D: usize>(
+    witness: &mut W,
+    all_proof_target: &AllProofTarget<D>,
+    all_proof: &AllProof<F, C, D>,
+) where
+    F: RichField + Extendable<D>,
+    C::Hasher: AlgebraicHasher<F>,
+    W: Witness<F>,
+{
+    for (proof_target, proof) in all_proof_target
+        .stark_proofs
+        .iter()
+        .zip_eq(&all_proof.stark_proofs)
+    {
+        set_stark_proof_with_pis_target(witness, proof_target, proof);
+    }
+}
+
 pub fn set_stark_proof_target<F, C: GenericConfig<D, F = F>, W, const D: usize>(
     witness: &mut W,
     proof_target: &StarkProofTarget<D>,
@@ -307,6 +561,16 @@ pub fn set_stark_proof_target<F, C: GenericConfig<D, F = F>, W, const D: usize>(
     {
         witness.set_cap_target(permutation_zs_cap_target, permutation_zs_cap);
     }
+    if let (Some(lookup_helpers_cap_target), Some(lookup_helpers_cap)) =
+        (&proof_target.lookup_helpers_cap, &proof.lookup_helpers_cap)
+    {
+        witness.set_cap_target(lookup_helpers_cap_target, lookup_helpers_cap);
+    }
+    if let (Some(ctl_zs_cap_target), Some(ctl_zs_cap)) =
+        (&proof_target.ctl_zs_cap, &proof.ctl_zs_cap)
+    {
+        witness.set_cap_target(ctl_zs_cap_target, ctl_zs_cap);
+    }
 
     set_fri_proof_target(witness, &proof_target.opening_proof, &proof.opening_proof);
 }
@@ -332,3 +596,25 @@ fn check_permutation_options<F: RichField + Extendable<D>, S: Stark<F, D>, const
     );
     Ok(())
 }
+
+/// Utility function to check that all lookup data wrapped in `Option`s are `Some` iff the Stark
+/// uses lookups.
+fn check_lookup_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
+    challenges: &StarkProofChallengesTarget<D>,
+) -> Result<()> {
+    let options_is_some = [
+        proof_with_pis.proof.lookup_helpers_cap.is_some(),
+        proof_with_pis.proof.openings.lookup_helpers.is_some(),
+        proof_with_pis.proof.openings.lookup_helpers_next.is_some(),
+        challenges.lookup_challenges.is_some(),
+    ];
+    ensure!(
+        options_is_some
+            .into_iter()
+            .all(|b| b == stark.uses_lookups()),
+        "Lookup data doesn't match with Stark configuration."
+    );
+    Ok(())
+}
@@ -0,0 +1,549 @@
+//! Byte encodings of STARK proofs, extending the readers and writers of
+//! `plonky2::util::serialization`.
+//!
+//! The encodings are not self-describing: optional Merkle caps and openings are only written when
+//! present, and the reader infers the shape of a proof from the `Stark`, the `StarkConfig`, the
+//! number of cross-table lookup Z polynomials and the degree of the trace.
+
+use alloc::vec::Vec;
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::polynomial::PolynomialCoeffs;
+use plonky2::fri::proof::{
+    CompressedFriProof, CompressedFriQueryRounds, FriInitialTreeProof, FriProof, FriQueryRound,
+};
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::plonk_common::salt_size;
+use plonky2::util::serialization::{IoResult, Read, Write};
+
+use crate::config::StarkConfig;
+use crate::proof::{
+    CompressedStarkProof, CompressedStarkProofWithPublicInputs, StarkOpeningSet, StarkProof,
+    StarkProofWithPublicInputs,
+};
+use crate::stark::Stark;
+
+/// The number of leaves of each oracle opened by the FRI proof of `stark`, in commitment order.
+fn oracle_leaves<F, S, const D: usize>(
+    stark: &S,
+    num_ctl_zs: usize,
+    config: &StarkConfig,
+) -> Vec<usize>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let salt = salt_size(config.zero_knowledge);
+    let mut leaves = Vec::with_capacity(5);
+    leaves.push(S::COLUMNS + salt);
+    if stark.uses_permutation_args() {
+        leaves.push(stark.num_permutation_batches(config) + salt);
+    }
+    if stark.uses_lookups() {
+        leaves.push(stark.num_lookup_helper_columns(config) + salt);
+    }
+    if num_ctl_zs > 0 {
+        leaves.push(num_ctl_zs + salt);
+    }
+    leaves.push(stark.num_quotient_polys(config) + salt);
+    leaves
+}
+
+/// Readers of STARK proofs, whose shapes are given by the `Stark` they prove, the `StarkConfig`,
+/// the number `num_ctl_zs` of cross-table lookup Z polynomials and the trace's `degree_bits`.
+pub trait StarkRead: Read {
+    /// Reads a value of type [`StarkOpeningSet`] from `self`.
+    #[inline]
+    fn read_stark_opening_set<F, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+    ) -> IoResult<StarkOpeningSet<F, D>>
+    where
+        F: RichField + Extendable<D>,
+        S: Stark<F, D>,
+    {
+        let local_values = self.read_field_ext_vec::<F, D>(S::COLUMNS)?;
+        let next_values = self.read_field_ext_vec::<F, D>(S::COLUMNS)?;
+        let (permutation_zs, permutation_zs_next) = if stark.uses_permutation_args() {
+            let num_z_polys = stark.num_permutation_batches(config);
+            (
+                Some(self.read_field_ext_vec::<F, D>(num_z_polys)?),
+                Some(self.read_field_ext_vec::<F, D>(num_z_polys)?),
+            )
+        } else {
+            (None, None)
+        };
+        let (lookup_helpers, lookup_helpers_next) = if stark.uses_lookups() {
+            let num_helper_columns = stark.num_lookup_helper_columns(config);
+            (
+                Some(self.read_field_ext_vec::<F, D>(num_helper_columns)?),
+                Some(self.read_field_ext_vec::<F, D>(num_helper_columns)?),
+            )
+        } else {
+            (None, None)
+        };
+        let (ctl_zs, ctl_zs_next, ctl_zs_first) = if num_ctl_zs > 0 {
+            (
+                Some(self.read_field_ext_vec::<F, D>(num_ctl_zs)?),
+                Some(self.read_field_ext_vec::<F, D>(num_ctl_zs)?),
+                Some(self.read_field_ext_vec::<F, D>(num_ctl_zs)?),
+            )
+        } else {
+            (None, None, None)
+        };
+        let quotient_polys = self.read_field_ext_vec::<F, D>(stark.num_quotient_polys(config))?;
+        Ok(StarkOpeningSet {
+            local_values,
+            next_values,
+            permutation_zs,
+            permutation_zs_next,
+            lookup_helpers,
+            lookup_helpers_next,
+            ctl_zs,
+            ctl_zs_next,
+            ctl_zs_first,
+            quotient_polys,
+        })
+    }
+
+    /// Reads a value of type [`FriInitialTreeProof`] from `self`.
+    #[inline]
+    fn read_stark_fri_initial_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+    ) -> IoResult<FriInitialTreeProof<F, C::Hasher>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let evals_proofs = oracle_leaves::<F, S, D>(stark, num_ctl_zs, config)
+            .into_iter()
+            .map(|num_leaves| Ok((self.read_field_vec(num_leaves)?, self.read_merkle_proof()?)))
+            .collect::<IoResult<_>>()?;
+        Ok(FriInitialTreeProof { evals_proofs })
+    }
+
+    /// Reads a vector of [`FriQueryRound`]s from `self`.
+    #[inline]
+    fn read_stark_fri_query_rounds<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> IoResult<Vec<FriQueryRound<F, C::Hasher, D>>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let fri_params = config.fri_params(degree_bits);
+        let mut fqrs = Vec::with_capacity(config.fri_config.num_query_rounds);
+        for _ in 0..config.fri_config.num_query_rounds {
+            let initial_trees_proof =
+                self.read_stark_fri_initial_proof::<F, C, S, D>(stark, num_ctl_zs, config)?;
+            let steps = fri_params
+                .reduction_arity_bits
+                .iter()
+                .map(|&ar| self.read_fri_query_step::<F, C, D>(1 << ar, false))
+                .collect::<Result<_, _>>()?;
+            fqrs.push(FriQueryRound {
+                initial_trees_proof,
+                steps,
+            })
+        }
+        Ok(fqrs)
+    }
+
+    /// Reads a value of type [`FriProof`] from `self`.
+    #[inline]
+    fn read_stark_fri_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> IoResult<FriProof<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let fri_params = config.fri_params(degree_bits);
+        let commit_phase_merkle_caps = (0..fri_params.reduction_arity_bits.len())
+            .map(|_| self.read_merkle_cap(config.fri_config.cap_height))
+            .collect::<Result<Vec<_>, _>>()?;
+        let query_round_proofs =
+            self.read_stark_fri_query_rounds::<F, C, S, D>(stark, num_ctl_zs, config, degree_bits)?;
+        let final_poly =
+            PolynomialCoeffs::new(self.read_field_ext_vec::<F, D>(fri_params.final_poly_len())?);
+        let pow_witness = self.read_field()?;
+        Ok(FriProof {
+            commit_phase_merkle_caps,
+            query_round_proofs,
+            final_poly,
+            pow_witness,
+        })
+    }
+
+    /// Reads a value of type [`StarkProof`] from `self`.
+    #[inline]
+    fn read_stark_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> IoResult<StarkProof<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let cap_height = config.fri_config.cap_height;
+        let trace_cap = self.read_merkle_cap(cap_height)?;
+        let permutation_zs_cap = stark
+            .uses_permutation_args()
+            .then(|| self.read_merkle_cap(cap_height))
+            .transpose()?;
+        let lookup_helpers_cap = stark
+            .uses_lookups()
+            .then(|| self.read_merkle_cap(cap_height))
+            .transpose()?;
+        let ctl_zs_cap = (num_ctl_zs > 0)
+            .then(|| self.read_merkle_cap(cap_height))
+            .transpose()?;
+        let quotient_polys_cap = self.read_merkle_cap(cap_height)?;
+        let openings = self.read_stark_opening_set::<F, S, D>(stark, num_ctl_zs, config)?;
+        let opening_proof =
+            self.read_stark_fri_proof::<F, C, S, D>(stark, num_ctl_zs, config, degree_bits)?;
+        Ok(StarkProof {
+            trace_cap,
+            permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+
+    /// Reads a value of type [`StarkProofWithPublicInputs`] from `self`.
+    #[inline]
+    fn read_stark_proof_with_public_inputs<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> IoResult<StarkProofWithPublicInputs<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let proof = self.read_stark_proof::<F, C, S, D>(stark, num_ctl_zs, config, degree_bits)?;
+        let public_inputs = self.read_field_vec(S::PUBLIC_INPUTS)?;
+        Ok(StarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        })
+    }
+
+    /// Reads a value of type [`CompressedFriQueryRounds`] from `self`.
+    #[inline]
+    fn read_compressed_stark_fri_query_rounds<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> IoResult<CompressedFriQueryRounds<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let fri_params = config.fri_params(degree_bits);
+        let original_indices = (0..config.fri_config.num_query_rounds)
+            .map(|_| self.read_u32().map(|i| i as usize))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut indices = original_indices.clone();
+        indices.sort_unstable();
+        indices.dedup();
+        let mut pairs = Vec::new();
+        for &i in &indices {
+            pairs.push((
+                i,
+                self.read_stark_fri_initial_proof::<F, C, S, D>(stark, num_ctl_zs, config)?,
+            ));
+        }
+        let initial_trees_proofs = pairs.into_iter().collect();
+
+        let mut steps = Vec::with_capacity(fri_params.reduction_arity_bits.len());
+        for &a in &fri_params.reduction_arity_bits {
+            indices.iter_mut().for_each(|x| {
+                *x >>= a;
+            });
+            indices.dedup();
+            let query_steps = (0..indices.len())
+                .map(|_| self.read_fri_query_step::<F, C, D>(1 << a, true))
+                .collect::<Result<Vec<_>, _>>()?;
+            steps.push(indices.iter().copied().zip(query_steps).collect());
+        }
+
+        Ok(CompressedFriQueryRounds {
+            indices: original_indices,
+            initial_trees_proofs,
+            steps,
+        })
+    }
+
+    /// Reads a value of type [`CompressedFriProof`] from `self`.
+    #[inline]
+    fn read_compressed_stark_fri_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> IoResult<CompressedFriProof<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let fri_params = config.fri_params(degree_bits);
+        let commit_phase_merkle_caps = (0..fri_params.reduction_arity_bits.len())
+            .map(|_| self.read_merkle_cap(config.fri_config.cap_height))
+            .collect::<Result<Vec<_>, _>>()?;
+        let query_round_proofs = self.read_compressed_stark_fri_query_rounds::<F, C, S, D>(
+            stark,
+            num_ctl_zs,
+            config,
+            degree_bits,
+        )?;
+        let final_poly =
+            PolynomialCoeffs::new(self.read_field_ext_vec::<F, D>(fri_params.final_poly_len())?);
+        let pow_witness = self.read_field()?;
+        Ok(CompressedFriProof {
+            commit_phase_merkle_caps,
+            query_round_proofs,
+            final_poly,
+            pow_witness,
+        })
+    }
+
+    /// Reads a value of type [`CompressedStarkProof`] from `self`.
+    #[inline]
+    fn read_compressed_stark_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> IoResult<CompressedStarkProof<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let cap_height = config.fri_config.cap_height;
+        let trace_cap = self.read_merkle_cap(cap_height)?;
+        let permutation_zs_cap = stark
+            .uses_permutation_args()
+            .then(|| self.read_merkle_cap(cap_height))
+            .transpose()?;
+        let lookup_helpers_cap = stark
+            .uses_lookups()
+            .then(|| self.read_merkle_cap(cap_height))
+            .transpose()?;
+        let ctl_zs_cap = (num_ctl_zs > 0)
+            .then(|| self.read_merkle_cap(cap_height))
+            .transpose()?;
+        let quotient_polys_cap = self.read_merkle_cap(cap_height)?;
+        let openings = self.read_stark_opening_set::<F, S, D>(stark, num_ctl_zs, config)?;
+        let opening_proof = self.read_compressed_stark_fri_proof::<F, C, S, D>(
+            stark,
+            num_ctl_zs,
+            config,
+            degree_bits,
+        )?;
+        Ok(CompressedStarkProof {
+            trace_cap,
+            permutation_zs_cap,
+            lookup_helpers_cap,
+            ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+
+    /// Reads a value of type [`CompressedStarkProofWithPublicInputs`] from `self`.
+    #[inline]
+    fn read_compressed_stark_proof_with_public_inputs<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+        degree_bits: usize,
+    ) -> IoResult<CompressedStarkProofWithPublicInputs<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let proof =
+            self.read_compressed_stark_proof::<F, C, S, D>(stark, num_ctl_zs, config, degree_bits)?;
+        let public_inputs = self.read_field_vec(S::PUBLIC_INPUTS)?;
+        Ok(CompressedStarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        })
+    }
+}
+
+impl<R: Read + ?Sized> StarkRead for R {}
+
+/// Writers of STARK proofs. Absent optional caps and openings are skipped.
+pub trait StarkWrite: Write {
+    /// Writes a value `os` of type [`StarkOpeningSet`] to `self`.
+    #[inline]
+    fn write_stark_opening_set<F, const D: usize>(
+        &mut self,
+        os: &StarkOpeningSet<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+    {
+        self.write_field_ext_vec::<F, D>(&os.local_values)?;
+        self.write_field_ext_vec::<F, D>(&os.next_values)?;
+        for values in [
+            &os.permutation_zs,
+            &os.permutation_zs_next,
+            &os.lookup_helpers,
+            &os.lookup_helpers_next,
+            &os.ctl_zs,
+            &os.ctl_zs_next,
+            &os.ctl_zs_first,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            self.write_field_ext_vec::<F, D>(values)?;
+        }
+        self.write_field_ext_vec::<F, D>(&os.quotient_polys)
+    }
+
+    /// Writes the Merkle caps of the commitments of a STARK proof to `self`.
+    #[inline]
+    fn write_stark_merkle_caps<F, C, const D: usize>(
+        &mut self,
+        trace_cap: &MerkleCap<F, C::Hasher>,
+        permutation_zs_cap: Option<&MerkleCap<F, C::Hasher>>,
+        lookup_helpers_cap: Option<&MerkleCap<F, C::Hasher>>,
+        ctl_zs_cap: Option<&MerkleCap<F, C::Hasher>>,
+        quotient_polys_cap: &MerkleCap<F, C::Hasher>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_merkle_cap(trace_cap)?;
+        for cap in [permutation_zs_cap, lookup_helpers_cap, ctl_zs_cap]
+            .into_iter()
+            .flatten()
+        {
+            self.write_merkle_cap(cap)?;
+        }
+        self.write_merkle_cap(quotient_polys_cap)
+    }
+
+    /// Writes a value `proof` of type [`StarkProof`] to `self`.
+    #[inline]
+    fn write_stark_proof<F, C, const D: usize>(
+        &mut self,
+        proof: &StarkProof<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_stark_merkle_caps::<F, C, D>(
+            &proof.trace_cap,
+            proof.permutation_zs_cap.as_ref(),
+            proof.lookup_helpers_cap.as_ref(),
+            proof.ctl_zs_cap.as_ref(),
+            &proof.quotient_polys_cap,
+        )?;
+        self.write_stark_opening_set(&proof.openings)?;
+        self.write_fri_proof::<F, C, D>(&proof.opening_proof)
+    }
+
+    /// Writes a value `proof_with_pis` of type [`StarkProofWithPublicInputs`] to `self`.
+    #[inline]
+    fn write_stark_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let StarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+        self.write_stark_proof(proof)?;
+        self.write_field_vec(public_inputs)
+    }
+
+    /// Writes a value `proof` of type [`CompressedStarkProof`] to `self`.
+    #[inline]
+    fn write_compressed_stark_proof<F, C, const D: usize>(
+        &mut self,
+        proof: &CompressedStarkProof<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_stark_merkle_caps::<F, C, D>(
+            &proof.trace_cap,
+            proof.permutation_zs_cap.as_ref(),
+            proof.lookup_helpers_cap.as_ref(),
+            proof.ctl_zs_cap.as_ref(),
+            &proof.quotient_polys_cap,
+        )?;
+        self.write_stark_opening_set(&proof.openings)?;
+        self.write_compressed_fri_proof::<F, C, D>(&proof.opening_proof)
+    }
+
+    /// Writes a value `proof_with_pis` of type [`CompressedStarkProofWithPublicInputs`] to `self`.
+    #[inline]
+    fn write_compressed_stark_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        proof_with_pis: &CompressedStarkProofWithPublicInputs<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let CompressedStarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+        self.write_compressed_stark_proof(proof)?;
+        self.write_field_vec(public_inputs)
+    }
+}
+
+impl<W: Write + ?Sized> StarkWrite for W {}
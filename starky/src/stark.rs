@@ -3,6 +3,7 @@ use alloc::vec::Vec;
 
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
+use plonky2::field::types::Field;
 use plonky2::fri::structure::{
     FriBatchInfo, FriBatchInfoTarget, FriInstanceInfo, FriInstanceInfoTarget, FriOracleInfo,
     FriPolynomialInfo,
@@ -14,6 +15,7 @@ use plonky2::util::ceil_div_usize;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::lookup::Lookup;
 use crate::permutation::PermutationPair;
 use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 
@@ -80,15 +82,28 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         1.max(self.constraint_degree() - 1)
     }
 
+    /// The number of degree `n` chunks each quotient polynomial is split into. With zero knowledge,
+    /// the committed polynomials have degree `2n`, so the quotients have degree
+    /// `(2 * constraint_degree - 1) n`.
+    fn num_quotient_chunks(&self, config: &StarkConfig) -> usize {
+        if config.zero_knowledge {
+            2 * self.constraint_degree() - 1
+        } else {
+            self.quotient_degree_factor()
+        }
+    }
+
     fn num_quotient_polys(&self, config: &StarkConfig) -> usize {
-        self.quotient_degree_factor() * config.num_challenges
+        self.num_quotient_chunks(config) * config.num_challenges
     }
 
-    /// Computes the FRI instance used to prove this Stark.
+    /// Computes the FRI instance used to prove this Stark, with `num_ctl_zs` Z polynomials of
+    /// cross-table lookups.
     fn fri_instance(
         &self,
         zeta: F::Extension,
         g: F,
+        num_ctl_zs: usize,
         config: &StarkConfig,
     ) -> FriInstanceInfo<F, D> {
         let mut oracles = vec![];
@@ -96,7 +111,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
         oracles.push(FriOracleInfo {
             num_polys: Self::COLUMNS,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let permutation_zs_info = if self.uses_permutation_args() {
@@ -104,18 +119,41 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_z_polys);
             oracles.push(FriOracleInfo {
                 num_polys: num_z_polys,
-                blinding: false,
+                blinding: config.zero_knowledge,
+            });
+            polys
+        } else {
+            vec![]
+        };
+
+        let lookup_helpers_info = if self.uses_lookups() {
+            let num_helper_columns = self.num_lookup_helper_columns(config);
+            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_helper_columns);
+            oracles.push(FriOracleInfo {
+                num_polys: num_helper_columns,
+                blinding: config.zero_knowledge,
             });
             polys
         } else {
             vec![]
         };
 
-        let num_quotient_polys = self.quotient_degree_factor() * config.num_challenges;
+        let ctl_zs_info = if num_ctl_zs > 0 {
+            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_ctl_zs);
+            oracles.push(FriOracleInfo {
+                num_polys: num_ctl_zs,
+                blinding: config.zero_knowledge,
+            });
+            polys
+        } else {
+            vec![]
+        };
+
+        let num_quotient_polys = self.num_quotient_polys(config);
         let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
         oracles.push(FriOracleInfo {
             num_polys: num_quotient_polys,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let zeta_batch = FriBatchInfo {
@@ -123,25 +161,42 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             polynomials: [
                 trace_info.clone(),
                 permutation_zs_info.clone(),
+                lookup_helpers_info.clone(),
+                ctl_zs_info.clone(),
                 quotient_info,
             ]
             .concat(),
         };
         let zeta_next_batch = FriBatchInfo {
             point: zeta.scalar_mul(g),
-            polynomials: [trace_info, permutation_zs_info].concat(),
+            polynomials: [
+                trace_info,
+                permutation_zs_info,
+                lookup_helpers_info,
+                ctl_zs_info.clone(),
+            ]
+            .concat(),
         };
-        let batches = vec![zeta_batch, zeta_next_batch];
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        // The CTL Zs are also opened at the first row, where they hold the products of the lookups.
+        if num_ctl_zs > 0 {
+            batches.push(FriBatchInfo {
+                point: F::Extension::ONE,
+                polynomials: ctl_zs_info,
+            });
+        }
 
         FriInstanceInfo { oracles, batches }
     }
 
-    /// Computes the FRI instance used to prove this Stark.
+    /// Computes the FRI instance used to prove this Stark, with `num_ctl_zs` Z polynomials of
+    /// cross-table lookups.
     fn fri_instance_target(
         &self,
         builder: &mut CircuitBuilder<F, D>,
         zeta: ExtensionTarget<D>,
         g: F,
+        num_ctl_zs: usize,
         config: &StarkConfig,
     ) -> FriInstanceInfoTarget<D> {
         let mut oracles = vec![];
@@ -149,7 +204,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
         oracles.push(FriOracleInfo {
             num_polys: Self::COLUMNS,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let permutation_zs_info = if self.uses_permutation_args() {
@@ -157,18 +212,41 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_z_polys);
             oracles.push(FriOracleInfo {
                 num_polys: num_z_polys,
-                blinding: false,
+                blinding: config.zero_knowledge,
+            });
+            polys
+        } else {
+            vec![]
+        };
+
+        let lookup_helpers_info = if self.uses_lookups() {
+            let num_helper_columns = self.num_lookup_helper_columns(config);
+            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_helper_columns);
+            oracles.push(FriOracleInfo {
+                num_polys: num_helper_columns,
+                blinding: config.zero_knowledge,
+            });
+            polys
+        } else {
+            vec![]
+        };
+
+        let ctl_zs_info = if num_ctl_zs > 0 {
+            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_ctl_zs);
+            oracles.push(FriOracleInfo {
+                num_polys: num_ctl_zs,
+                blinding: config.zero_knowledge,
             });
             polys
         } else {
             vec![]
         };
 
-        let num_quotient_polys = self.quotient_degree_factor() * config.num_challenges;
+        let num_quotient_polys = self.num_quotient_polys(config);
         let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
         oracles.push(FriOracleInfo {
             num_polys: num_quotient_polys,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let zeta_batch = FriBatchInfoTarget {
@@ -176,6 +254,8 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             polynomials: [
                 trace_info.clone(),
                 permutation_zs_info.clone(),
+                lookup_helpers_info.clone(),
+                ctl_zs_info.clone(),
                 quotient_info,
             ]
             .concat(),
@@ -183,9 +263,21 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let zeta_next = builder.mul_const_extension(g, zeta);
         let zeta_next_batch = FriBatchInfoTarget {
             point: zeta_next,
-            polynomials: [trace_info, permutation_zs_info].concat(),
+            polynomials: [
+                trace_info,
+                permutation_zs_info,
+                lookup_helpers_info,
+                ctl_zs_info.clone(),
+            ]
+            .concat(),
         };
-        let batches = vec![zeta_batch, zeta_next_batch];
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        if num_ctl_zs > 0 {
+            batches.push(FriBatchInfoTarget {
+                point: builder.one_extension(),
+                polynomials: ctl_zs_info,
+            });
+        }
 
         FriInstanceInfoTarget { oracles, batches }
     }
@@ -219,4 +311,22 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             self.permutation_batch_size(),
         )
     }
+
+    /// Lookups of columns into a table column, checked with logarithmic derivatives. Their helper
+    /// columns are generated by the prover. Empty by default.
+    fn lookups(&self) -> Vec<Lookup> {
+        vec![]
+    }
+
+    fn uses_lookups(&self) -> bool {
+        !self.lookups().is_empty()
+    }
+
+    fn num_lookup_helper_columns(&self, config: &StarkConfig) -> usize {
+        self.lookups()
+            .iter()
+            .map(|lookup| lookup.num_helper_columns(self.constraint_degree()))
+            .sum::<usize>()
+            * config.num_challenges
+    }
 }
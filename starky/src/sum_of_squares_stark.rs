@@ -0,0 +1,433 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use anyhow::{bail, Result};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::all_stark::{AllStark, TableVisitor};
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::{Column, CrossTableLookup, TableWithColumns};
+use crate::stark::Stark;
+use crate::util::trace_rows_to_poly_values;
+use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+
+/// Toy STARK system used for testing cross-table lookups.
+/// Table 0 holds the squares `[x, x^2]` of `x = 0, ..., num_rows - 1`.
+#[derive(Copy, Clone)]
+struct SquareStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SquareStark<F, D> {
+    const X: usize = 0;
+    const X_SQUARED: usize = 1;
+
+    fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn generate_trace(&self) -> Vec<PolynomialValues<F>> {
+        let trace_rows = (0..self.num_rows as u64)
+            .map(|x| [F::from_canonical_u64(x), F::from_canonical_u64(x * x)])
+            .collect::<Vec<_>>();
+        trace_rows_to_poly_values(trace_rows)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for SquareStark<F, D> {
+    const COLUMNS: usize = 2;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let x = vars.local_values[Self::X];
+        let x_squared = vars.local_values[Self::X_SQUARED];
+
+        // x starts at 0.
+        yield_constr.constraint_first_row(x);
+        // x' <- x + 1
+        yield_constr.constraint_transition(vars.next_values[Self::X] - x - P::ONES);
+        // x_squared = x * x
+        yield_constr.constraint(x_squared - x * x);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let x = vars.local_values[Self::X];
+        let x_squared = vars.local_values[Self::X_SQUARED];
+
+        // x starts at 0.
+        yield_constr.constraint_first_row(builder, x);
+        // x' <- x + 1
+        let increment_constraint = {
+            let tmp = builder.sub_extension(vars.next_values[Self::X], x);
+            builder.add_const_extension(tmp, F::NEG_ONE)
+        };
+        yield_constr.constraint_transition(builder, increment_constraint);
+        // x_squared = x * x
+        let square_constraint = builder.arithmetic_extension(F::NEG_ONE, F::ONE, x, x, x_squared);
+        yield_constr.constraint(builder, square_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+/// Toy STARK system used for testing cross-table lookups.
+/// Table 1 sums the squares `y_squared` of the rows whose `filter` is set, in the `sum` column,
+/// and exposes the total as its public input. Nothing in this table checks that `y_squared` is the
+/// square of `y`: this is done by looking up `[y, y_squared]` in the `SquareStark` table.
+#[derive(Copy, Clone)]
+struct SumOfSquaresStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SumOfSquaresStark<F, D> {
+    const Y: usize = 0;
+    const Y_SQUARED: usize = 1;
+    const SUM: usize = 2;
+    const FILTER: usize = 3;
+    // The public input is the total of the `sum` column.
+    const PI_INDEX_SUM: usize = 0;
+
+    fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate a trace whose even rows hold `y = num_rows / 2 - 1, ..., 0`, and whose odd rows
+    /// are filtered out. Returns the trace along with its sum of squares.
+    fn generate_trace(&self) -> (Vec<PolynomialValues<F>>, F) {
+        let mut sum = F::ZERO;
+        let trace_rows = (0..self.num_rows)
+            .map(|i| {
+                let y = F::from_canonical_usize(self.num_rows / 2 - 1 - i / 2);
+                let filter = F::from_bool(i % 2 == 0);
+                sum += filter * y.square();
+                [y, y.square(), sum, filter]
+            })
+            .collect::<Vec<_>>();
+        (trace_rows_to_poly_values(trace_rows), sum)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for SumOfSquaresStark<F, D> {
+    const COLUMNS: usize = 4;
+    const PUBLIC_INPUTS: usize = 1;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_sum = vars.local_values[Self::SUM];
+        let local_filter = vars.local_values[Self::FILTER];
+        let next_filter = vars.next_values[Self::FILTER];
+
+        // The filter is binary.
+        yield_constr.constraint(local_filter * (local_filter - P::ONES));
+        // sum <- filter * y_squared
+        yield_constr
+            .constraint_first_row(local_sum - local_filter * vars.local_values[Self::Y_SQUARED]);
+        // sum' <- sum + filter' * y_squared'
+        yield_constr.constraint_transition(
+            vars.next_values[Self::SUM]
+                - local_sum
+                - next_filter * vars.next_values[Self::Y_SQUARED],
+        );
+        // Check public inputs.
+        yield_constr.constraint_last_row(local_sum - vars.public_inputs[Self::PI_INDEX_SUM]);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_sum = vars.local_values[Self::SUM];
+        let local_filter = vars.local_values[Self::FILTER];
+        let next_filter = vars.next_values[Self::FILTER];
+
+        // The filter is binary.
+        let filter_constraint = builder.mul_sub_extension(local_filter, local_filter, local_filter);
+        yield_constr.constraint(builder, filter_constraint);
+        // sum <- filter * y_squared
+        let first_sum_constraint = builder.arithmetic_extension(
+            F::NEG_ONE,
+            F::ONE,
+            local_filter,
+            vars.local_values[Self::Y_SQUARED],
+            local_sum,
+        );
+        yield_constr.constraint_first_row(builder, first_sum_constraint);
+        // sum' <- sum + filter' * y_squared'
+        let sum_constraint = {
+            let tmp = builder.sub_extension(vars.next_values[Self::SUM], local_sum);
+            builder.arithmetic_extension(
+                F::NEG_ONE,
+                F::ONE,
+                next_filter,
+                vars.next_values[Self::Y_SQUARED],
+                tmp,
+            )
+        };
+        yield_constr.constraint_transition(builder, sum_constraint);
+        // Check public inputs.
+        let pi_constraint =
+            builder.sub_extension(local_sum, vars.public_inputs[Self::PI_INDEX_SUM]);
+        yield_constr.constraint_last_row(builder, pi_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+/// The two toy tables, where table 1 looks up its filtered `[y, y_squared]` rows in table 0.
+struct SumOfSquaresAllStark {
+    square_stark: SquareStark<GoldilocksField, 2>,
+    sum_of_squares_stark: SumOfSquaresStark<GoldilocksField, 2>,
+}
+
+impl SumOfSquaresAllStark {
+    const SQUARE_TABLE: usize = 0;
+    const SUM_OF_SQUARES_TABLE: usize = 1;
+
+    fn new(num_squares: usize) -> Self {
+        Self {
+            square_stark: SquareStark::new(num_squares),
+            sum_of_squares_stark: SumOfSquaresStark::new(2 * num_squares),
+        }
+    }
+}
+
+impl AllStark<GoldilocksField, 2> for SumOfSquaresAllStark {
+    fn num_tables(&self) -> usize {
+        2
+    }
+
+    fn cross_table_lookups(&self) -> Vec<CrossTableLookup<GoldilocksField>> {
+        type S = SumOfSquaresStark<GoldilocksField, 2>;
+        type T = SquareStark<GoldilocksField, 2>;
+        let looking_table = TableWithColumns::new(
+            Self::SUM_OF_SQUARES_TABLE,
+            Column::singles([S::Y, S::Y_SQUARED]).collect(),
+            Some(Column::single(S::FILTER)),
+        );
+        let looked_table = TableWithColumns::new(
+            Self::SQUARE_TABLE,
+            Column::singles([T::X, T::X_SQUARED]).collect(),
+            None,
+        );
+        vec![CrossTableLookup::new(vec![looking_table], looked_table)]
+    }
+
+    fn visit_table<V: TableVisitor<GoldilocksField, 2>>(
+        &self,
+        table: usize,
+        visitor: &mut V,
+    ) -> Result<()> {
+        match table {
+            Self::SQUARE_TABLE => visitor.visit(table, &self.square_stark),
+            Self::SUM_OF_SQUARES_TABLE => visitor.visit(table, &self.sum_of_squares_stark),
+            _ => bail!("Unknown table {}", table),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use anyhow::Result;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::polynomial::PolynomialValues;
+    use plonky2::field::types::Field;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::PoseidonGoldilocksConfig;
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::proof::{AllProof, CompressedAllProof};
+    use crate::prover::prove_all;
+    use crate::recursive_verifier::{
+        add_virtual_all_proof, set_all_proof_target, verify_all_proof_circuit,
+    };
+    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::sum_of_squares_stark::{SquareStark, SumOfSquaresAllStark, SumOfSquaresStark};
+    use crate::verifier::verify_all_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = GoldilocksField;
+
+    const NUM_SQUARES: usize = 1 << 4;
+
+    fn prove_sum_of_squares(
+        all_stark: &SumOfSquaresAllStark,
+        config: &StarkConfig,
+        sum_of_squares_trace: Vec<PolynomialValues<F>>,
+        sum: F,
+    ) -> Result<AllProof<F, C, D>> {
+        let square_trace = all_stark.square_stark.generate_trace();
+        prove_all::<F, C, _, D>(
+            all_stark,
+            config,
+            vec![square_trace, sum_of_squares_trace],
+            vec![vec![], vec![sum]],
+            &mut TimingTree::default(),
+        )
+    }
+
+    #[test]
+    fn test_sum_of_squares_stark() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let all_stark = SumOfSquaresAllStark::new(NUM_SQUARES);
+        let (trace, sum) = all_stark.sum_of_squares_stark.generate_trace();
+        assert_eq!(sum, F::from_canonical_u64(1240));
+        let all_proof = prove_sum_of_squares(&all_stark, &config, trace, sum)?;
+
+        verify_all_proof(&all_stark, &all_proof, &config)
+    }
+
+    #[test]
+    fn test_sum_of_squares_stark_zero_knowledge() -> Result<()> {
+        let mut config = StarkConfig {
+            zero_knowledge: true,
+            ..StarkConfig::standard_fast_config()
+        };
+        // The cross-table lookups need degree 3 constraints, whose 5 blinded quotient chunks need
+        // an LDE of rate 1/4.
+        config.fri_config.rate_bits = 2;
+        let all_stark = SumOfSquaresAllStark::new(NUM_SQUARES);
+        let (trace, sum) = all_stark.sum_of_squares_stark.generate_trace();
+        let all_proof = prove_sum_of_squares(&all_stark, &config, trace, sum)?;
+
+        verify_all_proof(&all_stark, &all_proof, &config)
+    }
+
+    #[test]
+    fn test_sum_of_squares_stark_serialization() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let all_stark = SumOfSquaresAllStark::new(NUM_SQUARES);
+        let (trace, sum) = all_stark.sum_of_squares_stark.generate_trace();
+        let all_proof = prove_sum_of_squares(&all_stark, &config, trace, sum)?;
+        let degree_bits = all_proof.degree_bits(&config);
+
+        // The FRI query indices of a table depend on the other tables.
+        assert!(all_proof.stark_proofs[0]
+            .clone()
+            .compress(&all_stark.square_stark, &config)
+            .is_err());
+
+        let bytes = all_proof.to_bytes();
+        let all_proof = AllProof::<F, C, D>::from_bytes(bytes, &all_stark, &config, &degree_bits)?;
+        let compressed_bytes = all_proof.clone().compress(&all_stark, &config)?.to_bytes();
+        assert!(compressed_bytes.len() < all_proof.to_bytes().len());
+
+        let compressed_proof = CompressedAllProof::<F, C, D>::from_bytes(
+            compressed_bytes,
+            &all_stark,
+            &config,
+            &degree_bits,
+        )?;
+        let decompressed_proof = compressed_proof.decompress(&all_stark, &config, &degree_bits)?;
+        assert_eq!(decompressed_proof.to_bytes(), all_proof.to_bytes());
+
+        verify_all_proof(&all_stark, &decompressed_proof, &config)
+    }
+
+    #[test]
+    fn test_wrong_square_is_rejected() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let all_stark = SumOfSquaresAllStark::new(NUM_SQUARES);
+        let (mut trace, mut sum) = all_stark.sum_of_squares_stark.generate_trace();
+        // Claim that the square of the first `y` is one more than it is, and keep the sum
+        // consistent, so that only the lookup in the table of squares fails.
+        trace[SumOfSquaresStark::<F, D>::Y_SQUARED].values[0] += F::ONE;
+        for v in trace[SumOfSquaresStark::<F, D>::SUM].values.iter_mut() {
+            *v += F::ONE;
+        }
+        sum += F::ONE;
+        let all_proof = prove_sum_of_squares(&all_stark, &config, trace, sum)?;
+
+        assert!(verify_all_proof(&all_stark, &all_proof, &config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_binary_filter_is_rejected() {
+        let config = StarkConfig::standard_fast_config();
+        let all_stark = SumOfSquaresAllStark::new(NUM_SQUARES);
+        let (mut trace, sum) = all_stark.sum_of_squares_stark.generate_trace();
+        trace[SumOfSquaresStark::<F, D>::FILTER].values[0] = F::TWO;
+
+        assert!(prove_sum_of_squares(&all_stark, &config, trace, sum).is_err());
+    }
+
+    #[test]
+    fn test_sum_of_squares_stark_degree() -> Result<()> {
+        test_stark_low_degree(SquareStark::<F, D>::new(NUM_SQUARES))?;
+        test_stark_low_degree(SumOfSquaresStark::<F, D>::new(2 * NUM_SQUARES))
+    }
+
+    #[test]
+    fn test_sum_of_squares_stark_circuit() -> Result<()> {
+        test_stark_circuit_constraints::<F, C, _, D>(SquareStark::<F, D>::new(NUM_SQUARES))?;
+        test_stark_circuit_constraints::<F, C, _, D>(SumOfSquaresStark::<F, D>::new(
+            2 * NUM_SQUARES,
+        ))
+    }
+
+    #[test]
+    fn test_recursive_all_proof_verifier() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let all_stark = SumOfSquaresAllStark::new(NUM_SQUARES);
+        let (trace, sum) = all_stark.sum_of_squares_stark.generate_trace();
+        let all_proof = prove_sum_of_squares(&all_stark, &config, trace, sum)?;
+        verify_all_proof(&all_stark, &all_proof, &config)?;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let mut pw = PartialWitness::new();
+        let degree_bits = all_proof.degree_bits(&config);
+        let pt = add_virtual_all_proof(&mut builder, &all_stark, &config, &degree_bits);
+        set_all_proof_target(&mut pw, &pt, &all_proof);
+
+        verify_all_proof_circuit::<F, C, _, D>(&mut builder, &all_stark, &pt, &config);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
@@ -7,13 +7,21 @@ use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
 use plonky2::fri::verifier::verify_fri_proof;
 use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::Challenger;
 use plonky2::plonk::config::{GenericConfig, Hasher};
 use plonky2::plonk::plonk_common::reduce_with_powers;
 
+use crate::all_stark::{AllStark, TableVisitor};
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
-use crate::permutation::PermutationCheckVars;
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofChallenges, StarkProofWithPublicInputs};
+use crate::cross_table_lookup::{verify_cross_table_lookups, CrossTableLookup, CtlCheckVars};
+use crate::lookup::LookupCheckVars;
+use crate::permutation::{
+    get_permutation_challenge_set, PermutationChallengeSet, PermutationCheckVars,
+};
+use crate::proof::{
+    AllProof, StarkOpeningSet, StarkProof, StarkProofChallenges, StarkProofWithPublicInputs,
+};
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
 use crate::vars::StarkEvaluationVars;
@@ -34,9 +42,123 @@ where
     [(); C::Hasher::HASH_SIZE]:,
 {
     ensure!(proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
+    validate_proof_shape(&stark, &proof_with_pis, 0, config)?;
     let degree_bits = proof_with_pis.proof.recover_degree_bits(config);
     let challenges = proof_with_pis.get_challenges(&stark, config, degree_bits);
-    verify_stark_proof_with_challenges(stark, proof_with_pis, challenges, degree_bits, config)
+    verify_stark_proof_with_challenges(
+        &stark,
+        &proof_with_pis,
+        challenges,
+        &[],
+        degree_bits,
+        config,
+    )
+}
+
+/// Verifies the proofs of the tables of `all_stark`, along with their cross-table lookups.
+pub fn verify_all_proof<F, C, A, const D: usize>(
+    all_stark: &A,
+    all_proof: &AllProof<F, C, D>,
+    config: &StarkConfig,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    A: AllStark<F, D>,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    let num_tables = all_stark.num_tables();
+    ensure!(
+        all_proof.stark_proofs.len() == num_tables,
+        "Expected the proofs of {} tables, got {}",
+        num_tables,
+        all_proof.stark_proofs.len()
+    );
+    let cross_table_lookups = all_stark.cross_table_lookups();
+
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for proof_with_pis in &all_proof.stark_proofs {
+        challenger.observe_cap(&proof_with_pis.proof.trace_cap);
+    }
+    let ctl_challenges = get_permutation_challenge_set(&mut challenger, config.num_challenges);
+
+    let mut table_verifier = TableVerifier {
+        config,
+        cross_table_lookups: &cross_table_lookups,
+        ctl_challenges: &ctl_challenges,
+        stark_proofs: &all_proof.stark_proofs,
+        challenger: &mut challenger,
+    };
+    for table in 0..num_tables {
+        all_stark.visit_table(table, &mut table_verifier)?;
+    }
+
+    let ctl_zs_first = all_proof
+        .stark_proofs
+        .iter()
+        .map(|proof_with_pis| {
+            proof_with_pis
+                .proof
+                .openings
+                .ctl_zs_first
+                .clone()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    verify_cross_table_lookups::<F, D>(&cross_table_lookups, &ctl_zs_first, config)
+}
+
+/// Verifies the proof of each table visited, in order.
+struct TableVerifier<'a, F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    config: &'a StarkConfig,
+    cross_table_lookups: &'a [CrossTableLookup<F>],
+    ctl_challenges: &'a PermutationChallengeSet<F>,
+    stark_proofs: &'a [StarkProofWithPublicInputs<F, C, D>],
+    challenger: &'a mut Challenger<F, C::Hasher>,
+}
+
+impl<'a, F, C, const D: usize> TableVisitor<F, D> for TableVerifier<'a, F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    fn visit<S: Stark<F, D>>(&mut self, table: usize, stark: &S) -> Result<()>
+    where
+        [(); S::COLUMNS]:,
+        [(); S::PUBLIC_INPUTS]:,
+    {
+        let proof_with_pis = &self.stark_proofs[table];
+        let num_ctl_zs = CrossTableLookup::num_ctl_zs(
+            self.cross_table_lookups,
+            table,
+            self.config.num_challenges,
+        );
+        validate_proof_shape(stark, proof_with_pis, num_ctl_zs, self.config)?;
+        let degree_bits = proof_with_pis.proof.recover_degree_bits(self.config);
+        let challenges =
+            proof_with_pis
+                .proof
+                .get_challenges(self.challenger, stark, self.config, degree_bits);
+        let ctl_vars = CtlCheckVars::from_openings(
+            table,
+            &proof_with_pis.proof.openings,
+            self.cross_table_lookups,
+            self.ctl_challenges,
+        );
+        verify_stark_proof_with_challenges(
+            stark,
+            proof_with_pis,
+            challenges,
+            &ctl_vars,
+            degree_bits,
+            self.config,
+        )
+    }
 }
 
 pub(crate) fn verify_stark_proof_with_challenges<
@@ -45,9 +167,10 @@ pub(crate) fn verify_stark_proof_with_challenges<
     S: Stark<F, D>,
     const D: usize,
 >(
-    stark: S,
-    proof_with_pis: StarkProofWithPublicInputs<F, C, D>,
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
     challenges: StarkProofChallenges<F, D>,
+    ctl_vars: &[CtlCheckVars<F, F::Extension, F::Extension, D>],
     degree_bits: usize,
     config: &StarkConfig,
 ) -> Result<()>
@@ -56,8 +179,8 @@ where
     [(); S::PUBLIC_INPUTS]:,
     [(); C::Hasher::HASH_SIZE]:,
 {
-    validate_proof_shape(&stark, &proof_with_pis, config)?;
-    check_permutation_options(&stark, &proof_with_pis, &challenges)?;
+    check_permutation_options(stark, proof_with_pis, &challenges)?;
+    check_lookup_options(stark, proof_with_pis, &challenges)?;
     let StarkProofWithPublicInputs {
         proof,
         public_inputs,
@@ -67,13 +190,17 @@ where
         next_values,
         permutation_zs,
         permutation_zs_next,
+        lookup_helpers,
+        lookup_helpers_next,
         quotient_polys,
+        ..
     } = &proof.openings;
     let vars = StarkEvaluationVars {
         local_values: &local_values.to_vec().try_into().unwrap(),
         next_values: &next_values.to_vec().try_into().unwrap(),
         public_inputs: &public_inputs
-            .into_iter()
+            .iter()
+            .copied()
             .map(F::Extension::from_basefield)
             .collect::<Vec<_>>()
             .try_into()
@@ -98,11 +225,18 @@ where
         next_zs: permutation_zs_next.as_ref().unwrap().clone(),
         permutation_challenge_sets: challenges.permutation_challenge_sets.unwrap(),
     });
+    let lookup_data = stark.uses_lookups().then(|| LookupCheckVars {
+        local_values: lookup_helpers.as_ref().unwrap().clone(),
+        next_values: lookup_helpers_next.as_ref().unwrap().clone(),
+        challenges: challenges.lookup_challenges.unwrap(),
+    });
     eval_vanishing_poly::<F, F::Extension, F::Extension, C, S, D, D>(
-        &stark,
+        stark,
         config,
         vars,
         permutation_data,
+        lookup_data,
+        ctl_vars,
         &mut consumer,
     );
     let vanishing_polys_zeta = consumer.accumulators();
@@ -110,13 +244,13 @@ where
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
     let zeta_pow_deg = challenges.stark_zeta.exp_power_of_2(degree_bits);
     let z_h_zeta = zeta_pow_deg - F::Extension::ONE;
-    // `quotient_polys_zeta` holds `num_challenges * quotient_degree_factor` evaluations.
-    // Each chunk of `quotient_degree_factor` holds the evaluations of `t_0(zeta),...,t_{quotient_degree_factor-1}(zeta)`
+    // `quotient_polys_zeta` holds `num_challenges * num_quotient_chunks` evaluations.
+    // Each chunk of `num_quotient_chunks` holds the evaluations of `t_0(zeta),...,t_{num_quotient_chunks-1}(zeta)`
     // where the "real" quotient polynomial is `t(X) = t_0(X) + t_1(X)*X^n + t_2(X)*X^{2n} + ...`.
     // So to reconstruct `t(zeta)` we can compute `reduce_with_powers(chunk, zeta^n)` for each
-    // `quotient_degree_factor`-sized chunk of the original evaluations.
+    // `num_quotient_chunks`-sized chunk of the original evaluations.
     for (i, chunk) in quotient_polys
-        .chunks(stark.quotient_degree_factor())
+        .chunks(stark.num_quotient_chunks(config))
         .enumerate()
     {
         ensure!(
@@ -125,15 +259,18 @@ where
         );
     }
 
-    let merkle_caps = once(proof.trace_cap)
-        .chain(proof.permutation_zs_cap)
-        .chain(once(proof.quotient_polys_cap))
+    let merkle_caps = once(proof.trace_cap.clone())
+        .chain(proof.permutation_zs_cap.clone())
+        .chain(proof.lookup_helpers_cap.clone())
+        .chain(proof.ctl_zs_cap.clone())
+        .chain(once(proof.quotient_polys_cap.clone()))
         .collect_vec();
 
     verify_fri_proof::<F, C, D>(
         &stark.fri_instance(
             challenges.stark_zeta,
             F::primitive_root_of_unity(degree_bits),
+            ctl_vars.len(),
             config,
         ),
         &proof.openings.to_fri_openings(),
@@ -149,6 +286,7 @@ where
 fn validate_proof_shape<F, C, S, const D: usize>(
     stark: &S,
     proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    num_ctl_zs: usize,
     config: &StarkConfig,
 ) -> anyhow::Result<()>
 where
@@ -167,6 +305,8 @@ where
     let StarkProof {
         trace_cap,
         permutation_zs_cap,
+        lookup_helpers_cap,
+        ctl_zs_cap,
         quotient_polys_cap,
         openings,
         // The shape of the opening proof will be checked in the FRI verifier (see
@@ -179,6 +319,11 @@ where
         next_values,
         permutation_zs,
         permutation_zs_next,
+        lookup_helpers,
+        lookup_helpers_next,
+        ctl_zs,
+        ctl_zs_next,
+        ctl_zs_first,
         quotient_polys,
     } = openings;
 
@@ -215,6 +360,50 @@ where
         ensure!(permutation_zs_next.is_none());
     }
 
+    if stark.uses_lookups() {
+        let num_helper_columns = stark.num_lookup_helper_columns(config);
+        let lookup_helpers_cap = lookup_helpers_cap
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing lookup helpers cap"))?;
+        let lookup_helpers = lookup_helpers
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing lookup_helpers"))?;
+        let lookup_helpers_next = lookup_helpers_next
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing lookup_helpers_next"))?;
+
+        ensure!(lookup_helpers_cap.height() == cap_height);
+        ensure!(lookup_helpers.len() == num_helper_columns);
+        ensure!(lookup_helpers_next.len() == num_helper_columns);
+    } else {
+        ensure!(lookup_helpers_cap.is_none());
+        ensure!(lookup_helpers.is_none());
+        ensure!(lookup_helpers_next.is_none());
+    }
+
+    if num_ctl_zs > 0 {
+        let ctl_zs_cap = ctl_zs_cap
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing CTL Zs cap"))?;
+        let ctl_zs = ctl_zs.as_ref().ok_or_else(|| anyhow!("Missing ctl_zs"))?;
+        let ctl_zs_next = ctl_zs_next
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing ctl_zs_next"))?;
+        let ctl_zs_first = ctl_zs_first
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing ctl_zs_first"))?;
+
+        ensure!(ctl_zs_cap.height() == cap_height);
+        ensure!(ctl_zs.len() == num_ctl_zs);
+        ensure!(ctl_zs_next.len() == num_ctl_zs);
+        ensure!(ctl_zs_first.len() == num_ctl_zs);
+    } else {
+        ensure!(ctl_zs_cap.is_none());
+        ensure!(ctl_zs.is_none());
+        ensure!(ctl_zs_next.is_none());
+        ensure!(ctl_zs_first.is_none());
+    }
+
     Ok(())
 }
 
@@ -257,6 +446,33 @@ fn check_permutation_options<
     Ok(())
 }
 
+/// Utility function to check that all lookup data wrapped in `Option`s are `Some` iff the Stark
+/// uses lookups.
+fn check_lookup_options<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    challenges: &StarkProofChallenges<F, D>,
+) -> Result<()> {
+    let options_is_some = [
+        proof_with_pis.proof.lookup_helpers_cap.is_some(),
+        proof_with_pis.proof.openings.lookup_helpers.is_some(),
+        proof_with_pis.proof.openings.lookup_helpers_next.is_some(),
+        challenges.lookup_challenges.is_some(),
+    ];
+    ensure!(
+        options_is_some
+            .into_iter()
+            .all(|b| b == stark.uses_lookups()),
+        "Lookup data doesn't match with Stark configuration."
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use plonky2::field::goldilocks_field::GoldilocksField;
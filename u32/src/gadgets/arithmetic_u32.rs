@@ -1,3 +1,4 @@
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
@@ -8,6 +9,7 @@ use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::{PartitionWitness, Witness};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
 
 use crate::gates::add_many_u32::U32AddManyGate;
 use crate::gates::arithmetic_u32::U32ArithmeticGate;
@@ -231,7 +233,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderU32<F, D>
 }
 
 #[derive(Debug)]
-struct SplitToU32Generator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct SplitToU32Generator<F: RichField + Extendable<D>, const D: usize> {
     x: Target,
     low: U32Target,
     high: U32Target,
@@ -254,11 +256,37 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
         out_buffer.set_u32_target(self.low, low);
         out_buffer.set_u32_target(self.high, high);
     }
+
+    fn id(&self) -> String {
+        "SplitToU32Generator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target(self.x)?;
+        dst.write_target(self.low.0)?;
+        dst.write_target(self.high.0)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let x = src.read_target()?;
+        let low = U32Target(src.read_target()?);
+        let high = U32Target(src.read_target()?);
+        Ok(Self {
+            x,
+            low,
+            high,
+            _phantom: PhantomData,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::sync::Arc;
+
     use anyhow::Result;
+    use plonky2::gates::gate_testing::test_witness_generation;
+    use plonky2::hash::poseidon::PoseidonHash;
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_data::CircuitConfig;
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
@@ -266,6 +294,7 @@ mod tests {
     use rand::Rng;
 
     use super::*;
+    use crate::witness::WitnessU32;
 
     #[test]
     pub fn test_add_many_u32s() -> Result<()> {
@@ -300,4 +329,35 @@ mod tests {
         let proof = data.prove(pw).unwrap();
         data.verify(proof)
     }
+
+    #[test]
+    fn test_witness_generation_in_waves() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_u32_target();
+        let y = builder.add_virtual_u32_target();
+        let (low, high) = builder.mul_u32(x, y);
+        let (sum, carry) = builder.add_many_u32(&[low, high, x]);
+
+        // The `LookupGenerator`s aren't `SimpleGenerator`s.
+        let squares = Arc::new((0..256).map(|i| (i, i * i)).collect::<Vec<_>>());
+        let squares = builder.add_lookup_table(squares);
+        let x_squared = builder.add_lookup(squares, x.0);
+        let y_squared = builder.add_lookup(squares, y.0);
+
+        let hash = builder
+            .hash_n_to_hash_no_pad::<PoseidonHash>(vec![sum.0, carry.0, x_squared, y_squared]);
+        builder.register_public_inputs(&hash.elements);
+        let data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_u32_target(x, 200);
+        inputs.set_u32_target(y, 17);
+        test_witness_generation(&data, inputs)
+    }
 }
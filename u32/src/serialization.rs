@@ -0,0 +1,74 @@
+//! Registration of this crate's gates and generators, for serializing circuits which use them.
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::util::serialization::gate_serialization::GateSerializer;
+use plonky2::util::serialization::generator_serialization::WitnessGeneratorSerializer;
+
+use crate::gadgets::arithmetic_u32::SplitToU32Generator;
+use crate::gates::add_many_u32::{U32AddManyGate, U32AddManyGenerator};
+use crate::gates::arithmetic_u32::{U32ArithmeticGate, U32ArithmeticGenerator};
+use crate::gates::comparison::{ComparisonGate, ComparisonGenerator};
+use crate::gates::range_check_u32::{U32RangeCheckGate, U32RangeCheckGenerator};
+use crate::gates::subtraction_u32::{U32SubtractionGate, U32SubtractionGenerator};
+
+pub fn register_gates<F: RichField + Extendable<D>, const D: usize>(
+    serializer: &mut GateSerializer<F, D>,
+) {
+    serializer.register::<U32AddManyGate<F, D>>("U32AddManyGate");
+    serializer.register::<U32ArithmeticGate<F, D>>("U32ArithmeticGate");
+    serializer.register::<ComparisonGate<F, D>>("ComparisonGate");
+    serializer.register::<U32RangeCheckGate<F, D>>("U32RangeCheckGate");
+    serializer.register::<U32SubtractionGate<F, D>>("U32SubtractionGate");
+}
+
+pub fn register_generators<F: RichField + Extendable<D>, const D: usize>(
+    serializer: &mut WitnessGeneratorSerializer<F, D>,
+) {
+    serializer.register_simple::<U32AddManyGenerator<F, D>>("U32AddManyGenerator");
+    serializer.register_simple::<U32ArithmeticGenerator<F, D>>("U32ArithmeticGenerator");
+    serializer.register_simple::<ComparisonGenerator<F, D>>("ComparisonGenerator");
+    serializer.register_simple::<U32RangeCheckGenerator<F, D>>("U32RangeCheckGenerator");
+    serializer.register_simple::<SplitToU32Generator<F, D>>("SplitToU32Generator");
+    serializer.register_simple::<U32SubtractionGenerator<F, D>>("U32SubtractionGenerator");
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use plonky2::gates::gate::GateRef;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::serialization::{Buffer, Read, Remaining, Write};
+
+    use super::*;
+
+    #[test]
+    fn test_gate_serialization() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let gates: [GateRef<F, D>; 5] = [
+            GateRef::new(U32AddManyGate::<F, D>::new_from_config(&config, 3)),
+            GateRef::new(U32ArithmeticGate::<F, D>::new_from_config(&config)),
+            GateRef::new(ComparisonGate::<F, D>::new(32, 16)),
+            GateRef::new(U32RangeCheckGate::<F, D>::new(4)),
+            GateRef::new(U32SubtractionGate::<F, D>::new_from_config(&config)),
+        ];
+
+        let mut serializer = GateSerializer::plonky2();
+        register_gates(&mut serializer);
+
+        for gate in gates {
+            let mut bytes = Vec::new();
+            bytes.write_gate(&gate, &serializer).unwrap();
+            let mut buffer = Buffer::new(bytes);
+            let read = buffer.read_gate(&serializer).unwrap();
+            assert!(buffer.is_empty());
+            assert_eq!(read.0.id(), gate.0.id());
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::marker::PhantomData;
 
+use anyhow::Result;
 use array_tool::vec::Union;
 use plonky2::gates::gate::Gate;
 use plonky2::gates::packed_util::PackedEvaluableBase;
@@ -16,6 +17,7 @@ use plonky2::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write, MAX_GATE_WIRES};
 use plonky2_field::extension::Extendable;
 use plonky2_field::packed::PackedField;
 use plonky2_field::types::Field;
@@ -77,10 +79,21 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for SwitchGate<F,
         format!("{self:?}<D={D}>")
     }
 
-    fn export_circom_verification_code(&self) -> String {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.chunk_size)?;
+        dst.write_usize(self.num_copies)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let chunk_size = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        let num_copies = src.read_usize_in(1..=MAX_GATE_WIRES)?;
+        Ok(Self::new(num_copies, chunk_size))
+    }
+
+    fn export_circom_verification_code(&self) -> Result<String> {
         todo!()
     }
-    fn export_solidity_verification_code(&self) -> String {
+    fn export_solidity_verification_code(&self) -> Result<String> {
         todo!()
     }
 
@@ -217,7 +230,7 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for
 }
 
 #[derive(Debug)]
-struct SwitchGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct SwitchGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     gate: SwitchGate<F, D>,
     copy: usize,
@@ -327,6 +340,23 @@ impl<F: RichField + Extendable<D>, const D: usize> WitnessGenerator<F> for Switc
             false
         }
     }
+
+    fn id(&self) -> String {
+        "SwitchGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.copy)?;
+        self.gate.serialize(dst)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let copy = src.read_usize()?;
+        let gate = SwitchGate::<F, D>::deserialize(src)?;
+        Ok(Self { row, gate, copy })
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,27 @@
+//! Registration of this crate's gates and generators, for serializing circuits which use them.
+
+use plonky2::hash::hash_types::RichField;
+use plonky2::util::serialization::gate_serialization::GateSerializer;
+use plonky2::util::serialization::generator_serialization::WitnessGeneratorSerializer;
+use plonky2_field::extension::Extendable;
+
+use crate::gates::assert_le::{AssertLessThanGate, AssertLessThanGenerator};
+use crate::gates::switch::{SwitchGate, SwitchGenerator};
+use crate::permutation::PermutationGenerator;
+use crate::sorting::MemoryOpSortGenerator;
+
+pub fn register_gates<F: RichField + Extendable<D>, const D: usize>(
+    serializer: &mut GateSerializer<F, D>,
+) {
+    serializer.register::<AssertLessThanGate<F, D>>("AssertLessThanGate");
+    serializer.register::<SwitchGate<F, D>>("SwitchGate");
+}
+
+pub fn register_generators<F: RichField + Extendable<D>, const D: usize>(
+    serializer: &mut WitnessGeneratorSerializer<F, D>,
+) {
+    serializer.register_simple::<AssertLessThanGenerator<F, D>>("AssertLessThanGenerator");
+    serializer.register::<SwitchGenerator<F, D>>("SwitchGenerator");
+    serializer.register_simple::<PermutationGenerator<F>>("PermutationGenerator");
+    serializer.register_simple::<MemoryOpSortGenerator<F, D>>("MemoryOpSortGenerator");
+}
@@ -8,6 +8,7 @@ use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::{BoolTarget, Target};
 use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
 use plonky2_util::ceil_div_usize;
 
 use crate::gates::assert_le::AssertLessThanGate;
@@ -28,6 +29,28 @@ pub struct MemoryOpTarget {
     value: Target,
 }
 
+impl MemoryOpTarget {
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_bool_target(self.is_write)?;
+        dst.write_target(self.address)?;
+        dst.write_target(self.timestamp)?;
+        dst.write_target(self.value)
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let is_write = src.read_bool_target()?;
+        let address = src.read_target()?;
+        let timestamp = src.read_target()?;
+        let value = src.read_target()?;
+        Ok(Self {
+            is_write,
+            address,
+            timestamp,
+            value,
+        })
+    }
+}
+
 pub fn assert_permutation_memory_ops_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
     a: &[MemoryOpTarget],
@@ -127,7 +150,7 @@ pub fn sort_memory_ops_circuit<F: RichField + Extendable<D>, const D: usize>(
 }
 
 #[derive(Debug)]
-struct MemoryOpSortGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct MemoryOpSortGenerator<F: RichField + Extendable<D>, const D: usize> {
     input_ops: Vec<MemoryOpTarget>,
     output_ops: Vec<MemoryOpTarget>,
     _phantom: PhantomData<F>,
@@ -178,6 +201,34 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
             out_buffer.set_target(out_op.value, op.value);
         }
     }
+
+    fn id(&self) -> String {
+        "MemoryOpSortGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        for ops in [&self.input_ops, &self.output_ops] {
+            dst.write_usize(ops.len())?;
+            for op in ops {
+                op.serialize(dst)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn deserialize(src: &mut Buffer) -> IoResult<Self> {
+        let mut read_ops = || -> IoResult<Vec<MemoryOpTarget>> {
+            let len = src.read_usize()?;
+            (0..len).map(|_| MemoryOpTarget::deserialize(src)).collect()
+        };
+        let input_ops = read_ops()?;
+        let output_ops = read_ops()?;
+        Ok(Self {
+            input_ops,
+            output_ops,
+            _phantom: PhantomData,
+        })
+    }
 }
 
 #[cfg(test)]